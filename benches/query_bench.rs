@@ -0,0 +1,86 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use itertools::Itertools;
+use kip_sql::db::{Database, DatabaseConfig};
+use kip_sql::storage::kip::KipStorage;
+use tempfile::TempDir;
+
+const TABLE_ROW_NUM: usize = 100_000;
+const FILTERED_QUERY: &str = "select a, b from t1 where b = 42";
+// every value of its rows is held inline by `ValueRef`, none behind an `Arc`
+const SMALL_INTEGER_QUERY: &str = "select * from t2";
+
+async fn init_storage(temp_dir: &TempDir) -> KipStorage {
+    let storage = KipStorage::new(temp_dir.path()).await.unwrap();
+    let database = Database::new(storage.clone()).unwrap();
+
+    let _ = database
+        .run("create table t1 (a int primary key, b int)")
+        .await
+        .unwrap();
+    for chunk in &(0..TABLE_ROW_NUM).chunks(1000) {
+        let values = chunk.map(|i| format!("({}, {})", i, i % 100)).join(", ");
+        let _ = database
+            .run(&format!("insert into t1 values {}", values))
+            .await
+            .unwrap();
+    }
+    let _ = database
+        .run("create table t2 (a int primary key, b int, c int, d int)")
+        .await
+        .unwrap();
+    for chunk in &(0..TABLE_ROW_NUM).chunks(1000) {
+        let values = chunk
+            .map(|i| format!("({}, {}, {}, {})", i, i % 100, i % 10_000, i * 3))
+            .join(", ");
+        let _ = database
+            .run(&format!("insert into t2 values {}", values))
+            .await
+            .unwrap();
+    }
+
+    storage
+}
+
+fn filtered_query(database: &Database<KipStorage>, rt: &tokio::runtime::Runtime) -> usize {
+    rt.block_on(database.run(FILTERED_QUERY)).unwrap().len()
+}
+
+fn small_integer_scan(database: &Database<KipStorage>, rt: &tokio::runtime::Runtime) -> usize {
+    rt.block_on(database.run(SMALL_INTEGER_QUERY))
+        .unwrap()
+        .len()
+}
+
+fn scan_benchmark(c: &mut Criterion) {
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    let temp_dir = TempDir::new().unwrap();
+    let storage = rt.block_on(init_storage(&temp_dir));
+    // a batch of a single tuple fetches the tuples one at a time
+    let single = Database::new_with_config(
+        storage.clone(),
+        DatabaseConfig::default().with_scan_batch_size(1),
+    )
+    .unwrap();
+    let batched = Database::new(storage).unwrap();
+
+    assert_eq!(filtered_query(&single, &rt), filtered_query(&batched, &rt));
+    assert_eq!(small_integer_scan(&batched, &rt), TABLE_ROW_NUM);
+
+    let mut group = c.benchmark_group("filtered_query");
+    group.sample_size(10);
+    group.bench_function("single_tuple_scan", |b| {
+        b.iter(|| filtered_query(&single, &rt))
+    });
+    group.bench_function("batched_scan", |b| b.iter(|| filtered_query(&batched, &rt)));
+    group.finish();
+
+    let mut group = c.benchmark_group("small_integer_scan");
+    group.sample_size(10);
+    group.bench_function("select_all", |b| {
+        b.iter(|| small_integer_scan(&batched, &rt))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, scan_benchmark);
+criterion_main!(benches);
@@ -1,9 +1,9 @@
-use ahash::RandomState;
 use itertools::Itertools;
-use sqlparser::ast::{Expr, OrderByExpr};
-use std::collections::HashSet;
+use sqlparser::ast::{Expr, OrderByExpr, Value};
+use std::mem;
 
 use crate::binder::BindError;
+use crate::catalog::ColumnRef;
 use crate::planner::LogicalPlan;
 use crate::storage::Transaction;
 use crate::{
@@ -19,8 +19,9 @@ impl<'a, T: Transaction> Binder<'a, T> {
         children: LogicalPlan,
         agg_calls: Vec<ScalarExpression>,
         groupby_exprs: Vec<ScalarExpression>,
+        grouping_sets: Vec<Vec<usize>>,
     ) -> LogicalPlan {
-        AggregateOperator::build(children, agg_calls, groupby_exprs)
+        AggregateOperator::build(children, agg_calls, groupby_exprs, grouping_sets)
     }
 
     pub fn extract_select_aggregate(
@@ -33,30 +34,143 @@ impl<'a, T: Transaction> Binder<'a, T> {
         Ok(())
     }
 
+    /// Also called without a GROUP BY when the select list has aggregate functions, then the
+    /// other select items may only be constants. The scalar sub queries of the select list are
+    /// joined after the aggregation, so their columns need not be grouped.
     pub fn extract_group_by_aggregate(
         &mut self,
         select_list: &mut [ScalarExpression],
         groupby: &[Expr],
+        sub_queries: &[LogicalPlan],
     ) -> Result<(), BindError> {
-        self.validate_groupby_illegal_column(select_list, groupby)?;
+        let (groupby, grouping_sets) = Self::expand_grouping_sets(groupby);
 
-        for gb in groupby {
-            let mut expr = self.bind_expr(gb)?;
-            self.visit_group_by_expr(select_list, &mut expr);
+        for gb in groupby.iter() {
+            let expr = self
+                .bind_expr(gb)
+                .map_err(|err| err.with_context("GROUP BY", gb))?;
+            if expr.has_agg_call() {
+                return Err(BindError::AggMiss(format!(
+                    "aggregate functions are not allowed in GROUP BY: {}",
+                    gb
+                )));
+            }
+            self.visit_group_by_expr(select_list, expr);
         }
-        Ok(())
+        self.context.grouping_sets = grouping_sets;
+        let sub_query_columns = sub_queries
+            .iter()
+            .flat_map(|plan| plan.schema().to_vec())
+            .collect_vec();
+
+        self.validate_groupby_illegal_column(select_list, &sub_query_columns)
+    }
+
+    /// Flatten `ROLLUP`, `CUBE` and `GROUPING SETS` into the distinct group by exprs
+    /// and the grouping sets, each set is made up of the indexes of the exprs.
+    ///
+    /// e.g. `GROUP BY a, ROLLUP(b, c)` -> `([a, b, c], [[0, 1, 2], [0, 1], [0]])`
+    ///
+    /// The grouping sets are empty when the clause is a plain group by.
+    fn expand_grouping_sets(groupby: &[Expr]) -> (Vec<Expr>, Vec<Vec<usize>>) {
+        fn position_or_push(exprs: &mut Vec<Expr>, expr: &Expr) -> usize {
+            exprs.iter().position(|e| e == expr).unwrap_or_else(|| {
+                exprs.push(expr.clone());
+                exprs.len() - 1
+            })
+        }
+        fn elements(exprs: &mut Vec<Expr>, lists: &[Vec<Expr>]) -> Vec<Vec<usize>> {
+            lists
+                .iter()
+                .map(|list| {
+                    list.iter()
+                        .map(|expr| position_or_push(exprs, expr))
+                        .collect_vec()
+                })
+                .collect_vec()
+        }
+        let mut exprs = Vec::new();
+        let mut sets = vec![vec![]];
+        let mut is_grouping_sets = false;
+
+        for item in groupby {
+            let item_sets = match item {
+                Expr::Rollup(lists) => {
+                    let elements = elements(&mut exprs, lists);
+
+                    (0..=elements.len())
+                        .rev()
+                        .map(|i| elements[..i].concat())
+                        .collect_vec()
+                }
+                Expr::Cube(lists) => {
+                    let elements = elements(&mut exprs, lists);
+
+                    (0..1_usize << elements.len())
+                        .rev()
+                        .map(|mask| {
+                            elements
+                                .iter()
+                                .enumerate()
+                                .filter(|(i, _)| mask & (1 << (elements.len() - 1 - i)) != 0)
+                                .flat_map(|(_, element)| element.clone())
+                                .collect_vec()
+                        })
+                        .collect_vec()
+                }
+                Expr::GroupingSets(lists) => elements(&mut exprs, lists),
+                expr => {
+                    sets.iter_mut()
+                        .for_each(|set| set.push(position_or_push(&mut exprs, expr)));
+                    continue;
+                }
+            };
+            is_grouping_sets = true;
+            sets = sets
+                .iter()
+                .cartesian_product(item_sets.iter())
+                .map(|(set, item_set)| {
+                    set.iter()
+                        .chain(item_set.iter())
+                        .cloned()
+                        .sorted()
+                        .dedup()
+                        .collect_vec()
+                })
+                .collect_vec();
+        }
+        if !is_grouping_sets {
+            sets.clear();
+        }
+
+        (exprs, sets)
     }
 
+    /// An integer literal in ORDER BY is the position of a select item, e.g. `ORDER BY 1`,
+    /// any other expression is bound as usual, where the columns of the tables come before
+    /// the aliases of the select items.
     pub fn extract_having_orderby_aggregate(
         &mut self,
+        select_list: &[ScalarExpression],
         having: &Option<Expr>,
         orderbys: &[OrderByExpr],
     ) -> Result<(Option<ScalarExpression>, Option<Vec<SortField>>), BindError> {
         // Extract having expression.
         let return_having = if let Some(having) = having {
-            let mut having = self.bind_expr(having)?;
+            let mut having = self
+                .bind_expr(having)
+                .map_err(|err| err.with_context("HAVING", having))?;
+            // the windows are computed after the tuples are filtered by HAVING
+            let window_calls = mem::take(&mut self.context.window_calls);
             self.visit_column_agg_expr(&mut having)?;
 
+            if !self.context.window_calls.is_empty() {
+                return Err(BindError::Window(
+                    "window functions are not allowed in HAVING".to_string(),
+                ));
+            }
+            self.context.window_calls = window_calls;
+
             Some(having)
         } else {
             None
@@ -71,13 +185,20 @@ impl<'a, T: Transaction> Binder<'a, T> {
                     asc,
                     nulls_first,
                 } = orderby;
-                let mut expr = self.bind_expr(expr)?;
+                let mut expr = match expr {
+                    Expr::Value(Value::Number(number, _)) if number.parse::<usize>().is_ok() => {
+                        Self::bind_orderby_position(select_list, number)
+                    }
+                    _ => self.bind_expr(expr),
+                }
+                .map_err(|err| err.with_context("ORDER BY", expr))?;
                 self.visit_column_agg_expr(&mut expr)?;
 
+                let asc = asc.map_or(true, |asc| asc);
                 return_orderby.push(SortField::new(
                     expr,
-                    asc.map_or(true, |asc| asc),
-                    nulls_first.map_or(false, |first| first),
+                    asc,
+                    nulls_first.unwrap_or_else(|| self.context.nulls_order.nulls_first(asc)),
                 ));
             }
             Some(return_orderby)
@@ -87,11 +208,37 @@ impl<'a, T: Transaction> Binder<'a, T> {
         Ok((return_having, return_orderby))
     }
 
+    fn bind_orderby_position(
+        select_list: &[ScalarExpression],
+        number: &str,
+    ) -> Result<ScalarExpression, BindError> {
+        number
+            .parse::<usize>()
+            .ok()
+            .and_then(|position| position.checked_sub(1))
+            .and_then(|i| select_list.get(i))
+            .cloned()
+            .ok_or_else(|| {
+                BindError::InvalidColumn(
+                    format!("ORDER BY position {} is not in select list", number),
+                    None,
+                )
+            })
+    }
+
     fn visit_column_agg_expr(&mut self, expr: &mut ScalarExpression) -> Result<(), BindError> {
         match expr {
             ScalarExpression::AggCall { .. } => {
                 self.context.agg_calls.push(expr.clone());
             }
+            ScalarExpression::WindowCall { .. } => {
+                for mut window_expr in expr.window_exprs().cloned().collect_vec() {
+                    self.visit_column_agg_expr(&mut window_expr)?;
+                }
+                if !self.context.window_calls.contains(expr) {
+                    self.context.window_calls.push(expr.clone());
+                }
+            }
             ScalarExpression::TypeCast { expr, .. } => self.visit_column_agg_expr(expr)?,
             ScalarExpression::IsNull { expr, .. } => self.visit_column_agg_expr(expr)?,
             ScalarExpression::Unary { expr, .. } => self.visit_column_agg_expr(expr)?,
@@ -110,6 +257,13 @@ impl<'a, T: Transaction> Binder<'a, T> {
                     self.visit_column_agg_expr(arg)?;
                 }
             }
+            ScalarExpression::Function { args, .. }
+            | ScalarExpression::Builtin { args, .. }
+            | ScalarExpression::Tuple(args) => {
+                for arg in args {
+                    self.visit_column_agg_expr(arg)?;
+                }
+            }
             ScalarExpression::Constant(_) | ScalarExpression::ColumnRef { .. } => {}
         }
 
@@ -117,70 +271,137 @@ impl<'a, T: Transaction> Binder<'a, T> {
     }
 
     /// Validate select exprs must appear in the GROUP BY clause or be used in
-    /// an aggregate function.
-    /// e.g. SELECT a,count(b) FROM t GROUP BY a. it's ok.
-    ///      SELECT a,b FROM t GROUP BY a.        it's error.
-    ///      SELECT a,count(b) FROM t GROUP BY b. it's error.
+    /// an aggregate function, or be made of such exprs.
+    /// e.g. SELECT a,count(b) FROM t GROUP BY a.   it's ok.
+    ///      SELECT a + 1 FROM t GROUP BY a.        it's ok.
+    ///      SELECT a,b FROM t GROUP BY a.          it's error.
+    ///      SELECT a,count(b) FROM t GROUP BY b.   it's error.
+    ///
+    /// A column of a table whose primary key is grouped is determined by the group, so it is
+    /// allowed and grouped as well, e.g. `SELECT id, name FROM t GROUP BY id`.
     fn validate_groupby_illegal_column(
         &mut self,
         select_items: &[ScalarExpression],
-        groupby: &[Expr],
+        sub_query_columns: &[ColumnRef],
     ) -> Result<(), BindError> {
-        let mut group_raw_exprs = vec![];
-        for expr in groupby {
-            let expr = self.bind_expr(expr)?;
-
-            if let ScalarExpression::Alias { alias, .. } = expr {
-                let alias_expr = select_items.iter().find(|column| {
-                    if let ScalarExpression::Alias {
-                        alias: inner_alias, ..
-                    } = &column
-                    {
-                        alias == *inner_alias
-                    } else {
-                        false
-                    }
-                });
+        for expr in select_items {
+            while let Some(column) =
+                Self::ungrouped_column(expr, &self.context.group_by_exprs, sub_query_columns)
+                    .cloned()
+            {
+                if !self.is_determined_by_group(&column) {
+                    let name = match column.table_name() {
+                        Some(table_name) => format!("{}.{}", table_name, column.name()),
+                        None => column.name().to_string(),
+                    };
+                    return Err(BindError::AggMiss(format!(
+                        "column {} must appear in the GROUP BY clause or be used in an aggregate function",
+                        name
+                    )));
+                }
+                let index = self.context.group_by_exprs.len();
 
-                if let Some(inner_expr) = alias_expr {
-                    group_raw_exprs.push(inner_expr.clone());
+                self.context
+                    .group_by_exprs
+                    .push(ScalarExpression::ColumnRef(column));
+                for set in self.context.grouping_sets.iter_mut() {
+                    set.push(index);
                 }
-            } else {
-                group_raw_exprs.push(expr);
             }
         }
-        let mut group_raw_set: HashSet<&ScalarExpression, RandomState> =
-            HashSet::from_iter(group_raw_exprs.iter());
 
-        for expr in select_items {
-            if expr.has_agg_call() {
-                continue;
-            }
-            group_raw_set.remove(expr);
+        Ok(())
+    }
 
-            if !group_raw_exprs.iter().contains(expr) {
-                return Err(BindError::AggMiss(format!(
-                    "{:?} must appear in the GROUP BY clause or be used in an aggregate function",
-                    expr
-                )));
+    /// The first column of the expr which is neither in a group by expr nor in an aggregate
+    /// function. The windows are computed over the aggregated tuples, so are their exprs.
+    fn ungrouped_column<'e>(
+        expr: &'e ScalarExpression,
+        group_by_exprs: &[ScalarExpression],
+        sub_query_columns: &[ColumnRef],
+    ) -> Option<&'e ColumnRef> {
+        if group_by_exprs.contains(expr) {
+            return None;
+        }
+        match expr {
+            ScalarExpression::ColumnRef(column) => {
+                (!sub_query_columns.contains(column)).then_some(column)
             }
+            ScalarExpression::Constant(_) | ScalarExpression::AggCall { .. } => None,
+            ScalarExpression::Alias { expr, .. }
+            | ScalarExpression::TypeCast { expr, .. }
+            | ScalarExpression::IsNull { expr, .. }
+            | ScalarExpression::Unary { expr, .. } => {
+                Self::ungrouped_column(expr, group_by_exprs, sub_query_columns)
+            }
+            ScalarExpression::Binary {
+                left_expr,
+                right_expr,
+                ..
+            } => Self::ungrouped_column(left_expr, group_by_exprs, sub_query_columns)
+                .or_else(|| Self::ungrouped_column(right_expr, group_by_exprs, sub_query_columns)),
+            ScalarExpression::In { expr, args, .. } => {
+                Self::ungrouped_column(expr, group_by_exprs, sub_query_columns).or_else(|| {
+                    args.iter().find_map(|arg| {
+                        Self::ungrouped_column(arg, group_by_exprs, sub_query_columns)
+                    })
+                })
+            }
+            ScalarExpression::Function { args, .. }
+            | ScalarExpression::Builtin { args, .. }
+            | ScalarExpression::Tuple(args) => args
+                .iter()
+                .find_map(|arg| Self::ungrouped_column(arg, group_by_exprs, sub_query_columns)),
+            ScalarExpression::WindowCall { .. } => expr.window_exprs().find_map(|window_expr| {
+                Self::ungrouped_column(window_expr, group_by_exprs, sub_query_columns)
+            }),
         }
+    }
 
-        if !group_raw_set.is_empty() {
-            return Err(BindError::AggMiss(
-                "In the GROUP BY clause the field must be in the select clause".to_string(),
-            ));
+    /// Whether all the primary key columns of the table of the column are grouped, in every
+    /// grouping set if any. A derived table has no primary key, so determines nothing.
+    fn is_determined_by_group(&self, column: &ColumnRef) -> bool {
+        let Some(table) = column
+            .table_name()
+            .and_then(|table_name| self.context.table(table_name.clone()))
+        else {
+            return false;
+        };
+        let Some(primary_index) = table.indexes().iter().find(|index| index.is_primary) else {
+            return false;
+        };
+        if !table.owns_column(column) {
+            return false;
         }
+        let group_by_exprs = &self.context.group_by_exprs;
+        let grouping_sets = &self.context.grouping_sets;
 
-        Ok(())
+        primary_index.column_ids.iter().all(|col_id| {
+            let Some(primary_key) = table.get_column_by_id(col_id) else {
+                return false;
+            };
+            let is_grouped = |i: &usize| {
+                matches!(
+                    group_by_exprs[*i].unpack_alias(),
+                    ScalarExpression::ColumnRef(col) if col.summary() == primary_key.summary()
+                )
+            };
+            if grouping_sets.is_empty() {
+                (0..group_by_exprs.len()).any(|i| is_grouped(&i))
+            } else {
+                grouping_sets.iter().all(|set| set.iter().any(is_grouped))
+            }
+        })
     }
 
+    /// The group by expr is the select item it names by alias or is equal to, so that
+    /// the aggregation outputs it under the name of the item.
     fn visit_group_by_expr(
         &mut self,
         select_list: &mut [ScalarExpression],
-        expr: &mut ScalarExpression,
+        expr: ScalarExpression,
     ) {
-        if let ScalarExpression::Alias { alias, .. } = expr {
+        if let ScalarExpression::Alias { alias, .. } = &expr {
             if let Some(i) = select_list.iter().position(|inner_expr| {
                 if let ScalarExpression::Alias {
                     alias: inner_alias, ..
@@ -196,8 +417,10 @@ impl<'a, T: Transaction> Binder<'a, T> {
             }
         }
 
-        if let Some(i) = select_list.iter().position(|column| column == expr) {
+        if let Some(i) = select_list.iter().position(|column| column == &expr) {
             self.context.group_by_exprs.push(select_list[i].clone())
+        } else {
+            self.context.group_by_exprs.push(expr)
         }
     }
 
@@ -248,6 +471,14 @@ impl<'a, T: Transaction> Binder<'a, T> {
                 }
                 Ok(())
             }
+            ScalarExpression::Function { args, .. }
+            | ScalarExpression::Builtin { args, .. }
+            | ScalarExpression::Tuple(args) => {
+                for arg in args {
+                    self.validate_having_orderby(arg)?;
+                }
+                Ok(())
+            }
             ScalarExpression::Binary {
                 left_expr,
                 right_expr,
@@ -258,6 +489,13 @@ impl<'a, T: Transaction> Binder<'a, T> {
                 Ok(())
             }
 
+            ScalarExpression::WindowCall { .. } => {
+                for window_expr in expr.window_exprs() {
+                    self.validate_having_orderby(window_expr)?;
+                }
+                Ok(())
+            }
+
             ScalarExpression::Constant(_) => Ok(()),
         }
     }
@@ -0,0 +1,106 @@
+use crate::binder::{lower_case_name, split_name, BindError, Binder};
+use crate::catalog::ROWID_COLUMN_NAME;
+use crate::planner::operator::alter_table::{AlterTableOperation, AlterTableOperator};
+use crate::planner::operator::Operator;
+use crate::planner::LogicalPlan;
+use crate::storage::Transaction;
+use sqlparser::ast::{AlterTableOperation as SqlAlterTableOperation, ObjectName};
+use std::sync::Arc;
+
+impl<'a, T: Transaction> Binder<'a, T> {
+    /// `ALTER TABLE t RENAME TO t2` or `ALTER TABLE t RENAME COLUMN a TO a2`.
+    pub(crate) fn bind_alter_table(
+        &mut self,
+        name: &ObjectName,
+        operation: &SqlAlterTableOperation,
+    ) -> Result<LogicalPlan, BindError> {
+        let name = lower_case_name(name);
+        let (_, name) = split_name(&name)?;
+        let table_name = Arc::new(name.to_string());
+        let table = self.context.table(table_name.clone()).ok_or_else(|| {
+            BindError::InvalidTable(format!("not found table {}", table_name), None)
+        })?;
+
+        let operation = match operation {
+            SqlAlterTableOperation::RenameTable { table_name } => {
+                let new_name = lower_case_name(table_name);
+                let (_, new_name) = split_name(&new_name)?;
+                let new_table_name = Arc::new(new_name.to_string());
+
+                if self.context.table(new_table_name.clone()).is_some() {
+                    return Err(BindError::InvalidTable(
+                        format!("{} already exists", new_table_name),
+                        None,
+                    ));
+                }
+                AlterTableOperation::RenameTable { new_table_name }
+            }
+            SqlAlterTableOperation::RenameColumn {
+                old_column_name,
+                new_column_name,
+            } => {
+                let old_column_name = old_column_name.value.to_lowercase();
+                let new_column_name = new_column_name.value.to_lowercase();
+                let column = table
+                    .get_column_by_name(&old_column_name)
+                    .filter(|column| !column.desc.is_hidden)
+                    .ok_or_else(|| BindError::InvalidColumn(old_column_name.clone(), None))?;
+                let column_id = column.id().unwrap();
+
+                if new_column_name == ROWID_COLUMN_NAME
+                    || (new_column_name != old_column_name
+                        && table.contains_column(&new_column_name))
+                {
+                    return Err(BindError::InvalidColumn(
+                        format!("{} already exists in {}", new_column_name, table_name),
+                        None,
+                    ));
+                }
+                // the expressions of the generated columns refer to the columns by name
+                if let Some(generated) = table.all_columns().into_iter().find(|col| {
+                    col.desc.generated.as_ref().is_some_and(|generated| {
+                        generated
+                            .expr
+                            .referenced_columns(true)
+                            .iter()
+                            .any(|col| col.id() == Some(column_id))
+                    })
+                }) {
+                    return Err(BindError::InvalidColumn(
+                        format!(
+                            "{} is used by the generated column {}",
+                            old_column_name,
+                            generated.name()
+                        ),
+                        None,
+                    ));
+                }
+                if let Some(index_meta) = table.indexes().iter().find(|index_meta| {
+                    index_meta.expr.is_some() && index_meta.column_ids.contains(&column_id)
+                }) {
+                    return Err(BindError::InvalidColumn(
+                        format!(
+                            "{} is used by the expression of the index {}",
+                            old_column_name, index_meta.name
+                        ),
+                        None,
+                    ));
+                }
+                AlterTableOperation::RenameColumn {
+                    column_id,
+                    old_column_name,
+                    new_column_name,
+                }
+            }
+            operation => return Err(BindError::UnsupportedStmt(operation.to_string())),
+        };
+
+        Ok(LogicalPlan {
+            operator: Operator::AlterTable(AlterTableOperator {
+                table_name,
+                operation,
+            }),
+            childrens: vec![],
+        })
+    }
+}
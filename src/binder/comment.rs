@@ -0,0 +1,45 @@
+use crate::binder::{lower_case_name, split_name, BindError, Binder};
+use crate::planner::operator::comment::CommentOperator;
+use crate::planner::operator::Operator;
+use crate::planner::LogicalPlan;
+use crate::storage::Transaction;
+use sqlparser::ast::{CommentObject, ObjectName};
+use std::sync::Arc;
+
+impl<'a, T: Transaction> Binder<'a, T> {
+    /// `COMMENT ON TABLE t IS '...'` or `COMMENT ON COLUMN t.c IS '...'`.
+    pub(crate) fn bind_comment(
+        &mut self,
+        object_type: &CommentObject,
+        object_name: &ObjectName,
+        comment: &Option<String>,
+    ) -> Result<LogicalPlan, BindError> {
+        let mut name = lower_case_name(object_name);
+        let column_name = match object_type {
+            CommentObject::Table => None,
+            CommentObject::Column => name.0.pop().map(|ident| ident.value),
+        };
+        let (_, table_name) = split_name(&name)?;
+        let table_name = Arc::new(table_name.to_string());
+        let table = self.context.table(table_name.clone()).ok_or_else(|| {
+            BindError::InvalidTable(format!("not found table {}", table_name), None)
+        })?;
+        let column_id = match column_name {
+            Some(column_name) => Some(
+                table
+                    .get_column_id_by_name(&column_name)
+                    .ok_or(BindError::InvalidColumn(column_name, None))?,
+            ),
+            None => None,
+        };
+
+        Ok(LogicalPlan {
+            operator: Operator::Comment(CommentOperator {
+                table_name,
+                column_id,
+                comment: comment.clone(),
+            }),
+            childrens: vec![],
+        })
+    }
+}
@@ -2,6 +2,7 @@ use std::path::PathBuf;
 use std::str::FromStr;
 use std::sync::Arc;
 
+use crate::parser::CopyErrorOptions;
 use crate::planner::operator::copy_from_file::CopyFromFileOperator;
 use crate::planner::operator::copy_to_file::CopyToFileOperator;
 use crate::planner::operator::Operator;
@@ -31,6 +32,17 @@ pub enum FileFormat {
     },
 }
 
+/// What `COPY FROM` does with a row of the file it cannot import.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum OnError {
+    /// `ON_ERROR = ABORT_STATEMENT`, the statement fails at the first one.
+    #[default]
+    Abort,
+    /// `ON_ERROR = CONTINUE`, the row is skipped and reported with its line, the statement
+    /// fails if more than `ERROR_LIMIT` rows are skipped.
+    Continue { limit: Option<usize> },
+}
+
 impl std::fmt::Display for ExtSource {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{self:?}")
@@ -57,6 +69,7 @@ impl<'a, T: Transaction> Binder<'a, T> {
         to: bool,
         target: CopyTarget,
         options: &[CopyOption],
+        copy_error_options: &CopyErrorOptions,
     ) -> Result<LogicalPlan, BindError> {
         let (table_name, ..) = match source {
             CopySource::Table {
@@ -71,7 +84,7 @@ impl<'a, T: Transaction> Binder<'a, T> {
         };
 
         if let Some(table) = self.context.table(Arc::new(table_name.to_string())) {
-            let cols = table.all_columns();
+            let cols = table.visible_columns();
             let ext_source = ExtSource {
                 path: match target {
                     CopyTarget::File { filename } => filename.into(),
@@ -95,16 +108,17 @@ impl<'a, T: Transaction> Binder<'a, T> {
                         types,
                         columns: cols,
                         table: table_name.to_string(),
+                        on_error: OnError::from_options(copy_error_options)?,
                     }),
                     childrens: vec![],
                 }
             };
             Ok(copy)
         } else {
-            Err(BindError::InvalidTable(format!(
-                "not found table {}",
-                table_name
-            )))
+            Err(BindError::InvalidTable(
+                format!("not found table {}", table_name),
+                None,
+            ))
         }
     }
 }
@@ -136,3 +150,32 @@ impl FileFormat {
         }
     }
 }
+
+impl OnError {
+    /// Create from the `ON_ERROR` and `ERROR_LIMIT` following the copy options.
+    pub fn from_options(options: &CopyErrorOptions) -> Result<Self, BindError> {
+        let is_continue = match options.on_error.as_deref() {
+            None | Some("ABORT_STATEMENT") => false,
+            Some("CONTINUE") => true,
+            Some(value) => {
+                return Err(BindError::UnsupportedCopySource(format!(
+                    "ON_ERROR = {} is not supported, use ABORT_STATEMENT or CONTINUE",
+                    value
+                )))
+            }
+        };
+        let limit = options.error_limit.map(|limit| limit as usize);
+
+        if !is_continue && limit.is_some() {
+            return Err(BindError::UnsupportedCopySource(
+                "ERROR_LIMIT only applies to ON_ERROR = CONTINUE".to_string(),
+            ));
+        }
+
+        Ok(if is_continue {
+            OnError::Continue { limit }
+        } else {
+            OnError::Abort
+        })
+    }
+}
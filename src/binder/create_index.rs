@@ -0,0 +1,127 @@
+use crate::binder::{lower_case_name, split_name, BindError, Binder};
+use crate::expression::ScalarExpression;
+use crate::planner::operator::create_index::CreateIndexOperator;
+use crate::planner::operator::Operator;
+use crate::planner::LogicalPlan;
+use crate::storage::Transaction;
+use crate::types::index::{IndexExpr, IndexMeta};
+use itertools::Itertools;
+use sqlparser::ast::{ObjectName, OrderByExpr};
+use std::sync::Arc;
+
+impl<'a, T: Transaction> Binder<'a, T> {
+    /// `CREATE [UNIQUE] INDEX idx ON t (a, b)` over columns, or `CREATE INDEX idx ON t (lower(a))`
+    /// over a single expression of the columns.
+    pub(crate) fn bind_create_index(
+        &mut self,
+        name: &ObjectName,
+        table_name: &ObjectName,
+        columns: &[OrderByExpr],
+        unique: bool,
+        if_not_exists: bool,
+    ) -> Result<LogicalPlan, BindError> {
+        let index_name = lower_case_name(name);
+        let (_, index_name) = split_name(&index_name)?;
+        let table_name = lower_case_name(table_name);
+        let (_, table_name) = split_name(&table_name)?;
+        let table_name = Arc::new(table_name.to_string());
+        let table = self
+            .context
+            .table(table_name.clone())
+            .cloned()
+            .ok_or_else(|| {
+                BindError::InvalidTable(format!("not found table {}", table_name), None)
+            })?;
+        self.context
+            .add_bind_table(table_name.clone(), table, None)?;
+
+        let exprs = columns
+            .iter()
+            .map(|column| {
+                self.bind_expr(&column.expr)
+                    .map_err(|err| err.with_context("CREATE INDEX", &column.expr))
+            })
+            .try_collect::<_, Vec<_>, _>()?;
+        let column_ids = exprs
+            .iter()
+            .filter_map(|expr| match expr {
+                ScalarExpression::ColumnRef(col) => col.id(),
+                _ => None,
+            })
+            .collect_vec();
+
+        let index_meta = if column_ids.len() == exprs.len() {
+            IndexMeta {
+                id: 0,
+                column_ids,
+                name: index_name.to_string(),
+                is_unique: unique,
+                is_primary: false,
+                expr: None,
+            }
+        } else {
+            let [expr] = <[ScalarExpression; 1]>::try_from(exprs).map_err(|_| {
+                BindError::UnsupportedStmt(
+                    "an index over an expression with other keys".to_string(),
+                )
+            })?;
+            if !is_indexable(&expr) {
+                return Err(BindError::UnsupportedStmt(format!(
+                    "an index over {}, only the builtin functions can be indexed",
+                    columns[0].expr
+                )));
+            }
+
+            IndexMeta {
+                id: 0,
+                column_ids: expr
+                    .referenced_columns(true)
+                    .iter()
+                    .filter_map(|col| col.id())
+                    .unique()
+                    .collect_vec(),
+                name: index_name.to_string(),
+                is_unique: unique,
+                is_primary: false,
+                expr: Some(IndexExpr {
+                    sql: columns[0].expr.to_string(),
+                    expr,
+                }),
+            }
+        };
+
+        Ok(LogicalPlan {
+            operator: Operator::CreateIndex(CreateIndexOperator {
+                table_name,
+                index_meta,
+                if_not_exists,
+            }),
+            childrens: vec![],
+        })
+    }
+}
+
+/// Only the expressions of builtin functions are kept in the index meta, since the
+/// registered functions cannot be restored from the storage.
+fn is_indexable(expr: &ScalarExpression) -> bool {
+    match expr {
+        ScalarExpression::Constant(_) | ScalarExpression::ColumnRef(_) => true,
+        ScalarExpression::Alias { expr, .. }
+        | ScalarExpression::TypeCast { expr, .. }
+        | ScalarExpression::IsNull { expr, .. }
+        | ScalarExpression::Unary { expr, .. } => is_indexable(expr),
+        ScalarExpression::Binary {
+            left_expr,
+            right_expr,
+            ..
+        } => is_indexable(left_expr) && is_indexable(right_expr),
+        ScalarExpression::In { expr, args, .. } => {
+            is_indexable(expr) && args.iter().all(is_indexable)
+        }
+        ScalarExpression::Builtin { args, .. } => args.iter().all(is_indexable),
+        ScalarExpression::AggCall { .. }
+        | ScalarExpression::Function { .. }
+        | ScalarExpression::Tuple(_)
+        | ScalarExpression::WindowCall { .. } => false,
+    }
+}
@@ -1,25 +1,29 @@
 use itertools::Itertools;
-use sqlparser::ast::{ColumnDef, ColumnOption, ObjectName, TableConstraint};
+use sqlparser::ast::{
+    ColumnDef, ColumnOption, Expr, GeneratedAs, ObjectName, Query, TableConstraint,
+};
 use std::collections::HashSet;
 use std::sync::Arc;
 
 use super::Binder;
 use crate::binder::{lower_case_name, split_name, BindError};
-use crate::catalog::{ColumnCatalog, ColumnDesc};
+use crate::catalog::{ColumnCatalog, ColumnDesc, GeneratedExpr, Identity, TableCatalog, TableName};
+use crate::expression::value_compute::unary_op;
 use crate::expression::ScalarExpression;
 use crate::planner::operator::create_table::CreateTableOperator;
 use crate::planner::operator::Operator;
 use crate::planner::LogicalPlan;
 use crate::storage::Transaction;
-use crate::types::value::DataValue;
+use crate::types::value::{DataValue, ValueRef};
 use crate::types::LogicalType;
 
 impl<'a, T: Transaction> Binder<'a, T> {
-    // TODO: TableConstraint
+    /// A primary key of several columns, `PRIMARY KEY (a, b)`, keys the tuples by the values
+    /// of its columns in the order of the columns of the table.
     pub(crate) fn bind_create_table(
         &mut self,
         name: &ObjectName,
-        columns: &[ColumnDef],
+        column_defs: &[ColumnDef],
         constraints: &[TableConstraint],
         if_not_exists: bool,
     ) -> Result<LogicalPlan, BindError> {
@@ -30,17 +34,37 @@ impl<'a, T: Transaction> Binder<'a, T> {
         {
             // check duplicated column names
             let mut set = HashSet::new();
-            for col in columns.iter() {
+            for col in column_defs.iter() {
                 let col_name = &col.name.value;
                 if !set.insert(col_name.clone()) {
-                    return Err(BindError::AmbiguousColumn(col_name.to_string()));
+                    return Err(BindError::AmbiguousColumn(col_name.to_string(), None));
                 }
             }
         }
-        let mut columns: Vec<ColumnCatalog> = columns
+        let mut columns: Vec<ColumnCatalog> = column_defs
             .iter()
             .map(|col| self.bind_column(col))
             .try_collect()?;
+        if columns
+            .iter()
+            .filter(|col| col.desc.identity.is_some())
+            .count()
+            > 1
+        {
+            return Err(BindError::InvalidTable(
+                format!("multiple identity columns for {}", table_name),
+                None,
+            ));
+        }
+        let mut unique_keys = Vec::new();
+        let multiple_primary_keys =
+            || BindError::InvalidTable(format!("multiple primary keys for {}", table_name), None);
+        let mut has_primary_key = match columns.iter().filter(|col| col.desc.is_primary).count() {
+            0 => false,
+            1 => true,
+            _ => return Err(multiple_primary_keys()),
+        };
+
         for constraint in constraints {
             match constraint {
                 TableConstraint::Unique {
@@ -48,33 +72,65 @@ impl<'a, T: Transaction> Binder<'a, T> {
                     is_primary,
                     ..
                 } => {
+                    if !*is_primary && column_names.len() > 1 {
+                        let mut unique_key = Vec::with_capacity(column_names.len());
+
+                        for column_name in column_names {
+                            let column_name = column_name.value.to_lowercase();
+
+                            if !columns.iter().any(|column| column.name() == column_name) {
+                                return Err(BindError::InvalidColumn(column_name, None));
+                            }
+                            unique_key.push(column_name);
+                        }
+                        unique_keys.push(unique_key);
+                        continue;
+                    }
+                    if *is_primary {
+                        if has_primary_key {
+                            return Err(multiple_primary_keys());
+                        }
+                        has_primary_key = true;
+                    }
                     for column_name in column_names {
-                        if let Some(column) = columns
+                        let column_name = column_name.value.to_lowercase();
+
+                        match columns
                             .iter_mut()
-                            .find(|column| column.name() == column_name.to_string())
+                            .find(|column| column.name() == column_name)
                         {
-                            if *is_primary {
+                            Some(column) if *is_primary => {
                                 column.desc.is_primary = true;
-                            } else {
-                                column.desc.is_unique = true;
+                                column.nullable = false;
+                            }
+                            Some(column) => column.desc.is_unique = true,
+                            None if *is_primary => {
+                                return Err(BindError::InvalidColumn(column_name, None))
                             }
+                            None => (),
                         }
                     }
                 }
+                TableConstraint::ForeignKey { .. } => {
+                    return Err(BindError::UnsupportedStmt(
+                        "foreign keys are not supported".to_string(),
+                    ))
+                }
                 _ => todo!(),
             }
         }
 
-        if columns.iter().filter(|col| col.desc.is_primary).count() != 1 {
-            return Err(BindError::InvalidTable(
-                "The primary key field must exist and have at least one".to_string(),
-            ));
+        self.bind_generated_columns(&table_name, column_defs, &mut columns)?;
+
+        if !has_primary_key {
+            columns.push(ColumnCatalog::new_rowid());
         }
 
         let plan = LogicalPlan {
             operator: Operator::CreateTable(CreateTableOperator {
                 table_name,
                 columns,
+                unique_keys,
                 if_not_exists,
             }),
             childrens: vec![],
@@ -82,6 +138,50 @@ impl<'a, T: Transaction> Binder<'a, T> {
         Ok(plan)
     }
 
+    /// `CREATE TABLE .. AS SELECT ..`, the columns of the table are the outputs of the query,
+    /// which is the child of the plan. Outputs that are neither columns nor aliased are named
+    /// `columnN` by their position, like the columns of `VALUES`.
+    pub(crate) fn bind_create_table_as(
+        &mut self,
+        name: &ObjectName,
+        query: &Query,
+        if_not_exists: bool,
+    ) -> Result<LogicalPlan, BindError> {
+        let name = lower_case_name(name);
+        let (_, name) = split_name(&name)?;
+        let table_name = Arc::new(name.to_string());
+        let plan = self.bind_query(query)?;
+
+        let mut set = HashSet::new();
+        let mut columns = Vec::new();
+        for (i, column) in plan.schema().iter().enumerate() {
+            let column_name = match &column.ref_expr {
+                None | Some(ScalarExpression::Alias { .. }) => column.name().to_string(),
+                Some(_) => format!("column{}", i + 1),
+            };
+            if !set.insert(column_name.clone()) {
+                return Err(BindError::AmbiguousColumn(column_name, None));
+            }
+            columns.push(ColumnCatalog::new(
+                column_name,
+                true,
+                ColumnDesc::new(*column.datatype(), false, false, None),
+                None,
+            ));
+        }
+        columns.push(ColumnCatalog::new_rowid());
+
+        Ok(LogicalPlan {
+            operator: Operator::CreateTable(CreateTableOperator {
+                table_name,
+                columns,
+                unique_keys: vec![],
+                if_not_exists,
+            }),
+            childrens: vec![plan],
+        })
+    }
+
     fn bind_column(&mut self, column_def: &ColumnDef) -> Result<ColumnCatalog, BindError> {
         let column_name = column_def.name.to_string();
         let mut column_desc = ColumnDesc::new(
@@ -108,20 +208,226 @@ impl<'a, T: Transaction> Binder<'a, T> {
                     }
                 }
                 ColumnOption::Default(expr) => {
-                    if let ScalarExpression::Constant(value) = self.bind_expr(expr)? {
-                        let cast_value =
-                            DataValue::clone(&value).cast(&column_desc.column_datatype)?;
-                        column_desc.default = Some(Arc::new(cast_value));
-                    } else {
-                        unreachable!("'default' only for constant")
+                    let bound_expr = self
+                        .bind_expr(expr)
+                        .map_err(|err| err.with_context("DEFAULT", expr))?;
+                    let value = match &bound_expr {
+                        ScalarExpression::Constant(value) => Some(DataValue::clone(value)),
+                        // e.g. `DEFAULT -1`
+                        ScalarExpression::Unary { expr, op, .. } => match expr.as_ref() {
+                            ScalarExpression::Constant(value) => Some(unary_op(value, op)?),
+                            _ => None,
+                        },
+                        _ => None,
+                    };
+                    match value {
+                        Some(value) => {
+                            column_desc.default =
+                                Some(ValueRef::new(value.cast(&column_desc.column_datatype)?))
+                        }
+                        // e.g. `DEFAULT now()`, evaluated for every inserted row
+                        None => {
+                            if bound_expr.has_agg_call() {
+                                return Err(BindError::InvalidColumn(
+                                    format!("{} cannot default to an aggregate", column_name),
+                                    None,
+                                ));
+                            }
+                            if bound_expr.has_function_call() {
+                                return Err(BindError::InvalidColumn(
+                                    format!(
+                                        "{} cannot default to a registered function",
+                                        column_name
+                                    ),
+                                    None,
+                                ));
+                            }
+                            column_desc.default_expr = Some(GeneratedExpr {
+                                sql: expr.to_string(),
+                                expr: bound_expr,
+                            })
+                        }
+                    }
+                }
+                // bound by `bind_generated_columns` once all columns are known
+                ColumnOption::Generated {
+                    generated_as: GeneratedAs::ExpStored,
+                    ..
+                } => (),
+                ColumnOption::Generated {
+                    generated_as,
+                    sequence_options,
+                    ..
+                } => {
+                    if !column_desc.column_datatype.is_integer() {
+                        return Err(BindError::InvalidColumn(
+                            format!(
+                                "{} is an identity column, it must be an integer",
+                                column_name
+                            ),
+                            None,
+                        ));
+                    }
+                    if sequence_options
+                        .as_ref()
+                        .map_or(false, |options| !options.is_empty())
+                    {
+                        return Err(BindError::UnsupportedStmt(
+                            "sequence options of identity columns".to_string(),
+                        ));
                     }
+                    column_desc.identity = Some(match generated_as {
+                        GeneratedAs::Always => Identity::Always,
+                        _ => Identity::ByDefault,
+                    });
+                }
+                ColumnOption::Comment(comment) => column_desc.comment = Some(comment.clone()),
+                ColumnOption::OnUpdate(expr) => {
+                    column_desc.on_update = Some(self.bind_on_update_expr(&column_name, expr)?)
+                }
+                ColumnOption::ForeignKey { .. } => {
+                    return Err(BindError::UnsupportedStmt(
+                        "foreign keys are not supported".to_string(),
+                    ))
                 }
                 _ => todo!(),
             }
         }
 
+        if column_desc.identity.is_some()
+            && (column_desc.default.is_some() || column_desc.default_expr.is_some())
+        {
+            return Err(BindError::InvalidColumn(
+                format!(
+                    "{} is an identity column, it cannot have a default",
+                    column_name
+                ),
+                None,
+            ));
+        }
+
         Ok(ColumnCatalog::new(column_name, nullable, column_desc, None))
     }
+
+    /// Binds the expression of `ON UPDATE expr`, e.g. `now()`, it is bound before the table
+    /// so it refers to none of its columns.
+    fn bind_on_update_expr(
+        &mut self,
+        column_name: &str,
+        expr: &Expr,
+    ) -> Result<GeneratedExpr, BindError> {
+        let bound_expr = self
+            .bind_expr(expr)
+            .map_err(|err| err.with_context("ON UPDATE", expr))?;
+
+        if bound_expr.has_agg_call() {
+            return Err(BindError::InvalidColumn(
+                format!("{} cannot be updated by an aggregate", column_name),
+                None,
+            ));
+        }
+        if bound_expr.has_function_call() {
+            return Err(BindError::InvalidColumn(
+                format!("{} cannot be updated by a registered function", column_name),
+                None,
+            ));
+        }
+
+        Ok(GeneratedExpr {
+            sql: expr.to_string(),
+            expr: bound_expr,
+        })
+    }
+
+    /// Binds the expressions of the generated columns, which may only refer to the columns
+    /// that are not generated.
+    fn bind_generated_columns(
+        &mut self,
+        table_name: &TableName,
+        column_defs: &[ColumnDef],
+        columns: &mut [ColumnCatalog],
+    ) -> Result<(), BindError> {
+        let generation_exprs = column_defs
+            .iter()
+            .map(|column_def| {
+                column_def
+                    .options
+                    .iter()
+                    .find_map(|option_def| match &option_def.option {
+                        ColumnOption::Generated {
+                            generated_as: GeneratedAs::ExpStored,
+                            generation_expr,
+                            ..
+                        } => generation_expr.as_ref(),
+                        _ => None,
+                    })
+            })
+            .collect_vec();
+        if generation_exprs.iter().all(Option::is_none) {
+            return Ok(());
+        }
+        let base_columns = columns
+            .iter()
+            .zip(generation_exprs.iter())
+            .filter(|(_, expr)| expr.is_none())
+            .map(|(column, _)| column.clone())
+            .collect_vec();
+        let base_table = TableCatalog::new(table_name.clone(), base_columns)?;
+        self.context
+            .add_bind_table(table_name.clone(), base_table, None)?;
+
+        for (column, expr) in columns.iter_mut().zip(generation_exprs) {
+            if let Some(expr) = expr {
+                column.desc.generated = Some(self.bind_generated_expr(column, expr)?);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn bind_generated_expr(
+        &mut self,
+        column: &ColumnCatalog,
+        expr: &Expr,
+    ) -> Result<GeneratedExpr, BindError> {
+        if column.desc.is_primary
+            || column.desc.default.is_some()
+            || column.desc.default_expr.is_some()
+            || column.desc.on_update.is_some()
+        {
+            return Err(BindError::InvalidColumn(
+                format!(
+                    "{} is generated, it cannot be the primary key, have a default or be updated",
+                    column.name()
+                ),
+                None,
+            ));
+        }
+        let bound_expr = self
+            .bind_expr(expr)
+            .map_err(|err| err.with_context("GENERATED", expr))?;
+
+        if bound_expr.has_agg_call() {
+            return Err(BindError::InvalidColumn(
+                format!("{} cannot be generated by an aggregate", column.name()),
+                None,
+            ));
+        }
+        if bound_expr.has_function_call() {
+            return Err(BindError::InvalidColumn(
+                format!(
+                    "{} cannot be generated by a registered function, only by the builtin ones",
+                    column.name()
+                ),
+                None,
+            ));
+        }
+
+        Ok(GeneratedExpr {
+            sql: expr.to_string(),
+            expr: bound_expr,
+        })
+    }
 }
 
 #[cfg(test)]
@@ -142,7 +448,8 @@ mod tests {
         let transaction = storage.transaction().await?;
 
         let sql = "create table t1 (id int primary key, name varchar(10) null)";
-        let binder = Binder::new(BinderContext::new(&transaction));
+        let functions = Default::default();
+        let binder = Binder::new(BinderContext::new(&transaction, &functions));
         let stmt = crate::parser::parse_sql(sql).unwrap();
         let plan1 = binder.bind(&stmt[0]).unwrap();
 
@@ -161,6 +468,22 @@ mod tests {
                     op.columns[1].desc,
                     ColumnDesc::new(LogicalType::Varchar(Some(10)), false, false, None)
                 );
+                assert!(op.unique_keys.is_empty());
+            }
+            _ => unreachable!(),
+        }
+
+        let sql = "create table t2 (id int primary key, a int, b int, unique (a, b))";
+        let functions = Default::default();
+        let binder = Binder::new(BinderContext::new(&transaction, &functions));
+        let stmt = crate::parser::parse_sql(sql).unwrap();
+        let plan2 = binder.bind(&stmt[0]).unwrap();
+
+        match plan2.operator {
+            Operator::CreateTable(op) => {
+                assert!(!op.columns[1].desc.is_unique);
+                assert!(!op.columns[2].desc.is_unique);
+                assert_eq!(op.unique_keys, vec![vec!["a".to_string(), "b".to_string()]]);
             }
             _ => unreachable!(),
         }
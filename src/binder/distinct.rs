@@ -1,8 +1,11 @@
-use crate::binder::Binder;
+use crate::binder::{BindError, Binder};
 use crate::expression::ScalarExpression;
 use crate::planner::operator::aggregate::AggregateOperator;
+use crate::planner::operator::sort::SortField;
 use crate::planner::LogicalPlan;
 use crate::storage::Transaction;
+use itertools::Itertools;
+use sqlparser::ast::Expr;
 
 impl<'a, T: Transaction> Binder<'a, T> {
     pub fn bind_distinct(
@@ -10,6 +13,44 @@ impl<'a, T: Transaction> Binder<'a, T> {
         children: LogicalPlan,
         select_list: Vec<ScalarExpression>,
     ) -> LogicalPlan {
-        AggregateOperator::build(children, vec![], select_list)
+        AggregateOperator::build(children, vec![], select_list, vec![])
+    }
+
+    /// The keys of `DISTINCT ON (..)`. The first tuple of each key is the one the ORDER BY
+    /// puts first, so the keys have to be the leading expressions of it, in any order.
+    pub fn bind_distinct_on(
+        &mut self,
+        exprs: &[Expr],
+        orderby: Option<&[SortField]>,
+    ) -> Result<Vec<ScalarExpression>, BindError> {
+        let mut on_exprs: Vec<ScalarExpression> = Vec::with_capacity(exprs.len());
+
+        for expr in exprs {
+            let on_expr = self
+                .bind_expr(expr)
+                .map_err(|err| err.with_context("DISTINCT ON", expr))?;
+
+            if !on_exprs.contains(&on_expr) {
+                on_exprs.push(on_expr);
+            }
+        }
+        let leading_exprs = orderby
+            .unwrap_or_default()
+            .iter()
+            .take(on_exprs.len())
+            .map(|field| &field.expr)
+            .unique()
+            .collect_vec();
+
+        if leading_exprs.len() != on_exprs.len()
+            || !leading_exprs.iter().all(|expr| on_exprs.contains(expr))
+        {
+            return Err(BindError::DistinctOn(
+                "SELECT DISTINCT ON expressions must match the leading ORDER BY expressions"
+                    .to_string(),
+            ));
+        }
+
+        Ok(on_exprs)
     }
 }
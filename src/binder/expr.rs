@@ -1,17 +1,26 @@
 use crate::binder::BindError;
+use crate::catalog::ColumnRef;
 use crate::expression;
 use crate::expression::agg::AggKind;
+use crate::expression::function::BuiltinKind;
 use itertools::Itertools;
 use sqlparser::ast::{
-    BinaryOperator, DataType, Expr, Function, FunctionArg, FunctionArgExpr, Ident, UnaryOperator,
+    BinaryOperator, DataType, DateTimeField, Expr, Function, FunctionArg, FunctionArgExpr, Ident,
+    Interval, Query, UnaryOperator, Value,
 };
 use std::slice;
+use std::str::FromStr;
 use std::sync::Arc;
 
 use super::Binder;
 use crate::expression::ScalarExpression;
+use crate::planner::operator::project::ProjectOperator;
+use crate::planner::operator::Operator;
+use crate::planner::LogicalPlan;
 use crate::storage::Transaction;
-use crate::types::value::DataValue;
+use crate::types::errors::TypeError;
+use crate::types::interval;
+use crate::types::value::{DataValue, ValueRef};
 use crate::types::LogicalType;
 
 impl<'a, T: Transaction> Binder<'a, T> {
@@ -22,7 +31,8 @@ impl<'a, T: Transaction> Binder<'a, T> {
             }
             Expr::CompoundIdentifier(idents) => self.bind_column_ref_from_identifiers(idents, None),
             Expr::BinaryOp { left, right, op } => self.bind_binary_op_internal(left, right, op),
-            Expr::Value(v) => Ok(ScalarExpression::Constant(Arc::new(v.into()))),
+            Expr::Value(Value::Placeholder(placeholder)) => self.bind_placeholder(placeholder),
+            Expr::Value(v) => Ok(ScalarExpression::Constant(ValueRef::new(v.into()))),
             Expr::Function(func) => self.bind_agg_call(func),
             Expr::Nested(expr) => self.bind_expr(expr),
             Expr::UnaryOp { expr, op } => self.bind_unary_op_internal(expr, op),
@@ -40,6 +50,27 @@ impl<'a, T: Transaction> Binder<'a, T> {
                 negated,
             } => self.bind_is_in(expr, list, *negated),
             Expr::Cast { expr, data_type } => self.bind_cast(expr, data_type),
+            Expr::TypedString { data_type, value } => {
+                let value = DataValue::Utf8(Some(value.clone()))
+                    .cast(&LogicalType::try_from(data_type.clone())?)?;
+
+                Ok(ScalarExpression::Constant(ValueRef::new(value)))
+            }
+            Expr::Interval(interval) => self.bind_interval(interval),
+            Expr::Tuple(exprs) => Ok(ScalarExpression::Tuple(
+                exprs
+                    .iter()
+                    .map(|expr| self.bind_expr(expr))
+                    .try_collect()?,
+            )),
+            Expr::Subquery(query) => self.bind_subquery(query),
+            Expr::Exists { .. } => Err(BindError::Subquery(
+                "EXISTS is only supported in the conjunctions of WHERE".to_string(),
+            )),
+            Expr::AnyOp(_) | Expr::AllOp(_) => Err(BindError::Subquery(
+                "ANY and ALL are only supported over a subquery in the conjunctions of WHERE"
+                    .to_string(),
+            )),
             _ => {
                 todo!()
             }
@@ -54,6 +85,8 @@ impl<'a, T: Transaction> Binder<'a, T> {
     ) -> Result<ScalarExpression, BindError> {
         let left_expr = Box::new(self.bind_expr(expr)?);
         let right_expr = Box::new(self.bind_expr(pattern)?);
+        self.infer_parameter(expr, &LogicalType::Varchar(None));
+        self.infer_parameter(pattern, &LogicalType::Varchar(None));
         let op = if negated {
             expression::BinaryOperator::NotLike
         } else {
@@ -71,6 +104,47 @@ impl<'a, T: Transaction> Binder<'a, T> {
         &mut self,
         idents: &[Ident],
         bind_table_name: Option<&String>,
+    ) -> Result<ScalarExpression, BindError> {
+        match self.bind_query_column_ref(idents, bind_table_name) {
+            Err(err @ (BindError::InvalidTable(..) | BindError::InvalidColumn(..)))
+                if bind_table_name.is_none() =>
+            {
+                let column = self.bind_outer_column_ref(idents).ok_or(err)?;
+
+                self.context.outer_columns.push(column.clone());
+                Ok(ScalarExpression::ColumnRef(column))
+            }
+            result => result,
+        }
+    }
+
+    /// A column of the tables of the query around an `EXISTS` subquery.
+    fn bind_outer_column_ref(&self, idents: &[Ident]) -> Option<ColumnRef> {
+        let outer_bind_table = &self.context.outer_bind_table;
+
+        match idents {
+            [column] => outer_bind_table
+                .values()
+                .filter_map(|(table_catalog, _)| {
+                    table_catalog.get_column_by_name(&column.value.to_lowercase())
+                })
+                .exactly_one()
+                .ok()
+                .cloned(),
+            [table, column] => outer_bind_table
+                .get(&table.value.to_lowercase())
+                .and_then(|(table_catalog, _)| {
+                    table_catalog.get_column_by_name(&column.value.to_lowercase())
+                })
+                .cloned(),
+            _ => None,
+        }
+    }
+
+    fn bind_query_column_ref(
+        &mut self,
+        idents: &[Ident],
+        bind_table_name: Option<&String>,
     ) -> Result<ScalarExpression, BindError> {
         let idents = idents
             .iter()
@@ -87,27 +161,40 @@ impl<'a, T: Transaction> Binder<'a, T> {
                         .map(|ident| ident.value.clone())
                         .join(".")
                         .to_string(),
+                    None,
                 ))
             }
         };
 
         if let Some(table) = table_name.or(bind_table_name) {
-            let table_catalog = self
-                .context
-                .table(Arc::new(table.clone()))
-                .ok_or_else(|| BindError::InvalidTable(table.to_string()))?;
+            let table_name = Arc::new(table.clone());
+            // a qualifier must name a table of the query, by its alias if it has one
+            let table_catalog = if idents.len() > 1 {
+                self.context
+                    .bind_table
+                    .get(&table_name)
+                    .map(|(table_catalog, _)| table_catalog)
+            } else {
+                self.context.table(table_name)
+            }
+            .ok_or_else(|| BindError::InvalidTable(table.to_string(), None))?;
 
             let column_catalog = table_catalog
                 .get_column_by_name(column_name)
-                .ok_or_else(|| BindError::InvalidColumn(column_name.to_string()))?;
+                .ok_or_else(|| BindError::InvalidColumn(column_name.to_string(), None))?;
             Ok(ScalarExpression::ColumnRef(column_catalog.clone()))
         } else {
             // handle col syntax
             let mut got_column = None;
-            for (table_catalog, _) in self.context.bind_table.values() {
+            for table_name in &self.context.bind_table_order {
+                let (table_catalog, _) = &self.context.bind_table[table_name];
+
                 if let Some(column_catalog) = table_catalog.get_column_by_name(column_name) {
                     if got_column.is_some() {
-                        return Err(BindError::InvalidColumn(column_name.to_string()));
+                        if self.context.prefer_first_table {
+                            break;
+                        }
+                        return Err(BindError::AmbiguousColumn(column_name.to_string(), None));
                     }
                     got_column = Some(column_catalog);
                 }
@@ -120,8 +207,8 @@ impl<'a, T: Transaction> Binder<'a, T> {
                     });
                 }
             }
-            let column_catalog =
-                got_column.ok_or_else(|| BindError::InvalidColumn(column_name.to_string()))?;
+            let column_catalog = got_column
+                .ok_or_else(|| BindError::InvalidColumn(column_name.to_string(), None))?;
             Ok(ScalarExpression::ColumnRef(column_catalog.clone()))
         }
     }
@@ -132,26 +219,68 @@ impl<'a, T: Transaction> Binder<'a, T> {
         right: &Expr,
         op: &BinaryOperator,
     ) -> Result<ScalarExpression, BindError> {
-        let left_expr = Box::new(self.bind_expr(left)?);
-        let right_expr = Box::new(self.bind_expr(right)?);
+        let mut left_expr = Box::new(self.bind_expr(left)?);
+        let mut right_expr = Box::new(self.bind_expr(right)?);
+        self.infer_parameter(left, &right_expr.return_type());
+        self.infer_parameter(right, &left_expr.return_type());
 
+        let promote = |left_ty: &LogicalType, right_ty: &LogicalType| {
+            LogicalType::promote(left_ty, right_ty).ok_or_else(|| {
+                BindError::BinaryOpTypeMismatch(left_ty.to_string(), right_ty.to_string())
+            })
+        };
         let ty = match op {
-            BinaryOperator::Plus
-            | BinaryOperator::Minus
-            | BinaryOperator::Multiply
-            | BinaryOperator::Divide
-            | BinaryOperator::Modulo => {
-                LogicalType::max_logical_type(&left_expr.return_type(), &right_expr.return_type())?
+            BinaryOperator::Plus | BinaryOperator::Minus => {
+                let (left_ty, right_ty) = (left_expr.return_type(), right_expr.return_type());
+
+                match LogicalType::temporal_arithmetic_type(
+                    &left_ty,
+                    &right_ty,
+                    op == &BinaryOperator::Minus,
+                ) {
+                    Some(ty) => ty,
+                    None => promote(&left_ty, &right_ty)?,
+                }
+            }
+            BinaryOperator::Multiply | BinaryOperator::Divide | BinaryOperator::Modulo => {
+                promote(&left_expr.return_type(), &right_expr.return_type())?
+            }
+            BinaryOperator::BitwiseAnd
+            | BinaryOperator::BitwiseOr
+            | BinaryOperator::BitwiseXor
+            | BinaryOperator::PGExp
+            | BinaryOperator::PGBitwiseXor
+            | BinaryOperator::PGBitwiseShiftLeft
+            | BinaryOperator::PGBitwiseShiftRight => {
+                let (left_ty, right_ty) = (left_expr.return_type(), right_expr.return_type());
+
+                if !left_ty.is_integer() || !right_ty.is_integer() {
+                    return Err(BindError::BinaryOpTypeMismatch(
+                        left_ty.to_string(),
+                        right_ty.to_string(),
+                    ));
+                }
+                // shifts keep the type of the shifted value
+                if matches!(
+                    op,
+                    BinaryOperator::PGBitwiseShiftLeft | BinaryOperator::PGBitwiseShiftRight
+                ) {
+                    left_ty
+                } else {
+                    promote(&left_ty, &right_ty)?
+                }
             }
             BinaryOperator::Gt
             | BinaryOperator::Lt
             | BinaryOperator::GtEq
             | BinaryOperator::LtEq
             | BinaryOperator::Eq
-            | BinaryOperator::NotEq
-            | BinaryOperator::And
-            | BinaryOperator::Or
-            | BinaryOperator::Xor => LogicalType::Boolean,
+            | BinaryOperator::NotEq => {
+                self.coerce_comparison(&mut left_expr, &mut right_expr)?;
+
+                LogicalType::Boolean
+            }
+            BinaryOperator::And | BinaryOperator::Or | BinaryOperator::Xor => LogicalType::Boolean,
             _ => todo!(),
         };
 
@@ -163,6 +292,107 @@ impl<'a, T: Transaction> Binder<'a, T> {
         })
     }
 
+    /// Brings both sides of a comparison to the same type.
+    ///
+    /// A constant takes the type of the other side if the cast loses nothing, e.g. `c1 > 1`
+    /// on a tinyint `c1`. Otherwise both sides are cast to their common type, which is not
+    /// done for strict types.
+    fn coerce_comparison(
+        &self,
+        left_expr: &mut ScalarExpression,
+        right_expr: &mut ScalarExpression,
+    ) -> Result<(), BindError> {
+        match (&mut *left_expr, &mut *right_expr) {
+            (ScalarExpression::Tuple(left), ScalarExpression::Tuple(right)) => {
+                // rows are compared element by element
+                if left.len() != right.len() {
+                    return Err(BindError::BinaryOpTypeMismatch(
+                        format!("row of {} values", left.len()),
+                        format!("row of {} values", right.len()),
+                    ));
+                }
+                for (left_expr, right_expr) in left.iter_mut().zip(right.iter_mut()) {
+                    self.coerce_comparison(left_expr, right_expr)?;
+                }
+
+                return Ok(());
+            }
+            (ScalarExpression::Tuple(_), _) | (_, ScalarExpression::Tuple(_)) => {
+                return Err(BindError::BinaryOpTypeMismatch(
+                    left_expr.output_columns().name().to_string(),
+                    right_expr.output_columns().name().to_string(),
+                ));
+            }
+            _ => (),
+        }
+        let (left_ty, right_ty) = (left_expr.return_type(), right_expr.return_type());
+        let is_same_type = left_ty == right_ty
+            || matches!(
+                (left_ty, right_ty),
+                (LogicalType::SqlNull, _)
+                    | (_, LogicalType::SqlNull)
+                    | (LogicalType::Varchar(_), LogicalType::Varchar(_))
+                    | (LogicalType::Decimal(_, _), LogicalType::Decimal(_, _))
+            );
+        if is_same_type
+            || Self::coerce_constant(left_expr, &right_ty)
+            || Self::coerce_constant(right_expr, &left_ty)
+        {
+            return Ok(());
+        }
+        let mismatch =
+            || BindError::BinaryOpTypeMismatch(left_ty.to_string(), right_ty.to_string());
+
+        if self.context.strict_types {
+            return Err(mismatch());
+        }
+        let ty = LogicalType::max_logical_type(&left_ty, &right_ty).map_err(|_| mismatch())?;
+
+        for expr in [left_expr, right_expr] {
+            if expr.return_type() != ty {
+                *expr = ScalarExpression::TypeCast {
+                    expr: Box::new(expr.clone()),
+                    ty,
+                };
+            }
+        }
+
+        Ok(())
+    }
+
+    fn coerce_constant(expr: &mut ScalarExpression, ty: &LogicalType) -> bool {
+        if let ScalarExpression::Constant(value) = expr {
+            if let Ok(cast_value) = DataValue::clone(value).cast(ty) {
+                let is_integer =
+                    |ty: &LogicalType| ty.is_signed_numeric() || ty.is_unsigned_numeric();
+                let is_number =
+                    |ty: &LogicalType| ty.is_numeric() || matches!(ty, LogicalType::Decimal(_, _));
+                // the casts between integers fail instead of losing anything
+                let is_lossless = is_integer(&value.logical_type()) && is_integer(ty)
+                    || LogicalType::can_implicit_cast(&value.logical_type(), ty)
+                    || match value.as_ref() {
+                        // a string is compared by the number it is parsed into, e.g. `'01'`
+                        // and `' 1'` are `1`
+                        DataValue::Utf8(_) if is_number(ty) => DataValue::clone(&cast_value)
+                            .cast(&LogicalType::Varchar(None))
+                            .and_then(|round_trip| round_trip.cast(ty))
+                            .is_ok_and(|round_trip| round_trip == cast_value),
+                        _ => DataValue::clone(&cast_value)
+                            .cast(&value.logical_type())
+                            .map_or(false, |round_trip| &round_trip == value.as_ref()),
+                    };
+
+                if is_lossless {
+                    *expr = ScalarExpression::Constant(ValueRef::new(cast_value));
+
+                    return true;
+                }
+            }
+        }
+
+        false
+    }
+
     fn bind_unary_op_internal(
         &mut self,
         expr: &Expr,
@@ -196,9 +426,13 @@ impl<'a, T: Transaction> Binder<'a, T> {
                 _ => todo!(),
             }
         }
-        let ty = args[0].return_type();
+        let name = func.name.to_string().to_lowercase();
+        let ty = args
+            .first()
+            .map(|expr| expr.return_type())
+            .unwrap_or(LogicalType::SqlNull);
 
-        Ok(match func.name.to_string().to_lowercase().as_str() {
+        let expr = match name.as_str() {
             "count" => ScalarExpression::AggCall {
                 distinct: func.distinct,
                 kind: AggKind::Count,
@@ -229,8 +463,98 @@ impl<'a, T: Transaction> Binder<'a, T> {
                 args,
                 ty,
             },
-            _ => todo!(),
-        })
+            "approx_count_distinct" => ScalarExpression::AggCall {
+                distinct: false,
+                kind: AggKind::ApproxCountDistinct,
+                args,
+                ty: LogicalType::Integer,
+            },
+            "grouping" => ScalarExpression::AggCall {
+                distinct: false,
+                kind: AggKind::Grouping,
+                args,
+                ty: LogicalType::Integer,
+            },
+            _ => match BuiltinKind::from_name(&name) {
+                Some(kind) => Self::bind_builtin(kind, args)?,
+                None => self.bind_scalar_function(name, args)?,
+            },
+        };
+
+        match &func.over {
+            Some(over) => self.bind_window_call(expr, over),
+            None => Ok(expr),
+        }
+    }
+
+    /// Binds a built-in function, the arguments are unified to a common type.
+    fn bind_builtin(
+        kind: BuiltinKind,
+        args: Vec<ScalarExpression>,
+    ) -> Result<ScalarExpression, BindError> {
+        let is_valid_arity = match kind {
+            BuiltinKind::NullIf => args.len() == 2,
+            BuiltinKind::Lower | BuiltinKind::Upper => args.len() == 1,
+            BuiltinKind::Now | BuiltinKind::GenRandomUuid => args.is_empty(),
+            _ => !args.is_empty(),
+        };
+        if !is_valid_arity {
+            return Err(BindError::InvalidFunction(format!(
+                "{} got {} arguments",
+                kind.name(),
+                args.len()
+            )));
+        }
+        let mut ty = LogicalType::SqlNull;
+
+        for arg in args.iter() {
+            ty = LogicalType::max_logical_type(&ty, &arg.return_type())?;
+        }
+        match kind {
+            BuiltinKind::NullIf => ty = args[0].return_type(),
+            BuiltinKind::Lower | BuiltinKind::Upper => ty = LogicalType::Varchar(None),
+            BuiltinKind::Now => ty = LogicalType::DateTime,
+            BuiltinKind::GenRandomUuid => ty = LogicalType::Varchar(Some(36)),
+            _ => (),
+        }
+
+        Ok(ScalarExpression::Builtin { kind, args, ty })
+    }
+
+    /// Binds a call of a registered function, the arguments must be implicitly castable
+    /// to its signature.
+    fn bind_scalar_function(
+        &mut self,
+        name: String,
+        args: Vec<ScalarExpression>,
+    ) -> Result<ScalarExpression, BindError> {
+        let function = self
+            .context
+            .functions
+            .get(&name)
+            .cloned()
+            .ok_or_else(|| BindError::InvalidFunction(name.clone()))?;
+
+        if args.len() != function.signature.args.len() {
+            return Err(BindError::InvalidFunction(format!(
+                "{} expects {} arguments, but got {}",
+                name,
+                function.signature.args.len(),
+                args.len()
+            )));
+        }
+        for (arg, ty) in args.iter().zip(function.signature.args.iter()) {
+            let arg_ty = arg.return_type();
+
+            if !LogicalType::can_implicit_cast(&arg_ty, ty) {
+                return Err(BindError::InvalidFunction(format!(
+                    "{} expects argument of type {}, but got {}",
+                    name, ty, arg_ty
+                )));
+            }
+        }
+
+        Ok(ScalarExpression::Function { function, args })
     }
 
     fn bind_is_null(&mut self, expr: &Expr, negated: bool) -> Result<ScalarExpression, BindError> {
@@ -246,23 +570,150 @@ impl<'a, T: Transaction> Binder<'a, T> {
         list: &[Expr],
         negated: bool,
     ) -> Result<ScalarExpression, BindError> {
-        let args = list.iter().map(|expr| self.bind_expr(expr)).try_collect()?;
+        let args: Vec<ScalarExpression> =
+            list.iter().map(|expr| self.bind_expr(expr)).try_collect()?;
+        let bound_expr = self.bind_expr(expr)?;
+
+        for (arg, arg_expr) in list.iter().zip(args.iter()) {
+            self.infer_parameter(arg, &bound_expr.return_type());
+            self.infer_parameter(expr, &arg_expr.return_type());
+        }
 
         Ok(ScalarExpression::In {
             negated,
-            expr: Box::new(self.bind_expr(expr)?),
+            expr: Box::new(bound_expr),
             args,
         })
     }
 
     fn bind_cast(&mut self, expr: &Expr, ty: &DataType) -> Result<ScalarExpression, BindError> {
+        let ty = LogicalType::try_from(ty.clone())?;
+        self.infer_parameter(expr, &ty);
+
         Ok(ScalarExpression::TypeCast {
             expr: Box::new(self.bind_expr(expr)?),
-            ty: LogicalType::try_from(ty.clone())?,
+            ty,
         })
     }
 
+    /// Binds a scalar subquery, its plan is joined with the query and the expression refers
+    /// to the single column it produces. It can not refer to the columns of the query.
+    fn bind_subquery(&mut self, query: &Query) -> Result<ScalarExpression, BindError> {
+        let (plan, column) = self.bind_subquery_column(query)?;
+        self.context.sub_queries.push(plan);
+
+        Ok(ScalarExpression::ColumnRef(column))
+    }
+
+    /// Binds `expr op ANY (subquery)`, or `expr op ALL (subquery)`, to the comparison of `expr`
+    /// and the single column of the subquery, of which the plan is returned with it.
+    pub(crate) fn bind_quantified_comparison(
+        &mut self,
+        left: &Expr,
+        op: &BinaryOperator,
+        query: &Query,
+    ) -> Result<(ScalarExpression, LogicalPlan), BindError> {
+        if !matches!(
+            op,
+            BinaryOperator::Gt
+                | BinaryOperator::Lt
+                | BinaryOperator::GtEq
+                | BinaryOperator::LtEq
+                | BinaryOperator::Eq
+                | BinaryOperator::NotEq
+        ) {
+            return Err(BindError::Subquery(format!(
+                "ANY and ALL are only supported with a comparison, got {}",
+                op
+            )));
+        }
+        let (plan, column) = self.bind_subquery_column(query)?;
+        let mut left_expr = self.bind_expr(left)?;
+        let mut right_expr = ScalarExpression::ColumnRef(column);
+        self.infer_parameter(left, &right_expr.return_type());
+        self.coerce_comparison(&mut left_expr, &mut right_expr)?;
+
+        let comparison = ScalarExpression::Binary {
+            op: op.clone().into(),
+            left_expr: Box::new(left_expr),
+            right_expr: Box::new(right_expr),
+            ty: LogicalType::Boolean,
+        };
+
+        Ok((comparison, plan))
+    }
+
+    /// The plan of a subquery of a single column, and the column.
+    fn bind_subquery_column(
+        &mut self,
+        query: &Query,
+    ) -> Result<(LogicalPlan, ColumnRef), BindError> {
+        let mut plan = Binder::new(self.context.derive()).bind_query(query)?;
+        let columns = plan.schema();
+
+        if columns.len() != 1 {
+            return Err(BindError::Subquery(format!(
+                "subquery must return only one column, got {}",
+                columns.len()
+            )));
+        }
+        // a name of its own, so that it is never taken for a column of the query
+        let alias = format!("({})", query);
+        let column = if let Operator::Project(op) = &mut plan.operator {
+            let expr = op.exprs.remove(0).unpack_alias().clone();
+            op.exprs.push(ScalarExpression::Alias {
+                expr: Box::new(expr),
+                alias,
+            });
+
+            op.exprs[0].output_columns()
+        } else {
+            let expr = ScalarExpression::Alias {
+                expr: Box::new(ScalarExpression::ColumnRef(columns[0].clone())),
+                alias,
+            };
+            let column = expr.output_columns();
+
+            plan = LogicalPlan {
+                operator: Operator::Project(ProjectOperator { exprs: vec![expr] }),
+                childrens: vec![plan],
+            };
+            column
+        };
+
+        Ok((plan, column))
+    }
+
+    /// Binds `INTERVAL '1 day'` and the single unit form `INTERVAL '3' HOUR`.
+    fn bind_interval(&mut self, interval: &Interval) -> Result<ScalarExpression, BindError> {
+        let value = match interval.value.as_ref() {
+            Expr::Value(Value::SingleQuotedString(value) | Value::Number(value, _)) => value,
+            expr => return Err(TypeError::InvalidInterval(expr.to_string()).into()),
+        };
+        let value = match (&interval.leading_field, &interval.last_field) {
+            (None, None) => value.clone(),
+            (Some(field), None) => {
+                let unit = match field {
+                    DateTimeField::Year => "year",
+                    DateTimeField::Month => "month",
+                    DateTimeField::Week => "week",
+                    DateTimeField::Day => "day",
+                    DateTimeField::Hour => "hour",
+                    DateTimeField::Minute => "minute",
+                    DateTimeField::Second => "second",
+                    _ => return Err(TypeError::InvalidInterval(interval.to_string()).into()),
+                };
+                format!("{} {}", value, unit)
+            }
+            _ => return Err(TypeError::InvalidInterval(interval.to_string()).into()),
+        };
+
+        Ok(ScalarExpression::Constant(ValueRef::new(
+            DataValue::Interval(Some(interval::Interval::from_str(&value)?)),
+        )))
+    }
+
     fn wildcard_expr() -> ScalarExpression {
-        ScalarExpression::Constant(Arc::new(DataValue::Utf8(Some("*".to_string()))))
+        ScalarExpression::Constant(ValueRef::new(DataValue::Utf8(Some("*".to_string()))))
     }
 }
@@ -1,5 +1,5 @@
 use crate::binder::{lower_case_name, split_name, BindError, Binder};
-use crate::catalog::ColumnRef;
+use crate::catalog::{ColumnCatalog, ColumnRef, Identity, TableName};
 use crate::expression::value_compute::unary_op;
 use crate::expression::ScalarExpression;
 use crate::planner::operator::insert::InsertOperator;
@@ -8,17 +8,21 @@ use crate::planner::operator::Operator;
 use crate::planner::LogicalPlan;
 use crate::storage::Transaction;
 use crate::types::value::{DataValue, ValueRef};
-use sqlparser::ast::{Expr, Ident, ObjectName};
+use sqlparser::ast::{Expr, Ident, ObjectName, SelectItem};
 use std::slice;
 use std::sync::Arc;
 
 impl<'a, T: Transaction> Binder<'a, T> {
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn bind_insert(
         &mut self,
         name: ObjectName,
         idents: &[Ident],
         expr_rows: &Vec<Vec<Expr>>,
         is_overwrite: bool,
+        is_replace: bool,
+        overriding_system_value: bool,
+        returning: Option<&[SelectItem]>,
     ) -> Result<LogicalPlan, BindError> {
         let name = lower_case_name(&name);
         let (_, name) = split_name(&name)?;
@@ -28,7 +32,11 @@ impl<'a, T: Transaction> Binder<'a, T> {
             let mut columns = Vec::new();
 
             if idents.is_empty() {
-                columns = table.all_columns();
+                columns = table
+                    .visible_columns()
+                    .into_iter()
+                    .filter(|column| Self::check_writable(column).is_ok())
+                    .collect();
             } else {
                 let bind_table_name = Some(table_name.to_string());
                 for ident in idents {
@@ -36,56 +44,123 @@ impl<'a, T: Transaction> Binder<'a, T> {
                         slice::from_ref(ident),
                         bind_table_name.as_ref(),
                     )? {
-                        ScalarExpression::ColumnRef(catalog) => columns.push(catalog),
+                        ScalarExpression::ColumnRef(catalog) => {
+                            if !(overriding_system_value && catalog.desc.identity.is_some()) {
+                                Self::check_writable(&catalog)?;
+                            }
+                            columns.push(catalog)
+                        }
                         _ => unreachable!(),
                     }
                 }
             }
             let mut rows = Vec::with_capacity(expr_rows.len());
 
-            for expr_row in expr_rows {
+            for (i, expr_row) in expr_rows.iter().enumerate() {
+                if expr_row.len() > columns.len() {
+                    return Err(BindError::InvalidColumn(
+                        format!(
+                            "{} values given for {} writable columns",
+                            expr_row.len(),
+                            columns.len()
+                        ),
+                        None,
+                    ));
+                }
                 let mut row = Vec::with_capacity(expr_row.len());
 
-                for (i, expr) in expr_row.iter().enumerate() {
-                    match &self.bind_expr(expr)? {
+                for (j, expr) in expr_row.iter().enumerate() {
+                    let column = &columns[j];
+                    self.infer_parameter(expr, column.datatype());
+                    let value = match &self.bind_expr(expr)? {
                         ScalarExpression::Constant(value) => {
                             // Check if the value length is too long
-                            value.check_len(columns[i].datatype())?;
-                            let cast_value = DataValue::clone(value).cast(columns[i].datatype())?;
-                            row.push(Arc::new(cast_value))
+                            value
+                                .check_len(column.datatype())
+                                .and_then(|_| DataValue::clone(value).cast(column.datatype()))
                         }
                         ScalarExpression::Unary { expr, op, .. } => {
                             if let ScalarExpression::Constant(value) = expr.as_ref() {
-                                row.push(Arc::new(
-                                    unary_op(value, op)?.cast(columns[i].datatype())?,
-                                ))
+                                unary_op(value, op)?.cast(column.datatype())
                             } else {
                                 unreachable!()
                             }
                         }
                         _ => unreachable!(),
-                    }
+                    };
+                    row.push(ValueRef::new(value.map_err(|source| {
+                        BindError::ValuesTypeMismatch {
+                            row: i + 1,
+                            column: column.name().to_string(),
+                            source,
+                        }
+                    })?))
                 }
 
                 rows.push(row);
             }
             let values_plan = self.bind_values(rows, columns);
+            let returning = match returning {
+                Some(items) => self.bind_returning(table_name.clone(), items)?,
+                None => vec![],
+            };
 
             Ok(LogicalPlan {
                 operator: Operator::Insert(InsertOperator {
                     table_name,
                     is_overwrite,
+                    is_replace,
+                    overriding_system_value,
+                    returning,
                 }),
                 childrens: vec![values_plan],
             })
         } else {
-            Err(BindError::InvalidTable(format!(
-                "not found table {}",
-                table_name
-            )))
+            Err(BindError::InvalidTable(
+                format!("not found table {}", table_name),
+                None,
+            ))
         }
     }
 
+    /// Binds the items of `RETURNING` over the columns of the table written, `*` is all of
+    /// its visible columns.
+    fn bind_returning(
+        &mut self,
+        table_name: TableName,
+        items: &[SelectItem],
+    ) -> Result<Vec<ScalarExpression>, BindError> {
+        let table = self
+            .context
+            .table(table_name.clone())
+            .cloned()
+            .ok_or_else(|| BindError::InvalidTable(table_name.to_string(), None))?;
+        self.context.add_bind_table(table_name, table, None)?;
+
+        let exprs = self.normalize_select_item(items)?;
+        if exprs.iter().any(ScalarExpression::has_agg_call) {
+            return Err(BindError::InvalidColumn(
+                "RETURNING cannot have an aggregate".to_string(),
+                None,
+            ));
+        }
+
+        Ok(exprs)
+    }
+
+    /// The values of generated columns and of `GENERATED ALWAYS AS IDENTITY` columns are only
+    /// computed, never written.
+    pub(crate) fn check_writable(column: &ColumnCatalog) -> Result<(), BindError> {
+        if column.desc.generated.is_some() || column.desc.identity == Some(Identity::Always) {
+            return Err(BindError::InvalidColumn(
+                format!("{} is generated and cannot be written", column.name()),
+                None,
+            ));
+        }
+
+        Ok(())
+    }
+
     pub(crate) fn bind_values(
         &mut self,
         rows: Vec<Vec<ValueRef>>,
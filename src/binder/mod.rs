@@ -1,25 +1,47 @@
 pub mod aggregate;
+mod alter_table;
+mod comment;
 pub mod copy;
+mod create_index;
 mod create_table;
 mod delete;
 mod distinct;
 mod drop_table;
 pub mod expr;
 mod insert;
+pub mod parameter;
 mod select;
+mod set_operation;
+mod set_variable;
 mod show;
 mod truncate;
 mod update;
+mod values;
+mod window;
 
-use sqlparser::ast::{Ident, ObjectName, ObjectType, SetExpr, Statement};
+use sqlparser::ast::{
+    AnalyzeFormat, Expr, Ident, ObjectName, ObjectType, Query, SetExpr, ShowCreateObject,
+    SqliteOnConflict, Statement, WindowSpec,
+};
+use std::cell::RefCell;
 use std::collections::BTreeMap;
+use std::fmt;
+use std::fmt::Formatter;
 
-use crate::catalog::{CatalogError, TableCatalog, TableName, DEFAULT_SCHEMA_NAME};
+use crate::binder::parameter::Parameters;
+use crate::catalog::{CatalogError, ColumnRef, TableCatalog, TableName, DEFAULT_SCHEMA_NAME};
+use crate::db::SessionSettings;
+use crate::expression::function::Functions;
 use crate::expression::ScalarExpression;
+use crate::parser::ParsedStatement;
 use crate::planner::operator::join::JoinType;
+use crate::planner::operator::sort::NullsOrder;
+use crate::planner::operator::{ExplainFormat, Operator};
 use crate::planner::LogicalPlan;
 use crate::storage::Transaction;
 use crate::types::errors::TypeError;
+use crate::types::LogicalType;
+use std::rc::Rc;
 
 pub enum InputRefType {
     AggCall,
@@ -29,28 +51,124 @@ pub enum InputRefType {
 #[derive(Clone)]
 pub struct BinderContext<'a, T: Transaction> {
     transaction: &'a T,
+    functions: &'a Functions,
+    // comparisons of different types are rejected instead of being cast to a common type
+    strict_types: bool,
+    // `UPDATE` and `DELETE` without `WHERE` are rejected
+    safe_updates: bool,
+    // an unqualified column of several tables is the one of the table listed first
+    prefer_first_table: bool,
+    // the joins without a condition joining their tables are cartesian products
+    // like `CROSS JOIN` instead of being rejected
+    allow_cartesian_products: bool,
+    // the placement of the nulls of the sort keys without `NULLS FIRST` or `NULLS LAST`
+    nulls_order: NullsOrder,
+    // the parameters of a prepared statement
+    parameters: Option<Rc<RefCell<Parameters>>>,
+    // the tables of the query keyed by their alias, or by their name if they have none
     pub(crate) bind_table: BTreeMap<TableName, (TableCatalog, Option<JoinType>)>,
+    // the names of `bind_table` in the order the tables are listed
+    bind_table_order: Vec<TableName>,
     aliases: BTreeMap<String, ScalarExpression>,
-    table_aliases: BTreeMap<String, TableName>,
+    // the queries of the `WITH` clause, inlined where they are referenced
+    ctes: BTreeMap<String, Query>,
     group_by_exprs: Vec<ScalarExpression>,
+    grouping_sets: Vec<Vec<usize>>,
     pub(crate) agg_calls: Vec<ScalarExpression>,
+    // the windows of the `WINDOW` clause keyed by their name, and the window calls
+    // computed over the aggregated tuples
+    windows: BTreeMap<String, WindowSpec>,
+    window_calls: Vec<ScalarExpression>,
+    // the plans of the scalar subqueries, joined with the query before its projection
+    sub_queries: Vec<LogicalPlan>,
+    // the tables of the query of an `EXISTS` subquery, whose columns it may refer to,
+    // and the columns of them it refers to
+    outer_bind_table: BTreeMap<TableName, (TableCatalog, Option<JoinType>)>,
+    outer_columns: Vec<ColumnRef>,
 }
 
 impl<'a, T: Transaction> BinderContext<'a, T> {
-    pub fn new(transaction: &'a T) -> Self {
+    pub fn new(transaction: &'a T, functions: &'a Functions) -> Self {
         BinderContext {
             transaction,
+            functions,
+            strict_types: false,
+            safe_updates: false,
+            prefer_first_table: false,
+            allow_cartesian_products: false,
+            nulls_order: NullsOrder::default(),
+            parameters: None,
             bind_table: Default::default(),
+            bind_table_order: vec![],
             aliases: Default::default(),
-            table_aliases: Default::default(),
+            ctes: Default::default(),
             group_by_exprs: vec![],
+            grouping_sets: vec![],
             agg_calls: Default::default(),
+            windows: Default::default(),
+            window_calls: vec![],
+            sub_queries: vec![],
+            outer_bind_table: Default::default(),
+            outer_columns: vec![],
         }
     }
 
+    /// A context for binding a subquery, it shares the `WITH` clause but none of the tables.
+    pub fn derive(&self) -> Self {
+        BinderContext {
+            strict_types: self.strict_types,
+            prefer_first_table: self.prefer_first_table,
+            allow_cartesian_products: self.allow_cartesian_products,
+            nulls_order: self.nulls_order,
+            parameters: self.parameters.clone(),
+            ctes: self.ctes.clone(),
+            ..BinderContext::new(self.transaction, self.functions)
+        }
+    }
+
+    /// The settings of the session the statement is bound in.
+    pub fn with_settings(self, settings: &SessionSettings) -> Self {
+        self.with_strict_types(settings.strict_types)
+            .with_safe_updates(settings.safe_updates)
+            .with_prefer_first_table(settings.prefer_first_table)
+            .with_allow_cartesian_products(settings.allow_cartesian_products)
+            .with_nulls_order(settings.nulls_order)
+    }
+
+    pub fn with_strict_types(mut self, strict_types: bool) -> Self {
+        self.strict_types = strict_types;
+        self
+    }
+
+    pub fn with_safe_updates(mut self, safe_updates: bool) -> Self {
+        self.safe_updates = safe_updates;
+        self
+    }
+
+    pub fn with_prefer_first_table(mut self, prefer_first_table: bool) -> Self {
+        self.prefer_first_table = prefer_first_table;
+        self
+    }
+
+    pub fn with_allow_cartesian_products(mut self, allow_cartesian_products: bool) -> Self {
+        self.allow_cartesian_products = allow_cartesian_products;
+        self
+    }
+
+    pub fn with_nulls_order(mut self, nulls_order: NullsOrder) -> Self {
+        self.nulls_order = nulls_order;
+        self
+    }
+
+    pub(crate) fn with_parameters(mut self, parameters: Option<Rc<RefCell<Parameters>>>) -> Self {
+        self.parameters = parameters;
+        self
+    }
+
+    /// The table bound in the query under `table_name`, otherwise the table of the catalog.
     pub fn table(&self, table_name: TableName) -> Option<&TableCatalog> {
-        if let Some(real_name) = self.table_aliases.get(table_name.as_ref()) {
-            self.transaction.table(real_name.clone())
+        if let Some((table_catalog, _)) = self.bind_table.get(&table_name) {
+            Some(table_catalog)
         } else {
             self.transaction.table(table_name)
         }
@@ -67,19 +185,21 @@ impl<'a, T: Transaction> BinderContext<'a, T> {
     pub fn add_alias(&mut self, alias: String, expr: ScalarExpression) -> Result<(), BindError> {
         let is_exist = self.aliases.insert(alias.clone(), expr).is_some();
         if is_exist {
-            return Err(BindError::InvalidColumn(format!("{} duplicated", alias)));
+            return Err(BindError::InvalidColumn(
+                format!("{} duplicated", alias),
+                None,
+            ));
         }
 
         Ok(())
     }
 
-    pub fn add_table_alias(&mut self, alias: String, table: TableName) -> Result<(), BindError> {
-        let is_alias_exist = self
-            .table_aliases
-            .insert(alias.clone(), table.clone())
-            .is_some();
-        if is_alias_exist {
-            return Err(BindError::InvalidTable(format!("{} duplicated", alias)));
+    pub fn add_cte(&mut self, name: String, query: Query) -> Result<(), BindError> {
+        if self.ctes.insert(name.clone(), query).is_some() {
+            return Err(BindError::InvalidTable(
+                format!("{} duplicated", name),
+                None,
+            ));
         }
 
         Ok(())
@@ -96,8 +216,15 @@ impl<'a, T: Transaction> BinderContext<'a, T> {
             .insert(table.clone(), (table_catalog.clone(), join_type))
             .is_some();
         if is_bound {
-            return Err(BindError::InvalidTable(format!("{} duplicated", table)));
+            return Err(BindError::InvalidTable(
+                format!(
+                    "{} duplicated, the same table needs a different alias",
+                    table
+                ),
+                None,
+            ));
         }
+        self.bind_table_order.push(table);
 
         Ok(())
     }
@@ -116,7 +243,8 @@ impl<'a, T: Transaction> Binder<'a, T> {
         Binder { context }
     }
 
-    pub fn bind(mut self, stmt: &Statement) -> Result<LogicalPlan, BindError> {
+    pub fn bind(mut self, parsed: &ParsedStatement) -> Result<LogicalPlan, BindError> {
+        let stmt = &parsed.stmt;
         let plan = match stmt {
             Statement::Query(query) => self.bind_query(query)?,
             Statement::CreateTable {
@@ -124,8 +252,27 @@ impl<'a, T: Transaction> Binder<'a, T> {
                 columns,
                 constraints,
                 if_not_exists,
+                query,
+                ..
+            } => match query {
+                Some(query) => {
+                    if !columns.is_empty() || !constraints.is_empty() {
+                        return Err(BindError::UnsupportedStmt(
+                            "column definitions of CREATE TABLE AS".to_string(),
+                        ));
+                    }
+                    self.bind_create_table_as(name, query, *if_not_exists)?
+                }
+                None => self.bind_create_table(name, columns, constraints, *if_not_exists)?,
+            },
+            Statement::CreateIndex {
+                name,
+                table_name,
+                columns,
+                unique,
+                if_not_exists,
                 ..
-            } => self.bind_create_table(name, columns, constraints, *if_not_exists)?,
+            } => self.bind_create_index(name, table_name, columns, *unique, *if_not_exists)?,
             Statement::Drop {
                 object_type, names, ..
             } => match object_type {
@@ -133,14 +280,24 @@ impl<'a, T: Transaction> Binder<'a, T> {
                 _ => todo!(),
             },
             Statement::Insert {
+                or,
                 table_name,
                 columns,
                 source,
                 overwrite,
+                returning,
                 ..
             } => {
                 if let SetExpr::Values(values) = source.body.as_ref() {
-                    self.bind_insert(table_name.to_owned(), columns, &values.rows, *overwrite)?
+                    self.bind_insert(
+                        table_name.to_owned(),
+                        columns,
+                        &values.rows,
+                        *overwrite,
+                        matches!(or, Some(SqliteOnConflict::Replace)),
+                        parsed.overriding_system_value,
+                        returning.as_deref(),
+                    )?
                 } else {
                     todo!()
                 }
@@ -149,12 +306,14 @@ impl<'a, T: Transaction> Binder<'a, T> {
                 table,
                 selection,
                 assignments,
+                from,
                 ..
             } => {
                 if !table.joins.is_empty() {
                     unimplemented!()
                 } else {
-                    self.bind_update(table, selection, assignments)?
+                    self.check_safe_update("UPDATE", selection)?;
+                    self.bind_update(table, from.as_ref(), selection, assignments)?
                 }
             }
             Statement::Delete {
@@ -165,22 +324,97 @@ impl<'a, T: Transaction> Binder<'a, T> {
                 if !table.joins.is_empty() {
                     unimplemented!()
                 } else {
+                    self.check_safe_update("DELETE", selection)?;
                     self.bind_delete(table, selection)?
                 }
             }
-            Statement::Truncate { table_name, .. } => self.bind_truncate(table_name)?,
-            Statement::ShowTables { .. } => self.bind_show_tables()?,
+            Statement::Truncate { table_name, .. } => {
+                self.bind_truncate(table_name, parsed.restart_identity)?
+            }
+            Statement::AlterTable { name, operation } => self.bind_alter_table(name, operation)?,
+            Statement::Comment {
+                object_type,
+                object_name,
+                comment,
+                ..
+            } => self.bind_comment(object_type, object_name, comment)?,
+            Statement::ShowTables { filter, .. } => self.bind_show_tables(filter)?,
+            // sqlparser 0.34 only takes `SHOW INDEX FROM t` as showing a variable
+            Statement::ShowVariable { variable }
+                if variable.len() == 3
+                    && ["index", "indexes"]
+                        .contains(&variable[0].value.to_lowercase().as_str())
+                    && variable[1].value.eq_ignore_ascii_case("from") =>
+            {
+                self.bind_show_index(&ObjectName(vec![variable[2].clone()]))?
+            }
+            Statement::ShowCreate {
+                obj_type: ShowCreateObject::Table,
+                obj_name,
+            } => self.bind_show_create_table(obj_name)?,
             Statement::Copy {
                 source,
                 to,
                 target,
                 options,
                 ..
-            } => self.bind_copy(source.clone(), *to, target.clone(), options)?,
+            } => self.bind_copy(
+                source.clone(),
+                *to,
+                target.clone(),
+                options,
+                &parsed.copy_error_options,
+            )?,
+            Statement::SetVariable {
+                local: false,
+                hivevar: false,
+                variable,
+                value,
+            } => self.bind_set_variable(variable, value)?,
+            Statement::Explain {
+                statement,
+                analyze: false,
+                format: format @ (None | Some(AnalyzeFormat::TEXT) | Some(AnalyzeFormat::JSON)),
+                ..
+            } => LogicalPlan {
+                operator: Operator::Explain(match format {
+                    Some(AnalyzeFormat::JSON) => ExplainFormat::Json,
+                    _ => ExplainFormat::Text,
+                }),
+                childrens: vec![Binder::new(self.context.derive())
+                    .bind(&ParsedStatement::from(statement.as_ref().clone()))?],
+            },
             _ => return Err(BindError::UnsupportedStmt(stmt.to_string())),
         };
+        self.check_sub_queries("the statement")?;
+
         Ok(plan)
     }
+
+    /// The scalar subqueries are only joined with the query for the SELECT list,
+    /// HAVING and ORDER BY, which are all bound after the aggregation.
+    fn check_sub_queries(&self, clause: &str) -> Result<(), BindError> {
+        if !self.context.sub_queries.is_empty() {
+            return Err(BindError::Subquery(format!(
+                "scalar subqueries are not supported in {}",
+                clause
+            )));
+        }
+
+        Ok(())
+    }
+
+    fn check_safe_update(
+        &self,
+        stmt: &'static str,
+        selection: &Option<Expr>,
+    ) -> Result<(), BindError> {
+        if self.context.safe_updates && selection.is_none() {
+            return Err(BindError::UnsafeUpdate(stmt));
+        }
+
+        Ok(())
+    }
 }
 
 /// Convert an object name into lower case
@@ -202,30 +436,106 @@ fn split_name(name: &ObjectName) -> Result<(&str, &str), BindError> {
     })
 }
 
+/// Where in the statement a bind error was raised, e.g. `in WHERE: c9 > 1`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorContext {
+    pub clause: &'static str,
+    pub snippet: String,
+}
+
+impl ErrorContext {
+    const MAX_SNIPPET_LEN: usize = 64;
+
+    pub fn new(clause: &'static str, snippet: &impl fmt::Display) -> Self {
+        let mut snippet = snippet.to_string();
+        if let Some((pos, _)) = snippet.char_indices().nth(Self::MAX_SNIPPET_LEN) {
+            snippet.truncate(pos);
+            snippet.push_str("...");
+        }
+
+        ErrorContext { clause, snippet }
+    }
+}
+
+impl fmt::Display for ErrorContext {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, " (in {}: {})", self.clause, self.snippet)
+    }
+}
+
+fn display_context(context: &Option<ErrorContext>) -> String {
+    context
+        .as_ref()
+        .map(ErrorContext::to_string)
+        .unwrap_or_default()
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum BindError {
     #[error("unsupported statement {0}")]
     UnsupportedStmt(String),
-    #[error("invalid table {0}")]
-    InvalidTable(String),
+    #[error("invalid table {0}{}", display_context(.1))]
+    InvalidTable(String, Option<ErrorContext>),
     #[error("invalid table name: {0:?}")]
     InvalidTableName(Vec<Ident>),
-    #[error("invalid column {0}")]
-    InvalidColumn(String),
-    #[error("ambiguous column {0}")]
-    AmbiguousColumn(String),
+    #[error("invalid column {0}{}", display_context(.1))]
+    InvalidColumn(String, Option<ErrorContext>),
+    #[error("ambiguous column {0}{}", display_context(.1))]
+    AmbiguousColumn(String, Option<ErrorContext>),
+    #[error("argument of {0} must be boolean, not {1}")]
+    NotBoolean(&'static str, LogicalType),
+    #[error("row {row} of VALUES does not fit {column}: {source}")]
+    ValuesTypeMismatch {
+        row: usize,
+        column: String,
+        source: TypeError,
+    },
     #[error("binary operator types mismatch: {0} != {1}")]
     BinaryOpTypeMismatch(String, String),
     #[error("subquery error: {0}")]
     Subquery(String),
+    #[error("parameter error: {0}")]
+    Parameter(String),
+    #[error("set operation error: {0}")]
+    SetOperation(String),
+    #[error("distinct on error: {0}")]
+    DistinctOn(String),
+    #[error("window error: {0}")]
+    Window(String),
+    #[error("variable error: {0}")]
+    Variable(String),
     #[error("agg miss: {0}")]
     AggMiss(String),
+    #[error("invalid function {0}")]
+    InvalidFunction(String),
     #[error("catalog error: {0}")]
     CatalogError(#[from] CatalogError),
     #[error("type error: {0}")]
     TypeError(#[from] TypeError),
     #[error("copy error: {0}")]
     UnsupportedCopySource(String),
+    #[error("{0} without WHERE is rejected in safe update mode, use TRUNCATE to empty the table or WHERE true to change every row")]
+    UnsafeUpdate(&'static str),
+    #[error("{0} has no condition joining its tables, use CROSS JOIN for a cartesian product")]
+    CartesianProduct(String),
+}
+
+impl BindError {
+    /// Attaches the clause being bound, the innermost context is kept when they nest.
+    pub fn with_context(self, clause: &'static str, snippet: &impl fmt::Display) -> Self {
+        match self {
+            BindError::InvalidTable(name, None) => {
+                BindError::InvalidTable(name, Some(ErrorContext::new(clause, snippet)))
+            }
+            BindError::InvalidColumn(name, None) => {
+                BindError::InvalidColumn(name, Some(ErrorContext::new(clause, snippet)))
+            }
+            BindError::AmbiguousColumn(name, None) => {
+                BindError::AmbiguousColumn(name, Some(ErrorContext::new(clause, snippet)))
+            }
+            err => err,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -263,6 +573,7 @@ pub mod test {
                     None,
                 ),
             ],
+            vec![],
             false,
         )?;
 
@@ -282,6 +593,7 @@ pub mod test {
                     None,
                 ),
             ],
+            vec![],
             false,
         )?;
 
@@ -294,7 +606,8 @@ pub mod test {
         let temp_dir = TempDir::new().expect("unable to create temporary working directory");
         let storage = build_test_catalog(temp_dir.path()).await?;
         let transaction = storage.transaction().await?;
-        let binder = Binder::new(BinderContext::new(&transaction));
+        let functions = Default::default();
+        let binder = Binder::new(BinderContext::new(&transaction, &functions));
         let stmt = crate::parser::parse_sql(sql)?;
 
         Ok(binder.bind(&stmt[0])?)
@@ -0,0 +1,190 @@
+use crate::binder::{BindError, Binder};
+use crate::expression::ScalarExpression;
+use crate::storage::Transaction;
+use crate::types::value::{DataValue, ValueRef};
+use crate::types::LogicalType;
+use sqlparser::ast::{Expr, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::fmt::Formatter;
+
+/// A parameter of a prepared statement, `$1` by position or `:name` by name.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Parameter {
+    Positional(usize),
+    Named(String),
+}
+
+impl Parameter {
+    fn parse(placeholder: &str) -> Result<Self, BindError> {
+        if let Some(position) = placeholder.strip_prefix('$') {
+            if let Ok(position) = position.parse::<usize>() {
+                if position > 0 {
+                    return Ok(Parameter::Positional(position));
+                }
+            }
+        } else if let Some(name) = placeholder.strip_prefix(':') {
+            if !name.is_empty() {
+                return Ok(Parameter::Named(name.to_string()));
+            }
+        }
+
+        Err(BindError::Parameter(format!(
+            "unsupported placeholder {}, use $1 or :name",
+            placeholder
+        )))
+    }
+}
+
+impl From<usize> for Parameter {
+    fn from(position: usize) -> Self {
+        Parameter::Positional(position)
+    }
+}
+
+impl From<&str> for Parameter {
+    fn from(name: &str) -> Self {
+        Parameter::Named(name.to_string())
+    }
+}
+
+impl fmt::Display for Parameter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Parameter::Positional(position) => write!(f, "${}", position),
+            Parameter::Named(name) => write!(f, ":{}", name),
+        }
+    }
+}
+
+/// The parameters of the statement being bound, shared by the binders of its subqueries.
+#[derive(Debug)]
+pub(crate) enum Parameters {
+    /// Preparing the statement, the types inferred from where the parameters are used.
+    Infer(BTreeMap<Parameter, Option<LogicalType>>),
+    /// Executing it, the values checked against the inferred types.
+    Values(HashMap<Parameter, ValueRef>),
+}
+
+impl Parameters {
+    /// The inferred types, failing on the first parameter giving no hint of its type,
+    /// e.g. both of `$1 = $2`.
+    pub(crate) fn into_types(self) -> Result<Vec<(Parameter, LogicalType)>, BindError> {
+        match self {
+            Parameters::Infer(types) => types
+                .into_iter()
+                .map(|(parameter, ty)| {
+                    ty.map(|ty| (parameter.clone(), ty)).ok_or_else(|| {
+                        BindError::Parameter(format!(
+                            "could not infer the type of {}, cast it e.g. CAST({} AS INT)",
+                            parameter, parameter
+                        ))
+                    })
+                })
+                .collect(),
+            Parameters::Values(_) => unreachable!("the statement is not being prepared"),
+        }
+    }
+
+    /// Checks the values supplied for each parameter and casts them to its type.
+    pub(crate) fn check_values(
+        types: &[(Parameter, LogicalType)],
+        values: &[(Parameter, DataValue)],
+    ) -> Result<Self, BindError> {
+        let mut checked = HashMap::with_capacity(types.len());
+
+        for (parameter, value) in values {
+            let ty = types
+                .iter()
+                .find_map(|(p, ty)| (p == parameter).then_some(ty))
+                .ok_or_else(|| {
+                    BindError::Parameter(format!("the statement has no parameter {}", parameter))
+                })?;
+            let value_ty = value.logical_type();
+            let is_compatible = LogicalType::can_implicit_cast(&value_ty, ty)
+                || matches!(
+                    (&value_ty, ty),
+                    (LogicalType::Varchar(_), LogicalType::Varchar(_))
+                        | (LogicalType::Decimal(_, _), LogicalType::Decimal(_, _))
+                );
+            let mismatch =
+                || BindError::Parameter(format!("{} expects {}, got {}", parameter, ty, value_ty));
+
+            if !is_compatible {
+                return Err(mismatch());
+            }
+            let value = value.clone().cast(ty).map_err(|_| mismatch())?;
+            value.check_len(ty).map_err(|_| mismatch())?;
+
+            if checked
+                .insert(parameter.clone(), ValueRef::new(value))
+                .is_some()
+            {
+                return Err(BindError::Parameter(format!(
+                    "{} is given more than once",
+                    parameter
+                )));
+            }
+        }
+        if let Some((parameter, _)) = types.iter().find(|(p, _)| !checked.contains_key(p)) {
+            return Err(BindError::Parameter(format!("no value for {}", parameter)));
+        }
+
+        Ok(Parameters::Values(checked))
+    }
+}
+
+impl<'a, T: Transaction> Binder<'a, T> {
+    /// The value of the parameter when executing, a null placeholder while preparing.
+    pub(crate) fn bind_placeholder(
+        &mut self,
+        placeholder: &str,
+    ) -> Result<ScalarExpression, BindError> {
+        let parameter = Parameter::parse(placeholder)?;
+        let parameters = self.context.parameters.as_ref().ok_or_else(|| {
+            BindError::Parameter(format!(
+                "{} can only be used in a prepared statement",
+                parameter
+            ))
+        })?;
+
+        let value = match &mut *parameters.borrow_mut() {
+            Parameters::Infer(types) => {
+                types.entry(parameter).or_default();
+
+                ValueRef::new(DataValue::Null)
+            }
+            Parameters::Values(values) => values
+                .get(&parameter)
+                .cloned()
+                .ok_or_else(|| BindError::Parameter(format!("no value for {}", parameter)))?,
+        };
+
+        Ok(ScalarExpression::Constant(value))
+    }
+
+    /// Takes `ty` as the type of `expr` if it is a parameter whose type is not known yet.
+    pub(crate) fn infer_parameter(&self, expr: &Expr, ty: &LogicalType) {
+        let expr = match expr {
+            Expr::Nested(expr) => expr.as_ref(),
+            expr => expr,
+        };
+        let Expr::Value(Value::Placeholder(placeholder)) = expr else {
+            return;
+        };
+        if matches!(ty, LogicalType::SqlNull | LogicalType::Invalid) {
+            return;
+        }
+        if let (Some(parameters), Ok(parameter)) =
+            (&self.context.parameters, Parameter::parse(placeholder))
+        {
+            if let Parameters::Infer(types) = &mut *parameters.borrow_mut() {
+                let inferred = types.entry(parameter).or_default();
+
+                if inferred.is_none() {
+                    *inferred = Some(*ty);
+                }
+            }
+        }
+    }
+}
@@ -1,4 +1,6 @@
 use std::borrow::Borrow;
+use std::iter;
+use std::mem;
 use std::sync::Arc;
 
 use crate::{
@@ -17,12 +19,14 @@ use super::Binder;
 
 use crate::binder::BindError;
 use crate::catalog::{
-    ColumnCatalog, TableCatalog, TableName, DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME,
+    ColumnCatalog, ColumnRef, TableCatalog, TableName, DEFAULT_DATABASE_NAME, DEFAULT_SCHEMA_NAME,
 };
 use crate::execution::executor::dql::join::joins_nullable;
-use crate::expression::BinaryOperator;
+use crate::expression::{BinaryOperator, UnaryOperator};
+use crate::planner::operator::distinct_on::DistinctOnOperator;
 use crate::planner::operator::join::JoinCondition;
 use crate::planner::operator::sort::{SortField, SortOperator};
+use crate::planner::operator::window::WindowOperator;
 use crate::planner::LogicalPlan;
 use crate::storage::Transaction;
 use crate::types::errors::TypeError;
@@ -30,19 +34,50 @@ use crate::types::LogicalType;
 use itertools::Itertools;
 use sqlparser::ast;
 use sqlparser::ast::{
-    Distinct, Expr, Ident, Join, JoinConstraint, JoinOperator, Offset, OrderByExpr, Query, Select,
-    SelectItem, SetExpr, TableAlias, TableFactor, TableWithJoins,
+    Distinct, Expr, Ident, Join, JoinConstraint, JoinOperator, LockClause, LockType, NonBlock,
+    Offset, OrderByExpr, Query, Select, SelectItem, SetExpr, TableAlias, TableFactor,
+    TableWithJoins,
 };
 
 impl<'a, T: Transaction> Binder<'a, T> {
     pub(crate) fn bind_query(&mut self, query: &Query) -> Result<LogicalPlan, BindError> {
-        if let Some(_with) = &query.with {
-            // TODO support with clause.
+        if let Some(with) = &query.with {
+            if with.recursive {
+                return Err(BindError::UnsupportedStmt(
+                    "recursive cte is not supported".to_string(),
+                ));
+            }
+            for cte in with.cte_tables.iter() {
+                if !cte.alias.columns.is_empty() {
+                    return Err(BindError::UnsupportedStmt(format!(
+                        "column aliases of cte {}",
+                        cte.alias
+                    )));
+                }
+                self.context.add_cte(
+                    cte.alias.name.value.to_lowercase(),
+                    cte.query.as_ref().clone(),
+                )?;
+            }
         }
 
         let mut plan = match query.body.borrow() {
             SetExpr::Select(select) => self.bind_select(select, &query.order_by),
             SetExpr::Query(query) => self.bind_query(query),
+            SetExpr::Values(values) => self.bind_values_query(values),
+            SetExpr::SetOperation {
+                op,
+                set_quantifier,
+                left,
+                right,
+            } => {
+                if !query.order_by.is_empty() {
+                    return Err(BindError::UnsupportedStmt(
+                        "ORDER BY of set operations".to_string(),
+                    ));
+                }
+                self.bind_set_operation(op, set_quantifier, left, right)
+            }
             _ => unimplemented!(),
         }?;
 
@@ -52,52 +87,108 @@ impl<'a, T: Transaction> Binder<'a, T> {
         if limit.is_some() || offset.is_some() {
             plan = self.bind_limit(plan, limit, offset)?;
         }
+        if !query.locks.is_empty() {
+            Self::bind_locks(&mut plan, &query.locks)?;
+        }
 
         Ok(plan)
     }
 
-    fn bind_select(
+    /// `FOR UPDATE` locks the tuples read by the scans of the query until the transaction ends.
+    /// The locks never wait for each other, so `NOWAIT` is always met.
+    fn bind_locks(plan: &mut LogicalPlan, locks: &[LockClause]) -> Result<(), BindError> {
+        fn lock_scans(plan: &mut LogicalPlan) {
+            if let Operator::Scan(op) = &mut plan.operator {
+                op.lock = true;
+            }
+            plan.childrens.iter_mut().for_each(lock_scans);
+        }
+
+        for lock in locks {
+            if lock.lock_type != LockType::Update
+                || lock.of.is_some()
+                || lock.nonblock == Some(NonBlock::SkipLocked)
+            {
+                return Err(BindError::UnsupportedStmt(format!(
+                    "lock clause {} is not supported",
+                    lock
+                )));
+            }
+        }
+        lock_scans(plan);
+
+        Ok(())
+    }
+
+    pub(crate) fn bind_select(
         &mut self,
         select: &Select,
         orderby: &[OrderByExpr],
     ) -> Result<LogicalPlan, BindError> {
         let mut plan = self.bind_table_ref(&select.from)?;
+        self.check_sub_queries("FROM")?;
 
         // Resolve scalar function call.
         // TODO support SRF(Set-Returning Function).
 
+        self.bind_named_windows(&select.named_window)?;
+
         let mut select_list = self.normalize_select_item(&select.projection)?;
+        let mut sub_queries = mem::take(&mut self.context.sub_queries);
 
         self.extract_select_join(&mut select_list);
 
         if let Some(predicate) = &select.selection {
             plan = self.bind_where(plan, predicate)?;
+            self.check_sub_queries("WHERE")?;
         }
 
         self.extract_select_aggregate(&mut select_list)?;
 
-        if !select.group_by.is_empty() {
-            self.extract_group_by_aggregate(&mut select_list, &select.group_by)?;
+        if !select.group_by.is_empty() || !self.context.agg_calls.is_empty() {
+            self.extract_group_by_aggregate(&mut select_list, &select.group_by, &sub_queries)?;
+            self.check_sub_queries("GROUP BY")?;
         }
 
         let mut having_orderby = (None, None);
 
         if select.having.is_some() || !orderby.is_empty() {
-            having_orderby = self.extract_having_orderby_aggregate(&select.having, orderby)?;
+            having_orderby =
+                self.extract_having_orderby_aggregate(&select_list, &select.having, orderby)?;
+            sub_queries.append(&mut self.context.sub_queries);
         }
+        let distinct_on = match &select.distinct {
+            Some(Distinct::On(exprs)) => {
+                Some(self.bind_distinct_on(exprs, having_orderby.1.as_deref())?)
+            }
+            _ => None,
+        };
 
         if !self.context.agg_calls.is_empty() || !self.context.group_by_exprs.is_empty() {
             plan = self.bind_aggregate(
                 plan,
                 self.context.agg_calls.clone(),
                 self.context.group_by_exprs.clone(),
+                self.context.grouping_sets.clone(),
             );
         }
 
+        for sub_query in sub_queries {
+            let on = JoinCondition::On {
+                on: vec![],
+                filter: None,
+            };
+            plan = LJoinOperator::build(sub_query, plan, on, JoinType::Single);
+        }
+
         if let Some(having) = having_orderby.0 {
             plan = self.bind_having(plan, having)?;
         }
 
+        if !self.context.window_calls.is_empty() {
+            plan = WindowOperator::build(mem::take(&mut self.context.window_calls), plan);
+        }
+
         if let Some(Distinct::Distinct) = select.distinct {
             plan = self.bind_distinct(plan, select_list.clone());
         }
@@ -106,6 +197,10 @@ impl<'a, T: Transaction> Binder<'a, T> {
             plan = self.bind_sort(plan, orderby);
         }
 
+        if let Some(on_exprs) = distinct_on {
+            plan = DistinctOnOperator::build(on_exprs, plan);
+        }
+
         plan = self.bind_project(plan, select_list);
 
         Ok(plan)
@@ -162,36 +257,126 @@ impl<'a, T: Transaction> Binder<'a, T> {
                     _ => return Err(BindError::InvalidTableName(obj_name)),
                 };
 
-                let (table, plan) =
-                    self._bind_single_table_ref(joint_type, table, Self::trans_alias(alias))?;
-                (Some(table), plan)
-            }
-            TableFactor::Derived {
-                subquery, alias, ..
-            } => {
-                let plan = self.bind_query(subquery)?;
-                let mut tables = plan.referenced_table();
-
-                if let Some(alias) = Self::trans_alias(alias) {
-                    let alias = Arc::new(alias.clone());
-
-                    if tables.len() > 1 {
-                        todo!("Implement virtual tables for multiple table aliases");
-                    }
-                    self.context
-                        .add_table_alias(alias.to_string(), tables.remove(0))?;
-
-                    (Some(alias), plan)
+                // the cte is taken out while binding, so that it can not refer to itself
+                let cte = match obj_name.as_slice() {
+                    [_] => self.context.ctes.remove(table),
+                    _ => None,
+                };
+                if let Some(cte) = cte {
+                    let column_aliases: &[Ident] =
+                        alias.as_ref().map_or(&[], |alias| alias.columns.as_slice());
+                    let alias = Self::trans_alias(alias).unwrap_or(&obj_name[0].value);
+                    let result =
+                        self.bind_derived_table(&cte, Some(alias), column_aliases, joint_type);
+                    self.context.ctes.insert(table.to_string(), cte);
+
+                    result?
                 } else {
-                    ((tables.len() > 1).then(|| tables.pop()).flatten(), plan)
+                    let (table, plan) =
+                        self._bind_single_table_ref(joint_type, table, Self::trans_alias(alias))?;
+                    (Some(table), plan)
                 }
             }
+            TableFactor::Derived {
+                subquery, alias, ..
+            } => self.bind_derived_table(
+                subquery,
+                Self::trans_alias(alias),
+                alias.as_ref().map_or(&[], |alias| alias.columns.as_slice()),
+                joint_type,
+            )?,
             _ => unimplemented!(),
         };
 
         Ok(plan_with_name)
     }
 
+    /// `(subquery) AS alias (c1, c2, ..)`, the column aliases rename the first columns of the
+    /// subquery, the others keep their names.
+    fn bind_derived_table(
+        &mut self,
+        subquery: &Query,
+        alias: Option<&String>,
+        column_aliases: &[Ident],
+        join_type: Option<JoinType>,
+    ) -> Result<(Option<TableName>, LogicalPlan), BindError> {
+        let alias = alias
+            .map(|alias| Arc::new(alias.to_lowercase()))
+            .ok_or_else(|| {
+                BindError::InvalidTable("subquery in FROM must have an alias".to_string(), None)
+            })?;
+        let mut plan = Binder::new(self.context.derive()).bind_query(subquery)?;
+
+        if !column_aliases.is_empty() {
+            plan = Self::rename_plan_columns(plan, &alias, column_aliases)?;
+        }
+        let table_catalog = TableCatalog::new_derived(alias.clone(), plan.schema().to_vec())?;
+
+        self.context
+            .add_bind_table(alias.clone(), table_catalog, join_type)?;
+
+        Ok((Some(alias), plan))
+    }
+
+    fn rename_plan_columns(
+        mut plan: LogicalPlan,
+        alias: &TableName,
+        column_aliases: &[Ident],
+    ) -> Result<LogicalPlan, BindError> {
+        let columns = plan.schema().to_vec();
+
+        if column_aliases.len() > columns.len() {
+            return Err(BindError::InvalidTable(
+                format!(
+                    "{} has {} columns available but {} columns specified",
+                    alias,
+                    columns.len(),
+                    column_aliases.len()
+                ),
+                None,
+            ));
+        }
+        let names = column_aliases
+            .iter()
+            .map(|ident| Some(ident.value.to_lowercase()))
+            .chain(iter::repeat(None));
+
+        // the rows of `VALUES` are renamed in place, another query is projected under the aliases
+        if let Operator::Values(op) = &mut plan.operator {
+            op.columns = columns
+                .into_iter()
+                .zip(names)
+                .map(|(column, name)| match name {
+                    Some(name) => {
+                        let mut column = ColumnCatalog::clone(&column);
+                        column.summary.name = name;
+
+                        Arc::new(column)
+                    }
+                    None => column,
+                })
+                .collect();
+
+            return Ok(plan);
+        }
+        let exprs = columns
+            .into_iter()
+            .zip(names)
+            .map(|(column, name)| match name {
+                Some(alias) => ScalarExpression::Alias {
+                    expr: Box::new(ScalarExpression::ColumnRef(column)),
+                    alias,
+                },
+                None => ScalarExpression::ColumnRef(column),
+            })
+            .collect();
+
+        Ok(LogicalPlan {
+            operator: Operator::Project(ProjectOperator { exprs }),
+            childrens: vec![plan],
+        })
+    }
+
     pub(crate) fn trans_alias(alias: &Option<TableAlias>) -> Option<&String> {
         alias.as_ref().map(|alias| &alias.name.value)
     }
@@ -204,24 +389,21 @@ impl<'a, T: Transaction> Binder<'a, T> {
     ) -> Result<(Arc<String>, LogicalPlan), BindError> {
         let table_name = Arc::new(table.to_string());
 
+        let bind_name = alias
+            .map(|alias| Arc::new(alias.to_lowercase()))
+            .unwrap_or_else(|| table_name.clone());
+
         let table_catalog = self
             .context
+            .transaction
             .table(table_name.clone())
-            .cloned()
-            .ok_or_else(|| BindError::InvalidTable(format!("bind table {}", table)))?;
+            .ok_or_else(|| BindError::InvalidTable(format!("bind table {}", table), None))?
+            .qualified(bind_name.clone());
 
         self.context
-            .add_bind_table(table_name.clone(), table_catalog.clone(), join_type)?;
+            .add_bind_table(bind_name.clone(), table_catalog.clone(), join_type)?;
 
-        if let Some(alias) = alias {
-            self.context
-                .add_table_alias(alias.to_string(), table_name.clone())?;
-        }
-
-        Ok((
-            table_name.clone(),
-            ScanOperator::build(table_name, &table_catalog),
-        ))
+        Ok((bind_name, ScanOperator::build(table_name, &table_catalog)))
     }
 
     /// Normalize select item.
@@ -230,7 +412,7 @@ impl<'a, T: Transaction> Binder<'a, T> {
     /// - Qualified name with wildcard, e.g. `SELECT t.* FROM t,t1`
     /// - Scalar expression or aggregate expression, e.g. `SELECT COUNT(*) + 1 AS count FROM t`
     ///  
-    fn normalize_select_item(
+    pub(crate) fn normalize_select_item(
         &mut self,
         items: &[SelectItem],
     ) -> Result<Vec<ScalarExpression>, BindError> {
@@ -238,9 +420,14 @@ impl<'a, T: Transaction> Binder<'a, T> {
 
         for item in items.iter().enumerate() {
             match item.1 {
-                SelectItem::UnnamedExpr(expr) => select_items.push(self.bind_expr(expr)?),
+                SelectItem::UnnamedExpr(expr) => select_items.push(
+                    self.bind_expr(expr)
+                        .map_err(|err| err.with_context("SELECT", expr))?,
+                ),
                 SelectItem::ExprWithAlias { expr, alias } => {
-                    let expr = self.bind_expr(expr)?;
+                    let expr = self
+                        .bind_expr(expr)
+                        .map_err(|err| err.with_context("SELECT", expr))?;
                     let alias_name = alias.to_string();
 
                     self.context.add_alias(alias_name.clone(), expr.clone())?;
@@ -267,8 +454,8 @@ impl<'a, T: Transaction> Binder<'a, T> {
             let table = self
                 .context
                 .table(table_name.clone())
-                .ok_or_else(|| BindError::InvalidTable(table_name.to_string()))?;
-            for col in table.all_columns() {
+                .ok_or_else(|| BindError::InvalidTable(table_name.to_string(), None))?;
+            for col in table.visible_columns() {
                 exprs.push(ScalarExpression::ColumnRef(col));
             }
         }
@@ -302,30 +489,244 @@ impl<'a, T: Transaction> Binder<'a, T> {
             .context
             .table(left_table.clone())
             .cloned()
-            .ok_or_else(|| BindError::InvalidTable(format!("Left: {} not found", left_table)))?;
+            .ok_or_else(|| {
+                BindError::InvalidTable(format!("Left: {} not found", left_table), None)
+            })?;
         let right_table = self
             .context
             .table(right_table.clone())
             .cloned()
-            .ok_or_else(|| BindError::InvalidTable(format!("Right: {} not found", right_table)))?;
+            .ok_or_else(|| {
+                BindError::InvalidTable(format!("Right: {} not found", right_table), None)
+            })?;
 
         let on = match joint_condition {
+            // e.g. `t1 JOIN t2`
+            Some(JoinConstraint::None) | None => JoinCondition::None,
             Some(constraint) => self.bind_join_constraint(&left_table, &right_table, constraint)?,
-            None => JoinCondition::None,
         };
+        // a join forgetting its condition multiplies its tables, which is only done on purpose
+        // with `CROSS JOIN`
+        if join_type != JoinType::Cross
+            && !self.context.allow_cartesian_products
+            && !Self::is_joined(&on, &right_table)
+        {
+            return Err(BindError::CartesianProduct(
+                join.to_string().trim().to_string(),
+            ));
+        }
 
         Ok(LJoinOperator::build(left, right, on, join_type))
     }
 
+    /// Whether the condition of a join relates the right table to the tables joined before,
+    /// by a key or by a filter referring to both.
+    fn is_joined(on: &JoinCondition, right_table: &TableCatalog) -> bool {
+        match on {
+            JoinCondition::On { on, .. } if !on.is_empty() => true,
+            JoinCondition::On {
+                filter: Some(filter),
+                ..
+            } => {
+                let (right_columns, other_columns): (Vec<_>, Vec<_>) = filter
+                    .referenced_columns(true)
+                    .into_iter()
+                    .partition(|col| right_table.owns_column(col));
+
+                !right_columns.is_empty() && !other_columns.is_empty()
+            }
+            _ => false,
+        }
+    }
+
+    /// The `[NOT] EXISTS` subqueries among the conjunctions of the predicate are bound as
+    /// semi joins applied after the filter of the others.
     pub(crate) fn bind_where(
         &mut self,
-        children: LogicalPlan,
+        mut children: LogicalPlan,
         predicate: &Expr,
     ) -> Result<LogicalPlan, BindError> {
-        Ok(FilterOperator::build(
-            self.bind_expr(predicate)?,
+        let mut conjunctions = Vec::new();
+        split_conjunctions(predicate, &mut conjunctions);
+
+        let (subqueries, others): (Vec<_>, Vec<_>) = conjunctions.into_iter().partition(|expr| {
+            matches!(expr, Expr::Exists { .. }) || quantified_subquery(expr).is_some()
+        });
+        let predicates = if subqueries.is_empty() {
+            vec![predicate]
+        } else {
+            others
+        };
+        let mut bound_predicates = Vec::with_capacity(predicates.len());
+
+        for predicate in predicates {
+            let predicate = self
+                .bind_expr(predicate)
+                .map_err(|err| err.with_context("WHERE", predicate))?;
+
+            // NULL is filtered out like FALSE
+            match predicate.return_type() {
+                LogicalType::Boolean | LogicalType::SqlNull => (),
+                ty => return Err(BindError::NotBoolean("WHERE", ty)),
+            }
+            bound_predicates.push(predicate);
+        }
+        if let Some(predicate) = and_all(bound_predicates) {
+            children = FilterOperator::build(predicate, children, false);
+        }
+        for expr in subqueries {
+            if let Expr::Exists { subquery, negated } = expr {
+                children = self.bind_exists(children, subquery, *negated)?;
+            } else if let Some((left, op, subquery, is_all)) = quantified_subquery(expr) {
+                children = self
+                    .bind_quantified(children, left, op, subquery, is_all)
+                    .map_err(|err| err.with_context("WHERE", expr))?;
+            }
+        }
+
+        Ok(children)
+    }
+
+    /// Binds `[NOT] EXISTS (subquery)` as a semi join of the subquery, or an anti join.
+    ///
+    /// The subquery may refer to the columns of the query in its `WHERE` alone, where the
+    /// equalities of such a column and a column of the subquery become the keys of the join
+    /// and the other conjunctions referring to them its filter.
+    fn bind_exists(
+        &mut self,
+        children: LogicalPlan,
+        subquery: &Query,
+        negated: bool,
+    ) -> Result<LogicalPlan, BindError> {
+        let mut binder = Binder::new(self.context.derive());
+        binder.context.outer_bind_table = self.context.bind_table.clone();
+
+        let mut plan = binder.bind_query(subquery)?;
+        let outer_columns = binder.context.outer_columns;
+        // only whether the subquery yields a tuple matters
+        if let Operator::Project(_) = plan.operator {
+            plan = plan.childrens.remove(0);
+        }
+        let mut on_keys = vec![];
+        let mut filter = vec![];
+
+        if !outer_columns.is_empty() {
+            let predicate = match &plan.operator {
+                Operator::Filter(op)
+                    if !op.having && !refers_to_columns(&plan.childrens[0], &outer_columns) =>
+                {
+                    op.predicate.clone()
+                }
+                _ => {
+                    return Err(BindError::Subquery(
+                        "the columns of the query are only supported in the WHERE of EXISTS"
+                            .to_string(),
+                    ))
+                }
+            };
+            let is_outer = |col: &ColumnRef| outer_columns.contains(col);
+            let mut conjunctions = Vec::new();
+            let mut inner_predicates = vec![];
+            split_scalar_conjunctions(predicate, &mut conjunctions);
+
+            for expr in conjunctions {
+                if !expr.referenced_columns(true).iter().any(is_outer) {
+                    inner_predicates.push(expr);
+                    continue;
+                }
+                let on_key = match &expr {
+                    ScalarExpression::Binary {
+                        op: BinaryOperator::Eq,
+                        left_expr,
+                        right_expr,
+                        ..
+                    } => match (left_expr.as_ref(), right_expr.as_ref()) {
+                        (ScalarExpression::ColumnRef(l), ScalarExpression::ColumnRef(r))
+                            if is_outer(l) != is_outer(r) =>
+                        {
+                            let (left, right) = (left_expr.as_ref(), right_expr.as_ref());
+
+                            Some(if is_outer(l) {
+                                (left.clone(), right.clone())
+                            } else {
+                                (right.clone(), left.clone())
+                            })
+                        }
+                        _ => None,
+                    },
+                    _ => None,
+                };
+
+                match on_key {
+                    Some(on_key) => on_keys.push(on_key),
+                    None => filter.push(expr),
+                }
+            }
+            plan = plan.childrens.remove(0);
+
+            if let Some(predicate) = and_all(inner_predicates) {
+                plan = FilterOperator::build(predicate, plan, false);
+            }
+        }
+        let join_type = if negated {
+            JoinType::Anti
+        } else {
+            JoinType::Semi
+        };
+        let on = JoinCondition::On {
+            on: on_keys,
+            filter: and_all(filter),
+        };
+
+        Ok(LJoinOperator::build(children, plan, on, join_type))
+    }
+
+    /// Binds `expr op ANY (subquery)` as a semi join of the subquery on the comparison, with
+    /// `expr` as the key of the join for `=`, and `expr op ALL (subquery)` as an anti join on
+    /// the comparison not being true. So `ANY` is false, and `ALL` true, when the subquery
+    /// yields no tuple, and a null compared to a tuple is neither.
+    fn bind_quantified(
+        &mut self,
+        children: LogicalPlan,
+        left: &Expr,
+        op: &ast::BinaryOperator,
+        subquery: &Query,
+        is_all: bool,
+    ) -> Result<LogicalPlan, BindError> {
+        let (comparison, plan) = self.bind_quantified_comparison(left, op, subquery)?;
+
+        let (on, filter, join_type) = match comparison {
+            ScalarExpression::Binary {
+                op: BinaryOperator::Eq,
+                left_expr,
+                right_expr,
+                ..
+            } if !is_all => (vec![(*left_expr, *right_expr)], None, JoinType::Semi),
+            comparison if !is_all => (vec![], Some(comparison), JoinType::Semi),
+            comparison => {
+                let is_not_true = ScalarExpression::Binary {
+                    op: BinaryOperator::Or,
+                    left_expr: Box::new(ScalarExpression::Unary {
+                        op: UnaryOperator::Not,
+                        expr: Box::new(comparison.clone()),
+                        ty: LogicalType::Boolean,
+                    }),
+                    right_expr: Box::new(ScalarExpression::IsNull {
+                        negated: false,
+                        expr: Box::new(comparison),
+                    }),
+                    ty: LogicalType::Boolean,
+                };
+
+                (vec![], Some(is_not_true), JoinType::Anti)
+            }
+        };
+
+        Ok(LJoinOperator::build(
             children,
-            false,
+            plan,
+            JoinCondition::On { on, filter },
+            join_type,
         ))
     }
 
@@ -378,6 +779,7 @@ impl<'a, T: Transaction> Binder<'a, T> {
                 _ => {
                     return Err(BindError::InvalidColumn(
                         "invalid limit expression.".to_owned(),
+                        None,
                     ))
                 }
             }
@@ -394,6 +796,7 @@ impl<'a, T: Transaction> Binder<'a, T> {
                 _ => {
                     return Err(BindError::InvalidColumn(
                         "invalid limit expression.".to_owned(),
+                        None,
                     ))
                 }
             }
@@ -432,7 +835,7 @@ impl<'a, T: Transaction> Binder<'a, T> {
             if let ScalarExpression::ColumnRef(col) = column {
                 let _ = table_force_nullable
                     .iter()
-                    .find(|(table, _)| table.contains_column(col.name()))
+                    .find(|(table, _)| table.owns_column(col))
                     .map(|(_, nullable)| {
                         let mut new_col = ColumnCatalog::clone(col);
                         new_col.nullable = *nullable;
@@ -456,7 +859,8 @@ impl<'a, T: Transaction> Binder<'a, T> {
                 // expression that didn't match equi-join pattern
                 let mut filter = vec![];
 
-                self.extract_join_keys(expr, &mut on_keys, &mut filter, left_table, right_table)?;
+                self.extract_join_keys(expr, &mut on_keys, &mut filter, left_table, right_table)
+                    .map_err(|err| err.with_context("JOIN ON", expr))?;
 
                 // combine multiple filter exprs into one BinaryExpr
                 let join_filter = filter
@@ -506,13 +910,9 @@ impl<'a, T: Transaction> Binder<'a, T> {
                         // example: foo = bar
                         (ScalarExpression::ColumnRef(l), ScalarExpression::ColumnRef(r)) => {
                             // reorder left and right joins keys to pattern: (left, right)
-                            if left_schema.contains_column(l.name())
-                                && right_schema.contains_column(r.name())
-                            {
+                            if left_schema.owns_column(l) && right_schema.owns_column(r) {
                                 accum.push((left, right));
-                            } else if left_schema.contains_column(r.name())
-                                && right_schema.contains_column(l.name())
-                            {
+                            } else if left_schema.owns_column(r) && right_schema.owns_column(l) {
                                 accum.push((right, left));
                             } else {
                                 accum_filter.push(self.bind_expr(expr)?);
@@ -557,10 +957,84 @@ impl<'a, T: Transaction> Binder<'a, T> {
     }
 }
 
+fn split_conjunctions<'a>(expr: &'a Expr, exprs: &mut Vec<&'a Expr>) {
+    match expr {
+        Expr::BinaryOp {
+            left,
+            op: ast::BinaryOperator::And,
+            right,
+        } => {
+            split_conjunctions(left, exprs);
+            split_conjunctions(right, exprs);
+        }
+        Expr::Nested(expr) => split_conjunctions(expr, exprs),
+        expr => exprs.push(expr),
+    }
+}
+
+/// The parts of `expr op ANY (subquery)`, or of `expr op ALL (subquery)` with `true`.
+fn quantified_subquery(expr: &Expr) -> Option<(&Expr, &ast::BinaryOperator, &Query, bool)> {
+    match expr {
+        Expr::BinaryOp { left, op, right } => match right.as_ref() {
+            Expr::AnyOp(quantified) | Expr::AllOp(quantified) => match quantified.as_ref() {
+                Expr::Subquery(subquery) => {
+                    Some((left, op, subquery, matches!(right.as_ref(), Expr::AllOp(_))))
+                }
+                _ => None,
+            },
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn split_scalar_conjunctions(expr: ScalarExpression, exprs: &mut Vec<ScalarExpression>) {
+    match expr {
+        ScalarExpression::Binary {
+            op: BinaryOperator::And,
+            left_expr,
+            right_expr,
+            ..
+        } => {
+            split_scalar_conjunctions(*left_expr, exprs);
+            split_scalar_conjunctions(*right_expr, exprs);
+        }
+        expr => exprs.push(expr),
+    }
+}
+
+fn and_all(exprs: Vec<ScalarExpression>) -> Option<ScalarExpression> {
+    exprs
+        .into_iter()
+        .reduce(|acc, expr| ScalarExpression::Binary {
+            op: BinaryOperator::And,
+            left_expr: Box::new(acc),
+            right_expr: Box::new(expr),
+            ty: LogicalType::Boolean,
+        })
+}
+
+fn refers_to_columns(plan: &LogicalPlan, columns: &[ColumnRef]) -> bool {
+    plan.operator
+        .referenced_columns(true)
+        .iter()
+        .any(|col| columns.contains(col))
+        || plan
+            .childrens
+            .iter()
+            .any(|child| refers_to_columns(child, columns))
+}
+
 #[cfg(test)]
 mod tests {
     use crate::binder::test::select_sql_run;
     use crate::execution::ExecutorError;
+    use crate::expression::window::{WindowFrame, WindowFrameBound, WindowFrameUnits};
+    use crate::expression::ScalarExpression;
+    use crate::planner::operator::join::{JoinCondition, JoinType};
+    use crate::planner::operator::Operator;
+    use crate::types::value::{DataValue, ValueRef};
+    use std::sync::Arc;
 
     #[tokio::test]
     async fn test_select_bind() -> Result<(), ExecutorError> {
@@ -590,4 +1064,267 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_select_bind_cte() -> Result<(), ExecutorError> {
+        let plan = select_sql_run(
+            "with x as (select c1, c2 from t1), y as (select c1 from x where c2 > 1) select c1 from y",
+        )
+        .await?;
+        println!("cte:\n {:#?}", plan);
+        assert_eq!(plan.referenced_table(), vec![Arc::new("t1".to_string())]);
+
+        assert!(
+            select_sql_run("with t1 as (select c1 from t1) select c1 from t1")
+                .await
+                .is_ok()
+        );
+        assert!(
+            select_sql_run("with x as (select c1 from x) select c1 from x")
+                .await
+                .is_err()
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_select_bind_window() -> Result<(), ExecutorError> {
+        let plan = select_sql_run(
+            "select c1, sum(c2) over w from t1 window w as (order by c1 rows 2 preceding) \
+            order by sum(c2) over (order by c1 rows between 2 preceding and current row)",
+        )
+        .await?;
+        let window_calls = match &plan.childrens[0].childrens[0].operator {
+            Operator::Window(op) => &op.window_calls,
+            operator => unreachable!("Should be a window operator: {}", operator),
+        };
+        // the named window is the same as the one of the ORDER BY
+        assert_eq!(window_calls.len(), 1);
+        match &window_calls[0] {
+            ScalarExpression::WindowCall { spec, .. } => assert_eq!(
+                spec.frame,
+                WindowFrame::new(
+                    WindowFrameUnits::Rows,
+                    WindowFrameBound::Preceding(ValueRef::new(DataValue::UInt64(Some(2)))),
+                    WindowFrameBound::CurrentRow,
+                )
+            ),
+            expr => unreachable!("Should be a window call: {}", expr),
+        }
+
+        for sql in [
+            "select sum(c2) over w from t1",
+            "select c1 over () from t1",
+            "select grouping(c1) over () from t1 group by c1",
+            "select sum(c2) over (order by c1 range 1 preceding), c3 from t1 group by c3",
+            "select sum(c2) over (order by c1, c2 range 1 preceding) from t1",
+            "select sum(c2) over (rows between current row and unbounded preceding) from t1",
+            "select c1 from t1 group by c1 having sum(c1) over () > 1",
+        ] {
+            assert!(select_sql_run(sql).await.is_err(), "{}", sql);
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_select_bind_group_by() -> Result<(), ExecutorError> {
+        let err = select_sql_run("select c1, c2 from t1 group by c2")
+            .await
+            .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("column t1.c1 must appear in the GROUP BY"),
+            "{}",
+            err
+        );
+        assert!(select_sql_run("select c2, count(*) from t1").await.is_err());
+
+        // `c1` is the primary key of `t1`, which determines `c2`
+        let plan = select_sql_run("select c1, c2, count(*) from t1 group by c1").await?;
+        match &plan.childrens[0].operator {
+            Operator::Aggregate(op) => assert_eq!(op.groupby_exprs.len(), 2),
+            operator => unreachable!("Should be an aggregate operator: {}", operator),
+        }
+        assert!(
+            select_sql_run("select t2.c4 from t1 join t2 on c1 = c3 group by c1")
+                .await
+                .is_err()
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_select_bind_derived_table() -> Result<(), ExecutorError> {
+        let plan = select_sql_run("select x.s from (select c1 + c2 as s from t1) as x where s > 1")
+            .await?;
+        println!(
+            "derived_table:
+ {:#?}",
+            plan
+        );
+        assert_eq!(plan.referenced_table(), vec![Arc::new("t1".to_string())]);
+
+        assert!(select_sql_run("select c1 from (select c1 from t1)")
+            .await
+            .is_err());
+        assert!(select_sql_run("select x.c2 from (select c1 from t1) as x")
+            .await
+            .is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_select_bind_scalar_subquery() -> Result<(), ExecutorError> {
+        let plan = select_sql_run("select c1, (select count(*) from t2) from t1").await?;
+        println!("scalar_subquery:\n {:#?}", plan);
+
+        let Operator::Join(join_op) = &plan.childrens[0].operator else {
+            unreachable!()
+        };
+        assert_eq!(join_op.join_type, JoinType::Single);
+        assert_eq!(
+            plan.childrens[0].childrens[0].referenced_table(),
+            vec![Arc::new("t2".to_string())]
+        );
+        assert_eq!(
+            plan.output_schema()
+                .unwrap()
+                .iter()
+                .map(|col| col.name().to_string())
+                .collect::<Vec<_>>(),
+            vec!["c1", "(SELECT count(*) FROM t2)"]
+        );
+
+        assert!(select_sql_run("select (select c3, c4 from t2) from t1")
+            .await
+            .is_err());
+        assert!(select_sql_run("select c1 from t1 where c1 = (select 1)")
+            .await
+            .is_err());
+        // correlated subqueries are not supported
+        assert!(
+            select_sql_run("select (select c3 from t2 where c3 = c1) from t1")
+                .await
+                .is_err()
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_select_bind_quantified_subquery() -> Result<(), ExecutorError> {
+        let plan = select_sql_run("select c1 from t1 where c1 = any (select c3 from t2)").await?;
+        println!("any_subquery:\n {:#?}", plan);
+
+        let Operator::Join(join_op) = &plan.childrens[0].operator else {
+            unreachable!()
+        };
+        assert_eq!(join_op.join_type, JoinType::Semi);
+        assert!(matches!(
+            &join_op.on,
+            JoinCondition::On { on, filter: None } if on.len() == 1
+        ));
+
+        let plan =
+            select_sql_run("select c1 from t1 where c2 > 1 and c1 > all (select c3 from t2)")
+                .await?;
+        println!("all_subquery:\n {:#?}", plan);
+
+        let Operator::Join(join_op) = &plan.childrens[0].operator else {
+            unreachable!()
+        };
+        assert_eq!(join_op.join_type, JoinType::Anti);
+        assert!(matches!(
+            &join_op.on,
+            JoinCondition::On { on, filter: Some(_) } if on.is_empty()
+        ));
+        assert!(matches!(
+            plan.childrens[0].childrens[0].operator,
+            Operator::Filter(_)
+        ));
+
+        assert!(
+            select_sql_run("select c1 from t1 where c1 > all (select c3, c4 from t2)")
+                .await
+                .is_err()
+        );
+        assert!(
+            select_sql_run("select c1 > any (select c3 from t2) from t1")
+                .await
+                .is_err()
+        );
+        assert!(
+            select_sql_run("select c1 from t1 where c1 = 1 or c1 = any (select c3 from t2)")
+                .await
+                .is_err()
+        );
+        // correlated subqueries are not supported
+        assert!(select_sql_run(
+            "select c1 from t1 where c1 > all (select c3 from t2 where c4 = c2)"
+        )
+        .await
+        .is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_select_bind_error_context() {
+        let err = select_sql_run("select c1 from t1 where c1 > 1 and c9 < 2")
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("invalid column c9 (in WHERE: c1 > 1 AND c9 < 2)"),
+            "{}",
+            err
+        );
+
+        let err = select_sql_run("select c1 + c8 from t1")
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("invalid column c8 (in SELECT: c1 + c8)"),
+            "{}",
+            err
+        );
+
+        let err = select_sql_run("select c1 from t1 order by c7")
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("invalid column c7 (in ORDER BY: c7)"),
+            "{}",
+            err
+        );
+    }
+
+    #[tokio::test]
+    async fn test_select_bind_self_join() -> Result<(), ExecutorError> {
+        let plan = select_sql_run("select a.c1, b.c2 from t1 a join t1 b on a.c1 = b.c2").await?;
+        println!("self_join:\n {:#?}", plan);
+
+        assert!(select_sql_run("select * from t1 join t1 on c1 = c2")
+            .await
+            .is_err());
+        // the columns of an aliased table are only reachable through the alias
+        assert!(
+            select_sql_run("select t1.c1 from t1 a join t1 b on a.c1 = b.c2")
+                .await
+                .is_err()
+        );
+        let err = select_sql_run("select c1 from t1 a join t1 b on a.c1 = b.c2")
+            .await
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("ambiguous column c1"), "{}", err);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,75 @@
+use crate::binder::{BindError, Binder};
+use crate::planner::operator::set_operation::{SetOperationKind, SetOperationOperator};
+use crate::planner::operator::Operator;
+use crate::planner::LogicalPlan;
+use crate::storage::Transaction;
+use sqlparser::ast::{SetExpr, SetOperator, SetQuantifier};
+
+impl<'a, T: Transaction> Binder<'a, T> {
+    /// Both sides are bound apart from each other and from the enclosing query,
+    /// they only share its `WITH` clause.
+    pub(crate) fn bind_set_operation(
+        &mut self,
+        op: &SetOperator,
+        set_quantifier: &SetQuantifier,
+        left: &SetExpr,
+        right: &SetExpr,
+    ) -> Result<LogicalPlan, BindError> {
+        let left_plan = self.bind_set_operand(left)?;
+        let right_plan = self.bind_set_operand(right)?;
+        let left_columns = left_plan.schema();
+        let right_columns = right_plan.schema();
+
+        if left_columns.len() != right_columns.len() {
+            return Err(BindError::SetOperation(format!(
+                "each {} query must have the same number of columns, {} != {}",
+                op,
+                left_columns.len(),
+                right_columns.len()
+            )));
+        }
+        for (i, (left_column, right_column)) in
+            left_columns.iter().zip(right_columns.iter()).enumerate()
+        {
+            if left_column.datatype() != right_column.datatype() {
+                return Err(BindError::SetOperation(format!(
+                    "column {} of {} has the types {} and {}",
+                    i + 1,
+                    op,
+                    left_column.datatype(),
+                    right_column.datatype()
+                )));
+            }
+        }
+        let kind = match op {
+            SetOperator::Union => SetOperationKind::Union,
+            SetOperator::Except => SetOperationKind::Except,
+            SetOperator::Intersect => SetOperationKind::Intersect,
+        };
+
+        Ok(LogicalPlan {
+            operator: Operator::SetOperation(SetOperationOperator {
+                kind,
+                all: matches!(set_quantifier, SetQuantifier::All),
+            }),
+            childrens: vec![left_plan, right_plan],
+        })
+    }
+
+    fn bind_set_operand(&self, expr: &SetExpr) -> Result<LogicalPlan, BindError> {
+        let mut binder = Binder::new(self.context.derive());
+
+        match expr {
+            SetExpr::Select(select) => binder.bind_select(select, &[]),
+            SetExpr::Query(query) => binder.bind_query(query),
+            SetExpr::Values(values) => binder.bind_values_query(values),
+            SetExpr::SetOperation {
+                op,
+                set_quantifier,
+                left,
+                right,
+            } => binder.bind_set_operation(op, set_quantifier, left, right),
+            expr => Err(BindError::UnsupportedStmt(expr.to_string())),
+        }
+    }
+}
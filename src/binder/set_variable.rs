@@ -0,0 +1,89 @@
+use crate::binder::{BindError, Binder};
+use crate::expression::value_compute::unary_op;
+use crate::expression::ScalarExpression;
+use crate::planner::operator::set_variable::{SetVariableOperator, Variable};
+use crate::planner::operator::sort::NullsOrder;
+use crate::planner::operator::Operator;
+use crate::planner::LogicalPlan;
+use crate::storage::Transaction;
+use crate::types::value::DataValue;
+use crate::types::LogicalType;
+use itertools::Itertools;
+use sqlparser::ast::{Expr, ObjectName};
+
+impl<'a, T: Transaction> Binder<'a, T> {
+    /// `SET name = value`, the value is a constant or a bare word, e.g.
+    /// `SET nulls_order = smallest`.
+    pub(crate) fn bind_set_variable(
+        &mut self,
+        variable: &ObjectName,
+        value: &[Expr],
+    ) -> Result<LogicalPlan, BindError> {
+        let name = variable
+            .0
+            .iter()
+            .map(|ident| ident.value.to_lowercase())
+            .join(".");
+        let value = match value {
+            [Expr::Identifier(ident)] => DataValue::Utf8(Some(ident.value.to_lowercase())),
+            [expr] => self.bind_variable_value(&name, expr)?,
+            _ => {
+                return Err(BindError::Variable(format!(
+                    "{} takes a single value",
+                    name
+                )))
+            }
+        };
+        let variable = match name.as_str() {
+            "strict_types" => Variable::StrictTypes(Self::bool_variable(&name, value)?),
+            "safe_updates" => Variable::SafeUpdates(Self::bool_variable(&name, value)?),
+            "prefer_first_table" => Variable::PreferFirstTable(Self::bool_variable(&name, value)?),
+            "allow_cartesian_products" => {
+                Variable::AllowCartesianProducts(Self::bool_variable(&name, value)?)
+            }
+            "nulls_order" => Variable::NullsOrder(match value {
+                DataValue::Utf8(Some(order)) if order.eq_ignore_ascii_case("largest") => {
+                    NullsOrder::Largest
+                }
+                DataValue::Utf8(Some(order)) if order.eq_ignore_ascii_case("smallest") => {
+                    NullsOrder::Smallest
+                }
+                value => {
+                    return Err(BindError::Variable(format!(
+                        "{} is largest or smallest, not {}",
+                        name, value
+                    )))
+                }
+            }),
+            "max_rows" => Variable::MaxRows(match value.cast(&LogicalType::UBigint)? {
+                DataValue::UInt64(Some(rows)) => Some(rows as usize),
+                _ => None,
+            }),
+            _ => return Err(BindError::Variable(format!("unknown variable {}", name))),
+        };
+
+        Ok(LogicalPlan {
+            operator: Operator::SetVariable(SetVariableOperator { variable }),
+            childrens: vec![],
+        })
+    }
+
+    fn bind_variable_value(&mut self, name: &str, expr: &Expr) -> Result<DataValue, BindError> {
+        match self.bind_expr(expr)? {
+            ScalarExpression::Constant(value) => Ok(DataValue::clone(&value)),
+            // e.g. `SET max_rows = -1`, rejected as it is cast
+            ScalarExpression::Unary { expr, op, .. } => match expr.as_ref() {
+                ScalarExpression::Constant(value) => Ok(unary_op(value, &op)?),
+                _ => Err(BindError::Variable(format!("{} takes a constant", name))),
+            },
+            _ => Err(BindError::Variable(format!("{} takes a constant", name))),
+        }
+    }
+
+    fn bool_variable(name: &str, value: DataValue) -> Result<bool, BindError> {
+        match value.cast(&LogicalType::Boolean)? {
+            DataValue::Boolean(Some(value)) => Ok(value),
+            _ => Err(BindError::Variable(format!("{} cannot be null", name))),
+        }
+    }
+}
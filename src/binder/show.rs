@@ -1,13 +1,56 @@
-use crate::binder::{BindError, Binder};
-use crate::planner::operator::show::ShowTablesOperator;
+use crate::binder::{lower_case_name, split_name, BindError, Binder};
+use crate::planner::operator::show::{
+    ShowCreateTableOperator, ShowIndexOperator, ShowTablesOperator,
+};
 use crate::planner::operator::Operator;
 use crate::planner::LogicalPlan;
 use crate::storage::Transaction;
+use sqlparser::ast::{ObjectName, ShowStatementFilter};
+use std::sync::Arc;
 
 impl<'a, T: Transaction> Binder<'a, T> {
-    pub(crate) fn bind_show_tables(&mut self) -> Result<LogicalPlan, BindError> {
+    /// `SHOW TABLES LIKE 'pattern'`, as the names of the tables are lower case `ILIKE` is
+    /// the same with the pattern in lower case.
+    pub(crate) fn bind_show_tables(
+        &mut self,
+        filter: &Option<ShowStatementFilter>,
+    ) -> Result<LogicalPlan, BindError> {
+        let pattern = match filter {
+            Some(ShowStatementFilter::Like(pattern)) => Some(pattern.clone()),
+            Some(ShowStatementFilter::ILike(pattern)) => Some(pattern.to_lowercase()),
+            Some(ShowStatementFilter::Where(_)) => {
+                return Err(BindError::UnsupportedStmt("SHOW TABLES WHERE".to_string()))
+            }
+            None => None,
+        };
+        let plan = LogicalPlan {
+            operator: Operator::Show(ShowTablesOperator { pattern }),
+            childrens: vec![],
+        };
+        Ok(plan)
+    }
+
+    pub(crate) fn bind_show_create_table(
+        &mut self,
+        name: &ObjectName,
+    ) -> Result<LogicalPlan, BindError> {
+        let name = lower_case_name(name);
+        let (_, name) = split_name(&name)?;
+        let table_name = Arc::new(name.to_string());
+
+        let plan = LogicalPlan {
+            operator: Operator::ShowCreateTable(ShowCreateTableOperator { table_name }),
+            childrens: vec![],
+        };
+        Ok(plan)
+    }
+
+    pub(crate) fn bind_show_index(&mut self, name: &ObjectName) -> Result<LogicalPlan, BindError> {
+        let name = lower_case_name(name);
+        let (_, name) = split_name(&name)?;
+        let table_name = Arc::new(name.to_string());
         let plan = LogicalPlan {
-            operator: Operator::Show(ShowTablesOperator {}),
+            operator: Operator::ShowIndex(ShowIndexOperator { table_name }),
             childrens: vec![],
         };
         Ok(plan)
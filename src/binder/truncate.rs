@@ -7,13 +7,28 @@ use sqlparser::ast::ObjectName;
 use std::sync::Arc;
 
 impl<'a, T: Transaction> Binder<'a, T> {
-    pub(crate) fn bind_truncate(&mut self, name: &ObjectName) -> Result<LogicalPlan, BindError> {
+    /// `TRUNCATE t1, t2` comes as a statement for each table, which all run in the same
+    /// transaction, so a missing table fails the whole of it and none of them is emptied.
+    pub(crate) fn bind_truncate(
+        &mut self,
+        name: &ObjectName,
+        restart_identity: bool,
+    ) -> Result<LogicalPlan, BindError> {
         let name = lower_case_name(name);
         let (_, name) = split_name(&name)?;
         let table_name = Arc::new(name.to_string());
+        if self.context.table(table_name.clone()).is_none() {
+            return Err(BindError::InvalidTable(
+                format!("not found table {}", table_name),
+                None,
+            ));
+        }
 
         let plan = LogicalPlan {
-            operator: Operator::Truncate(TruncateOperator { table_name }),
+            operator: Operator::Truncate(TruncateOperator {
+                table_name,
+                restart_identity,
+            }),
             childrens: vec![],
         };
         Ok(plan)
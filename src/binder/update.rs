@@ -1,10 +1,13 @@
 use crate::binder::{lower_case_name, split_name, BindError, Binder};
+use crate::catalog::ColumnRef;
 use crate::expression::ScalarExpression;
+use crate::planner::operator::join::{JoinCondition, JoinOperator, JoinType};
+use crate::planner::operator::project::ProjectOperator;
 use crate::planner::operator::update::UpdateOperator;
 use crate::planner::operator::Operator;
 use crate::planner::LogicalPlan;
 use crate::storage::Transaction;
-use crate::types::value::ValueRef;
+use crate::types::value::{DataValue, ValueRef};
 use sqlparser::ast::{Assignment, Expr, TableFactor, TableWithJoins};
 use std::slice;
 use std::sync::Arc;
@@ -13,6 +16,7 @@ impl<'a, T: Transaction> Binder<'a, T> {
     pub(crate) fn bind_update(
         &mut self,
         to: &TableWithJoins,
+        from: Option<&TableWithJoins>,
         selection: &Option<Expr>,
         assignments: &[Assignment],
     ) -> Result<LogicalPlan, BindError> {
@@ -23,6 +27,16 @@ impl<'a, T: Transaction> Binder<'a, T> {
 
             let mut plan = self.bind_table_ref(slice::from_ref(to))?;
 
+            if let Some(from) = from {
+                let source = self.bind_table_ref(slice::from_ref(from))?;
+                // every row of the target with every row of the source, filtered by the `WHERE`
+                let on = JoinCondition::On {
+                    on: vec![],
+                    filter: None,
+                };
+
+                plan = JoinOperator::build(plan, source, on, JoinType::Inner);
+            }
             if let Some(predicate) = selection {
                 plan = self.bind_where(plan, predicate)?;
             }
@@ -33,34 +47,104 @@ impl<'a, T: Transaction> Binder<'a, T> {
             let mut row = Vec::with_capacity(assignments.len());
 
             for assignment in assignments {
-                let value = match self.bind_expr(&assignment.value)? {
-                    ScalarExpression::Constant(value) => Ok::<ValueRef, BindError>(value),
-                    _ => unreachable!(),
-                }?;
+                let value = self
+                    .bind_expr(&assignment.value)
+                    .map_err(|err| err.with_context("SET", assignment))?;
 
                 for ident in &assignment.id {
-                    match self.bind_column_ref_from_identifiers(
-                        slice::from_ref(ident),
-                        bind_table_name.as_ref(),
-                    )? {
+                    match self
+                        .bind_column_ref_from_identifiers(
+                            slice::from_ref(ident),
+                            bind_table_name.as_ref(),
+                        )
+                        .map_err(|err| err.with_context("SET", assignment))?
+                    {
                         ScalarExpression::ColumnRef(catalog) => {
-                            value.check_len(catalog.datatype())?;
+                            Self::check_writable(&catalog)
+                                .map_err(|err| err.with_context("SET", assignment))?;
+                            self.infer_parameter(&assignment.value, catalog.datatype());
+                            // cast to the type of the column like the values inserted,
+                            // e.g. padded for `CHAR(n)`
+                            let value = match &value {
+                                ScalarExpression::Constant(value) => {
+                                    value.check_len(catalog.datatype())?;
+                                    ScalarExpression::Constant(ValueRef::new(
+                                        DataValue::clone(value).cast(catalog.datatype())?,
+                                    ))
+                                }
+                                value => value.clone(),
+                            };
                             columns.push(catalog);
-                            row.push(value.clone());
+                            row.push(value);
                         }
                         _ => unreachable!(),
                     }
                 }
             }
 
+            if from.is_some() {
+                return self.bind_update_from(table_name, plan, columns, row);
+            }
+            let row = row
+                .into_iter()
+                .map(|value| match value {
+                    ScalarExpression::Constant(value) => value,
+                    _ => unreachable!(),
+                })
+                .collect::<Vec<ValueRef>>();
             let values_plan = self.bind_values(vec![row], columns);
 
             Ok(LogicalPlan {
-                operator: Operator::Update(UpdateOperator { table_name }),
+                operator: Operator::Update(UpdateOperator {
+                    table_name,
+                    source_columns: vec![],
+                }),
                 childrens: vec![plan, values_plan],
             })
         } else {
             unreachable!("only table")
         }
     }
+
+    /// `UPDATE .. FROM`: the values are evaluated on each row of the target joined with
+    /// the source, and follow the columns of the target in the tuples given to the update.
+    fn bind_update_from(
+        &mut self,
+        table_name: Arc<String>,
+        plan: LogicalPlan,
+        columns: Vec<ColumnRef>,
+        values: Vec<ScalarExpression>,
+    ) -> Result<LogicalPlan, BindError> {
+        let table = self
+            .context
+            .table(table_name.clone())
+            .ok_or_else(|| BindError::InvalidTable(table_name.to_string(), None))?;
+        let exprs = table
+            .all_columns()
+            .into_iter()
+            .map(ScalarExpression::ColumnRef)
+            // not aliased by the names of the columns, which would evaluate to their old values
+            .chain(
+                values
+                    .into_iter()
+                    .zip(columns.iter())
+                    .map(|(expr, column)| ScalarExpression::TypeCast {
+                        expr: Box::new(expr),
+                        ty: *column.datatype(),
+                    }),
+            )
+            .collect();
+        let project = LogicalPlan {
+            operator: Operator::Project(ProjectOperator { exprs }),
+            childrens: vec![plan],
+        };
+
+        Ok(LogicalPlan {
+            operator: Operator::Update(UpdateOperator {
+                table_name,
+                source_columns: columns,
+            }),
+            childrens: vec![project, self.bind_values(vec![], vec![])],
+        })
+    }
 }
@@ -0,0 +1,96 @@
+use crate::binder::{BindError, Binder};
+use crate::catalog::{ColumnCatalog, ColumnDesc};
+use crate::planner::LogicalPlan;
+use crate::storage::Transaction;
+use crate::types::tuple::Tuple;
+use crate::types::value::{DataValue, ValueRef};
+use crate::types::LogicalType;
+use itertools::Itertools;
+use sqlparser::ast::Values;
+use std::sync::Arc;
+
+impl<'a, T: Transaction> Binder<'a, T> {
+    /// `VALUES (1, 'a'), (2, null)` as a query, the columns are named `column1`, `column2`...
+    /// and typed by the common type of their values across the rows.
+    pub(crate) fn bind_values_query(&mut self, values: &Values) -> Result<LogicalPlan, BindError> {
+        let width = values.rows.first().map(Vec::len).unwrap_or(0);
+        let empty = Tuple {
+            id: None,
+            schema_ref: Arc::new(vec![]),
+            values: vec![],
+        };
+        let mut rows = Vec::with_capacity(values.rows.len());
+
+        for expr_row in values.rows.iter() {
+            if expr_row.len() != width {
+                return Err(BindError::InvalidColumn(
+                    "VALUES lists must all be the same length".to_string(),
+                    None,
+                ));
+            }
+            let mut row = Vec::with_capacity(width);
+
+            for expr in expr_row {
+                let expr = self.bind_expr(expr)?;
+
+                if let Some(column) = expr.referenced_columns(true).first() {
+                    return Err(BindError::InvalidColumn(
+                        format!("{} can not be referenced in VALUES", column.name()),
+                        None,
+                    ));
+                }
+                row.push(expr.eval(&empty, &empty.schema_ref)?);
+            }
+            rows.push(row);
+        }
+
+        let mismatch = |row: usize, column: usize, source| BindError::ValuesTypeMismatch {
+            row: row + 1,
+            column: format!("column{}", column + 1),
+            source,
+        };
+        let mut types = vec![LogicalType::SqlNull; width];
+        for (i, row) in rows.iter().enumerate() {
+            for (j, (ty, value)) in types.iter_mut().zip(row.iter()).enumerate() {
+                *ty = LogicalType::max_logical_type(ty, &value.logical_type())
+                    .map_err(|err| mismatch(i, j, err))?;
+            }
+        }
+        let rows: Vec<Vec<ValueRef>> = rows
+            .into_iter()
+            .enumerate()
+            .map(|(i, row)| {
+                row.into_iter()
+                    .zip(types.iter())
+                    .enumerate()
+                    .map(|(j, (value, ty))| {
+                        if &value.logical_type() == ty {
+                            Ok(value)
+                        } else {
+                            DataValue::clone(&value)
+                                .cast(ty)
+                                .map(ValueRef::new)
+                                .map_err(|err| mismatch(i, j, err))
+                        }
+                    })
+                    .try_collect()
+            })
+            .try_collect::<_, _, BindError>()?;
+        let columns = types
+            .iter()
+            .enumerate()
+            .map(|(i, ty)| {
+                let nullable = rows.iter().any(|row| row[i].is_null());
+
+                Arc::new(ColumnCatalog::new(
+                    format!("column{}", i + 1),
+                    nullable,
+                    ColumnDesc::new(*ty, false, false, None),
+                    None,
+                ))
+            })
+            .collect_vec();
+
+        Ok(self.bind_values(rows, columns))
+    }
+}
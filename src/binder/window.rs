@@ -0,0 +1,180 @@
+use crate::binder::{BindError, Binder};
+use crate::expression::agg::AggKind;
+use crate::expression::window::{WindowFrame, WindowFrameBound, WindowFrameUnits, WindowSpec};
+use crate::expression::ScalarExpression;
+use crate::planner::operator::sort::SortField;
+use crate::storage::Transaction;
+use crate::types::value::{DataValue, ValueRef};
+use crate::types::LogicalType;
+use itertools::Itertools;
+use sqlparser::ast;
+use sqlparser::ast::{Expr, NamedWindowDefinition, OrderByExpr, WindowType};
+
+impl<'a, T: Transaction> Binder<'a, T> {
+    /// The windows of the `WINDOW` clause, the `OVER` of the select list and the ORDER BY
+    /// refer to them by name.
+    pub(crate) fn bind_named_windows(
+        &mut self,
+        windows: &[NamedWindowDefinition],
+    ) -> Result<(), BindError> {
+        for NamedWindowDefinition(name, spec) in windows {
+            let name = name.value.to_lowercase();
+
+            if self.context.windows.contains_key(&name) {
+                return Err(BindError::Window(format!(
+                    "window {} is defined more than once",
+                    name
+                )));
+            }
+            self.context.windows.insert(name, spec.clone());
+        }
+
+        Ok(())
+    }
+
+    /// `agg OVER (..)`, only the aggregates can be computed over a window.
+    pub(crate) fn bind_window_call(
+        &mut self,
+        agg: ScalarExpression,
+        over: &WindowType,
+    ) -> Result<ScalarExpression, BindError> {
+        if !matches!(&agg, ScalarExpression::AggCall { kind, .. } if kind != &AggKind::Grouping) {
+            return Err(BindError::Window(format!(
+                "{} is not an aggregate function",
+                agg
+            )));
+        }
+        let spec = match over {
+            WindowType::WindowSpec(spec) => spec.clone(),
+            WindowType::NamedWindow(name) => self
+                .context
+                .windows
+                .get(&name.value.to_lowercase())
+                .cloned()
+                .ok_or_else(|| BindError::Window(format!("window {} does not exist", name)))?,
+        };
+        let partition_by = spec
+            .partition_by
+            .iter()
+            .map(|expr| self.bind_expr(expr))
+            .try_collect()?;
+        let mut order_by = Vec::with_capacity(spec.order_by.len());
+
+        for OrderByExpr {
+            expr,
+            asc,
+            nulls_first,
+        } in spec.order_by.iter()
+        {
+            let asc = asc.map_or(true, |asc| asc);
+            order_by.push(SortField::new(
+                self.bind_expr(expr)?,
+                asc,
+                nulls_first.unwrap_or_else(|| self.context.nulls_order.nulls_first(asc)),
+            ));
+        }
+        let frame = match &spec.window_frame {
+            Some(frame) => self.bind_window_frame(frame, &order_by)?,
+            None => WindowFrame::default_of(&order_by),
+        };
+
+        Ok(ScalarExpression::WindowCall {
+            agg: Box::new(agg),
+            spec: Box::new(WindowSpec {
+                partition_by,
+                order_by,
+                frame,
+            }),
+        })
+    }
+
+    fn bind_window_frame(
+        &mut self,
+        frame: &ast::WindowFrame,
+        order_by: &[SortField],
+    ) -> Result<WindowFrame, BindError> {
+        let units = match frame.units {
+            ast::WindowFrameUnits::Rows => WindowFrameUnits::Rows,
+            ast::WindowFrameUnits::Range => WindowFrameUnits::Range,
+            ast::WindowFrameUnits::Groups => {
+                return Err(BindError::UnsupportedStmt(
+                    "GROUPS frames are not supported".to_string(),
+                ))
+            }
+        };
+        let start = self.bind_window_frame_bound(&frame.start_bound, units, order_by)?;
+        // `ROWS 2 PRECEDING` is short for `ROWS BETWEEN 2 PRECEDING AND CURRENT ROW`
+        let end = match &frame.end_bound {
+            Some(bound) => self.bind_window_frame_bound(bound, units, order_by)?,
+            None => WindowFrameBound::CurrentRow,
+        };
+        if start == WindowFrameBound::UnboundedFollowing {
+            return Err(BindError::Window(
+                "frame start cannot be UNBOUNDED FOLLOWING".to_string(),
+            ));
+        }
+        if end == WindowFrameBound::UnboundedPreceding {
+            return Err(BindError::Window(
+                "frame end cannot be UNBOUNDED PRECEDING".to_string(),
+            ));
+        }
+
+        Ok(WindowFrame::new(units, start, end))
+    }
+
+    fn bind_window_frame_bound(
+        &mut self,
+        bound: &ast::WindowFrameBound,
+        units: WindowFrameUnits,
+        order_by: &[SortField],
+    ) -> Result<WindowFrameBound, BindError> {
+        Ok(match bound {
+            ast::WindowFrameBound::CurrentRow => WindowFrameBound::CurrentRow,
+            ast::WindowFrameBound::Preceding(None) => WindowFrameBound::UnboundedPreceding,
+            ast::WindowFrameBound::Following(None) => WindowFrameBound::UnboundedFollowing,
+            ast::WindowFrameBound::Preceding(Some(offset)) => {
+                WindowFrameBound::Preceding(self.bind_frame_offset(offset, units, order_by)?)
+            }
+            ast::WindowFrameBound::Following(Some(offset)) => {
+                WindowFrameBound::Following(self.bind_frame_offset(offset, units, order_by)?)
+            }
+        })
+    }
+
+    /// A number of rows for `ROWS`, and for `RANGE` a distance from the value of the only
+    /// `ORDER BY` expr, which has to be numeric.
+    fn bind_frame_offset(
+        &mut self,
+        offset: &Expr,
+        units: WindowFrameUnits,
+        order_by: &[SortField],
+    ) -> Result<ValueRef, BindError> {
+        let ty = match (units, order_by) {
+            (WindowFrameUnits::Rows, _) => LogicalType::UBigint,
+            (WindowFrameUnits::Range, [field]) if field.expr.return_type().is_numeric() => {
+                field.expr.return_type()
+            }
+            (WindowFrameUnits::Range, _) => {
+                return Err(BindError::Window(
+                    "RANGE with an offset needs a single numeric ORDER BY expr".to_string(),
+                ))
+            }
+        };
+        let invalid = || {
+            BindError::Window(format!(
+                "frame offset {} must be a non-negative number",
+                offset
+            ))
+        };
+        // a negative number is bound as a unary minus
+        let ScalarExpression::Constant(value) = self.bind_expr(offset)? else {
+            return Err(invalid());
+        };
+        if value.is_null() {
+            return Err(invalid());
+        }
+        let value = DataValue::clone(&value).cast(&ty).map_err(|_| invalid())?;
+
+        Ok(ValueRef::new(value))
+    }
+}
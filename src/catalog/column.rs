@@ -1,3 +1,4 @@
+use crate::catalog::{TableName, ROWID_COLUMN_NAME};
 use crate::expression::ScalarExpression;
 use serde::{Deserialize, Serialize};
 use std::hash::Hash;
@@ -20,6 +21,10 @@ pub struct ColumnCatalog {
 pub struct ColumnSummary {
     pub id: Option<ColumnId>,
     pub name: String,
+    /// The name or alias the table is bound under in a query, it tells apart the columns
+    /// of a table joined with itself.
+    #[serde(skip)]
+    pub table_name: Option<TableName>,
 }
 
 impl ColumnCatalog {
@@ -33,6 +38,7 @@ impl ColumnCatalog {
             summary: ColumnSummary {
                 id: None,
                 name: column_name,
+                table_name: None,
             },
             nullable,
             desc: column_desc,
@@ -45,6 +51,7 @@ impl ColumnCatalog {
             summary: ColumnSummary {
                 id: Some(0),
                 name: column_name,
+                table_name: None,
             },
             nullable: false,
             desc: ColumnDesc::new(LogicalType::Varchar(None), false, false, None),
@@ -52,6 +59,15 @@ impl ColumnCatalog {
         }
     }
 
+    /// The primary key of a table declared without one, the insertion fills it with an
+    /// increasing id and `SELECT *` leaves it out.
+    pub(crate) fn new_rowid() -> ColumnCatalog {
+        let mut desc = ColumnDesc::new(LogicalType::UBigint, true, false, None);
+        desc.is_hidden = true;
+
+        ColumnCatalog::new(ROWID_COLUMN_NAME.to_string(), false, desc, None)
+    }
+
     pub(crate) fn summary(&self) -> &ColumnSummary {
         &self.summary
     }
@@ -64,6 +80,10 @@ impl ColumnCatalog {
         &self.summary.name
     }
 
+    pub(crate) fn table_name(&self) -> Option<&TableName> {
+        self.summary.table_name.as_ref()
+    }
+
     pub(crate) fn datatype(&self) -> &LogicalType {
         &self.desc.column_datatype
     }
@@ -85,6 +105,41 @@ pub struct ColumnDesc {
     pub(crate) is_primary: bool,
     pub(crate) is_unique: bool,
     pub(crate) default: Option<ValueRef>,
+    pub(crate) is_hidden: bool,
+    pub(crate) generated: Option<GeneratedExpr>,
+    pub(crate) identity: Option<Identity>,
+    /// Kept in an entry of its own by the storage, see `TableCodec::encode_comment`.
+    #[serde(skip)]
+    pub(crate) comment: Option<String>,
+    /// The expression of `ON UPDATE expr`, written to the column by every `UPDATE` of a row
+    /// that does not set it. Kept in an entry of its own like the comment, see
+    /// `TableCodec::encode_on_update`.
+    #[serde(skip)]
+    pub(crate) on_update: Option<GeneratedExpr>,
+    /// The expression of a `DEFAULT expr` that is not a constant, e.g. `now()`, evaluated
+    /// anew for every inserted row that does not give the column. Kept in an entry of its own
+    /// like the `ON UPDATE` one, see `TableCodec::encode_default_expr`.
+    #[serde(skip)]
+    pub(crate) default_expr: Option<GeneratedExpr>,
+}
+
+/// The expression of a `GENERATED ALWAYS AS (expr) STORED` column, it is computed from the
+/// other columns of the row whenever the row is written. It is the one of `ON UPDATE expr`
+/// and of a `DEFAULT expr` that is not a constant as well.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Hash)]
+pub struct GeneratedExpr {
+    /// The expression as written, to show the definition of the table.
+    pub(crate) sql: String,
+    pub(crate) expr: ScalarExpression,
+}
+
+/// A `GENERATED { ALWAYS | BY DEFAULT } AS IDENTITY` column, filled by the insertion with
+/// an increasing id when it is not given a value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Hash)]
+pub enum Identity {
+    /// The values are only generated, never written.
+    Always,
+    ByDefault,
 }
 
 impl ColumnDesc {
@@ -99,6 +154,12 @@ impl ColumnDesc {
             is_primary,
             is_unique,
             default,
+            is_hidden: false,
+            generated: None,
+            identity: None,
+            comment: None,
+            on_update: None,
+            default_expr: None,
         }
     }
 
@@ -10,6 +10,8 @@ pub type RootRef = Arc<RootCatalog>;
 
 pub(crate) static DEFAULT_DATABASE_NAME: &str = "kipsql";
 pub(crate) static DEFAULT_SCHEMA_NAME: &str = "kipsql";
+/// The hidden primary key of the tables declared without one.
+pub(crate) static ROWID_COLUMN_NAME: &str = "_rowid";
 
 mod column;
 mod root;
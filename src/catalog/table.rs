@@ -1,8 +1,11 @@
+use itertools::Itertools;
 use std::collections::BTreeMap;
 use std::sync::Arc;
 
-use crate::catalog::{CatalogError, ColumnCatalog, ColumnRef};
+use crate::catalog::{CatalogError, ColumnCatalog, ColumnRef, Identity};
 use crate::types::index::{IndexMeta, IndexMetaRef};
+use crate::types::tuple::SchemaRef;
+use crate::types::value::DataValue;
 use crate::types::ColumnId;
 
 pub type TableName = Arc<String>;
@@ -14,21 +17,27 @@ pub struct TableCatalog {
     column_idxs: BTreeMap<String, ColumnId>,
     pub(crate) columns: BTreeMap<ColumnId, ColumnRef>,
     pub(crate) indexes: Vec<IndexMetaRef>,
+    /// All columns in column id order, shared by the tuples read from the table
+    schema_ref: SchemaRef,
+    /// `COMMENT ON TABLE`
+    pub(crate) comment: Option<String>,
 }
 
 impl TableCatalog {
-    pub(crate) fn get_unique_index(&self, col_id: &ColumnId) -> Option<&IndexMetaRef> {
-        self.indexes
-            .iter()
-            .find(|meta| meta.is_unique && &meta.column_ids[0] == col_id)
+    /// All unique indexes of the table, single-column and composite ones.
+    pub(crate) fn unique_indexes(&self) -> impl Iterator<Item = &IndexMetaRef> {
+        self.indexes.iter().filter(|meta| meta.is_unique)
+    }
+
+    /// All indexes but the primary key, whose entries are written along with the tuples.
+    pub(crate) fn secondary_indexes(&self) -> impl Iterator<Item = &IndexMetaRef> {
+        self.indexes.iter().filter(|meta| !meta.is_primary)
     }
 
-    #[allow(dead_code)]
     pub(crate) fn get_column_by_id(&self, id: &ColumnId) -> Option<&ColumnRef> {
         self.columns.get(id)
     }
 
-    #[allow(dead_code)]
     pub(crate) fn get_column_id_by_name(&self, name: &String) -> Option<ColumnId> {
         self.column_idxs.get(name).cloned()
     }
@@ -38,10 +47,33 @@ impl TableCatalog {
         self.columns.get(id)
     }
 
-    pub(crate) fn contains_column(&self, name: &str) -> bool {
+    pub fn contains_column(&self, name: &str) -> bool {
         self.column_idxs.contains_key(name)
     }
 
+    /// Whether the column is one of this table, and not just of the same name,
+    /// the name is not enough once a table is joined with itself.
+    pub(crate) fn owns_column(&self, column: &ColumnCatalog) -> bool {
+        self.get_column_by_name(&column.summary.name)
+            .map_or(false, |own| own.summary == column.summary)
+    }
+
+    /// The catalog of the table bound under `table_name` in a query, its columns are
+    /// qualified by that name.
+    pub(crate) fn qualified(&self, table_name: TableName) -> TableCatalog {
+        let mut table_catalog = self.clone();
+
+        for column in table_catalog.columns.values_mut() {
+            let mut qualified = ColumnCatalog::clone(column);
+            qualified.summary.table_name = Some(table_name.clone());
+
+            *column = Arc::new(qualified);
+        }
+        table_catalog.schema_ref = Arc::new(table_catalog.all_columns());
+
+        table_catalog
+    }
+
     pub(crate) fn all_columns_with_id(&self) -> Vec<(&ColumnId, &ColumnRef)> {
         self.columns.iter().collect()
     }
@@ -50,6 +82,141 @@ impl TableCatalog {
         self.columns.values().map(Arc::clone).collect()
     }
 
+    /// The columns without the hidden ones, which are what `SELECT *` returns.
+    pub fn visible_columns(&self) -> Vec<ColumnRef> {
+        self.columns
+            .values()
+            .filter(|col| !col.desc.is_hidden)
+            .map(Arc::clone)
+            .collect()
+    }
+
+    /// The indexes of the table, the primary key and the unique ones, in index id order.
+    pub fn indexes(&self) -> &[IndexMetaRef] {
+        &self.indexes
+    }
+
+    pub(crate) fn schema_ref(&self) -> &SchemaRef {
+        &self.schema_ref
+    }
+
+    /// The `CREATE TABLE` statement, without the trailing `;`, that recreates the table
+    /// with its column types, constraints and unique indexes. It is followed by
+    /// a `COMMENT ON TABLE` if the table has a comment, and by the `CREATE INDEX` of
+    /// its other indexes.
+    pub fn create_sql(&self) -> String {
+        let mut definitions = Vec::new();
+        let primary_key = self
+            .visible_columns()
+            .into_iter()
+            .filter(|column| column.desc.is_primary)
+            .collect_vec();
+
+        for column in self.visible_columns() {
+            let mut definition = format!("{} {}", column.name(), column.datatype().to_sql());
+
+            definition.push_str(if column.nullable {
+                " NULL"
+            } else {
+                " NOT NULL"
+            });
+            if let Some(default) = column.default_value() {
+                definition.push_str(&format!(" DEFAULT {}", default.to_sql()));
+            }
+            if let Some(default_expr) = &column.desc.default_expr {
+                definition.push_str(&format!(" DEFAULT {}", default_expr.sql));
+            }
+            if let Some(on_update) = &column.desc.on_update {
+                definition.push_str(&format!(" ON UPDATE {}", on_update.sql));
+            }
+            if let Some(generated) = &column.desc.generated {
+                definition.push_str(&format!(" GENERATED ALWAYS AS ({}) STORED", generated.sql));
+            }
+            match column.desc.identity {
+                Some(Identity::Always) => definition.push_str(" GENERATED ALWAYS AS IDENTITY"),
+                Some(Identity::ByDefault) => {
+                    definition.push_str(" GENERATED BY DEFAULT AS IDENTITY")
+                }
+                None => (),
+            }
+            if let Some(comment) = &column.desc.comment {
+                definition.push_str(&format!(" COMMENT {}", Self::quote(comment)));
+            }
+            if column.desc.is_unique {
+                definition.push_str(" UNIQUE");
+            }
+            // the options after `PRIMARY KEY` are skipped by the binder
+            if column.desc.is_primary && primary_key.len() == 1 {
+                definition.push_str(" PRIMARY KEY");
+            }
+            definitions.push(definition);
+        }
+        if primary_key.len() > 1 {
+            definitions.push(format!(
+                "PRIMARY KEY ({})",
+                primary_key.iter().map(|column| column.name()).join(", ")
+            ));
+        }
+        for index_meta in self.unique_indexes() {
+            if index_meta.column_ids.len() > 1 {
+                let column_names = index_meta
+                    .column_ids
+                    .iter()
+                    .filter_map(|col_id| self.get_column_by_id(col_id))
+                    .map(|column| column.name())
+                    .join(", ");
+
+                definitions.push(format!("UNIQUE ({})", column_names));
+            }
+        }
+
+        let mut sql = format!(
+            "CREATE TABLE {} (\n    {}\n)",
+            self.name,
+            definitions.join(",\n    ")
+        );
+        if let Some(comment) = &self.comment {
+            sql.push_str(&format!(
+                ";\nCOMMENT ON TABLE {} IS {}",
+                self.name,
+                Self::quote(comment)
+            ));
+        }
+        // the indexes of `CREATE INDEX`, but those of unique columns and `UNIQUE (..)`
+        for index_meta in self.secondary_indexes() {
+            let key = match &index_meta.expr {
+                Some(index_expr) => index_expr.sql.clone(),
+                None => {
+                    let columns = index_meta
+                        .column_ids
+                        .iter()
+                        .filter_map(|col_id| self.get_column_by_id(col_id))
+                        .collect_vec();
+                    let is_constraint = index_meta.is_unique
+                        && (columns.len() > 1 || columns.iter().all(|col| col.desc.is_unique));
+
+                    if is_constraint {
+                        continue;
+                    }
+                    columns.iter().map(|column| column.name()).join(", ")
+                }
+            };
+            sql.push_str(&format!(
+                ";\nCREATE {}INDEX {} ON {} ({})",
+                if index_meta.is_unique { "UNIQUE " } else { "" },
+                index_meta.name,
+                self.name,
+                key
+            ));
+        }
+
+        sql
+    }
+
+    fn quote(comment: &str) -> String {
+        DataValue::Utf8(Some(comment.to_string())).to_sql()
+    }
+
     /// Add a column to the table catalog.
     pub(crate) fn add_column(&mut self, mut col: ColumnCatalog) -> Result<ColumnId, CatalogError> {
         if self.column_idxs.contains_key(col.name()) {
@@ -61,6 +228,7 @@ impl TableCatalog {
         col.summary.id = Some(col_id);
         self.column_idxs.insert(col.name().to_string(), col_id);
         self.columns.insert(col_id, Arc::new(col));
+        self.schema_ref = Arc::new(self.all_columns());
 
         Ok(col_id)
     }
@@ -86,6 +254,8 @@ impl TableCatalog {
             column_idxs: BTreeMap::new(),
             columns: BTreeMap::new(),
             indexes: vec![],
+            schema_ref: Arc::new(vec![]),
+            comment: None,
         };
         for col_catalog in columns.into_iter() {
             let _ = table_catalog.add_column(col_catalog)?;
@@ -94,6 +264,37 @@ impl TableCatalog {
         Ok(table_catalog)
     }
 
+    /// A table over the output columns of a subquery, the columns keep their summaries
+    /// so that the outer query refers to exactly what the subquery produces.
+    pub(crate) fn new_derived(
+        name: TableName,
+        columns: Vec<ColumnRef>,
+    ) -> Result<TableCatalog, CatalogError> {
+        if columns.is_empty() {
+            return Err(CatalogError::ColumnsEmpty);
+        }
+        let mut table_catalog = TableCatalog {
+            name,
+            column_idxs: BTreeMap::new(),
+            columns: BTreeMap::new(),
+            indexes: vec![],
+            schema_ref: Arc::new(vec![]),
+            comment: None,
+        };
+        for (i, col) in columns.into_iter().enumerate() {
+            if table_catalog.column_idxs.contains_key(col.name()) {
+                return Err(CatalogError::Duplicated("column", col.name().to_string()));
+            }
+            table_catalog
+                .column_idxs
+                .insert(col.name().to_string(), i as ColumnId);
+            table_catalog.columns.insert(i as ColumnId, col);
+        }
+        table_catalog.schema_ref = Arc::new(table_catalog.all_columns());
+
+        Ok(table_catalog)
+    }
+
     pub(crate) fn new_with_indexes(
         name: TableName,
         columns: Vec<ColumnCatalog>,
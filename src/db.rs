@@ -1,71 +1,701 @@
+use futures::future::BoxFuture;
+use futures::TryStreamExt;
+use itertools::Itertools;
+use kip_db::kernel::utils::lru_cache::LruCache;
+use parking_lot::Mutex;
+use sqlparser::ast::Statement;
 use sqlparser::parser::ParserError;
 use std::cell::RefCell;
+use std::future::Future;
+use std::io::Write;
 use std::path::PathBuf;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 
+use crate::binder::copy::FileFormat;
+use crate::binder::parameter::{Parameter, Parameters};
 use crate::binder::{BindError, Binder, BinderContext};
-use crate::execution::executor::{build, try_collect, BoxedExecutor};
+use crate::execution::executor::{build, try_collect, BoxedExecutor, ExecutorConfig};
 use crate::execution::ExecutorError;
+use crate::expression::function::{
+    FunctionSignature, Functions, ScalarFunction, ScalarFunctionImpl,
+};
 use crate::optimizer::heuristic::batch::HepBatchStrategy;
 use crate::optimizer::heuristic::optimizer::HepOptimizer;
 use crate::optimizer::rule::RuleImpl;
 use crate::optimizer::OptimizerError;
-use crate::parser::parse_sql;
+use crate::parser::{parse_sql, ParsedStatement};
+use crate::planner::operator::set_variable::Variable;
+use crate::planner::operator::sort::NullsOrder;
+use crate::planner::operator::Operator;
 use crate::planner::LogicalPlan;
-use crate::storage::kip::KipStorage;
-use crate::storage::{Storage, StorageError, Transaction};
-use crate::types::tuple::Tuple;
+use crate::storage::kip::{CatalogCacheConfig, KipStorage, StorageConfig, WalMode};
+use crate::storage::{IsolationLevel, Storage, StorageError, Transaction};
+use crate::types::errors::TypeError;
+use crate::types::tuple::{SchemaRef, Tuple};
+use crate::types::value::DataValue;
+use crate::types::LogicalType;
+use std::sync::Arc;
 
 pub struct Database<S: Storage> {
     pub(crate) storage: S,
+    functions: Functions,
+    // the ones every session starts with, a `SET` only changes those of its session
+    settings: SessionSettings,
+    executor_config: ExecutorConfig,
+    plan_cache: Arc<PlanCache>,
+}
+
+/// The settings of a session, a `Session` or a `DBTransaction`, which starts with those of
+/// its `Database`. `SET name = value` changes them for the statements after it,
+/// e.g. `SET strict_types = true`, the name of each one is the one of its field.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SessionSettings {
+    /// See `Database::set_strict_types`.
+    pub strict_types: bool,
+    /// See `Database::set_safe_updates`.
+    pub safe_updates: bool,
+    /// See `Database::set_prefer_first_table`.
+    pub prefer_first_table: bool,
+    /// See `Database::set_allow_cartesian_products`.
+    pub allow_cartesian_products: bool,
+    /// See `Database::set_nulls_order`, `SET nulls_order = smallest` or `largest`.
+    pub nulls_order: NullsOrder,
+    /// The most tuples a query returns, the rest are dropped. `SET max_rows = NULL` for no limit.
+    pub max_rows: Option<usize>,
+}
+
+impl SessionSettings {
+    fn set(&mut self, variable: Variable) {
+        match variable {
+            Variable::StrictTypes(value) => self.strict_types = value,
+            Variable::SafeUpdates(value) => self.safe_updates = value,
+            Variable::PreferFirstTable(value) => self.prefer_first_table = value,
+            Variable::AllowCartesianProducts(value) => self.allow_cartesian_products = value,
+            Variable::NullsOrder(value) => self.nulls_order = value,
+            Variable::MaxRows(value) => self.max_rows = value,
+        }
+    }
+
+    /// The limit of the tuples of `stmt`, only queries are limited.
+    fn max_rows_of(&self, stmt: &Statement) -> Option<usize> {
+        self.max_rows
+            .filter(|_| matches!(stmt, Statement::Query(_)))
+    }
+}
+
+/// The settings of a `Database`, built up with the `with_*` methods and given to
+/// `Database::with_config`, e.g.
+///
+/// ```ignore
+/// let config = DatabaseConfig::default()
+///     .with_safe_updates(true)
+///     .with_sort_spill_threshold(Some(64 * 1024 * 1024));
+/// let database = Database::with_config(path, config).await?;
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct DatabaseConfig {
+    storage: StorageConfig,
+    plan_cache_cap: usize,
+    settings: SessionSettings,
+    executor: ExecutorConfig,
+}
+
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        DatabaseConfig {
+            storage: Default::default(),
+            plan_cache_cap: PLAN_CACHE_CAP,
+            settings: Default::default(),
+            executor: Default::default(),
+        }
+    }
+}
+
+impl DatabaseConfig {
+    /// The size of the `TableCatalog` cache of each transaction.
+    pub fn with_catalog_cache(mut self, catalog_cache: CatalogCacheConfig) -> Self {
+        self.storage.catalog_cache = catalog_cache;
+        self
+    }
+
+    /// How the commits are written to the log, see `WalMode`.
+    pub fn with_wal_mode(mut self, wal_mode: WalMode) -> Self {
+        self.storage.wal_mode = wal_mode;
+        self
+    }
+
+    /// The number of query plans kept to run the same query again without planning it.
+    pub fn with_plan_cache_cap(mut self, cap: usize) -> Self {
+        self.plan_cache_cap = cap;
+        self
+    }
+
+    /// See `Database::set_strict_types`.
+    pub fn with_strict_types(mut self, strict_types: bool) -> Self {
+        self.settings.strict_types = strict_types;
+        self
+    }
+
+    /// See `Database::set_safe_updates`.
+    pub fn with_safe_updates(mut self, safe_updates: bool) -> Self {
+        self.settings.safe_updates = safe_updates;
+        self
+    }
+
+    /// See `Database::set_prefer_first_table`.
+    pub fn with_prefer_first_table(mut self, prefer_first_table: bool) -> Self {
+        self.settings.prefer_first_table = prefer_first_table;
+        self
+    }
+
+    /// See `Database::set_allow_cartesian_products`.
+    pub fn with_allow_cartesian_products(mut self, allow_cartesian_products: bool) -> Self {
+        self.settings.allow_cartesian_products = allow_cartesian_products;
+        self
+    }
+
+    /// See `Database::set_nulls_order`.
+    pub fn with_nulls_order(mut self, nulls_order: NullsOrder) -> Self {
+        self.settings.nulls_order = nulls_order;
+        self
+    }
+
+    /// See `Database::set_sort_spill_threshold`.
+    pub fn with_sort_spill_threshold(mut self, bytes: Option<usize>) -> Self {
+        self.executor.sort_spill_threshold = bytes;
+        self
+    }
+
+    /// See `Database::set_agg_spill_threshold`.
+    pub fn with_agg_spill_threshold(mut self, groups: Option<usize>) -> Self {
+        self.executor.agg_spill_threshold = groups;
+        self
+    }
+
+    /// See `Database::set_deterministic`.
+    pub fn with_deterministic(mut self, deterministic: bool) -> Self {
+        self.executor.deterministic = deterministic;
+        self
+    }
+
+    /// The number of tuples the scans fetch from the storage at once, 1024 by default.
+    /// The executors above the scans still take the tuples one at a time.
+    pub fn with_scan_batch_size(mut self, size: usize) -> Self {
+        self.executor.scan_batch_size = Some(size);
+        self
+    }
 }
 
 impl Database<KipStorage> {
     /// Create a new Database instance With KipDB.
     pub async fn with_kipdb(path: impl Into<PathBuf> + Send) -> Result<Self, DatabaseError> {
-        let storage = KipStorage::new(path).await?;
+        Self::with_config(path, DatabaseConfig::default()).await
+    }
 
-        Ok(Database { storage })
+    /// Create a new Database instance With KipDB and the given settings.
+    pub async fn with_config(
+        path: impl Into<PathBuf> + Send,
+        config: DatabaseConfig,
+    ) -> Result<Self, DatabaseError> {
+        let storage = KipStorage::new_with_config(path, config.storage).await?;
+
+        Database::new_with_config(storage, config)
     }
 }
 
 impl<S: Storage> Database<S> {
     /// Create a new Database instance.
     pub fn new(storage: S) -> Result<Self, DatabaseError> {
-        Ok(Database { storage })
+        Self::new_with_config(storage, DatabaseConfig::default())
+    }
+
+    /// Create a new Database instance with the given settings,
+    /// those of the storage are only used by `Database::with_config`.
+    pub fn new_with_config(storage: S, config: DatabaseConfig) -> Result<Self, DatabaseError> {
+        Ok(Database {
+            storage,
+            functions: Default::default(),
+            settings: config.settings,
+            executor_config: config.executor,
+            plan_cache: Arc::new(PlanCache::new(config.plan_cache_cap)?),
+        })
+    }
+
+    /// Reject comparisons between different types, e.g. `int_column = float_column`,
+    /// instead of casting both sides to their common type.
+    ///
+    /// Constants are still cast to the type of the other side if the cast loses nothing.
+    pub fn set_strict_types(&mut self, strict_types: bool) {
+        self.settings.strict_types = strict_types;
+        self.plan_cache.invalidate();
+    }
+
+    /// Reject `UPDATE` and `DELETE` without a `WHERE` clause, which would change every row
+    /// of the table by accident. `WHERE true` still writes all of them on purpose.
+    pub fn set_safe_updates(&mut self, safe_updates: bool) {
+        self.settings.safe_updates = safe_updates;
+        self.plan_cache.invalidate();
+    }
+
+    /// Resolve a column name of several tables of the query, e.g. `id` in
+    /// `select id from t1 join t2 on t1.id = t2.id`, to the column of the table listed first
+    /// instead of rejecting it as ambiguous, as lenient databases do.
+    pub fn set_prefer_first_table(&mut self, prefer_first_table: bool) {
+        self.settings.prefer_first_table = prefer_first_table;
+        self.plan_cache.invalidate();
+    }
+
+    /// Run the joins without a condition joining their tables, e.g. `t1 JOIN t2 ON true`,
+    /// as cartesian products instead of rejecting them as forgotten conditions. `CROSS JOIN`
+    /// is always a cartesian product.
+    pub fn set_allow_cartesian_products(&mut self, allow_cartesian_products: bool) {
+        self.settings.allow_cartesian_products = allow_cartesian_products;
+        self.plan_cache.invalidate();
+    }
+
+    /// Where `ORDER BY` places the nulls of a sort key without `NULLS FIRST` or `NULLS LAST`,
+    /// by default last in ascending order and first in descending order as the SQL standard.
+    pub fn set_nulls_order(&mut self, nulls_order: NullsOrder) {
+        self.settings.nulls_order = nulls_order;
+        self.plan_cache.invalidate();
+    }
+
+    /// Spill the sorted runs of `ORDER BY` to temporary files once more than `bytes` of tuples
+    /// are buffered, and merge them afterwards. Sorts stay in memory when it is `None`.
+    pub fn set_sort_spill_threshold(&mut self, bytes: Option<usize>) {
+        self.executor_config.sort_spill_threshold = bytes;
+    }
+
+    /// Spill the partial aggregates of `GROUP BY` to temporary files, partitioned by the hash
+    /// of the group, once more than `groups` are in memory. They stay in memory when it is `None`.
+    pub fn set_agg_spill_threshold(&mut self, groups: Option<usize>) {
+        self.executor_config.agg_spill_threshold = groups;
+    }
+
+    /// Make the order of the tuples of a query the same on every run, for tests comparing
+    /// the results of queries. The hash tables of `GROUP BY`, `DISTINCT` and the joins use
+    /// fixed seeds instead of random ones, which are the defence against crafted keys
+    /// colliding on purpose. The scans always read in the order of the keys.
+    pub fn set_deterministic(&mut self, deterministic: bool) {
+        self.executor_config.deterministic = deterministic;
+    }
+
+    /// Register a scalar function that can be called by name in SQL.
+    ///
+    /// The arguments are cast to `signature.args` before `impl_fn` is called,
+    /// registering the same name again replaces the previous function.
+    pub fn register_function<F>(&mut self, name: &str, signature: FunctionSignature, impl_fn: F)
+    where
+        F: Fn(&[DataValue]) -> Result<DataValue, TypeError> + Send + Sync + 'static,
+    {
+        let name = name.to_lowercase();
+        let impl_fn: ScalarFunctionImpl = Arc::new(impl_fn);
+
+        self.functions.insert(
+            name.clone(),
+            Arc::new(ScalarFunction::new(name, signature, impl_fn)),
+        );
+        self.plan_cache.invalidate();
     }
 
     /// Run SQL queries.
+    ///
+    /// The statements run one after another in a single transaction,
+    /// the tuples of the last one are returned.
     pub async fn run(&self, sql: &str) -> Result<Vec<Tuple>, DatabaseError> {
-        let transaction = self.storage.transaction().await?;
+        Ok(self.query(sql).await?.tuples)
+    }
+
+    /// Run SQL queries like `run`, the result of the last one is returned along with
+    /// the columns it is made of.
+    ///
+    /// A `SET` only changes the settings of the statements after it in `sql`,
+    /// see `Database::session` to keep them for the next queries.
+    pub async fn query(&self, sql: &str) -> Result<ResultSet, DatabaseError> {
+        let mut settings = self.settings;
+
+        self.query_with_settings(sql, &mut settings).await
+    }
+
+    /// Run SQL queries like `query` with `settings`, which are changed by the `SET` statements
+    /// of `sql` once its transaction is committed.
+    async fn query_with_settings(
+        &self,
+        sql: &str,
+        settings: &mut SessionSettings,
+    ) -> Result<ResultSet, DatabaseError> {
+        let mut transaction = self
+            .wrap_transaction(
+                self.storage.transaction(),
+                IsolationLevel::RepeatableRead,
+                *settings,
+            )
+            .await?;
+        let result = transaction.query(sql).await?;
+        let changed_settings = transaction.settings;
+
+        transaction.commit().await?;
+        *settings = changed_settings;
+
+        Ok(result)
+    }
+
+    /// A session of the `Database`, whose settings are changed by its `SET` statements
+    /// for the queries after them, while other sessions keep theirs.
+    ///
+    /// ```ignore
+    /// let mut session = database.session();
+    /// session.run("set max_rows = 100").await?;
+    /// let tuples = session.run("select * from t1").await?;
+    /// ```
+    pub fn session(&self) -> Session<'_, S> {
+        Session {
+            database: self,
+            settings: self.settings,
+        }
+    }
+
+    /// The plan of a single statement as `EXPLAIN` prints it once optimized, without running
+    /// it. Tests compare it to a snapshot to catch the changes of the optimizer reshaping
+    /// plans, so what differs between two plannings of the same statement is normalized,
+    /// see `normalize_plan`.
+    pub async fn explain_plan(&self, sql: &str) -> Result<String, DatabaseError> {
+        let stmt = match Self::parse(sql)?.as_slice() {
+            [stmt] => stmt.clone(),
+            _ => {
+                return Err(BindError::UnsupportedStmt(
+                    "only a single statement can be explained".to_string(),
+                )
+                .into())
+            }
+        };
+        let transaction = RefCell::new(self.storage.read_only_transaction().await?);
+        let plan = Self::build_plan(&stmt, &self.functions, &self.settings, None, &transaction)?;
+
+        Ok(normalize_plan(&plan.explain(0)))
+    }
+
+    /// Prepare a single statement with parameters, `$1` by position or `:name` by name,
+    /// for `execute_prepared`.
+    ///
+    /// The type of each parameter is inferred from where it is used, e.g. `a = $1` takes
+    /// the type of `a`. It fails if nothing tells the type, as in `$1 = $2`.
+    pub async fn prepare(&self, sql: &str) -> Result<PreparedStatement, DatabaseError> {
+        let stmt = match Self::parse(sql)?.as_slice() {
+            [stmt] => stmt.clone(),
+            _ => {
+                return Err(BindError::UnsupportedStmt(
+                    "only a single statement can be prepared".to_string(),
+                )
+                .into())
+            }
+        };
+        let transaction = RefCell::new(self.storage.read_only_transaction().await?);
+        let parameters = Rc::new(RefCell::new(Parameters::Infer(Default::default())));
+        let binder = Binder::new(
+            BinderContext::new(
+                unsafe { transaction.as_ptr().as_ref().unwrap() },
+                &self.functions,
+            )
+            .with_settings(&self.settings)
+            .with_parameters(Some(parameters.clone())),
+        );
+        let _ = binder.bind(&stmt)?;
+        let parameters = parameters
+            .replace(Parameters::Infer(Default::default()))
+            .into_types()?;
+
+        Ok(PreparedStatement { stmt, parameters })
+    }
+
+    /// Execute a prepared statement in a transaction of its own. `values` holds a value
+    /// for each parameter, of its inferred type or a type implicitly cast to it.
+    pub async fn execute_prepared(
+        &self,
+        prepared: &PreparedStatement,
+        values: &[(Parameter, DataValue)],
+    ) -> Result<ResultSet, DatabaseError> {
+        let mut transaction = self.new_transaction().await?;
+        let result = transaction.execute_prepared(prepared, values).await?;
+        transaction.commit().await?;
+
+        Ok(result)
+    }
+
+    /// Dump the table as SQL, a `CREATE TABLE` followed by an `INSERT` of all rows,
+    /// which recreates the table when it is passed to `run`. The generated columns are left
+    /// out, they are computed again from the restored rows. The ids of an identity column
+    /// are written by `OVERRIDING SYSTEM VALUE`, so the restored table generates the ids
+    /// following them.
+    pub async fn dump(&self, table_name: &str) -> Result<String, DatabaseError> {
+        let table_name = table_name.to_lowercase();
+        let table = self
+            .storage
+            .transaction()
+            .await?
+            .table(Arc::new(table_name.clone()))
+            .cloned()
+            .ok_or(StorageError::TableNotFound)?;
+        let mut sql = format!("{};\n", table.create_sql());
+        let columns = table
+            .visible_columns()
+            .into_iter()
+            .filter(|column| column.desc.generated.is_none())
+            .collect_vec();
+        let column_names = columns.iter().map(|column| column.name()).join(", ");
+        let tuples = self
+            .run(&format!("SELECT {} FROM {}", column_names, table_name))
+            .await?;
+
+        if !tuples.is_empty() {
+            let overriding = if columns.iter().any(|column| column.desc.identity.is_some()) {
+                " OVERRIDING SYSTEM VALUE"
+            } else {
+                ""
+            };
+            let rows = tuples
+                .iter()
+                .map(|tuple| format!("({})", tuple.values.iter().map(|v| v.to_sql()).join(", ")))
+                .join(",\n    ");
+
+            sql.push_str(&format!(
+                "INSERT INTO {} ({}){} VALUES\n    {};\n",
+                table_name, column_names, overriding, rows
+            ));
+        }
+
+        Ok(sql)
+    }
+
+    /// The number of rows of the table committed so far, which is quicker than
+    /// `select count(*)` as the rows are not decoded.
+    pub async fn table_row_count(&self, table_name: &str) -> Result<usize, DatabaseError> {
+        let table_name = Arc::new(table_name.to_lowercase());
+
+        Ok(self
+            .storage
+            .read_only_transaction()
+            .await?
+            .row_count(table_name)?)
+    }
+
+    /// Export the result of a single query to `writer` in `format`, e.g. a CSV or a TSV with
+    /// a tab as the delimiter, led by a row of the column names if the format has a header.
+    /// The rows are written as the query produces them, and their number is returned.
+    pub async fn export(
+        &self,
+        sql: &str,
+        writer: impl Write,
+        format: &FileFormat,
+    ) -> Result<usize, DatabaseError> {
+        let stmt = match Self::parse(sql)?.as_slice() {
+            [stmt] if matches!(**stmt, Statement::Query(_)) => stmt.clone(),
+            _ => {
+                return Err(BindError::UnsupportedStmt(
+                    "only a single query can be exported".to_string(),
+                )
+                .into())
+            }
+        };
+        let (transaction, cache_version) = self
+            .plan_cache
+            .snapshot(self.storage.read_only_transaction())
+            .await?;
         let transaction = RefCell::new(transaction);
-        let mut stream = Self::_run(sql, &transaction)?;
-        let tuples = try_collect(&mut stream).await?;
+        let plan = self.plan_cache.get_or_plan(&stmt, cache_version, || {
+            Self::build_plan(&stmt, &self.functions, &self.settings, None, &transaction)
+        })?;
+        let schema = plan.output_schema().unwrap_or_default();
+        let mut stream = build(plan, &transaction, &self.executor_config);
+
+        let FileFormat::Csv {
+            delimiter,
+            quote,
+            escape,
+            header,
+        } = *format;
+        let mut writer = csv::WriterBuilder::new()
+            .delimiter(delimiter as u8)
+            .quote(quote as u8)
+            .escape(escape.unwrap_or(quote) as u8)
+            .double_quote(escape.is_none())
+            .from_writer(writer);
+
+        if header {
+            writer
+                .write_record(schema.iter().map(|col| col.name()))
+                .map_err(ExecutorError::from)?;
+        }
+        let mut rows = 0;
 
-        transaction.into_inner().commit().await?;
+        while let Some(tuple) = stream.try_next().await? {
+            writer
+                .write_record(tuple.values.iter().map(|value| value.to_string()))
+                .map_err(ExecutorError::from)?;
+            rows += 1;
+        }
+        writer.flush().map_err(ExecutorError::from)?;
 
-        Ok(tuples)
+        Ok(rows)
     }
 
     pub async fn new_transaction(&self) -> Result<DBTransaction<S>, DatabaseError> {
-        let transaction = self.storage.transaction().await?;
+        self.wrap_transaction(
+            self.storage.transaction(),
+            IsolationLevel::RepeatableRead,
+            self.settings,
+        )
+        .await
+    }
+
+    /// A transaction whose statements see the commits of other transactions as `isolation`
+    /// tells: under `IsolationLevel::ReadCommitted` each statement reads the data committed
+    /// before it began, under `IsolationLevel::RepeatableRead`, the one of `new_transaction`,
+    /// all of them read the data as of the beginning of the transaction.
+    pub async fn new_transaction_with_isolation(
+        &self,
+        isolation: IsolationLevel,
+    ) -> Result<DBTransaction<S>, DatabaseError> {
+        self.wrap_transaction(
+            self.storage.transaction_with_isolation(isolation),
+            isolation,
+            self.settings,
+        )
+        .await
+    }
+
+    /// A transaction for reads only, any statement writing data or changing the catalog
+    /// in it fails with `StorageError::ReadOnly`.
+    ///
+    /// All its statements read the same snapshot, the data as of its beginning: KipDB reads
+    /// at the sequence number a transaction began at, so the commits of other transactions
+    /// after it are not seen by any of them.
+    pub async fn read_transaction(&self) -> Result<DBTransaction<S>, DatabaseError> {
+        self.wrap_transaction(
+            self.storage.read_only_transaction(),
+            IsolationLevel::RepeatableRead,
+            self.settings,
+        )
+        .await
+    }
+
+    /// Makes every commit so far durable, see `WalMode` for what may be lost without it.
+    pub async fn flush(&self) -> Result<(), DatabaseError> {
+        Ok(self.storage.flush().await?)
+    }
+
+    /// Wrap the transaction `begin` opens, with the version of the plan cache of its catalog,
+    /// see `PlanCache::snapshot`.
+    async fn wrap_transaction(
+        &self,
+        begin: impl Future<Output = Result<S::TransactionType, StorageError>>,
+        isolation: IsolationLevel,
+        settings: SessionSettings,
+    ) -> Result<DBTransaction<S>, DatabaseError> {
+        let (transaction, cache_version) = self.plan_cache.snapshot(begin).await?;
 
         Ok(DBTransaction {
             inner: RefCell::new(transaction),
+            isolation,
+            functions: self.functions.clone(),
+            settings,
+            executor_config: self.executor_config,
+            plan_cache: self.plan_cache.clone(),
+            // the cached plans are bound with the settings of the `Database`
+            cache_version: cache_version.filter(|_| settings == self.settings),
+            changed_catalog: false,
         })
     }
 
-    fn _run(
+    /// Run `f` on a new transaction and commit it, `f` is run again on a fresh transaction
+    /// when the transaction conflicts with a concurrent one, for `max_attempts` at most.
+    ///
+    /// ```ignore
+    /// database
+    ///     .transaction_retry(
+    ///         |tx| Box::pin(async move { tx.run("update t1 set b = 1 where a = 1").await }),
+    ///         3,
+    ///     )
+    ///     .await?;
+    /// ```
+    pub async fn transaction_retry<F, R>(
+        &self,
+        mut f: F,
+        max_attempts: usize,
+    ) -> Result<R, DatabaseError>
+    where
+        F: for<'a> FnMut(&'a mut DBTransaction<S>) -> BoxFuture<'a, Result<R, DatabaseError>>,
+    {
+        let mut attempts = 0;
+
+        loop {
+            attempts += 1;
+            let mut transaction = self.new_transaction().await?;
+            let result = match f(&mut transaction).await {
+                Ok(output) => transaction.commit().await.map(|_| output),
+                Err(err) => Err(err),
+            };
+
+            match result {
+                Err(DatabaseError::StorageError(StorageError::TransactionConflict))
+                    if attempts < max_attempts => {}
+                result => return result,
+            }
+        }
+    }
+
+    /// Run a script of SQL statements, e.g. a migration file, the result of each statement
+    /// is returned in order. The statements run in a single transaction
+    /// with `single_transaction`, otherwise each one is committed on its own.
+    ///
+    /// It stops on the first statement failing, the statements committed before are kept.
+    pub async fn execute_script(
+        &self,
         sql: &str,
-        transaction: &RefCell<<S as Storage>::TransactionType>,
-    ) -> Result<BoxedExecutor, DatabaseError> {
-        // parse
+        single_transaction: bool,
+    ) -> Result<Vec<ResultSet>, DatabaseError> {
+        let stmts = Self::parse(sql)?;
+        let mut results = Vec::with_capacity(stmts.len());
+
+        if single_transaction {
+            let mut transaction = self.new_transaction().await?;
+            for stmt in stmts.iter() {
+                results.push(transaction.execute(stmt).await?);
+            }
+            transaction.commit().await?;
+        } else {
+            for stmt in stmts.iter() {
+                let mut transaction = self.new_transaction().await?;
+                results.push(transaction.execute(stmt).await?);
+                transaction.commit().await?;
+            }
+        }
+
+        Ok(results)
+    }
+
+    fn parse(sql: &str) -> Result<Vec<ParsedStatement>, DatabaseError> {
         let stmts = parse_sql(sql)?;
         if stmts.is_empty() {
             return Err(DatabaseError::EmptyStatement);
         }
-        let binder = Binder::new(BinderContext::new(unsafe {
-            transaction.as_ptr().as_ref().unwrap()
-        }));
+
+        Ok(stmts)
+    }
+
+    fn build_plan(
+        stmt: &ParsedStatement,
+        functions: &Functions,
+        settings: &SessionSettings,
+        parameters: Option<Parameters>,
+        transaction: &RefCell<<S as Storage>::TransactionType>,
+    ) -> Result<LogicalPlan, DatabaseError> {
+        let binder = Binder::new(
+            BinderContext::new(unsafe { transaction.as_ptr().as_ref().unwrap() }, functions)
+                .with_settings(settings)
+                .with_parameters(parameters.map(|parameters| Rc::new(RefCell::new(parameters)))),
+        );
         /// Build a logical plan.
         ///
         /// SELECT a,b FROM t1 ORDER BY a LIMIT 1;
@@ -73,13 +703,13 @@ impl<S: Storage> Database<S> {
         ///   Sort(a)
         ///     Limit(1)
         ///       Project(a,b)
-        let source_plan = binder.bind(&stmts[0])?;
+        let source_plan = binder.bind(stmt)?;
         // println!("source_plan plan: {:#?}", source_plan);
 
         let best_plan = Self::default_optimizer(source_plan).find_best()?;
         // println!("best_plan plan: {:#?}", best_plan);
 
-        Ok(build(best_plan, &transaction))
+        Ok(best_plan)
     }
 
     fn default_optimizer(source_plan: LogicalPlan) -> HepOptimizer {
@@ -102,11 +732,21 @@ impl<S: Storage> Database<S> {
                     RuleImpl::PushPredicateIntoScan,
                 ],
             )
+            .batch(
+                "Eliminate Distinct".to_string(),
+                HepBatchStrategy::once_topdown(),
+                vec![RuleImpl::EliminateDistinct],
+            )
             .batch(
                 "Combine Operators".to_string(),
                 HepBatchStrategy::fix_point_topdown(10),
                 vec![RuleImpl::CollapseProject, RuleImpl::CombineFilter],
             )
+            .batch(
+                "Filter Pushdown".to_string(),
+                HepBatchStrategy::fix_point_topdown(10),
+                vec![RuleImpl::PushFilterIntoScan],
+            )
             .batch(
                 "Limit Pushdown".to_string(),
                 HepBatchStrategy::fix_point_topdown(10),
@@ -114,28 +754,359 @@ impl<S: Storage> Database<S> {
                     RuleImpl::LimitProjectTranspose,
                     RuleImpl::PushLimitThroughJoin,
                     RuleImpl::PushLimitIntoTableScan,
+                    RuleImpl::PushLimitIntoOrderedScan,
                     RuleImpl::EliminateLimits,
                 ],
             )
+            .batch(
+                "Aggregate Pushdown".to_string(),
+                HepBatchStrategy::once_topdown(),
+                vec![RuleImpl::PushAggregateIntoScan],
+            )
+            .batch(
+                "Project Pushdown".to_string(),
+                HepBatchStrategy::once_topdown(),
+                vec![RuleImpl::PushProjectIntoScan],
+            )
+            .batch(
+                "Semi Join Index Probe".to_string(),
+                HepBatchStrategy::once_topdown(),
+                vec![RuleImpl::IndexProbeSemiJoin],
+            )
+    }
+}
+
+/// The printed plan with the directories made in the temporary directory replaced by `$TMP`,
+/// e.g. the one of `tempfile::TempDir` for the file of a `COPY` in a test, as their names are
+/// random: `Copy /tmp/.tmpa1B2c3/t.csv -> t` is `Copy $TMP/t.csv -> t`.
+fn normalize_plan(plan: &str) -> String {
+    let temp_dir = std::env::temp_dir();
+    let temp_dir = temp_dir.to_string_lossy();
+    let temp_dir = temp_dir.trim_end_matches('/');
+    let mut normalized = String::with_capacity(plan.len());
+    let mut rest = plan;
+
+    while let Some(start) = rest.find(temp_dir) {
+        normalized.push_str(&rest[..start]);
+        normalized.push_str("$TMP");
+        rest = &rest[start + temp_dir.len()..];
+        // the directory made in it, the files right in it keep their names
+        let dir_len = rest.strip_prefix('/').and_then(|dir| {
+            let end = dir.find(['/', ' ', '\n'])?;
+
+            dir[end..].starts_with('/').then_some(1 + end)
+        });
+        if let Some(dir_len) = dir_len {
+            rest = &rest[dir_len..];
+        }
     }
+    normalized.push_str(rest);
+
+    normalized
 }
 
 pub struct DBTransaction<S: Storage> {
     inner: RefCell<S::TransactionType>,
+    functions: Functions,
+    // a `SET` in the transaction changes them for the rest of it
+    settings: SessionSettings,
+    executor_config: ExecutorConfig,
+    plan_cache: Arc<PlanCache>,
+    /// The version of the plan cache when the transaction began, `None` if its settings
+    /// are not those of the `Database` or once the transaction changed the catalog.
+    cache_version: Option<u64>,
+    changed_catalog: bool,
+    isolation: IsolationLevel,
 }
 
 impl<S: Storage> DBTransaction<S> {
     pub async fn run(&mut self, sql: &str) -> Result<Vec<Tuple>, DatabaseError> {
-        let mut stream = Database::<S>::_run(sql, &self.inner)?;
+        Ok(self.query(sql).await?.tuples)
+    }
+
+    pub async fn query(&mut self, sql: &str) -> Result<ResultSet, DatabaseError> {
+        let mut result = ResultSet::default();
+
+        for stmt in Database::<S>::parse(sql)? {
+            result = self.execute(&stmt).await?;
+        }
 
-        Ok(try_collect(&mut stream).await?)
+        Ok(result)
     }
 
-    pub async fn commit(self) -> Result<(), DatabaseError> {
-        self.inner.into_inner().commit().await?;
+    async fn execute(&mut self, stmt: &ParsedStatement) -> Result<ResultSet, DatabaseError> {
+        self.begin_statement().await?;
+        if PlanCache::changes_catalog(stmt) {
+            self.cache_version = None;
+            self.changed_catalog = true;
+        }
+        let plan = self.plan_cache.get_or_plan(stmt, self.cache_version, || {
+            Database::<S>::build_plan(stmt, &self.functions, &self.settings, None, &self.inner)
+        })?;
+        if let Operator::SetVariable(op) = plan.operator {
+            self.settings.set(op.variable);
+            // the cached plans are bound with the settings of the `Database`
+            self.cache_version = None;
+
+            return Ok(ResultSet::default());
+        }
+        let schema = plan.output_schema();
+        let stream = build(plan, &self.inner, &self.executor_config);
+
+        ResultSet::collect(schema, stream, self.settings.max_rows_of(stmt)).await
+    }
+
+    pub async fn execute_prepared(
+        &mut self,
+        prepared: &PreparedStatement,
+        values: &[(Parameter, DataValue)],
+    ) -> Result<ResultSet, DatabaseError> {
+        self.begin_statement().await?;
+        if PlanCache::changes_catalog(&prepared.stmt) {
+            self.cache_version = None;
+            self.changed_catalog = true;
+        }
+        let parameters = Parameters::check_values(&prepared.parameters, values)?;
+        // the values are bound as constants, so the plan is not cached
+        let plan = Database::<S>::build_plan(
+            &prepared.stmt,
+            &self.functions,
+            &self.settings,
+            Some(parameters),
+            &self.inner,
+        )?;
+        let schema = plan.output_schema();
+        let stream = build(plan, &self.inner, &self.executor_config);
+
+        ResultSet::collect(schema, stream, self.settings.max_rows_of(&prepared.stmt)).await
+    }
+
+    /// A statement of a transaction under `IsolationLevel::ReadCommitted` reads a new
+    /// snapshot, which may have a catalog changed since the transaction began.
+    async fn begin_statement(&mut self) -> Result<(), DatabaseError> {
+        if self.isolation == IsolationLevel::ReadCommitted && self.cache_version.is_some() {
+            let (_, cache_version) = self
+                .plan_cache
+                .snapshot(self.inner.get_mut().begin_statement())
+                .await?;
+            self.cache_version = cache_version;
+        } else {
+            self.inner.get_mut().begin_statement().await?;
+        }
 
         Ok(())
     }
+
+    pub async fn commit(self) -> Result<(), DatabaseError> {
+        if !self.changed_catalog {
+            return Ok(self.inner.into_inner().commit().await?);
+        }
+        // the transactions beginning while the change is committed may see it or not, it
+        // ends even if the commit is dropped before it completes
+        let _change = self.plan_cache.begin_change();
+
+        Ok(self.inner.into_inner().commit().await?)
+    }
+}
+
+/// A session of a `Database`, see `Database::session`. Each query runs in a transaction
+/// of its own as `Database::query`, with the settings of the session.
+pub struct Session<'a, S: Storage> {
+    database: &'a Database<S>,
+    settings: SessionSettings,
+}
+
+impl<S: Storage> Session<'_, S> {
+    pub async fn run(&mut self, sql: &str) -> Result<Vec<Tuple>, DatabaseError> {
+        Ok(self.query(sql).await?.tuples)
+    }
+
+    /// Run SQL queries like `Database::query`, the `SET` statements of `sql` are kept
+    /// for the next queries of the session if its transaction is committed.
+    pub async fn query(&mut self, sql: &str) -> Result<ResultSet, DatabaseError> {
+        self.database
+            .query_with_settings(sql, &mut self.settings)
+            .await
+    }
+
+    /// A transaction with the settings of the session, a `SET` in it is only for the rest
+    /// of the transaction.
+    pub async fn new_transaction(&self) -> Result<DBTransaction<S>, DatabaseError> {
+        self.database
+            .wrap_transaction(
+                self.database.storage.transaction(),
+                IsolationLevel::RepeatableRead,
+                self.settings,
+            )
+            .await
+    }
+
+    /// The settings of the next queries of the session.
+    pub fn settings(&self) -> &SessionSettings {
+        &self.settings
+    }
+}
+
+/// A statement parsed by `Database::prepare`, run with different values of its parameters.
+#[derive(Debug, Clone)]
+pub struct PreparedStatement {
+    stmt: ParsedStatement,
+    parameters: Vec<(Parameter, LogicalType)>,
+}
+
+impl PreparedStatement {
+    /// The parameters with their inferred types, the positional ones first in order.
+    pub fn parameters(&self) -> &[(Parameter, LogicalType)] {
+        &self.parameters
+    }
+}
+
+/// The tuples of a statement and the columns they are made of,
+/// which are known even if there are no tuples.
+#[derive(Debug, Default)]
+pub struct ResultSet {
+    pub schema: SchemaRef,
+    pub tuples: Vec<Tuple>,
+}
+
+impl ResultSet {
+    /// `schema` is the output schema of the plan `stream` is built from, if it is known.
+    /// The tuples past `max_rows` are not read.
+    async fn collect(
+        schema: Option<SchemaRef>,
+        mut stream: BoxedExecutor,
+        max_rows: Option<usize>,
+    ) -> Result<Self, DatabaseError> {
+        let tuples = match max_rows {
+            Some(max_rows) => {
+                let mut tuples = Vec::new();
+
+                while tuples.len() < max_rows {
+                    match stream.try_next().await? {
+                        Some(tuple) => tuples.push(tuple),
+                        None => break,
+                    }
+                }
+                tuples
+            }
+            None => try_collect(&mut stream).await?,
+        };
+        let schema = schema
+            .or_else(|| tuples.first().map(|tuple| tuple.schema_ref.clone()))
+            .unwrap_or_default();
+
+        Ok(ResultSet { schema, tuples })
+    }
+}
+
+/// The number of query plans kept by the plan cache of a `Database`.
+const PLAN_CACHE_CAP: usize = 256;
+
+/// The optimized plans of queries keyed by their normalized SQL, which are shared by all
+/// transactions of a `Database` so that repeated queries skip binding and optimizing.
+///
+/// Every plan is tagged with the version of the cache it was built under, the version is
+/// bumped before and after a change of the catalog is committed, so that older plans are not
+/// used anymore. A transaction uses the plans of the version its snapshot is taken under,
+/// none if a change was being committed meanwhile, as its snapshot may see the change or not.
+pub(crate) struct PlanCache {
+    plans: Mutex<LruCache<String, (u64, LogicalPlan)>>,
+    version: AtomicU64,
+    // the changes of the catalog being committed
+    changing: AtomicUsize,
+    hits: AtomicUsize,
+}
+
+impl PlanCache {
+    fn new(cap: usize) -> Result<Self, DatabaseError> {
+        let plans =
+            LruCache::new(cap).map_err(|err| DatabaseError::InternalError(err.to_string()))?;
+
+        Ok(PlanCache {
+            plans: Mutex::new(plans),
+            version: AtomicU64::new(0),
+            changing: AtomicUsize::new(0),
+            hits: AtomicUsize::new(0),
+        })
+    }
+
+    fn version(&self) -> u64 {
+        self.version.load(Ordering::Acquire)
+    }
+
+    fn invalidate(&self) {
+        let _ = self.version.fetch_add(1, Ordering::AcqRel);
+    }
+
+    fn begin_change(&self) -> CatalogChange<'_> {
+        let _ = self.changing.fetch_add(1, Ordering::AcqRel);
+        self.invalidate();
+
+        CatalogChange(self)
+    }
+
+    /// The snapshot `begin` takes, with the version of the cache its catalog is the one of.
+    /// It is `None` if the version changed or a change of the catalog was being committed
+    /// while the snapshot was taken.
+    async fn snapshot<T>(
+        &self,
+        begin: impl Future<Output = Result<T, StorageError>>,
+    ) -> Result<(T, Option<u64>), DatabaseError> {
+        let version = self.version();
+        let snapshot = begin.await?;
+        let unchanged = self.changing.load(Ordering::Acquire) == 0 && self.version() == version;
+
+        Ok((snapshot, unchanged.then_some(version)))
+    }
+
+    /// The number of plans taken from the cache instead of being built.
+    #[cfg(test)]
+    fn hits(&self) -> usize {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    fn changes_catalog(stmt: &Statement) -> bool {
+        matches!(
+            stmt,
+            Statement::CreateTable { .. }
+                | Statement::CreateIndex { .. }
+                | Statement::AlterTable { .. }
+                | Statement::Drop { .. }
+                | Statement::Comment { .. }
+        )
+    }
+
+    /// The cached plan of a query built under `version`, otherwise the plan of `build_plan`,
+    /// which is cached if it is a query. Nothing is cached without a `version`.
+    fn get_or_plan<F>(
+        &self,
+        stmt: &Statement,
+        version: Option<u64>,
+        build_plan: F,
+    ) -> Result<LogicalPlan, DatabaseError>
+    where
+        F: FnOnce() -> Result<LogicalPlan, DatabaseError>,
+    {
+        let Some(version) = version.filter(|_| matches!(stmt, Statement::Query(_))) else {
+            return build_plan();
+        };
+        let key = stmt.to_string();
+
+        if let Some((plan_version, plan)) = self.plans.lock().get(&key) {
+            if *plan_version == version {
+                let _ = self.hits.fetch_add(1, Ordering::Relaxed);
+
+                return Ok(plan.clone());
+            }
+        }
+        let plan = build_plan()?;
+        // the catalog may have changed while the plan was built
+        if self.version() == version {
+            let _ = self.plans.lock().put(key, (version, plan.clone()));
+        }
+
+        Ok(plan)
+    }
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -149,41 +1120,127 @@ pub enum DatabaseError {
         ParserError,
     ),
     #[error("bind error: {0}")]
-    Bind(
-        #[source]
-        #[from]
-        BindError,
-    ),
+    Bind(#[source] BindError),
     #[error("Storage error: {0}")]
-    StorageError(
-        #[source]
-        #[from]
-        StorageError,
-    ),
+    StorageError(#[source] StorageError),
     #[error("executor error: {0}")]
-    ExecutorError(
-        #[source]
-        #[from]
-        ExecutorError,
-    ),
+    ExecutorError(#[source] ExecutorError),
     #[error("Internal error: {0}")]
     InternalError(String),
     #[error("optimizer error: {0}")]
-    OptimizerError(
-        #[source]
-        #[from]
-        OptimizerError,
-    ),
+    OptimizerError(#[source] OptimizerError),
+    /// `x / 0` or `x % 0`.
+    #[error("division by zero")]
+    DivideByZero,
+    /// The result of an arithmetic operation does not fit in its type.
+    #[error("{0} out of range")]
+    Overflow(LogicalType),
+    /// A value cannot be converted to the type it is cast to.
+    #[error("invalid cast: {0}")]
+    InvalidCast(#[source] TypeError),
+}
+
+impl DatabaseError {
+    /// The failures of evaluating an expression are the same whether the binder, the constant
+    /// folding of the optimizer or an executor evaluated it, `or` wraps the other errors.
+    fn from_type_error(err: TypeError, or: impl FnOnce(TypeError) -> DatabaseError) -> Self {
+        match err {
+            TypeError::DivisionByZero => DatabaseError::DivideByZero,
+            TypeError::Overflow(ty) => DatabaseError::Overflow(ty),
+            TypeError::CastFail
+            | TypeError::InvalidInput { .. }
+            | TypeError::TryFromInt(_)
+            | TypeError::ParseInt(_)
+            | TypeError::ParseBool(_)
+            | TypeError::ParseFloat(_)
+            | TypeError::ParseDate(_)
+            | TypeError::TryFromDecimal(_) => DatabaseError::InvalidCast(err),
+            err => or(err),
+        }
+    }
+}
+
+impl From<BindError> for DatabaseError {
+    fn from(err: BindError) -> Self {
+        match err {
+            BindError::TypeError(err) => {
+                Self::from_type_error(err, |err| DatabaseError::Bind(BindError::TypeError(err)))
+            }
+            err => DatabaseError::Bind(err),
+        }
+    }
+}
+
+impl From<StorageError> for DatabaseError {
+    fn from(err: StorageError) -> Self {
+        match err {
+            StorageError::TypeError(err) => Self::from_type_error(err, |err| {
+                DatabaseError::StorageError(StorageError::TypeError(err))
+            }),
+            err => DatabaseError::StorageError(err),
+        }
+    }
+}
+
+impl From<ExecutorError> for DatabaseError {
+    fn from(err: ExecutorError) -> Self {
+        match err {
+            ExecutorError::TypeError(err) => Self::from_type_error(err, |err| {
+                DatabaseError::ExecutorError(ExecutorError::TypeError(err))
+            }),
+            // e.g. a projection evaluated while the scan decodes the tuples
+            ExecutorError::StorageError(StorageError::TypeError(err)) => {
+                Self::from_type_error(err, |err| {
+                    DatabaseError::ExecutorError(ExecutorError::StorageError(
+                        StorageError::TypeError(err),
+                    ))
+                })
+            }
+            err => DatabaseError::ExecutorError(err),
+        }
+    }
+}
+
+impl From<OptimizerError> for DatabaseError {
+    fn from(err: OptimizerError) -> Self {
+        match err {
+            OptimizerError::TypeError(err) => Self::from_type_error(err, |err| {
+                DatabaseError::OptimizerError(OptimizerError::TypeError(err))
+            }),
+        }
+    }
+}
+
+/// A change of the catalog being committed, see `PlanCache::begin_change`. It ends once
+/// dropped.
+struct CatalogChange<'a>(&'a PlanCache);
+
+impl Drop for CatalogChange<'_> {
+    fn drop(&mut self) {
+        self.0.invalidate();
+        let _ = self.0.changing.fetch_sub(1, Ordering::AcqRel);
+    }
 }
 
 #[cfg(test)]
 mod test {
+    use crate::binder::copy::FileFormat;
+    use crate::binder::parameter::Parameter;
+    use crate::binder::BindError;
     use crate::catalog::{ColumnCatalog, ColumnDesc};
-    use crate::db::{Database, DatabaseError};
-    use crate::storage::{Storage, StorageError, Transaction};
-    use crate::types::tuple::create_table;
-    use crate::types::value::DataValue;
+    use crate::db::{Database, DatabaseConfig, DatabaseError, ResultSet};
+    use crate::execution::ExecutorError;
+    use crate::expression::function::{FunctionSignature, Volatility};
+    use crate::planner::operator::sort::NullsOrder;
+    use crate::storage::kip::{CatalogCacheConfig, KipStorage, StorageConfig, WalMode};
+    use crate::storage::{IsolationLevel, Storage, StorageError, Transaction};
+    use crate::types::tuple::{create_table, Tuple};
+    use crate::types::value::{DataValue, ValueRef};
     use crate::types::LogicalType;
+    use itertools::Itertools;
+    use serde_json::json;
+    use std::fs::{self, File};
+    use std::sync::atomic::{AtomicI64, Ordering};
     use std::sync::Arc;
     use tempfile::TempDir;
 
@@ -202,7 +1259,7 @@ mod test {
                 None,
             ),
         ];
-        let _ = transaction.create_table(Arc::new("t1".to_string()), columns, false)?;
+        let _ = transaction.create_table(Arc::new("t1".to_string()), columns, vec![], false)?;
         transaction.commit().await?;
 
         Ok(())
@@ -222,11 +1279,43 @@ mod test {
     }
 
     #[tokio::test]
-    async fn test_transaction_sql() -> Result<(), DatabaseError> {
+    async fn test_execute_script() -> Result<(), DatabaseError> {
         let temp_dir = TempDir::new().expect("unable to create temporary working directory");
         let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+        let count =
+            || async { Ok::<_, DatabaseError>(kipsql.run("select * from t1").await?.len()) };
+
+        let results = kipsql
+            .execute_script(
+                "create table t1 (a int primary key, b int);
+                 insert into t1 values (1, 1), (2, 2);
+                 select b from t1 where a > 1;",
+                true,
+            )
+            .await?;
+        assert_eq!(results.len(), 3);
+        assert_eq!(
+            results[2].tuples[0].values,
+            vec![ValueRef::new(DataValue::Int32(Some(2)))]
+        );
+        assert_eq!(results[2].schema[0].name(), "b");
 
-        let mut tx_1 = kipsql.new_transaction().await?;
+        // the statements before the failing one are rolled back in a single transaction
+        let script = "insert into t1 values (3, 3); insert into t2 values (1);";
+        assert!(kipsql.execute_script(script, true).await.is_err());
+        assert_eq!(count().await?, 2);
+        assert!(kipsql.execute_script(script, false).await.is_err());
+        assert_eq!(count().await?, 3);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_transaction_sql() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+
+        let mut tx_1 = kipsql.new_transaction().await?;
         let mut tx_2 = kipsql.new_transaction().await?;
 
         let _ = tx_1
@@ -251,36 +1340,2242 @@ mod test {
         assert_eq!(
             tuples_1[0].values,
             vec![
-                Arc::new(DataValue::Int32(Some(0))),
-                Arc::new(DataValue::Int32(Some(0)))
+                ValueRef::new(DataValue::Int32(Some(0))),
+                ValueRef::new(DataValue::Int32(Some(0)))
             ]
         );
         assert_eq!(
             tuples_1[1].values,
             vec![
-                Arc::new(DataValue::Int32(Some(1))),
-                Arc::new(DataValue::Int32(Some(1)))
+                ValueRef::new(DataValue::Int32(Some(1))),
+                ValueRef::new(DataValue::Int32(Some(1)))
             ]
         );
 
         assert_eq!(
             tuples_2[0].values,
             vec![
-                Arc::new(DataValue::Int32(Some(2))),
-                Arc::new(DataValue::Int32(Some(2)))
+                ValueRef::new(DataValue::Int32(Some(2))),
+                ValueRef::new(DataValue::Int32(Some(2)))
             ]
         );
         assert_eq!(
             tuples_2[1].values,
             vec![
-                Arc::new(DataValue::Int32(Some(3))),
-                Arc::new(DataValue::Int32(Some(3)))
+                ValueRef::new(DataValue::Int32(Some(3))),
+                ValueRef::new(DataValue::Int32(Some(3)))
             ]
         );
 
         tx_1.commit().await?;
 
-        assert!(tx_2.commit().await.is_err());
+        assert!(matches!(
+            tx_2.commit().await,
+            Err(DatabaseError::StorageError(
+                StorageError::TransactionConflict
+            ))
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_transaction_conflict() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+        let _ = kipsql
+            .run("create table t1 (a int primary key, b int); insert into t1 values (1, 0)")
+            .await?;
+
+        let mut tx_1 = kipsql.new_transaction().await?;
+        let mut tx_2 = kipsql.new_transaction().await?;
+        let _ = tx_1.run("update t1 set b = 1 where a = 1").await?;
+        let _ = tx_2.run("update t1 set b = 2 where a = 1").await?;
+
+        let results = [tx_1.commit().await, tx_2.commit().await];
+        assert_eq!(results.iter().filter(|result| result.is_ok()).count(), 1);
+        assert!(results.iter().any(|result| matches!(
+            result,
+            Err(DatabaseError::StorageError(
+                StorageError::TransactionConflict
+            ))
+        )));
+
+        let tuples = kipsql.run("select b from t1").await?;
+        assert_eq!(
+            tuples[0].values,
+            vec![ValueRef::new(DataValue::Int32(Some(1)))]
+        );
+
+        // a retry on a fresh transaction goes through
+        let mut tx_3 = kipsql.new_transaction().await?;
+        let _ = tx_3.run("update t1 set b = 2 where a = 1").await?;
+        tx_3.commit().await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_select_for_update() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+        let _ = kipsql
+            .run("create table t1 (a int primary key, b int); insert into t1 values (1, 0), (2, 0)")
+            .await?;
+
+        let mut tx_1 = kipsql.new_transaction().await?;
+        let mut tx_2 = kipsql.new_transaction().await?;
+        let mut tx_3 = kipsql.new_transaction().await?;
+        let tuples = tx_1.run("select b from t1 where a = 1 for update").await?;
+        assert_eq!(tuples.len(), 1);
+        let _ = tx_2.run("update t1 set b = 2 where a = 1").await?;
+        let _ = tx_3.run("update t1 set b = 3 where a = 2").await?;
+
+        tx_1.commit().await?;
+        // the locked row conflicts, the other one does not
+        assert!(matches!(
+            tx_2.commit().await,
+            Err(DatabaseError::StorageError(
+                StorageError::TransactionConflict
+            ))
+        ));
+        tx_3.commit().await?;
+
+        let tuples = kipsql.run("select b from t1").await?;
+        assert_eq!(
+            tuples
+                .iter()
+                .map(|tuple| tuple.values.clone())
+                .collect_vec(),
+            vec![
+                vec![ValueRef::new(DataValue::Int32(Some(0)))],
+                vec![ValueRef::new(DataValue::Int32(Some(3)))],
+            ]
+        );
+        assert!(matches!(
+            kipsql
+                .run("select b from t1 where a = 1 for update of t1")
+                .await,
+            Err(DatabaseError::Bind(BindError::UnsupportedStmt(_)))
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_read_transaction() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+        let _ = kipsql
+            .run("create table t1 (a int primary key, b int); insert into t1 values (1, 0)")
+            .await?;
+
+        let mut tx = kipsql.read_transaction().await?;
+        for sql in [
+            "insert into t1 values (2, 0)",
+            "update t1 set b = 1 where a = 1",
+            "delete from t1 where a = 1",
+            "create table t2 (a int primary key)",
+            "drop table t1",
+            "select b from t1 where a = 1 for update",
+        ] {
+            assert!(
+                matches!(
+                    tx.run(sql).await,
+                    Err(DatabaseError::ExecutorError(ExecutorError::StorageError(
+                        StorageError::ReadOnly
+                    )))
+                ),
+                "{}",
+                sql
+            );
+        }
+        let tuples = tx.run("select b from t1").await?;
+        assert_eq!(tuples.len(), 1);
+        tx.commit().await?;
+
+        assert_eq!(kipsql.run("select * from t1").await?.len(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_read_transaction_snapshot() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+        let _ = kipsql
+            .run("create table t1 (a int primary key, b int); insert into t1 values (1, 0)")
+            .await?;
+
+        let mut snapshot = kipsql.read_transaction().await?;
+        let count = "select count(*) from t1";
+        assert_eq!(
+            snapshot.run(count).await?[0].values,
+            vec![ValueRef::new(DataValue::Int32(Some(1)))]
+        );
+
+        let values = (2..500).map(|i| format!("({}, {})", i, i)).join(", ");
+        let mut tx = kipsql.new_transaction().await?;
+        let _ = tx.run(&format!("insert into t1 values {}", values)).await?;
+        let _ = tx.run("update t1 set b = 1 where a = 1").await?;
+        tx.commit().await?;
+        let _ = kipsql.run("delete from t1 where a = 2").await?;
+
+        // the commits after the snapshot began are not seen, whatever is read
+        assert_eq!(
+            snapshot.run(count).await?[0].values,
+            vec![ValueRef::new(DataValue::Int32(Some(1)))]
+        );
+        assert_eq!(
+            snapshot.run("select b from t1 where a = 1").await?[0].values,
+            vec![ValueRef::new(DataValue::Int32(Some(0)))]
+        );
+        assert!(snapshot
+            .run("select * from t1 where a > 1")
+            .await?
+            .is_empty());
+        snapshot.commit().await?;
+
+        assert_eq!(
+            kipsql.run(count).await?[0].values,
+            vec![ValueRef::new(DataValue::Int32(Some(498)))]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_isolation_level() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+        let _ = kipsql
+            .run("create table t1 (a int primary key, b int); insert into t1 values (0, 0), (1, 1)")
+            .await?;
+        let select = "select a, b from t1 order by a";
+
+        let mut repeatable = kipsql
+            .new_transaction_with_isolation(IsolationLevel::RepeatableRead)
+            .await?;
+        let mut committed = kipsql
+            .new_transaction_with_isolation(IsolationLevel::ReadCommitted)
+            .await?;
+        assert_eq!(repeatable.run(select).await?.len(), 2);
+        let _ = committed.run("delete from t1 where a = 0").await?;
+        let _ = committed.run("insert into t1 values (3, 3)").await?;
+
+        let _ = kipsql
+            .run("insert into t1 values (2, 2); update t1 set b = 10 where a = 1")
+            .await?;
+
+        // the re-read sees the same rows as the first one despite the commit in between
+        let tuples = repeatable.run(select).await?;
+        assert_eq!(
+            tuples
+                .iter()
+                .map(|tuple| tuple.values.clone())
+                .collect_vec(),
+            vec![
+                vec![
+                    ValueRef::new(DataValue::Int32(Some(0))),
+                    ValueRef::new(DataValue::Int32(Some(0)))
+                ],
+                vec![
+                    ValueRef::new(DataValue::Int32(Some(1))),
+                    ValueRef::new(DataValue::Int32(Some(1)))
+                ],
+            ]
+        );
+        repeatable.commit().await?;
+
+        // the commit is seen along with the writes of the transaction itself
+        let tuples = committed.run(select).await?;
+        assert_eq!(
+            tuples
+                .iter()
+                .map(|tuple| tuple.values.clone())
+                .collect_vec(),
+            [(1, 10), (2, 2), (3, 3)]
+                .into_iter()
+                .map(|(a, b)| vec![
+                    ValueRef::new(DataValue::Int32(Some(a))),
+                    ValueRef::new(DataValue::Int32(Some(b)))
+                ])
+                .collect_vec()
+        );
+        let _ = committed.run("update t1 set b = 11 where a = 1").await?;
+        committed.commit().await?;
+        assert_eq!(
+            kipsql.run("select b from t1 where a = 1").await?[0].values,
+            vec![ValueRef::new(DataValue::Int32(Some(11)))]
+        );
+        assert_eq!(kipsql.run(select).await?.len(), 3);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_flush() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let values = (0..1000).map(|i| format!("({}, {})", i, i * 2)).join(", ");
+
+        for wal_mode in [WalMode::Buffered, WalMode::Direct] {
+            let path = temp_dir.path().join(format!("{:?}", wal_mode));
+            let config = StorageConfig {
+                wal_mode,
+                ..Default::default()
+            };
+            {
+                let kipsql = Database::new(KipStorage::new_with_config(&path, config).await?)?;
+                let _ = kipsql
+                    .run("create table t1 (a int primary key, b int)")
+                    .await?;
+                let _ = kipsql
+                    .run(&format!("insert into t1 values {}", values))
+                    .await?;
+                kipsql.flush().await?;
+            }
+            let kipsql = Database::new(KipStorage::new_with_config(&path, config).await?)?;
+            let tuples = kipsql.run("select count(*), sum(b) from t1").await?;
+
+            assert_eq!(
+                tuples[0].values,
+                vec![
+                    ValueRef::new(DataValue::Int32(Some(1000))),
+                    ValueRef::new(DataValue::Int32(Some(999_000))),
+                ]
+            );
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_table_row_count() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+        let _ = kipsql
+            .run("create table t1 (a int primary key, b int); create table t2 (a int, b int)")
+            .await?;
+        assert_eq!(kipsql.table_row_count("t1").await?, 0);
+
+        let values = (0..100).map(|i| format!("({}, {})", i, i % 7)).join(", ");
+        let _ = kipsql
+            .run(&format!("insert into t1 values {}", values))
+            .await?;
+        let _ = kipsql
+            .run(&format!("insert into t2 values {}", values))
+            .await?;
+        assert_eq!(kipsql.table_row_count("t1").await?, 100);
+        assert_eq!(kipsql.table_row_count("T2").await?, 100);
+
+        let _ = kipsql.run("delete from t1 where b = 0").await?;
+        let _ = kipsql.run("delete from t2 where a >= 50").await?;
+        let _ = kipsql.run("update t1 set b = 7 where a < 10").await?;
+        let _ = kipsql.run("insert into t1 values (100, 0)").await?;
+        assert_eq!(kipsql.table_row_count("t1").await?, 86);
+        assert_eq!(kipsql.table_row_count("t2").await?, 50);
+
+        // the rows of a transaction are counted once it commits
+        let mut transaction = kipsql.new_transaction().await?;
+        let _ = transaction.run("delete from t1").await?;
+        assert_eq!(kipsql.table_row_count("t1").await?, 86);
+        transaction.commit().await?;
+        assert_eq!(kipsql.table_row_count("t1").await?, 0);
+
+        kipsql.flush().await?;
+        assert_eq!(kipsql.table_row_count("t2").await?, 50);
+        assert!(matches!(
+            kipsql.table_row_count("t3").await,
+            Err(DatabaseError::StorageError(StorageError::TableNotFound))
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_export() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+        let _ = kipsql
+            .run("create table t1 (a int primary key, b varchar null, c int)")
+            .await?;
+        let _ = kipsql
+            .run("insert into t1 values (1, 'one', 1), (2, 'two, three', 2), (3, null, 3)")
+            .await?;
+
+        let path = temp_dir.path().join("export.csv");
+        let csv = FileFormat::Csv {
+            delimiter: ',',
+            quote: '"',
+            escape: None,
+            header: true,
+        };
+        let rows = kipsql
+            .export(
+                "select a, b, c * 10 as d from t1 where a > 1 order by a",
+                File::create(&path).unwrap(),
+                &csv,
+            )
+            .await?;
+        assert_eq!(rows, 2);
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "a,b,d\n2,\"two, three\",20\n3,null,30\n"
+        );
+
+        // the file is read back by `COPY FROM`
+        let _ = kipsql
+            .run("create table t2 (a int primary key, b varchar null, d int)")
+            .await?;
+        let _ = kipsql
+            .run(&format!(
+                "copy t2 from '{}' ( HEADER true )",
+                path.display()
+            ))
+            .await?;
+        let tuples = kipsql.run("select * from t2").await?;
+        assert_eq!(
+            tuples
+                .iter()
+                .map(|tuple| tuple.values.clone())
+                .collect_vec(),
+            vec![
+                vec![
+                    ValueRef::new(DataValue::Int32(Some(2))),
+                    ValueRef::new(DataValue::Utf8(Some("two, three".to_string()))),
+                    ValueRef::new(DataValue::Int32(Some(20))),
+                ],
+                vec![
+                    ValueRef::new(DataValue::Int32(Some(3))),
+                    ValueRef::new(DataValue::Utf8(None)),
+                    ValueRef::new(DataValue::Int32(Some(30))),
+                ],
+            ]
+        );
+
+        let tsv = FileFormat::Csv {
+            delimiter: '\t',
+            quote: '"',
+            escape: None,
+            header: true,
+        };
+        let mut buf = Vec::new();
+        let rows = kipsql
+            .export(
+                "select t1.a, t2.d from t1 join t2 on t1.a = t2.a order by t1.a",
+                &mut buf,
+                &tsv,
+            )
+            .await?;
+        assert_eq!(rows, 2);
+        assert_eq!(String::from_utf8(buf).unwrap(), "a\td\n2\t20\n3\t30\n");
+
+        assert!(matches!(
+            kipsql.export("delete from t1", Vec::new(), &csv).await,
+            Err(DatabaseError::Bind(BindError::UnsupportedStmt(_)))
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_explain_plan() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+        let _ = kipsql
+            .run("create table t1 (id int primary key, v int, c varchar null unique)")
+            .await?;
+        let _ = kipsql
+            .run("create table t2 (id int primary key, w int)")
+            .await?;
+
+        // the filters pushed down
+        for (sql, plan) in [
+            (
+                "select id from t1 where v > 1",
+                vec!["SeqScan t1 -> [id], Filter (v > 1)"],
+            ),
+            (
+                "select t1.id, w from t1 join t2 on t1.id = t2.id where w > 2 and v < 3",
+                vec![
+                    "Projection [id, w]",
+                    "  Inner Join On id = id",
+                    "    SeqScan t1 -> [id, v], Filter (v < 3)",
+                    "    SeqScan t2 -> [id, w], Filter (w > 2)",
+                ],
+            ),
+        ] {
+            assert_eq!(kipsql.explain_plan(sql).await?, plan.join("\n"), "{}", sql);
+        }
+        // the indexes chosen
+        for (sql, plan) in [
+            (
+                "select * from t1 where id = 1",
+                vec![
+                    "Projection [id, v, c]",
+                    "  Filter (id = 1)",
+                    "    IndexScan t1 -> [id, v, c], Index pk_id",
+                ],
+            ),
+            (
+                "select id from t1 where c = 'a' and v > 1",
+                vec!["IndexScan t1 -> [id], Index uk_c, Filter ((c = a) && (v > 1))"],
+            ),
+            (
+                "select c from t1 where c > 'a'",
+                vec![
+                    "Projection [c]",
+                    "  Filter (c > a)",
+                    "    IndexScan t1 -> [c], Index uk_c Only",
+                ],
+            ),
+        ] {
+            assert_eq!(kipsql.explain_plan(sql).await?, plan.join("\n"), "{}", sql);
+        }
+
+        // the directory of the file is random
+        let path = temp_dir.path().join("t1.csv");
+        assert_eq!(
+            kipsql
+                .explain_plan(&format!("copy t1 from '{}'", path.display()))
+                .await?,
+            "Copy $TMP/t1.csv -> t1"
+        );
+        assert!(kipsql.explain_plan("select 1; select 2").await.is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_explain_json() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+        let _ = kipsql
+            .run("create table t1 (id int primary key, v int)")
+            .await?;
+
+        let explain_json = |tuples: Vec<Tuple>| -> serde_json::Value {
+            assert_eq!(tuples.len(), 1);
+            serde_json::from_str(&tuples[0].values[0].to_string()).unwrap()
+        };
+        let plan = explain_json(
+            kipsql
+                .run("explain format json select id from t1 where v > 1")
+                .await?,
+        );
+        assert_eq!(
+            plan,
+            json!({
+                "operator": "Scan",
+                "detail": "SeqScan t1 -> [id], Filter (v > 1)",
+                "table": "t1",
+                "index": null,
+                "index_intersection": [],
+                "index_only": false,
+                "filter": "(v > 1)",
+                "agg_calls": [],
+                "limit": null,
+                "offset": null,
+                "columns": ["id"],
+                "children": [],
+            })
+        );
+
+        // the bounds of an index scan
+        let plan = explain_json(
+            kipsql
+                .run("explain format json select * from t1 where id > 1 and id <= 5")
+                .await?,
+        );
+        assert_eq!(plan["operator"], "Project");
+        assert_eq!(plan["children"][0]["operator"], "Filter");
+
+        let scan = &plan["children"][0]["children"][0];
+        assert_eq!(scan["operator"], "Scan");
+        assert_eq!(scan["index"]["bounds"], json!(["(1, 5]"]));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_prepared_statement() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+        let _ = kipsql
+            .run("create table t1 (a int primary key, b bigint null, c varchar(5) null)")
+            .await?;
+
+        let insert = kipsql.prepare("insert into t1 values ($1, :b, :c)").await?;
+        assert_eq!(
+            insert.parameters(),
+            &[
+                (Parameter::Positional(1), LogicalType::Integer),
+                (Parameter::from("b"), LogicalType::Bigint),
+                (Parameter::from("c"), LogicalType::Varchar(Some(5))),
+            ]
+        );
+        for (a, b, c) in [(0, Some(10), "x"), (1, None, "y"), (2, Some(30), "x")] {
+            let _ = kipsql
+                .execute_prepared(
+                    &insert,
+                    &[
+                        (Parameter::from(1), DataValue::Int32(Some(a))),
+                        (Parameter::from("b"), DataValue::Int64(b)),
+                        (Parameter::from("c"), DataValue::Utf8(Some(c.to_string()))),
+                    ],
+                )
+                .await?;
+        }
+
+        let select = kipsql
+            .prepare("select a from t1 where (b > $1 or a in ($2)) and c like :pattern")
+            .await?;
+        assert_eq!(
+            select.parameters(),
+            &[
+                (Parameter::from(1), LogicalType::Bigint),
+                (Parameter::from(2), LogicalType::Integer),
+                (Parameter::from("pattern"), LogicalType::Varchar(None)),
+            ]
+        );
+        // an integer is implicitly cast to the bigint of `b`
+        let result = kipsql
+            .execute_prepared(
+                &select,
+                &[
+                    (Parameter::from(1), DataValue::Int32(Some(20))),
+                    (Parameter::from(2), DataValue::Int32(Some(1))),
+                    (
+                        Parameter::from("pattern"),
+                        DataValue::Utf8(Some("x".to_string())),
+                    ),
+                ],
+            )
+            .await?;
+        assert_eq!(
+            result
+                .tuples
+                .iter()
+                .map(|tuple| &tuple.values)
+                .collect_vec(),
+            vec![&vec![ValueRef::new(DataValue::Int32(Some(2)))]]
+        );
+
+        let update = kipsql.prepare("update t1 set b = $2 where a = $1").await?;
+        assert_eq!(
+            update.parameters(),
+            &[
+                (Parameter::from(1), LogicalType::Integer),
+                (Parameter::from(2), LogicalType::Bigint),
+            ]
+        );
+        let cast = kipsql.prepare("select cast($1 as bigint) from t1").await?;
+        assert_eq!(
+            cast.parameters(),
+            &[(Parameter::from(1), LogicalType::Bigint)]
+        );
+        let _ = kipsql
+            .execute_prepared(
+                &update,
+                &[
+                    (Parameter::from(1), DataValue::Int32(Some(1))),
+                    (Parameter::from(2), DataValue::Int64(Some(20))),
+                ],
+            )
+            .await?;
+        let tuples = kipsql.run("select b from t1 where a = 1").await?;
+        assert_eq!(
+            tuples[0].values,
+            vec![ValueRef::new(DataValue::Int64(Some(20)))]
+        );
+
+        for values in [
+            vec![(Parameter::from(1), DataValue::Utf8(Some("1".to_string())))],
+            vec![(Parameter::from(1), DataValue::Int64(Some(1)))],
+            vec![],
+            vec![
+                (Parameter::from(1), DataValue::Int32(Some(1))),
+                (Parameter::from("a"), DataValue::Int32(Some(1))),
+            ],
+        ] {
+            let delete = kipsql.prepare("delete from t1 where a = $1").await?;
+            assert!(matches!(
+                kipsql.execute_prepared(&delete, &values).await,
+                Err(DatabaseError::Bind(BindError::Parameter(_)))
+            ));
+        }
+        for sql in [
+            "select a from t1 where $1 = $2",
+            "select $1 from t1",
+            "select a from t1 where a = ?",
+        ] {
+            assert!(matches!(
+                kipsql.prepare(sql).await,
+                Err(DatabaseError::Bind(BindError::Parameter(_)))
+            ));
+        }
+        assert!(matches!(
+            kipsql.run("select a from t1 where a = $1").await,
+            Err(DatabaseError::Bind(BindError::Parameter(_)))
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_transaction_retry() -> Result<(), DatabaseError> {
+        async fn retry(
+            kipsql: &Arc<Database<KipStorage>>,
+            max_attempts: usize,
+        ) -> Result<usize, DatabaseError> {
+            let concurrent = kipsql.clone();
+            let mut attempts = 0;
+
+            kipsql
+                .transaction_retry(
+                    move |tx| {
+                        let concurrent = concurrent.clone();
+                        attempts += 1;
+                        let attempt = attempts;
+
+                        Box::pin(async move {
+                            let _ = tx.run("update t1 set b = 1 where a = 1").await?;
+                            // a concurrent write to the same row commits first on the first attempt
+                            if attempt == 1 {
+                                let _ = concurrent.run("update t1 set b = 10 where a = 1").await?;
+                            }
+                            Ok(attempt)
+                        })
+                    },
+                    max_attempts,
+                )
+                .await
+        }
+
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Arc::new(Database::with_kipdb(temp_dir.path()).await?);
+        let _ = kipsql
+            .run("create table t1 (a int primary key, b int); insert into t1 values (1, 0)")
+            .await?;
+
+        assert_eq!(retry(&kipsql, 3).await?, 2);
+        let tuples = kipsql.run("select b from t1").await?;
+        assert_eq!(
+            tuples[0].values,
+            vec![ValueRef::new(DataValue::Int32(Some(1)))]
+        );
+
+        assert!(matches!(
+            retry(&kipsql, 1).await,
+            Err(DatabaseError::StorageError(
+                StorageError::TransactionConflict
+            ))
+        ));
+        let tuples = kipsql.run("select b from t1").await?;
+        assert_eq!(
+            tuples[0].values,
+            vec![ValueRef::new(DataValue::Int32(Some(10)))]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_register_function() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let mut kipsql = Database::with_kipdb(temp_dir.path()).await?;
+        kipsql.register_function(
+            "double",
+            FunctionSignature::new(vec![LogicalType::Bigint], LogicalType::Bigint),
+            |args| Ok(DataValue::Int64(args[0].i64().map(|v| v * 2))),
+        );
+
+        let _ = kipsql
+            .run("create table t1 (a int primary key, b int null)")
+            .await?;
+        let _ = kipsql
+            .run("insert into t1 values (1, 10), (2, null), (3, 30)")
+            .await?;
+
+        let tuples = kipsql
+            .run("select a, double(b) from t1 where double(a) > 2")
+            .await?;
+        assert_eq!(tuples[0].schema_ref[1].name(), "double(b)");
+        assert_eq!(
+            tuples.into_iter().map(|tuple| tuple.values).collect_vec(),
+            vec![
+                vec![
+                    ValueRef::new(DataValue::Int32(Some(2))),
+                    ValueRef::new(DataValue::Int64(None))
+                ],
+                vec![
+                    ValueRef::new(DataValue::Int32(Some(3))),
+                    ValueRef::new(DataValue::Int64(Some(60)))
+                ],
+            ]
+        );
+
+        let mut tx = kipsql.new_transaction().await?;
+        let tuples = tx.run("select DOUBLE(a) from t1 where a = 1").await?;
+        assert_eq!(
+            tuples[0].values,
+            vec![ValueRef::new(DataValue::Int64(Some(2)))]
+        );
+
+        // evaluated for each row instead of once while planning
+        let counter = Arc::new(AtomicI64::new(0));
+        kipsql.register_function(
+            "next_id",
+            FunctionSignature::new(vec![LogicalType::Bigint], LogicalType::Bigint)
+                .with_volatility(Volatility::Volatile),
+            move |args| {
+                let id = counter.fetch_add(1, Ordering::Relaxed);
+                Ok(DataValue::Int64(args[0].i64().map(|start| start + id)))
+            },
+        );
+        let tuples = kipsql.run("select next_id(100) from t1").await?;
+        assert_eq!(
+            tuples.into_iter().map(|tuple| tuple.values).collect_vec(),
+            (100..103)
+                .map(|id| vec![ValueRef::new(DataValue::Int64(Some(id)))])
+                .collect_vec()
+        );
+
+        assert!(kipsql.run("select triple(a) from t1").await.is_err());
+        assert!(kipsql.run("select double(a, b) from t1").await.is_err());
+        assert!(kipsql.run("select double(a > b) from t1").await.is_err());
+
+        // the registered functions cannot be restored from the catalog
+        assert!(kipsql
+            .run(
+                "create table t2 (a int primary key, b bigint,
+                 c bigint generated always as (double(b)) stored)"
+            )
+            .await
+            .is_err());
+        assert!(kipsql
+            .run("create table t2 (a int primary key, b bigint default double(1))")
+            .await
+            .is_err());
+        assert!(kipsql
+            .run("create table t2 (a int primary key, b bigint on update double(1))")
+            .await
+            .is_err());
+        assert!(kipsql.run("select * from t2").await.is_err());
+        let _ = kipsql.run("select * from t1").await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_dump() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+        let _ = kipsql
+            .run(
+                "create table t1 (a int primary key, b varchar(10) null, c double null default -1.5, \
+                 d date null, e bigint unsigned null, f decimal(10, 2) null, g int null, h int null, \
+                 unique (g, h))",
+            )
+            .await?;
+        let _ = kipsql
+            .run(
+                "insert into t1 values (1, 'it''s', 0.1, '2023-01-01', '18446744073709551615', 1.25, 1, 2), \
+                 (2, null, null, null, null, null, null, null), (-3, '', 1e20, '1999-12-31', 0, -0.5, 1, 3)",
+            )
+            .await?;
+        let dump = kipsql.dump("t1").await?;
+        println!("{}", dump);
+
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let restored = Database::with_kipdb(temp_dir.path()).await?;
+        let _ = restored.run(&dump).await?;
+
+        let select = "select * from t1";
+        assert_eq!(kipsql.run(select).await?, restored.run(select).await?);
+        assert_eq!(restored.dump("t1").await?, dump);
+        // the unique key is restored as well
+        assert!(restored
+            .run("insert into t1 (a, g, h) values (4, 1, 2)")
+            .await
+            .is_err());
+        assert!(kipsql.dump("t2").await.is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_dump_generated_columns() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+        let _ = kipsql
+            .run(
+                "create table t1 (a int primary key, b int null, \
+                 c int null generated always as (a + b) stored, d varchar, \
+                 e int null generated always as (b * 2) stored); \
+                 insert into t1 (a, b, d) values (1, 10, 'x'), (2, null, 'y')",
+            )
+            .await?;
+        let dump = kipsql.dump("t1").await?;
+        assert!(dump.contains("INSERT INTO t1 (a, b, d) VALUES"));
+
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let restored = Database::with_kipdb(temp_dir.path()).await?;
+        let _ = restored.run(&dump).await?;
+
+        let select = "select * from t1";
+        assert_eq!(kipsql.run(select).await?, restored.run(select).await?);
+        assert_eq!(restored.dump("t1").await?, dump);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_dump_identity() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+        let _ = kipsql
+            .run(
+                "create table t1 (id int generated always as identity primary key, name varchar); \
+                 create table t2 (id bigint generated by default as identity primary key, name varchar); \
+                 insert into t1 (name) values ('a'), ('b'), ('c'); \
+                 insert into t2 (name) values ('a'), ('b'); \
+                 insert into t2 (id, name) values (10, 'c'); \
+                 delete from t1 where id = 2",
+            )
+            .await?;
+        let dump = format!("{}{}", kipsql.dump("t1").await?, kipsql.dump("t2").await?);
+
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let restored = Database::with_kipdb(temp_dir.path()).await?;
+        let _ = restored.run(&dump).await?;
+
+        for table_name in ["t1", "t2"] {
+            let select = format!("select * from {}", table_name);
+            assert_eq!(kipsql.run(&select).await?, restored.run(&select).await?);
+        }
+        assert_eq!(
+            format!(
+                "{}{}",
+                restored.dump("t1").await?,
+                restored.dump("t2").await?
+            ),
+            dump
+        );
+        // the ids are generated after the restored ones
+        let tuples = restored
+            .run("insert into t1 (name) values ('d') returning id")
+            .await?;
+        assert_eq!(
+            tuples[0].values,
+            vec![ValueRef::new(DataValue::Int32(Some(4)))]
+        );
+        let tuples = restored
+            .run("insert into t2 (name) values ('d') returning id")
+            .await?;
+        assert_eq!(
+            tuples[0].values,
+            vec![ValueRef::new(DataValue::Int64(Some(11)))]
+        );
+        // the ids are only written by the dump
+        assert!(restored
+            .run("insert into t1 (id, name) values (5, 'e')")
+            .await
+            .is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_show_index() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+        let _ = kipsql
+            .run(
+                "create table t1 (a int primary key, b int null unique, c int, d int, unique (c, d)); \
+                 insert into t1 values (1, 1, 1, 1), (2, 2, 1, 2), (3, null, 2, 1)",
+            )
+            .await?;
+
+        let tuples = kipsql.run("show index from t1").await?;
+        assert_eq!(
+            tuples
+                .iter()
+                .map(|tuple| tuple.values[1..6]
+                    .iter()
+                    .map(|value| value.to_string())
+                    .join(" "))
+                .collect_vec(),
+            vec![
+                "pk_a a true true 3",
+                "uk_b b true false 2",
+                "uk_c_d c, d true false 3",
+            ]
+        );
+
+        let transaction = kipsql.storage.transaction().await?;
+        let stats = transaction.index_stats(Arc::new("t1".to_string()))?;
+        assert!(stats.iter().all(|(_, stats)| stats.bytes > 0));
+        assert!(matches!(
+            kipsql.run("show index from t2").await,
+            Err(DatabaseError::ExecutorError(ExecutorError::StorageError(
+                StorageError::TableNotFound
+            )))
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_comment() -> Result<(), DatabaseError> {
+        async fn comments(
+            kipsql: &Database<KipStorage>,
+        ) -> Result<(Option<String>, Vec<Option<String>>), DatabaseError> {
+            let transaction = kipsql.storage.transaction().await?;
+            let table = transaction
+                .table(Arc::new("t1".to_string()))
+                .ok_or(StorageError::TableNotFound)?;
+
+            Ok((
+                table.comment.clone(),
+                table
+                    .visible_columns()
+                    .iter()
+                    .map(|column| column.desc.comment.clone())
+                    .collect_vec(),
+            ))
+        }
+
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+        let _ = kipsql
+            .run("create table t1 (a int primary key, b int comment 'the b''s value')")
+            .await?;
+        let _ = kipsql
+            .run("comment on table t1 is 'numbers'; comment on column t1.a is 'key'")
+            .await?;
+        assert_eq!(
+            comments(&kipsql).await?,
+            (
+                Some("numbers".to_string()),
+                vec![Some("key".to_string()), Some("the b's value".to_string())]
+            )
+        );
+
+        // the comments are part of the definition of the table
+        let ddl = kipsql.run("show create table t1").await?[0].values[1].to_string();
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let restored = Database::with_kipdb(temp_dir.path()).await?;
+        let _ = restored.run(&ddl).await?;
+        assert_eq!(comments(&restored).await?, comments(&kipsql).await?);
+
+        let _ = kipsql.run("comment on column t1.b is null").await?;
+        assert_eq!(
+            comments(&kipsql).await?.1,
+            vec![Some("key".to_string()), None]
+        );
+        assert!(kipsql
+            .run("comment on column t1.c is 'missing'")
+            .await
+            .is_err());
+
+        let _ = kipsql
+            .run("drop table t1; create table t1 (a int primary key, b int)")
+            .await?;
+        assert_eq!(comments(&kipsql).await?, (None, vec![None, None]));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_show_create_table() -> Result<(), DatabaseError> {
+        async fn show_create_table(
+            kipsql: &Database<KipStorage>,
+            table_name: &str,
+        ) -> Result<String, DatabaseError> {
+            let tuples = kipsql
+                .run(&format!("show create table {}", table_name))
+                .await?;
+            assert_eq!(tuples.len(), 1);
+            assert_eq!(tuples[0].values[0].to_string(), table_name);
+
+            Ok(tuples[0].values[1].to_string())
+        }
+
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+        let _ = kipsql
+            .run(
+                "create table t1 (a int primary key, b varchar(10) not null unique, \
+                 c double null default 0.5, d int null, e int null, \
+                 f int null generated always as (d + e) stored, unique (d, e))",
+            )
+            .await?;
+        let ddl = show_create_table(&kipsql, "t1").await?;
+        println!("{}", ddl);
+
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let restored = Database::with_kipdb(temp_dir.path()).await?;
+        let _ = restored.run(&ddl).await?;
+
+        assert_eq!(show_create_table(&restored, "t1").await?, ddl);
+        assert_eq!(
+            kipsql
+                .storage
+                .transaction()
+                .await?
+                .table(Arc::new("t1".to_string())),
+            restored
+                .storage
+                .transaction()
+                .await?
+                .table(Arc::new("t1".to_string()))
+        );
+        // the constraints hold on the restored table
+        let _ = restored
+            .run("insert into t1 (a, b, d, e) values (1, 'x', 1, 1)")
+            .await?;
+        let select = "select c from t1";
+        assert_eq!(
+            restored.run(select).await?[0].values[0],
+            ValueRef::new(DataValue::Float64(Some(0.5)))
+        );
+        assert!(restored
+            .run("insert into t1 (a, b) values (1, 'y')")
+            .await
+            .is_err());
+        assert!(restored
+            .run("insert into t1 (a, b) values (2, 'x')")
+            .await
+            .is_err());
+        assert!(restored
+            .run("insert into t1 (a, b, d, e) values (3, 'z', 1, 1)")
+            .await
+            .is_err());
+        assert!(restored.run("insert into t1 (a) values (4)").await.is_err());
+        assert!(kipsql.run("show create table t2").await.is_err());
+
+        let _ = kipsql
+            .run("create table t3 (a int, b varchar, c int null, primary key (b, a))")
+            .await?;
+        let ddl = show_create_table(&kipsql, "t3").await?;
+        assert!(ddl.contains("PRIMARY KEY (a, b)"));
+        let _ = restored.run(&ddl).await?;
+        assert_eq!(show_create_table(&restored, "t3").await?, ddl);
+        let _ = restored
+            .run("insert into t3 values (1, 'x', null), (1, 'y', null)")
+            .await?;
+        assert!(restored
+            .run("insert into t3 values (1, 'x', 1)")
+            .await
+            .is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_expression_index() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+        let _ = kipsql
+            .run(
+                "create table t1 (id int primary key, name varchar); \
+                 insert into t1 values (1, 'Kip'), (2, 'SQL'), (3, 'kip')",
+            )
+            .await?;
+        let _ = kipsql
+            .run("create index idx_lower on t1 (lower(name))")
+            .await?;
+
+        let select = "select id from t1 where lower(name) = 'kip'";
+        let plan = kipsql
+            .run(&format!("explain {}", select))
+            .await?
+            .iter()
+            .map(|tuple| tuple.values[0].to_string())
+            .join("\n");
+        assert!(plan.contains("IndexScan t1"), "{}", plan);
+        assert!(plan.contains("Index idx_lower"), "{}", plan);
+
+        let ids = |tuples: Vec<Tuple>| {
+            tuples
+                .iter()
+                .map(|tuple| tuple.values[0].to_string())
+                .sorted()
+                .collect_vec()
+        };
+        assert_eq!(ids(kipsql.run(select).await?), vec!["1", "3"]);
+
+        // the entries follow the writes of the rows
+        let _ = kipsql
+            .run(
+                "insert into t1 values (4, 'KIP'); \
+                 update t1 set name = 'Sql' where id = 1; \
+                 delete from t1 where id = 3",
+            )
+            .await?;
+        assert_eq!(ids(kipsql.run(select).await?), vec!["4"]);
+        assert_eq!(
+            ids(kipsql
+                .run("select id from t1 where lower(name) = 'sql'")
+                .await?),
+            vec!["1", "2"]
+        );
+
+        let tuples = kipsql.run("show index from t1").await?;
+        assert_eq!(tuples[1].values[2].to_string(), "lower(name)");
+        // the names of 1 and 2 are the same in upper case
+        assert!(kipsql
+            .run("create unique index uk_upper on t1 (upper(name))")
+            .await
+            .is_err());
+        assert!(kipsql
+            .run("create index idx_lower on t1 (lower(name))")
+            .await
+            .is_err());
+        let _ = kipsql
+            .run("create index if not exists idx_lower on t1 (lower(name))")
+            .await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_composite_index_prefix() -> Result<(), DatabaseError> {
+        async fn plan(kipsql: &Database<KipStorage>, sql: &str) -> Result<String, DatabaseError> {
+            Ok(kipsql
+                .run(&format!("explain {}", sql))
+                .await?
+                .iter()
+                .map(|tuple| tuple.values[0].to_string())
+                .join("\n"))
+        }
+
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+        let _ = kipsql
+            .run(
+                "create table t1 (id int primary key, a int, b int, c int null, unique (a, b)); \
+                 insert into t1 values (0, 0, 3, 0), (1, 1, 1, 1), (2, 1, 2, 2), (3, 1, 3, 3), \
+                 (4, 1, 4, 4), (5, 2, 3, 5)",
+            )
+            .await?;
+        let ids = |tuples: Vec<Tuple>| {
+            tuples
+                .iter()
+                .map(|tuple| tuple.values[0].to_string())
+                .sorted()
+                .collect_vec()
+        };
+
+        let select = "select id from t1 where a = 1 and b > 2";
+        assert!(plan(&kipsql, select).await?.contains("Index uk_a_b"));
+        assert_eq!(ids(kipsql.run(select).await?), vec!["3", "4"]);
+
+        let select = "select id from t1 where a = 1 and b >= 2 and b < 4";
+        assert!(plan(&kipsql, select).await?.contains("Index uk_a_b"));
+        assert_eq!(ids(kipsql.run(select).await?), vec!["2", "3"]);
+
+        let select = "select * from t1 where a = 1";
+        assert!(plan(&kipsql, select).await?.contains("Index uk_a_b"));
+        assert_eq!(ids(kipsql.run(select).await?), vec!["1", "2", "3", "4"]);
+
+        let select = "select id from t1 where a > 0 and b = 3";
+        assert!(plan(&kipsql, select).await?.contains("Index uk_a_b"));
+        assert_eq!(ids(kipsql.run(select).await?), vec!["3", "5"]);
+
+        // `b` is not the leading column of the index
+        let select = "select id from t1 where b > 2";
+        assert!(plan(&kipsql, select).await?.contains("SeqScan"));
+        assert_eq!(ids(kipsql.run(select).await?), vec!["0", "3", "4", "5"]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_boolean_index() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+        let _ = kipsql
+            .run(
+                "create table t1 (id int primary key, b boolean null); \
+                 create index i_b on t1 (b); \
+                 insert into t1 values (0, true), (1, false), (2, null), (3, true), (4, false)",
+            )
+            .await?;
+        let ids = |tuples: Vec<Tuple>| {
+            tuples
+                .iter()
+                .map(|tuple| tuple.values[0].to_string())
+                .sorted()
+                .collect_vec()
+        };
+
+        for (select, expected) in [
+            ("select id from t1 where b = true", vec!["0", "3"]),
+            ("select id from t1 where b", vec!["0", "3"]),
+            ("select id from t1 where not b", vec!["1", "4"]),
+            ("select id from t1 where b = false", vec!["1", "4"]),
+            // false < true, the nulls are not in the index
+            ("select id from t1 where b > false", vec!["0", "3"]),
+            (
+                "select id from t1 where b <= true",
+                vec!["0", "1", "3", "4"],
+            ),
+        ] {
+            let plan = kipsql
+                .run(&format!("explain {}", select))
+                .await?
+                .iter()
+                .map(|tuple| tuple.values[0].to_string())
+                .join("\n");
+            assert!(plan.contains("Index i_b"), "{}: {}", select, plan);
+            assert_eq!(ids(kipsql.run(select).await?), expected, "{}", select);
+        }
+        assert_eq!(
+            ids(kipsql.run("select id from t1 where b is null").await?),
+            vec!["2"]
+        );
+        let _ = kipsql
+            .run("update t1 set b = false where id = 0; update t1 set b = true where id = 1")
+            .await?;
+        assert_eq!(
+            ids(kipsql.run("select id from t1 where b = true").await?),
+            vec!["1", "3"]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_plan_cache() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let mut kipsql = Database::with_kipdb(temp_dir.path()).await?;
+        let _ = kipsql
+            .run("create table t1 (a int primary key, b int)")
+            .await?;
+        let _ = kipsql.run("insert into t1 values (1, 10), (2, 20)").await?;
+
+        let tuples = kipsql.run("select b from t1 where a = 1").await?;
+        assert_eq!(kipsql.plan_cache.hits(), 0);
+        // the cache is keyed by the normalized SQL
+        assert_eq!(kipsql.run("SELECT b FROM t1   WHERE a = 1").await?, tuples);
+        assert_eq!(kipsql.plan_cache.hits(), 1);
+        let _ = kipsql.run("select b from t1 where a = 2").await?;
+        assert_eq!(kipsql.plan_cache.hits(), 1);
+
+        let mut transaction = kipsql.new_transaction().await?;
+        assert_eq!(
+            transaction.run("select b from t1 where a = 1").await?,
+            tuples
+        );
+        assert_eq!(kipsql.plan_cache.hits(), 2);
+        // plans are not cached once the transaction changed the catalog
+        let _ = transaction
+            .run("create table t2 (a int primary key)")
+            .await?;
+        let _ = transaction.run("select b from t1 where a = 1").await?;
+        assert_eq!(kipsql.plan_cache.hits(), 2);
+        transaction.commit().await?;
+
+        // the committed change of the catalog invalidates the cached plans
+        let _ = kipsql.run("drop table t1").await?;
+        let _ = kipsql
+            .run("create table t1 (b int primary key, a int)")
+            .await?;
+        let _ = kipsql.run("insert into t1 values (30, 1)").await?;
+        let tuples = kipsql.run("select b from t1 where a = 1").await?;
+        assert_eq!(
+            tuples[0].values[0],
+            ValueRef::new(DataValue::Int32(Some(30)))
+        );
+        assert_eq!(kipsql.plan_cache.hits(), 2);
+        let _ = kipsql.run("select b from t1 where a = 1").await?;
+        assert_eq!(kipsql.plan_cache.hits(), 3);
+
+        kipsql.set_strict_types(true);
+        let _ = kipsql.run("select b from t1 where a = 1").await?;
+        assert_eq!(kipsql.plan_cache.hits(), 3);
+
+        // a transaction beginning while a change of the catalog is committed may see it or not,
+        // so it neither uses nor caches plans
+        let change = kipsql.plan_cache.begin_change();
+        let mut transaction = kipsql.new_transaction().await?;
+        drop(change);
+        assert_eq!(transaction.cache_version, None);
+        let _ = transaction.run("select b from t1 where a = 2").await?;
+        let _ = transaction.run("select b from t1 where a = 2").await?;
+        transaction.commit().await?;
+        assert_eq!(kipsql.plan_cache.hits(), 3);
+        let _ = kipsql.run("select b from t1 where a = 2").await?;
+        assert_eq!(kipsql.plan_cache.hits(), 3);
+        let _ = kipsql.run("select b from t1 where a = 2").await?;
+        assert_eq!(kipsql.plan_cache.hits(), 4);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_safe_updates() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let mut kipsql = Database::with_kipdb(temp_dir.path()).await?;
+        let _ = kipsql
+            .run("create table t1 (a int primary key, b int); insert into t1 values (1, 0), (2, 0)")
+            .await?;
+
+        let _ = kipsql.run("update t1 set b = 1").await?;
+        let _ = kipsql.run("delete from t1").await?;
+        assert!(kipsql.run("select * from t1").await?.is_empty());
+        let _ = kipsql.run("insert into t1 values (1, 0), (2, 0)").await?;
+
+        kipsql.set_safe_updates(true);
+        for sql in ["update t1 set b = 1", "delete from t1"] {
+            assert!(matches!(
+                kipsql.run(sql).await,
+                Err(DatabaseError::Bind(BindError::UnsafeUpdate(_)))
+            ));
+        }
+        assert_eq!(kipsql.run("select * from t1").await?.len(), 2);
+
+        let _ = kipsql.run("update t1 set b = 1 where a = 1").await?;
+        let _ = kipsql.run("delete from t1 where true").await?;
+        assert!(kipsql.run("select * from t1").await?.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_prefer_first_table() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let mut kipsql = Database::with_kipdb(temp_dir.path()).await?;
+        let _ = kipsql
+            .run("create table t1 (id int primary key, v int); create table t2 (id int primary key, v int)")
+            .await?;
+        let _ = kipsql
+            .run("insert into t1 values (1, 10), (2, 20); insert into t2 values (1, 100), (3, 300)")
+            .await?;
+        let sql = "select id, v from t2 left join t1 on t1.id = t2.id order by id";
+
+        assert!(matches!(
+            kipsql.run(sql).await,
+            Err(DatabaseError::Bind(BindError::AmbiguousColumn(_, _)))
+        ));
+
+        kipsql.set_prefer_first_table(true);
+        // the columns are those of `t2`, which is listed first
+        let tuples = kipsql.run(sql).await?;
+        assert_eq!(
+            tuples
+                .iter()
+                .map(|tuple| tuple.values.clone())
+                .collect_vec(),
+            vec![
+                vec![
+                    ValueRef::new(DataValue::Int32(Some(1))),
+                    ValueRef::new(DataValue::Int32(Some(100))),
+                ],
+                vec![
+                    ValueRef::new(DataValue::Int32(Some(3))),
+                    ValueRef::new(DataValue::Int32(Some(300))),
+                ],
+            ]
+        );
+        // the tables of an alias are ordered the same way
+        let tuples = kipsql
+            .run("select a.v, id from t1 as a join t2 as b on a.id = b.id where id < 3")
+            .await?;
+        assert_eq!(
+            tuples[0].values,
+            vec![
+                ValueRef::new(DataValue::Int32(Some(10))),
+                ValueRef::new(DataValue::Int32(Some(1))),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_cartesian_product() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let mut kipsql = Database::with_kipdb(temp_dir.path()).await?;
+        let _ = kipsql
+            .run("create table t1 (a int primary key, b int); create table t2 (c int primary key, d int)")
+            .await?;
+        let _ = kipsql
+            .run("insert into t1 values (1, 10), (2, 20); insert into t2 values (1, 100), (2, 200), (3, 300)")
+            .await?;
+
+        for sql in [
+            "select * from t1 join t2",
+            "select * from t1 join t2 on true",
+            "select * from t1 left join t2 on t2.d > 100",
+        ] {
+            assert!(
+                matches!(
+                    kipsql.run(sql).await,
+                    Err(DatabaseError::Bind(BindError::CartesianProduct(_)))
+                ),
+                "{}",
+                sql
+            );
+        }
+        assert_eq!(kipsql.run("select * from t1 cross join t2").await?.len(), 6);
+        assert_eq!(
+            kipsql
+                .run("select * from t1 join t2 on t1.a = t2.c")
+                .await?
+                .len(),
+            2
+        );
+        assert_eq!(
+            kipsql
+                .run("select * from t1 join t2 on t1.b < t2.d")
+                .await?
+                .len(),
+            6
+        );
+
+        kipsql.set_allow_cartesian_products(true);
+        assert_eq!(kipsql.run("select * from t1 join t2").await?.len(), 6);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_nulls_order() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let mut kipsql = Database::with_kipdb(temp_dir.path()).await?;
+        let _ = kipsql
+            .run("create table t1 (id int primary key, v int null)")
+            .await?;
+        let _ = kipsql
+            .run("insert into t1 values (0, 2), (1, null), (2, 1), (3, 3)")
+            .await?;
+        let ids = |tuples: Vec<Tuple>| {
+            tuples
+                .iter()
+                .map(|tuple| match tuple.values[0].as_ref() {
+                    DataValue::Int32(Some(id)) => *id,
+                    value => panic!("unexpected id {}", value),
+                })
+                .collect_vec()
+        };
+
+        // the nulls are the largest values
+        assert_eq!(
+            ids(kipsql.run("select id from t1 order by v").await?),
+            vec![2, 0, 3, 1]
+        );
+        assert_eq!(
+            ids(kipsql.run("select id from t1 order by v desc").await?),
+            vec![1, 3, 0, 2]
+        );
+        assert_eq!(
+            ids(kipsql
+                .run("select id from t1 order by v desc nulls last")
+                .await?),
+            vec![3, 0, 2, 1]
+        );
+
+        kipsql.set_nulls_order(NullsOrder::Smallest);
+        assert_eq!(
+            ids(kipsql.run("select id from t1 order by v").await?),
+            vec![1, 2, 0, 3]
+        );
+        assert_eq!(
+            ids(kipsql.run("select id from t1 order by v desc").await?),
+            vec![3, 0, 2, 1]
+        );
+        assert_eq!(
+            ids(kipsql
+                .run("select id from t1 order by v nulls last")
+                .await?),
+            vec![2, 0, 3, 1]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_strict_types() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let mut kipsql = Database::with_kipdb(temp_dir.path()).await?;
+        let _ = kipsql
+            .run("create table t1 (a int primary key, b float, c boolean, d varchar)")
+            .await?;
+        let _ = kipsql
+            .run("insert into t1 values (1, 1.5, true, 'x'), (2, 2.0, false, 'y')")
+            .await?;
+
+        let tuples = kipsql.run("select a from t1 where a = b").await?;
+        assert_eq!(
+            tuples[0].values,
+            vec![ValueRef::new(DataValue::Int32(Some(2)))]
+        );
+        assert!(matches!(
+            kipsql.run("select a from t1 where c = d").await,
+            Err(DatabaseError::Bind(BindError::BinaryOpTypeMismatch(_, _)))
+        ));
+
+        kipsql.set_strict_types(true);
+        assert!(matches!(
+            kipsql.run("select a from t1 where a = b").await,
+            Err(DatabaseError::Bind(BindError::BinaryOpTypeMismatch(_, _)))
+        ));
+        assert!(kipsql.run("select a from t1 where a < 1.5").await.is_err());
+        // constants still take the type of the column if nothing is lost
+        let tuples = kipsql.run("select a from t1 where b > 1 and a = 1").await?;
+        assert_eq!(
+            tuples[0].values,
+            vec![ValueRef::new(DataValue::Int32(Some(1)))]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_set_variable() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+        let _ = kipsql
+            .run("create table t1 (a int primary key, b float)")
+            .await?;
+        let _ = kipsql
+            .run("insert into t1 values (1, 1.5), (2, 2.0), (3, 3.0)")
+            .await?;
+
+        let mut session = kipsql.session();
+        assert_eq!(session.run("select a from t1 where a = b").await?.len(), 2);
+        assert!(session.run("set strict_types = true").await?.is_empty());
+        assert!(session.settings().strict_types);
+        assert!(matches!(
+            session.run("select a from t1 where a = b").await,
+            Err(DatabaseError::Bind(BindError::BinaryOpTypeMismatch(_, _)))
+        ));
+        // the other sessions keep their settings
+        assert_eq!(kipsql.run("select a from t1 where a = b").await?.len(), 2);
+        assert_eq!(
+            kipsql
+                .session()
+                .run("select a from t1 where a = b")
+                .await?
+                .len(),
+            2
+        );
+        let _ = session.run("SET Strict_Types = false").await?;
+        assert_eq!(session.run("select a from t1 where a = b").await?.len(), 2);
+
+        // the statements after the `SET` of the same script
+        let tuples = kipsql
+            .run("set max_rows = 2; select a from t1 order by a")
+            .await?;
+        assert_eq!(tuples.len(), 2);
+        assert_eq!(kipsql.run("select a from t1").await?.len(), 3);
+        let _ = session.run("set max_rows = 2").await?;
+        assert_eq!(session.run("select a from t1").await?.len(), 2);
+        let _ = session.run("set max_rows = null").await?;
+        assert_eq!(session.run("select a from t1").await?.len(), 3);
+
+        // the settings of a failing query are not kept
+        assert!(session
+            .run("set max_rows = 1; select c from t1")
+            .await
+            .is_err());
+        assert_eq!(session.settings().max_rows, None);
+
+        let _ = session.run("set nulls_order = smallest").await?;
+        assert!(matches!(
+            session.run("set nulls_order = first").await,
+            Err(DatabaseError::Bind(BindError::Variable(_)))
+        ));
+        assert!(matches!(
+            session.run("set strict_type = true").await,
+            Err(DatabaseError::Bind(BindError::Variable(_)))
+        ));
+        assert!(session.run("set max_rows = 'many'").await.is_err());
+
+        // a `SET` in a transaction is only for the rest of it
+        let mut transaction = kipsql.new_transaction().await?;
+        let _ = transaction.run("set strict_types = true").await?;
+        assert!(transaction
+            .run("select a from t1 where a = b")
+            .await
+            .is_err());
+        transaction.commit().await?;
+        assert_eq!(kipsql.run("select a from t1 where a = b").await?.len(), 2);
+
+        // and it starts with those of its session
+        let _ = session.run("set strict_types = true").await?;
+        let mut transaction = session.new_transaction().await?;
+        assert!(transaction
+            .run("select a from t1 where a = b")
+            .await
+            .is_err());
+        transaction.commit().await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_typed_evaluation_errors() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+        let _ = kipsql
+            .run("create table t1 (a int primary key, b int, c bigint, d varchar)")
+            .await?;
+        let _ = kipsql
+            .run("insert into t1 values (0, 2147483647, 9223372036854775807, 'x'), (1, 1, 1, '12')")
+            .await?;
+
+        // folded by the optimizer
+        assert!(matches!(
+            kipsql.run("select 1 / 0").await,
+            Err(DatabaseError::DivideByZero)
+        ));
+        assert!(matches!(
+            kipsql.run("select b / 0 from t1").await,
+            Err(DatabaseError::DivideByZero)
+        ));
+        assert!(matches!(
+            kipsql.run("select a from t1 where b % 0 = 1").await,
+            Err(DatabaseError::DivideByZero)
+        ));
+        assert!(matches!(
+            kipsql.run("select b + 1 from t1").await,
+            Err(DatabaseError::Overflow(LogicalType::Integer))
+        ));
+        assert!(matches!(
+            kipsql.run("select sum(c) from t1").await,
+            Err(DatabaseError::Overflow(LogicalType::Bigint))
+        ));
+        assert!(matches!(
+            kipsql.run("select cast(d as int) from t1").await,
+            Err(DatabaseError::InvalidCast(_))
+        ));
+        assert!(matches!(
+            kipsql.run("select cast(c as int) from t1").await,
+            Err(DatabaseError::InvalidCast(_))
+        ));
+        // the failed statements leave the database usable
+        let tuples = kipsql.run("select b + 1 from t1 where a = 1").await?;
+        assert_eq!(
+            tuples[0].values,
+            vec![ValueRef::new(DataValue::Int32(Some(2)))]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sort_spill() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let mut kipsql = Database::with_kipdb(temp_dir.path()).await?;
+        let _ = kipsql
+            .run("create table t1 (a int primary key, b int null, c varchar null)")
+            .await?;
+        let values = (0..200)
+            .map(|i| match (i * 37) % 101 {
+                0 => format!("({}, null, null)", i),
+                b => format!("({}, {}, 'v{}')", i, b, i),
+            })
+            .join(", ");
+        let _ = kipsql
+            .run(&format!("insert into t1 values {}", values))
+            .await?;
+        let select = "select a, b, c from t1 order by b desc, a";
+        let in_memory = kipsql.run(select).await?;
+
+        // a few tuples per run forces dozens of spilled runs
+        kipsql.set_sort_spill_threshold(Some(64));
+        let spilled = kipsql.run(select).await?;
+        assert_eq!(spilled.len(), 200);
+        assert_eq!(spilled, in_memory);
+
+        let b_values = spilled
+            .iter()
+            .map(|tuple| tuple.values[1].clone())
+            .collect_vec();
+        assert!(b_values[..2].iter().all(|value| value.is_null()));
+        assert!(b_values[2..].windows(2).all(|pair| pair[0] >= pair[1]));
+
+        let tuples = kipsql.run(&format!("{} limit 3", select)).await?;
+        assert_eq!(tuples, in_memory[..3]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_agg_spill() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let mut kipsql = Database::with_kipdb(temp_dir.path()).await?;
+        let _ = kipsql
+            .run("create table t1 (a int primary key, b int, c int null)")
+            .await?;
+        let values = (0..600)
+            .map(|i| match i % 7 {
+                0 => format!("({}, {}, null)", i, i % 150),
+                _ => format!("({}, {}, {})", i, i % 150, i % 5),
+            })
+            .join(", ");
+        let _ = kipsql
+            .run(&format!("insert into t1 values {}", values))
+            .await?;
+        let select = "select b, count(c), sum(a), min(a), max(c), avg(a), count(distinct c) \
+                      from t1 group by b order by b";
+        let in_memory = kipsql.run(select).await?;
+        assert_eq!(in_memory.len(), 150);
+
+        // far fewer groups than the distinct b values fit in memory
+        kipsql.set_agg_spill_threshold(Some(8));
+        let spilled = kipsql.run(select).await?;
+        assert_eq!(spilled, in_memory);
+
+        // b = 1 is the group of a in (1, 151, 301, 451)
+        assert_eq!(
+            spilled[1].values,
+            vec![
+                ValueRef::new(DataValue::Int32(Some(1))),
+                ValueRef::new(DataValue::Int32(Some(3))),
+                ValueRef::new(DataValue::Int32(Some(904))),
+                ValueRef::new(DataValue::Int32(Some(1))),
+                ValueRef::new(DataValue::Int32(Some(1))),
+                ValueRef::new(DataValue::Float64(Some(226.0))),
+                ValueRef::new(DataValue::Int32(Some(1))),
+            ]
+        );
+        let tuples = kipsql
+            .run("select b, count(a) from t1 group by rollup(b) order by b")
+            .await?;
+        assert_eq!(tuples.len(), 151);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_exists_index_probe() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+
+        let _ = kipsql
+            .run("create table t1 (a int primary key, b int null)")
+            .await?;
+        let _ = kipsql
+            .run("create table t2 (c int primary key, d int, e int)")
+            .await?;
+        let _ = kipsql.run("create index d_index on t2 (d)").await?;
+        let _ = kipsql
+            .run("insert into t1 values (1, 10), (2, 20), (3, null), (4, 40)")
+            .await?;
+        let _ = kipsql
+            .run("insert into t2 values (1, 10, 1), (2, 10, 2), (3, 40, 0)")
+            .await?;
+
+        for (sql, is_probed, rows) in [
+            (
+                "select a from t1 where exists (select 1 from t2 where d = t1.b)",
+                true,
+                vec!["1", "4"],
+            ),
+            (
+                "select a from t1 where exists (select 1 from t2 where t1.b = d and e > 0)",
+                true,
+                vec!["1"],
+            ),
+            (
+                "select a from t1 where not exists (select 1 from t2 where d = t1.b)",
+                true,
+                vec!["2", "3"],
+            ),
+            // `e` has no index, the tuples of `t2` are hashed
+            (
+                "select a from t1 where exists (select 1 from t2 where e = t1.a)",
+                false,
+                vec!["1", "2"],
+            ),
+        ] {
+            let plan = kipsql
+                .run(&format!("explain {}", sql))
+                .await?
+                .iter()
+                .map(|tuple| tuple.values[0].to_string())
+                .join("\n");
+            assert_eq!(plan.contains("Probe d_index"), is_probed, "{}", plan);
+
+            let tuples = kipsql.run(sql).await?;
+            let values = tuples
+                .iter()
+                .map(|tuple| tuple.values[0].to_string())
+                .collect_vec();
+            assert_eq!(values, rows, "{}", sql);
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_truncate_tables() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+
+        let _ = kipsql
+            .run("create table t1 (a int primary key, b int unique null)")
+            .await?;
+        let _ = kipsql
+            .run("create table t2 (c int primary key, d int)")
+            .await?;
+        let _ = kipsql.run("insert into t1 values (1, 10), (2, 20)").await?;
+        let _ = kipsql.run("insert into t2 values (1, 1), (2, 2)").await?;
+
+        // a missing table leaves the tables before it untouched
+        assert!(kipsql.run("truncate t1, not_exist").await.is_err());
+        assert_eq!(kipsql.run("select * from t1").await?.len(), 2);
+
+        let _ = kipsql.run("truncate table t1, t2").await?;
+        assert!(kipsql.run("select * from t1").await?.is_empty());
+        assert!(kipsql.run("select * from t2").await?.is_empty());
+        assert!(kipsql
+            .run("select * from t1 where b = 10")
+            .await?
+            .is_empty());
+
+        let _ = kipsql.run("insert into t1 values (1, 10)").await?;
+        assert_eq!(kipsql.run("select * from t1").await?.len(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_on_update() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+        let old = "2000-01-01 00:00:00";
+
+        let _ = kipsql
+            .run(
+                "create table t (id int primary key, a int, b int, \
+                 updated_at timestamp null on update now())",
+            )
+            .await?;
+        let _ = kipsql
+            .run(&format!(
+                "insert into t values (0, 1, 1, '{}'), (1, 2, 2, '{}')",
+                old, old
+            ))
+            .await?;
+        let _ = kipsql.run("update t set a = 10 where id = 0").await?;
+
+        let select = "select id, a, b, updated_at from t";
+        let tuples = kipsql.run(select).await?;
+        let values = |tuple: &Tuple| tuple.values.iter().map(|v| v.to_string()).collect_vec();
+        assert_eq!(values(&tuples[0])[..3], ["0", "10", "1"]);
+        assert_ne!(values(&tuples[0])[3], old);
+        // the rows not updated are left as they are
+        assert_eq!(values(&tuples[1]), ["1", "2", "2", old]);
+
+        // a value set by the update is kept
+        let _ = kipsql
+            .run("update t set b = 3, updated_at = '2001-01-01 00:00:00' where id = 1")
+            .await?;
+        let tuples = kipsql.run(select).await?;
+        assert_eq!(values(&tuples[1]), ["1", "2", "3", "2001-01-01 00:00:00"]);
+
+        let ddl = kipsql.run("show create table t").await?[0].values[1].to_string();
+        assert!(
+            ddl.contains("updated_at DATETIME NULL ON UPDATE now()"),
+            "{}",
+            ddl
+        );
+        assert!(kipsql
+            .run("create table t1 (id int primary key, a int on update id + 1)")
+            .await
+            .is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_default_expr() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+
+        let _ = kipsql
+            .run(
+                "create table t (id int primary key, a int default 1, \
+                 created_at timestamp default now(), uid uuid default gen_random_uuid())",
+            )
+            .await?;
+        let _ = kipsql.run("insert into t (id) values (0), (1)").await?;
+        let _ = kipsql
+            .run("insert into t (id, uid) values (2, 'given')")
+            .await?;
+
+        let tuples = kipsql.run("select id, a, created_at, uid from t").await?;
+        let values = |tuple: &Tuple| tuple.values.iter().map(|v| v.to_string()).collect_vec();
+        let (row_0, row_1) = (values(&tuples[0]), values(&tuples[1]));
+        assert_eq!(row_0[..2], ["0", "1"]);
+        assert_ne!(row_0[2], "null");
+        // evaluated anew for each row
+        assert_eq!(row_0[3].len(), 36);
+        assert_ne!(row_0[3], row_1[3]);
+        assert_eq!(values(&tuples[2])[3], "given");
+
+        let ddl = kipsql.run("show create table t").await?[0].values[1].to_string();
+        assert!(
+            ddl.contains("created_at DATETIME NOT NULL DEFAULT now()"),
+            "{}",
+            ddl
+        );
+        assert!(
+            ddl.contains("uid VARCHAR(36) NOT NULL DEFAULT gen_random_uuid()"),
+            "{}",
+            ddl
+        );
+        assert!(kipsql
+            .run(
+                "create table t1 (id int primary key, \
+                 a int generated always as identity default now())"
+            )
+            .await
+            .is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_deterministic() -> Result<(), DatabaseError> {
+        let queries = [
+            "select b, count(*), sum(a) from t1 group by b",
+            "select distinct c from t1",
+            "select t1.a, t2.a from t2 left join t1 on t1.b = t2.a",
+        ];
+        let mut results = Vec::new();
+
+        // the same queries on two databases of the same rows
+        for _ in 0..2 {
+            let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+            let config = DatabaseConfig::default()
+                .with_deterministic(true)
+                .with_agg_spill_threshold(Some(16));
+            let kipsql = Database::with_config(temp_dir.path(), config).await?;
+            let _ = kipsql
+                .run("create table t1 (a int primary key, b int, c int); create table t2 (a int primary key)")
+                .await?;
+            let values = (0..300)
+                .map(|i| format!("({}, {}, {})", i, (i * 37) % 101, i % 53))
+                .join(", ");
+            let _ = kipsql
+                .run(&format!("insert into t1 values {}", values))
+                .await?;
+            let values = (0..200).map(|i| format!("({})", i)).join(", ");
+            let _ = kipsql
+                .run(&format!("insert into t2 values {}", values))
+                .await?;
+
+            for query in queries {
+                let tuples = kipsql.run(query).await?;
+                assert_eq!(kipsql.run(query).await?, tuples);
+
+                results.push(tuples);
+            }
+        }
+        let (first, second) = results.split_at(queries.len());
+        assert_eq!(first, second);
+        assert_eq!(first[0].len(), 101);
+        assert_eq!(first[1].len(), 53);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_with_config() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let catalog_cache = CatalogCacheConfig {
+            cap: 4,
+            sharding_size: 2,
+        };
+        let config = DatabaseConfig::default()
+            .with_catalog_cache(catalog_cache)
+            .with_wal_mode(WalMode::Direct)
+            .with_plan_cache_cap(8)
+            .with_safe_updates(true)
+            .with_strict_types(true)
+            .with_sort_spill_threshold(Some(64));
+        let kipsql = Database::with_config(temp_dir.path(), config).await?;
+        assert_eq!(kipsql.storage.cache_config(), catalog_cache);
+
+        let _ = kipsql
+            .run("create table t1 (a int primary key, b bigint)")
+            .await?;
+        let values = (0..100)
+            .map(|i| format!("({}, {})", i, (i * 37) % 101))
+            .join(", ");
+        let _ = kipsql
+            .run(&format!("insert into t1 values {}", values))
+            .await?;
+
+        // sorted in dozens of spilled runs
+        let tuples = kipsql.run("select b from t1 order by b").await?;
+        assert_eq!(tuples.len(), 100);
+        assert!(tuples
+            .windows(2)
+            .all(|pair| pair[0].values[0] <= pair[1].values[0]));
+
+        assert!(kipsql.run("delete from t1").await.is_err());
+        assert!(kipsql.run("select a from t1 where a = b").await.is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_scan_batch_size() -> Result<(), DatabaseError> {
+        let mut results = Vec::new();
+
+        for config in [
+            DatabaseConfig::default(),
+            DatabaseConfig::default().with_scan_batch_size(1),
+            DatabaseConfig::default().with_scan_batch_size(7),
+        ] {
+            let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+            let kipsql = Database::with_config(temp_dir.path(), config).await?;
+            let _ = kipsql
+                .run("create table t1 (a int primary key, b int)")
+                .await?;
+            let _ = kipsql.run("create index t1_b on t1 (b)").await?;
+            let values = (0..300).map(|i| format!("({}, {})", i, i % 7)).join(", ");
+            let _ = kipsql
+                .run(&format!("insert into t1 values {}", values))
+                .await?;
+
+            let mut result = Vec::new();
+            for sql in [
+                "select a, b from t1 where a % 7 = 3",
+                "select a from t1 where b = 3 and a > 100",
+                "select a from t1 where a >= 10 and a < 150 limit 50 offset 20",
+                "select count(*), sum(a) from t1 where b < 2",
+            ] {
+                result.push(kipsql.run(sql).await?);
+            }
+            results.push(result);
+        }
+        assert!(!results[0][0].is_empty());
+        assert_eq!(results[0], results[1]);
+        assert_eq!(results[0], results[2]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_result_schema() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+        let _ = kipsql
+            .run("create table t1 (c1 int primary key, c2 varchar)")
+            .await?;
+        let columns = |result: &ResultSet| {
+            result
+                .schema
+                .iter()
+                .map(|column| (column.name().to_string(), *column.datatype()))
+                .collect_vec()
+        };
+        let sql = "select c1, count(*) from t1 group by c1";
+
+        // the columns are known without any tuple
+        let result = kipsql.query(sql).await?;
+        assert!(result.tuples.is_empty());
+        assert_eq!(
+            columns(&result),
+            vec![
+                ("c1".to_string(), LogicalType::Integer),
+                ("Count(*)".to_string(), LogicalType::Integer)
+            ]
+        );
+
+        let _ = kipsql
+            .run("insert into t1 values (1, 'a'), (2, 'b')")
+            .await?;
+        let result = kipsql.query(sql).await?;
+        assert_eq!(result.tuples.len(), 2);
+        assert_eq!(result.schema, result.tuples[0].schema_ref);
+
+        let result = kipsql
+            .query("select c2 as name, c1 + 1 from t1 order by c1 limit 1")
+            .await?;
+        assert_eq!(
+            columns(&result),
+            vec![
+                ("name".to_string(), LogicalType::Varchar(None)),
+                ("(c1 + 1)".to_string(), LogicalType::Integer)
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_table_without_primary_key() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+        let _ = kipsql.run("create table t1 (a int null, b int)").await?;
+        let _ = kipsql
+            .run("insert into t1 values (1, 10), (1, 10), (2, 20)")
+            .await?;
+        let _ = kipsql.run("insert into t1 (b) values (30)").await?;
+
+        let tuples = kipsql.run("select * from t1").await?;
+        assert_eq!(tuples.len(), 4);
+        assert_eq!(tuples[0].schema_ref.len(), 2);
+
+        let _ = kipsql.run("delete from t1 where _rowid = 2").await?;
+        let _ = kipsql.run("update t1 set b = 11 where a = 1").await?;
+        let tuples = kipsql.run("select _rowid, a, b from t1").await?;
+        let rows = tuples
+            .iter()
+            .map(|tuple| {
+                tuple
+                    .values
+                    .iter()
+                    .map(|value| value.to_string())
+                    .join(", ")
+            })
+            .collect_vec();
+        assert_eq!(rows, vec!["1, 1, 11", "3, 2, 20", "4, null, 30"]);
+
+        // the rowids are not given out again
+        let _ = kipsql.run("delete from t1").await?;
+        let _ = kipsql.run("insert into t1 values (5, 50)").await?;
+        let tuples = kipsql.run("select _rowid from t1").await?;
+        assert_eq!(
+            tuples[0].values,
+            vec![ValueRef::new(DataValue::UInt64(Some(5)))]
+        );
+
+        let _ = kipsql.run("truncate t1").await?;
+        let _ = kipsql.run("insert into t1 values (6, 60)").await?;
+        let tuples = kipsql.run("select _rowid from t1").await?;
+        assert_eq!(
+            tuples[0].values,
+            vec![ValueRef::new(DataValue::UInt64(Some(6)))]
+        );
+        let _ = kipsql.run("truncate t1 continue identity").await?;
+        let _ = kipsql.run("insert into t1 values (6, 60)").await?;
+        let tuples = kipsql.run("select _rowid from t1").await?;
+        assert_eq!(
+            tuples[0].values,
+            vec![ValueRef::new(DataValue::UInt64(Some(7)))]
+        );
+
+        let _ = kipsql.run("truncate table t1 restart identity").await?;
+        let _ = kipsql.run("insert into t1 values (7, 70)").await?;
+        let tuples = kipsql.run("select _rowid, a from t1").await?;
+        assert_eq!(
+            tuples[0].values,
+            vec![
+                ValueRef::new(DataValue::UInt64(Some(1))),
+                ValueRef::new(DataValue::Int32(Some(7)))
+            ]
+        );
 
         Ok(())
     }
@@ -427,7 +3722,7 @@ mod test {
         println!("{}", create_table(&tuples_time_where_t2));
 
         assert!(kipsql
-            .run("select max(d) from t2 group by c")
+            .run("select d, max(c) from t2 group by e")
             .await
             .is_err());
 
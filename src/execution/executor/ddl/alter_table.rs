@@ -0,0 +1,44 @@
+use crate::execution::executor::{BoxedExecutor, Executor};
+use crate::execution::ExecutorError;
+use crate::planner::operator::alter_table::{AlterTableOperation, AlterTableOperator};
+use crate::storage::Transaction;
+use crate::types::tuple::Tuple;
+use futures_async_stream::try_stream;
+use std::cell::RefCell;
+
+pub struct AlterTable {
+    op: AlterTableOperator,
+}
+
+impl From<AlterTableOperator> for AlterTable {
+    fn from(op: AlterTableOperator) -> Self {
+        AlterTable { op }
+    }
+}
+
+impl<T: Transaction> Executor<T> for AlterTable {
+    fn execute(self, transaction: &RefCell<T>) -> BoxedExecutor {
+        unsafe { self._execute(transaction.as_ptr().as_mut().unwrap()) }
+    }
+}
+
+impl AlterTable {
+    #[try_stream(boxed, ok = Tuple, error = ExecutorError)]
+    pub async fn _execute<T: Transaction>(self, transaction: &mut T) {
+        let AlterTableOperator {
+            table_name,
+            operation,
+        } = self.op;
+
+        match operation {
+            AlterTableOperation::RenameTable { new_table_name } => {
+                transaction.rename_table(&table_name, &new_table_name)?
+            }
+            AlterTableOperation::RenameColumn {
+                column_id,
+                new_column_name,
+                ..
+            } => transaction.rename_column(&table_name, column_id, &new_column_name)?,
+        }
+    }
+}
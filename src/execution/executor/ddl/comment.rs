@@ -0,0 +1,36 @@
+use crate::execution::executor::{BoxedExecutor, Executor};
+use crate::execution::ExecutorError;
+use crate::planner::operator::comment::CommentOperator;
+use crate::storage::Transaction;
+use crate::types::tuple::Tuple;
+use futures_async_stream::try_stream;
+use std::cell::RefCell;
+
+pub struct Comment {
+    op: CommentOperator,
+}
+
+impl From<CommentOperator> for Comment {
+    fn from(op: CommentOperator) -> Self {
+        Comment { op }
+    }
+}
+
+impl<T: Transaction> Executor<T> for Comment {
+    fn execute(self, transaction: &RefCell<T>) -> BoxedExecutor {
+        unsafe { self._execute(transaction.as_ptr().as_mut().unwrap()) }
+    }
+}
+
+impl Comment {
+    #[try_stream(boxed, ok = Tuple, error = ExecutorError)]
+    pub async fn _execute<T: Transaction>(self, transaction: &mut T) {
+        let CommentOperator {
+            table_name,
+            column_id,
+            comment,
+        } = self.op;
+
+        transaction.set_comment(&table_name, column_id, comment)?;
+    }
+}
@@ -0,0 +1,36 @@
+use crate::execution::executor::{BoxedExecutor, Executor};
+use crate::execution::ExecutorError;
+use crate::planner::operator::create_index::CreateIndexOperator;
+use crate::storage::Transaction;
+use crate::types::tuple::Tuple;
+use futures_async_stream::try_stream;
+use std::cell::RefCell;
+
+pub struct CreateIndex {
+    op: CreateIndexOperator,
+}
+
+impl From<CreateIndexOperator> for CreateIndex {
+    fn from(op: CreateIndexOperator) -> Self {
+        CreateIndex { op }
+    }
+}
+
+impl<T: Transaction> Executor<T> for CreateIndex {
+    fn execute(self, transaction: &RefCell<T>) -> BoxedExecutor {
+        unsafe { self._execute(transaction.as_ptr().as_mut().unwrap()) }
+    }
+}
+
+impl CreateIndex {
+    #[try_stream(boxed, ok = Tuple, error = ExecutorError)]
+    pub async fn _execute<T: Transaction>(self, transaction: &mut T) {
+        let CreateIndexOperator {
+            table_name,
+            index_meta,
+            if_not_exists,
+        } = self.op;
+
+        transaction.create_index(&table_name, index_meta, if_not_exists)?;
+    }
+}
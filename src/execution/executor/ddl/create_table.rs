@@ -1,19 +1,24 @@
+use crate::execution::executor::dml::insert::Insert;
 use crate::execution::executor::{BoxedExecutor, Executor};
 use crate::execution::ExecutorError;
 use crate::planner::operator::create_table::CreateTableOperator;
-use crate::storage::Transaction;
-use crate::types::tuple::Tuple;
+use crate::planner::operator::insert::InsertOperator;
+use crate::storage::{StorageError, Transaction};
+use crate::types::tuple::{SchemaRef, Tuple};
 use crate::types::tuple_builder::TupleBuilder;
 use futures_async_stream::try_stream;
 use std::cell::RefCell;
+use std::sync::Arc;
 
 pub struct CreateTable {
     op: CreateTableOperator,
+    /// The query of `CREATE TABLE .. AS SELECT ..`
+    input: Option<BoxedExecutor>,
 }
 
-impl From<CreateTableOperator> for CreateTable {
-    fn from(op: CreateTableOperator) -> Self {
-        CreateTable { op }
+impl From<(CreateTableOperator, Option<BoxedExecutor>)> for CreateTable {
+    fn from((op, input): (CreateTableOperator, Option<BoxedExecutor>)) -> Self {
+        CreateTable { op, input }
     }
 }
 
@@ -29,13 +34,55 @@ impl CreateTable {
         let CreateTableOperator {
             table_name,
             columns,
+            unique_keys,
             if_not_exists,
         } = self.op;
-        let _ = transaction.create_table(table_name.clone(), columns, if_not_exists)?;
+        let exists = transaction.table(table_name.clone()).is_some();
+        let _ =
+            transaction.create_table(table_name.clone(), columns, unique_keys, if_not_exists)?;
+
+        if let Some(input) = self.input.filter(|_| !exists) {
+            let schema_ref = Arc::new(
+                transaction
+                    .table(table_name.clone())
+                    .ok_or(StorageError::TableNotFound)?
+                    .visible_columns(),
+            );
+            let insert = Insert::from((
+                InsertOperator {
+                    table_name: table_name.clone(),
+                    is_overwrite: false,
+                    is_replace: false,
+                    overriding_system_value: false,
+                    returning: vec![],
+                },
+                Self::with_schema(input, schema_ref),
+            ));
+
+            #[for_await]
+            for tuple in insert._execute(transaction) {
+                let _ = tuple?;
+            }
+        }
         let tuple_builder = TupleBuilder::new_result();
         let tuple = tuple_builder
             .push_result("CREATE TABLE SUCCESS", format!("{}", table_name).as_str())?;
 
         yield tuple;
     }
+
+    /// The tuples of the query are inserted into the columns of the new table by position.
+    #[try_stream(boxed, ok = Tuple, error = ExecutorError)]
+    async fn with_schema(input: BoxedExecutor, schema_ref: SchemaRef) {
+        #[for_await]
+        for tuple in input {
+            let Tuple { values, .. } = tuple?;
+
+            yield Tuple {
+                id: None,
+                schema_ref: schema_ref.clone(),
+                values,
+            };
+        }
+    }
 }
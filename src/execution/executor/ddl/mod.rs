@@ -1,3 +1,6 @@
+pub(crate) mod alter_table;
+pub(crate) mod comment;
+pub(crate) mod create_index;
 pub(crate) mod create_table;
 pub(crate) mod drop_table;
 pub(crate) mod truncate;
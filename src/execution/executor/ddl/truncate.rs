@@ -25,8 +25,14 @@ impl<T: Transaction> Executor<T> for Truncate {
 impl Truncate {
     #[try_stream(boxed, ok = Tuple, error = ExecutorError)]
     pub async fn _execute<T: Transaction>(self, transaction: &mut T) {
-        let TruncateOperator { table_name } = self.op;
+        let TruncateOperator {
+            table_name,
+            restart_identity,
+        } = self.op;
 
         transaction.drop_data(&table_name)?;
+        if restart_identity {
+            transaction.reset_rowid(&table_name)?;
+        }
     }
 }
@@ -1,14 +1,16 @@
-use crate::binder::copy::FileFormat;
+use crate::binder::copy::{FileFormat, OnError};
 use crate::execution::executor::{BoxedExecutor, Executor};
 use crate::execution::ExecutorError;
 use crate::planner::operator::copy_from_file::CopyFromFileOperator;
-use crate::storage::Transaction;
-use crate::types::tuple::Tuple;
+use crate::storage::{StorageError, Transaction};
+use crate::types::tuple::{Tuple, TupleId};
 use crate::types::tuple_builder::TupleBuilder;
+use crate::types::value::{DataValue, ValueRef};
 use futures_async_stream::try_stream;
 use std::cell::RefCell;
 use std::fs::File;
 use std::io::BufReader;
+use std::sync::Arc;
 use tokio::sync::mpsc::Sender;
 
 pub struct CopyFromFile {
@@ -38,14 +40,40 @@ impl CopyFromFile {
         // `tx`, then the task will finish.
         let table_name = self.op.table.clone();
         let handle = tokio::task::spawn_blocking(|| self.read_file_blocking(tx));
+        let table = transaction
+            .table(Arc::new(table_name.clone()))
+            .cloned()
+            .ok_or(StorageError::TableNotFound)?;
         let mut size = 0_usize;
-        while let Some(chunk) = rx.recv().await {
+        while let Some(mut chunk) = rx.recv().await {
+            if chunk.id.is_none() {
+                // the table has no primary key, the file holds all but the hidden rowid
+                let rowid = transaction.next_rowid(&table_name)?;
+                let rowid_value = ValueRef::new(DataValue::UInt64(Some(rowid)));
+                let mut values = chunk.values.into_iter();
+
+                chunk.values = table
+                    .all_columns()
+                    .iter()
+                    .filter_map(|col| {
+                        if col.desc.is_hidden {
+                            Some(rowid_value.clone())
+                        } else {
+                            values.next()
+                        }
+                    })
+                    .collect();
+                chunk.id = Some(TupleId::RowId(rowid));
+                chunk.schema_ref = table.schema_ref().clone();
+            }
+            // the values of the generated columns in the file are computed again
+            chunk.eval_generated_columns()?;
             transaction.append(&table_name, chunk, false)?;
             size += 1;
         }
-        handle.await??;
+        let skipped_rows = handle.await??;
 
-        let handle = tokio::task::spawn_blocking(move || return_result(size, tx1));
+        let handle = tokio::task::spawn_blocking(move || return_result(size, skipped_rows, tx1));
         while let Some(chunk) = rx1.recv().await {
             yield chunk;
         }
@@ -53,8 +81,12 @@ impl CopyFromFile {
     }
     /// Read records from file using blocking IO.
     ///
-    /// The read data chunks will be sent through `tx`.
-    fn read_file_blocking(mut self, tx: Sender<Tuple>) -> Result<(), ExecutorError> {
+    /// The read data chunks will be sent through `tx`. With `ON_ERROR = CONTINUE` the rows
+    /// that cannot be read are skipped, they are returned with their line and the reason.
+    fn read_file_blocking(
+        mut self,
+        tx: Sender<Tuple>,
+    ) -> Result<Vec<(u64, String)>, ExecutorError> {
         let file = File::open(self.op.source.path)?;
         let mut buf_reader = BufReader::new(file);
         let mut reader = match self.op.source.format {
@@ -73,39 +105,92 @@ impl CopyFromFile {
 
         let column_count = self.op.types.len();
         let mut size_count = 0;
+        let mut skipped_rows = Vec::new();
 
         for record in reader.records() {
             let mut tuple_builder =
                 TupleBuilder::new(self.op.types.clone(), self.op.columns.clone());
-            // read records and push raw str rows into data chunk builder
-            let record = record?;
-
-            if !(record.len() == column_count
-                || record.len() == column_count + 1 && record.get(column_count) == Some(""))
-            {
-                return Err(ExecutorError::LengthMismatch {
-                    expected: column_count,
-                    actual: record.len(),
-                });
-            }
+            let line = record
+                .as_ref()
+                .map(|record| record.position())
+                .unwrap_or_else(|err| err.position())
+                .map_or(0, |position| position.line());
+            let result = record.map_err(ExecutorError::from).and_then(|record| {
+                if !(record.len() == column_count
+                    || record.len() == column_count + 1 && record.get(column_count) == Some(""))
+                {
+                    return Err(ExecutorError::LengthMismatch {
+                        expected: column_count,
+                        actual: record.len(),
+                    });
+                }
+                // read records and push raw str rows into data chunk builder
+                Ok(tuple_builder.push_str_row(record.iter())?)
+            });
 
+            let chunk = match (result, self.op.on_error) {
+                (Ok(chunk), _) => chunk,
+                (Err(err), OnError::Continue { limit }) if Self::is_row_error(&err) => {
+                    let reason = match err {
+                        // unlike `ExecutorError::Csv`, the csv error tells what is wrong
+                        ExecutorError::Csv(err) => err.to_string(),
+                        err => err.to_string(),
+                    };
+                    skipped_rows.push((line, reason));
+
+                    if let Some(limit) = limit.filter(|limit| skipped_rows.len() > *limit) {
+                        return Err(ExecutorError::InternalError(format!(
+                            "more than ERROR_LIMIT = {} rows are skipped, the last at line {}",
+                            limit, line
+                        )));
+                    }
+                    continue;
+                }
+                (Err(err), _) => return Err(err),
+            };
             size_count += 1;
 
             // push a raw str row and send it if necessary
-            if let Some(chunk) = tuple_builder.push_str_row(record.iter())? {
+            if let Some(chunk) = chunk {
                 tx.blocking_send(chunk).map_err(|_| ExecutorError::Abort)?;
             }
         }
         self.size = size_count;
-        Ok(())
+        Ok(skipped_rows)
+    }
+
+    /// Whether the error is of a single row, the reading goes on past it, unlike an IO error.
+    fn is_row_error(err: &ExecutorError) -> bool {
+        match err {
+            ExecutorError::Csv(err) => matches!(
+                err.kind(),
+                csv::ErrorKind::UnequalLengths { .. } | csv::ErrorKind::Utf8 { .. }
+            ),
+            ExecutorError::LengthMismatch { .. } | ExecutorError::TypeError(_) => true,
+            _ => false,
+        }
     }
 }
 
-fn return_result(size: usize, tx: Sender<Tuple>) -> Result<(), ExecutorError> {
-    let tuple_builder = TupleBuilder::new_result();
-    let tuple =
-        tuple_builder.push_result("COPY FROM SOURCE", format!("import {} rows", size).as_str())?;
+/// The number of imported rows, then each skipped row.
+fn return_result(
+    size: usize,
+    skipped_rows: Vec<(u64, String)>,
+    tx: Sender<Tuple>,
+) -> Result<(), ExecutorError> {
+    let message = if skipped_rows.is_empty() {
+        format!("import {} rows", size)
+    } else {
+        format!("import {} rows, skip {} rows", size, skipped_rows.len())
+    };
+    let tuple = TupleBuilder::new_result().push_result("COPY FROM SOURCE", &message)?;
     tx.blocking_send(tuple).map_err(|_| ExecutorError::Abort)?;
+
+    for (line, reason) in skipped_rows {
+        let tuple = TupleBuilder::new_result()
+            .push_result("COPY FROM SOURCE", &format!("line {}: {}", line, reason))?;
+        tx.blocking_send(tuple).map_err(|_| ExecutorError::Abort)?;
+    }
     Ok(())
 }
 
@@ -135,6 +220,7 @@ mod tests {
                 summary: ColumnSummary {
                     id: Some(0),
                     name: "a".to_string(),
+                    table_name: None,
                 },
                 nullable: false,
                 desc: ColumnDesc::new(LogicalType::Integer, true, false, None),
@@ -144,6 +230,7 @@ mod tests {
                 summary: ColumnSummary {
                     id: Some(1),
                     name: "b".to_string(),
+                    table_name: None,
                 },
                 nullable: false,
                 desc: ColumnDesc::new(LogicalType::Float, false, false, None),
@@ -153,6 +240,7 @@ mod tests {
                 summary: ColumnSummary {
                     id: Some(1),
                     name: "c".to_string(),
+                    table_name: None,
                 },
                 nullable: false,
                 desc: ColumnDesc::new(LogicalType::Varchar(Some(10)), false, false, None),
@@ -178,6 +266,7 @@ mod tests {
                 LogicalType::Varchar(Some(10)),
             ],
             columns: columns.clone(),
+            on_error: OnError::Abort,
         };
         let executor = CopyFromFile {
             op: op.clone(),
@@ -201,4 +290,56 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn read_csv_on_error_continue() -> Result<(), DatabaseError> {
+        let csv = "a,b\n1,one\n2\nx,three\n4,four\n";
+
+        let mut file = tempfile::NamedTempFile::new().expect("failed to create temp file");
+        write!(file, "{}", csv).expect("failed to write file");
+
+        let temp_dir = TempDir::new().unwrap();
+        let db = Database::with_kipdb(temp_dir.path()).await?;
+        let _ = db
+            .run("create table test_copy (a int primary key, b varchar)")
+            .await?;
+        let path = file.path().display();
+
+        let tuples = db
+            .run(&format!(
+                "copy test_copy from '{}' ( HEADER ) on_error = continue",
+                path
+            ))
+            .await?;
+        let messages = tuples
+            .iter()
+            .map(|tuple| tuple.values[0].to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[0], "import 2 rows, skip 2 rows");
+        assert!(
+            messages[1].starts_with("line 3: CSV error"),
+            "{}",
+            messages[1]
+        );
+        assert!(messages[2].starts_with("line 4: "), "{}", messages[2]);
+
+        let tuples = db.run("select a from test_copy").await?;
+        assert_eq!(
+            tuples
+                .iter()
+                .map(|tuple| tuple.values[0].to_string())
+                .collect::<Vec<_>>(),
+            vec!["1", "4"]
+        );
+        assert!(db
+            .run(&format!(
+                "copy test_copy from '{}' ( HEADER ) on_error = skip_file",
+                path
+            ))
+            .await
+            .is_err());
+
+        Ok(())
+    }
 }
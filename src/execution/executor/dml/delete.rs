@@ -30,46 +30,24 @@ impl Delete {
     #[try_stream(boxed, ok = Tuple, error = ExecutorError)]
     async fn _execute<T: Transaction>(self, transaction: &mut T) {
         let Delete { table_name, input } = self;
-        let option_index_metas = transaction.table(table_name.clone()).map(|table_catalog| {
-            table_catalog
-                .all_columns()
-                .into_iter()
-                .enumerate()
-                .filter_map(|(i, col)| {
-                    col.desc
-                        .is_unique
-                        .then(|| {
-                            col.id().and_then(|col_id| {
-                                table_catalog
-                                    .get_unique_index(&col_id)
-                                    .map(|index_meta| (i, index_meta.clone()))
-                            })
-                        })
-                        .flatten()
-                })
-                .collect_vec()
-        });
+        let option_index_metas = transaction
+            .table(table_name.clone())
+            .map(|table_catalog| table_catalog.secondary_indexes().cloned().collect_vec());
 
         if let Some(index_metas) = option_index_metas {
             #[for_await]
             for tuple in input {
                 let tuple: Tuple = tuple?;
 
-                for (i, index_meta) in index_metas.iter() {
-                    let value = &tuple.values[*i];
+                if let Some(tuple_id) = &tuple.id {
+                    for index_meta in index_metas.iter() {
+                        if let Some(column_values) = index_meta.column_values(&tuple)? {
+                            let index = Index::new(index_meta.id, column_values);
 
-                    if !value.is_null() {
-                        let index = Index {
-                            id: index_meta.id,
-                            column_values: vec![value.clone()],
-                        };
-
-                        transaction.del_index(&table_name, &index)?;
+                            transaction.del_index(&table_name, &index, tuple_id)?;
+                        }
                     }
-                }
-
-                if let Some(tuple_id) = tuple.id {
-                    transaction.delete(&table_name, tuple_id)?;
+                    transaction.delete(&table_name, tuple_id.clone())?;
                 }
             }
         }
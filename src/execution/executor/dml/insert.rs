@@ -1,20 +1,28 @@
-use crate::catalog::TableName;
+use crate::catalog::{TableCatalog, TableName};
 use crate::execution::executor::{BoxedExecutor, Executor};
 use crate::execution::ExecutorError;
+use crate::expression::simplify::ConstantBinary;
+use crate::expression::ScalarExpression;
 use crate::planner::operator::insert::InsertOperator;
-use crate::storage::Transaction;
+use crate::storage::{projection_schema, unpack_aliases, Iter, Transaction};
+use crate::types::errors::TypeError;
 use crate::types::index::Index;
 use crate::types::tuple::Tuple;
-use crate::types::value::DataValue;
+use crate::types::value::{DataValue, ValueRef};
+use crate::types::LogicalType;
 use futures_async_stream::try_stream;
+use itertools::Itertools;
 use std::cell::RefCell;
+use std::collections::hash_map::Entry;
 use std::collections::HashMap;
-use std::sync::Arc;
 
 pub struct Insert {
     table_name: TableName,
     input: BoxedExecutor,
     is_overwrite: bool,
+    is_replace: bool,
+    overriding_system_value: bool,
+    returning: Vec<ScalarExpression>,
 }
 
 impl From<(InsertOperator, BoxedExecutor)> for Insert {
@@ -23,6 +31,9 @@ impl From<(InsertOperator, BoxedExecutor)> for Insert {
             InsertOperator {
                 table_name,
                 is_overwrite,
+                is_replace,
+                overriding_system_value,
+                returning,
             },
             input,
         ): (InsertOperator, BoxedExecutor),
@@ -31,6 +42,9 @@ impl From<(InsertOperator, BoxedExecutor)> for Insert {
             table_name,
             input,
             is_overwrite,
+            is_replace,
+            overriding_system_value,
+            returning,
         }
     }
 }
@@ -41,6 +55,95 @@ impl<T: Transaction> Executor<T> for Insert {
     }
 }
 
+/// The id as a value of the integer type of the identity column, an overflow once the ids
+/// are past the largest value of the type.
+fn identity_value(id: u64, ty: &LogicalType) -> Result<DataValue, TypeError> {
+    let overflow = |_| TypeError::Overflow(*ty);
+
+    Ok(match ty {
+        LogicalType::Tinyint => DataValue::Int8(Some(i8::try_from(id).map_err(overflow)?)),
+        LogicalType::UTinyint => DataValue::UInt8(Some(u8::try_from(id).map_err(overflow)?)),
+        LogicalType::Smallint => DataValue::Int16(Some(i16::try_from(id).map_err(overflow)?)),
+        LogicalType::USmallint => DataValue::UInt16(Some(u16::try_from(id).map_err(overflow)?)),
+        LogicalType::Integer => DataValue::Int32(Some(i32::try_from(id).map_err(overflow)?)),
+        LogicalType::UInteger => DataValue::UInt32(Some(u32::try_from(id).map_err(overflow)?)),
+        LogicalType::Bigint => DataValue::Int64(Some(i64::try_from(id).map_err(overflow)?)),
+        LogicalType::UBigint => DataValue::UInt64(Some(id)),
+        _ => unreachable!("identity columns are bound as integers"),
+    })
+}
+
+/// Deletes the tuples of the table having the primary key of `tuple`, or its values of a
+/// unique index, with all their index entries. The nulls never collide, see
+/// `IndexMeta::column_values`.
+fn delete_conflicts<T: Transaction>(
+    transaction: &mut T,
+    table_catalog: &TableCatalog,
+    tuple: &Tuple,
+) -> Result<(), ExecutorError> {
+    let tuple_id = tuple.id.as_ref().ok_or(TypeError::PrimaryKeyNotFound)?;
+    let mut conflicts = Vec::new();
+
+    if let Some(old) = transaction.get_tuple(&table_catalog.name, tuple_id)? {
+        conflicts.push(old);
+    }
+    let projections = table_catalog
+        .all_columns()
+        .into_iter()
+        .map(ScalarExpression::ColumnRef)
+        .collect_vec();
+
+    for index_meta in table_catalog
+        .secondary_indexes()
+        .filter(|index_meta| index_meta.is_unique)
+    {
+        let Some(mut column_values) = index_meta.column_values(tuple)? else {
+            continue;
+        };
+        let last = ConstantBinary::Eq(column_values.pop().unwrap());
+        let binary = if column_values.is_empty() {
+            last
+        } else {
+            ConstantBinary::Prefix {
+                values: column_values,
+                binary: Box::new(last),
+            }
+        };
+        let mut iter = transaction.read_by_index(
+            table_catalog.name.clone(),
+            (None, None),
+            projections.clone(),
+            index_meta.clone(),
+            vec![binary],
+            false,
+        )?;
+
+        while let Some(old) = iter.next_tuple()? {
+            if !conflicts
+                .iter()
+                .any(|conflict: &Tuple| conflict.id == old.id)
+            {
+                conflicts.push(old);
+            }
+        }
+    }
+    for old in conflicts {
+        let Some(old_id) = old.id.clone() else {
+            continue;
+        };
+        for index_meta in table_catalog.secondary_indexes() {
+            if let Some(column_values) = index_meta.column_values(&old)? {
+                let index = Index::new(index_meta.id, column_values);
+
+                transaction.del_index(&table_catalog.name, &index, &old_id)?;
+            }
+        }
+        transaction.delete(&table_catalog.name, old_id)?;
+    }
+
+    Ok(())
+}
+
 impl Insert {
     #[try_stream(boxed, ok = Tuple, error = ExecutorError)]
     pub async fn _execute<T: Transaction>(self, transaction: &mut T) {
@@ -48,76 +151,144 @@ impl Insert {
             table_name,
             input,
             is_overwrite,
+            is_replace,
+            overriding_system_value,
+            returning,
         } = self;
-        let mut primary_key_index = None;
-        let mut unique_values = HashMap::new();
+        let mut indexes = Vec::new();
+        let mut returned = Vec::new();
 
         if let Some(table_catalog) = transaction.table(table_name.clone()).cloned() {
+            let primary_key = table_catalog
+                .all_columns()
+                .into_iter()
+                .filter(|col| col.desc.is_primary)
+                .collect_vec();
+            let identity = table_catalog
+                .all_columns()
+                .into_iter()
+                .find(|col| col.desc.identity.is_some());
+            let returning_schema = projection_schema(&returning);
+            let returning = unpack_aliases(returning);
+
             #[for_await]
             for tuple in input {
                 let Tuple {
-                    columns, values, ..
+                    schema_ref, values, ..
                 } = tuple?;
                 let mut tuple_map = HashMap::new();
                 for (i, value) in values.into_iter().enumerate() {
-                    let col = &columns[i];
+                    let col = &schema_ref[i];
 
                     if let Some(col_id) = col.id() {
                         tuple_map.insert(col_id, value);
                     }
                 }
-                let primary_col_id = primary_key_index.get_or_insert_with(|| {
-                    columns
-                        .iter()
-                        .find(|col| col.desc.is_primary)
-                        .map(|col| col.id().unwrap())
-                        .unwrap()
-                });
+                // the identity column and the hidden rowid of a row take the same id
+                let mut generated_id = None;
+
+                if let Some(col) = &identity {
+                    match tuple_map.entry(col.id().unwrap()) {
+                        Entry::Vacant(entry) => {
+                            let id = transaction.next_rowid(&table_name)?;
+                            generated_id = Some(id);
+                            entry.insert(ValueRef::new(identity_value(id, col.datatype())?));
+                        }
+                        // e.g. the ids of a dump, the next ones are generated after them
+                        Entry::Occupied(entry) if overriding_system_value => {
+                            let given_id = DataValue::clone(entry.get())
+                                .cast(&LogicalType::UBigint)
+                                .ok()
+                                .and_then(|value| value.u64());
+
+                            if let Some(id) = given_id {
+                                transaction.skip_rowid(&table_name, id)?;
+                            }
+                        }
+                        Entry::Occupied(_) => (),
+                    }
+                }
+                for col in primary_key.iter() {
+                    if let Entry::Vacant(entry) = tuple_map.entry(col.id().unwrap()) {
+                        if !col.desc.is_hidden {
+                            Err(TypeError::PrimaryKeyNotFound)?
+                        }
+                        let rowid = match generated_id {
+                            Some(id) => id,
+                            None => transaction.next_rowid(&table_name)?,
+                        };
+                        entry.insert(ValueRef::new(DataValue::UInt64(Some(rowid))));
+                    }
+                }
                 let all_columns = table_catalog.all_columns_with_id();
-                let tuple_id = tuple_map.get(primary_col_id).cloned().unwrap();
                 let mut tuple = Tuple {
-                    id: Some(tuple_id.clone()),
-                    columns: Vec::with_capacity(all_columns.len()),
+                    id: None,
+                    schema_ref: table_catalog.schema_ref().clone(),
                     values: Vec::with_capacity(all_columns.len()),
                 };
                 for (col_id, col) in all_columns {
-                    let value = tuple_map
-                        .remove(col_id)
-                        .or_else(|| col.default_value())
-                        .unwrap_or_else(|| Arc::new(DataValue::none(col.datatype())));
-
-                    if col.desc.is_unique && !value.is_null() {
-                        unique_values
-                            .entry(col.id())
-                            .or_insert_with(Vec::new)
-                            .push((tuple_id.clone(), value.clone()))
-                    }
-                    if value.is_null() && !col.nullable {
+                    let value = match tuple_map.remove(col_id) {
+                        Some(value) => value,
+                        // evaluated for each row, e.g. `DEFAULT gen_random_uuid()`
+                        None => match &col.desc.default_expr {
+                            Some(default_expr) => {
+                                let value = default_expr.expr.eval(&tuple, &[])?;
+
+                                value.check_len(col.datatype())?;
+                                ValueRef::new(DataValue::clone(&value).cast(col.datatype())?)
+                            }
+                            None => col
+                                .default_value()
+                                .unwrap_or_else(|| ValueRef::new(DataValue::none(col.datatype()))),
+                        },
+                    };
+
+                    if value.is_null() && !col.nullable && col.desc.generated.is_none() {
                         return Err(ExecutorError::InternalError(format!(
                             "Non-null fields do not allow null values to be passed in: {:?}",
                             col
                         )));
                     }
 
-                    tuple.columns.push(col.clone());
                     tuple.values.push(value)
                 }
+                tuple.eval_generated_columns()?;
+                let tuple_id = tuple.primary_key().ok_or(TypeError::PrimaryKeyNotFound)?;
+                tuple.id = Some(tuple_id.clone());
 
-                transaction.append(&table_name, tuple, is_overwrite)?;
-            }
-            // Unique Index
-            for (col_id, values) in unique_values {
-                if let Some(index_meta) = table_catalog.get_unique_index(&col_id.unwrap()) {
-                    for (tuple_id, value) in values {
-                        let index = Index {
-                            id: index_meta.id,
-                            column_values: vec![value],
-                        };
+                if is_replace {
+                    delete_conflicts(transaction, &table_catalog, &tuple)?;
+                }
+
+                for index_meta in table_catalog.secondary_indexes() {
+                    if let Some(column_values) = index_meta.column_values(&tuple)? {
+                        indexes.push((
+                            Index::new(index_meta.id, column_values),
+                            tuple_id.clone(),
+                            index_meta.is_unique,
+                        ));
+                    }
+                }
 
-                        transaction.add_index(&table_name, index, vec![tuple_id], true)?;
+                if !returning.is_empty() {
+                    returned.push(tuple.project(&returning, &returning_schema)?);
+                }
+
+                transaction.append(&table_name, tuple, is_overwrite)?;
+                // a later row of the statement replacing this one has to find its entries
+                if is_replace {
+                    for (index, tuple_id, is_unique) in indexes.drain(..) {
+                        transaction.add_index(&table_name, index, vec![tuple_id], is_unique)?;
                     }
                 }
             }
+            for (index, tuple_id, is_unique) in indexes {
+                transaction.add_index(&table_name, index, vec![tuple_id], is_unique)?;
+            }
+        }
+        // only once all the rows are written, a row breaking a unique index returns nothing
+        for tuple in returned {
+            yield tuple;
         }
     }
 }
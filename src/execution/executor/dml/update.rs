@@ -1,30 +1,36 @@
-use crate::catalog::TableName;
+use crate::catalog::{ColumnRef, TableName};
 use crate::execution::executor::{BoxedExecutor, Executor};
 use crate::execution::ExecutorError;
 use crate::planner::operator::update::UpdateOperator;
+use crate::storage::table_codec::TableCodec;
 use crate::storage::Transaction;
-use crate::types::index::Index;
 use crate::types::tuple::Tuple;
 use futures_async_stream::try_stream;
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 
 pub struct Update {
     table_name: TableName,
+    source_columns: Vec<ColumnRef>,
     input: BoxedExecutor,
     values: BoxedExecutor,
 }
 
 impl From<(UpdateOperator, BoxedExecutor, BoxedExecutor)> for Update {
     fn from(
-        (UpdateOperator { table_name }, input, values): (
-            UpdateOperator,
-            BoxedExecutor,
-            BoxedExecutor,
-        ),
+        (
+            UpdateOperator {
+                table_name,
+                source_columns,
+            },
+            input,
+            values,
+        ): (UpdateOperator, BoxedExecutor, BoxedExecutor),
     ) -> Self {
         Update {
             table_name,
+            source_columns,
             input,
             values,
         }
@@ -42,6 +48,7 @@ impl Update {
     pub async fn _execute<T: Transaction>(self, transaction: &mut T) {
         let Update {
             table_name,
+            source_columns,
             input,
             values,
         } = self;
@@ -53,52 +60,59 @@ impl Update {
             #[for_await]
             for tuple in values {
                 let Tuple {
-                    columns, values, ..
+                    schema_ref, values, ..
                 } = tuple?;
-                for i in 0..columns.len() {
-                    value_map.insert(columns[i].id(), values[i].clone());
+                for i in 0..schema_ref.len() {
+                    value_map.insert(schema_ref[i].id(), values[i].clone());
                 }
             }
+            let table_len = table_catalog.all_columns().len();
+            let mut table_schema = None;
+            // the target rows already updated by `UPDATE .. FROM`
+            let mut updated_keys = HashSet::new();
+
             #[for_await]
             for tuple in input {
                 let mut tuple: Tuple = tuple?;
-                let mut is_overwrite = true;
-
-                for (i, column) in tuple.columns.iter().enumerate() {
-                    if let Some(value) = value_map.get(&column.id()) {
-                        if column.desc.is_primary {
-                            let old_key = tuple.id.replace(value.clone()).unwrap();
 
-                            transaction.delete(&table_name, old_key)?;
-                            is_overwrite = false;
-                        }
-                        if column.desc.is_unique && value != &tuple.values[i] {
-                            if let Some(index_meta) =
-                                table_catalog.get_unique_index(&column.id().unwrap())
-                            {
-                                let mut index = Index {
-                                    id: index_meta.id,
-                                    column_values: vec![tuple.values[i].clone()],
-                                };
-                                transaction.del_index(&table_name, &index)?;
+                if !source_columns.is_empty() {
+                    value_map.clear();
+                    for (column, value) in
+                        source_columns.iter().zip(tuple.values.drain(table_len..))
+                    {
+                        value.check_len(column.datatype())?;
+                        value_map.insert(column.id(), value);
+                    }
+                    tuple.schema_ref = table_schema
+                        .get_or_insert_with(|| Arc::new(tuple.schema_ref[..table_len].to_vec()))
+                        .clone();
+                    tuple.id = tuple.primary_key();
 
-                                if !value.is_null() {
-                                    index.column_values[0] = value.clone();
-                                    transaction.add_index(
-                                        &table_name,
-                                        index,
-                                        vec![tuple.id.clone().unwrap()],
-                                        true,
-                                    )?;
-                                }
-                            }
-                        }
+                    let key =
+                        TableCodec::encode_tuple_key(&table_name, tuple.id.as_ref().unwrap())?;
+                    if !updated_keys.insert(key) {
+                        Err(ExecutorError::InternalError(format!(
+                            "a row of {} is matched by more than one row of the source",
+                            table_name
+                        )))?;
+                    }
+                }
+                let old_tuple = tuple.clone();
+                let mut is_key_changed = false;
 
+                for (i, column) in tuple.schema_ref.iter().enumerate() {
+                    if let Some(value) = value_map.get(&column.id()) {
+                        is_key_changed |= column.desc.is_primary;
                         tuple.values[i] = value.clone();
                     }
                 }
+                if is_key_changed {
+                    tuple.id = tuple.primary_key();
+                }
+                tuple.eval_on_update_columns(|col| value_map.contains_key(&col.id()))?;
+                tuple.eval_generated_columns()?;
 
-                transaction.append(&table_name, tuple, is_overwrite)?;
+                transaction.update_tuple(&table_name, &old_tuple, tuple)?;
             }
         }
     }
@@ -0,0 +1,146 @@
+use crate::execution::executor::dql::aggregate::Accumulator;
+use crate::execution::ExecutorError;
+use crate::types::value::{DataValue, GroupKey, ValueRef};
+use ahash::RandomState;
+
+/// Number of bits of the hash picking the register.
+const PRECISION: u32 = 12;
+const REGISTERS: usize = 1 << PRECISION;
+/// Registers packed into each value of the partial state.
+const REGISTERS_PER_WORD: usize = 8;
+
+/// Counts the distinct values with a HyperLogLog sketch of 4096 registers, a fixed 4KiB
+/// per accumulator however many values there are.
+///
+/// The standard error of the estimate is `1.04 / sqrt(4096)`, about 1.6%, so it is within 5%
+/// of the exact count in nearly all cases. Small counts use linear counting and are
+/// almost always exact.
+pub struct ApproxCountDistinctAccumulator {
+    registers: Vec<u8>,
+    hash_builder: RandomState,
+}
+
+impl ApproxCountDistinctAccumulator {
+    pub fn new() -> Self {
+        Self {
+            registers: vec![0; REGISTERS],
+            // the sketches are only mergeable if they all hash the same way
+            hash_builder: RandomState::with_seeds(
+                0x243f_6a88_85a3_08d3,
+                0x1319_8a2e_0370_7344,
+                0xa409_3822_299f_31d0,
+                0x082e_fa98_ec4e_6c89,
+            ),
+        }
+    }
+}
+
+impl Accumulator for ApproxCountDistinctAccumulator {
+    fn update_value(&mut self, value: &ValueRef) -> Result<(), ExecutorError> {
+        if !value.is_null() {
+            let hash = self.hash_builder.hash_one(GroupKey(value.clone()));
+            let index = (hash >> (64 - PRECISION)) as usize;
+            // the guard bit bounds the rank when the remaining bits are all zero
+            let rank = ((hash << PRECISION) | (1 << (PRECISION - 1))).leading_zeros() as u8 + 1;
+
+            self.registers[index] = self.registers[index].max(rank);
+        }
+
+        Ok(())
+    }
+
+    fn evaluate(&self) -> Result<ValueRef, ExecutorError> {
+        let m = REGISTERS as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let sum: f64 = self
+            .registers
+            .iter()
+            .map(|register| 2f64.powi(-(*register as i32)))
+            .sum();
+        let zeros = self
+            .registers
+            .iter()
+            .filter(|register| **register == 0)
+            .count();
+        let mut estimate = alpha * m * m / sum;
+
+        if estimate <= 2.5 * m && zeros > 0 {
+            estimate = m * (m / zeros as f64).ln();
+        }
+
+        Ok(ValueRef::new(DataValue::Int32(Some(
+            estimate.round() as i32
+        ))))
+    }
+
+    fn state(&self) -> Vec<ValueRef> {
+        self.registers
+            .chunks(REGISTERS_PER_WORD)
+            .map(|chunk| {
+                let mut bytes = [0; REGISTERS_PER_WORD];
+                bytes.copy_from_slice(chunk);
+
+                ValueRef::new(DataValue::UInt64(Some(u64::from_le_bytes(bytes))))
+            })
+            .collect()
+    }
+
+    fn merge_state(&mut self, state: &[ValueRef]) -> Result<(), ExecutorError> {
+        for (chunk, word) in self.registers.chunks_mut(REGISTERS_PER_WORD).zip(state) {
+            if let DataValue::UInt64(Some(word)) = word.as_ref() {
+                for (register, other) in chunk.iter_mut().zip(word.to_le_bytes()) {
+                    *register = (*register).max(other);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::execution::executor::dql::aggregate::approx_count::ApproxCountDistinctAccumulator;
+    use crate::execution::executor::dql::aggregate::Accumulator;
+    use crate::execution::ExecutorError;
+    use crate::types::value::{DataValue, ValueRef};
+
+    fn estimate(acc: &ApproxCountDistinctAccumulator) -> Result<i32, ExecutorError> {
+        match acc.evaluate()?.as_ref() {
+            DataValue::Int32(Some(count)) => Ok(*count),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_approx_count_distinct() -> Result<(), ExecutorError> {
+        let mut small = ApproxCountDistinctAccumulator::new();
+        for i in [1, 2, 3, 2, 1] {
+            small.update_value(&ValueRef::new(DataValue::Int32(Some(i))))?;
+        }
+        small.update_value(&ValueRef::new(DataValue::Int32(None)))?;
+        assert_eq!(estimate(&small)?, 3);
+
+        let exact = 100_000;
+        let mut left = ApproxCountDistinctAccumulator::new();
+        let mut right = ApproxCountDistinctAccumulator::new();
+        // the even values are seen twice, the odd ones by both accumulators
+        for i in 0..exact {
+            let value = ValueRef::new(DataValue::Int64(Some(i * 7919)));
+            left.update_value(&value)?;
+            if i % 2 == 0 {
+                left.update_value(&value)?;
+            } else {
+                right.update_value(&value)?;
+            }
+        }
+        let within_tolerance =
+            |count: i32| (count as f64 - exact as f64).abs() / (exact as f64) < 0.05;
+        assert!(within_tolerance(estimate(&left)?));
+
+        left.merge_state(&right.state())?;
+        assert!(within_tolerance(estimate(&left)?));
+
+        Ok(())
+    }
+}
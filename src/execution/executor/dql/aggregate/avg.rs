@@ -5,7 +5,6 @@ use crate::expression::value_compute::binary_op;
 use crate::expression::BinaryOperator;
 use crate::types::value::{DataValue, ValueRef};
 use crate::types::LogicalType;
-use std::sync::Arc;
 
 pub struct AvgAccumulator {
     inner: SumAccumulator,
@@ -40,10 +39,26 @@ impl Accumulator for AvgAccumulator {
             DataValue::UInt32(Some(self.count as u32))
         };
 
-        Ok(Arc::new(binary_op(
+        Ok(ValueRef::new(binary_op(
             &value,
             &quantity,
             &BinaryOperator::Divide,
         )?))
     }
+
+    fn state(&self) -> Vec<ValueRef> {
+        let mut state = self.inner.state();
+        state.push(ValueRef::new(DataValue::Int64(Some(self.count as i64))));
+
+        state
+    }
+
+    fn merge_state(&mut self, state: &[ValueRef]) -> Result<(), ExecutorError> {
+        self.inner.merge_state(&state[..1])?;
+        if let DataValue::Int64(Some(count)) = state[1].as_ref() {
+            self.count += *count as usize;
+        }
+
+        Ok(())
+    }
 }
@@ -1,9 +1,8 @@
 use crate::execution::executor::dql::aggregate::Accumulator;
 use crate::execution::ExecutorError;
-use crate::types::value::{DataValue, ValueRef};
+use crate::types::value::{DataValue, GroupKey, ValueRef};
 use ahash::RandomState;
 use std::collections::HashSet;
-use std::sync::Arc;
 
 pub struct CountAccumulator {
     result: i32,
@@ -25,12 +24,24 @@ impl Accumulator for CountAccumulator {
     }
 
     fn evaluate(&self) -> Result<ValueRef, ExecutorError> {
-        Ok(Arc::new(DataValue::Int32(Some(self.result))))
+        Ok(ValueRef::new(DataValue::Int32(Some(self.result))))
+    }
+
+    fn state(&self) -> Vec<ValueRef> {
+        vec![ValueRef::new(DataValue::Int32(Some(self.result)))]
+    }
+
+    fn merge_state(&mut self, state: &[ValueRef]) -> Result<(), ExecutorError> {
+        if let DataValue::Int32(Some(count)) = state[0].as_ref() {
+            self.result += count;
+        }
+
+        Ok(())
     }
 }
 
 pub struct DistinctCountAccumulator {
-    distinct_values: HashSet<ValueRef, RandomState>,
+    distinct_values: HashSet<GroupKey, RandomState>,
 }
 
 impl DistinctCountAccumulator {
@@ -44,15 +55,29 @@ impl DistinctCountAccumulator {
 impl Accumulator for DistinctCountAccumulator {
     fn update_value(&mut self, value: &ValueRef) -> Result<(), ExecutorError> {
         if !value.is_null() {
-            self.distinct_values.insert(value.clone());
+            self.distinct_values.insert(GroupKey(value.clone()));
         }
 
         Ok(())
     }
 
     fn evaluate(&self) -> Result<ValueRef, ExecutorError> {
-        Ok(Arc::new(DataValue::Int32(Some(
-            self.distinct_values.len() as i32
+        Ok(ValueRef::new(DataValue::Int32(Some(
+            self.distinct_values.len() as i32,
         ))))
     }
+
+    fn state(&self) -> Vec<ValueRef> {
+        self.distinct_values
+            .iter()
+            .map(|key| key.0.clone())
+            .collect()
+    }
+
+    fn merge_state(&mut self, state: &[ValueRef]) -> Result<(), ExecutorError> {
+        self.distinct_values
+            .extend(state.iter().cloned().map(GroupKey));
+
+        Ok(())
+    }
 }
@@ -0,0 +1,34 @@
+use crate::execution::executor::dql::aggregate::Accumulator;
+use crate::execution::ExecutorError;
+use crate::types::value::{DataValue, ValueRef};
+
+/// `GROUPING(expr)`: 1 if the expr is rolled up in the grouping set of the row, otherwise 0.
+pub struct GroupingAccumulator {
+    result: ValueRef,
+}
+
+impl GroupingAccumulator {
+    pub fn new(is_rolled_up: bool) -> Self {
+        Self {
+            result: ValueRef::new(DataValue::Int32(Some(is_rolled_up as i32))),
+        }
+    }
+}
+
+impl Accumulator for GroupingAccumulator {
+    fn update_value(&mut self, _value: &ValueRef) -> Result<(), ExecutorError> {
+        Ok(())
+    }
+
+    fn evaluate(&self) -> Result<ValueRef, ExecutorError> {
+        Ok(self.result.clone())
+    }
+
+    fn state(&self) -> Vec<ValueRef> {
+        vec![]
+    }
+
+    fn merge_state(&mut self, _state: &[ValueRef]) -> Result<(), ExecutorError> {
+        Ok(())
+    }
+}
@@ -1,19 +1,32 @@
-use crate::execution::executor::dql::aggregate::create_accumulators;
+use crate::execution::executor::dql::aggregate::{create_accumulators, Accumulator};
+use crate::execution::executor::dql::spill::SpillFile;
 use crate::execution::executor::{BoxedExecutor, Executor};
 use crate::execution::ExecutorError;
 use crate::expression::ScalarExpression;
 use crate::planner::operator::aggregate::AggregateOperator;
 use crate::storage::Transaction;
-use crate::types::tuple::Tuple;
-use crate::types::value::ValueRef;
-use ahash::{HashMap, HashMapExt};
+use crate::types::errors::TypeError;
+use crate::types::tuple::{SchemaRef, Tuple};
+use crate::types::value::{DataValue, GroupKey, ValueRef};
+use ahash::{HashMap, RandomState};
 use futures_async_stream::try_stream;
 use itertools::Itertools;
 use std::cell::RefCell;
 
+/// Number of files the groups are partitioned into by their hash once they are spilled.
+const SPILL_PARTITIONS: usize = 16;
+
+/// The grouping set index and the group keys.
+type Group = (usize, Vec<GroupKey>);
+
 pub struct HashAggExecutor {
     pub agg_calls: Vec<ScalarExpression>,
     pub groupby_exprs: Vec<ScalarExpression>,
+    pub grouping_sets: Vec<Vec<usize>>,
+    /// The number of groups in memory above which their partial states are spilled.
+    pub spill_threshold: Option<usize>,
+    /// The hasher of the groups, which are yielded in the order of their hashes.
+    pub random_state: RandomState,
     pub input: BoxedExecutor,
 }
 
@@ -23,6 +36,7 @@ impl From<(AggregateOperator, BoxedExecutor)> for HashAggExecutor {
             AggregateOperator {
                 agg_calls,
                 groupby_exprs,
+                grouping_sets,
             },
             input,
         ): (AggregateOperator, BoxedExecutor),
@@ -30,6 +44,9 @@ impl From<(AggregateOperator, BoxedExecutor)> for HashAggExecutor {
         HashAggExecutor {
             agg_calls,
             groupby_exprs,
+            grouping_sets,
+            spill_threshold: None,
+            random_state: RandomState::new(),
             input,
         }
     }
@@ -42,10 +59,40 @@ impl<T: Transaction> Executor<T> for HashAggExecutor {
 }
 
 impl HashAggExecutor {
+    pub fn with_spill_threshold(mut self, spill_threshold: Option<usize>) -> Self {
+        self.spill_threshold = spill_threshold;
+        self
+    }
+
+    pub fn with_random_state(mut self, random_state: RandomState) -> Self {
+        self.random_state = random_state;
+        self
+    }
+
     #[try_stream(boxed, ok = Tuple, error = ExecutorError)]
     pub async fn _execute(self) {
-        let mut group_and_agg_columns_option = None;
-        let mut group_hash_accs = HashMap::new();
+        let mut group_and_agg_columns_option: Option<SchemaRef> = None;
+        let mut group_hash_accs: HashMap<Group, Vec<Box<dyn Accumulator>>> =
+            HashMap::with_hasher(self.random_state.clone());
+        let hash_builder = self.random_state.clone();
+        let mut partitions = Vec::new();
+        // a plain group by is the grouping set of all the group by exprs
+        let grouping_sets = if self.grouping_sets.is_empty() {
+            vec![(0..self.groupby_exprs.len()).collect_vec()]
+        } else {
+            self.grouping_sets.clone()
+        };
+        let rolled_up_exprs = grouping_sets
+            .iter()
+            .map(|set| {
+                self.groupby_exprs
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| !set.contains(i))
+                    .map(|(_, expr)| expr)
+                    .collect_vec()
+            })
+            .collect_vec();
 
         #[for_await]
         for tuple in self.input {
@@ -59,6 +106,7 @@ impl HashAggExecutor {
                     .chain(self.groupby_exprs.iter())
                     .map(|expr| expr.output_columns())
                     .collect_vec()
+                    .into()
             });
 
             // 2.1 evaluate agg exprs and collect the result values for later accumulators.
@@ -67,7 +115,7 @@ impl HashAggExecutor {
                 .iter()
                 .map(|expr| {
                     if let ScalarExpression::AggCall { args, .. } = expr {
-                        args[0].eval(&tuple)
+                        args[0].eval(&tuple, &tuple.schema_ref)
                     } else {
                         unreachable!()
                     }
@@ -77,36 +125,111 @@ impl HashAggExecutor {
             let group_keys: Vec<ValueRef> = self
                 .groupby_exprs
                 .iter()
-                .map(|expr| expr.eval(&tuple))
+                .map(|expr| expr.eval(&tuple, &tuple.schema_ref))
                 .try_collect()?;
 
-            for (acc, value) in group_hash_accs
-                .entry(group_keys)
-                .or_insert_with(|| create_accumulators(&self.agg_calls))
-                .iter_mut()
-                .zip_eq(values.iter())
+            for (set_index, set) in grouping_sets.iter().enumerate() {
+                // the rolled up exprs are filled with NULL
+                let set_keys = group_keys
+                    .iter()
+                    .zip_eq(self.groupby_exprs.iter())
+                    .enumerate()
+                    .map(|(i, (key, expr))| {
+                        if set.contains(&i) {
+                            GroupKey(key.clone())
+                        } else {
+                            GroupKey(ValueRef::new(DataValue::none(&expr.return_type())))
+                        }
+                    })
+                    .collect_vec();
+
+                for (acc, value) in group_hash_accs
+                    .entry((set_index, set_keys))
+                    .or_insert_with(|| {
+                        create_accumulators(&self.agg_calls, &rolled_up_exprs[set_index])
+                    })
+                    .iter_mut()
+                    .zip_eq(values.iter())
+                {
+                    acc.update_value(value)?;
+                }
+            }
+
+            if matches!(self.spill_threshold, Some(threshold) if group_hash_accs.len() > threshold)
             {
-                acc.update_value(value)?;
+                Self::spill(&mut partitions, &hash_builder, &mut group_hash_accs)?;
             }
         }
 
         if let Some(group_and_agg_columns) = group_and_agg_columns_option {
-            for (group_keys, accs) in group_hash_accs {
-                // Tips: Accumulator First
-                let values: Vec<ValueRef> = accs
-                    .iter()
-                    .map(|acc| acc.evaluate())
-                    .chain(group_keys.into_iter().map(Ok))
-                    .try_collect()?;
-
-                yield Tuple {
-                    id: None,
-                    columns: group_and_agg_columns.clone(),
-                    values,
+            if !partitions.is_empty() {
+                Self::spill(&mut partitions, &hash_builder, &mut group_hash_accs)?;
+            }
+            // all the partial states of a group are in the same partition,
+            // so each partition is merged and emitted on its own
+            let mut partitions = partitions.into_iter();
+
+            loop {
+                for ((_, group_keys), accs) in group_hash_accs.drain() {
+                    // Tips: Accumulator First
+                    let values: Vec<ValueRef> = accs
+                        .iter()
+                        .map(|acc| acc.evaluate())
+                        .chain(group_keys.into_iter().map(|key| Ok(key.0)))
+                        .try_collect()?;
+
+                    yield Tuple {
+                        id: None,
+                        schema_ref: group_and_agg_columns.clone(),
+                        values,
+                    };
+                }
+                let Some(mut partition) = partitions.next() else {
+                    break;
                 };
+                let mut reader = partition.reader()?;
+
+                while let Some(bytes) = reader.next()? {
+                    let (group_key, states): (Group, Vec<Vec<ValueRef>>) =
+                        bincode::deserialize(&bytes).map_err(TypeError::from)?;
+                    let set_index = group_key.0;
+
+                    for (acc, state) in group_hash_accs
+                        .entry(group_key)
+                        .or_insert_with(|| {
+                            create_accumulators(&self.agg_calls, &rolled_up_exprs[set_index])
+                        })
+                        .iter_mut()
+                        .zip_eq(states.iter())
+                    {
+                        acc.merge_state(state)?;
+                    }
+                }
             }
         }
     }
+
+    /// Moves the partial states of the groups into the partition files by the hash of the group.
+    fn spill(
+        partitions: &mut Vec<SpillFile>,
+        hash_builder: &RandomState,
+        group_hash_accs: &mut HashMap<Group, Vec<Box<dyn Accumulator>>>,
+    ) -> Result<(), ExecutorError> {
+        if partitions.is_empty() {
+            for _ in 0..SPILL_PARTITIONS {
+                partitions.push(SpillFile::new()?);
+            }
+        }
+        for (group_key, accs) in group_hash_accs.drain() {
+            let partition = hash_builder.hash_one(&group_key) as usize % SPILL_PARTITIONS;
+            let states = accs.iter().map(|acc| acc.state()).collect_vec();
+            let bytes = bincode::serialize(&(group_key, states)).map_err(TypeError::from)?;
+
+            partitions[partition].write(&bytes)?;
+        }
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -124,7 +247,7 @@ mod test {
     use crate::storage::kip::KipStorage;
     use crate::storage::Storage;
     use crate::types::tuple::create_table;
-    use crate::types::value::DataValue;
+    use crate::types::value::{DataValue, ValueRef};
     use crate::types::LogicalType;
     use itertools::Itertools;
     use std::cell::RefCell;
@@ -167,29 +290,30 @@ mod test {
                 args: vec![ScalarExpression::ColumnRef(t1_columns[1].clone())],
                 ty: LogicalType::Integer,
             }],
+            grouping_sets: vec![],
         };
 
         let input = Values::from(ValuesOperator {
             rows: vec![
                 vec![
-                    Arc::new(DataValue::Int32(Some(0))),
-                    Arc::new(DataValue::Int32(Some(2))),
-                    Arc::new(DataValue::Int32(Some(4))),
+                    ValueRef::new(DataValue::Int32(Some(0))),
+                    ValueRef::new(DataValue::Int32(Some(2))),
+                    ValueRef::new(DataValue::Int32(Some(4))),
                 ],
                 vec![
-                    Arc::new(DataValue::Int32(Some(1))),
-                    Arc::new(DataValue::Int32(Some(3))),
-                    Arc::new(DataValue::Int32(Some(5))),
+                    ValueRef::new(DataValue::Int32(Some(1))),
+                    ValueRef::new(DataValue::Int32(Some(3))),
+                    ValueRef::new(DataValue::Int32(Some(5))),
                 ],
                 vec![
-                    Arc::new(DataValue::Int32(Some(0))),
-                    Arc::new(DataValue::Int32(Some(1))),
-                    Arc::new(DataValue::Int32(Some(2))),
+                    ValueRef::new(DataValue::Int32(Some(0))),
+                    ValueRef::new(DataValue::Int32(Some(1))),
+                    ValueRef::new(DataValue::Int32(Some(2))),
                 ],
                 vec![
-                    Arc::new(DataValue::Int32(Some(1))),
-                    Arc::new(DataValue::Int32(Some(2))),
-                    Arc::new(DataValue::Int32(Some(3))),
+                    ValueRef::new(DataValue::Int32(Some(1))),
+                    ValueRef::new(DataValue::Int32(Some(2))),
+                    ValueRef::new(DataValue::Int32(Some(3))),
                 ],
             ],
             columns: t1_columns,
@@ -211,4 +335,141 @@ mod test {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_hash_agg_grouping_sets() -> Result<(), ExecutorError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let storage = KipStorage::new(temp_dir.path()).await.unwrap();
+        let transaction = RefCell::new(storage.transaction().await?);
+        let desc = ColumnDesc::new(LogicalType::Integer, false, false, None);
+
+        let t1_columns = ["c1", "c2", "c3"]
+            .into_iter()
+            .map(|name| {
+                Arc::new(ColumnCatalog::new(
+                    name.to_string(),
+                    true,
+                    desc.clone(),
+                    None,
+                ))
+            })
+            .collect_vec();
+        let rows = [(0, 2, 4), (1, 3, 5), (0, 1, 2), (1, 2, 3)]
+            .into_iter()
+            .map(|(c1, c2, c3)| build_integers(vec![Some(c1), Some(c2), Some(c3)]))
+            .collect_vec();
+        let run = |grouping_sets: Vec<Vec<usize>>| {
+            let operator = AggregateOperator {
+                groupby_exprs: vec![
+                    ScalarExpression::ColumnRef(t1_columns[0].clone()),
+                    ScalarExpression::ColumnRef(t1_columns[1].clone()),
+                ],
+                agg_calls: vec![ScalarExpression::AggCall {
+                    distinct: false,
+                    kind: AggKind::Sum,
+                    args: vec![ScalarExpression::ColumnRef(t1_columns[2].clone())],
+                    ty: LogicalType::Integer,
+                }],
+                grouping_sets,
+            };
+            let input = Values::from(ValuesOperator {
+                rows: rows.clone(),
+                columns: t1_columns.clone(),
+            })
+            .execute(&transaction);
+
+            HashAggExecutor::from((operator, input)).execute(&transaction)
+        };
+
+        // ROLLUP(c1, c2): 4 groups + 2 subtotals of c1 + 1 grand total
+        let tuples = try_collect(&mut run(vec![vec![0, 1], vec![0], vec![]])).await?;
+        assert_eq!(tuples.len(), 7);
+
+        let vec_values = tuples.into_iter().map(|tuple| tuple.values).collect_vec();
+        assert!(vec_values.contains(&build_integers(vec![Some(6), Some(0), None])));
+        assert!(vec_values.contains(&build_integers(vec![Some(8), Some(1), None])));
+        assert!(vec_values.contains(&build_integers(vec![Some(14), None, None])));
+
+        // CUBE(c1, c2): the rollup above + 3 subtotals of c2
+        let tuples = try_collect(&mut run(vec![vec![0, 1], vec![0], vec![1], vec![]])).await?;
+        assert_eq!(tuples.len(), 10);
+
+        let vec_values = tuples.into_iter().map(|tuple| tuple.values).collect_vec();
+        assert!(vec_values.contains(&build_integers(vec![Some(7), None, Some(2)])));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_hash_agg_distinct_per_group() -> Result<(), ExecutorError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let storage = KipStorage::new(temp_dir.path()).await.unwrap();
+        let transaction = RefCell::new(storage.transaction().await?);
+        let desc = ColumnDesc::new(LogicalType::Integer, false, false, None);
+
+        let t1_columns = ["c1", "c2"]
+            .into_iter()
+            .map(|name| {
+                Arc::new(ColumnCatalog::new(
+                    name.to_string(),
+                    true,
+                    desc.clone(),
+                    None,
+                ))
+            })
+            .collect_vec();
+        // every group has duplicated values of c1, and some of them are in other groups too
+        let rows = [
+            (Some(1), 0),
+            (Some(1), 0),
+            (Some(2), 0),
+            (Some(1), 1),
+            (Some(1), 1),
+            (Some(1), 1),
+            (Some(3), 2),
+            (Some(4), 2),
+            (Some(3), 2),
+            (None, 2),
+        ]
+        .into_iter()
+        .map(|(c1, c2)| build_integers(vec![c1, Some(c2)]))
+        .collect_vec();
+
+        // the spilled distinct values of a group are only merged into the same group
+        for spill_threshold in [None, Some(1)] {
+            let operator = AggregateOperator {
+                groupby_exprs: vec![ScalarExpression::ColumnRef(t1_columns[1].clone())],
+                agg_calls: vec![ScalarExpression::AggCall {
+                    distinct: true,
+                    kind: AggKind::Count,
+                    args: vec![ScalarExpression::ColumnRef(t1_columns[0].clone())],
+                    ty: LogicalType::Integer,
+                }],
+                grouping_sets: vec![],
+            };
+            let input = Values::from(ValuesOperator {
+                rows: rows.clone(),
+                columns: t1_columns.clone(),
+            })
+            .execute(&transaction);
+            let executor =
+                HashAggExecutor::from((operator, input)).with_spill_threshold(spill_threshold);
+            let tuples = try_collect(&mut executor.execute(&transaction)).await?;
+
+            let mut vec_values = tuples.into_iter().map(|tuple| tuple.values).collect_vec();
+            vec_values.sort_by(|a, b| a[1].partial_cmp(&b[1]).unwrap());
+            assert_eq!(
+                vec_values,
+                vec![
+                    build_integers(vec![Some(2), Some(0)]),
+                    build_integers(vec![Some(1), Some(1)]),
+                    build_integers(vec![Some(2), Some(2)]),
+                ],
+                "{:?}",
+                spill_threshold
+            );
+        }
+
+        Ok(())
+    }
 }
@@ -4,7 +4,6 @@ use crate::expression::value_compute::binary_op;
 use crate::expression::BinaryOperator;
 use crate::types::value::{DataValue, ValueRef};
 use crate::types::LogicalType;
-use std::sync::Arc;
 
 pub struct MinMaxAccumulator {
     inner: Option<ValueRef>,
@@ -50,6 +49,18 @@ impl Accumulator for MinMaxAccumulator {
         Ok(self
             .inner
             .clone()
-            .unwrap_or_else(|| Arc::new(DataValue::none(&self.ty))))
+            .unwrap_or_else(|| ValueRef::new(DataValue::none(&self.ty))))
+    }
+
+    fn state(&self) -> Vec<ValueRef> {
+        self.inner.iter().cloned().collect()
+    }
+
+    fn merge_state(&mut self, state: &[ValueRef]) -> Result<(), ExecutorError> {
+        for value in state {
+            self.update_value(value)?;
+        }
+
+        Ok(())
     }
 }
@@ -1,14 +1,18 @@
+mod approx_count;
 mod avg;
 mod count;
+mod grouping;
 pub mod hash_agg;
 mod min_max;
 pub mod simple_agg;
 mod sum;
 
+use crate::execution::executor::dql::aggregate::approx_count::ApproxCountDistinctAccumulator;
 use crate::execution::executor::dql::aggregate::avg::AvgAccumulator;
 use crate::execution::executor::dql::aggregate::count::{
     CountAccumulator, DistinctCountAccumulator,
 };
+use crate::execution::executor::dql::aggregate::grouping::GroupingAccumulator;
 use crate::execution::executor::dql::aggregate::min_max::MinMaxAccumulator;
 use crate::execution::executor::dql::aggregate::sum::{DistinctSumAccumulator, SumAccumulator};
 use crate::execution::ExecutorError;
@@ -25,11 +29,25 @@ pub trait Accumulator: Send + Sync {
 
     /// returns its value based on its current state.
     fn evaluate(&self) -> Result<ValueRef, ExecutorError>;
+
+    /// returns the partial state, which is spilled and merged into another accumulator
+    /// of the same aggregate.
+    fn state(&self) -> Vec<ValueRef>;
+
+    /// merges the partial state of another accumulator of the same aggregate.
+    fn merge_state(&mut self, state: &[ValueRef]) -> Result<(), ExecutorError>;
 }
 
-fn create_accumulator(expr: &ScalarExpression) -> Box<dyn Accumulator> {
+/// `rolled_up` is the group by exprs that don't belong to the grouping set of the accumulators.
+pub(crate) fn create_accumulator(
+    expr: &ScalarExpression,
+    rolled_up: &[&ScalarExpression],
+) -> Box<dyn Accumulator> {
     if let ScalarExpression::AggCall {
-        kind, ty, distinct, ..
+        kind,
+        ty,
+        distinct,
+        args,
     } = expr
     {
         match (kind, distinct) {
@@ -40,6 +58,12 @@ fn create_accumulator(expr: &ScalarExpression) -> Box<dyn Accumulator> {
             (AggKind::Min, _) => Box::new(MinMaxAccumulator::new(ty, false)),
             (AggKind::Max, _) => Box::new(MinMaxAccumulator::new(ty, true)),
             (AggKind::Avg, _) => Box::new(AvgAccumulator::new(ty)),
+            (AggKind::ApproxCountDistinct, _) => Box::new(ApproxCountDistinctAccumulator::new()),
+            (AggKind::Grouping, _) => {
+                Box::new(GroupingAccumulator::new(rolled_up.iter().any(|expr| {
+                    expr == &&args[0] || expr.unpack_alias() == &args[0]
+                })))
+            }
         }
     } else {
         unreachable!(
@@ -49,6 +73,12 @@ fn create_accumulator(expr: &ScalarExpression) -> Box<dyn Accumulator> {
     }
 }
 
-fn create_accumulators(exprs: &[ScalarExpression]) -> Vec<Box<dyn Accumulator>> {
-    exprs.iter().map(create_accumulator).collect()
+fn create_accumulators(
+    exprs: &[ScalarExpression],
+    rolled_up: &[&ScalarExpression],
+) -> Vec<Box<dyn Accumulator>> {
+    exprs
+        .iter()
+        .map(|expr| create_accumulator(expr, rolled_up))
+        .collect()
 }
@@ -1,10 +1,10 @@
-use crate::execution::executor::dql::aggregate::create_accumulators;
+use crate::execution::executor::dql::aggregate::{create_accumulators, Accumulator};
 use crate::execution::executor::{BoxedExecutor, Executor};
 use crate::execution::ExecutorError;
 use crate::expression::ScalarExpression;
 use crate::planner::operator::aggregate::AggregateOperator;
 use crate::storage::Transaction;
-use crate::types::tuple::Tuple;
+use crate::types::tuple::{SchemaRef, Tuple};
 use crate::types::value::ValueRef;
 use futures_async_stream::try_stream;
 use itertools::Itertools;
@@ -32,42 +32,68 @@ impl<T: Transaction> Executor<T> for SimpleAggExecutor {
 impl SimpleAggExecutor {
     #[try_stream(boxed, ok = Tuple, error = ExecutorError)]
     pub async fn _execute(self) {
-        let mut accs = create_accumulators(&self.agg_calls);
-        let mut columns_option = None;
+        let mut agg = GlobalAggregate::new(self.agg_calls);
 
         #[for_await]
         for tuple in self.input {
-            let tuple = tuple?;
+            agg.update(&tuple?)?;
+        }
+
+        yield agg.finish()?;
+    }
+}
 
-            columns_option.get_or_insert_with(|| {
-                self.agg_calls
-                    .iter()
-                    .map(|expr| expr.output_columns())
-                    .collect_vec()
-            });
+/// The accumulators of an aggregate without group by, shared by `SimpleAggExecutor`
+/// and the scans that the aggregate is pushed into.
+pub(crate) struct GlobalAggregate {
+    agg_calls: Vec<ScalarExpression>,
+    accs: Vec<Box<dyn Accumulator>>,
+    schema_ref: SchemaRef,
+}
 
-            let values: Vec<ValueRef> = self
-                .agg_calls
+impl GlobalAggregate {
+    pub(crate) fn new(agg_calls: Vec<ScalarExpression>) -> Self {
+        GlobalAggregate {
+            accs: create_accumulators(&agg_calls, &[]),
+            schema_ref: agg_calls
                 .iter()
-                .map(|expr| match expr {
-                    ScalarExpression::AggCall { args, .. } => args[0].eval(&tuple),
-                    _ => unreachable!(),
-                })
-                .try_collect()?;
-
-            for (acc, value) in accs.iter_mut().zip_eq(values.iter()) {
-                acc.update_value(value)?;
-            }
+                .map(ScalarExpression::output_columns)
+                .collect_vec()
+                .into(),
+            agg_calls,
         }
+    }
 
-        if let Some(columns) = columns_option {
-            let values: Vec<ValueRef> = accs.into_iter().map(|acc| acc.evaluate()).try_collect()?;
+    pub(crate) fn update(&mut self, tuple: &Tuple) -> Result<(), ExecutorError> {
+        let agg_calls = &self.agg_calls;
+        let values: Vec<ValueRef> = agg_calls
+            .iter()
+            .map(|expr| match expr {
+                ScalarExpression::AggCall { args, .. } => args[0].eval(tuple, &tuple.schema_ref),
+                _ => unreachable!(),
+            })
+            .try_collect()?;
 
-            yield Tuple {
-                id: None,
-                columns,
-                values,
-            };
+        for (acc, value) in self.accs.iter_mut().zip_eq(values.iter()) {
+            acc.update_value(value)?;
         }
+
+        Ok(())
+    }
+
+    /// The tuple of the aggregated values, which there is even if no tuple was aggregated:
+    /// the aggregate of no rows is one row, e.g. `count(*)` is 0 and `sum(c1)` is null.
+    pub(crate) fn finish(self) -> Result<Tuple, ExecutorError> {
+        let values: Vec<ValueRef> = self
+            .accs
+            .into_iter()
+            .map(|acc| acc.evaluate())
+            .try_collect()?;
+
+        Ok(Tuple {
+            id: None,
+            schema_ref: self.schema_ref,
+            values,
+        })
     }
 }
@@ -2,13 +2,14 @@ use crate::execution::executor::dql::aggregate::Accumulator;
 use crate::execution::ExecutorError;
 use crate::expression::value_compute::binary_op;
 use crate::expression::BinaryOperator;
-use crate::types::value::{DataValue, ValueRef};
+use crate::types::value::{DataValue, GroupKey, ValueRef};
 use crate::types::LogicalType;
 use ahash::RandomState;
 use std::collections::HashSet;
-use std::sync::Arc;
 
+/// The sum is null until a value that is not null is summed, like the sum of no rows.
 pub struct SumAccumulator {
+    ty: LogicalType,
     result: DataValue,
 }
 
@@ -17,7 +18,8 @@ impl SumAccumulator {
         assert!(ty.is_numeric());
 
         Self {
-            result: DataValue::init(ty),
+            ty: *ty,
+            result: DataValue::none(ty),
         }
     }
 }
@@ -25,19 +27,32 @@ impl SumAccumulator {
 impl Accumulator for SumAccumulator {
     fn update_value(&mut self, value: &ValueRef) -> Result<(), ExecutorError> {
         if !value.is_null() {
-            self.result = binary_op(&self.result, value, &BinaryOperator::Plus)?;
+            let result = if self.result.is_null() {
+                DataValue::init(&self.ty)
+            } else {
+                self.result.clone()
+            };
+            self.result = binary_op(&result, value, &BinaryOperator::Plus)?;
         }
 
         Ok(())
     }
 
     fn evaluate(&self) -> Result<ValueRef, ExecutorError> {
-        Ok(Arc::new(self.result.clone()))
+        Ok(ValueRef::new(self.result.clone()))
+    }
+
+    fn state(&self) -> Vec<ValueRef> {
+        vec![ValueRef::new(self.result.clone())]
+    }
+
+    fn merge_state(&mut self, state: &[ValueRef]) -> Result<(), ExecutorError> {
+        self.update_value(&state[0])
     }
 }
 
 pub struct DistinctSumAccumulator {
-    distinct_values: HashSet<ValueRef, RandomState>,
+    distinct_values: HashSet<GroupKey, RandomState>,
     inner: SumAccumulator,
 }
 
@@ -52,8 +67,7 @@ impl DistinctSumAccumulator {
 
 impl Accumulator for DistinctSumAccumulator {
     fn update_value(&mut self, value: &ValueRef) -> Result<(), ExecutorError> {
-        if !self.distinct_values.contains(value) {
-            self.distinct_values.insert(value.clone());
+        if self.distinct_values.insert(GroupKey(value.clone())) {
             self.inner.update_value(value)?;
         }
 
@@ -63,4 +77,19 @@ impl Accumulator for DistinctSumAccumulator {
     fn evaluate(&self) -> Result<ValueRef, ExecutorError> {
         self.inner.evaluate()
     }
+
+    fn state(&self) -> Vec<ValueRef> {
+        self.distinct_values
+            .iter()
+            .map(|key| key.0.clone())
+            .collect()
+    }
+
+    fn merge_state(&mut self, state: &[ValueRef]) -> Result<(), ExecutorError> {
+        for value in state {
+            self.update_value(value)?;
+        }
+
+        Ok(())
+    }
 }
@@ -0,0 +1,52 @@
+use crate::execution::executor::{BoxedExecutor, Executor};
+use crate::execution::ExecutorError;
+use crate::expression::ScalarExpression;
+use crate::planner::operator::distinct_on::DistinctOnOperator;
+use crate::storage::Transaction;
+use crate::types::tuple::Tuple;
+use crate::types::value::ValueRef;
+use futures_async_stream::try_stream;
+use itertools::Itertools;
+use std::cell::RefCell;
+
+/// Yields the first tuple of each key, the input is sorted so the tuples of a key are adjacent.
+pub struct DistinctOn {
+    on_exprs: Vec<ScalarExpression>,
+    input: BoxedExecutor,
+}
+
+impl From<(DistinctOnOperator, BoxedExecutor)> for DistinctOn {
+    fn from((DistinctOnOperator { on_exprs }, input): (DistinctOnOperator, BoxedExecutor)) -> Self {
+        DistinctOn { on_exprs, input }
+    }
+}
+
+impl<T: Transaction> Executor<T> for DistinctOn {
+    fn execute(self, _transaction: &RefCell<T>) -> BoxedExecutor {
+        self._execute()
+    }
+}
+
+impl DistinctOn {
+    #[try_stream(boxed, ok = Tuple, error = ExecutorError)]
+    pub async fn _execute(self) {
+        let DistinctOn { on_exprs, input } = self;
+        let mut last_key: Option<Vec<ValueRef>> = None;
+
+        #[for_await]
+        for tuple in input {
+            let tuple = tuple?;
+            // the nulls are one key, as for DISTINCT
+            let key: Vec<ValueRef> = on_exprs
+                .iter()
+                .map(|expr| expr.eval(&tuple, &tuple.schema_ref))
+                .try_collect()?;
+
+            if last_key.as_ref() != Some(&key) {
+                last_key = Some(key);
+
+                yield tuple;
+            }
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use crate::catalog::ColumnCatalog;
+use crate::execution::executor::{BoxedExecutor, Executor};
+use crate::execution::ExecutorError;
+use crate::planner::operator::ExplainFormat;
+use crate::planner::LogicalPlan;
+use crate::storage::Transaction;
+use crate::types::tuple::{SchemaRef, Tuple};
+use crate::types::value::{DataValue, ValueRef};
+use futures_async_stream::try_stream;
+use std::cell::RefCell;
+use std::sync::Arc;
+
+/// Yields the lines of the plan, or a single line of its JSON tree, the plan itself is
+/// never executed.
+pub struct Explain {
+    format: ExplainFormat,
+    plan: LogicalPlan,
+}
+
+impl From<(ExplainFormat, LogicalPlan)> for Explain {
+    fn from((format, plan): (ExplainFormat, LogicalPlan)) -> Self {
+        Explain { format, plan }
+    }
+}
+
+impl<T: Transaction> Executor<T> for Explain {
+    fn execute(self, _transaction: &RefCell<T>) -> BoxedExecutor {
+        self._execute()
+    }
+}
+
+impl Explain {
+    #[try_stream(boxed, ok = Tuple, error = ExecutorError)]
+    pub async fn _execute(self) {
+        let schema_ref: SchemaRef =
+            Arc::new(vec![Arc::new(ColumnCatalog::new_dummy("PLAN".to_string()))]);
+        let lines: Vec<String> = match self.format {
+            ExplainFormat::Text => self.plan.explain(0).lines().map(String::from).collect(),
+            ExplainFormat::Json => vec![self.plan.explain_json().to_string()],
+        };
+
+        for line in lines {
+            yield Tuple {
+                id: None,
+                schema_ref: schema_ref.clone(),
+                values: vec![ValueRef::new(DataValue::Utf8(Some(line)))],
+            };
+        }
+    }
+}
@@ -3,6 +3,7 @@ use crate::execution::ExecutorError;
 use crate::expression::ScalarExpression;
 use crate::planner::operator::filter::FilterOperator;
 use crate::storage::Transaction;
+use crate::types::errors::TypeError;
 use crate::types::tuple::Tuple;
 use crate::types::value::DataValue;
 use futures_async_stream::try_stream;
@@ -33,14 +34,12 @@ impl Filter {
         #[for_await]
         for tuple in input {
             let tuple = tuple?;
-            if let DataValue::Boolean(option) = predicate.eval(&tuple)?.as_ref() {
-                if let Some(true) = option {
-                    yield tuple;
-                } else {
-                    continue;
-                }
-            } else {
-                unreachable!("only bool");
+
+            // only TRUE passes, FALSE and NULL are filtered out
+            match predicate.eval(&tuple, &tuple.schema_ref)?.as_ref() {
+                DataValue::Boolean(Some(true)) => yield tuple,
+                DataValue::Boolean(_) | DataValue::Null => continue,
+                _ => Err(TypeError::InvalidType)?,
             }
         }
     }
@@ -1,3 +1,5 @@
+use crate::execution::executor::dql::aggregate::simple_agg::GlobalAggregate;
+use crate::execution::executor::dql::{lock_tuples, SCAN_BATCH_SIZE};
 use crate::execution::executor::{BoxedExecutor, Executor};
 use crate::execution::ExecutorError;
 use crate::planner::operator::scan::ScanOperator;
@@ -9,21 +11,37 @@ use std::cell::RefCell;
 
 pub(crate) struct IndexScan {
     op: ScanOperator,
+    batch_size: usize,
 }
 
 impl From<ScanOperator> for IndexScan {
     fn from(op: ScanOperator) -> Self {
-        IndexScan { op }
+        IndexScan {
+            op,
+            batch_size: SCAN_BATCH_SIZE,
+        }
     }
 }
 
 impl<T: Transaction> Executor<T> for IndexScan {
     fn execute(self, transaction: &RefCell<T>) -> BoxedExecutor {
-        unsafe { self._execute(transaction.as_ptr().as_ref().unwrap()) }
+        let (table_name, lock) = (self.op.table_name.clone(), self.op.lock);
+        let scan = unsafe { self._execute(transaction.as_ptr().as_ref().unwrap()) };
+
+        if lock {
+            unsafe { lock_tuples(table_name, scan, transaction.as_ptr().as_mut().unwrap()) }
+        } else {
+            scan
+        }
     }
 }
 
 impl IndexScan {
+    pub fn with_batch_size(mut self, batch_size: Option<usize>) -> Self {
+        self.batch_size = batch_size.unwrap_or(SCAN_BATCH_SIZE).max(1);
+        self
+    }
+
     #[try_stream(boxed, ok = Tuple, error = ExecutorError)]
     pub async fn _execute<T: Transaction>(self, transaction: &T) {
         let ScanOperator {
@@ -31,14 +49,46 @@ impl IndexScan {
             columns,
             limit,
             index_by,
+            index_intersection,
+            index_only,
+            filter,
+            agg_calls,
             ..
         } = self.op;
+        let batch_size = self.batch_size;
         let (index_meta, binaries) = index_by.ok_or(TypeError::InvalidType)?;
-        let mut iter =
-            transaction.read_by_index(table_name, limit, columns, index_meta, binaries)?;
+        let mut iter = if index_intersection.is_empty() {
+            transaction
+                .read_by_index(table_name, limit, columns, index_meta, binaries, index_only)?
+        } else {
+            let index_by = [(index_meta, binaries)]
+                .into_iter()
+                .chain(index_intersection)
+                .collect();
 
-        while let Some(tuple) = iter.next_tuple()? {
-            yield tuple;
+            transaction.read_by_index_intersection(table_name, limit, columns, index_by)?
+        };
+        if let Some(predicate) = filter {
+            iter.set_filter(predicate);
+        }
+
+        let mut agg = (!agg_calls.is_empty()).then(|| GlobalAggregate::new(agg_calls));
+
+        loop {
+            let tuples = iter.next_batch(batch_size)?;
+
+            if tuples.is_empty() {
+                break;
+            }
+            for tuple in tuples {
+                match agg.as_mut() {
+                    Some(agg) => agg.update(&tuple)?,
+                    None => yield tuple,
+                }
+            }
+        }
+        if let Some(agg) = agg {
+            yield agg.finish()?;
         }
     }
 }
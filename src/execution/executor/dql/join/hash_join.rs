@@ -6,9 +6,9 @@ use crate::expression::ScalarExpression;
 use crate::planner::operator::join::{JoinCondition, JoinOperator, JoinType};
 use crate::storage::Transaction;
 use crate::types::errors::TypeError;
-use crate::types::tuple::Tuple;
-use crate::types::value::DataValue;
-use ahash::{HashMap, HashMapExt, HashSet, HashSetExt, RandomState};
+use crate::types::tuple::{SchemaRef, Tuple};
+use crate::types::value::{DataValue, ValueRef};
+use ahash::{HashMap, HashSet, HashSetExt, RandomState};
 use futures_async_stream::try_stream;
 use itertools::Itertools;
 use std::cell::RefCell;
@@ -19,11 +19,12 @@ pub struct HashJoin {
     ty: JoinType,
     left_input: BoxedExecutor,
     right_input: BoxedExecutor,
+    random_state: RandomState,
 }
 
 impl From<(JoinOperator, BoxedExecutor, BoxedExecutor)> for HashJoin {
     fn from(
-        (JoinOperator { on, join_type }, left_input, right_input): (
+        (JoinOperator { on, join_type, .. }, left_input, right_input): (
             JoinOperator,
             BoxedExecutor,
             BoxedExecutor,
@@ -34,17 +35,29 @@ impl From<(JoinOperator, BoxedExecutor, BoxedExecutor)> for HashJoin {
             ty: join_type,
             left_input,
             right_input,
+            random_state: RandomState::new(),
         }
     }
 }
 
 impl<T: Transaction> Executor<T> for HashJoin {
     fn execute(self, _transaction: &RefCell<T>) -> BoxedExecutor {
-        self._execute()
+        if matches!(self.ty, JoinType::Semi | JoinType::Anti) {
+            self._semi_execute()
+        } else {
+            self._execute()
+        }
     }
 }
 
 impl HashJoin {
+    /// The hasher of the table of the left tuples, the unmatched ones of an outer join
+    /// are yielded in its order.
+    pub fn with_random_state(mut self, random_state: RandomState) -> Self {
+        self.random_state = random_state;
+        self
+    }
+
     #[try_stream(boxed, ok = Tuple, error = ExecutorError)]
     pub async fn _execute(self) {
         let HashJoin {
@@ -52,22 +65,22 @@ impl HashJoin {
             ty,
             left_input,
             right_input,
+            random_state,
         } = self;
 
-        if ty == JoinType::Cross {
-            unreachable!("Cross join should not be in HashJoinExecutor");
-        }
+        // without keys all the tuples are of the same hash, so each left tuple is joined
+        // with every right tuple as in `CROSS JOIN`
         let ((on_left_keys, on_right_keys), filter): (
             (Vec<ScalarExpression>, Vec<ScalarExpression>),
             _,
         ) = match on {
             JoinCondition::On { on, filter } => (on.into_iter().unzip(), filter),
-            JoinCondition::None => unreachable!("HashJoin must has on condition"),
+            JoinCondition::None => ((vec![], vec![]), None),
         };
 
         let mut join_columns = Vec::new();
         let mut used_set = HashSet::<u64>::new();
-        let mut left_map = HashMap::new();
+        let mut left_map = HashMap::with_hasher(random_state);
 
         let hash_random_state = RandomState::with_seeds(0, 0, 0, 0);
         let (left_force_nullable, right_force_nullable) = joins_nullable(&ty);
@@ -89,20 +102,27 @@ impl HashJoin {
             left_map.entry(hash).or_insert(Vec::new()).push(tuple);
         }
 
+        // the join output shares one schema, which is completed by the first right tuple
+        let mut join_schema: SchemaRef = Arc::new(join_columns.clone());
+
         // probe phase
         let mut right_init_flag = false;
         #[for_await]
         for tuple in right_input {
             let tuple: Tuple = tuple?;
-            let right_cols_len = tuple.columns.len();
+            let right_cols_len = tuple.schema_ref.len();
             let hash = Self::hash_row(&on_right_keys, &hash_random_state, &tuple)?;
 
             if !right_init_flag {
                 Self::columns_filling(&tuple, &mut join_columns, right_force_nullable);
+                join_schema = Arc::new(join_columns.clone());
                 right_init_flag = true;
             }
 
             let mut join_tuples = if let Some(tuples) = left_map.get(&hash) {
+                if ty == JoinType::Single && tuples.len() > 1 {
+                    Err(ExecutorError::SubqueryRows)?
+                }
                 let _ = used_set.insert(hash);
 
                 tuples
@@ -116,22 +136,22 @@ impl HashJoin {
 
                         Tuple {
                             id: None,
-                            columns: join_columns.clone(),
+                            schema_ref: join_schema.clone(),
                             values: full_values,
                         }
                     })
                     .collect_vec()
-            } else if matches!(ty, JoinType::Right | JoinType::Full) {
+            } else if matches!(ty, JoinType::Right | JoinType::Full | JoinType::Single) {
                 let empty_len = join_columns.len() - right_cols_len;
                 let values = join_columns[..empty_len]
                     .iter()
-                    .map(|col| Arc::new(DataValue::none(col.datatype())))
+                    .map(|col| ValueRef::new(DataValue::none(col.datatype())))
                     .chain(tuple.values)
                     .collect_vec();
 
                 vec![Tuple {
                     id: None,
-                    columns: join_columns.clone(),
+                    schema_ref: join_schema.clone(),
                     values,
                 }]
             } else {
@@ -146,25 +166,27 @@ impl HashJoin {
                 let mut filter_tuples = Vec::with_capacity(join_tuples.len());
 
                 for mut tuple in join_tuples {
-                    if let DataValue::Boolean(option) = expr.eval(&tuple)?.as_ref() {
+                    if let DataValue::Boolean(option) =
+                        expr.eval(&tuple, &tuple.schema_ref)?.as_ref()
+                    {
                         if let Some(false) | None = option {
-                            let full_cols_len = tuple.columns.len();
+                            let full_cols_len = tuple.schema_ref.len();
                             let left_cols_len = full_cols_len - right_cols_len;
 
                             match ty {
                                 JoinType::Left => {
                                     for i in left_cols_len..full_cols_len {
-                                        let value_type = tuple.columns[i].datatype();
+                                        let value_type = tuple.schema_ref[i].datatype();
 
-                                        tuple.values[i] = Arc::new(DataValue::none(value_type))
+                                        tuple.values[i] = ValueRef::new(DataValue::none(value_type))
                                     }
                                     filter_tuples.push(tuple)
                                 }
                                 JoinType::Right => {
                                     for i in 0..left_cols_len {
-                                        let value_type = tuple.columns[i].datatype();
+                                        let value_type = tuple.schema_ref[i].datatype();
 
-                                        tuple.values[i] = Arc::new(DataValue::none(value_type))
+                                        tuple.values[i] = ValueRef::new(DataValue::none(value_type))
                                     }
                                     filter_tuples.push(tuple)
                                 }
@@ -194,20 +216,20 @@ impl HashJoin {
 
                 for Tuple {
                     mut values,
-                    columns,
+                    schema_ref,
                     ..
                 } in tuples
                 {
-                    let mut right_empties = join_columns[columns.len()..]
+                    let mut right_empties = join_schema[schema_ref.len()..]
                         .iter()
-                        .map(|col| Arc::new(DataValue::none(col.datatype())))
+                        .map(|col| ValueRef::new(DataValue::none(col.datatype())))
                         .collect_vec();
 
                     values.append(&mut right_empties);
 
                     yield Tuple {
                         id: None,
-                        columns: join_columns.clone(),
+                        schema_ref: join_schema.clone(),
                         values,
                     }
                 }
@@ -215,9 +237,116 @@ impl HashJoin {
         }
     }
 
+    /// The semi join hashes the right tuples, then yields each left tuple at the first
+    /// matching one, or at none of them for an anti join. Null keys never match.
+    #[try_stream(boxed, ok = Tuple, error = ExecutorError)]
+    pub async fn _semi_execute(self) {
+        let HashJoin {
+            on,
+            ty,
+            left_input,
+            right_input,
+            random_state,
+        } = self;
+
+        let ((on_left_keys, on_right_keys), filter): (
+            (Vec<ScalarExpression>, Vec<ScalarExpression>),
+            _,
+        ) = match on {
+            JoinCondition::On { on, filter } => (on.into_iter().unzip(), filter),
+            JoinCondition::None => unreachable!("HashJoin must has on condition"),
+        };
+        let hash_random_state = RandomState::with_seeds(0, 0, 0, 0);
+        let mut right_map = HashMap::with_hasher(random_state);
+        let mut right_columns = Vec::new();
+
+        #[for_await]
+        for tuple in right_input {
+            let tuple: Tuple = tuple?;
+
+            if right_columns.is_empty() {
+                right_columns = tuple.schema_ref.to_vec();
+            }
+            if let Some(hash) = Self::hash_keys(&on_right_keys, &hash_random_state, &tuple)? {
+                right_map.entry(hash).or_insert(Vec::new()).push(tuple);
+            }
+        }
+        let mut join_schema: Option<SchemaRef> = None;
+
+        #[for_await]
+        for tuple in left_input {
+            let tuple: Tuple = tuple?;
+            let mut is_matched = false;
+
+            if let Some(right_tuples) = Self::hash_keys(&on_left_keys, &hash_random_state, &tuple)?
+                .and_then(|hash| right_map.get(&hash))
+            {
+                match &filter {
+                    Some(expr) => {
+                        let join_schema = join_schema.get_or_insert_with(|| {
+                            Arc::new(
+                                tuple
+                                    .schema_ref
+                                    .iter()
+                                    .chain(right_columns.iter())
+                                    .cloned()
+                                    .collect_vec(),
+                            )
+                        });
+
+                        for right_tuple in right_tuples {
+                            let values = tuple
+                                .values
+                                .iter()
+                                .chain(right_tuple.values.iter())
+                                .cloned()
+                                .collect_vec();
+                            let join_tuple = Tuple {
+                                id: None,
+                                schema_ref: join_schema.clone(),
+                                values,
+                            };
+
+                            if let DataValue::Boolean(Some(true)) =
+                                expr.eval(&join_tuple, join_schema)?.as_ref()
+                            {
+                                is_matched = true;
+                                break;
+                            }
+                        }
+                    }
+                    None => is_matched = true,
+                }
+            }
+            if is_matched == (ty == JoinType::Semi) {
+                yield tuple;
+            }
+        }
+    }
+
+    /// The hash of the keys of the tuple, `None` if one of them is null.
+    fn hash_keys(
+        on_keys: &[ScalarExpression],
+        hash_random_state: &RandomState,
+        tuple: &Tuple,
+    ) -> Result<Option<u64>, TypeError> {
+        let mut values = Vec::with_capacity(on_keys.len());
+
+        for expr in on_keys {
+            let value = expr.eval(tuple, &tuple.schema_ref)?;
+
+            if value.is_null() {
+                return Ok(None);
+            }
+            values.push(value);
+        }
+
+        Ok(Some(hash_random_state.hash_one(values)))
+    }
+
     fn columns_filling(tuple: &Tuple, join_columns: &mut Vec<ColumnRef>, force_nullable: bool) {
         let mut new_columns = tuple
-            .columns
+            .schema_ref
             .iter()
             .cloned()
             .map(|col| {
@@ -239,7 +368,7 @@ impl HashJoin {
         let mut values = Vec::with_capacity(on_keys.len());
 
         for expr in on_keys {
-            values.push(expr.eval(tuple)?);
+            values.push(expr.eval(tuple, &tuple.schema_ref)?);
         }
 
         Ok(hash_random_state.hash_one(values))
@@ -260,7 +389,7 @@ mod test {
     use crate::storage::kip::KipStorage;
     use crate::storage::{Storage, Transaction};
     use crate::types::tuple::create_table;
-    use crate::types::value::DataValue;
+    use crate::types::value::{DataValue, ValueRef};
     use crate::types::LogicalType;
     use std::cell::RefCell;
     use std::sync::Arc;
@@ -325,19 +454,19 @@ mod test {
         let values_t1 = Values::from(ValuesOperator {
             rows: vec![
                 vec![
-                    Arc::new(DataValue::Int32(Some(0))),
-                    Arc::new(DataValue::Int32(Some(2))),
-                    Arc::new(DataValue::Int32(Some(4))),
+                    ValueRef::new(DataValue::Int32(Some(0))),
+                    ValueRef::new(DataValue::Int32(Some(2))),
+                    ValueRef::new(DataValue::Int32(Some(4))),
                 ],
                 vec![
-                    Arc::new(DataValue::Int32(Some(1))),
-                    Arc::new(DataValue::Int32(Some(3))),
-                    Arc::new(DataValue::Int32(Some(5))),
+                    ValueRef::new(DataValue::Int32(Some(1))),
+                    ValueRef::new(DataValue::Int32(Some(3))),
+                    ValueRef::new(DataValue::Int32(Some(5))),
                 ],
                 vec![
-                    Arc::new(DataValue::Int32(Some(3))),
-                    Arc::new(DataValue::Int32(Some(5))),
-                    Arc::new(DataValue::Int32(Some(7))),
+                    ValueRef::new(DataValue::Int32(Some(3))),
+                    ValueRef::new(DataValue::Int32(Some(5))),
+                    ValueRef::new(DataValue::Int32(Some(7))),
                 ],
             ],
             columns: t1_columns,
@@ -346,24 +475,24 @@ mod test {
         let values_t2 = Values::from(ValuesOperator {
             rows: vec![
                 vec![
-                    Arc::new(DataValue::Int32(Some(0))),
-                    Arc::new(DataValue::Int32(Some(2))),
-                    Arc::new(DataValue::Int32(Some(4))),
+                    ValueRef::new(DataValue::Int32(Some(0))),
+                    ValueRef::new(DataValue::Int32(Some(2))),
+                    ValueRef::new(DataValue::Int32(Some(4))),
                 ],
                 vec![
-                    Arc::new(DataValue::Int32(Some(1))),
-                    Arc::new(DataValue::Int32(Some(3))),
-                    Arc::new(DataValue::Int32(Some(5))),
+                    ValueRef::new(DataValue::Int32(Some(1))),
+                    ValueRef::new(DataValue::Int32(Some(3))),
+                    ValueRef::new(DataValue::Int32(Some(5))),
                 ],
                 vec![
-                    Arc::new(DataValue::Int32(Some(4))),
-                    Arc::new(DataValue::Int32(Some(6))),
-                    Arc::new(DataValue::Int32(Some(8))),
+                    ValueRef::new(DataValue::Int32(Some(4))),
+                    ValueRef::new(DataValue::Int32(Some(6))),
+                    ValueRef::new(DataValue::Int32(Some(8))),
                 ],
                 vec![
-                    Arc::new(DataValue::Int32(Some(1))),
-                    Arc::new(DataValue::Int32(Some(1))),
-                    Arc::new(DataValue::Int32(Some(1))),
+                    ValueRef::new(DataValue::Int32(Some(1))),
+                    ValueRef::new(DataValue::Int32(Some(1))),
+                    ValueRef::new(DataValue::Int32(Some(1))),
                 ],
             ],
             columns: t2_columns,
@@ -385,6 +514,7 @@ mod test {
                 filter: None,
             },
             join_type: JoinType::Inner,
+            index_probe: None,
         };
         let mut executor = HashJoin::from((op, left, right)).execute(&transaction);
         let tuples = try_collect(&mut executor).await?;
@@ -422,6 +552,7 @@ mod test {
                 filter: None,
             },
             join_type: JoinType::Left,
+            index_probe: None,
         };
         let mut executor = HashJoin::from((op, left, right)).execute(&transaction);
         let tuples = try_collect(&mut executor).await?;
@@ -463,6 +594,7 @@ mod test {
                 filter: None,
             },
             join_type: JoinType::Right,
+            index_probe: None,
         };
         let mut executor = HashJoin::from((op, left, right)).execute(&transaction);
         let tuples = try_collect(&mut executor).await?;
@@ -504,6 +636,7 @@ mod test {
                 filter: None,
             },
             join_type: JoinType::Full,
+            index_probe: None,
         };
         let mut executor = HashJoin::from((op, left, right)).execute(&transaction);
         let tuples = try_collect(&mut executor).await?;
@@ -0,0 +1,157 @@
+use crate::execution::executor::{BoxedExecutor, Executor};
+use crate::execution::ExecutorError;
+use crate::expression::simplify::ConstantBinary;
+use crate::expression::ScalarExpression;
+use crate::planner::operator::join::{JoinCondition, JoinOperator, JoinType};
+use crate::planner::operator::scan::ScanOperator;
+use crate::planner::operator::Operator;
+use crate::planner::LogicalPlan;
+use crate::storage::{Iter, Transaction};
+use crate::types::index::IndexMetaRef;
+use crate::types::tuple::{SchemaRef, Tuple};
+use crate::types::value::DataValue;
+use futures_async_stream::try_stream;
+use itertools::Itertools;
+use std::cell::RefCell;
+use std::sync::Arc;
+
+/// The semi join of `JoinOperator::index_probe`, the index of the right key is probed with
+/// the left key of each left tuple, which is yielded at the first right tuple found passing
+/// the predicates, or at none of them for an anti join.
+pub struct IndexSemiJoin {
+    on: JoinCondition,
+    ty: JoinType,
+    index_meta: IndexMetaRef,
+    scan: ScanOperator,
+    // the predicate of the right tuples, of the `Filter` above the scan
+    predicate: Option<ScalarExpression>,
+    left_input: BoxedExecutor,
+}
+
+impl From<(JoinOperator, BoxedExecutor, LogicalPlan)> for IndexSemiJoin {
+    fn from(
+        (
+            JoinOperator {
+                on,
+                join_type,
+                index_probe,
+            },
+            left_input,
+            mut right_plan,
+        ): (JoinOperator, BoxedExecutor, LogicalPlan),
+    ) -> Self {
+        let predicate = match right_plan.operator {
+            Operator::Filter(op) => {
+                right_plan = right_plan.childrens.remove(0);
+                Some(op.predicate)
+            }
+            _ => None,
+        };
+        let Operator::Scan(scan) = right_plan.operator else {
+            unreachable!("the index probe of a semi join must be over a scan")
+        };
+
+        IndexSemiJoin {
+            on,
+            ty: join_type,
+            index_meta: index_probe.expect("the semi join has no index probe"),
+            scan,
+            predicate,
+            left_input,
+        }
+    }
+}
+
+impl<T: Transaction> Executor<T> for IndexSemiJoin {
+    fn execute(self, transaction: &RefCell<T>) -> BoxedExecutor {
+        unsafe { self._execute(transaction.as_ptr().as_ref().unwrap()) }
+    }
+}
+
+impl IndexSemiJoin {
+    #[try_stream(boxed, ok = Tuple, error = ExecutorError)]
+    pub async fn _execute<T: Transaction>(self, transaction: &T) {
+        let IndexSemiJoin {
+            on,
+            ty,
+            index_meta,
+            scan,
+            predicate,
+            left_input,
+        } = self;
+
+        let (left_key, filter) = match on {
+            JoinCondition::On { mut on, filter } if on.len() == 1 => (on.remove(0).0, filter),
+            _ => unreachable!("the index probe of a semi join must have a single key"),
+        };
+        let mut join_schema: Option<SchemaRef> = None;
+
+        #[for_await]
+        for tuple in left_input {
+            let tuple: Tuple = tuple?;
+            let key = left_key.eval(&tuple, &tuple.schema_ref)?;
+            let mut is_matched = false;
+
+            if !key.is_null() {
+                let mut iter = transaction.read_by_index(
+                    scan.table_name.clone(),
+                    (None, None),
+                    scan.columns.clone(),
+                    index_meta.clone(),
+                    vec![ConstantBinary::Eq(key)],
+                    false,
+                )?;
+                if let Some(filter) = &scan.filter {
+                    iter.set_filter(filter.clone());
+                }
+
+                while let Some(right_tuple) = iter.next_tuple()? {
+                    if let Some(predicate) = &predicate {
+                        if !is_true(
+                            predicate
+                                .eval(&right_tuple, &right_tuple.schema_ref)?
+                                .as_ref(),
+                        ) {
+                            continue;
+                        }
+                    }
+                    if let Some(filter) = &filter {
+                        let join_schema = join_schema.get_or_insert_with(|| {
+                            Arc::new(
+                                tuple
+                                    .schema_ref
+                                    .iter()
+                                    .chain(right_tuple.schema_ref.iter())
+                                    .cloned()
+                                    .collect_vec(),
+                            )
+                        });
+                        let join_tuple = Tuple {
+                            id: None,
+                            schema_ref: join_schema.clone(),
+                            values: tuple
+                                .values
+                                .iter()
+                                .chain(right_tuple.values.iter())
+                                .cloned()
+                                .collect_vec(),
+                        };
+
+                        if !is_true(filter.eval(&join_tuple, join_schema)?.as_ref()) {
+                            continue;
+                        }
+                    }
+                    is_matched = true;
+                    break;
+                }
+            }
+            if is_matched == (ty == JoinType::Semi) {
+                yield tuple;
+            }
+        }
+    }
+}
+
+fn is_true(value: &DataValue) -> bool {
+    matches!(value, DataValue::Boolean(Some(true)))
+}
@@ -1,6 +1,7 @@
 use crate::planner::operator::join::JoinType;
 
 pub(crate) mod hash_join;
+pub(crate) mod index_semi_join;
 
 pub fn joins_nullable(join_type: &JoinType) -> (bool, bool) {
     match join_type {
@@ -8,6 +9,8 @@ pub fn joins_nullable(join_type: &JoinType) -> (bool, bool) {
         JoinType::Left => (false, true),
         JoinType::Right => (true, false),
         JoinType::Full => (true, true),
-        JoinType::Cross => (true, true),
+        JoinType::Cross => (false, false),
+        JoinType::Single => (true, false),
+        JoinType::Semi | JoinType::Anti => (false, false),
     }
 }
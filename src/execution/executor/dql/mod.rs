@@ -1,23 +1,59 @@
 pub(crate) mod aggregate;
+pub(crate) mod distinct_on;
 pub(crate) mod dummy;
+pub(crate) mod explain;
 pub(crate) mod filter;
 pub(crate) mod index_scan;
 pub(crate) mod join;
 pub(crate) mod limit;
 pub(crate) mod projection;
 pub(crate) mod seq_scan;
+pub(crate) mod set_operation;
 pub(crate) mod sort;
+pub(crate) mod spill;
 pub(crate) mod values;
+pub(crate) mod window;
+
+use crate::catalog::TableName;
+use crate::execution::executor::BoxedExecutor;
+use crate::execution::ExecutorError;
+use crate::storage::Transaction;
+use crate::types::tuple::Tuple;
+use futures_async_stream::try_stream;
+
+/// Number of tuples the scans fetch from the storage per call by default.
+pub(crate) const SCAN_BATCH_SIZE: usize = 1024;
+
+/// Locks the tuples of the scan `input` for `SELECT ... FOR UPDATE`. They are all read
+/// before the first lock is taken, the transaction is not written while the scan reads it.
+#[try_stream(boxed, ok = Tuple, error = ExecutorError)]
+pub(crate) async fn lock_tuples<T: Transaction>(
+    table_name: TableName,
+    input: BoxedExecutor,
+    transaction: &mut T,
+) {
+    let mut tuples = Vec::new();
+
+    #[for_await]
+    for tuple in input {
+        tuples.push(tuple?);
+    }
+    for tuple in tuples {
+        if let Some(tuple_id) = &tuple.id {
+            transaction.lock(&table_name, tuple_id.clone())?;
+        }
+        yield tuple;
+    }
+}
 
 #[cfg(test)]
 pub(crate) mod test {
     use crate::types::value::{DataValue, ValueRef};
     use itertools::Itertools;
-    use std::sync::Arc;
 
     pub(crate) fn build_integers(ints: Vec<Option<i32>>) -> Vec<ValueRef> {
         ints.into_iter()
-            .map(|i| Arc::new(DataValue::Int32(i)))
+            .map(|i| ValueRef::new(DataValue::Int32(i)))
             .collect_vec()
     }
 }
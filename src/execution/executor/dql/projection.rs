@@ -3,9 +3,10 @@ use crate::execution::ExecutorError;
 use crate::expression::ScalarExpression;
 use crate::planner::operator::project::ProjectOperator;
 use crate::storage::Transaction;
-use crate::types::tuple::Tuple;
+use crate::types::tuple::{SchemaRef, Tuple};
 use futures_async_stream::try_stream;
 use std::cell::RefCell;
+use std::sync::Arc;
 
 pub struct Projection {
     exprs: Vec<ScalarExpression>,
@@ -28,24 +29,12 @@ impl Projection {
     #[try_stream(boxed, ok = Tuple, error = ExecutorError)]
     pub async fn _execute(self) {
         let Projection { exprs, input } = self;
+        let schema_ref: SchemaRef =
+            Arc::new(exprs.iter().map(|expr| expr.output_columns()).collect());
 
         #[for_await]
         for tuple in input {
-            let tuple = tuple?;
-
-            let mut columns = Vec::with_capacity(exprs.len());
-            let mut values = Vec::with_capacity(exprs.len());
-
-            for expr in exprs.iter() {
-                values.push(expr.eval(&tuple)?);
-                columns.push(expr.output_columns());
-            }
-
-            yield Tuple {
-                id: None,
-                columns,
-                values,
-            };
+            yield tuple?.project(&exprs, &schema_ref)?;
         }
     }
 }
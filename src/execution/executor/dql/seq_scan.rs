@@ -1,3 +1,5 @@
+use crate::execution::executor::dql::aggregate::simple_agg::GlobalAggregate;
+use crate::execution::executor::dql::{lock_tuples, SCAN_BATCH_SIZE};
 use crate::execution::executor::{BoxedExecutor, Executor};
 use crate::execution::ExecutorError;
 use crate::planner::operator::scan::ScanOperator;
@@ -8,33 +10,70 @@ use std::cell::RefCell;
 
 pub(crate) struct SeqScan {
     op: ScanOperator,
+    batch_size: usize,
 }
 
 impl From<ScanOperator> for SeqScan {
     fn from(op: ScanOperator) -> Self {
-        SeqScan { op }
+        SeqScan {
+            op,
+            batch_size: SCAN_BATCH_SIZE,
+        }
     }
 }
 
 impl<T: Transaction> Executor<T> for SeqScan {
     fn execute(self, transaction: &RefCell<T>) -> BoxedExecutor {
-        unsafe { self._execute(transaction.as_ptr().as_ref().unwrap()) }
+        let (table_name, lock) = (self.op.table_name.clone(), self.op.lock);
+        let scan = unsafe { self._execute(transaction.as_ptr().as_ref().unwrap()) };
+
+        if lock {
+            unsafe { lock_tuples(table_name, scan, transaction.as_ptr().as_mut().unwrap()) }
+        } else {
+            scan
+        }
     }
 }
 
 impl SeqScan {
+    pub fn with_batch_size(mut self, batch_size: Option<usize>) -> Self {
+        self.batch_size = batch_size.unwrap_or(SCAN_BATCH_SIZE).max(1);
+        self
+    }
+
     #[try_stream(boxed, ok = Tuple, error = ExecutorError)]
     pub async fn _execute<T: Transaction>(self, transaction: &T) {
         let ScanOperator {
             table_name,
             columns,
             limit,
+            filter,
+            agg_calls,
             ..
         } = self.op;
+        let batch_size = self.batch_size;
         let mut iter = transaction.read(table_name, limit, columns)?;
+        if let Some(predicate) = filter {
+            iter.set_filter(predicate);
+        }
+
+        let mut agg = (!agg_calls.is_empty()).then(|| GlobalAggregate::new(agg_calls));
 
-        while let Some(tuple) = iter.next_tuple()? {
-            yield tuple;
+        loop {
+            let tuples = iter.next_batch(batch_size)?;
+
+            if tuples.is_empty() {
+                break;
+            }
+            for tuple in tuples {
+                match agg.as_mut() {
+                    Some(agg) => agg.update(&tuple)?,
+                    None => yield tuple,
+                }
+            }
+        }
+        if let Some(agg) = agg {
+            yield agg.finish()?;
         }
     }
 }
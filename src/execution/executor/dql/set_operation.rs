@@ -0,0 +1,105 @@
+use crate::execution::executor::{BoxedExecutor, Executor};
+use crate::execution::ExecutorError;
+use crate::planner::operator::set_operation::{SetOperationKind, SetOperationOperator};
+use crate::storage::Transaction;
+use crate::types::tuple::Tuple;
+use crate::types::value::GroupKey;
+use ahash::{HashMap, HashMapExt, HashSet, HashSetExt};
+use futures_async_stream::try_stream;
+use std::cell::RefCell;
+
+pub struct SetOperation {
+    op: SetOperationOperator,
+    left_input: BoxedExecutor,
+    right_input: BoxedExecutor,
+}
+
+impl From<(SetOperationOperator, BoxedExecutor, BoxedExecutor)> for SetOperation {
+    fn from(
+        (op, left_input, right_input): (SetOperationOperator, BoxedExecutor, BoxedExecutor),
+    ) -> Self {
+        SetOperation {
+            op,
+            left_input,
+            right_input,
+        }
+    }
+}
+
+impl<T: Transaction> Executor<T> for SetOperation {
+    fn execute(self, _transaction: &RefCell<T>) -> BoxedExecutor {
+        self._execute()
+    }
+}
+
+fn row_key(tuple: &Tuple) -> Vec<GroupKey> {
+    tuple.values.iter().cloned().map(GroupKey).collect()
+}
+
+impl SetOperation {
+    /// The tuples of the right side are counted by their values, e.g. with `INTERSECT ALL`
+    /// a tuple of the left side is kept as long as the count of its values isn't used up,
+    /// so it is kept `min(count_left, count_right)` times, and with `EXCEPT ALL`
+    /// `max(count_left - count_right, 0)` times. The tuples take the schema of the left side.
+    #[try_stream(boxed, ok = Tuple, error = ExecutorError)]
+    pub async fn _execute(self) {
+        let SetOperation {
+            op: SetOperationOperator { kind, all },
+            left_input,
+            right_input,
+        } = self;
+        // the rows already given out, without `ALL` each of them is given out once
+        let mut seen = HashSet::new();
+
+        if kind == SetOperationKind::Union {
+            let mut schema_ref = None;
+
+            #[for_await]
+            for tuple in left_input {
+                let tuple = tuple?;
+                schema_ref.get_or_insert_with(|| tuple.schema_ref.clone());
+
+                if all || seen.insert(row_key(&tuple)) {
+                    yield tuple;
+                }
+            }
+            #[for_await]
+            for tuple in right_input {
+                let mut tuple = tuple?;
+                if let Some(schema_ref) = &schema_ref {
+                    tuple.schema_ref = schema_ref.clone();
+                }
+
+                if all || seen.insert(row_key(&tuple)) {
+                    yield tuple;
+                }
+            }
+            return Ok(());
+        }
+
+        let mut right_counts: HashMap<Vec<GroupKey>, usize> = HashMap::new();
+        #[for_await]
+        for tuple in right_input {
+            *right_counts.entry(row_key(&tuple?)).or_default() += 1;
+        }
+
+        #[for_await]
+        for tuple in left_input {
+            let tuple = tuple?;
+            let key = row_key(&tuple);
+            let is_kept = match right_counts.get_mut(&key) {
+                Some(count) if *count > 0 => {
+                    if all {
+                        *count -= 1;
+                    }
+                    kind == SetOperationKind::Intersect
+                }
+                _ => kind == SetOperationKind::Except,
+            };
+
+            if is_kept && (all || seen.insert(key)) {
+                yield tuple;
+            }
+        }
+    }
+}
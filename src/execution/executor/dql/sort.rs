@@ -1,8 +1,12 @@
+use crate::execution::executor::dql::spill::{SpillFile, SpillReader};
 use crate::execution::executor::{BoxedExecutor, Executor};
 use crate::execution::ExecutorError;
 use crate::planner::operator::sort::{SortField, SortOperator};
+use crate::storage::table_codec::TableCodec;
 use crate::storage::Transaction;
-use crate::types::tuple::Tuple;
+use crate::types::errors::TypeError;
+use crate::types::tuple::{SchemaRef, Tuple};
+use crate::types::value::{DataValue, ValueRef};
 use futures_async_stream::try_stream;
 use std::cell::RefCell;
 use std::cmp::Ordering;
@@ -10,6 +14,8 @@ use std::cmp::Ordering;
 pub struct Sort {
     sort_fields: Vec<SortField>,
     limit: Option<usize>,
+    /// The bytes of buffered tuples above which a sorted run is spilled to a temporary file.
+    spill_threshold: Option<usize>,
     input: BoxedExecutor,
 }
 
@@ -18,6 +24,7 @@ impl From<(SortOperator, BoxedExecutor)> for Sort {
         Sort {
             sort_fields,
             limit,
+            spill_threshold: None,
             input,
         }
     }
@@ -30,68 +37,207 @@ impl<T: Transaction> Executor<T> for Sort {
 }
 
 impl Sort {
+    pub fn with_spill_threshold(mut self, spill_threshold: Option<usize>) -> Self {
+        self.spill_threshold = spill_threshold;
+        self
+    }
+
     #[try_stream(boxed, ok = Tuple, error = ExecutorError)]
     pub async fn _execute(self) {
         let Sort {
             sort_fields,
             limit,
+            spill_threshold,
             input,
         } = self;
-        let mut tuples: Vec<Tuple> = vec![];
+        // the tuples along with the values of their sort fields
+        let mut tuples: Vec<(Vec<ValueRef>, Tuple)> = vec![];
+        let mut buffered_bytes = 0;
+        let mut runs: Vec<SpillRun> = vec![];
 
         #[for_await]
         for tuple in input {
-            tuples.push(tuple?);
-        }
+            let tuple = tuple?;
+            let keys = sort_keys(&sort_fields, &tuple)?;
 
-        tuples.sort_by(|tuple_1, tuple_2| {
-            let mut ordering = Ordering::Equal;
-
-            for SortField {
-                expr,
-                asc,
-                nulls_first,
-            } in &sort_fields
-            {
-                let value_1 = expr.eval(tuple_1).unwrap();
-                let value_2 = expr.eval(tuple_2).unwrap();
-
-                ordering = value_1.partial_cmp(&value_2).unwrap_or_else(|| {
-                    match (value_1.is_null(), value_2.is_null()) {
-                        (false, true) => {
-                            if *nulls_first {
-                                Ordering::Less
-                            } else {
-                                Ordering::Greater
-                            }
-                        }
-                        (true, false) => {
-                            if *nulls_first {
-                                Ordering::Greater
-                            } else {
-                                Ordering::Less
-                            }
-                        }
-                        _ => Ordering::Equal,
-                    }
-                });
+            if let Some(threshold) = spill_threshold {
+                buffered_bytes += estimated_size(&tuple);
 
-                if !*asc {
-                    ordering = ordering.reverse();
+                if buffered_bytes > threshold {
+                    tuples.push((keys, tuple));
+                    sort_tuples(&sort_fields, &mut tuples);
+                    runs.push(SpillRun::write(&tuples)?);
+                    tuples.clear();
+                    buffered_bytes = 0;
+                    continue;
                 }
+            }
+            tuples.push((keys, tuple));
+        }
+        sort_tuples(&sort_fields, &mut tuples);
 
-                if ordering != Ordering::Equal {
-                    break;
+        if runs.is_empty() {
+            let len = limit.unwrap_or(tuples.len()).min(tuples.len());
+
+            for (_, tuple) in tuples.drain(..len) {
+                yield tuple;
+            }
+            return Ok(());
+        }
+
+        // k-way merge of the spilled runs and the tuples left in memory, which come last
+        // because ties are taken from the earliest run to keep the sort stable
+        let mut readers = runs
+            .iter_mut()
+            .map(SpillRun::reader)
+            .try_collect::<Vec<_>>()?;
+        let mut in_memory = tuples.into_iter();
+        let mut heads = Vec::with_capacity(readers.len() + 1);
+
+        for reader in readers.iter_mut() {
+            heads.push(reader.next(&sort_fields)?);
+        }
+        heads.push(in_memory.next());
+
+        let mut remaining = limit.unwrap_or(usize::MAX);
+
+        while remaining > 0 {
+            let mut min_pos: Option<usize> = None;
+
+            for (pos, head) in heads.iter().enumerate() {
+                if let Some((keys, _)) = head {
+                    let is_less = min_pos.map_or(true, |min_pos| {
+                        let (min_keys, _) = heads[min_pos].as_ref().unwrap();
+
+                        compare_keys(&sort_fields, keys, min_keys) == Ordering::Less
+                    });
+                    if is_less {
+                        min_pos = Some(pos);
+                    }
                 }
             }
+            let Some(min_pos) = min_pos else {
+                break;
+            };
+            let next = match readers.get_mut(min_pos) {
+                Some(reader) => reader.next(&sort_fields)?,
+                None => in_memory.next(),
+            };
 
-            ordering
-        });
+            yield std::mem::replace(&mut heads[min_pos], next).unwrap().1;
+            remaining -= 1;
+        }
+    }
+}
 
-        let len = limit.unwrap_or(tuples.len());
+fn sort_tuples(sort_fields: &[SortField], tuples: &mut [(Vec<ValueRef>, Tuple)]) {
+    tuples.sort_by(|(keys_1, _), (keys_2, _)| compare_keys(sort_fields, keys_1, keys_2));
+}
+
+/// The values of the sort fields of the tuple, they are evaluated once before sorting so that
+/// an expression failing on a row, e.g. a `CAST`, fails the query instead of a comparison.
+fn sort_keys(sort_fields: &[SortField], tuple: &Tuple) -> Result<Vec<ValueRef>, TypeError> {
+    sort_fields
+        .iter()
+        .map(|field| field.expr.eval(tuple, &tuple.schema_ref))
+        .collect()
+}
 
-        for tuple in tuples.drain(..len) {
-            yield tuple;
+fn compare_keys(sort_fields: &[SortField], keys_1: &[ValueRef], keys_2: &[ValueRef]) -> Ordering {
+    sort_fields
+        .iter()
+        .zip(keys_1.iter().zip(keys_2.iter()))
+        .map(|(field, (value_1, value_2))| {
+            compare_values(value_1, value_2, field.asc, field.nulls_first)
+        })
+        .find(|ordering| ordering != &Ordering::Equal)
+        .unwrap_or(Ordering::Equal)
+}
+
+/// The order of two values of a sort field, the same for all the operators sorting tuples.
+/// The nulls are placed by `nulls_first` whether the order is ascending or descending.
+pub(crate) fn compare_values(
+    value_1: &DataValue,
+    value_2: &DataValue,
+    asc: bool,
+    nulls_first: bool,
+) -> Ordering {
+    match (value_1.is_null(), value_2.is_null()) {
+        (true, true) => Ordering::Equal,
+        (true, false) if nulls_first => Ordering::Less,
+        (true, false) => Ordering::Greater,
+        (false, true) if nulls_first => Ordering::Greater,
+        (false, true) => Ordering::Less,
+        (false, false) => {
+            let ordering = value_1.partial_cmp(value_2).unwrap_or(Ordering::Equal);
+
+            if asc {
+                ordering
+            } else {
+                ordering.reverse()
+            }
+        }
+    }
+}
+
+/// The bytes a tuple takes once encoded, a cheap stand-in for its size in memory.
+fn estimated_size(tuple: &Tuple) -> usize {
+    tuple
+        .values
+        .iter()
+        .map(|value| {
+            value
+                .logical_type()
+                .raw_len()
+                .unwrap_or_else(|| value.to_raw().len())
+        })
+        .sum()
+}
+
+/// A sorted run spilled to a temporary file, the tuples are encoded by `TableCodec`.
+struct SpillRun {
+    file: SpillFile,
+    schema_ref: SchemaRef,
+}
+
+impl SpillRun {
+    fn write(tuples: &[(Vec<ValueRef>, Tuple)]) -> Result<Self, ExecutorError> {
+        let mut file = SpillFile::new()?;
+
+        for (_, tuple) in tuples {
+            file.write(&tuple.serialize_to())?;
         }
+
+        Ok(SpillRun {
+            file,
+            schema_ref: tuples[0].1.schema_ref.clone(),
+        })
+    }
+
+    fn reader(&mut self) -> Result<SpillRunReader, ExecutorError> {
+        Ok(SpillRunReader {
+            inner: self.file.reader()?,
+            schema_ref: self.schema_ref.clone(),
+        })
+    }
+}
+
+struct SpillRunReader {
+    inner: SpillReader,
+    schema_ref: SchemaRef,
+}
+
+impl SpillRunReader {
+    /// The next tuple of the run along with the values of its sort fields.
+    fn next(
+        &mut self,
+        sort_fields: &[SortField],
+    ) -> Result<Option<(Vec<ValueRef>, Tuple)>, ExecutorError> {
+        let Some(bytes) = self.inner.next()? else {
+            return Ok(None);
+        };
+        let tuple = TableCodec::decode_tuple(self.schema_ref.clone(), &bytes);
+
+        Ok(Some((sort_keys(sort_fields, &tuple)?, tuple)))
     }
 }
@@ -0,0 +1,81 @@
+use crate::execution::ExecutorError;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, ErrorKind, Read, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::{env, fs, process};
+
+static SPILL_FILE_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// A temporary file of length-prefixed records that the executors spill to when their
+/// buffers get too big, it is removed once dropped.
+pub(crate) struct SpillFile {
+    path: PathBuf,
+    writer: Option<BufWriter<File>>,
+}
+
+impl SpillFile {
+    pub(crate) fn new() -> Result<Self, ExecutorError> {
+        let path = env::temp_dir().join(format!(
+            "kipsql-spill-{}-{}",
+            process::id(),
+            SPILL_FILE_ID.fetch_add(1, Ordering::Relaxed)
+        ));
+        let writer = BufWriter::new(File::create(&path)?);
+
+        Ok(SpillFile {
+            path,
+            writer: Some(writer),
+        })
+    }
+
+    pub(crate) fn write(&mut self, bytes: &[u8]) -> Result<(), ExecutorError> {
+        let writer = self
+            .writer
+            .as_mut()
+            .ok_or_else(|| ExecutorError::InternalError("spill file is finished".to_string()))?;
+
+        writer.write_all(&(bytes.len() as u32).to_le_bytes())?;
+        writer.write_all(bytes)?;
+
+        Ok(())
+    }
+
+    /// Flushes the records written so far, no more records can be written afterwards.
+    pub(crate) fn reader(&mut self) -> Result<SpillReader, ExecutorError> {
+        if let Some(mut writer) = self.writer.take() {
+            writer.flush()?;
+        }
+
+        Ok(SpillReader {
+            inner: BufReader::new(File::open(&self.path)?),
+        })
+    }
+}
+
+impl Drop for SpillFile {
+    fn drop(&mut self) {
+        drop(self.writer.take());
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+pub(crate) struct SpillReader {
+    inner: BufReader<File>,
+}
+
+impl SpillReader {
+    pub(crate) fn next(&mut self) -> Result<Option<Vec<u8>>, ExecutorError> {
+        let mut len = [0; 4];
+
+        match self.inner.read_exact(&mut len) {
+            Ok(()) => (),
+            Err(err) if err.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+            Err(err) => return Err(err.into()),
+        }
+        let mut bytes = vec![0; u32::from_le_bytes(len) as usize];
+        self.inner.read_exact(&mut bytes)?;
+
+        Ok(Some(bytes))
+    }
+}
@@ -5,7 +5,9 @@ use crate::storage::Transaction;
 use crate::types::tuple::Tuple;
 use futures_async_stream::try_stream;
 use std::cell::RefCell;
+use std::sync::Arc;
 
+/// Emits the literal rows of `VALUES`, for a `VALUES` relation as for the rows of an insertion.
 pub struct Values {
     op: ValuesOperator,
 }
@@ -26,11 +28,12 @@ impl Values {
     #[try_stream(boxed, ok = Tuple, error = ExecutorError)]
     pub async fn _execute(self) {
         let ValuesOperator { columns, rows } = self.op;
+        let schema_ref = Arc::new(columns);
 
         for values in rows {
             yield Tuple {
                 id: None,
-                columns: columns.clone(),
+                schema_ref: schema_ref.clone(),
                 values,
             };
         }
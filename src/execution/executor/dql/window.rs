@@ -0,0 +1,257 @@
+use crate::execution::executor::dql::aggregate::create_accumulator;
+use crate::execution::executor::dql::sort::compare_values;
+use crate::execution::executor::{BoxedExecutor, Executor};
+use crate::execution::ExecutorError;
+use crate::expression::value_compute::binary_op;
+use crate::expression::window::{WindowFrameBound, WindowFrameUnits, WindowSpec};
+use crate::expression::{BinaryOperator, ScalarExpression};
+use crate::planner::operator::window::WindowOperator;
+use crate::storage::Transaction;
+use crate::types::errors::TypeError;
+use crate::types::tuple::{SchemaRef, Tuple};
+use crate::types::value::{DataValue, ValueRef};
+use futures_async_stream::try_stream;
+use itertools::Itertools;
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::sync::Arc;
+
+/// Computes each window call over all the tuples of the input, which are then yielded in
+/// the order they came in with the values of the window calls after their own.
+pub struct Window {
+    window_calls: Vec<ScalarExpression>,
+    input: BoxedExecutor,
+}
+
+impl From<(WindowOperator, BoxedExecutor)> for Window {
+    fn from((WindowOperator { window_calls }, input): (WindowOperator, BoxedExecutor)) -> Self {
+        Window {
+            window_calls,
+            input,
+        }
+    }
+}
+
+impl<T: Transaction> Executor<T> for Window {
+    fn execute(self, _transaction: &RefCell<T>) -> BoxedExecutor {
+        self._execute()
+    }
+}
+
+impl Window {
+    #[try_stream(boxed, ok = Tuple, error = ExecutorError)]
+    pub async fn _execute(self) {
+        let Window {
+            window_calls,
+            input,
+        } = self;
+        let mut tuples = Vec::new();
+
+        #[for_await]
+        for tuple in input {
+            tuples.push(tuple?);
+        }
+        let Some(first) = tuples.first() else {
+            return Ok(());
+        };
+        let schema_ref: SchemaRef = Arc::new(
+            first
+                .schema_ref
+                .iter()
+                .cloned()
+                .chain(window_calls.iter().map(ScalarExpression::output_columns))
+                .collect(),
+        );
+        let results: Vec<Vec<ValueRef>> = window_calls
+            .iter()
+            .map(|window_call| compute_window_call(window_call, &tuples))
+            .try_collect()?;
+
+        for (i, tuple) in tuples.into_iter().enumerate() {
+            let mut values = tuple.values;
+            values.extend(results.iter().map(|result| result[i].clone()));
+
+            yield Tuple {
+                id: tuple.id,
+                schema_ref: schema_ref.clone(),
+                values,
+            };
+        }
+    }
+}
+
+/// The values of the window call for each tuple, in the order of the tuples.
+fn compute_window_call(
+    window_call: &ScalarExpression,
+    tuples: &[Tuple],
+) -> Result<Vec<ValueRef>, ExecutorError> {
+    let ScalarExpression::WindowCall { agg, spec } = window_call else {
+        unreachable!("not a window call: {}", window_call)
+    };
+    let ScalarExpression::AggCall { args, .. } = agg.as_ref() else {
+        unreachable!("not an aggregate: {}", agg)
+    };
+    let eval_all = |exprs: Vec<&ScalarExpression>| {
+        tuples
+            .iter()
+            .map(|tuple| {
+                exprs
+                    .iter()
+                    .map(|expr| expr.eval(tuple, &tuple.schema_ref))
+                    .try_collect()
+            })
+            .collect::<Result<Vec<Vec<ValueRef>>, TypeError>>()
+    };
+    let partition_keys = eval_all(spec.partition_by.iter().collect_vec())?;
+    let order_keys = eval_all(spec.order_by.iter().map(|field| &field.expr).collect_vec())?;
+    let arg_values: Vec<ValueRef> = tuples
+        .iter()
+        .map(|tuple| args[0].eval(tuple, &tuple.schema_ref))
+        .try_collect()?;
+
+    let compare_order = |key_1: &[ValueRef], key_2: &[ValueRef]| {
+        key_1
+            .iter()
+            .zip(key_2.iter())
+            .zip(spec.order_by.iter())
+            .map(|((value_1, value_2), field)| {
+                compare_values(value_1, value_2, field.asc, field.nulls_first)
+            })
+            .find(|ordering| ordering != &Ordering::Equal)
+            .unwrap_or(Ordering::Equal)
+    };
+    let mut rows = (0..tuples.len()).collect_vec();
+    rows.sort_by(|row_1, row_2| {
+        let partition_ordering = partition_keys[*row_1]
+            .iter()
+            .zip(partition_keys[*row_2].iter())
+            .map(|(value_1, value_2)| compare_values(value_1, value_2, true, false))
+            .find(|ordering| ordering != &Ordering::Equal)
+            .unwrap_or(Ordering::Equal);
+
+        partition_ordering.then_with(|| compare_order(&order_keys[*row_1], &order_keys[*row_2]))
+    });
+
+    // every row is in a partition, so all of them are replaced
+    let null = ValueRef::new(DataValue::Null);
+    let mut results = vec![null; tuples.len()];
+    let mut partition_start = 0;
+
+    // the rows of a partition are adjacent once sorted, the nulls are one partition
+    while partition_start < rows.len() {
+        let partition_key = &partition_keys[rows[partition_start]];
+        let partition_len = rows[partition_start..]
+            .iter()
+            .take_while(|row| &partition_keys[**row] == partition_key)
+            .count();
+        let partition = &rows[partition_start..partition_start + partition_len];
+        partition_start += partition_len;
+
+        let keys = partition
+            .iter()
+            .map(|row| order_keys[*row].as_slice())
+            .collect_vec();
+        let frame_of = |i: usize, bound: &WindowFrameBound, is_start: bool| {
+            frame_position(spec, &keys, i, bound, is_start, &compare_order)
+        };
+        // a frame from the first row only grows, the rows are added as it does
+        let mut running = (spec.frame.start == WindowFrameBound::UnboundedPreceding)
+            .then(|| (create_accumulator(agg, &[]), 0));
+
+        for (i, row) in partition.iter().enumerate() {
+            let end = frame_of(i, &spec.frame.end, false)?;
+
+            results[*row] = match &mut running {
+                Some((acc, added)) => {
+                    for row in &partition[*added..end.max(*added)] {
+                        acc.update_value(&arg_values[*row])?;
+                    }
+                    *added = end.max(*added);
+
+                    acc.evaluate()?
+                }
+                None => {
+                    let start = frame_of(i, &spec.frame.start, true)?;
+                    let mut acc = create_accumulator(agg, &[]);
+
+                    for row in &partition[start.min(end)..end] {
+                        acc.update_value(&arg_values[*row])?;
+                    }
+                    acc.evaluate()?
+                }
+            };
+        }
+    }
+
+    Ok(results)
+}
+
+/// The position in the partition where the frame of its `i`th row starts, or where it ends
+/// exclusively.
+fn frame_position(
+    spec: &WindowSpec,
+    keys: &[&[ValueRef]],
+    i: usize,
+    bound: &WindowFrameBound,
+    is_start: bool,
+    compare_order: &impl Fn(&[ValueRef], &[ValueRef]) -> Ordering,
+) -> Result<usize, TypeError> {
+    let len = keys.len();
+    let rows_offset = |offset: &ValueRef| match offset.as_ref() {
+        DataValue::UInt64(Some(offset)) => usize::try_from(*offset).unwrap_or(usize::MAX),
+        _ => unreachable!("the offset of ROWS is bound as an UBIGINT"),
+    };
+
+    if spec.frame.units == WindowFrameUnits::Rows {
+        // the end is exclusive, it is past the row it is bound to
+        let row = if is_start { i } else { i + 1 };
+        let position = match bound {
+            WindowFrameBound::UnboundedPreceding => 0,
+            WindowFrameBound::Preceding(offset) => row.saturating_sub(rows_offset(offset)),
+            WindowFrameBound::CurrentRow => row,
+            WindowFrameBound::Following(offset) => row.saturating_add(rows_offset(offset)),
+            WindowFrameBound::UnboundedFollowing => len,
+        };
+
+        return Ok(position.min(len));
+    }
+    // the rows of a `RANGE` frame are those whose value is within the offset of the value
+    // of the row, the offsets are towards the first rows for `PRECEDING` whatever the order
+    let shifted = |offset: &ValueRef, is_preceding: bool| {
+        let field = &spec.order_by[0];
+        let op = if is_preceding == field.asc {
+            BinaryOperator::Minus
+        } else {
+            BinaryOperator::Plus
+        };
+
+        match binary_op(&keys[i][0], offset, &op) {
+            Ok(value) => Ok(Some(vec![ValueRef::new(value)])),
+            // beyond all the values
+            Err(TypeError::Overflow(_)) => Ok(None),
+            Err(err) => Err(err),
+        }
+    };
+    let target = match bound {
+        WindowFrameBound::UnboundedPreceding => None,
+        WindowFrameBound::Preceding(offset) => shifted(offset, true)?,
+        WindowFrameBound::CurrentRow => Some(keys[i].to_vec()),
+        WindowFrameBound::Following(offset) => shifted(offset, false)?,
+        WindowFrameBound::UnboundedFollowing => None,
+    };
+    let is_following = matches!(
+        bound,
+        WindowFrameBound::Following(_) | WindowFrameBound::UnboundedFollowing
+    );
+
+    Ok(match target {
+        Some(target) if is_start => {
+            keys.partition_point(|key| compare_order(key, &target) == Ordering::Less)
+        }
+        Some(target) => {
+            keys.partition_point(|key| compare_order(key, &target) != Ordering::Greater)
+        }
+        None if is_following => len,
+        None => 0,
+    })
+}
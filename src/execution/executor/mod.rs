@@ -3,6 +3,9 @@ pub(crate) mod dml;
 pub(crate) mod dql;
 pub(crate) mod show;
 
+use crate::execution::executor::ddl::alter_table::AlterTable;
+use crate::execution::executor::ddl::comment::Comment;
+use crate::execution::executor::ddl::create_index::CreateIndex;
 use crate::execution::executor::ddl::create_table::CreateTable;
 use crate::execution::executor::ddl::drop_table::DropTable;
 use crate::execution::executor::ddl::truncate::Truncate;
@@ -12,21 +15,29 @@ use crate::execution::executor::dml::insert::Insert;
 use crate::execution::executor::dml::update::Update;
 use crate::execution::executor::dql::aggregate::hash_agg::HashAggExecutor;
 use crate::execution::executor::dql::aggregate::simple_agg::SimpleAggExecutor;
+use crate::execution::executor::dql::distinct_on::DistinctOn;
 use crate::execution::executor::dql::dummy::Dummy;
+use crate::execution::executor::dql::explain::Explain;
 use crate::execution::executor::dql::filter::Filter;
 use crate::execution::executor::dql::index_scan::IndexScan;
 use crate::execution::executor::dql::join::hash_join::HashJoin;
+use crate::execution::executor::dql::join::index_semi_join::IndexSemiJoin;
 use crate::execution::executor::dql::limit::Limit;
 use crate::execution::executor::dql::projection::Projection;
 use crate::execution::executor::dql::seq_scan::SeqScan;
+use crate::execution::executor::dql::set_operation::SetOperation;
 use crate::execution::executor::dql::sort::Sort;
 use crate::execution::executor::dql::values::Values;
+use crate::execution::executor::dql::window::Window;
+use crate::execution::executor::show::show_create_table::ShowCreateTable;
+use crate::execution::executor::show::show_index::ShowIndex;
 use crate::execution::executor::show::show_table::ShowTables;
 use crate::execution::ExecutorError;
 use crate::planner::operator::Operator;
 use crate::planner::LogicalPlan;
 use crate::storage::Transaction;
 use crate::types::tuple::Tuple;
+use ahash::RandomState;
 use futures::stream::BoxStream;
 use futures::TryStreamExt;
 use std::cell::RefCell;
@@ -37,7 +48,36 @@ pub trait Executor<T: Transaction> {
     fn execute(self, transaction: &RefCell<T>) -> BoxedExecutor;
 }
 
-pub fn build<T: Transaction>(plan: LogicalPlan, transaction: &RefCell<T>) -> BoxedExecutor {
+/// The settings of the executors, they are set on `Database`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct ExecutorConfig {
+    /// Sorts spill to temporary files once they buffer more bytes of tuples than this.
+    pub sort_spill_threshold: Option<usize>,
+    /// Hash aggregates spill the partial states once they hold more groups than this.
+    pub agg_spill_threshold: Option<usize>,
+    /// The hash tables of the executors use fixed seeds instead of random ones, so that
+    /// a query yields its tuples in the same order on every run.
+    pub deterministic: bool,
+    /// The scans fetch this many tuples from the storage per call, `SCAN_BATCH_SIZE` if unset.
+    pub scan_batch_size: Option<usize>,
+}
+
+impl ExecutorConfig {
+    /// The hasher of the hash tables of the executors, whose iteration order depends on it.
+    pub(crate) fn random_state(&self) -> RandomState {
+        if self.deterministic {
+            RandomState::with_seeds(0, 0, 0, 0)
+        } else {
+            RandomState::new()
+        }
+    }
+}
+
+pub fn build<T: Transaction>(
+    plan: LogicalPlan,
+    transaction: &RefCell<T>,
+    config: &ExecutorConfig,
+) -> BoxedExecutor {
     let LogicalPlan {
         operator,
         mut childrens,
@@ -46,73 +86,119 @@ pub fn build<T: Transaction>(plan: LogicalPlan, transaction: &RefCell<T>) -> Box
     match operator {
         Operator::Dummy => Dummy {}.execute(transaction),
         Operator::Aggregate(op) => {
-            let input = build(childrens.remove(0), transaction);
+            let input = build(childrens.remove(0), transaction, config);
 
             if op.groupby_exprs.is_empty() {
                 SimpleAggExecutor::from((op, input)).execute(transaction)
             } else {
-                HashAggExecutor::from((op, input)).execute(transaction)
+                HashAggExecutor::from((op, input))
+                    .with_spill_threshold(config.agg_spill_threshold)
+                    .with_random_state(config.random_state())
+                    .execute(transaction)
             }
         }
         Operator::Filter(op) => {
-            let input = build(childrens.remove(0), transaction);
+            let input = build(childrens.remove(0), transaction, config);
 
             Filter::from((op, input)).execute(transaction)
         }
         Operator::Join(op) => {
-            let left_input = build(childrens.remove(0), transaction);
-            let right_input = build(childrens.remove(0), transaction);
+            let left_input = build(childrens.remove(0), transaction, config);
+
+            if op.index_probe.is_some() {
+                IndexSemiJoin::from((op, left_input, childrens.remove(0))).execute(transaction)
+            } else {
+                let right_input = build(childrens.remove(0), transaction, config);
+
+                HashJoin::from((op, left_input, right_input))
+                    .with_random_state(config.random_state())
+                    .execute(transaction)
+            }
+        }
+        Operator::SetOperation(op) => {
+            let left_input = build(childrens.remove(0), transaction, config);
+            let right_input = build(childrens.remove(0), transaction, config);
 
-            HashJoin::from((op, left_input, right_input)).execute(transaction)
+            SetOperation::from((op, left_input, right_input)).execute(transaction)
         }
         Operator::Project(op) => {
-            let input = build(childrens.remove(0), transaction);
+            let input = build(childrens.remove(0), transaction, config);
 
             Projection::from((op, input)).execute(transaction)
         }
         Operator::Scan(op) => {
             if op.index_by.is_some() {
-                IndexScan::from(op).execute(transaction)
+                IndexScan::from(op)
+                    .with_batch_size(config.scan_batch_size)
+                    .execute(transaction)
             } else {
-                SeqScan::from(op).execute(transaction)
+                SeqScan::from(op)
+                    .with_batch_size(config.scan_batch_size)
+                    .execute(transaction)
             }
         }
         Operator::Sort(op) => {
-            let input = build(childrens.remove(0), transaction);
+            let input = build(childrens.remove(0), transaction, config);
 
-            Sort::from((op, input)).execute(transaction)
+            Sort::from((op, input))
+                .with_spill_threshold(config.sort_spill_threshold)
+                .execute(transaction)
+        }
+        Operator::DistinctOn(op) => {
+            let input = build(childrens.remove(0), transaction, config);
+
+            DistinctOn::from((op, input)).execute(transaction)
+        }
+        Operator::Window(op) => {
+            let input = build(childrens.remove(0), transaction, config);
+
+            Window::from((op, input)).execute(transaction)
         }
         Operator::Limit(op) => {
-            let input = build(childrens.remove(0), transaction);
+            let input = build(childrens.remove(0), transaction, config);
 
             Limit::from((op, input)).execute(transaction)
         }
         Operator::Insert(op) => {
-            let input = build(childrens.remove(0), transaction);
+            let input = build(childrens.remove(0), transaction, config);
 
             Insert::from((op, input)).execute(transaction)
         }
         Operator::Update(op) => {
-            let input = build(childrens.remove(0), transaction);
-            let values = build(childrens.remove(0), transaction);
+            let input = build(childrens.remove(0), transaction, config);
+            let values = build(childrens.remove(0), transaction, config);
 
             Update::from((op, input, values)).execute(transaction)
         }
         Operator::Delete(op) => {
-            let input = build(childrens.remove(0), transaction);
+            let input = build(childrens.remove(0), transaction, config);
 
             Delete::from((op, input)).execute(transaction)
         }
         Operator::Values(op) => Values::from(op).execute(transaction),
-        Operator::CreateTable(op) => CreateTable::from(op).execute(transaction),
+        Operator::CreateTable(op) => {
+            let input =
+                (!childrens.is_empty()).then(|| build(childrens.remove(0), transaction, config));
+
+            CreateTable::from((op, input)).execute(transaction)
+        }
+        Operator::CreateIndex(op) => CreateIndex::from(op).execute(transaction),
         Operator::DropTable(op) => DropTable::from(op).execute(transaction),
         Operator::Truncate(op) => Truncate::from(op).execute(transaction),
+        Operator::AlterTable(op) => AlterTable::from(op).execute(transaction),
+        Operator::Comment(op) => Comment::from(op).execute(transaction),
         Operator::Show(op) => ShowTables::from(op).execute(transaction),
+        Operator::ShowCreateTable(op) => ShowCreateTable::from(op).execute(transaction),
+        Operator::ShowIndex(op) => ShowIndex::from(op).execute(transaction),
         Operator::CopyFromFile(op) => CopyFromFile::from(op).execute(transaction),
         #[warn(unused_assignments)]
         Operator::CopyToFile(_op) => {
             todo!()
         }
+        Operator::SetVariable(_) => unreachable!("`SET` is applied by the `Database`"),
+        Operator::Explain(format) => {
+            Explain::from((format, childrens.remove(0))).execute(transaction)
+        }
     }
 }
 
@@ -1 +1,3 @@
+pub(crate) mod show_create_table;
+pub(crate) mod show_index;
 pub(crate) mod show_table;
@@ -0,0 +1,50 @@
+use crate::catalog::ColumnCatalog;
+use crate::execution::executor::{BoxedExecutor, Executor};
+use crate::execution::ExecutorError;
+use crate::planner::operator::show::ShowCreateTableOperator;
+use crate::storage::{StorageError, Transaction};
+use crate::types::tuple::{SchemaRef, Tuple};
+use crate::types::value::{DataValue, ValueRef};
+use futures_async_stream::try_stream;
+use std::cell::RefCell;
+use std::sync::Arc;
+
+pub struct ShowCreateTable {
+    op: ShowCreateTableOperator,
+}
+
+impl From<ShowCreateTableOperator> for ShowCreateTable {
+    fn from(op: ShowCreateTableOperator) -> Self {
+        ShowCreateTable { op }
+    }
+}
+
+impl<T: Transaction> Executor<T> for ShowCreateTable {
+    fn execute(self, transaction: &RefCell<T>) -> BoxedExecutor {
+        unsafe { self._execute(transaction.as_ptr().as_ref().unwrap()) }
+    }
+}
+
+impl ShowCreateTable {
+    #[try_stream(boxed, ok = Tuple, error = ExecutorError)]
+    pub async fn _execute<T: Transaction>(self, transaction: &T) {
+        let ShowCreateTableOperator { table_name } = self.op;
+        let table = transaction
+            .table(table_name.clone())
+            .ok_or(StorageError::TableNotFound)?;
+        let schema_ref: SchemaRef = Arc::new(vec![
+            Arc::new(ColumnCatalog::new_dummy("TABLE".to_string())),
+            Arc::new(ColumnCatalog::new_dummy("CREATE TABLE".to_string())),
+        ]);
+        let values: Vec<ValueRef> = vec![
+            ValueRef::new(DataValue::Utf8(Some(table_name.to_string()))),
+            ValueRef::new(DataValue::Utf8(Some(table.create_sql()))),
+        ];
+
+        yield Tuple {
+            id: None,
+            schema_ref,
+            values,
+        };
+    }
+}
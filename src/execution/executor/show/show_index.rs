@@ -0,0 +1,74 @@
+use crate::catalog::ColumnCatalog;
+use crate::execution::executor::{BoxedExecutor, Executor};
+use crate::execution::ExecutorError;
+use crate::planner::operator::show::ShowIndexOperator;
+use crate::storage::{StorageError, Transaction};
+use crate::types::tuple::{SchemaRef, Tuple};
+use crate::types::value::{DataValue, ValueRef};
+use futures_async_stream::try_stream;
+use itertools::Itertools;
+use std::cell::RefCell;
+use std::sync::Arc;
+
+pub struct ShowIndex {
+    op: ShowIndexOperator,
+}
+
+impl From<ShowIndexOperator> for ShowIndex {
+    fn from(op: ShowIndexOperator) -> Self {
+        ShowIndex { op }
+    }
+}
+
+impl<T: Transaction> Executor<T> for ShowIndex {
+    fn execute(self, transaction: &RefCell<T>) -> BoxedExecutor {
+        unsafe { self._execute(transaction.as_ptr().as_ref().unwrap()) }
+    }
+}
+
+impl ShowIndex {
+    #[try_stream(boxed, ok = Tuple, error = ExecutorError)]
+    pub async fn _execute<T: Transaction>(self, transaction: &T) {
+        let ShowIndexOperator { table_name } = self.op;
+        let table = transaction
+            .table(table_name.clone())
+            .ok_or(StorageError::TableNotFound)?;
+        let schema_ref: SchemaRef = Arc::new(
+            [
+                "TABLE", "INDEX", "COLUMNS", "UNIQUE", "PRIMARY", "ENTRIES", "BYTES",
+            ]
+            .into_iter()
+            .map(|name| Arc::new(ColumnCatalog::new_dummy(name.to_string())))
+            .collect(),
+        );
+
+        for (index_meta, stats) in transaction.index_stats(table_name.clone())? {
+            let columns = match &index_meta.expr {
+                Some(index_expr) => index_expr.sql.clone(),
+                None => index_meta
+                    .column_ids
+                    .iter()
+                    .filter_map(|column_id| table.get_column_by_id(column_id))
+                    .map(|column| column.name())
+                    .join(", "),
+            };
+            let values: Vec<ValueRef> = vec![
+                ValueRef::new(DataValue::Utf8(Some(table_name.to_string()))),
+                ValueRef::new(DataValue::Utf8(Some(index_meta.name.clone()))),
+                ValueRef::new(DataValue::Utf8(Some(columns))),
+                ValueRef::new(DataValue::Boolean(Some(
+                    index_meta.is_unique || index_meta.is_primary,
+                ))),
+                ValueRef::new(DataValue::Boolean(Some(index_meta.is_primary))),
+                ValueRef::new(DataValue::UInt64(Some(stats.entries as u64))),
+                ValueRef::new(DataValue::UInt64(Some(stats.bytes as u64))),
+            ];
+
+            yield Tuple {
+                id: None,
+                schema_ref: schema_ref.clone(),
+                values,
+            };
+        }
+    }
+}
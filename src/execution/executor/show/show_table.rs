@@ -1,22 +1,22 @@
 use crate::catalog::ColumnCatalog;
-use crate::catalog::ColumnRef;
 use crate::execution::executor::{BoxedExecutor, Executor};
 use crate::execution::ExecutorError;
+use crate::expression::value_compute::like_match;
 use crate::planner::operator::show::ShowTablesOperator;
 use crate::storage::Transaction;
-use crate::types::tuple::Tuple;
+use crate::types::tuple::{SchemaRef, Tuple};
 use crate::types::value::{DataValue, ValueRef};
 use futures_async_stream::try_stream;
 use std::cell::RefCell;
 use std::sync::Arc;
 
 pub struct ShowTables {
-    _op: ShowTablesOperator,
+    op: ShowTablesOperator,
 }
 
 impl From<ShowTablesOperator> for ShowTables {
     fn from(op: ShowTablesOperator) -> Self {
-        ShowTables { _op: op }
+        ShowTables { op }
     }
 }
 
@@ -30,15 +30,21 @@ impl ShowTables {
     #[try_stream(boxed, ok = Tuple, error = ExecutorError)]
     pub async fn _execute<T: Transaction>(self, transaction: &T) {
         let tables = transaction.show_tables()?;
+        let schema_ref: SchemaRef = Arc::new(vec![Arc::new(ColumnCatalog::new_dummy(
+            "TABLES".to_string(),
+        ))]);
 
         for table in tables {
-            let columns: Vec<ColumnRef> =
-                vec![Arc::new(ColumnCatalog::new_dummy("TABLES".to_string()))];
-            let values: Vec<ValueRef> = vec![Arc::new(DataValue::Utf8(Some(table)))];
+            if let Some(pattern) = &self.op.pattern {
+                if !like_match(&table, pattern) {
+                    continue;
+                }
+            }
+            let values: Vec<ValueRef> = vec![ValueRef::new(DataValue::Utf8(Some(table)))];
 
             yield Tuple {
                 id: None,
-                columns,
+                schema_ref: schema_ref.clone(),
                 values,
             };
         }
@@ -54,6 +54,8 @@ pub enum ExecutorError {
     ),
     #[error("tuple length mismatch: expected {expected} but got {actual}")]
     LengthMismatch { expected: usize, actual: usize },
+    #[error("more than one row returned by a subquery used as an expression")]
+    SubqueryRows,
     #[error("abort")]
     Abort,
     #[error("unknown error")]
@@ -7,6 +7,9 @@ pub enum AggKind {
     Min,
     Sum,
     Count,
+    /// `COUNT(DISTINCT ..)` estimated from a HyperLogLog sketch.
+    ApproxCountDistinct,
+    Grouping,
 }
 
 impl AggKind {
@@ -17,6 +20,8 @@ impl AggKind {
             AggKind::Min => false,
             AggKind::Sum => true,
             AggKind::Count => true,
+            AggKind::ApproxCountDistinct => false,
+            AggKind::Grouping => false,
         }
     }
 }
@@ -1,42 +1,50 @@
+use crate::catalog::{ColumnCatalog, ColumnRef};
+use crate::expression::function::BuiltinKind;
 use crate::expression::value_compute::{binary_op, unary_op};
-use crate::expression::ScalarExpression;
+use crate::expression::{BinaryOperator, ScalarExpression};
 use crate::types::errors::TypeError;
 use crate::types::tuple::Tuple;
 use crate::types::value::{DataValue, ValueRef};
+use crate::types::LogicalType;
+use chrono::Utc;
 use itertools::Itertools;
 use lazy_static::lazy_static;
-use std::sync::Arc;
 
 lazy_static! {
-    static ref NULL_VALUE: ValueRef = Arc::new(DataValue::Null);
+    static ref NULL_VALUE: ValueRef = ValueRef::new(DataValue::Null);
 }
 
 impl ScalarExpression {
-    pub fn eval(&self, tuple: &Tuple) -> Result<ValueRef, TypeError> {
-        if let Some(value) = Self::eval_with_name(tuple, self.output_columns().name()) {
+    /// The only entry point for evaluating an expression against a tuple.
+    ///
+    /// `columns` describes the layout of `tuple.values`, every operator(filter, projection,
+    /// join condition, sort and aggregation) must evaluate through it so that the
+    /// NULL propagation and type rules stay the same everywhere.
+    pub fn eval(&self, tuple: &Tuple, columns: &[ColumnRef]) -> Result<ValueRef, TypeError> {
+        if let Some(value) = Self::eval_with_column(tuple, columns, &self.output_columns()) {
             return Ok(value.clone());
         }
 
         match &self {
             ScalarExpression::Constant(val) => Ok(val.clone()),
             ScalarExpression::ColumnRef(col) => {
-                let value = Self::eval_with_name(tuple, col.name())
+                let value = Self::eval_with_column(tuple, columns, col)
                     .unwrap_or(&NULL_VALUE)
                     .clone();
 
                 Ok(value)
             }
             ScalarExpression::Alias { expr, alias } => {
-                if let Some(value) = Self::eval_with_name(tuple, alias) {
+                if let Some(value) = Self::eval_with_name(tuple, columns, alias) {
                     return Ok(value.clone());
                 }
 
-                expr.eval(tuple)
+                expr.eval(tuple, columns)
             }
             ScalarExpression::TypeCast { expr, ty, .. } => {
-                let value = expr.eval(tuple)?;
+                let value = expr.eval(tuple, columns)?;
 
-                Ok(Arc::new(DataValue::clone(&value).cast(ty)?))
+                Ok(ValueRef::new(DataValue::clone(&value).cast(ty)?))
             }
             ScalarExpression::Binary {
                 left_expr,
@@ -44,56 +52,598 @@ impl ScalarExpression {
                 op,
                 ..
             } => {
-                let left = left_expr.eval(tuple)?;
-                let right = right_expr.eval(tuple)?;
+                if let (ScalarExpression::Tuple(left), ScalarExpression::Tuple(right)) =
+                    (left_expr.as_ref(), right_expr.as_ref())
+                {
+                    return Ok(ValueRef::new(Self::eval_row_comparison(
+                        left, right, op, tuple, columns,
+                    )?));
+                }
+                let left = left_expr.eval(tuple, columns)?;
+
+                // the right side is skipped once the left one decides, so that it can guard it,
+                // e.g. `c2 <> 0 AND c1 / c2 > 1`
+                match (op, left.as_ref()) {
+                    (BinaryOperator::And, DataValue::Boolean(Some(false)))
+                    | (BinaryOperator::Or, DataValue::Boolean(Some(true))) => return Ok(left),
+                    _ => (),
+                }
+                let right = right_expr.eval(tuple, columns)?;
 
-                Ok(Arc::new(binary_op(&left, &right, op)?))
+                Ok(ValueRef::new(binary_op(&left, &right, op)?))
             }
             ScalarExpression::IsNull { expr, negated } => {
-                let mut is_null = expr.eval(tuple)?.is_null();
+                let mut is_null = expr.eval(tuple, columns)?.is_null();
                 if *negated {
                     is_null = !is_null;
                 }
-                Ok(Arc::new(DataValue::Boolean(Some(is_null))))
+                Ok(ValueRef::new(DataValue::Boolean(Some(is_null))))
             }
             ScalarExpression::In {
                 expr,
                 args,
                 negated,
             } => {
-                let value = expr.eval(tuple)?;
-                let mut is_in = false;
+                let value = expr.eval(tuple, columns)?;
+                if value.is_null() {
+                    return Ok(ValueRef::new(DataValue::Boolean(None)));
+                }
+                let mut is_in = Some(false);
                 for arg in args {
-                    if arg.eval(tuple)? == value {
-                        is_in = true;
+                    let arg_value = arg.eval(tuple, columns)?;
+
+                    if arg_value.is_null() {
+                        // `1 IN (2, NULL)` is unknown rather than false
+                        is_in = None;
+                    } else if arg_value == value {
+                        is_in = Some(true);
                         break;
                     }
                 }
                 if *negated {
-                    is_in = !is_in;
+                    is_in = is_in.map(|v| !v);
                 }
-                Ok(Arc::new(DataValue::Boolean(Some(is_in))))
+                Ok(ValueRef::new(DataValue::Boolean(is_in)))
             }
             ScalarExpression::Unary { expr, op, .. } => {
-                let value = expr.eval(tuple)?;
+                let value = expr.eval(tuple, columns)?;
 
-                Ok(Arc::new(unary_op(&value, op)?))
+                Ok(ValueRef::new(unary_op(&value, op)?))
             }
-            ScalarExpression::AggCall { .. } => {
-                let value = Self::eval_with_name(tuple, self.output_columns().name())
+            ScalarExpression::AggCall { .. } | ScalarExpression::WindowCall { .. } => {
+                let value = Self::eval_with_name(tuple, columns, self.output_columns().name())
                     .unwrap_or(&NULL_VALUE)
                     .clone();
 
                 Ok(value)
             }
+            ScalarExpression::Function { function, args } => {
+                let values: Vec<DataValue> = args
+                    .iter()
+                    .zip(function.signature.args.iter())
+                    .map(|(arg, ty)| {
+                        let value = arg.eval(tuple, columns)?;
+
+                        DataValue::clone(&value).cast(ty)
+                    })
+                    .try_collect()?;
+
+                Ok(ValueRef::new(function.call(&values)?))
+            }
+            ScalarExpression::Builtin { kind, args, ty } => {
+                let values: Vec<DataValue> = args
+                    .iter()
+                    .map(|arg| {
+                        let value = arg.eval(tuple, columns)?;
+
+                        Ok::<_, TypeError>(DataValue::clone(&value))
+                    })
+                    .try_collect()?;
+
+                Ok(ValueRef::new(Self::eval_builtin(kind, values, ty)?))
+            }
+            ScalarExpression::Tuple(_) => Err(TypeError::InternalError(format!(
+                "row value {} can only be compared",
+                self.output_columns().name()
+            ))),
         }
     }
 
-    fn eval_with_name<'a>(tuple: &'a Tuple, name: &str) -> Option<&'a ValueRef> {
-        tuple
-            .columns
+    /// Compares two rows element by element, the first pair that is not equal decides the
+    /// result and it is unknown if either of them is null. An equality only needs a pair that
+    /// is not equal to be false, so it looks past the nulls.
+    fn eval_row_comparison(
+        left: &[ScalarExpression],
+        right: &[ScalarExpression],
+        op: &BinaryOperator,
+        tuple: &Tuple,
+        columns: &[ColumnRef],
+    ) -> Result<DataValue, TypeError> {
+        let is_equality = matches!(op, BinaryOperator::Eq | BinaryOperator::NotEq);
+        let mut has_null = false;
+
+        for (left_expr, right_expr) in left.iter().zip(right) {
+            let left_value = left_expr.eval(tuple, columns)?;
+            let right_value = right_expr.eval(tuple, columns)?;
+
+            if left_value.is_null() || right_value.is_null() {
+                if !is_equality {
+                    return Ok(DataValue::Boolean(None));
+                }
+                has_null = true;
+                continue;
+            }
+            if binary_op(&left_value, &right_value, &BinaryOperator::Eq)?
+                != DataValue::Boolean(Some(true))
+            {
+                return binary_op(&left_value, &right_value, op);
+            }
+        }
+        if has_null {
+            return Ok(DataValue::Boolean(None));
+        }
+
+        Ok(DataValue::Boolean(Some(matches!(
+            op,
+            BinaryOperator::Eq | BinaryOperator::GtEq | BinaryOperator::LtEq
+        ))))
+    }
+
+    fn eval_builtin(
+        kind: &BuiltinKind,
+        values: Vec<DataValue>,
+        ty: &LogicalType,
+    ) -> Result<DataValue, TypeError> {
+        if let BuiltinKind::NullIf = kind {
+            // compared with the common type of both, but the result keeps the type of the first
+            let is_equal = binary_op(&values[0], &values[1], &BinaryOperator::Eq)?;
+            if let DataValue::Boolean(Some(true)) = is_equal {
+                return Ok(DataValue::none(ty));
+            }
+            return values[0].clone().cast(ty);
+        }
+        if let BuiltinKind::Now = kind {
+            return Ok(DataValue::Date64(Some(Utc::now().timestamp())));
+        }
+        if let BuiltinKind::GenRandomUuid = kind {
+            return Ok(DataValue::Utf8(Some(Self::random_uuid())));
+        }
+        if let BuiltinKind::Lower | BuiltinKind::Upper = kind {
+            return Ok(match values[0].clone().cast(ty)? {
+                DataValue::Utf8(Some(string)) if kind == &BuiltinKind::Lower => {
+                    DataValue::Utf8(Some(string.to_lowercase()))
+                }
+                DataValue::Utf8(Some(string)) => DataValue::Utf8(Some(string.to_uppercase())),
+                value => value,
+            });
+        }
+        let mut result: Option<DataValue> = None;
+
+        for value in values {
+            if value.is_null() {
+                continue;
+            }
+            let value = value.cast(ty)?;
+
+            result = match (kind, result) {
+                (BuiltinKind::Coalesce, _) => return Ok(value),
+                (BuiltinKind::Greatest, Some(result)) if result >= value => Some(result),
+                (BuiltinKind::Least, Some(result)) if result <= value => Some(result),
+                _ => Some(value),
+            };
+        }
+
+        Ok(result.unwrap_or_else(|| DataValue::none(ty)))
+    }
+
+    /// A version 4 UUID, e.g. `6f1c0a2e-93b4-4d5f-8a7e-2b9c4d1e0f3a`.
+    fn random_uuid() -> String {
+        let mut bits = rand::random::<u128>();
+        // the version in the 4 bits of the 7th byte and the variant in the 2 bits of the 9th
+        bits = (bits & !(0xf << 76)) | (0x4 << 76);
+        bits = (bits & !(0x3 << 62)) | (0x2 << 62);
+        let hex = format!("{:032x}", bits);
+
+        format!(
+            "{}-{}-{}-{}-{}",
+            &hex[0..8],
+            &hex[8..12],
+            &hex[12..16],
+            &hex[16..20],
+            &hex[20..32]
+        )
+    }
+
+    /// Columns of the same name are told apart by the table they are bound under,
+    /// e.g. in the tuples of a table joined with itself.
+    fn eval_with_column<'a>(
+        tuple: &'a Tuple,
+        columns: &[ColumnRef],
+        col: &ColumnCatalog,
+    ) -> Option<&'a ValueRef> {
+        columns
+            .iter()
+            .position(|tul_col| {
+                tul_col.name() == col.name() && tul_col.table_name() == col.table_name()
+            })
+            .or_else(|| {
+                columns
+                    .iter()
+                    .position(|tul_col| tul_col.name() == col.name())
+            })
+            .and_then(|i| tuple.values.get(i))
+    }
+
+    fn eval_with_name<'a>(
+        tuple: &'a Tuple,
+        columns: &[ColumnRef],
+        name: &str,
+    ) -> Option<&'a ValueRef> {
+        columns
             .iter()
             .find_position(|tul_col| tul_col.name() == name)
-            .map(|(i, _)| &tuple.values[i])
+            .and_then(|(i, _)| tuple.values.get(i))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::catalog::{ColumnCatalog, ColumnDesc, ColumnRef};
+    use crate::expression::function::BuiltinKind;
+    use crate::expression::{BinaryOperator, ScalarExpression, UnaryOperator};
+    use crate::types::errors::TypeError;
+    use crate::types::tuple::Tuple;
+    use crate::types::value::{DataValue, ValueRef};
+    use crate::types::LogicalType;
+    use std::sync::Arc;
+
+    fn build_tuple() -> (Tuple, Vec<ColumnRef>) {
+        let columns = vec![
+            Arc::new(ColumnCatalog::new(
+                "c1".to_string(),
+                false,
+                ColumnDesc::new(LogicalType::Integer, true, false, None),
+                None,
+            )),
+            Arc::new(ColumnCatalog::new(
+                "c2".to_string(),
+                true,
+                ColumnDesc::new(LogicalType::Integer, false, false, None),
+                None,
+            )),
+        ];
+        let tuple = Tuple {
+            id: None,
+            schema_ref: Arc::new(columns.clone()),
+            values: vec![
+                ValueRef::new(DataValue::Int32(Some(1))),
+                ValueRef::new(DataValue::Int32(None)),
+            ],
+        };
+
+        (tuple, columns)
+    }
+
+    fn binary(
+        op: BinaryOperator,
+        left_expr: ScalarExpression,
+        right_expr: ScalarExpression,
+    ) -> ScalarExpression {
+        ScalarExpression::Binary {
+            op,
+            left_expr: Box::new(left_expr),
+            right_expr: Box::new(right_expr),
+            ty: LogicalType::Integer,
+        }
+    }
+
+    #[test]
+    fn test_eval_column_ref_and_constant() -> Result<(), TypeError> {
+        let (tuple, columns) = build_tuple();
+
+        let c1 = ScalarExpression::ColumnRef(columns[0].clone());
+        assert_eq!(
+            c1.eval(&tuple, &columns)?,
+            ValueRef::new(DataValue::Int32(Some(1)))
+        );
+
+        let constant = ScalarExpression::Constant(ValueRef::new(DataValue::Int32(Some(7))));
+        assert_eq!(
+            constant.eval(&tuple, &columns)?,
+            ValueRef::new(DataValue::Int32(Some(7)))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_eval_binary() -> Result<(), TypeError> {
+        let (tuple, columns) = build_tuple();
+
+        // c1 + 2 > 2
+        let expr = binary(
+            BinaryOperator::Gt,
+            binary(
+                BinaryOperator::Plus,
+                ScalarExpression::ColumnRef(columns[0].clone()),
+                ScalarExpression::Constant(ValueRef::new(DataValue::Int32(Some(2)))),
+            ),
+            ScalarExpression::Constant(ValueRef::new(DataValue::Int32(Some(2)))),
+        );
+        assert_eq!(
+            expr.eval(&tuple, &columns)?,
+            ValueRef::new(DataValue::Boolean(Some(true)))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_eval_short_circuit() -> Result<(), TypeError> {
+        let (tuple, columns) = build_tuple();
+        let boolean = |value| ScalarExpression::Constant(ValueRef::new(DataValue::Boolean(value)));
+        // c1 / 0 = 1, which fails whenever it is evaluated
+        let failing = binary(
+            BinaryOperator::Eq,
+            binary(
+                BinaryOperator::Divide,
+                ScalarExpression::ColumnRef(columns[0].clone()),
+                ScalarExpression::Constant(ValueRef::new(DataValue::Int32(Some(0)))),
+            ),
+            ScalarExpression::Constant(ValueRef::new(DataValue::Int32(Some(1)))),
+        );
+
+        let expr = binary(BinaryOperator::And, boolean(Some(false)), failing.clone());
+        assert_eq!(
+            expr.eval(&tuple, &columns)?,
+            ValueRef::new(DataValue::Boolean(Some(false)))
+        );
+        let expr = binary(BinaryOperator::Or, boolean(Some(true)), failing.clone());
+        assert_eq!(
+            expr.eval(&tuple, &columns)?,
+            ValueRef::new(DataValue::Boolean(Some(true)))
+        );
+
+        // the left side does not decide
+        for (op, left) in [
+            (BinaryOperator::And, Some(true)),
+            (BinaryOperator::And, None),
+            (BinaryOperator::Or, Some(false)),
+            (BinaryOperator::Or, None),
+        ] {
+            let expr = binary(op, boolean(left), failing.clone());
+            assert!(matches!(
+                expr.eval(&tuple, &columns),
+                Err(TypeError::DivisionByZero)
+            ));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_eval_null_propagation() -> Result<(), TypeError> {
+        let (tuple, columns) = build_tuple();
+        let c1 = ScalarExpression::ColumnRef(columns[0].clone());
+        let c2 = ScalarExpression::ColumnRef(columns[1].clone());
+
+        // -(c1 * c2) = 1
+        let expr = binary(
+            BinaryOperator::Eq,
+            ScalarExpression::Unary {
+                op: UnaryOperator::Minus,
+                expr: Box::new(binary(BinaryOperator::Multiply, c1.clone(), c2.clone())),
+                ty: LogicalType::Integer,
+            },
+            ScalarExpression::Constant(ValueRef::new(DataValue::Int32(Some(1)))),
+        );
+        assert_eq!(
+            expr.eval(&tuple, &columns)?,
+            ValueRef::new(DataValue::Boolean(None))
+        );
+
+        // NOT NULL
+        let expr = ScalarExpression::Unary {
+            op: UnaryOperator::Not,
+            expr: Box::new(ScalarExpression::Constant(ValueRef::new(DataValue::Null))),
+            ty: LogicalType::Boolean,
+        };
+        assert!(expr.eval(&tuple, &columns)?.is_null());
+
+        // c2 IS NULL
+        let expr = ScalarExpression::IsNull {
+            expr: Box::new(c2.clone()),
+            negated: false,
+        };
+        assert_eq!(
+            expr.eval(&tuple, &columns)?,
+            ValueRef::new(DataValue::Boolean(Some(true)))
+        );
+
+        // c1 IN (2, NULL) / c2 IN (1)
+        let expr = ScalarExpression::In {
+            expr: Box::new(c1),
+            args: vec![
+                ScalarExpression::Constant(ValueRef::new(DataValue::Int32(Some(2)))),
+                ScalarExpression::Constant(ValueRef::new(DataValue::Null)),
+            ],
+            negated: false,
+        };
+        assert_eq!(
+            expr.eval(&tuple, &columns)?,
+            ValueRef::new(DataValue::Boolean(None))
+        );
+        let expr = ScalarExpression::In {
+            expr: Box::new(c2),
+            args: vec![ScalarExpression::Constant(ValueRef::new(DataValue::Int32(
+                Some(1),
+            )))],
+            negated: true,
+        };
+        assert_eq!(
+            expr.eval(&tuple, &columns)?,
+            ValueRef::new(DataValue::Boolean(None))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_eval_builtin() -> Result<(), TypeError> {
+        let (tuple, columns) = build_tuple();
+        let c1 = ScalarExpression::ColumnRef(columns[0].clone());
+        let c2 = ScalarExpression::ColumnRef(columns[1].clone());
+        let constant = |value| ScalarExpression::Constant(ValueRef::new(DataValue::Int32(value)));
+        let builtin = |kind, args| ScalarExpression::Builtin {
+            kind,
+            args,
+            ty: LogicalType::Integer,
+        };
+
+        // COALESCE(c2, c1) / COALESCE(c2, NULL)
+        let expr = builtin(BuiltinKind::Coalesce, vec![c2.clone(), c1.clone()]);
+        assert_eq!(
+            expr.eval(&tuple, &columns)?,
+            ValueRef::new(DataValue::Int32(Some(1)))
+        );
+        let expr = builtin(BuiltinKind::Coalesce, vec![c2.clone(), constant(None)]);
+        assert_eq!(
+            expr.eval(&tuple, &columns)?,
+            ValueRef::new(DataValue::Int32(None))
+        );
+
+        // NULLIF(c1, 1) / NULLIF(c1, c2)
+        let expr = builtin(BuiltinKind::NullIf, vec![c1.clone(), constant(Some(1))]);
+        assert_eq!(
+            expr.eval(&tuple, &columns)?,
+            ValueRef::new(DataValue::Int32(None))
+        );
+        let expr = builtin(BuiltinKind::NullIf, vec![c1.clone(), c2.clone()]);
+        assert_eq!(
+            expr.eval(&tuple, &columns)?,
+            ValueRef::new(DataValue::Int32(Some(1)))
+        );
+
+        // GREATEST(c1, c2, 3) / LEAST(c1, c2, 3) / GREATEST(c2, NULL)
+        let args = vec![c1, c2.clone(), constant(Some(3))];
+        let expr = builtin(BuiltinKind::Greatest, args.clone());
+        assert_eq!(
+            expr.eval(&tuple, &columns)?,
+            ValueRef::new(DataValue::Int32(Some(3)))
+        );
+        let expr = builtin(BuiltinKind::Least, args);
+        assert_eq!(
+            expr.eval(&tuple, &columns)?,
+            ValueRef::new(DataValue::Int32(Some(1)))
+        );
+        let expr = builtin(BuiltinKind::Greatest, vec![c2, constant(None)]);
+        assert_eq!(
+            expr.eval(&tuple, &columns)?,
+            ValueRef::new(DataValue::Int32(None))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_eval_row_comparison() -> Result<(), TypeError> {
+        let (tuple, columns) = build_tuple();
+        let c1 = ScalarExpression::ColumnRef(columns[0].clone());
+        let c2 = ScalarExpression::ColumnRef(columns[1].clone());
+        let constant = |value| ScalarExpression::Constant(ValueRef::new(DataValue::Int32(value)));
+        let row = |left: Vec<ScalarExpression>, op, right: Vec<ScalarExpression>| {
+            binary(
+                op,
+                ScalarExpression::Tuple(left),
+                ScalarExpression::Tuple(right),
+            )
+        };
+        let cases = [
+            // (1, 2) > (1, 1) / (1, 2) < (1, 1) / (1, 2) >= (1, 2) / (1, 2) > (1, 2)
+            (
+                row(
+                    vec![c1.clone(), constant(Some(2))],
+                    BinaryOperator::Gt,
+                    vec![constant(Some(1)), constant(Some(1))],
+                ),
+                Some(true),
+            ),
+            (
+                row(
+                    vec![c1.clone(), constant(Some(2))],
+                    BinaryOperator::Lt,
+                    vec![constant(Some(1)), constant(Some(1))],
+                ),
+                Some(false),
+            ),
+            (
+                row(
+                    vec![c1.clone(), constant(Some(2))],
+                    BinaryOperator::GtEq,
+                    vec![constant(Some(1)), constant(Some(2))],
+                ),
+                Some(true),
+            ),
+            (
+                row(
+                    vec![c1.clone(), constant(Some(2))],
+                    BinaryOperator::Gt,
+                    vec![constant(Some(1)), constant(Some(2))],
+                ),
+                Some(false),
+            ),
+            // (1, NULL) > (0, 5): decided before the null
+            (
+                row(
+                    vec![c1.clone(), c2.clone()],
+                    BinaryOperator::Gt,
+                    vec![constant(Some(0)), constant(Some(5))],
+                ),
+                Some(true),
+            ),
+            // (1, NULL) > (1, 5)
+            (
+                row(
+                    vec![c1.clone(), c2.clone()],
+                    BinaryOperator::Gt,
+                    vec![constant(Some(1)), constant(Some(5))],
+                ),
+                None,
+            ),
+            // (NULL, 1) = (5, 2) / (NULL, 1) = (5, 1) / (NULL, 1) <> (5, 2)
+            (
+                row(
+                    vec![c2.clone(), c1.clone()],
+                    BinaryOperator::Eq,
+                    vec![constant(Some(5)), constant(Some(2))],
+                ),
+                Some(false),
+            ),
+            (
+                row(
+                    vec![c2.clone(), c1.clone()],
+                    BinaryOperator::Eq,
+                    vec![constant(Some(5)), constant(Some(1))],
+                ),
+                None,
+            ),
+            (
+                row(
+                    vec![c2, c1],
+                    BinaryOperator::NotEq,
+                    vec![constant(Some(5)), constant(Some(2))],
+                ),
+                Some(true),
+            ),
+        ];
+
+        for (expr, result) in cases {
+            assert_eq!(
+                expr.eval(&tuple, &columns)?,
+                ValueRef::new(DataValue::Boolean(result))
+            );
+        }
+
+        Ok(())
     }
 }
@@ -0,0 +1,171 @@
+use crate::types::errors::TypeError;
+use crate::types::value::DataValue;
+use crate::types::LogicalType;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::HashMap;
+use std::fmt;
+use std::fmt::{Debug, Formatter};
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+pub type ScalarFunctionImpl =
+    Arc<dyn Fn(&[DataValue]) -> Result<DataValue, TypeError> + Send + Sync>;
+
+pub type ScalarFunctionRef = Arc<ScalarFunction>;
+
+/// The scalar functions registered by the embedder, keyed by their lower case name.
+pub type Functions = HashMap<String, ScalarFunctionRef>;
+
+/// The built-in functions over values of a common type, over strings, `now()` and
+/// `gen_random_uuid()`, they are evaluated without a registry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BuiltinKind {
+    Coalesce,
+    NullIf,
+    Greatest,
+    Least,
+    Lower,
+    Upper,
+    /// The current date and time, evaluated anew for every row.
+    Now,
+    /// A random (version 4) UUID in its text form, evaluated anew for every row.
+    GenRandomUuid,
+}
+
+impl BuiltinKind {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "coalesce" => Some(BuiltinKind::Coalesce),
+            "nullif" => Some(BuiltinKind::NullIf),
+            "greatest" => Some(BuiltinKind::Greatest),
+            "least" => Some(BuiltinKind::Least),
+            "lower" => Some(BuiltinKind::Lower),
+            "upper" => Some(BuiltinKind::Upper),
+            "now" => Some(BuiltinKind::Now),
+            "gen_random_uuid" => Some(BuiltinKind::GenRandomUuid),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            BuiltinKind::Coalesce => "coalesce",
+            BuiltinKind::NullIf => "nullif",
+            BuiltinKind::Greatest => "greatest",
+            BuiltinKind::Least => "least",
+            BuiltinKind::Lower => "lower",
+            BuiltinKind::Upper => "upper",
+            BuiltinKind::Now => "now",
+            BuiltinKind::GenRandomUuid => "gen_random_uuid",
+        }
+    }
+
+    /// Whether the result may change from one call to the next.
+    pub fn is_volatile(&self) -> bool {
+        matches!(self, BuiltinKind::Now | BuiltinKind::GenRandomUuid)
+    }
+}
+
+/// Whether a function returns the same result whenever it is called with the same arguments.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Volatility {
+    /// The result only depends on the arguments, so a call with constant arguments
+    /// is evaluated once while planning.
+    #[default]
+    Immutable,
+    /// The result may change from one call to the next, e.g. `random()` or `now()`.
+    Volatile,
+}
+
+/// The argument types a function accepts and the type it returns.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct FunctionSignature {
+    pub args: Vec<LogicalType>,
+    pub return_type: LogicalType,
+    pub volatility: Volatility,
+}
+
+impl FunctionSignature {
+    pub fn new(args: Vec<LogicalType>, return_type: LogicalType) -> Self {
+        FunctionSignature {
+            args,
+            return_type,
+            volatility: Volatility::Immutable,
+        }
+    }
+
+    pub fn with_volatility(mut self, volatility: Volatility) -> Self {
+        self.volatility = volatility;
+        self
+    }
+}
+
+/// A user-defined scalar function, the arguments are cast to the signature before the call.
+pub struct ScalarFunction {
+    pub name: String,
+    pub signature: FunctionSignature,
+    inner: ScalarFunctionImpl,
+}
+
+impl ScalarFunction {
+    pub fn new(name: String, signature: FunctionSignature, inner: ScalarFunctionImpl) -> Self {
+        ScalarFunction {
+            name,
+            signature,
+            inner,
+        }
+    }
+
+    pub fn call(&self, args: &[DataValue]) -> Result<DataValue, TypeError> {
+        let value = (self.inner)(args)?;
+
+        if value.logical_type() == self.signature.return_type {
+            Ok(value)
+        } else {
+            value.cast(&self.signature.return_type)
+        }
+    }
+}
+
+impl Debug for ScalarFunction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ScalarFunction")
+            .field("name", &self.name)
+            .field("signature", &self.signature)
+            .finish()
+    }
+}
+
+impl PartialEq for ScalarFunction {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name && self.signature == other.signature
+    }
+}
+
+impl Eq for ScalarFunction {}
+
+impl Hash for ScalarFunction {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.name.hash(state);
+        self.signature.hash(state);
+    }
+}
+
+/// Only the name and the signature are serialized, the implementation lives in the registry.
+impl Serialize for ScalarFunction {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        (&self.name, &self.signature).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ScalarFunction {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (name, _): (String, FunctionSignature) = Deserialize::deserialize(deserializer)?;
+
+        Err(D::Error::custom(format!(
+            "function {} can not be deserialized without its implementation",
+            name
+        )))
+    }
+}
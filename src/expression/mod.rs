@@ -7,14 +7,18 @@ use std::sync::Arc;
 use sqlparser::ast::{BinaryOperator as SqlBinaryOperator, UnaryOperator as SqlUnaryOperator};
 
 use self::agg::AggKind;
+use self::function::{BuiltinKind, ScalarFunctionRef, Volatility};
+use self::window::WindowSpec;
 use crate::catalog::{ColumnCatalog, ColumnDesc, ColumnRef};
 use crate::types::value::ValueRef;
 use crate::types::LogicalType;
 
 pub mod agg;
 mod evaluator;
+pub mod function;
 pub mod simplify;
 pub mod value_compute;
+pub mod window;
 
 /// ScalarExpression represnet all scalar expression in SQL.
 /// SELECT a+1, b FROM t1.
@@ -58,6 +62,25 @@ pub enum ScalarExpression {
         expr: Box<ScalarExpression>,
         args: Vec<ScalarExpression>,
     },
+    Function {
+        function: ScalarFunctionRef,
+        args: Vec<ScalarExpression>,
+    },
+    Builtin {
+        kind: BuiltinKind,
+        args: Vec<ScalarExpression>,
+        ty: LogicalType,
+    },
+    /// A row value like `(a, b)`, it can only be compared with another row of the same length.
+    Tuple(Vec<ScalarExpression>),
+    /// An `AggCall` computed over the window of each row, e.g. `sum(a) OVER (ORDER BY b)`.
+    ///
+    /// Like the agg calls, it is computed by its own operator and read from the tuples
+    /// by its name.
+    WindowCall {
+        agg: Box<ScalarExpression>,
+        spec: Box<WindowSpec>,
+    },
 }
 
 impl ScalarExpression {
@@ -80,11 +103,47 @@ impl ScalarExpression {
                 right_expr,
                 ..
             } => left_expr.has_count_star() || right_expr.has_count_star(),
-            ScalarExpression::AggCall { args, .. } => args.iter().any(Self::has_count_star),
+            ScalarExpression::AggCall { args, .. }
+            | ScalarExpression::Function { args, .. }
+            | ScalarExpression::Builtin { args, .. }
+            | ScalarExpression::Tuple(args) => args.iter().any(Self::has_count_star),
             _ => false,
         }
     }
 
+    /// Whether the expression evaluates to the same value for the same tuple, i.e. it calls
+    /// no volatile function.
+    pub fn is_deterministic(&self) -> bool {
+        match self {
+            ScalarExpression::Constant(_) | ScalarExpression::ColumnRef(_) => true,
+            ScalarExpression::Alias { expr, .. }
+            | ScalarExpression::TypeCast { expr, .. }
+            | ScalarExpression::IsNull { expr, .. }
+            | ScalarExpression::Unary { expr, .. } => expr.is_deterministic(),
+            ScalarExpression::Binary {
+                left_expr,
+                right_expr,
+                ..
+            } => left_expr.is_deterministic() && right_expr.is_deterministic(),
+            ScalarExpression::In { expr, args, .. } => {
+                expr.is_deterministic() && args.iter().all(ScalarExpression::is_deterministic)
+            }
+            ScalarExpression::Function { function, args } => {
+                function.signature.volatility == Volatility::Immutable
+                    && args.iter().all(ScalarExpression::is_deterministic)
+            }
+            ScalarExpression::Builtin { kind, args, .. } => {
+                !kind.is_volatile() && args.iter().all(ScalarExpression::is_deterministic)
+            }
+            ScalarExpression::AggCall { args, .. } | ScalarExpression::Tuple(args) => {
+                args.iter().all(ScalarExpression::is_deterministic)
+            }
+            ScalarExpression::WindowCall { .. } => {
+                self.window_exprs().all(ScalarExpression::is_deterministic)
+            }
+        }
+    }
+
     pub fn nullable(&self) -> bool {
         match self {
             ScalarExpression::Constant(_) => false,
@@ -102,6 +161,13 @@ impl ScalarExpression {
                 args.iter().all(ScalarExpression::nullable) && expr.nullable()
             }
             ScalarExpression::AggCall { args, .. } => args.iter().all(ScalarExpression::nullable),
+            ScalarExpression::Function { .. } => true,
+            ScalarExpression::Builtin { kind, args, .. } => {
+                kind == &BuiltinKind::NullIf || args.iter().all(ScalarExpression::nullable)
+            }
+            ScalarExpression::Tuple(args) => args.iter().all(ScalarExpression::nullable),
+            // the frame of a row may be empty
+            ScalarExpression::WindowCall { .. } => true,
         }
     }
 
@@ -121,8 +187,14 @@ impl ScalarExpression {
             Self::AggCall {
                 ty: return_type, ..
             } => *return_type,
+            Self::Function { function, .. } => function.signature.return_type,
+            Self::Builtin {
+                ty: return_type, ..
+            } => *return_type,
             Self::IsNull { .. } | Self::In { .. } => LogicalType::Boolean,
             Self::Alias { expr, .. } => expr.return_type(),
+            Self::Tuple(_) => LogicalType::Invalid,
+            Self::WindowCall { agg, .. } => agg.return_type(),
         }
     }
 
@@ -156,7 +228,10 @@ impl ScalarExpression {
                     columns_collect(left_expr, vec, only_column_ref);
                     columns_collect(right_expr, vec, only_column_ref);
                 }
-                ScalarExpression::AggCall { args, .. } => {
+                ScalarExpression::AggCall { args, .. }
+                | ScalarExpression::Function { args, .. }
+                | ScalarExpression::Builtin { args, .. }
+                | ScalarExpression::Tuple(args) => {
                     for expr in args {
                         columns_collect(expr, vec, only_column_ref)
                     }
@@ -167,6 +242,11 @@ impl ScalarExpression {
                         columns_collect(arg, vec, only_column_ref)
                     }
                 }
+                ScalarExpression::WindowCall { .. } => {
+                    for expr in expr.window_exprs() {
+                        columns_collect(expr, vec, only_column_ref)
+                    }
+                }
                 _ => (),
             }
         }
@@ -194,9 +274,61 @@ impl ScalarExpression {
             ScalarExpression::In { expr, args, .. } => {
                 expr.has_agg_call() || args.iter().any(|arg| arg.has_agg_call())
             }
+            ScalarExpression::Function { args, .. }
+            | ScalarExpression::Builtin { args, .. }
+            | ScalarExpression::Tuple(args) => args.iter().any(|arg| arg.has_agg_call()),
+            // the window is computed over the aggregated tuples, e.g. `sum(count(*)) OVER ()`
+            ScalarExpression::WindowCall { .. } => {
+                self.window_exprs().any(|expr| expr.has_agg_call())
+            }
+        }
+    }
+
+    /// Whether a registered function is called, such an expr cannot be kept in the catalog
+    /// since the registered functions cannot be restored from the storage.
+    pub fn has_function_call(&self) -> bool {
+        match self {
+            ScalarExpression::Function { .. } => true,
+            ScalarExpression::Constant(_) | ScalarExpression::ColumnRef(_) => false,
+            ScalarExpression::Alias { expr, .. }
+            | ScalarExpression::TypeCast { expr, .. }
+            | ScalarExpression::IsNull { expr, .. }
+            | ScalarExpression::Unary { expr, .. } => expr.has_function_call(),
+            ScalarExpression::Binary {
+                left_expr,
+                right_expr,
+                ..
+            } => left_expr.has_function_call() || right_expr.has_function_call(),
+            ScalarExpression::In { expr, args, .. } => {
+                expr.has_function_call() || args.iter().any(|arg| arg.has_function_call())
+            }
+            ScalarExpression::AggCall { args, .. }
+            | ScalarExpression::Builtin { args, .. }
+            | ScalarExpression::Tuple(args) => args.iter().any(|arg| arg.has_function_call()),
+            ScalarExpression::WindowCall { agg, .. } => {
+                agg.has_function_call() || self.window_exprs().any(|expr| expr.has_function_call())
+            }
         }
     }
 
+    /// The exprs a `WindowCall` is computed from: the args of its aggregate, then those of
+    /// the `PARTITION BY` and the `ORDER BY`. Nothing for the other exprs.
+    pub fn window_exprs(&self) -> impl Iterator<Item = &ScalarExpression> {
+        let (args, spec) = match self {
+            ScalarExpression::WindowCall { agg, spec } => match agg.as_ref() {
+                ScalarExpression::AggCall { args, .. } => (args.as_slice(), Some(spec)),
+                _ => (&[][..], Some(spec)),
+            },
+            _ => (&[][..], None),
+        };
+
+        args.iter().chain(spec.into_iter().flat_map(|spec| {
+            spec.partition_by
+                .iter()
+                .chain(spec.order_by.iter().map(|field| &field.expr))
+        }))
+    }
+
     pub fn output_columns(&self) -> ColumnRef {
         match self {
             ScalarExpression::ColumnRef(col) => col.clone(),
@@ -309,6 +441,51 @@ impl ScalarExpression {
                 ColumnDesc::new(*ty, false, false, None),
                 Some(self.clone()),
             )),
+            ScalarExpression::Function { function, args } => {
+                let args_str = args
+                    .iter()
+                    .map(|expr| expr.output_columns().name().to_string())
+                    .join(", ");
+
+                Arc::new(ColumnCatalog::new(
+                    format!("{}({})", function.name, args_str),
+                    true,
+                    ColumnDesc::new(function.signature.return_type, false, false, None),
+                    Some(self.clone()),
+                ))
+            }
+            ScalarExpression::Builtin { kind, args, ty } => {
+                let args_str = args
+                    .iter()
+                    .map(|expr| expr.output_columns().name().to_string())
+                    .join(", ");
+
+                Arc::new(ColumnCatalog::new(
+                    format!("{}({})", kind.name(), args_str),
+                    true,
+                    ColumnDesc::new(*ty, false, false, None),
+                    Some(self.clone()),
+                ))
+            }
+            ScalarExpression::Tuple(args) => {
+                let args_str = args
+                    .iter()
+                    .map(|expr| expr.output_columns().name().to_string())
+                    .join(", ");
+
+                Arc::new(ColumnCatalog::new(
+                    format!("({})", args_str),
+                    true,
+                    ColumnDesc::new(LogicalType::Invalid, false, false, None),
+                    Some(self.clone()),
+                ))
+            }
+            ScalarExpression::WindowCall { agg, spec } => Arc::new(ColumnCatalog::new(
+                format!("{} OVER ({})", agg.output_columns().name(), spec),
+                true,
+                ColumnDesc::new(agg.return_type(), false, false, None),
+                Some(self.clone()),
+            )),
         }
     }
 }
@@ -341,6 +518,12 @@ pub enum BinaryOperator {
     Modulo,
     StringConcat,
 
+    BitwiseAnd,
+    BitwiseOr,
+    BitwiseXor,
+    ShiftLeft,
+    ShiftRight,
+
     Gt,
     Lt,
     GtEq,
@@ -356,6 +539,12 @@ pub enum BinaryOperator {
     Xor,
 }
 
+impl fmt::Display for ScalarExpression {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.output_columns().name())
+    }
+}
+
 impl fmt::Display for BinaryOperator {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         match self {
@@ -365,6 +554,11 @@ impl fmt::Display for BinaryOperator {
             BinaryOperator::Divide => write!(f, "/"),
             BinaryOperator::Modulo => write!(f, "mod"),
             BinaryOperator::StringConcat => write!(f, "&"),
+            BinaryOperator::BitwiseAnd => write!(f, "&"),
+            BinaryOperator::BitwiseOr => write!(f, "|"),
+            BinaryOperator::BitwiseXor => write!(f, "^"),
+            BinaryOperator::ShiftLeft => write!(f, "<<"),
+            BinaryOperator::ShiftRight => write!(f, ">>"),
             BinaryOperator::Gt => write!(f, ">"),
             BinaryOperator::Lt => write!(f, "<"),
             BinaryOperator::GtEq => write!(f, ">="),
@@ -410,6 +604,15 @@ impl From<SqlBinaryOperator> for BinaryOperator {
             SqlBinaryOperator::And => BinaryOperator::And,
             SqlBinaryOperator::Or => BinaryOperator::Or,
             SqlBinaryOperator::Xor => BinaryOperator::Xor,
+            SqlBinaryOperator::BitwiseAnd => BinaryOperator::BitwiseAnd,
+            SqlBinaryOperator::BitwiseOr => BinaryOperator::BitwiseOr,
+            // the PostgreSQL dialect parses `^` as exponentiation and `#` as xor,
+            // both are taken as the bitwise xor
+            SqlBinaryOperator::BitwiseXor
+            | SqlBinaryOperator::PGExp
+            | SqlBinaryOperator::PGBitwiseXor => BinaryOperator::BitwiseXor,
+            SqlBinaryOperator::PGBitwiseShiftLeft => BinaryOperator::ShiftLeft,
+            SqlBinaryOperator::PGBitwiseShiftRight => BinaryOperator::ShiftRight,
             _ => unimplemented!("not support!"),
         }
     }
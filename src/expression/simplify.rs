@@ -1,4 +1,4 @@
-use crate::catalog::ColumnRef;
+use crate::catalog::{ColumnCatalog, ColumnRef};
 use crate::expression::value_compute::{binary_op, unary_op};
 use crate::expression::{BinaryOperator, ScalarExpression, UnaryOperator};
 use crate::types::errors::TypeError;
@@ -8,9 +8,14 @@ use ahash::RandomState;
 use itertools::Itertools;
 use std::cmp::Ordering;
 use std::collections::{Bound, HashSet};
+use std::fmt;
+use std::fmt::Formatter;
 use std::mem;
 use std::sync::Arc;
 
+/// The id the expression of an index is bounded under by `convert_expr_binary`.
+const INDEX_EXPR_COLUMN_ID: ColumnId = ColumnId::MAX;
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum ConstantBinary {
     Scope {
@@ -24,6 +29,13 @@ pub enum ConstantBinary {
     And(Vec<ConstantBinary>),
     // ConstantBinary in Or can only be Scope\Eq\NotEq\And
     Or(Vec<ConstantBinary>),
+    /// A bound of a composite index: its leading columns are equal to `values` and the
+    /// next column is bounded by the `Scope` or `Eq`, e.g. `a = 1 AND b > 2` on `(a, b)`
+    /// is the `Scope` of `b` after the prefix `[1]`.
+    Prefix {
+        values: Vec<ValueRef>,
+        binary: Box<ConstantBinary>,
+    },
 }
 
 impl ConstantBinary {
@@ -207,7 +219,7 @@ impl ConstantBinary {
             ConstantBinary::Scope { .. } => 3,
             ConstantBinary::NotEq(_) => 2,
             ConstantBinary::Eq(_) => 1,
-            ConstantBinary::And(_) | ConstantBinary::Or(_) => 0,
+            ConstantBinary::And(_) | ConstantBinary::Or(_) | ConstantBinary::Prefix { .. } => 0,
         };
 
         // Aggregate various ranges to get the minimum range
@@ -237,7 +249,7 @@ impl ConstantBinary {
                 ConstantBinary::NotEq(val) => {
                     let _ = eq_set.remove(val);
                 }
-                ConstantBinary::Or(_) | ConstantBinary::And(_) => {
+                ConstantBinary::Or(_) | ConstantBinary::And(_) | ConstantBinary::Prefix { .. } => {
                     return Err(TypeError::InvalidType)
                 }
             }
@@ -375,6 +387,32 @@ impl ConstantBinary {
     }
 }
 
+impl fmt::Display for ConstantBinary {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            ConstantBinary::Scope { min, max } => {
+                match min {
+                    Bound::Included(val) => write!(f, "[{}", val)?,
+                    Bound::Excluded(val) => write!(f, "({}", val)?,
+                    Bound::Unbounded => write!(f, "(-inf")?,
+                }
+                match max {
+                    Bound::Included(val) => write!(f, ", {}]", val),
+                    Bound::Excluded(val) => write!(f, ", {})", val),
+                    Bound::Unbounded => write!(f, ", +inf)"),
+                }
+            }
+            ConstantBinary::Eq(val) => write!(f, "= {}", val),
+            ConstantBinary::NotEq(val) => write!(f, "!= {}", val),
+            ConstantBinary::And(binaries) => write!(f, "{}", binaries.iter().join(" AND ")),
+            ConstantBinary::Or(binaries) => write!(f, "{}", binaries.iter().join(" OR ")),
+            ConstantBinary::Prefix { values, binary } => {
+                write!(f, "[{}] {}", values.iter().join(", "), binary)
+            }
+        }
+    }
+}
+
 #[derive(Debug)]
 enum Replace {
     Binary(ReplaceBinary),
@@ -398,6 +436,155 @@ struct ReplaceUnary {
 }
 
 impl ScalarExpression {
+    /// Like `convert_binary`, but bounds the value of the expression of an index,
+    /// e.g. `lower(c1) = 'a'` by the index on `lower(c1)`.
+    ///
+    /// The occurrences of the expression are taken as a column no table has, which is
+    /// then bounded the same way as a column.
+    pub fn convert_expr_binary(
+        &self,
+        index_expr: &ScalarExpression,
+    ) -> Result<Option<ConstantBinary>, TypeError> {
+        let mut column = ColumnCatalog::new_dummy(index_expr.to_string());
+        column.summary.id = Some(INDEX_EXPR_COLUMN_ID);
+
+        self.replace_index_expr(index_expr, &Arc::new(column))
+            .convert_binary(&INDEX_EXPR_COLUMN_ID)
+    }
+
+    fn replace_index_expr(
+        &self,
+        index_expr: &ScalarExpression,
+        column: &ColumnRef,
+    ) -> ScalarExpression {
+        if self.is_same_expr(index_expr) {
+            return ScalarExpression::ColumnRef(column.clone());
+        }
+        let replace =
+            |expr: &ScalarExpression| Box::new(expr.replace_index_expr(index_expr, column));
+
+        match self {
+            ScalarExpression::Alias { expr, alias } => ScalarExpression::Alias {
+                expr: replace(expr),
+                alias: alias.clone(),
+            },
+            ScalarExpression::TypeCast { expr, ty } => ScalarExpression::TypeCast {
+                expr: replace(expr),
+                ty: *ty,
+            },
+            ScalarExpression::IsNull { negated, expr } => ScalarExpression::IsNull {
+                negated: *negated,
+                expr: replace(expr),
+            },
+            ScalarExpression::Unary { op, expr, ty } => ScalarExpression::Unary {
+                op: *op,
+                expr: replace(expr),
+                ty: *ty,
+            },
+            ScalarExpression::Binary {
+                op,
+                left_expr,
+                right_expr,
+                ty,
+            } => ScalarExpression::Binary {
+                op: *op,
+                left_expr: replace(left_expr),
+                right_expr: replace(right_expr),
+                ty: *ty,
+            },
+            ScalarExpression::Tuple(args) => ScalarExpression::Tuple(
+                args.iter()
+                    .map(|arg| arg.replace_index_expr(index_expr, column))
+                    .collect(),
+            ),
+            expr => expr.clone(),
+        }
+    }
+
+    /// Whether both are the same expression, the columns are told apart by their ids
+    /// since the columns of a query carry the alias of their table.
+    fn is_same_expr(&self, other: &ScalarExpression) -> bool {
+        let all_same = |left: &[ScalarExpression], right: &[ScalarExpression]| {
+            left.len() == right.len()
+                && left
+                    .iter()
+                    .zip(right.iter())
+                    .all(|(left, right)| left.is_same_expr(right))
+        };
+
+        match (self.unpack_alias(), other.unpack_alias()) {
+            (ScalarExpression::ColumnRef(left), ScalarExpression::ColumnRef(right)) => {
+                left.id().is_some() && left.id() == right.id()
+            }
+            (ScalarExpression::Constant(left), ScalarExpression::Constant(right)) => left == right,
+            (
+                ScalarExpression::TypeCast { expr, ty },
+                ScalarExpression::TypeCast {
+                    expr: other_expr,
+                    ty: other_ty,
+                },
+            ) => ty == other_ty && expr.is_same_expr(other_expr),
+            (
+                ScalarExpression::IsNull { negated, expr },
+                ScalarExpression::IsNull {
+                    negated: other_negated,
+                    expr: other_expr,
+                },
+            ) => negated == other_negated && expr.is_same_expr(other_expr),
+            (
+                ScalarExpression::Unary { op, expr, .. },
+                ScalarExpression::Unary {
+                    op: other_op,
+                    expr: other_expr,
+                    ..
+                },
+            ) => op == other_op && expr.is_same_expr(other_expr),
+            (
+                ScalarExpression::Binary {
+                    op,
+                    left_expr,
+                    right_expr,
+                    ..
+                },
+                ScalarExpression::Binary {
+                    op: other_op,
+                    left_expr: other_left,
+                    right_expr: other_right,
+                    ..
+                },
+            ) => {
+                op == other_op
+                    && left_expr.is_same_expr(other_left)
+                    && right_expr.is_same_expr(other_right)
+            }
+            (
+                ScalarExpression::In {
+                    negated,
+                    expr,
+                    args,
+                },
+                ScalarExpression::In {
+                    negated: other_negated,
+                    expr: other_expr,
+                    args: other_args,
+                },
+            ) => {
+                negated == other_negated
+                    && expr.is_same_expr(other_expr)
+                    && all_same(args, other_args)
+            }
+            (
+                ScalarExpression::Builtin { kind, args, .. },
+                ScalarExpression::Builtin {
+                    kind: other_kind,
+                    args: other_args,
+                    ..
+                },
+            ) => kind == other_kind && all_same(args, other_args),
+            _ => false,
+        }
+    }
+
     pub fn exist_column(&self, col_id: &ColumnId) -> bool {
         match self {
             ScalarExpression::ColumnRef(col) => col.id() == Some(*col_id),
@@ -410,6 +597,46 @@ impl ScalarExpression {
                 right_expr,
                 ..
             } => left_expr.exist_column(col_id) || right_expr.exist_column(col_id),
+            ScalarExpression::Tuple(args) => args.iter().any(|arg| arg.exist_column(col_id)),
+            _ => false,
+        }
+    }
+
+    /// Whether the predicate only compares the column with constants, then the ranges of
+    /// `convert_binary` hold exactly the values it accepts and nothing more.
+    pub(crate) fn is_bounded_by(&self, col_id: &ColumnId) -> bool {
+        match self {
+            ScalarExpression::Binary {
+                op: BinaryOperator::And | BinaryOperator::Or,
+                left_expr,
+                right_expr,
+                ..
+            } => left_expr.is_bounded_by(col_id) && right_expr.is_bounded_by(col_id),
+            ScalarExpression::Binary {
+                op:
+                    BinaryOperator::Gt
+                    | BinaryOperator::Lt
+                    | BinaryOperator::GtEq
+                    | BinaryOperator::LtEq
+                    | BinaryOperator::Eq
+                    | BinaryOperator::NotEq,
+                left_expr,
+                right_expr,
+                ..
+            } => match (left_expr.as_ref(), right_expr.as_ref()) {
+                (ScalarExpression::ColumnRef(col), ScalarExpression::Constant(val))
+                | (ScalarExpression::Constant(val), ScalarExpression::ColumnRef(col)) => {
+                    col.id() == Some(*col_id) && !val.is_null()
+                }
+                _ => false,
+            },
+            ScalarExpression::IsNull {
+                negated: true,
+                expr,
+            } => {
+                matches!(expr.as_ref(), ScalarExpression::ColumnRef(col) if col.id() == Some(*col_id))
+            }
+            ScalarExpression::Alias { expr, .. } => expr.is_bounded_by(col_id),
             _ => false,
         }
     }
@@ -420,16 +647,16 @@ impl ScalarExpression {
             ScalarExpression::Alias { expr, .. } => expr.unpack_val(),
             ScalarExpression::TypeCast { expr, ty, .. } => expr
                 .unpack_val()
-                .and_then(|val| DataValue::clone(&val).cast(ty).ok().map(Arc::new)),
+                .and_then(|val| DataValue::clone(&val).cast(ty).ok().map(ValueRef::new)),
             ScalarExpression::IsNull { expr, .. } => {
                 let is_null = expr.unpack_val().map(|val| val.is_null());
 
-                Some(Arc::new(DataValue::Boolean(is_null)))
+                Some(ValueRef::new(DataValue::Boolean(is_null)))
             }
             ScalarExpression::Unary { expr, op, .. } => {
                 let val = expr.unpack_val()?;
 
-                unary_op(&val, op).ok().map(Arc::new)
+                unary_op(&val, op).ok().map(ValueRef::new)
             }
             ScalarExpression::Binary {
                 left_expr,
@@ -440,7 +667,7 @@ impl ScalarExpression {
                 let left = left_expr.unpack_val()?;
                 let right = right_expr.unpack_val()?;
 
-                binary_op(&left, &right, op).ok().map(Arc::new)
+                binary_op(&left, &right, op).ok().map(ValueRef::new)
             }
             _ => None,
         }
@@ -472,14 +699,19 @@ impl ScalarExpression {
         self._simplify(&mut Vec::new())
     }
 
+    /// Folds the operations on constants, as in the evaluation the right side of an `AND`
+    /// or `OR` is skipped once the left one decides. An operation failing, e.g. `1 / 0`, is
+    /// kept as it is, so that it fails only if it is evaluated.
     pub fn constant_calculation(&mut self) -> Result<(), TypeError> {
         match self {
             ScalarExpression::Unary { expr, op, .. } => {
                 expr.constant_calculation()?;
 
                 if let ScalarExpression::Constant(unary_val) = expr.as_ref() {
-                    let value = unary_op(unary_val, op)?;
-                    let _ = mem::replace(self, ScalarExpression::Constant(Arc::new(value)));
+                    if let Ok(value) = unary_op(unary_val, op) {
+                        let _ =
+                            mem::replace(self, ScalarExpression::Constant(ValueRef::new(value)));
+                    }
                 }
             }
             ScalarExpression::Binary {
@@ -489,6 +721,18 @@ impl ScalarExpression {
                 ..
             } => {
                 left_expr.constant_calculation()?;
+
+                if let ScalarExpression::Constant(left_val) = left_expr.as_ref() {
+                    if let (BinaryOperator::And, DataValue::Boolean(Some(false)))
+                    | (BinaryOperator::Or, DataValue::Boolean(Some(true))) =
+                        (&op, left_val.as_ref())
+                    {
+                        let left_val = left_val.clone();
+                        let _ = mem::replace(self, ScalarExpression::Constant(left_val));
+
+                        return Ok(());
+                    }
+                }
                 right_expr.constant_calculation()?;
 
                 if let (
@@ -496,14 +740,29 @@ impl ScalarExpression {
                     ScalarExpression::Constant(right_val),
                 ) = (left_expr.as_ref(), right_expr.as_ref())
                 {
-                    let value = binary_op(left_val, right_val, op)?;
-                    let _ = mem::replace(self, ScalarExpression::Constant(Arc::new(value)));
+                    if let Ok(value) = binary_op(left_val, right_val, op) {
+                        let _ =
+                            mem::replace(self, ScalarExpression::Constant(ValueRef::new(value)));
+                    }
                 }
             }
             ScalarExpression::Alias { expr, .. } => expr.constant_calculation()?,
             ScalarExpression::TypeCast { expr, .. } => expr.constant_calculation()?,
             ScalarExpression::IsNull { expr, .. } => expr.constant_calculation()?,
-            ScalarExpression::AggCall { args, .. } => {
+            ScalarExpression::Function { args, .. } => {
+                for expr in args {
+                    expr.constant_calculation()?;
+                }
+                if self.is_deterministic() {
+                    if let Some(value) = self.function_on_constants()? {
+                        let _ =
+                            mem::replace(self, ScalarExpression::Constant(ValueRef::new(value)));
+                    }
+                }
+            }
+            ScalarExpression::AggCall { args, .. }
+            | ScalarExpression::Builtin { args, .. }
+            | ScalarExpression::Tuple(args) => {
                 for expr in args {
                     expr.constant_calculation()?;
                 }
@@ -514,6 +773,23 @@ impl ScalarExpression {
         Ok(())
     }
 
+    /// The result of a function call whose arguments are all constants.
+    fn function_on_constants(&self) -> Result<Option<DataValue>, TypeError> {
+        let ScalarExpression::Function { function, args } = self else {
+            return Ok(None);
+        };
+        let mut values = Vec::with_capacity(args.len());
+
+        for (arg, ty) in args.iter().zip(function.signature.args.iter()) {
+            match arg {
+                ScalarExpression::Constant(value) => values.push(DataValue::clone(value).cast(ty)?),
+                _ => return Ok(None),
+            }
+        }
+
+        function.call(&values).map(Some)
+    }
+
     // Tips: Indirect expressions like `ScalarExpression:：Alias` will be lost
     fn _simplify(&mut self, replaces: &mut Vec<Replace>) -> Result<(), TypeError> {
         match self {
@@ -589,7 +865,7 @@ impl ScalarExpression {
                 if let Some(val) = expr.unpack_val() {
                     let _ = mem::replace(
                         self,
-                        ScalarExpression::Constant(Arc::new(DataValue::Boolean(Some(
+                        ScalarExpression::Constant(ValueRef::new(DataValue::Boolean(Some(
                             val.is_null(),
                         )))),
                     );
@@ -597,7 +873,7 @@ impl ScalarExpression {
             }
             ScalarExpression::Unary { expr, op, ty } => {
                 if let Some(val) = expr.unpack_val() {
-                    let new_expr = ScalarExpression::Constant(Arc::new(unary_op(&val, op)?));
+                    let new_expr = ScalarExpression::Constant(ValueRef::new(unary_op(&val, op)?));
                     let _ = mem::replace(self, new_expr);
                 } else {
                     replaces.push(Replace::Unary(ReplaceUnary {
@@ -706,6 +982,17 @@ impl ScalarExpression {
             ty: fix_ty,
             is_column_left,
         } = replace_binary;
+        // `c1 * 0 > 1` cannot be moved to `c1 > 1 / 0`
+        if fix_op == BinaryOperator::Multiply
+            && val_expr.unpack_val().map_or(false, |val| {
+                matches!(
+                    binary_op(&val, &DataValue::Int32(Some(0)), &BinaryOperator::Eq),
+                    Ok(DataValue::Boolean(Some(true)))
+                )
+            })
+        {
+            return;
+        }
         let op_flip = |op: BinaryOperator| match op {
             BinaryOperator::Plus => BinaryOperator::Minus,
             BinaryOperator::Minus => BinaryOperator::Plus,
@@ -745,6 +1032,61 @@ impl ScalarExpression {
     /// The And and Or of ConstantBinary are concerned with the data range that needs to be aggregated.
     /// - `ConstantBinary::And`: Aggregate the minimum range of all conditions in and
     /// - `ConstantBinary::Or`: Rearrange and sort the range of each OR data
+    /// The bounds of the composite index on `col_ids` by the leading columns, each column
+    /// bounded by a single `Eq` is followed by the bounds of the next column.
+    /// e.g. `a = 1 AND b > 2` seeks `(1, 2)..` of the index on `(a, b)`, while `b > 2`
+    /// bounds no leading column and gives nothing.
+    pub fn convert_prefix_binaries(
+        &self,
+        col_ids: &[ColumnId],
+    ) -> Result<Vec<ConstantBinary>, TypeError> {
+        let mut values = Vec::new();
+        let mut binaries = Vec::new();
+
+        for (i, col_id) in col_ids.iter().enumerate() {
+            if i > 0 && !matches!(binaries.as_slice(), [ConstantBinary::Eq(_)]) {
+                break;
+            }
+            let col_binaries = match self.convert_binary(col_id)? {
+                Some(mut binary) => {
+                    binary.scope_aggregation()?;
+                    binary.rearrange()?
+                }
+                None => vec![],
+            };
+            let is_seekable = col_binaries.iter().all(|binary| {
+                matches!(binary, ConstantBinary::Scope { .. } | ConstantBinary::Eq(_))
+            });
+            if col_binaries.is_empty() || !is_seekable {
+                break;
+            }
+            if let Some(ConstantBinary::Eq(val)) = binaries.pop() {
+                values.push(val);
+            }
+            binaries = col_binaries;
+        }
+        // bounding the leading column by `IS NOT NULL` alone finds almost every tuple
+        if values.is_empty()
+            && matches!(
+                binaries.as_slice(),
+                [ConstantBinary::Scope {
+                    min: Bound::Unbounded,
+                    max: Bound::Unbounded
+                }]
+            )
+        {
+            return Ok(vec![]);
+        }
+
+        Ok(binaries
+            .into_iter()
+            .map(|binary| ConstantBinary::Prefix {
+                values: values.clone(),
+                binary: Box::new(binary),
+            })
+            .collect())
+    }
+
     pub fn convert_binary(&self, col_id: &ColumnId) -> Result<Option<ConstantBinary>, TypeError> {
         match self {
             ScalarExpression::Binary {
@@ -753,10 +1095,25 @@ impl ScalarExpression {
                 op,
                 ..
             } => {
-                match (
-                    left_expr.convert_binary(col_id)?,
-                    right_expr.convert_binary(col_id)?,
+                if let (ScalarExpression::Tuple(left), ScalarExpression::Tuple(right)) =
+                    (left_expr.as_ref(), right_expr.as_ref())
+                {
+                    return Ok(Self::convert_row_binary(col_id, *op, left, right));
+                }
+                // only the operands of a logical operator are predicates themselves, a boolean
+                // column compared by `b = false` is not `b` alone
+                let (left_binary, right_binary) = if matches!(
+                    op,
+                    BinaryOperator::And | BinaryOperator::Or | BinaryOperator::Xor
                 ) {
+                    (
+                        left_expr.convert_binary(col_id)?,
+                        right_expr.convert_binary(col_id)?,
+                    )
+                } else {
+                    (None, None)
+                };
+                match (left_binary, right_binary) {
                     (Some(left_binary), Some(right_binary)) => match (left_binary, right_binary) {
                         (ConstantBinary::And(mut left), ConstantBinary::And(mut right)) => match op
                         {
@@ -791,6 +1148,15 @@ impl ScalarExpression {
 
                             Ok(Some(ConstantBinary::Or(left)))
                         }
+                        (ConstantBinary::And(binaries), binary)
+                        | (binary, ConstantBinary::And(binaries))
+                            if matches!(op, BinaryOperator::Or) =>
+                        {
+                            Ok(Some(ConstantBinary::Or(vec![
+                                ConstantBinary::And(binaries),
+                                binary,
+                            ])))
+                        }
                         (ConstantBinary::And(mut binaries), binary)
                         | (binary, ConstantBinary::And(mut binaries)) => {
                             binaries.push(binary);
@@ -830,12 +1196,71 @@ impl ScalarExpression {
             }
             ScalarExpression::Alias { expr, .. } => expr.convert_binary(col_id),
             ScalarExpression::TypeCast { expr, .. } => expr.convert_binary(col_id),
-            ScalarExpression::IsNull { expr, .. } => expr.convert_binary(col_id),
+            // the indexes have no entries of nulls, so `IS NOT NULL` is the whole of them
+            // and `IS NULL` is left to the filter
+            ScalarExpression::IsNull {
+                negated: true,
+                expr,
+            } if matches!(expr.as_ref(), ScalarExpression::ColumnRef(col) if col.id() == Some(*col_id)) => {
+                Ok(Some(ConstantBinary::Scope {
+                    min: Bound::Unbounded,
+                    max: Bound::Unbounded,
+                }))
+            }
+            ScalarExpression::IsNull { .. } => Ok(None),
+            // a boolean column alone is `b = true` and `NOT b` is `b = false`, the other
+            // negations are left to the filter
+            ScalarExpression::ColumnRef(_) if self.is_boolean_column(col_id) => Ok(Some(
+                ConstantBinary::Eq(ValueRef::new(DataValue::Boolean(Some(true)))),
+            )),
+            ScalarExpression::Unary {
+                op: UnaryOperator::Not,
+                expr,
+                ..
+            } => Ok(expr
+                .is_boolean_column(col_id)
+                .then(|| ConstantBinary::Eq(ValueRef::new(DataValue::Boolean(Some(false)))))),
             ScalarExpression::Unary { expr, .. } => expr.convert_binary(col_id),
             _ => Ok(None),
         }
     }
 
+    fn is_boolean_column(&self, col_id: &ColumnId) -> bool {
+        matches!(
+            self,
+            ScalarExpression::ColumnRef(col)
+                if col.id() == Some(*col_id) && col.datatype() == &LogicalType::Boolean
+        )
+    }
+
+    /// Only the leading elements of a row comparison can bound a column,
+    /// e.g. `(c1, c2) > (1, 2)` needs `c1 >= 1` and the filter checks the rest.
+    fn convert_row_binary(
+        col_id: &ColumnId,
+        mut op: BinaryOperator,
+        left: &[ScalarExpression],
+        right: &[ScalarExpression],
+    ) -> Option<ConstantBinary> {
+        if left.len() > 1 {
+            op = match op {
+                BinaryOperator::Gt => BinaryOperator::GtEq,
+                BinaryOperator::Lt => BinaryOperator::LtEq,
+                BinaryOperator::NotEq => return None,
+                source_op => source_op,
+            };
+        }
+        let (left_expr, right_expr) = (left.first()?, right.first()?);
+
+        if let (Some(col), Some(val)) = (left_expr.unpack_col(false), right_expr.unpack_val()) {
+            return Self::new_binary(col_id, op, col, val, false);
+        }
+        if let (Some(val), Some(col)) = (left_expr.unpack_val(), right_expr.unpack_col(false)) {
+            return Self::new_binary(col_id, op, col, val, true);
+        }
+
+        None
+    }
+
     /// check if: c1 > c2 or c1 > 1
     /// this case it makes no sense to just extract c1 > 1
     fn check_or(
@@ -899,10 +1324,13 @@ impl ScalarExpression {
 #[cfg(test)]
 mod test {
     use crate::catalog::{ColumnCatalog, ColumnDesc, ColumnSummary};
+    use crate::expression::function::{
+        FunctionSignature, ScalarFunction, ScalarFunctionRef, Volatility,
+    };
     use crate::expression::simplify::ConstantBinary;
-    use crate::expression::{BinaryOperator, ScalarExpression};
+    use crate::expression::{BinaryOperator, ScalarExpression, UnaryOperator};
     use crate::types::errors::TypeError;
-    use crate::types::value::DataValue;
+    use crate::types::value::{DataValue, ValueRef};
     use crate::types::LogicalType;
     use std::collections::Bound;
     use std::sync::Arc;
@@ -913,6 +1341,7 @@ mod test {
             summary: ColumnSummary {
                 id: Some(0),
                 name: "c1".to_string(),
+                table_name: None,
             },
             nullable: false,
             desc: ColumnDesc {
@@ -920,10 +1349,16 @@ mod test {
                 is_primary: false,
                 is_unique: false,
                 default: None,
+                is_hidden: false,
+                generated: None,
+                identity: None,
+                comment: None,
+                on_update: None,
+                default_expr: None,
             },
             ref_expr: None,
         });
-        let val_1 = Arc::new(DataValue::Int32(Some(1)));
+        let val_1 = ValueRef::new(DataValue::Int32(Some(1)));
 
         let binary_eq = ScalarExpression::Binary {
             op: BinaryOperator::Eq,
@@ -1015,15 +1450,168 @@ mod test {
             }
         );
 
+        let is_null = |negated, expr| ScalarExpression::IsNull {
+            negated,
+            expr: Box::new(expr),
+        };
+        let binary_is_not_null = is_null(true, ScalarExpression::ColumnRef(col_1.clone()))
+            .convert_binary(&0)?
+            .unwrap();
+
+        assert_eq!(
+            binary_is_not_null,
+            ConstantBinary::Scope {
+                min: Bound::Unbounded,
+                max: Bound::Unbounded
+            }
+        );
+        assert_eq!(
+            is_null(false, ScalarExpression::ColumnRef(col_1.clone())).convert_binary(&0)?,
+            None
+        );
+        // true for the nulls only, not for the values greater than 1
+        let gt_is_null = is_null(
+            false,
+            ScalarExpression::Binary {
+                op: BinaryOperator::Gt,
+                left_expr: Box::new(ScalarExpression::ColumnRef(col_1.clone())),
+                right_expr: Box::new(ScalarExpression::Constant(val_1.clone())),
+                ty: LogicalType::Boolean,
+            },
+        );
+        assert_eq!(gt_is_null.convert_binary(&0)?, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_convert_binary_boolean() -> Result<(), TypeError> {
+        let col_b = ScalarExpression::ColumnRef(Arc::new(ColumnCatalog {
+            summary: ColumnSummary {
+                id: Some(0),
+                name: "b".to_string(),
+                table_name: None,
+            },
+            nullable: true,
+            desc: ColumnDesc::new(LogicalType::Boolean, false, false, None),
+            ref_expr: None,
+        }));
+        let eq = |v| {
+            Some(ConstantBinary::Eq(ValueRef::new(DataValue::Boolean(Some(
+                v,
+            )))))
+        };
+        let not = |expr: ScalarExpression| ScalarExpression::Unary {
+            op: UnaryOperator::Not,
+            expr: Box::new(expr),
+            ty: LogicalType::Boolean,
+        };
+        let binary = |op, right| ScalarExpression::Binary {
+            op,
+            left_expr: Box::new(col_b.clone()),
+            right_expr: Box::new(right),
+            ty: LogicalType::Boolean,
+        };
+        let val_false = ScalarExpression::Constant(ValueRef::new(DataValue::Boolean(Some(false))));
+
+        assert_eq!(col_b.convert_binary(&0)?, eq(true));
+        assert_eq!(not(col_b.clone()).convert_binary(&0)?, eq(false));
+        assert_eq!(col_b.convert_binary(&1)?, None);
+        // `b = false` is not `b`
+        assert_eq!(
+            binary(BinaryOperator::Eq, val_false.clone()).convert_binary(&0)?,
+            eq(false)
+        );
+        // the other negations are not converted
+        assert_eq!(
+            not(binary(BinaryOperator::Eq, val_false)).convert_binary(&0)?,
+            None
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_convert_prefix_binaries() -> Result<(), TypeError> {
+        let column = |id: u32, name: &str| {
+            ScalarExpression::ColumnRef(Arc::new(ColumnCatalog {
+                summary: ColumnSummary {
+                    id: Some(id),
+                    name: name.to_string(),
+                    table_name: None,
+                },
+                nullable: false,
+                desc: ColumnDesc::new(LogicalType::Integer, false, false, None),
+                ref_expr: None,
+            }))
+        };
+        let val = |v: i32| ValueRef::new(DataValue::Int32(Some(v)));
+        let binary =
+            |op, left: ScalarExpression, right: ScalarExpression| ScalarExpression::Binary {
+                op,
+                left_expr: Box::new(left),
+                right_expr: Box::new(right),
+                ty: LogicalType::Boolean,
+            };
+        let a_eq_1 = binary(
+            BinaryOperator::Eq,
+            column(0, "a"),
+            ScalarExpression::Constant(val(1)),
+        );
+        let b_gt_2 = binary(
+            BinaryOperator::Gt,
+            column(1, "b"),
+            ScalarExpression::Constant(val(2)),
+        );
+
+        // `a = 1 AND b > 2` seeks `(1, 2)..`
+        assert_eq!(
+            binary(BinaryOperator::And, a_eq_1.clone(), b_gt_2.clone())
+                .convert_prefix_binaries(&[0, 1])?,
+            vec![ConstantBinary::Prefix {
+                values: vec![val(1)],
+                binary: Box::new(ConstantBinary::Scope {
+                    min: Bound::Excluded(val(2)),
+                    max: Bound::Unbounded,
+                }),
+            }]
+        );
+        // `a = 1` alone seeks all the keys of `(1, ..)`
+        assert_eq!(
+            a_eq_1.convert_prefix_binaries(&[0, 1])?,
+            vec![ConstantBinary::Prefix {
+                values: vec![],
+                binary: Box::new(ConstantBinary::Eq(val(1))),
+            }]
+        );
+        // `b` is not the leading column
+        assert!(b_gt_2.convert_prefix_binaries(&[0, 1])?.is_empty());
+        // the columns after a range of `a` are left to the filter
+        let a_gt_1 = binary(
+            BinaryOperator::Gt,
+            column(0, "a"),
+            ScalarExpression::Constant(val(1)),
+        );
+        assert_eq!(
+            binary(BinaryOperator::And, a_gt_1, b_gt_2).convert_prefix_binaries(&[0, 1])?,
+            vec![ConstantBinary::Prefix {
+                values: vec![],
+                binary: Box::new(ConstantBinary::Scope {
+                    min: Bound::Excluded(val(1)),
+                    max: Bound::Unbounded,
+                }),
+            }]
+        );
+
         Ok(())
     }
 
     #[test]
     fn test_scope_aggregation_eq_noteq() -> Result<(), TypeError> {
-        let val_0 = Arc::new(DataValue::Int32(Some(0)));
-        let val_1 = Arc::new(DataValue::Int32(Some(1)));
-        let val_2 = Arc::new(DataValue::Int32(Some(2)));
-        let val_3 = Arc::new(DataValue::Int32(Some(3)));
+        let val_0 = ValueRef::new(DataValue::Int32(Some(0)));
+        let val_1 = ValueRef::new(DataValue::Int32(Some(1)));
+        let val_2 = ValueRef::new(DataValue::Int32(Some(2)));
+        let val_3 = ValueRef::new(DataValue::Int32(Some(3)));
 
         let mut binary = ConstantBinary::And(vec![
             ConstantBinary::Eq(val_0.clone()),
@@ -1041,10 +1629,10 @@ mod test {
 
     #[test]
     fn test_scope_aggregation_eq_noteq_cover() -> Result<(), TypeError> {
-        let val_0 = Arc::new(DataValue::Int32(Some(0)));
-        let val_1 = Arc::new(DataValue::Int32(Some(1)));
-        let val_2 = Arc::new(DataValue::Int32(Some(2)));
-        let val_3 = Arc::new(DataValue::Int32(Some(3)));
+        let val_0 = ValueRef::new(DataValue::Int32(Some(0)));
+        let val_1 = ValueRef::new(DataValue::Int32(Some(1)));
+        let val_2 = ValueRef::new(DataValue::Int32(Some(2)));
+        let val_3 = ValueRef::new(DataValue::Int32(Some(3)));
 
         let mut binary = ConstantBinary::And(vec![
             ConstantBinary::Eq(val_0.clone()),
@@ -1066,10 +1654,10 @@ mod test {
 
     #[test]
     fn test_scope_aggregation_scope() -> Result<(), TypeError> {
-        let val_0 = Arc::new(DataValue::Int32(Some(0)));
-        let val_1 = Arc::new(DataValue::Int32(Some(1)));
-        let val_2 = Arc::new(DataValue::Int32(Some(2)));
-        let val_3 = Arc::new(DataValue::Int32(Some(3)));
+        let val_0 = ValueRef::new(DataValue::Int32(Some(0)));
+        let val_1 = ValueRef::new(DataValue::Int32(Some(1)));
+        let val_2 = ValueRef::new(DataValue::Int32(Some(2)));
+        let val_3 = ValueRef::new(DataValue::Int32(Some(3)));
 
         let mut binary = ConstantBinary::And(vec![
             ConstantBinary::Scope {
@@ -1109,10 +1697,10 @@ mod test {
 
     #[test]
     fn test_scope_aggregation_mixed() -> Result<(), TypeError> {
-        let val_0 = Arc::new(DataValue::Int32(Some(0)));
-        let val_1 = Arc::new(DataValue::Int32(Some(1)));
-        let val_2 = Arc::new(DataValue::Int32(Some(2)));
-        let val_3 = Arc::new(DataValue::Int32(Some(3)));
+        let val_0 = ValueRef::new(DataValue::Int32(Some(0)));
+        let val_1 = ValueRef::new(DataValue::Int32(Some(1)));
+        let val_2 = ValueRef::new(DataValue::Int32(Some(2)));
+        let val_3 = ValueRef::new(DataValue::Int32(Some(3)));
 
         let mut binary = ConstantBinary::And(vec![
             ConstantBinary::Scope {
@@ -1152,10 +1740,10 @@ mod test {
 
     #[test]
     fn test_scope_aggregation_or() -> Result<(), TypeError> {
-        let val_0 = Arc::new(DataValue::Int32(Some(0)));
-        let val_1 = Arc::new(DataValue::Int32(Some(1)));
-        let val_2 = Arc::new(DataValue::Int32(Some(2)));
-        let val_3 = Arc::new(DataValue::Int32(Some(3)));
+        let val_0 = ValueRef::new(DataValue::Int32(Some(0)));
+        let val_1 = ValueRef::new(DataValue::Int32(Some(1)));
+        let val_2 = ValueRef::new(DataValue::Int32(Some(2)));
+        let val_3 = ValueRef::new(DataValue::Int32(Some(3)));
 
         let mut binary = ConstantBinary::Or(vec![
             ConstantBinary::Scope {
@@ -1191,10 +1779,10 @@ mod test {
 
     #[test]
     fn test_scope_aggregation_or_unbounded() -> Result<(), TypeError> {
-        let val_0 = Arc::new(DataValue::Int32(Some(0)));
-        let val_1 = Arc::new(DataValue::Int32(Some(1)));
-        let val_2 = Arc::new(DataValue::Int32(Some(2)));
-        let val_3 = Arc::new(DataValue::Int32(Some(3)));
+        let val_0 = ValueRef::new(DataValue::Int32(Some(0)));
+        let val_1 = ValueRef::new(DataValue::Int32(Some(1)));
+        let val_2 = ValueRef::new(DataValue::Int32(Some(2)));
+        let val_3 = ValueRef::new(DataValue::Int32(Some(3)));
 
         let mut binary = ConstantBinary::Or(vec![
             ConstantBinary::Scope {
@@ -1224,8 +1812,8 @@ mod test {
 
     #[test]
     fn test_scope_aggregation_or_lower_unbounded() -> Result<(), TypeError> {
-        let val_0 = Arc::new(DataValue::Int32(Some(2)));
-        let val_1 = Arc::new(DataValue::Int32(Some(3)));
+        let val_0 = ValueRef::new(DataValue::Int32(Some(2)));
+        let val_1 = ValueRef::new(DataValue::Int32(Some(3)));
 
         let mut binary = ConstantBinary::Or(vec![
             ConstantBinary::Scope {
@@ -1261,8 +1849,8 @@ mod test {
 
     #[test]
     fn test_scope_aggregation_or_upper_unbounded() -> Result<(), TypeError> {
-        let val_0 = Arc::new(DataValue::Int32(Some(2)));
-        let val_1 = Arc::new(DataValue::Int32(Some(3)));
+        let val_0 = ValueRef::new(DataValue::Int32(Some(2)));
+        let val_1 = ValueRef::new(DataValue::Int32(Some(3)));
 
         let mut binary = ConstantBinary::Or(vec![
             ConstantBinary::Scope {
@@ -1298,18 +1886,18 @@ mod test {
 
     #[test]
     fn test_rearrange() -> Result<(), TypeError> {
-        let val_0 = Arc::new(DataValue::Int32(Some(0)));
-        let val_1 = Arc::new(DataValue::Int32(Some(1)));
-        let val_2 = Arc::new(DataValue::Int32(Some(2)));
-        let val_3 = Arc::new(DataValue::Int32(Some(3)));
+        let val_0 = ValueRef::new(DataValue::Int32(Some(0)));
+        let val_1 = ValueRef::new(DataValue::Int32(Some(1)));
+        let val_2 = ValueRef::new(DataValue::Int32(Some(2)));
+        let val_3 = ValueRef::new(DataValue::Int32(Some(3)));
 
-        let val_5 = Arc::new(DataValue::Int32(Some(5)));
+        let val_5 = ValueRef::new(DataValue::Int32(Some(5)));
 
-        let val_6 = Arc::new(DataValue::Int32(Some(6)));
-        let val_7 = Arc::new(DataValue::Int32(Some(7)));
-        let val_8 = Arc::new(DataValue::Int32(Some(8)));
+        let val_6 = ValueRef::new(DataValue::Int32(Some(6)));
+        let val_7 = ValueRef::new(DataValue::Int32(Some(7)));
+        let val_8 = ValueRef::new(DataValue::Int32(Some(8)));
 
-        let val_10 = Arc::new(DataValue::Int32(Some(10)));
+        let val_10 = ValueRef::new(DataValue::Int32(Some(10)));
 
         let binary = ConstantBinary::Or(vec![
             ConstantBinary::Scope {
@@ -1363,4 +1951,69 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn test_is_deterministic() -> Result<(), TypeError> {
+        let col_1 = Arc::new(ColumnCatalog::new(
+            "c1".to_string(),
+            false,
+            ColumnDesc::new(LogicalType::Integer, false, false, None),
+            None,
+        ));
+        let function = |name: &str, volatility| {
+            Arc::new(ScalarFunction::new(
+                name.to_string(),
+                FunctionSignature::new(vec![LogicalType::Integer], LogicalType::Integer)
+                    .with_volatility(volatility),
+                Arc::new(|args: &[DataValue]| Ok(args[0].clone())),
+            ))
+        };
+        let call =
+            |function: &ScalarFunctionRef, arg: ScalarExpression| ScalarExpression::Function {
+                function: function.clone(),
+                args: vec![arg],
+            };
+        let plus =
+            |left_expr: ScalarExpression, right_expr: ScalarExpression| ScalarExpression::Binary {
+                op: BinaryOperator::Plus,
+                left_expr: Box::new(left_expr),
+                right_expr: Box::new(right_expr),
+                ty: LogicalType::Integer,
+            };
+        let immutable = function("f", Volatility::Immutable);
+        let volatile = function("v", Volatility::Volatile);
+        let val_1 = ScalarExpression::Constant(ValueRef::new(DataValue::Int32(Some(1))));
+        let c1 = ScalarExpression::ColumnRef(col_1);
+
+        assert!(plus(c1.clone(), val_1.clone()).is_deterministic());
+        assert!(call(&immutable, c1.clone()).is_deterministic());
+        assert!(!call(&volatile, val_1.clone()).is_deterministic());
+        assert!(!plus(c1.clone(), call(&volatile, c1.clone())).is_deterministic());
+        assert!(!ScalarExpression::In {
+            negated: false,
+            expr: Box::new(c1.clone()),
+            args: vec![val_1.clone(), call(&volatile, val_1.clone())],
+        }
+        .is_deterministic());
+        assert!(!call(&immutable, call(&volatile, val_1.clone())).is_deterministic());
+
+        // only the calls of immutable functions on constants are folded
+        let mut expr = plus(
+            call(&immutable, plus(val_1.clone(), val_1.clone())),
+            c1.clone(),
+        );
+        expr.constant_calculation()?;
+        assert_eq!(
+            expr,
+            plus(
+                ScalarExpression::Constant(ValueRef::new(DataValue::Int32(Some(2)))),
+                c1.clone()
+            )
+        );
+        let mut expr = call(&volatile, val_1.clone());
+        expr.constant_calculation()?;
+        assert_eq!(expr, call(&volatile, val_1));
+
+        Ok(())
+    }
 }
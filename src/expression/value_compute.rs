@@ -1,8 +1,11 @@
 use crate::expression::{BinaryOperator, UnaryOperator};
 use crate::types::errors::TypeError;
+use crate::types::interval::Interval;
 use crate::types::value::DataValue;
 use crate::types::LogicalType;
+use ordered_float::OrderedFloat;
 use regex::Regex;
+use std::ops::{BitAnd, BitOr, BitXor};
 
 fn unpack_i32(value: DataValue) -> Option<i32> {
     match value {
@@ -60,6 +63,13 @@ fn unpack_date(value: DataValue) -> Option<i64> {
     }
 }
 
+fn unpack_interval(value: DataValue) -> Option<Interval> {
+    match value {
+        DataValue::Interval(inner) => inner,
+        _ => None,
+    }
+}
+
 fn unpack_utf8(value: DataValue) -> Option<String> {
     match value {
         DataValue::Utf8(inner) => inner,
@@ -71,6 +81,10 @@ pub fn unary_op(value: &DataValue, op: &UnaryOperator) -> Result<DataValue, Type
     let mut value_type = value.logical_type();
     let mut value = value.clone();
 
+    if value_type == LogicalType::SqlNull {
+        return Ok(DataValue::Null);
+    }
+
     if value_type.is_numeric() && matches!(op, UnaryOperator::Plus | UnaryOperator::Minus) {
         if value_type.is_unsigned_numeric() {
             match value_type {
@@ -88,10 +102,26 @@ pub fn unary_op(value: &DataValue, op: &UnaryOperator) -> Result<DataValue, Type
             UnaryOperator::Minus => match value {
                 DataValue::Float32(option) => DataValue::Float32(option.map(|v| -v)),
                 DataValue::Float64(option) => DataValue::Float64(option.map(|v| -v)),
-                DataValue::Int8(option) => DataValue::Int8(option.map(|v| -v)),
-                DataValue::Int16(option) => DataValue::Int16(option.map(|v| -v)),
-                DataValue::Int32(option) => DataValue::Int32(option.map(|v| -v)),
-                DataValue::Int64(option) => DataValue::Int64(option.map(|v| -v)),
+                DataValue::Int8(option) => DataValue::Int8(
+                    option
+                        .map(|v| v.checked_neg().ok_or(TypeError::Overflow(value_type)))
+                        .transpose()?,
+                ),
+                DataValue::Int16(option) => DataValue::Int16(
+                    option
+                        .map(|v| v.checked_neg().ok_or(TypeError::Overflow(value_type)))
+                        .transpose()?,
+                ),
+                DataValue::Int32(option) => DataValue::Int32(
+                    option
+                        .map(|v| v.checked_neg().ok_or(TypeError::Overflow(value_type)))
+                        .transpose()?,
+                ),
+                DataValue::Int64(option) => DataValue::Int64(
+                    option
+                        .map(|v| v.checked_neg().ok_or(TypeError::Overflow(value_type)))
+                        .transpose()?,
+                ),
                 _ => unreachable!(),
             },
             _ => unreachable!(),
@@ -108,6 +138,23 @@ pub fn unary_op(value: &DataValue, op: &UnaryOperator) -> Result<DataValue, Type
     }
 }
 
+/// Whether the whole of `value` matches the `LIKE` pattern, where `%` is any characters
+/// and `_` a single one.
+pub(crate) fn like_match(value: &str, pattern: &str) -> bool {
+    let mut regex_pattern = String::from("(?s)^");
+
+    for c in pattern.chars() {
+        match c {
+            '%' => regex_pattern.push_str(".*"),
+            '_' => regex_pattern.push('.'),
+            c => regex_pattern.push_str(&regex::escape(c.encode_utf8(&mut [0; 4]))),
+        }
+    }
+    regex_pattern.push('$');
+
+    Regex::new(&regex_pattern).unwrap().is_match(value)
+}
+
 /// Tips:
 /// - Null values operate as null values
 pub fn binary_op(
@@ -120,17 +167,27 @@ pub fn binary_op(
         let pattern_option = unpack_utf8(right.clone().cast(&LogicalType::Varchar(None))?);
 
         let mut is_match = if let (Some(value), Some(pattern)) = (value_option, pattern_option) {
-            let regex_pattern = pattern.replace('%', ".*").replace('_', ".");
-
-            Regex::new(&regex_pattern).unwrap().is_match(&value)
+            like_match(&value, &pattern)
         } else {
-            unreachable!("The left and right values calculated by Like cannot be Null values.")
+            return Ok(DataValue::Boolean(None));
         };
         if op == &BinaryOperator::NotLike {
             is_match = !is_match;
         }
         return Ok(DataValue::Boolean(Some(is_match)));
     }
+    if matches!(op, BinaryOperator::ShiftLeft | BinaryOperator::ShiftRight) {
+        return shift_op(left, right, op);
+    }
+    if matches!(op, BinaryOperator::Plus | BinaryOperator::Minus) {
+        if let Some(result_type) = LogicalType::temporal_arithmetic_type(
+            &left.logical_type(),
+            &right.logical_type(),
+            op == &BinaryOperator::Minus,
+        ) {
+            return temporal_binary_op(left, right, op, &result_type);
+        }
+    }
     let unified_type = LogicalType::max_logical_type(&left.logical_type(), &right.logical_type())?;
 
     let value = match &unified_type {
@@ -141,7 +198,10 @@ pub fn binary_op(
             match op {
                 BinaryOperator::Plus => {
                     let value = if let (Some(v1), Some(v2)) = (left_value, right_value) {
-                        Some(v1 + v2)
+                        Some(
+                            v1.checked_add(v2)
+                                .ok_or(TypeError::Overflow(unified_type))?,
+                        )
                     } else {
                         None
                     };
@@ -150,7 +210,10 @@ pub fn binary_op(
                 }
                 BinaryOperator::Minus => {
                     let value = if let (Some(v1), Some(v2)) = (left_value, right_value) {
-                        Some(v1 - v2)
+                        Some(
+                            v1.checked_sub(v2)
+                                .ok_or(TypeError::Overflow(unified_type))?,
+                        )
                     } else {
                         None
                     };
@@ -159,7 +222,10 @@ pub fn binary_op(
                 }
                 BinaryOperator::Multiply => {
                     let value = if let (Some(v1), Some(v2)) = (left_value, right_value) {
-                        Some(v1 * v2)
+                        Some(
+                            v1.checked_mul(v2)
+                                .ok_or(TypeError::Overflow(unified_type))?,
+                        )
                     } else {
                         None
                     };
@@ -168,6 +234,9 @@ pub fn binary_op(
                 }
                 BinaryOperator::Divide => {
                     let value = if let (Some(v1), Some(v2)) = (left_value, right_value) {
+                        if v2 == 0 {
+                            return Err(TypeError::DivisionByZero);
+                        }
                         Some(v1 as f64 / v2 as f64)
                     } else {
                         None
@@ -175,6 +244,12 @@ pub fn binary_op(
 
                     DataValue::Float64(value)
                 }
+                BinaryOperator::Modulo
+                | BinaryOperator::BitwiseAnd
+                | BinaryOperator::BitwiseOr
+                | BinaryOperator::BitwiseXor => {
+                    DataValue::Int32(integer_op(left_value, right_value, op)?)
+                }
 
                 BinaryOperator::Gt => {
                     let value = if let (Some(v1), Some(v2)) = (left_value, right_value) {
@@ -240,7 +315,10 @@ pub fn binary_op(
             match op {
                 BinaryOperator::Plus => {
                     let value = if let (Some(v1), Some(v2)) = (left_value, right_value) {
-                        Some(v1 + v2)
+                        Some(
+                            v1.checked_add(v2)
+                                .ok_or(TypeError::Overflow(unified_type))?,
+                        )
                     } else {
                         None
                     };
@@ -249,7 +327,10 @@ pub fn binary_op(
                 }
                 BinaryOperator::Minus => {
                     let value = if let (Some(v1), Some(v2)) = (left_value, right_value) {
-                        Some(v1 - v2)
+                        Some(
+                            v1.checked_sub(v2)
+                                .ok_or(TypeError::Overflow(unified_type))?,
+                        )
                     } else {
                         None
                     };
@@ -258,7 +339,10 @@ pub fn binary_op(
                 }
                 BinaryOperator::Multiply => {
                     let value = if let (Some(v1), Some(v2)) = (left_value, right_value) {
-                        Some(v1 * v2)
+                        Some(
+                            v1.checked_mul(v2)
+                                .ok_or(TypeError::Overflow(unified_type))?,
+                        )
                     } else {
                         None
                     };
@@ -267,6 +351,9 @@ pub fn binary_op(
                 }
                 BinaryOperator::Divide => {
                     let value = if let (Some(v1), Some(v2)) = (left_value, right_value) {
+                        if v2 == 0 {
+                            return Err(TypeError::DivisionByZero);
+                        }
                         Some(v1 as f64 / v2 as f64)
                     } else {
                         None
@@ -274,6 +361,12 @@ pub fn binary_op(
 
                     DataValue::Float64(value)
                 }
+                BinaryOperator::Modulo
+                | BinaryOperator::BitwiseAnd
+                | BinaryOperator::BitwiseOr
+                | BinaryOperator::BitwiseXor => {
+                    DataValue::Int64(integer_op(left_value, right_value, op)?)
+                }
 
                 BinaryOperator::Gt => {
                     let value = if let (Some(v1), Some(v2)) = (left_value, right_value) {
@@ -339,7 +432,10 @@ pub fn binary_op(
             match op {
                 BinaryOperator::Plus => {
                     let value = if let (Some(v1), Some(v2)) = (left_value, right_value) {
-                        Some(v1 + v2)
+                        Some(
+                            v1.checked_add(v2)
+                                .ok_or(TypeError::Overflow(unified_type))?,
+                        )
                     } else {
                         None
                     };
@@ -348,7 +444,10 @@ pub fn binary_op(
                 }
                 BinaryOperator::Minus => {
                     let value = if let (Some(v1), Some(v2)) = (left_value, right_value) {
-                        Some(v1 - v2)
+                        Some(
+                            v1.checked_sub(v2)
+                                .ok_or(TypeError::Overflow(unified_type))?,
+                        )
                     } else {
                         None
                     };
@@ -357,7 +456,10 @@ pub fn binary_op(
                 }
                 BinaryOperator::Multiply => {
                     let value = if let (Some(v1), Some(v2)) = (left_value, right_value) {
-                        Some(v1 * v2)
+                        Some(
+                            v1.checked_mul(v2)
+                                .ok_or(TypeError::Overflow(unified_type))?,
+                        )
                     } else {
                         None
                     };
@@ -366,6 +468,9 @@ pub fn binary_op(
                 }
                 BinaryOperator::Divide => {
                     let value = if let (Some(v1), Some(v2)) = (left_value, right_value) {
+                        if v2 == 0 {
+                            return Err(TypeError::DivisionByZero);
+                        }
                         Some(v1 as f64 / v2 as f64)
                     } else {
                         None
@@ -373,6 +478,12 @@ pub fn binary_op(
 
                     DataValue::Float64(value)
                 }
+                BinaryOperator::Modulo
+                | BinaryOperator::BitwiseAnd
+                | BinaryOperator::BitwiseOr
+                | BinaryOperator::BitwiseXor => {
+                    DataValue::UInt32(integer_op(left_value, right_value, op)?)
+                }
 
                 BinaryOperator::Gt => {
                     let value = if let (Some(v1), Some(v2)) = (left_value, right_value) {
@@ -438,7 +549,10 @@ pub fn binary_op(
             match op {
                 BinaryOperator::Plus => {
                     let value = if let (Some(v1), Some(v2)) = (left_value, right_value) {
-                        Some(v1 + v2)
+                        Some(
+                            v1.checked_add(v2)
+                                .ok_or(TypeError::Overflow(unified_type))?,
+                        )
                     } else {
                         None
                     };
@@ -447,7 +561,10 @@ pub fn binary_op(
                 }
                 BinaryOperator::Minus => {
                     let value = if let (Some(v1), Some(v2)) = (left_value, right_value) {
-                        Some(v1 - v2)
+                        Some(
+                            v1.checked_sub(v2)
+                                .ok_or(TypeError::Overflow(unified_type))?,
+                        )
                     } else {
                         None
                     };
@@ -456,7 +573,10 @@ pub fn binary_op(
                 }
                 BinaryOperator::Multiply => {
                     let value = if let (Some(v1), Some(v2)) = (left_value, right_value) {
-                        Some(v1 * v2)
+                        Some(
+                            v1.checked_mul(v2)
+                                .ok_or(TypeError::Overflow(unified_type))?,
+                        )
                     } else {
                         None
                     };
@@ -465,6 +585,9 @@ pub fn binary_op(
                 }
                 BinaryOperator::Divide => {
                     let value = if let (Some(v1), Some(v2)) = (left_value, right_value) {
+                        if v2 == 0 {
+                            return Err(TypeError::DivisionByZero);
+                        }
                         Some(v1 as f64 / v2 as f64)
                     } else {
                         None
@@ -472,6 +595,12 @@ pub fn binary_op(
 
                     DataValue::Float64(value)
                 }
+                BinaryOperator::Modulo
+                | BinaryOperator::BitwiseAnd
+                | BinaryOperator::BitwiseOr
+                | BinaryOperator::BitwiseXor => {
+                    DataValue::UInt64(integer_op(left_value, right_value, op)?)
+                }
 
                 BinaryOperator::Gt => {
                     let value = if let (Some(v1), Some(v2)) = (left_value, right_value) {
@@ -564,6 +693,9 @@ pub fn binary_op(
                 }
                 BinaryOperator::Divide => {
                     let value = if let (Some(v1), Some(v2)) = (left_value, right_value) {
+                        if v2 == 0.0 {
+                            return Err(TypeError::DivisionByZero);
+                        }
                         Some(v1 / v2)
                     } else {
                         None
@@ -571,10 +703,25 @@ pub fn binary_op(
 
                     DataValue::Float64(value)
                 }
+                BinaryOperator::Modulo => {
+                    let value = if let (Some(v1), Some(v2)) = (left_value, right_value) {
+                        if v2 == 0.0 {
+                            return Err(TypeError::DivisionByZero);
+                        }
+                        Some(v1 % v2)
+                    } else {
+                        None
+                    };
+
+                    DataValue::Float64(value)
+                }
 
+                // NaN equals NaN and is greater than any other value, the order of
+                // `DataValue` and of the index keys
+                // NaN is ordered as for `Double`
                 BinaryOperator::Gt => {
                     let value = if let (Some(v1), Some(v2)) = (left_value, right_value) {
-                        Some(v1 > v2)
+                        Some(OrderedFloat(v1) > OrderedFloat(v2))
                     } else {
                         None
                     };
@@ -583,7 +730,7 @@ pub fn binary_op(
                 }
                 BinaryOperator::Lt => {
                     let value = if let (Some(v1), Some(v2)) = (left_value, right_value) {
-                        Some(v1 < v2)
+                        Some(OrderedFloat(v1) < OrderedFloat(v2))
                     } else {
                         None
                     };
@@ -592,7 +739,7 @@ pub fn binary_op(
                 }
                 BinaryOperator::GtEq => {
                     let value = if let (Some(v1), Some(v2)) = (left_value, right_value) {
-                        Some(v1 >= v2)
+                        Some(OrderedFloat(v1) >= OrderedFloat(v2))
                     } else {
                         None
                     };
@@ -601,7 +748,7 @@ pub fn binary_op(
                 }
                 BinaryOperator::LtEq => {
                     let value = if let (Some(v1), Some(v2)) = (left_value, right_value) {
-                        Some(v1 <= v2)
+                        Some(OrderedFloat(v1) <= OrderedFloat(v2))
                     } else {
                         None
                     };
@@ -610,7 +757,7 @@ pub fn binary_op(
                 }
                 BinaryOperator::Eq => {
                     let value = match (left_value, right_value) {
-                        (Some(v1), Some(v2)) => Some(v1 == v2),
+                        (Some(v1), Some(v2)) => Some(OrderedFloat(v1) == OrderedFloat(v2)),
                         (None, None) => Some(true),
                         (_, _) => None,
                     };
@@ -619,7 +766,7 @@ pub fn binary_op(
                 }
                 BinaryOperator::NotEq => {
                     let value = if let (Some(v1), Some(v2)) = (left_value, right_value) {
-                        Some(v1 != v2)
+                        Some(OrderedFloat(v1) != OrderedFloat(v2))
                     } else {
                         None
                     };
@@ -634,18 +781,74 @@ pub fn binary_op(
             let right_value = unpack_bool(right.clone().cast(&unified_type)?);
 
             match op {
+                // NULL is the unknown truth value, it decides the result only if
+                // the other side doesn't, e.g. `NULL AND false` is false
                 BinaryOperator::And => {
+                    let value = match (left_value, right_value) {
+                        (Some(false), _) | (_, Some(false)) => Some(false),
+                        (Some(true), Some(true)) => Some(true),
+                        _ => None,
+                    };
+
+                    DataValue::Boolean(value)
+                }
+                BinaryOperator::Or => {
+                    let value = match (left_value, right_value) {
+                        (Some(true), _) | (_, Some(true)) => Some(true),
+                        (Some(false), Some(false)) => Some(false),
+                        _ => None,
+                    };
+
+                    DataValue::Boolean(value)
+                }
+                BinaryOperator::Eq => {
+                    let value = match (left_value, right_value) {
+                        (Some(v1), Some(v2)) => Some(v1 == v2),
+                        (None, None) => Some(true),
+                        (_, _) => None,
+                    };
+
+                    DataValue::Boolean(value)
+                }
+                BinaryOperator::NotEq => {
                     let value = if let (Some(v1), Some(v2)) = (left_value, right_value) {
-                        Some(v1 && v2)
+                        Some(v1 != v2)
                     } else {
                         None
                     };
 
                     DataValue::Boolean(value)
                 }
-                BinaryOperator::Or => {
+                BinaryOperator::Gt => {
+                    let value = if let (Some(v1), Some(v2)) = (left_value, right_value) {
+                        Some(v1 & !v2)
+                    } else {
+                        None
+                    };
+
+                    DataValue::Boolean(value)
+                }
+                BinaryOperator::Lt => {
                     let value = if let (Some(v1), Some(v2)) = (left_value, right_value) {
-                        Some(v1 || v2)
+                        Some(!v1 & v2)
+                    } else {
+                        None
+                    };
+
+                    DataValue::Boolean(value)
+                }
+                BinaryOperator::GtEq => {
+                    let value = if let (Some(v1), Some(v2)) = (left_value, right_value) {
+                        Some(v1 >= v2)
+                    } else {
+                        None
+                    };
+
+                    DataValue::Boolean(value)
+                }
+                BinaryOperator::LtEq => {
+                    let value = if let (Some(v1), Some(v2)) = (left_value, right_value) {
+                        Some(v1 <= v2)
                     } else {
                         None
                     };
@@ -689,6 +892,9 @@ pub fn binary_op(
                 }
                 BinaryOperator::Divide => {
                     let value = if let (Some(v1), Some(v2)) = (left_value, right_value) {
+                        if v2 == 0.0 {
+                            return Err(TypeError::DivisionByZero);
+                        }
                         Some(v1 as f64 / v2 as f64)
                     } else {
                         None
@@ -696,6 +902,81 @@ pub fn binary_op(
 
                     DataValue::Float64(value)
                 }
+                BinaryOperator::Modulo => {
+                    let value = if let (Some(v1), Some(v2)) = (left_value, right_value) {
+                        if v2 == 0.0 {
+                            return Err(TypeError::DivisionByZero);
+                        }
+                        Some(v1 % v2)
+                    } else {
+                        None
+                    };
+
+                    DataValue::Float32(value)
+                }
+                BinaryOperator::Gt => {
+                    let value = if let (Some(v1), Some(v2)) = (left_value, right_value) {
+                        Some(OrderedFloat(v1) > OrderedFloat(v2))
+                    } else {
+                        None
+                    };
+
+                    DataValue::Boolean(value)
+                }
+                BinaryOperator::Lt => {
+                    let value = if let (Some(v1), Some(v2)) = (left_value, right_value) {
+                        Some(OrderedFloat(v1) < OrderedFloat(v2))
+                    } else {
+                        None
+                    };
+
+                    DataValue::Boolean(value)
+                }
+                BinaryOperator::GtEq => {
+                    let value = if let (Some(v1), Some(v2)) = (left_value, right_value) {
+                        Some(OrderedFloat(v1) >= OrderedFloat(v2))
+                    } else {
+                        None
+                    };
+
+                    DataValue::Boolean(value)
+                }
+                BinaryOperator::LtEq => {
+                    let value = if let (Some(v1), Some(v2)) = (left_value, right_value) {
+                        Some(OrderedFloat(v1) <= OrderedFloat(v2))
+                    } else {
+                        None
+                    };
+
+                    DataValue::Boolean(value)
+                }
+                BinaryOperator::Eq => {
+                    let value = match (left_value, right_value) {
+                        (Some(v1), Some(v2)) => Some(OrderedFloat(v1) == OrderedFloat(v2)),
+                        (None, None) => Some(true),
+                        (_, _) => None,
+                    };
+
+                    DataValue::Boolean(value)
+                }
+                BinaryOperator::NotEq => {
+                    let value = if let (Some(v1), Some(v2)) = (left_value, right_value) {
+                        Some(OrderedFloat(v1) != OrderedFloat(v2))
+                    } else {
+                        None
+                    };
+
+                    DataValue::Boolean(value)
+                }
+                _ => todo!("unsupported operator"),
+            }
+        }
+        LogicalType::SqlNull => DataValue::Boolean(None),
+        LogicalType::DateTime => {
+            let left_value = unpack_date(left.clone().cast(&unified_type)?);
+            let right_value = unpack_date(right.clone().cast(&unified_type)?);
+
+            match op {
                 BinaryOperator::Gt => {
                     let value = if let (Some(v1), Some(v2)) = (left_value, right_value) {
                         Some(v1 > v2)
@@ -753,10 +1034,9 @@ pub fn binary_op(
                 _ => todo!("unsupported operator"),
             }
         }
-        LogicalType::SqlNull => DataValue::Boolean(None),
-        LogicalType::DateTime => {
-            let left_value = unpack_date(left.clone().cast(&unified_type)?);
-            let right_value = unpack_date(right.clone().cast(&unified_type)?);
+        LogicalType::Interval => {
+            let left_value = unpack_interval(left.clone().cast(&unified_type)?);
+            let right_value = unpack_interval(right.clone().cast(&unified_type)?);
 
             match op {
                 BinaryOperator::Gt => {
@@ -813,7 +1093,8 @@ pub fn binary_op(
 
                     DataValue::Boolean(value)
                 }
-                _ => todo!("unsupported operator"),
+                // the binder only lets the comparisons through
+                _ => return Err(TypeError::InvalidType),
             }
         }
         LogicalType::Varchar(None) => {
@@ -885,12 +1166,157 @@ pub fn binary_op(
     Ok(value)
 }
 
+trait Integer:
+    Copy + PartialEq + Default + BitAnd<Output = Self> + BitOr<Output = Self> + BitXor<Output = Self>
+{
+    fn wrapping_rem(self, rhs: Self) -> Self;
+}
+
+macro_rules! impl_integer {
+    ($($ty:ty),*) => {
+        $(impl Integer for $ty {
+            fn wrapping_rem(self, rhs: Self) -> Self {
+                <$ty>::wrapping_rem(self, rhs)
+            }
+        })*
+    };
+}
+
+impl_integer!(i32, i64, u32, u64);
+
+/// `%` and the bitwise operators between integers of the same type, the remainder takes
+/// the sign of the dividend and `MIN % -1` is 0 instead of overflowing.
+fn integer_op<T: Integer>(
+    left: Option<T>,
+    right: Option<T>,
+    op: &BinaryOperator,
+) -> Result<Option<T>, TypeError> {
+    let (Some(v1), Some(v2)) = (left, right) else {
+        return Ok(None);
+    };
+
+    Ok(Some(match op {
+        BinaryOperator::Modulo => {
+            if v2 == T::default() {
+                return Err(TypeError::DivisionByZero);
+            }
+            v1.wrapping_rem(v2)
+        }
+        BinaryOperator::BitwiseAnd => v1 & v2,
+        BinaryOperator::BitwiseOr => v1 | v2,
+        BinaryOperator::BitwiseXor => v1 ^ v2,
+        _ => unreachable!(),
+    }))
+}
+
+/// `<<` and `>>` keep the type of the left operand:
+/// - a negative shift amount is an error
+/// - shifting by the bit width or more gives 0, or -1 for `>>` of a negative value
+fn shift_op(
+    left: &DataValue,
+    right: &DataValue,
+    op: &BinaryOperator,
+) -> Result<DataValue, TypeError> {
+    let amount =
+        if right.logical_type().is_numeric() && !right.logical_type().is_floating_point_numeric() {
+            unpack_i64(right.clone().cast(&LogicalType::Bigint)?)
+        } else {
+            return Err(TypeError::InvalidType);
+        };
+    let amount = match amount {
+        Some(amount) if amount < 0 => return Err(TypeError::InvalidShift(amount)),
+        Some(amount) => u32::try_from(amount).unwrap_or(u32::MAX),
+        None => return Ok(DataValue::none(&left.logical_type())),
+    };
+    let is_left = op == &BinaryOperator::ShiftLeft;
+
+    macro_rules! shift {
+        ($ty:ty, $value:expr) => {
+            $value.map(|v: $ty| {
+                if is_left {
+                    v.checked_shl(amount).unwrap_or(0)
+                } else {
+                    // shifting by one less than the bit width, then once more, fills with the sign bit
+                    v.checked_shr(amount)
+                        .unwrap_or_else(|| (v >> (<$ty>::BITS - 1)) >> 1)
+                }
+            })
+        };
+    }
+
+    Ok(match left {
+        DataValue::Int8(value) => DataValue::Int8(shift!(i8, value)),
+        DataValue::Int16(value) => DataValue::Int16(shift!(i16, value)),
+        DataValue::Int32(value) => DataValue::Int32(shift!(i32, value)),
+        DataValue::Int64(value) => DataValue::Int64(shift!(i64, value)),
+        DataValue::UInt8(value) => DataValue::UInt8(shift!(u8, value)),
+        DataValue::UInt16(value) => DataValue::UInt16(shift!(u16, value)),
+        DataValue::UInt32(value) => DataValue::UInt32(shift!(u32, value)),
+        DataValue::UInt64(value) => DataValue::UInt64(shift!(u64, value)),
+        DataValue::Null => DataValue::Null,
+        _ => return Err(TypeError::InvalidType),
+    })
+}
+
+/// `+` and `-` between temporal types and intervals:
+/// - date/datetime ± interval -> datetime
+/// - date/datetime - date/datetime -> interval
+/// - interval ± interval -> interval
+fn temporal_binary_op(
+    left: &DataValue,
+    right: &DataValue,
+    op: &BinaryOperator,
+    result_type: &LogicalType,
+) -> Result<DataValue, TypeError> {
+    if left.is_null() || right.is_null() {
+        return Ok(DataValue::none(result_type));
+    }
+    let out_of_range = || TypeError::InvalidInterval("interval out of range".to_string());
+    let signed = |interval: &Interval| {
+        if op == &BinaryOperator::Minus {
+            interval.checked_neg().ok_or_else(out_of_range)
+        } else {
+            Ok(*interval)
+        }
+    };
+    let to_datetime = |value: &DataValue| {
+        value
+            .clone()
+            .cast(&LogicalType::DateTime)?
+            .datetime()
+            .ok_or(TypeError::CastFail)
+    };
+
+    let value = match (left, right) {
+        (DataValue::Interval(Some(v1)), DataValue::Interval(Some(v2))) => {
+            let interval = v1.checked_add(&signed(v2)?).ok_or_else(out_of_range)?;
+
+            DataValue::Interval(Some(interval))
+        }
+        (date, DataValue::Interval(Some(interval)))
+        | (DataValue::Interval(Some(interval)), date) => {
+            let date_time = signed(interval)?.add_to(&to_datetime(date)?)?;
+
+            DataValue::Date64(Some(date_time.timestamp()))
+        }
+        (v1, v2) => {
+            let interval = Interval::between(&to_datetime(v1)?, &to_datetime(v2)?)?;
+
+            DataValue::Interval(Some(interval))
+        }
+    };
+
+    Ok(value)
+}
+
 #[cfg(test)]
 mod test {
-    use crate::expression::value_compute::binary_op;
+    use crate::expression::value_compute::{binary_op, like_match};
     use crate::expression::BinaryOperator;
     use crate::types::errors::TypeError;
+    use crate::types::interval::Interval;
     use crate::types::value::DataValue;
+    use crate::types::LogicalType;
 
     #[test]
     fn test_binary_op_arithmetic_plus() -> Result<(), TypeError> {
@@ -1212,6 +1638,96 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_binary_op_modulo_and_bitwise() -> Result<(), TypeError> {
+        let int = |v: i32| DataValue::Int32(Some(v));
+
+        assert_eq!(
+            binary_op(&int(7), &int(2), &BinaryOperator::Modulo)?,
+            int(1)
+        );
+        assert_eq!(
+            binary_op(&int(-7), &int(2), &BinaryOperator::Modulo)?,
+            int(-1)
+        );
+        assert_eq!(
+            binary_op(&int(i32::MIN), &int(-1), &BinaryOperator::Modulo)?,
+            int(0)
+        );
+        assert_eq!(
+            binary_op(&int(7), &DataValue::Int32(None), &BinaryOperator::Modulo)?,
+            DataValue::Int32(None)
+        );
+        assert_eq!(
+            binary_op(
+                &DataValue::Float64(Some(7.5)),
+                &DataValue::Float64(Some(2.0)),
+                &BinaryOperator::Modulo
+            )?,
+            DataValue::Float64(Some(1.5))
+        );
+        assert!(matches!(
+            binary_op(&int(7), &int(0), &BinaryOperator::Modulo),
+            Err(TypeError::DivisionByZero)
+        ));
+        assert!(matches!(
+            binary_op(&int(7), &int(0), &BinaryOperator::Divide),
+            Err(TypeError::DivisionByZero)
+        ));
+
+        assert_eq!(
+            binary_op(&int(6), &int(3), &BinaryOperator::BitwiseAnd)?,
+            int(2)
+        );
+        assert_eq!(
+            binary_op(&int(6), &int(3), &BinaryOperator::BitwiseOr)?,
+            int(7)
+        );
+        assert_eq!(
+            binary_op(&int(6), &int(3), &BinaryOperator::BitwiseXor)?,
+            int(5)
+        );
+        assert_eq!(
+            binary_op(
+                &DataValue::Int64(Some(6)),
+                &int(3),
+                &BinaryOperator::BitwiseAnd
+            )?,
+            DataValue::Int64(Some(2))
+        );
+
+        assert_eq!(
+            binary_op(&int(1), &int(4), &BinaryOperator::ShiftLeft)?,
+            int(16)
+        );
+        assert_eq!(
+            binary_op(&int(-16), &int(2), &BinaryOperator::ShiftRight)?,
+            int(-4)
+        );
+        assert_eq!(
+            binary_op(&int(1), &int(32), &BinaryOperator::ShiftLeft)?,
+            int(0)
+        );
+        assert_eq!(
+            binary_op(&int(-16), &int(40), &BinaryOperator::ShiftRight)?,
+            int(-1)
+        );
+        assert_eq!(
+            binary_op(
+                &DataValue::UInt8(Some(u8::MAX)),
+                &DataValue::Int64(Some(8)),
+                &BinaryOperator::ShiftRight
+            )?,
+            DataValue::UInt8(Some(0))
+        );
+        assert!(matches!(
+            binary_op(&int(1), &int(-1), &BinaryOperator::ShiftLeft),
+            Err(TypeError::InvalidShift(-1))
+        ));
+
+        Ok(())
+    }
+
     #[test]
     fn test_binary_op_cast() -> Result<(), TypeError> {
         let i32_cast_1 = binary_op(
@@ -1771,7 +2287,55 @@ mod test {
                 &DataValue::Boolean(Some(true)),
                 &BinaryOperator::Or
             )?,
-            DataValue::Boolean(None)
+            DataValue::Boolean(Some(true))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_binary_op_three_valued_logic() -> Result<(), TypeError> {
+        let (t, f, n) = (Some(true), Some(false), None);
+
+        // (left, right, left AND right, left OR right)
+        for (v1, v2, and, or) in [
+            (t, t, t, t),
+            (t, f, f, t),
+            (t, n, n, t),
+            (f, t, f, t),
+            (f, f, f, f),
+            (f, n, f, n),
+            (n, t, n, t),
+            (n, f, f, n),
+            (n, n, n, n),
+        ] {
+            let (left, right) = (DataValue::Boolean(v1), DataValue::Boolean(v2));
+
+            assert_eq!(
+                binary_op(&left, &right, &BinaryOperator::And)?,
+                DataValue::Boolean(and)
+            );
+            assert_eq!(
+                binary_op(&left, &right, &BinaryOperator::Or)?,
+                DataValue::Boolean(or)
+            );
+        }
+        // an untyped NULL is the unknown truth value too
+        assert_eq!(
+            binary_op(
+                &DataValue::Null,
+                &DataValue::Boolean(Some(true)),
+                &BinaryOperator::Or
+            )?,
+            DataValue::Boolean(Some(true))
+        );
+        assert_eq!(
+            binary_op(
+                &DataValue::Null,
+                &DataValue::Boolean(Some(false)),
+                &BinaryOperator::And
+            )?,
+            DataValue::Boolean(Some(false))
         );
 
         Ok(())
@@ -1871,4 +2435,118 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn test_binary_op_temporal_arithmetic() -> Result<(), TypeError> {
+        let timestamp = DataValue::Utf8(Some("2023-01-31 12:00:00".to_string()))
+            .cast(&LogicalType::DateTime)?;
+        let one_day = DataValue::Interval(Some(Interval::new(0, 1, 0)));
+        let one_month = DataValue::Interval(Some(Interval::new(1, 0, 0)));
+
+        assert_eq!(
+            binary_op(&timestamp, &one_day, &BinaryOperator::Plus)?.to_string(),
+            "2023-02-01 12:00:00"
+        );
+        assert_eq!(
+            binary_op(&one_month, &timestamp, &BinaryOperator::Plus)?.to_string(),
+            "2023-02-28 12:00:00"
+        );
+        assert_eq!(
+            binary_op(&timestamp, &one_day, &BinaryOperator::Minus)?.to_string(),
+            "2023-01-30 12:00:00"
+        );
+        assert_eq!(
+            binary_op(
+                &timestamp,
+                &DataValue::Interval(None),
+                &BinaryOperator::Plus
+            )?,
+            DataValue::Date64(None)
+        );
+
+        let date_1 = DataValue::Utf8(Some("2023-03-01".to_string())).cast(&LogicalType::Date)?;
+        let date_2 = DataValue::Utf8(Some("2023-01-01".to_string())).cast(&LogicalType::Date)?;
+
+        assert_eq!(
+            binary_op(&date_1, &date_2, &BinaryOperator::Minus)?,
+            DataValue::Interval(Some(Interval::new(0, 59, 0)))
+        );
+        assert_eq!(
+            binary_op(&date_2, &date_1, &BinaryOperator::Minus)?,
+            DataValue::Interval(Some(Interval::new(0, -59, 0)))
+        );
+        assert_eq!(
+            binary_op(&one_day, &one_month, &BinaryOperator::Plus)?,
+            DataValue::Interval(Some(Interval::new(1, 1, 0)))
+        );
+        assert_eq!(
+            binary_op(&one_month, &one_day, &BinaryOperator::Gt)?,
+            DataValue::Boolean(Some(true))
+        );
+        assert!(matches!(
+            binary_op(&one_month, &one_day, &BinaryOperator::Multiply),
+            Err(TypeError::InvalidType)
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_binary_op_special_float_compare() -> Result<(), TypeError> {
+        let compare = |v1: f64, op: BinaryOperator, v2: f64| {
+            binary_op(
+                &DataValue::Float64(Some(v1)),
+                &DataValue::Float64(Some(v2)),
+                &op,
+            )
+        };
+        let truth = DataValue::Boolean(Some(true));
+        let falsity = DataValue::Boolean(Some(false));
+
+        assert_eq!(compare(f64::NAN, BinaryOperator::Eq, f64::NAN)?, truth);
+        assert_eq!(
+            compare(f64::NAN, BinaryOperator::NotEq, -f64::NAN)?,
+            falsity
+        );
+        assert_eq!(compare(f64::NAN, BinaryOperator::Gt, f64::INFINITY)?, truth);
+        assert_eq!(compare(1.0, BinaryOperator::Lt, f64::NAN)?, truth);
+        assert_eq!(compare(f64::NAN, BinaryOperator::LtEq, 1.0)?, falsity);
+        assert_eq!(
+            compare(f64::NEG_INFINITY, BinaryOperator::Lt, f64::MIN)?,
+            truth
+        );
+        assert_eq!(
+            compare(f64::INFINITY, BinaryOperator::GtEq, f64::MAX)?,
+            truth
+        );
+        assert_eq!(compare(-0.0, BinaryOperator::Eq, 0.0)?, truth);
+        assert_eq!(
+            binary_op(
+                &DataValue::Float32(Some(f32::NAN)),
+                &DataValue::Float32(Some(f32::INFINITY)),
+                &BinaryOperator::Gt
+            )?,
+            truth
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_like_match() {
+        assert!(like_match("KipSQL", "Kip%"));
+        assert!(like_match("KipSQL", "K%L"));
+        assert!(like_match("KipDB", "KipD_"));
+        assert!(like_match("", "%"));
+        // the whole value matches
+        assert!(!like_match("xKipSQL", "Kip%"));
+        assert!(!like_match("KipSQLx", "K%L"));
+        assert!(!like_match("KipDBx", "KipD_"));
+        // the other characters are literal
+        assert!(like_match("a.b", "a.b"));
+        assert!(!like_match("axb", "a.b"));
+        assert!(like_match("(a)+", "(a)+"));
+        assert!(like_match("a\nb", "a%b"));
+        assert!(like_match("a\nb", "a_b"));
+    }
 }
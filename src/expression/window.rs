@@ -0,0 +1,112 @@
+use crate::expression::ScalarExpression;
+use crate::planner::operator::sort::SortField;
+use crate::types::value::ValueRef;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fmt::Formatter;
+
+/// The rows an aggregate is computed over for each row, e.g.
+/// `OVER (PARTITION BY a ORDER BY b ROWS BETWEEN 2 PRECEDING AND CURRENT ROW)`.
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Serialize, Deserialize)]
+pub struct WindowSpec {
+    pub partition_by: Vec<ScalarExpression>,
+    pub order_by: Vec<SortField>,
+    pub frame: WindowFrame,
+}
+
+/// The frame of a window, the bounds are relative to the row being computed.
+///
+/// Without a frame in the SQL, it is `RANGE BETWEEN UNBOUNDED PRECEDING AND CURRENT ROW` with an
+/// `ORDER BY`, so that the aggregate is a running one, and the whole partition without.
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Serialize, Deserialize)]
+pub struct WindowFrame {
+    pub units: WindowFrameUnits,
+    pub start: WindowFrameBound,
+    pub end: WindowFrameBound,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum WindowFrameUnits {
+    /// The offsets are numbers of rows.
+    Rows,
+    /// The offsets are distances from the value of the only `ORDER BY` expr,
+    /// the rows with the same value are all in or out of the frame.
+    Range,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum WindowFrameBound {
+    UnboundedPreceding,
+    Preceding(ValueRef),
+    CurrentRow,
+    Following(ValueRef),
+    UnboundedFollowing,
+}
+
+impl WindowFrame {
+    pub fn new(units: WindowFrameUnits, start: WindowFrameBound, end: WindowFrameBound) -> Self {
+        WindowFrame { units, start, end }
+    }
+
+    /// The frame of a window without one in the SQL.
+    pub fn default_of(order_by: &[SortField]) -> Self {
+        if order_by.is_empty() {
+            WindowFrame::new(
+                WindowFrameUnits::Rows,
+                WindowFrameBound::UnboundedPreceding,
+                WindowFrameBound::UnboundedFollowing,
+            )
+        } else {
+            WindowFrame::new(
+                WindowFrameUnits::Range,
+                WindowFrameBound::UnboundedPreceding,
+                WindowFrameBound::CurrentRow,
+            )
+        }
+    }
+}
+
+impl fmt::Display for WindowSpec {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let mut clauses = Vec::new();
+
+        if !self.partition_by.is_empty() {
+            clauses.push(format!(
+                "PARTITION BY {}",
+                self.partition_by.iter().join(", ")
+            ));
+        }
+        if !self.order_by.is_empty() {
+            clauses.push(format!("ORDER BY {}", self.order_by.iter().join(", ")));
+        }
+        if self.frame != WindowFrame::default_of(&self.order_by) {
+            clauses.push(self.frame.to_string());
+        }
+
+        write!(f, "{}", clauses.join(" "))
+    }
+}
+
+impl fmt::Display for WindowFrame {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let units = match self.units {
+            WindowFrameUnits::Rows => "ROWS",
+            WindowFrameUnits::Range => "RANGE",
+        };
+
+        write!(f, "{} BETWEEN {} AND {}", units, self.start, self.end)
+    }
+}
+
+impl fmt::Display for WindowFrameBound {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            WindowFrameBound::UnboundedPreceding => write!(f, "UNBOUNDED PRECEDING"),
+            WindowFrameBound::Preceding(offset) => write!(f, "{} PRECEDING", offset),
+            WindowFrameBound::CurrentRow => write!(f, "CURRENT ROW"),
+            WindowFrameBound::Following(offset) => write!(f, "{} FOLLOWING", offset),
+            WindowFrameBound::UnboundedFollowing => write!(f, "UNBOUNDED FOLLOWING"),
+        }
+    }
+}
@@ -28,7 +28,7 @@ macro_rules! implement_from_tuple {
             fn from(tuple: Tuple) -> Self {
                 fn try_get<T: 'static>(tuple: &Tuple, field_name: &str) -> Option<DataValue> {
                     let ty = LogicalType::type_trans::<T>()?;
-                    let (idx, _) = tuple.columns
+                    let (idx, _) = tuple.schema_ref
                         .iter()
                         .enumerate()
                         .find(|(_, col)| col.name() == field_name)?;
@@ -57,7 +57,7 @@ macro_rules! implement_from_tuple {
 mod test {
     use crate::catalog::{ColumnCatalog, ColumnDesc};
     use crate::types::tuple::Tuple;
-    use crate::types::value::DataValue;
+    use crate::types::value::{DataValue, ValueRef};
     use crate::types::LogicalType;
     use std::sync::Arc;
 
@@ -77,13 +77,13 @@ mod test {
             )),
         ];
         let values = vec![
-            Arc::new(DataValue::Int32(Some(9))),
-            Arc::new(DataValue::Utf8(Some("LOL".to_string()))),
+            ValueRef::new(DataValue::Int32(Some(9))),
+            ValueRef::new(DataValue::Utf8(Some("LOL".to_string()))),
         ];
 
         Tuple {
             id: None,
-            columns,
+            schema_ref: Arc::new(columns),
             values,
         }
     }
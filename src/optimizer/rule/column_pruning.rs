@@ -6,11 +6,10 @@ use crate::optimizer::core::rule::Rule;
 use crate::optimizer::heuristic::graph::{HepGraph, HepNodeId};
 use crate::optimizer::OptimizerError;
 use crate::planner::operator::Operator;
-use crate::types::value::DataValue;
+use crate::types::value::{DataValue, ValueRef};
 use crate::types::LogicalType;
 use lazy_static::lazy_static;
 use std::collections::HashSet;
-use std::sync::Arc;
 
 lazy_static! {
     static ref COLUMN_PRUNING_RULE: Pattern = {
@@ -53,7 +52,7 @@ impl ColumnPruning {
                     Self::clear_exprs(column_references, &mut op.agg_calls);
 
                     if op.agg_calls.is_empty() && op.groupby_exprs.is_empty() {
-                        let value = Arc::new(DataValue::Utf8(Some("*".to_string())));
+                        let value = ValueRef::new(DataValue::Utf8(Some("*".to_string())));
                         // only single COUNT(*) is not depend on any column
                         // removed all expressions from the aggregate: push a COUNT(*)
                         op.agg_calls.push(ScalarExpression::AggCall {
@@ -93,7 +92,11 @@ impl ColumnPruning {
                     Self::clear_exprs(column_references, &mut op.columns);
                 }
             }
-            Operator::Limit(_) | Operator::Join(_) | Operator::Filter(_) => {
+            Operator::Limit(_)
+            | Operator::Join(_)
+            | Operator::Filter(_)
+            | Operator::DistinctOn(_)
+            | Operator::Window(_) => {
                 for column in operator.referenced_columns(false) {
                     column_references.insert(column.summary().clone());
                 }
@@ -109,13 +112,24 @@ impl ColumnPruning {
 
                 Self::recollect_apply(op_ref_columns, true, graph.children_at(node_id)[0], graph);
             }
+            // the tuples are compared by all their values
+            Operator::SetOperation(_) => Self::recollect_apply(vec![], true, node_id, graph),
+            // the query of `CREATE TABLE .. AS SELECT ..`
+            Operator::CreateTable(_) => Self::recollect_apply(vec![], true, node_id, graph),
+            // the plan is shown as it would be executed
+            Operator::Explain(_) => Self::recollect_apply(vec![], true, node_id, graph),
             // DDL Single Plan
-            Operator::CreateTable(_)
+            Operator::CreateIndex(_)
             | Operator::DropTable(_)
             | Operator::Truncate(_)
+            | Operator::AlterTable(_)
+            | Operator::Comment(_)
             | Operator::Show(_)
+            | Operator::ShowCreateTable(_)
+            | Operator::ShowIndex(_)
             | Operator::CopyFromFile(_)
-            | Operator::CopyToFile(_) => (),
+            | Operator::CopyToFile(_)
+            | Operator::SetVariable(_) => (),
         }
     }
 
@@ -2,9 +2,9 @@ use crate::expression::{BinaryOperator, ScalarExpression};
 use crate::optimizer::core::pattern::{Pattern, PatternChildrenPredicate};
 use crate::optimizer::core::rule::Rule;
 use crate::optimizer::heuristic::graph::{HepGraph, HepNodeId};
-use crate::optimizer::rule::is_subset_exprs;
 use crate::optimizer::OptimizerError;
 use crate::planner::operator::filter::FilterOperator;
+use crate::planner::operator::project::ProjectOperator;
 use crate::planner::operator::Operator;
 use crate::types::LogicalType;
 use lazy_static::lazy_static;
@@ -30,9 +30,100 @@ lazy_static! {
     };
 }
 
-/// Combine two adjacent project operators into one.
+/// Combine two adjacent project operators into one, the columns of the child the parent
+/// refers to are replaced by the expressions of the child producing them.
 pub struct CollapseProject;
 
+impl CollapseProject {
+    /// `expr` evaluated on the tuples the child is evaluated on, `None` when it refers to what the
+    /// child does not produce. The columns are found as the evaluator finds them, by name and
+    /// table first and then by name, so a column renamed by the child is its aliased expression.
+    fn compose(
+        expr: &ScalarExpression,
+        child_exprs: &[ScalarExpression],
+    ) -> Option<ScalarExpression> {
+        let column = expr.output_columns();
+        let produced = child_exprs
+            .iter()
+            .find(|child_expr| {
+                let child_column = child_expr.output_columns();
+
+                child_column.name() == column.name()
+                    && child_column.table_name() == column.table_name()
+            })
+            .or_else(|| {
+                child_exprs
+                    .iter()
+                    .find(|child_expr| child_expr.output_columns().name() == column.name())
+            });
+        if let Some(child_expr) = produced {
+            return Some(child_expr.clone());
+        }
+        let compose = |expr: &ScalarExpression| Self::compose(expr, child_exprs).map(Box::new);
+        let compose_all = |exprs: &[ScalarExpression]| {
+            exprs
+                .iter()
+                .map(|expr| Self::compose(expr, child_exprs))
+                .collect::<Option<Vec<_>>>()
+        };
+
+        Some(match expr {
+            ScalarExpression::Constant(_) => expr.clone(),
+            // the columns, aggregates and windows are only read from the tuples of the child
+            ScalarExpression::ColumnRef(_)
+            | ScalarExpression::AggCall { .. }
+            | ScalarExpression::WindowCall { .. } => return None,
+            ScalarExpression::Alias { expr, alias } => ScalarExpression::Alias {
+                expr: compose(expr)?,
+                alias: alias.clone(),
+            },
+            ScalarExpression::TypeCast { expr, ty } => ScalarExpression::TypeCast {
+                expr: compose(expr)?,
+                ty: *ty,
+            },
+            ScalarExpression::IsNull { negated, expr } => ScalarExpression::IsNull {
+                negated: *negated,
+                expr: compose(expr)?,
+            },
+            ScalarExpression::Unary { op, expr, ty } => ScalarExpression::Unary {
+                op: *op,
+                expr: compose(expr)?,
+                ty: *ty,
+            },
+            ScalarExpression::Binary {
+                op,
+                left_expr,
+                right_expr,
+                ty,
+            } => ScalarExpression::Binary {
+                op: *op,
+                left_expr: compose(left_expr)?,
+                right_expr: compose(right_expr)?,
+                ty: *ty,
+            },
+            ScalarExpression::In {
+                negated,
+                expr,
+                args,
+            } => ScalarExpression::In {
+                negated: *negated,
+                expr: compose(expr)?,
+                args: compose_all(args)?,
+            },
+            ScalarExpression::Function { function, args } => ScalarExpression::Function {
+                function: function.clone(),
+                args: compose_all(args)?,
+            },
+            ScalarExpression::Builtin { kind, args, ty } => ScalarExpression::Builtin {
+                kind: *kind,
+                args: compose_all(args)?,
+                ty: *ty,
+            },
+            ScalarExpression::Tuple(args) => ScalarExpression::Tuple(compose_all(args)?),
+        })
+    }
+}
+
 impl Rule for CollapseProject {
     fn pattern(&self) -> &Pattern {
         &COLLAPSE_PROJECT_RULE
@@ -42,10 +133,15 @@ impl Rule for CollapseProject {
         if let Operator::Project(op) = graph.operator(node_id) {
             let child_id = graph.children_at(node_id)[0];
             if let Operator::Project(child_op) = graph.operator(child_id) {
-                if is_subset_exprs(&op.exprs, &child_op.exprs) {
+                let exprs = op
+                    .exprs
+                    .iter()
+                    .map(|expr| Self::compose(expr, &child_op.exprs))
+                    .collect::<Option<Vec<_>>>();
+
+                if let Some(exprs) = exprs {
+                    graph.replace_node(node_id, Operator::Project(ProjectOperator { exprs }));
                     graph.remove_node(child_id, false);
-                } else {
-                    graph.remove_node(node_id, false);
                 }
             }
         }
@@ -95,9 +191,8 @@ mod tests {
     use crate::optimizer::heuristic::optimizer::HepOptimizer;
     use crate::optimizer::rule::RuleImpl;
     use crate::planner::operator::Operator;
-    use crate::types::value::DataValue;
+    use crate::types::value::{DataValue, ValueRef};
     use crate::types::LogicalType;
-    use std::sync::Arc;
 
     #[tokio::test]
     async fn test_collapse_project() -> Result<(), DatabaseError> {
@@ -136,6 +231,46 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_collapse_project_composes_exprs() -> Result<(), DatabaseError> {
+        let plan =
+            select_sql_run("select c1 + 1, c2 from (select c1, c2 * 2 as c2 from t1) s").await?;
+        let child_exprs = match &plan.childrens[0].operator {
+            Operator::Project(op) => op.exprs.clone(),
+            _ => unreachable!("Should be a project operator"),
+        };
+
+        let best_plan = HepOptimizer::new(plan)
+            .batch(
+                "test_collapse_project".to_string(),
+                HepBatchStrategy::once_topdown(),
+                vec![RuleImpl::CollapseProject],
+            )
+            .find_best()?;
+
+        if let Operator::Project(op) = &best_plan.operator {
+            assert_eq!(op.exprs.len(), 2);
+            match &op.exprs[0] {
+                ScalarExpression::Binary {
+                    op: BinaryOperator::Plus,
+                    left_expr,
+                    ..
+                } => assert_eq!(left_expr.as_ref(), &child_exprs[0]),
+                expr => unreachable!("Should be a binary expression, not {}", expr),
+            }
+            // the column renamed by the child is the expression it is the alias of
+            assert_eq!(op.exprs[1], child_exprs[1]);
+        } else {
+            unreachable!("Should be a project operator")
+        }
+        assert!(matches!(
+            &best_plan.childrens[0].operator,
+            Operator::Scan(_)
+        ));
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_combine_filter() -> Result<(), DatabaseError> {
         let plan = select_sql_run("select * from t1 where c1 > 1").await?;
@@ -151,8 +286,8 @@ mod tests {
         if let Operator::Filter(op) = &mut new_filter_op {
             op.predicate = ScalarExpression::Binary {
                 op: BinaryOperator::Eq,
-                left_expr: Box::new(Constant(Arc::new(DataValue::Int8(Some(1))))),
-                right_expr: Box::new(Constant(Arc::new(DataValue::Int8(Some(1))))),
+                left_expr: Box::new(Constant(ValueRef::new(DataValue::Int8(Some(1))))),
+                right_expr: Box::new(Constant(ValueRef::new(DataValue::Int8(Some(1))))),
                 ty: LogicalType::Boolean,
             }
         } else {
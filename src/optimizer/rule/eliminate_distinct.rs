@@ -0,0 +1,207 @@
+use crate::catalog::ColumnRef;
+use crate::expression::ScalarExpression;
+use crate::optimizer::core::pattern::{Pattern, PatternChildrenPredicate};
+use crate::optimizer::core::rule::Rule;
+use crate::optimizer::heuristic::graph::{HepGraph, HepNodeId};
+use crate::optimizer::OptimizerError;
+use crate::planner::operator::join::JoinType;
+use crate::planner::operator::project::ProjectOperator;
+use crate::planner::operator::Operator;
+use itertools::Itertools;
+use lazy_static::lazy_static;
+
+lazy_static! {
+    static ref ELIMINATE_DISTINCT_RULE: Pattern = {
+        Pattern {
+            predicate: |op| match op {
+                Operator::Aggregate(op) => op.agg_calls.is_empty() && op.grouping_sets.is_empty(),
+                _ => false,
+            },
+            children: PatternChildrenPredicate::None,
+        }
+    };
+}
+
+/// Replace a `DISTINCT`, or a group by without aggregates, by the project of its group by exprs
+/// when the tuples of its child are already unique on them, for example:
+/// `select distinct c1, c2 from t1` where `c1` is the primary key of `t1`.
+pub struct EliminateDistinct;
+
+impl EliminateDistinct {
+    /// The sets of columns of the tuples produced by `node_id` no two tuples have the same values
+    /// of, an empty set when there is at most one tuple. The unique indexes count only on the
+    /// columns that are not nullable, as `DISTINCT` does not tell the nulls apart.
+    fn unique_keys(graph: &HepGraph, node_id: HepNodeId) -> Vec<Vec<ColumnRef>> {
+        let child_keys = |i: usize| {
+            graph
+                .children_at(node_id)
+                .get(i)
+                .map(|child_id| Self::unique_keys(graph, *child_id))
+                .unwrap_or_default()
+        };
+
+        match graph.operator(node_id) {
+            Operator::Scan(op) => {
+                if !op.agg_calls.is_empty() {
+                    return vec![vec![]];
+                }
+                op.index_metas
+                    .iter()
+                    .filter(|index_meta| {
+                        (index_meta.is_primary || index_meta.is_unique) && index_meta.expr.is_none()
+                    })
+                    .filter_map(|index_meta| {
+                        index_meta
+                            .column_ids
+                            .iter()
+                            .map(|col_id| {
+                                op.columns.iter().find_map(|expr| match expr {
+                                    ScalarExpression::ColumnRef(col)
+                                        if col.id() == Some(*col_id)
+                                            && (index_meta.is_primary || !col.nullable) =>
+                                    {
+                                        Some(col.clone())
+                                    }
+                                    _ => None,
+                                })
+                            })
+                            .collect::<Option<Vec<_>>>()
+                    })
+                    .collect_vec()
+            }
+            Operator::Filter(_)
+            | Operator::Sort(_)
+            | Operator::Limit(_)
+            | Operator::DistinctOn(_)
+            | Operator::Window(_) => child_keys(0),
+            Operator::Project(op) => child_keys(0)
+                .into_iter()
+                .filter_map(|key| {
+                    key.iter()
+                        .map(|col| {
+                            op.exprs.iter().find_map(|expr| match expr.unpack_alias() {
+                                ScalarExpression::ColumnRef(c) if c == col => {
+                                    Some(expr.output_columns())
+                                }
+                                _ => None,
+                            })
+                        })
+                        .collect::<Option<Vec<_>>>()
+                })
+                .collect_vec(),
+            Operator::Aggregate(op) if op.grouping_sets.is_empty() => {
+                vec![op
+                    .groupby_exprs
+                    .iter()
+                    .map(ScalarExpression::output_columns)
+                    .collect_vec()]
+            }
+            Operator::Join(op) => match op.join_type {
+                JoinType::Semi | JoinType::Anti | JoinType::Single => child_keys(0),
+                _ => {
+                    let right_keys = child_keys(1);
+
+                    child_keys(0)
+                        .into_iter()
+                        .cartesian_product(right_keys)
+                        .map(|(left_key, right_key)| {
+                            left_key.into_iter().chain(right_key).collect()
+                        })
+                        .collect_vec()
+                }
+            },
+            _ => vec![],
+        }
+    }
+}
+
+impl Rule for EliminateDistinct {
+    fn pattern(&self) -> &Pattern {
+        &ELIMINATE_DISTINCT_RULE
+    }
+
+    fn apply(&self, node_id: HepNodeId, graph: &mut HepGraph) -> Result<(), OptimizerError> {
+        if let Operator::Aggregate(op) = graph.operator(node_id) {
+            let child_id = graph.children_at(node_id)[0];
+            let distinct_columns = op
+                .groupby_exprs
+                .iter()
+                .map(|expr| expr.unpack_alias().output_columns())
+                .collect_vec();
+            let is_unique = Self::unique_keys(graph, child_id)
+                .iter()
+                .any(|key| key.iter().all(|col| distinct_columns.contains(col)));
+
+            if is_unique {
+                let exprs = op.groupby_exprs.clone();
+
+                graph.replace_node(node_id, Operator::Project(ProjectOperator { exprs }));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::binder::test::select_sql_run;
+    use crate::db::DatabaseError;
+    use crate::optimizer::heuristic::batch::HepBatchStrategy;
+    use crate::optimizer::heuristic::optimizer::HepOptimizer;
+    use crate::optimizer::rule::RuleImpl;
+    use crate::planner::operator::Operator;
+    use crate::planner::LogicalPlan;
+
+    fn has_distinct(plan: &LogicalPlan) -> bool {
+        matches!(&plan.operator, Operator::Aggregate(op) if op.agg_calls.is_empty())
+            || plan.childrens.iter().any(has_distinct)
+    }
+
+    async fn eliminate_distinct(sql: &str) -> Result<LogicalPlan, DatabaseError> {
+        let plan = select_sql_run(sql).await?;
+
+        Ok(HepOptimizer::new(plan)
+            .batch(
+                "test_eliminate_distinct".to_string(),
+                HepBatchStrategy::once_topdown(),
+                vec![RuleImpl::EliminateDistinct],
+            )
+            .find_best()?)
+    }
+
+    #[tokio::test]
+    async fn test_eliminate_distinct() -> Result<(), DatabaseError> {
+        // the primary key
+        assert!(!has_distinct(
+            &eliminate_distinct("select distinct c1 from t1").await?
+        ));
+        assert!(!has_distinct(
+            &eliminate_distinct("select distinct c2, c1 from t1 where c1 > 1").await?
+        ));
+        // the unique key of a not nullable column
+        assert!(!has_distinct(
+            &eliminate_distinct("select distinct c2 from t1").await?
+        ));
+        // grouped by the key of the sub query
+        assert!(!has_distinct(
+            &eliminate_distinct(
+                "select distinct c4, n from (select c4, count(c3) as n from t2 group by c4) s"
+            )
+            .await?
+        ));
+        // the keys of both sides of a join
+        assert!(!has_distinct(
+            &eliminate_distinct("select distinct c1, c3 from t1 join t2 on c2 = c4").await?
+        ));
+
+        assert!(has_distinct(
+            &eliminate_distinct("select distinct c4 from t2").await?
+        ));
+        assert!(has_distinct(
+            &eliminate_distinct("select distinct c1 from t1 join t2 on c2 = c4").await?
+        ));
+
+        Ok(())
+    }
+}
@@ -1,22 +1,31 @@
-use crate::expression::ScalarExpression;
 use crate::optimizer::core::pattern::Pattern;
 use crate::optimizer::core::rule::Rule;
 use crate::optimizer::heuristic::graph::{HepGraph, HepNodeId};
 use crate::optimizer::rule::column_pruning::ColumnPruning;
 use crate::optimizer::rule::combine_operators::{CollapseProject, CombineFilter};
+use crate::optimizer::rule::eliminate_distinct::EliminateDistinct;
+use crate::optimizer::rule::pushdown_aggregate::PushAggregateIntoScan;
 use crate::optimizer::rule::pushdown_limit::{
-    EliminateLimits, LimitProjectTranspose, PushLimitIntoScan, PushLimitThroughJoin,
+    EliminateLimits, LimitProjectTranspose, PushLimitIntoOrderedScan, PushLimitIntoScan,
+    PushLimitThroughJoin,
 };
+use crate::optimizer::rule::pushdown_predicates::PushFilterIntoScan;
 use crate::optimizer::rule::pushdown_predicates::PushPredicateIntoScan;
 use crate::optimizer::rule::pushdown_predicates::PushPredicateThroughJoin;
+use crate::optimizer::rule::pushdown_project::PushProjectIntoScan;
+use crate::optimizer::rule::semi_join::IndexProbeSemiJoin;
 use crate::optimizer::rule::simplification::ConstantCalculation;
 use crate::optimizer::rule::simplification::SimplifyFilter;
 use crate::optimizer::OptimizerError;
 
 mod column_pruning;
 mod combine_operators;
+mod eliminate_distinct;
+mod pushdown_aggregate;
 mod pushdown_limit;
 mod pushdown_predicates;
+mod pushdown_project;
+mod semi_join;
 mod simplification;
 
 #[derive(Debug, Copy, Clone)]
@@ -25,15 +34,29 @@ pub enum RuleImpl {
     // Combine operators
     CollapseProject,
     CombineFilter,
+    // Tips: need to be used before `CollapseProject`
+    EliminateDistinct,
     // PushDown limit
     LimitProjectTranspose,
     EliminateLimits,
     PushLimitThroughJoin,
     PushLimitIntoTableScan,
+    PushLimitIntoOrderedScan,
+    // PushDown aggregate
+    // Tips: need to be used after the other rules pushing into `Scan`
+    PushAggregateIntoScan,
+    // PushDown project
+    // Tips: need to be used after all the other rules
+    PushProjectIntoScan,
     // PushDown predicates
     PushPredicateThroughJoin,
     // Tips: need to be used with `SimplifyFilter`
     PushPredicateIntoScan,
+    // Tips: need to be used after `PushPredicateIntoScan` and before the other rules pushing into `Scan`
+    PushFilterIntoScan,
+    // Semi join
+    // Tips: need to be used after the rules pushing into `Scan`
+    IndexProbeSemiJoin,
     // Simplification
     SimplifyFilter,
     ConstantCalculation,
@@ -45,12 +68,18 @@ impl Rule for RuleImpl {
             RuleImpl::ColumnPruning => ColumnPruning.pattern(),
             RuleImpl::CollapseProject => CollapseProject.pattern(),
             RuleImpl::CombineFilter => CombineFilter.pattern(),
+            RuleImpl::EliminateDistinct => EliminateDistinct.pattern(),
             RuleImpl::LimitProjectTranspose => LimitProjectTranspose.pattern(),
             RuleImpl::EliminateLimits => EliminateLimits.pattern(),
             RuleImpl::PushLimitThroughJoin => PushLimitThroughJoin.pattern(),
             RuleImpl::PushLimitIntoTableScan => PushLimitIntoScan.pattern(),
+            RuleImpl::PushLimitIntoOrderedScan => PushLimitIntoOrderedScan.pattern(),
+            RuleImpl::PushAggregateIntoScan => PushAggregateIntoScan.pattern(),
+            RuleImpl::PushProjectIntoScan => PushProjectIntoScan.pattern(),
             RuleImpl::PushPredicateThroughJoin => PushPredicateThroughJoin.pattern(),
             RuleImpl::PushPredicateIntoScan => PushPredicateIntoScan.pattern(),
+            RuleImpl::PushFilterIntoScan => PushFilterIntoScan.pattern(),
+            RuleImpl::IndexProbeSemiJoin => IndexProbeSemiJoin.pattern(),
             RuleImpl::SimplifyFilter => SimplifyFilter.pattern(),
             RuleImpl::ConstantCalculation => ConstantCalculation.pattern(),
         }
@@ -61,19 +90,20 @@ impl Rule for RuleImpl {
             RuleImpl::ColumnPruning => ColumnPruning.apply(node_id, graph),
             RuleImpl::CollapseProject => CollapseProject.apply(node_id, graph),
             RuleImpl::CombineFilter => CombineFilter.apply(node_id, graph),
+            RuleImpl::EliminateDistinct => EliminateDistinct.apply(node_id, graph),
             RuleImpl::LimitProjectTranspose => LimitProjectTranspose.apply(node_id, graph),
             RuleImpl::EliminateLimits => EliminateLimits.apply(node_id, graph),
             RuleImpl::PushLimitThroughJoin => PushLimitThroughJoin.apply(node_id, graph),
             RuleImpl::PushLimitIntoTableScan => PushLimitIntoScan.apply(node_id, graph),
+            RuleImpl::PushLimitIntoOrderedScan => PushLimitIntoOrderedScan.apply(node_id, graph),
+            RuleImpl::PushAggregateIntoScan => PushAggregateIntoScan.apply(node_id, graph),
+            RuleImpl::PushProjectIntoScan => PushProjectIntoScan.apply(node_id, graph),
             RuleImpl::PushPredicateThroughJoin => PushPredicateThroughJoin.apply(node_id, graph),
             RuleImpl::SimplifyFilter => SimplifyFilter.apply(node_id, graph),
             RuleImpl::PushPredicateIntoScan => PushPredicateIntoScan.apply(node_id, graph),
+            RuleImpl::PushFilterIntoScan => PushFilterIntoScan.apply(node_id, graph),
+            RuleImpl::IndexProbeSemiJoin => IndexProbeSemiJoin.apply(node_id, graph),
             RuleImpl::ConstantCalculation => ConstantCalculation.apply(node_id, graph),
         }
     }
 }
-
-/// Return true when left is subset of right
-pub fn is_subset_exprs(left: &[ScalarExpression], right: &[ScalarExpression]) -> bool {
-    left.iter().all(|l| right.contains(l))
-}
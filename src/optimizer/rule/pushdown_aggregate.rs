@@ -0,0 +1,198 @@
+use crate::expression::agg::AggKind;
+use crate::expression::ScalarExpression;
+use crate::optimizer::core::pattern::Pattern;
+use crate::optimizer::core::pattern::PatternChildrenPredicate;
+use crate::optimizer::core::rule::Rule;
+use crate::optimizer::heuristic::graph::{HepGraph, HepNodeId};
+use crate::optimizer::OptimizerError;
+use crate::planner::operator::Operator;
+use lazy_static::lazy_static;
+
+lazy_static! {
+    static ref PUSH_AGGREGATE_INTO_SCAN_RULE: Pattern = {
+        Pattern {
+            predicate: |op| matches!(op, Operator::Aggregate(_)),
+            children: PatternChildrenPredicate::None,
+        }
+    };
+}
+
+/// Push down an `Aggregate` without group by into the `Scan` below it,
+/// e.g. `select count(*), sum(c1) from t1`, so the tuples are aggregated while reading.
+///
+/// A `Filter` in between is only passed if the index scan returns exactly the tuples
+/// it accepts. The scan yields the aggregated tuple afterwards, so this has to run after
+/// the rules pushing predicates and limits into the scan.
+pub struct PushAggregateIntoScan;
+
+impl PushAggregateIntoScan {
+    fn is_pushable(agg_call: &ScalarExpression) -> bool {
+        matches!(
+            agg_call,
+            ScalarExpression::AggCall { kind, .. } if !matches!(kind, AggKind::Grouping)
+        )
+    }
+}
+
+impl Rule for PushAggregateIntoScan {
+    fn pattern(&self) -> &Pattern {
+        &PUSH_AGGREGATE_INTO_SCAN_RULE
+    }
+
+    fn apply(&self, node_id: HepNodeId, graph: &mut HepGraph) -> Result<(), OptimizerError> {
+        let agg_calls = match graph.operator(node_id) {
+            Operator::Aggregate(op)
+                if op.groupby_exprs.is_empty()
+                    && op.grouping_sets.is_empty()
+                    && !op.agg_calls.is_empty()
+                    && op.agg_calls.iter().all(Self::is_pushable) =>
+            {
+                op.agg_calls.clone()
+            }
+            _ => return Ok(()),
+        };
+        let mut scan_id = graph.children_at(node_id)[0];
+        let mut filter_id = None;
+
+        if let Operator::Filter(_) = graph.operator(scan_id) {
+            filter_id = Some(scan_id);
+            scan_id = graph.children_at(scan_id)[0];
+        }
+        if let Operator::Scan(scan_op) = graph.operator(scan_id) {
+            // the locks are taken on the tuples the scan yields
+            if !scan_op.agg_calls.is_empty() || scan_op.lock {
+                return Ok(());
+            }
+            if let Some(Operator::Filter(filter_op)) = filter_id.map(|id| graph.operator(id)) {
+                match &scan_op.index_by {
+                    Some((index_meta, _))
+                        if index_meta.column_ids.len() == 1
+                            && filter_op.predicate.is_bounded_by(&index_meta.column_ids[0]) => {}
+                    _ => return Ok(()),
+                }
+            }
+            let mut new_scan_op = scan_op.clone();
+
+            new_scan_op.agg_calls = agg_calls;
+
+            graph.remove_node(node_id, false);
+            if let Some(filter_id) = filter_id {
+                graph.remove_node(filter_id, false);
+            }
+            graph.replace_node(scan_id, Operator::Scan(new_scan_op));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::binder::{Binder, BinderContext};
+    use crate::db::{Database, DatabaseError};
+    use crate::execution::executor::{build, try_collect};
+    use crate::optimizer::heuristic::batch::HepBatchStrategy;
+    use crate::optimizer::heuristic::optimizer::HepOptimizer;
+    use crate::optimizer::rule::RuleImpl;
+    use crate::planner::operator::Operator;
+    use crate::planner::LogicalPlan;
+    use crate::storage::Storage;
+    use std::cell::RefCell;
+    use tempfile::TempDir;
+
+    fn optimize(plan: LogicalPlan, push_aggregate: bool) -> Result<LogicalPlan, DatabaseError> {
+        let mut optimizer = HepOptimizer::new(plan)
+            .batch(
+                "test_column_pruning".to_string(),
+                HepBatchStrategy::once_topdown(),
+                vec![RuleImpl::ColumnPruning],
+            )
+            .batch(
+                "test_push_predicate_into_scan".to_string(),
+                HepBatchStrategy::fix_point_topdown(10),
+                vec![RuleImpl::PushPredicateIntoScan],
+            );
+        if push_aggregate {
+            optimizer = optimizer.batch(
+                "test_push_aggregate_into_scan".to_string(),
+                HepBatchStrategy::once_topdown(),
+                vec![RuleImpl::PushAggregateIntoScan],
+            );
+        }
+
+        Ok(optimizer.find_best()?)
+    }
+
+    fn find_scan(plan: &LogicalPlan) -> Option<&LogicalPlan> {
+        match plan.operator {
+            Operator::Scan(_) => Some(plan),
+            _ => plan.childrens.iter().find_map(find_scan),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_push_aggregate_into_scan() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+        let _ = kipsql
+            .run("create table t1 (a int primary key, b int null, c varchar null)")
+            .await?;
+        let _ = kipsql
+            .run(
+                "insert into t1 values (0, 1, 'x'), (1, null, 'y'), (2, 5, null), \
+                 (3, -2, 'x'), (4, 10, 'z')",
+            )
+            .await?;
+
+        for (sql, is_pushed) in [
+            ("select count(*) from t1", true),
+            ("select count(*), sum(b), min(b), max(c) from t1", true),
+            ("select count(b), avg(b), sum(distinct b) from t1", true),
+            ("select sum(b) + 1 from t1", true),
+            ("select count(*), max(b) from t1 where a > 1", true),
+            (
+                "select count(*), max(b) from t1 where a > 1 and b > 1",
+                false,
+            ),
+            ("select count(*) from t1 where b > 1", false),
+            ("select count(*) from t1 where a > 10", true),
+            ("select b, count(*) from t1 group by b order by b", false),
+        ] {
+            let storage = &kipsql.storage;
+            let transaction = RefCell::new(storage.transaction().await?);
+            let functions = Default::default();
+            let binder = Binder::new(BinderContext::new(
+                unsafe { transaction.as_ptr().as_ref().unwrap() },
+                &functions,
+            ));
+            let stmt = crate::parser::parse_sql(sql)?;
+            let plan = binder.bind(&stmt[0])?;
+
+            let pushed_plan = optimize(plan.clone(), true)?;
+            match &find_scan(&pushed_plan).unwrap().operator {
+                Operator::Scan(op) => assert_eq!(!op.agg_calls.is_empty(), is_pushed, "{}", sql),
+                _ => unreachable!(),
+            }
+            let pushed =
+                try_collect(&mut build(pushed_plan, &transaction, &Default::default())).await?;
+            let generic = try_collect(&mut build(
+                optimize(plan, false)?,
+                &transaction,
+                &Default::default(),
+            ))
+            .await?;
+
+            assert_eq!(
+                pushed.iter().map(|tuple| &tuple.values).collect::<Vec<_>>(),
+                generic
+                    .iter()
+                    .map(|tuple| &tuple.values)
+                    .collect::<Vec<_>>(),
+                "{}",
+                sql
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -36,6 +36,15 @@ lazy_static! {
             }]),
         }
     };
+    static ref PUSH_LIMIT_INTO_ORDERED_SCAN_RULE: Pattern = {
+        Pattern {
+            predicate: |op| matches!(op, Operator::Limit(_)),
+            children: PatternChildrenPredicate::Predicate(vec![Pattern {
+                predicate: |op| matches!(op, Operator::Sort(_)),
+                children: PatternChildrenPredicate::None,
+            }]),
+        }
+    };
     static ref PUSH_LIMIT_INTO_TABLE_SCAN_RULE: Pattern = {
         Pattern {
             predicate: |op| matches!(op, Operator::Limit(_)),
@@ -74,8 +83,8 @@ impl Rule for EliminateLimits {
         if let Operator::Limit(op) = graph.operator(node_id) {
             let child_id = graph.children_at(node_id)[0];
             if let Operator::Limit(child_op) = graph.operator(child_id) {
-                let offset = Self::binary_options(op.offset, child_op.offset, |a, b| a + b);
-                let limit = Self::binary_options(op.limit, child_op.limit, cmp::min);
+                let (offset, limit) =
+                    Self::merge((op.offset, op.limit), (child_op.offset, child_op.limit));
 
                 let new_limit_op = LimitOperator { offset, limit };
 
@@ -89,6 +98,21 @@ impl Rule for EliminateLimits {
 }
 
 impl EliminateLimits {
+    /// The `(offset, limit)` of a limit applied on the rows left by the `inner` one,
+    /// whose rows skipped by the `outer` offset no longer count for its limit.
+    pub(crate) fn merge(
+        (outer_offset, outer_limit): (Option<usize>, Option<usize>),
+        (inner_offset, inner_limit): (Option<usize>, Option<usize>),
+    ) -> (Option<usize>, Option<usize>) {
+        let offset = Self::binary_options(outer_offset, inner_offset, |a, b| a + b);
+        let inner_limit = inner_limit.map(|limit| limit.saturating_sub(outer_offset.unwrap_or(0)));
+
+        (
+            offset,
+            Self::binary_options(outer_limit, inner_limit, cmp::min),
+        )
+    }
+
     fn binary_options<F: Fn(usize, usize) -> usize>(
         a: Option<usize>,
         b: Option<usize>,
@@ -128,7 +152,7 @@ impl Rule for PushLimitThroughJoin {
             if let Some(ty) = join_type {
                 if let Some(grandson_id) = match ty {
                     JoinType::Left => Some(graph.children_at(child_id)[0]),
-                    JoinType::Right => Some(graph.children_at(child_id)[1]),
+                    JoinType::Right | JoinType::Single => Some(graph.children_at(child_id)[1]),
                     _ => None,
                 } {
                     graph.add_node(child_id, Some(grandson_id), Operator::Limit(op.clone()));
@@ -154,7 +178,9 @@ impl Rule for PushLimitIntoScan {
             if let Operator::Scan(scan_op) = graph.operator(child_index) {
                 let mut new_scan_op = scan_op.clone();
 
-                new_scan_op.limit = (limit_op.offset, limit_op.limit);
+                // the scan may already stop at the limit of a subquery
+                new_scan_op.limit =
+                    EliminateLimits::merge((limit_op.offset, limit_op.limit), scan_op.limit);
 
                 graph.remove_node(node_id, false);
                 graph.replace_node(child_index, Operator::Scan(new_scan_op));
@@ -165,12 +191,66 @@ impl Rule for PushLimitIntoScan {
     }
 }
 
+/// Push down `Limit` past a `Sort` whose order is the one the `Scan` already reads in,
+/// e.g. `ORDER BY pk LIMIT k`, so the scan stops after k tuples and the sort is dropped.
+///
+/// A `Filter` in between is only passed if the index scan returns exactly the tuples
+/// it accepts, otherwise the filtered out tuples would count for the limit.
+pub struct PushLimitIntoOrderedScan;
+
+impl Rule for PushLimitIntoOrderedScan {
+    fn pattern(&self) -> &Pattern {
+        &PUSH_LIMIT_INTO_ORDERED_SCAN_RULE
+    }
+
+    fn apply(&self, node_id: HepNodeId, graph: &mut HepGraph) -> Result<(), OptimizerError> {
+        let (limit_op, sort_id) = match graph.operator(node_id) {
+            Operator::Limit(limit_op) => (limit_op.clone(), graph.children_at(node_id)[0]),
+            _ => return Ok(()),
+        };
+        let sort_fields = match graph.operator(sort_id) {
+            Operator::Sort(sort_op) => sort_op.sort_fields.clone(),
+            _ => return Ok(()),
+        };
+        let mut scan_id = graph.children_at(sort_id)[0];
+        let mut predicate = None;
+
+        if let Operator::Filter(filter_op) = graph.operator(scan_id) {
+            predicate = Some(filter_op.predicate.clone());
+            scan_id = graph.children_at(scan_id)[0];
+        }
+        if let Operator::Scan(scan_op) = graph.operator(scan_id) {
+            if !scan_op.is_ordered_by(&sort_fields) || scan_op.limit != (None, None) {
+                return Ok(());
+            }
+            if let Some(predicate) = predicate {
+                match &scan_op.index_by {
+                    Some((index_meta, _))
+                        if index_meta.column_ids.len() == 1
+                            && predicate.is_bounded_by(&index_meta.column_ids[0]) => {}
+                    _ => return Ok(()),
+                }
+            }
+            let mut new_scan_op = scan_op.clone();
+
+            new_scan_op.limit = (limit_op.offset, limit_op.limit);
+
+            graph.remove_node(node_id, false);
+            graph.remove_node(sort_id, false);
+            graph.replace_node(scan_id, Operator::Scan(new_scan_op));
+        }
+
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::binder::test::select_sql_run;
     use crate::db::DatabaseError;
     use crate::optimizer::heuristic::batch::HepBatchStrategy;
     use crate::optimizer::heuristic::optimizer::HepOptimizer;
+    use crate::optimizer::rule::pushdown_limit::EliminateLimits;
     use crate::optimizer::rule::RuleImpl;
     use crate::planner::operator::limit::LimitOperator;
     use crate::planner::operator::Operator;
@@ -220,7 +300,8 @@ mod tests {
         let best_plan = optimizer.find_best()?;
 
         if let Operator::Limit(op) = &best_plan.operator {
-            assert_eq!(op.limit, Some(1));
+            // the outer offset skips the single row left by the inner limit
+            assert_eq!(op.limit, Some(0));
             assert_eq!(op.offset, Some(3));
         } else {
             unreachable!("Should be a project operator")
@@ -230,6 +311,16 @@ mod tests {
             unreachable!("Should not be a limit operator")
         }
 
+        for (outer, inner, merged) in [
+            ((Some(2), Some(1)), (None, Some(3)), (Some(2), Some(1))),
+            ((Some(1), Some(5)), (Some(1), Some(3)), (Some(2), Some(2))),
+            ((None, Some(5)), (None, Some(2)), (None, Some(2))),
+            ((Some(4), None), (None, Some(3)), (Some(4), Some(0))),
+            ((None, Some(2)), (Some(3), None), (Some(3), Some(2))),
+        ] {
+            assert_eq!(EliminateLimits::merge(outer, inner), merged);
+        }
+
         Ok(())
     }
 
@@ -262,6 +353,70 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_push_limit_into_ordered_scan() -> Result<(), DatabaseError> {
+        for (sql, limit) in [
+            (
+                "select * from t1 order by c1 limit 2",
+                Some((None, Some(2))),
+            ),
+            (
+                "select * from t1 where c2 > 1 order by c2 limit 1 offset 1",
+                Some((Some(1), Some(1))),
+            ),
+            ("select * from t1 order by c1 desc limit 2", None),
+            ("select * from t1 order by c2 limit 2", None),
+            ("select * from t1 where c2 > 1 order by c1 limit 2", None),
+            (
+                "select * from t1 where c2 > 1 and c1 < 3 order by c2 limit 2",
+                None,
+            ),
+        ] {
+            let plan = select_sql_run(sql).await?;
+            let best_plan = HepOptimizer::new(plan)
+                .batch(
+                    "simplify_filter".to_string(),
+                    HepBatchStrategy::once_topdown(),
+                    vec![RuleImpl::SimplifyFilter],
+                )
+                .batch(
+                    "push_predicate_into_scan".to_string(),
+                    HepBatchStrategy::once_topdown(),
+                    vec![RuleImpl::PushPredicateIntoScan],
+                )
+                .batch(
+                    "test_push_limit_into_ordered_scan".to_string(),
+                    HepBatchStrategy::fix_point_topdown(10),
+                    vec![
+                        RuleImpl::LimitProjectTranspose,
+                        RuleImpl::PushLimitIntoOrderedScan,
+                    ],
+                )
+                .find_best()?;
+            let mut plan = &best_plan.childrens[0];
+
+            match limit {
+                Some(limit) => {
+                    if let Operator::Filter(_) = &plan.operator {
+                        plan = &plan.childrens[0];
+                    }
+                    if let Operator::Scan(op) = &plan.operator {
+                        assert_eq!(op.limit, limit, "{}", sql);
+                    } else {
+                        unreachable!("Should be a scan operator: {}", sql)
+                    }
+                }
+                None => assert!(
+                    matches!(plan.operator, Operator::Limit(_)),
+                    "Should be a limit operator: {}",
+                    sql
+                ),
+            }
+        }
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_push_limit_into_table_scan() -> Result<(), DatabaseError> {
         let plan = select_sql_run("select * from t1 limit 1 offset 1").await?;
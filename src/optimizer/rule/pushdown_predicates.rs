@@ -1,4 +1,5 @@
 use crate::catalog::ColumnRef;
+use crate::expression::simplify::ConstantBinary;
 use crate::expression::{BinaryOperator, ScalarExpression};
 use crate::optimizer::core::pattern::Pattern;
 use crate::optimizer::core::pattern::PatternChildrenPredicate;
@@ -8,9 +9,11 @@ use crate::optimizer::OptimizerError;
 use crate::planner::operator::filter::FilterOperator;
 use crate::planner::operator::join::JoinType;
 use crate::planner::operator::Operator;
+use crate::types::errors::TypeError;
 use crate::types::LogicalType;
 use itertools::Itertools;
 use lazy_static::lazy_static;
+use std::collections::Bound;
 
 lazy_static! {
     static ref PUSH_PREDICATE_THROUGH_JOIN: Pattern = {
@@ -210,26 +213,132 @@ impl Rule for PushPredicateIntoScan {
                 }
 
                 //FIXME: now only support unique
-                for meta in &child_op.index_metas {
-                    let mut option = op.predicate.convert_binary(&meta.column_ids[0])?;
+                let mut index_by = Vec::new();
 
-                    if let Some(mut binary) = option.take() {
-                        binary.scope_aggregation()?;
-                        let rearrange_binaries = binary.rearrange()?;
+                for meta in &child_op.index_metas {
+                    let rearrange_binaries = match &meta.expr {
+                        Some(index_expr) => {
+                            Self::rearrange(op.predicate.convert_expr_binary(&index_expr.expr)?)?
+                        }
+                        // composite indexes are scanned by the bounds of their leading columns,
+                        // but the keys of a composite primary key are those of the tuples
+                        None if meta.column_ids.len() != 1 => {
+                            if meta.is_primary {
+                                continue;
+                            }
+                            let binaries =
+                                op.predicate.convert_prefix_binaries(&meta.column_ids)?;
 
-                        if rearrange_binaries.is_empty() {
-                            continue;
+                            if !child_op.is_prefix_complete(meta, &binaries) {
+                                continue;
+                            }
+                            binaries
                         }
-                        let mut scan_by_index = child_op.clone();
-                        scan_by_index.index_by = Some((meta.clone(), rearrange_binaries));
+                        None => Self::rearrange(op.predicate.convert_binary(&meta.column_ids[0])?)?,
+                    };
 
-                        // The constant expression extracted in prewhere is used to
-                        // reduce the data scanning range and cannot replace the role of Filter.
-                        graph.replace_node(child_id, Operator::Scan(scan_by_index));
+                    if rearrange_binaries.is_empty() {
+                        continue;
+                    }
+                    // the primary key is scanned alone as its entries are the tuples
+                    if meta.is_primary && index_by.is_empty() {
+                        index_by.push((meta.clone(), rearrange_binaries));
+                        break;
+                    }
+                    if !meta.is_primary {
+                        index_by.push((meta.clone(), rearrange_binaries));
+                    }
+                }
+                // an index bounding nothing but the nulls finds almost every tuple
+                if index_by.len() > 1 {
+                    let first = index_by[0].clone();
+
+                    index_by.retain(|(_, binaries)| {
+                        !matches!(
+                            binaries.as_slice(),
+                            [ConstantBinary::Scope {
+                                min: Bound::Unbounded,
+                                max: Bound::Unbounded
+                            }]
+                        )
+                    });
+                    if index_by.is_empty() {
+                        index_by.push(first);
+                    }
+                }
+                if !index_by.is_empty() {
+                    let (meta, binaries) = index_by.remove(0);
+                    let mut scan_by_index = child_op.clone();
+                    scan_by_index.index_only =
+                        index_by.is_empty() && scan_by_index.is_covered_by(&meta);
+                    scan_by_index.index_by = Some((meta, binaries));
+                    scan_by_index.index_intersection = index_by;
+
+                    // The constant expression extracted in prewhere is used to
+                    // reduce the data scanning range and cannot replace the role of Filter.
+                    graph.replace_node(child_id, Operator::Scan(scan_by_index));
+                }
+            }
+        }
 
+        Ok(())
+    }
+}
+
+impl PushPredicateIntoScan {
+    fn rearrange(option: Option<ConstantBinary>) -> Result<Vec<ConstantBinary>, TypeError> {
+        match option {
+            Some(mut binary) => {
+                binary.scope_aggregation()?;
+                binary.rearrange()
+            }
+            None => Ok(vec![]),
+        }
+    }
+}
+
+/// Evaluate the predicate of a `Filter` in the `Scan` below it, e.g. `select * from t1 where
+/// c2 > 1` without an index on `c2`, so the tuples failing it are skipped before they leave
+/// the storage instead of being pulled by the `Filter` one by one.
+///
+/// A predicate the index of the scan bounds exactly is answered by it already, its `Filter`
+/// is left to the rules removing it. This runs after `PushPredicateIntoScan` chose the index.
+pub struct PushFilterIntoScan;
+
+impl Rule for PushFilterIntoScan {
+    fn pattern(&self) -> &Pattern {
+        &PUSH_PREDICATE_INTO_SCAN
+    }
+
+    fn apply(&self, node_id: HepNodeId, graph: &mut HepGraph) -> Result<(), OptimizerError> {
+        if let Operator::Filter(op) = graph.operator(node_id) {
+            let child_id = graph.children_at(node_id)[0];
+            if let Operator::Scan(child_op) = graph.operator(child_id) {
+                // the filter would apply after the bounds or to the aggregated tuple
+                if child_op.limit != (None, None) || !child_op.agg_calls.is_empty() {
+                    return Ok(());
+                }
+                if let Some((index_meta, _)) = &child_op.index_by {
+                    if index_meta.column_ids.len() == 1
+                        && op.predicate.is_bounded_by(&index_meta.column_ids[0])
+                    {
                         return Ok(());
                     }
                 }
+                let mut new_scan_op = child_op.clone();
+
+                new_scan_op.filter = Some(match new_scan_op.filter.take() {
+                    Some(filter) => ScalarExpression::Binary {
+                        op: BinaryOperator::And,
+                        left_expr: Box::new(filter),
+                        right_expr: Box::new(op.predicate.clone()),
+                        ty: LogicalType::Boolean,
+                    },
+                    None => op.predicate.clone(),
+                });
+
+                graph.remove_node(node_id, false);
+                graph.replace_node(child_id, Operator::Scan(new_scan_op));
             }
         }
 
@@ -246,11 +355,12 @@ mod tests {
     use crate::optimizer::heuristic::batch::HepBatchStrategy;
     use crate::optimizer::heuristic::optimizer::HepOptimizer;
     use crate::optimizer::rule::RuleImpl;
+    use crate::planner::operator::scan::ScanOperator;
     use crate::planner::operator::Operator;
-    use crate::types::value::DataValue;
+    use crate::planner::LogicalPlan;
+    use crate::types::value::{DataValue, ValueRef};
     use crate::types::LogicalType;
     use std::collections::Bound;
-    use std::sync::Arc;
 
     #[tokio::test]
     async fn test_push_predicate_into_scan() -> Result<(), DatabaseError> {
@@ -272,7 +382,7 @@ mod tests {
 
         if let Operator::Scan(op) = &best_plan.childrens[0].childrens[0].operator {
             let mock_binaries = vec![Scope {
-                min: Bound::Excluded(Arc::new(DataValue::Int32(Some(1)))),
+                min: Bound::Excluded(ValueRef::new(DataValue::Int32(Some(1)))),
                 max: Bound::Unbounded,
             }];
 
@@ -284,6 +394,142 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_push_predicate_into_scan_by_row() -> Result<(), DatabaseError> {
+        let scope = |min, max| {
+            vec![Scope {
+                min: Bound::Included(ValueRef::new(DataValue::Int32(Some(min)))),
+                max,
+            }]
+        };
+        for (sql, binaries) in [
+            (
+                "select * from t1 where (c1, c2) > (1, 2)",
+                scope(1, Bound::Unbounded),
+            ),
+            (
+                "select * from t1 where (3, 4) >= (c1, c2)",
+                vec![Scope {
+                    min: Bound::Unbounded,
+                    max: Bound::Included(ValueRef::new(DataValue::Int32(Some(3)))),
+                }],
+            ),
+            (
+                "select * from t1 where (c1, c2) > (1, 2) and c1 < 5",
+                scope(1, Bound::Excluded(ValueRef::new(DataValue::Int32(Some(5))))),
+            ),
+        ] {
+            let plan = select_sql_run(sql).await?;
+            let best_plan = HepOptimizer::new(plan)
+                .batch(
+                    "test_push_predicate_into_scan".to_string(),
+                    HepBatchStrategy::once_topdown(),
+                    vec![RuleImpl::PushPredicateIntoScan],
+                )
+                .find_best()?;
+
+            if let Operator::Scan(op) = &best_plan.childrens[0].childrens[0].operator {
+                assert_eq!(op.index_by.clone().unwrap().1, binaries);
+            } else {
+                unreachable!("Should be a scan operator")
+            }
+        }
+        // `c1` is not bounded by `(c1, c2) <> (1, 2)`
+        let plan = select_sql_run("select * from t1 where (c1, c2) <> (1, 2)").await?;
+        let best_plan = HepOptimizer::new(plan)
+            .batch(
+                "test_push_predicate_into_scan".to_string(),
+                HepBatchStrategy::once_topdown(),
+                vec![RuleImpl::PushPredicateIntoScan],
+            )
+            .find_best()?;
+
+        if let Operator::Scan(op) = &best_plan.childrens[0].childrens[0].operator {
+            assert!(op.index_by.is_none());
+        } else {
+            unreachable!("Should be a scan operator")
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_push_predicate_into_scan_index_only() -> Result<(), DatabaseError> {
+        for (sql, index_only) in [
+            ("select c2 from t1 where c2 > 1", true),
+            ("select c1 from t1 where c1 > 1", false),
+        ] {
+            let plan = select_sql_run(sql).await?;
+            let best_plan = HepOptimizer::new(plan)
+                .batch(
+                    "column_pruning".to_string(),
+                    HepBatchStrategy::once_topdown(),
+                    vec![RuleImpl::ColumnPruning],
+                )
+                .batch(
+                    "test_push_predicate_into_scan".to_string(),
+                    HepBatchStrategy::once_topdown(),
+                    vec![RuleImpl::PushPredicateIntoScan],
+                )
+                .find_best()?;
+
+            if let Operator::Scan(op) = &best_plan.childrens[0].childrens[0].operator {
+                assert!(op.index_by.is_some());
+                assert_eq!(op.index_only, index_only);
+            } else {
+                unreachable!("Should be a scan operator")
+            }
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_push_filter_into_scan() -> Result<(), DatabaseError> {
+        fn find_scan(plan: &LogicalPlan) -> Option<&ScanOperator> {
+            match &plan.operator {
+                Operator::Scan(op) => Some(op),
+                _ => plan.childrens.iter().find_map(find_scan),
+            }
+        }
+        fn has_filter(plan: &LogicalPlan) -> bool {
+            matches!(plan.operator, Operator::Filter(_)) || plan.childrens.iter().any(has_filter)
+        }
+
+        for (sql, is_pushed) in [
+            // not bounded by any index
+            ("select * from t2 where c4 > 1", true),
+            // the index bounds only a part of it
+            ("select * from t1 where c1 > 1 and c1 + c2 > 3", true),
+            // answered by the index already
+            ("select * from t1 where c1 > 1", false),
+        ] {
+            let plan = select_sql_run(sql).await?;
+            let best_plan = HepOptimizer::new(plan)
+                .batch(
+                    "test_push_predicate_into_scan".to_string(),
+                    HepBatchStrategy::once_topdown(),
+                    vec![RuleImpl::PushPredicateIntoScan],
+                )
+                .batch(
+                    "test_push_filter_into_scan".to_string(),
+                    HepBatchStrategy::fix_point_topdown(10),
+                    vec![RuleImpl::PushFilterIntoScan],
+                )
+                .find_best()?;
+
+            assert_eq!(has_filter(&best_plan), !is_pushed, "{}", sql);
+            assert_eq!(
+                find_scan(&best_plan).unwrap().filter.is_some(),
+                is_pushed,
+                "{}",
+                sql
+            );
+        }
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_push_predicate_through_join_in_left_join() -> Result<(), DatabaseError> {
         let plan =
@@ -0,0 +1,169 @@
+use crate::optimizer::core::pattern::Pattern;
+use crate::optimizer::core::pattern::PatternChildrenPredicate;
+use crate::optimizer::core::rule::Rule;
+use crate::optimizer::heuristic::graph::{HepGraph, HepNodeId};
+use crate::optimizer::OptimizerError;
+use crate::planner::operator::Operator;
+use lazy_static::lazy_static;
+
+lazy_static! {
+    static ref PUSH_PROJECT_INTO_SCAN_RULE: Pattern = {
+        Pattern {
+            predicate: |op| matches!(op, Operator::Project(_)),
+            children: PatternChildrenPredicate::Predicate(vec![Pattern {
+                predicate: |op| matches!(op, Operator::Scan(_)),
+                children: PatternChildrenPredicate::None,
+            }]),
+        }
+    };
+}
+
+/// Fuse a `Project` into the `Scan` below it, e.g. `select c1 * 2 from t1`,
+/// so the expressions are evaluated while the tuples are decoded.
+///
+/// The scan is no longer made of columns afterwards, so this has to run after all the
+/// other rules looking into the columns of the scan.
+pub struct PushProjectIntoScan;
+
+impl Rule for PushProjectIntoScan {
+    fn pattern(&self) -> &Pattern {
+        &PUSH_PROJECT_INTO_SCAN_RULE
+    }
+
+    fn apply(&self, node_id: HepNodeId, graph: &mut HepGraph) -> Result<(), OptimizerError> {
+        let exprs = match graph.operator(node_id) {
+            Operator::Project(op) if !op.exprs.iter().any(|expr| expr.has_agg_call()) => {
+                op.exprs.clone()
+            }
+            _ => return Ok(()),
+        };
+        let scan_id = graph.children_at(node_id)[0];
+
+        if let Operator::Scan(scan_op) = graph.operator(scan_id) {
+            // the aggregated tuple and the locked ones are built after reading
+            if !scan_op.agg_calls.is_empty() || scan_op.lock {
+                return Ok(());
+            }
+            // an index-only scan has no other columns than those of the scan
+            let is_read = exprs.iter().all(|expr| {
+                expr.referenced_columns(true).iter().all(|col| {
+                    scan_op
+                        .columns
+                        .iter()
+                        .any(|scan_expr| scan_expr.output_columns() == *col)
+                })
+            });
+            if !is_read {
+                return Ok(());
+            }
+            let mut new_scan_op = scan_op.clone();
+
+            new_scan_op.columns = exprs;
+
+            graph.remove_node(node_id, false);
+            graph.replace_node(scan_id, Operator::Scan(new_scan_op));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::binder::{Binder, BinderContext};
+    use crate::db::{Database, DatabaseError};
+    use crate::execution::executor::{build, try_collect};
+    use crate::optimizer::heuristic::batch::HepBatchStrategy;
+    use crate::optimizer::heuristic::optimizer::HepOptimizer;
+    use crate::optimizer::rule::RuleImpl;
+    use crate::planner::operator::Operator;
+    use crate::planner::LogicalPlan;
+    use crate::storage::Storage;
+    use std::cell::RefCell;
+    use tempfile::TempDir;
+
+    fn optimize(plan: LogicalPlan, push_project: bool) -> Result<LogicalPlan, DatabaseError> {
+        let mut optimizer = HepOptimizer::new(plan)
+            .batch(
+                "test_column_pruning".to_string(),
+                HepBatchStrategy::once_topdown(),
+                vec![RuleImpl::ColumnPruning],
+            )
+            .batch(
+                "test_push_predicate_into_scan".to_string(),
+                HepBatchStrategy::fix_point_topdown(10),
+                vec![RuleImpl::PushPredicateIntoScan],
+            );
+        if push_project {
+            optimizer = optimizer.batch(
+                "test_push_project_into_scan".to_string(),
+                HepBatchStrategy::once_topdown(),
+                vec![RuleImpl::PushProjectIntoScan],
+            );
+        }
+
+        Ok(optimizer.find_best()?)
+    }
+
+    #[tokio::test]
+    async fn test_push_project_into_scan() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+        let _ = kipsql
+            .run("create table t1 (c1 int primary key, c2 int null, c3 varchar null unique)")
+            .await?;
+        let _ = kipsql
+            .run("insert into t1 values (0, 1, 'x'), (1, null, 'y'), (2, 5, null), (3, -2, 'z')")
+            .await?;
+
+        for (sql, is_fused) in [
+            ("select c1 * 2 from t1", true),
+            ("select c1 + c2 as s, c3 from t1", true),
+            ("select c1, c2 * 2, 'x' from t1", true),
+            // the filter on the scan is kept
+            ("select c1 * 2 from t1 where c1 > 1", false),
+            ("select count(*) from t1", false),
+        ] {
+            let storage = &kipsql.storage;
+            let transaction = RefCell::new(storage.transaction().await?);
+            let functions = Default::default();
+            let binder = Binder::new(BinderContext::new(
+                unsafe { transaction.as_ptr().as_ref().unwrap() },
+                &functions,
+            ));
+            let stmt = crate::parser::parse_sql(sql)?;
+            let plan = binder.bind(&stmt[0])?;
+
+            let fused_plan = optimize(plan.clone(), true)?;
+            assert_eq!(
+                matches!(&fused_plan.operator, Operator::Scan(_)),
+                is_fused,
+                "{}",
+                sql
+            );
+            let fused =
+                try_collect(&mut build(fused_plan, &transaction, &Default::default())).await?;
+            let generic = try_collect(&mut build(
+                optimize(plan, false)?,
+                &transaction,
+                &Default::default(),
+            ))
+            .await?;
+
+            assert_eq!(
+                fused
+                    .iter()
+                    .map(|tuple| (&tuple.schema_ref, &tuple.values))
+                    .collect::<Vec<_>>(),
+                generic
+                    .iter()
+                    .map(|tuple| (&tuple.schema_ref, &tuple.values))
+                    .collect::<Vec<_>>(),
+                "{}",
+                sql
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,153 @@
+use crate::expression::ScalarExpression;
+use crate::optimizer::core::pattern::Pattern;
+use crate::optimizer::core::pattern::PatternChildrenPredicate;
+use crate::optimizer::core::rule::Rule;
+use crate::optimizer::heuristic::graph::{HepGraph, HepNodeId};
+use crate::optimizer::OptimizerError;
+use crate::planner::operator::join::{JoinCondition, JoinType};
+use crate::planner::operator::Operator;
+use lazy_static::lazy_static;
+
+lazy_static! {
+    static ref INDEX_PROBE_SEMI_JOIN_RULE: Pattern = {
+        Pattern {
+            predicate: |op| matches!(op, Operator::Join(_)),
+            children: PatternChildrenPredicate::None,
+        }
+    };
+}
+
+/// Probe the index of the right key of a semi join for each left tuple, e.g.
+/// `select * from t1 where exists (select 1 from t2 where c3 = t1.c1)` with an index on `c3`,
+/// so the search stops at the first match instead of hashing all of `t2`.
+///
+/// The right side has to be a plain scan, with a `Filter` above it at most, and the keys must
+/// be of the same type, so this runs after the rules pushing into `Scan`.
+pub struct IndexProbeSemiJoin;
+
+impl Rule for IndexProbeSemiJoin {
+    fn pattern(&self) -> &Pattern {
+        &INDEX_PROBE_SEMI_JOIN_RULE
+    }
+
+    fn apply(&self, node_id: HepNodeId, graph: &mut HepGraph) -> Result<(), OptimizerError> {
+        let (join_op, right_key) = match graph.operator(node_id) {
+            Operator::Join(op)
+                if matches!(op.join_type, JoinType::Semi | JoinType::Anti)
+                    && op.index_probe.is_none() =>
+            {
+                match &op.on {
+                    JoinCondition::On { on, .. } if on.len() == 1 => match &on[0] {
+                        (left_key, ScalarExpression::ColumnRef(col))
+                            if left_key.return_type() == *col.datatype() =>
+                        {
+                            (op.clone(), col.clone())
+                        }
+                        _ => return Ok(()),
+                    },
+                    _ => return Ok(()),
+                }
+            }
+            _ => return Ok(()),
+        };
+        let mut scan_id = graph.children_at(node_id)[1];
+
+        if let Operator::Filter(filter_op) = graph.operator(scan_id) {
+            if filter_op.having {
+                return Ok(());
+            }
+            scan_id = graph.children_at(scan_id)[0];
+        }
+        if let Operator::Scan(scan_op) = graph.operator(scan_id) {
+            if scan_op.index_by.is_some()
+                || scan_op.lock
+                || !scan_op.agg_calls.is_empty()
+                || scan_op.limit != (None, None)
+                || !scan_op.columns.iter().any(
+                    |expr| matches!(expr, ScalarExpression::ColumnRef(col) if col == &right_key),
+                )
+            {
+                return Ok(());
+            }
+            let index_meta = scan_op
+                .index_metas
+                .iter()
+                .find(|index_meta| {
+                    index_meta.expr.is_none()
+                        && right_key
+                            .id()
+                            .map_or(false, |id| index_meta.column_ids == [id])
+                })
+                .cloned();
+
+            if let Some(index_meta) = index_meta {
+                let mut new_join_op = join_op;
+
+                new_join_op.index_probe = Some(index_meta);
+                graph.replace_node(node_id, Operator::Join(new_join_op));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::binder::test::select_sql_run;
+    use crate::db::DatabaseError;
+    use crate::optimizer::heuristic::batch::HepBatchStrategy;
+    use crate::optimizer::heuristic::optimizer::HepOptimizer;
+    use crate::optimizer::rule::RuleImpl;
+    use crate::planner::operator::Operator;
+    use crate::planner::LogicalPlan;
+
+    fn find_join(plan: &LogicalPlan) -> Option<&Operator> {
+        match &plan.operator {
+            op @ Operator::Join(_) => Some(op),
+            _ => plan.childrens.iter().find_map(find_join),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_index_probe_semi_join() -> Result<(), DatabaseError> {
+        for (sql, index_name) in [
+            (
+                "select c1 from t1 where exists (select 1 from t2 where c3 = t1.c2)",
+                Some("pk_c3"),
+            ),
+            (
+                "select c1 from t1 where not exists (select 1 from t2 where t1.c1 = c3 and c4 > 1)",
+                Some("pk_c3"),
+            ),
+            // `c4` has no index
+            (
+                "select c1 from t1 where exists (select 1 from t2 where c4 = t1.c1)",
+                None,
+            ),
+        ] {
+            let plan = select_sql_run(sql).await?;
+            let best_plan = HepOptimizer::new(plan)
+                .batch(
+                    "test_index_probe_semi_join".to_string(),
+                    HepBatchStrategy::once_topdown(),
+                    vec![RuleImpl::IndexProbeSemiJoin],
+                )
+                .find_best()?;
+
+            match find_join(&best_plan) {
+                Some(Operator::Join(op)) => assert_eq!(
+                    op.index_probe
+                        .as_ref()
+                        .map(|index_meta| index_meta.name.as_str()),
+                    index_name,
+                    "{}",
+                    sql
+                ),
+                _ => unreachable!("Should be a join operator"),
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -65,6 +65,11 @@ impl ConstantCalculation {
                     field.expr.constant_calculation()?;
                 }
             }
+            Operator::DistinctOn(op) => {
+                for expr in &mut op.on_exprs {
+                    expr.constant_calculation()?;
+                }
+            }
             _ => (),
         }
         for child_id in graph.children_at(node_id) {
@@ -122,7 +127,7 @@ mod test {
     use crate::planner::operator::filter::FilterOperator;
     use crate::planner::operator::Operator;
     use crate::planner::LogicalPlan;
-    use crate::types::value::DataValue;
+    use crate::types::value::{DataValue, ValueRef};
     use crate::types::LogicalType;
     use std::collections::Bound;
     use std::sync::Arc;
@@ -142,7 +147,8 @@ mod test {
             )
             .find_best()?;
         if let Operator::Project(project_op) = best_plan.clone().operator {
-            let constant_expr = ScalarExpression::Constant(Arc::new(DataValue::Int32(Some(3))));
+            let constant_expr =
+                ScalarExpression::Constant(ValueRef::new(DataValue::Int32(Some(3))));
             if let ScalarExpression::Binary { right_expr, .. } = &project_op.exprs[0] {
                 assert_eq!(right_expr.as_ref(), &constant_expr);
             } else {
@@ -156,7 +162,7 @@ mod test {
             let column_binary = filter_op.predicate.convert_binary(&0).unwrap();
             let final_binary = ConstantBinary::Scope {
                 min: Bound::Unbounded,
-                max: Bound::Excluded(Arc::new(DataValue::Int32(Some(-2)))),
+                max: Bound::Excluded(ValueRef::new(DataValue::Int32(Some(-2)))),
             };
             assert_eq!(column_binary, Some(final_binary));
         } else {
@@ -249,6 +255,7 @@ mod test {
                 summary: ColumnSummary {
                     id: Some(0),
                     name: "c1".to_string(),
+                    table_name: Some(Arc::new("t1".to_string())),
                 },
                 nullable: false,
                 desc: ColumnDesc {
@@ -256,6 +263,12 @@ mod test {
                     is_primary: true,
                     is_unique: false,
                     default: None,
+                    is_hidden: false,
+                    generated: None,
+                    identity: None,
+                    comment: None,
+                    on_update: None,
+                    default_expr: None,
                 },
                 ref_expr: None,
             };
@@ -263,6 +276,7 @@ mod test {
                 summary: ColumnSummary {
                     id: Some(1),
                     name: "c2".to_string(),
+                    table_name: Some(Arc::new("t1".to_string())),
                 },
                 nullable: false,
                 desc: ColumnDesc {
@@ -270,6 +284,12 @@ mod test {
                     is_primary: false,
                     is_unique: true,
                     default: None,
+                    is_hidden: false,
+                    generated: None,
+                    identity: None,
+                    comment: None,
+                    on_update: None,
+                    default_expr: None,
                 },
                 ref_expr: None,
             };
@@ -284,7 +304,7 @@ mod test {
                         expr: Box::new(ScalarExpression::Binary {
                             op: BinaryOperator::Plus,
                             left_expr: Box::new(ScalarExpression::ColumnRef(Arc::new(c1_col))),
-                            right_expr: Box::new(ScalarExpression::Constant(Arc::new(
+                            right_expr: Box::new(ScalarExpression::Constant(ValueRef::new(
                                 DataValue::Int32(Some(1))
                             ))),
                             ty: LogicalType::Integer,
@@ -343,7 +363,7 @@ mod test {
             cb_1_c1,
             Some(ConstantBinary::Scope {
                 min: Bound::Unbounded,
-                max: Bound::Excluded(Arc::new(DataValue::Int32(Some(-2))))
+                max: Bound::Excluded(ValueRef::new(DataValue::Int32(Some(-2))))
             })
         );
 
@@ -352,7 +372,7 @@ mod test {
         assert_eq!(
             cb_1_c2,
             Some(ConstantBinary::Scope {
-                min: Bound::Excluded(Arc::new(DataValue::Int32(Some(2)))),
+                min: Bound::Excluded(ValueRef::new(DataValue::Int32(Some(2)))),
                 max: Bound::Unbounded
             })
         );
@@ -362,7 +382,7 @@ mod test {
         assert_eq!(
             cb_2_c1,
             Some(ConstantBinary::Scope {
-                min: Bound::Excluded(Arc::new(DataValue::Int32(Some(2)))),
+                min: Bound::Excluded(ValueRef::new(DataValue::Int32(Some(2)))),
                 max: Bound::Unbounded
             })
         );
@@ -373,7 +393,7 @@ mod test {
             cb_1_c1,
             Some(ConstantBinary::Scope {
                 min: Bound::Unbounded,
-                max: Bound::Excluded(Arc::new(DataValue::Int32(Some(-2))))
+                max: Bound::Excluded(ValueRef::new(DataValue::Int32(Some(-2))))
             })
         );
 
@@ -383,7 +403,7 @@ mod test {
             cb_3_c1,
             Some(ConstantBinary::Scope {
                 min: Bound::Unbounded,
-                max: Bound::Excluded(Arc::new(DataValue::Int32(Some(-1))))
+                max: Bound::Excluded(ValueRef::new(DataValue::Int32(Some(-1))))
             })
         );
 
@@ -392,7 +412,7 @@ mod test {
         assert_eq!(
             cb_3_c2,
             Some(ConstantBinary::Scope {
-                min: Bound::Excluded(Arc::new(DataValue::Int32(Some(0)))),
+                min: Bound::Excluded(ValueRef::new(DataValue::Int32(Some(0)))),
                 max: Bound::Unbounded
             })
         );
@@ -402,7 +422,7 @@ mod test {
         assert_eq!(
             cb_4_c1,
             Some(ConstantBinary::Scope {
-                min: Bound::Excluded(Arc::new(DataValue::Int32(Some(0)))),
+                min: Bound::Excluded(ValueRef::new(DataValue::Int32(Some(0)))),
                 max: Bound::Unbounded
             })
         );
@@ -413,7 +433,7 @@ mod test {
             cb_4_c2,
             Some(ConstantBinary::Scope {
                 min: Bound::Unbounded,
-                max: Bound::Excluded(Arc::new(DataValue::Int32(Some(-1))))
+                max: Bound::Excluded(ValueRef::new(DataValue::Int32(Some(-1))))
             })
         );
 
@@ -1,8 +1,74 @@
+use sqlparser::ast::SqliteOnConflict;
+use sqlparser::dialect::GenericDialect;
+use sqlparser::keywords::Keyword;
 use sqlparser::parser::ParserError;
+use sqlparser::tokenizer::{Token, TokenWithLocation, Tokenizer};
 use sqlparser::{ast::Statement, dialect::PostgreSqlDialect, parser::Parser};
+use std::ops::Deref;
+
+/// A statement of `parse_sql`, along with the parts of it sqlparser 0.34 does not parse.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedStatement {
+    pub stmt: Statement,
+    pub copy_error_options: CopyErrorOptions,
+    /// The `RESTART IDENTITY` of a `TRUNCATE`, `CONTINUE IDENTITY` is the default.
+    pub restart_identity: bool,
+    /// The `OVERRIDING SYSTEM VALUE` of an `INSERT`.
+    pub overriding_system_value: bool,
+}
+
+/// The `ON_ERROR` and `ERROR_LIMIT` following a `COPY .. FROM`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CopyErrorOptions {
+    /// The upper cased value of `ON_ERROR`
+    pub on_error: Option<String>,
+    pub error_limit: Option<u64>,
+}
+
+impl From<Statement> for ParsedStatement {
+    fn from(stmt: Statement) -> Self {
+        ParsedStatement {
+            stmt,
+            copy_error_options: CopyErrorOptions::default(),
+            restart_identity: false,
+            overriding_system_value: false,
+        }
+    }
+}
+
+impl Deref for ParsedStatement {
+    type Target = Statement;
+
+    fn deref(&self) -> &Self::Target {
+        &self.stmt
+    }
+}
 
 /// Parse a string to a collection of statements.
 ///
+/// `TRUNCATE t1, t2` is parsed as a `TRUNCATE` of each table, as sqlparser 0.34 only takes
+/// a single one. They run in the same transaction like all the statements of the string.
+/// Its `RESTART IDENTITY` or `CONTINUE IDENTITY`, which sqlparser 0.34 does not know either,
+/// is kept in `ParsedStatement::restart_identity`.
+///
+/// A `CREATE` with a column of `ON UPDATE expr` is parsed in the generic dialect, the only
+/// one besides MySQL that sqlparser 0.34 parses it in.
+///
+/// `REPLACE INTO` is parsed as the `INSERT` it is in the SQLite dialect, with
+/// `SqliteOnConflict::Replace`, as sqlparser 0.34 only parses it there.
+///
+/// The `OVERRIDING SYSTEM VALUE` of an `INSERT`, which sqlparser 0.34 does not know, is
+/// taken out of the statement and kept in `ParsedStatement::overriding_system_value`.
+///
+/// An `INTERVAL` literal is wrapped in parentheses, see `wrap_interval_literals`.
+///
+/// The subquery of `expr op ANY (subquery)` and `expr op ALL (subquery)` is wrapped in
+/// another pair of parentheses, see `wrap_quantified_subqueries`.
+///
+/// A `COPY .. FROM` may be followed by `ON_ERROR = { ABORT_STATEMENT | CONTINUE }` and
+/// `ERROR_LIMIT = n`, which sqlparser 0.34 does not know, they are kept apart from the
+/// statement in `ParsedStatement::copy_error_options`.
+///
 /// # Example
 /// ```rust
 /// use kip_sql::parser::parse_sql;
@@ -13,7 +79,481 @@ use sqlparser::{ast::Statement, dialect::PostgreSqlDialect, parser::Parser};
 /// let ast = parse_sql(sql).unwrap();
 /// println!("{:?}", ast);
 /// ```
-pub fn parse_sql(sql: &str) -> Result<Vec<Statement>, ParserError> {
+pub fn parse_sql(sql: &str) -> Result<Vec<ParsedStatement>, ParserError> {
     let dialect = PostgreSqlDialect {};
-    Parser::parse_sql(&dialect, sql)
+    let tokens = wrap_quantified_subqueries(wrap_interval_literals(
+        Tokenizer::new(&dialect, sql).tokenize_with_location()?,
+    ));
+    let mut parser = Parser::new(&dialect).with_tokens_with_locations(tokens.clone());
+    let mut stmts = Vec::new();
+    let mut expecting_statement_delimiter = false;
+
+    loop {
+        // the empty statements between successive semicolons are skipped
+        while parser.consume_token(&Token::SemiColon) {
+            expecting_statement_delimiter = false;
+        }
+        if parser.peek_token() == Token::EOF {
+            break;
+        }
+        if expecting_statement_delimiter {
+            return parser.expected("end of statement", parser.peek_token());
+        }
+
+        if parser.parse_keyword(Keyword::TRUNCATE) {
+            let table = parser.parse_keyword(Keyword::TABLE);
+            let table_names = parser.parse_comma_separated(Parser::parse_object_name)?;
+            let restart_identity = parse_truncate_identity(&mut parser)?;
+
+            for table_name in table_names {
+                stmts.push(ParsedStatement {
+                    restart_identity,
+                    ..Statement::Truncate {
+                        table_name,
+                        partitions: None,
+                        table,
+                    }
+                    .into()
+                });
+            }
+        } else if parser.parse_keyword(Keyword::REPLACE) {
+            let mut stmt = parser.parse_insert()?;
+
+            if let Statement::Insert { or, .. } = &mut stmt {
+                *or = Some(SqliteOnConflict::Replace);
+            }
+            stmts.push(stmt.into());
+        } else if let Some((end, insert_tokens)) =
+            insert_overriding_system_value(&tokens, parser.index())
+        {
+            let mut insert_parser = Parser::new(&dialect).with_tokens_with_locations(insert_tokens);
+
+            stmts.push(ParsedStatement {
+                overriding_system_value: true,
+                ..insert_parser.parse_statement()?.into()
+            });
+            if insert_parser.peek_token() != Token::EOF {
+                return insert_parser.expected("end of statement", insert_parser.peek_token());
+            }
+            while parser.index() < end {
+                parser.next_token();
+            }
+        } else if let Some(end) = create_with_on_update(&tokens, parser.index()) {
+            let mut create_parser = Parser::new(&GenericDialect {})
+                .with_tokens_with_locations(tokens[parser.index()..end].to_vec());
+
+            stmts.push(create_parser.parse_statement()?.into());
+            if create_parser.peek_token() != Token::EOF {
+                return create_parser.expected("end of statement", create_parser.peek_token());
+            }
+            while parser.index() < end {
+                parser.next_token();
+            }
+        } else {
+            let mut stmt = ParsedStatement::from(parser.parse_statement()?);
+
+            if let Statement::Copy { to: false, .. } = stmt.stmt {
+                stmt.copy_error_options = parse_copy_error_options(&mut parser)?;
+            }
+            stmts.push(stmt);
+        }
+        expecting_statement_delimiter = true;
+    }
+
+    Ok(stmts)
+}
+
+/// Whether `RESTART IDENTITY` follows the tables of a `TRUNCATE`, rather than
+/// `CONTINUE IDENTITY` or nothing.
+fn parse_truncate_identity(parser: &mut Parser) -> Result<bool, ParserError> {
+    let restart_identity = match parser.peek_token().token {
+        Token::Word(word) if word.value.eq_ignore_ascii_case(TRUNCATE_RESTART) => true,
+        Token::Word(word) if word.value.eq_ignore_ascii_case(TRUNCATE_CONTINUE) => false,
+        _ => return Ok(false),
+    };
+    parser.next_token();
+    parser.expect_keyword(Keyword::IDENTITY)?;
+
+    Ok(restart_identity)
+}
+
+const INSERT_OVERRIDING: &str = "OVERRIDING";
+const TRUNCATE_RESTART: &str = "RESTART";
+const TRUNCATE_CONTINUE: &str = "CONTINUE";
+const COPY_ON_ERROR: &str = "ON_ERROR";
+const COPY_ERROR_LIMIT: &str = "ERROR_LIMIT";
+
+fn parse_copy_error_options(parser: &mut Parser) -> Result<CopyErrorOptions, ParserError> {
+    let mut options = CopyErrorOptions::default();
+
+    loop {
+        match parser.peek_token().token {
+            Token::Word(word) if word.value.eq_ignore_ascii_case(COPY_ON_ERROR) => {
+                parser.next_token();
+                parser.expect_token(&Token::Eq)?;
+                options.on_error = Some(parser.parse_identifier()?.value.to_uppercase());
+            }
+            Token::Word(word) if word.value.eq_ignore_ascii_case(COPY_ERROR_LIMIT) => {
+                parser.next_token();
+                parser.expect_token(&Token::Eq)?;
+                options.error_limit = Some(parser.parse_literal_uint()?);
+            }
+            _ => break,
+        }
+    }
+
+    Ok(options)
+}
+
+/// Wraps `INTERVAL 'value' [unit [TO unit]]` in parentheses, as sqlparser 0.34 takes the
+/// operators following the value for a part of it, e.g. the `>` of
+/// `c1 + INTERVAL '1 day' > c2`.
+fn wrap_interval_literals(tokens: Vec<TokenWithLocation>) -> Vec<TokenWithLocation> {
+    let is_keyword =
+        |token: &Token, keyword| matches!(token, Token::Word(word) if word.keyword == keyword);
+    let is_unit = |token: &Token| {
+        matches!(token, Token::Word(word) if [
+            Keyword::YEAR,
+            Keyword::MONTH,
+            Keyword::WEEK,
+            Keyword::DAY,
+            Keyword::HOUR,
+            Keyword::MINUTE,
+            Keyword::SECOND,
+        ]
+        .contains(&word.keyword))
+    };
+    let words = tokens
+        .iter()
+        .enumerate()
+        .filter(|(_, token)| !matches!(token.token, Token::Whitespace(_)))
+        .map(|(i, token)| (i, &token.token))
+        .collect::<Vec<_>>();
+    // the indexes of the first and the last token of each literal
+    let mut literals = Vec::new();
+    let mut i = 0;
+
+    while i + 1 < words.len() {
+        if !is_keyword(words[i].1, Keyword::INTERVAL)
+            || !matches!(
+                words[i + 1].1,
+                Token::SingleQuotedString(_) | Token::Number(..)
+            )
+        {
+            i += 1;
+            continue;
+        }
+        let is_unit_at = |i: usize| matches!(words.get(i), Some((_, token)) if is_unit(token));
+        let mut end = i + 1;
+
+        if is_unit_at(end + 1) {
+            end += 1;
+
+            if matches!(words.get(end + 1), Some((_, token)) if is_keyword(token, Keyword::TO))
+                && is_unit_at(end + 2)
+            {
+                end += 2;
+            }
+        }
+        literals.push((words[i].0, words[end].0));
+        i = end + 1;
+    }
+
+    if literals.is_empty() {
+        return tokens;
+    }
+    let mut wrapped = Vec::with_capacity(tokens.len() + literals.len() * 2);
+
+    for (i, token) in tokens.into_iter().enumerate() {
+        if literals.iter().any(|(start, _)| *start == i) {
+            wrapped.push(TokenWithLocation::wrap(Token::LParen));
+        }
+        wrapped.push(token);
+
+        if literals.iter().any(|(_, end)| *end == i) {
+            wrapped.push(TokenWithLocation::wrap(Token::RParen));
+        }
+    }
+
+    wrapped
+}
+
+/// Wraps the subquery of `expr op ANY (subquery)` and `expr op ALL (subquery)` in another
+/// pair of parentheses, as sqlparser 0.34 parses an expression alone between the parentheses
+/// of a quantifier, of which a parenthesized subquery is one.
+fn wrap_quantified_subqueries(tokens: Vec<TokenWithLocation>) -> Vec<TokenWithLocation> {
+    let is_keyword =
+        |token: &Token, keyword| matches!(token, Token::Word(word) if word.keyword == keyword);
+    let words = tokens
+        .iter()
+        .enumerate()
+        .filter(|(_, token)| !matches!(token.token, Token::Whitespace(_)))
+        .collect::<Vec<_>>();
+    let opens = words
+        .windows(4)
+        .filter(|words| {
+            matches!(
+                words[0].1.token,
+                Token::Eq | Token::Neq | Token::Lt | Token::Gt | Token::LtEq | Token::GtEq
+            ) && (is_keyword(&words[1].1.token, Keyword::ANY)
+                || is_keyword(&words[1].1.token, Keyword::ALL))
+                && words[2].1.token == Token::LParen
+                && (is_keyword(&words[3].1.token, Keyword::SELECT)
+                    || is_keyword(&words[3].1.token, Keyword::WITH))
+        })
+        .map(|words| words[2].0)
+        .collect::<Vec<_>>();
+
+    if opens.is_empty() {
+        return tokens;
+    }
+    let mut wrapped = Vec::with_capacity(tokens.len() + opens.len() * 2);
+    // the depths of the parentheses wrapped that are not closed yet
+    let mut open_depths = Vec::new();
+    let mut depth = 0usize;
+
+    for (i, token) in tokens.into_iter().enumerate() {
+        match token.token {
+            Token::LParen => depth += 1,
+            Token::RParen => {
+                if open_depths.last() == Some(&depth) {
+                    open_depths.pop();
+                    wrapped.push(TokenWithLocation::wrap(Token::RParen));
+                }
+                depth = depth.saturating_sub(1);
+            }
+            _ => (),
+        }
+        wrapped.push(token);
+
+        if opens.contains(&i) {
+            wrapped.push(TokenWithLocation::wrap(Token::LParen));
+            open_depths.push(depth);
+        }
+    }
+
+    wrapped
+}
+
+/// The end of the tokens of the `CREATE` statement at `start` if it has the keywords
+/// `ON UPDATE`, past its last token that is not a whitespace.
+fn create_with_on_update(tokens: &[TokenWithLocation], start: usize) -> Option<usize> {
+    let is_keyword =
+        |token: &Token, keyword| matches!(token, Token::Word(word) if word.keyword == keyword);
+    let words = tokens
+        .get(start..)?
+        .iter()
+        .enumerate()
+        .take_while(|(_, token)| token.token != Token::SemiColon)
+        .filter(|(_, token)| !matches!(token.token, Token::Whitespace(_)))
+        .collect::<Vec<_>>();
+
+    let (_, first) = words.first()?;
+    let has_on_update = words.windows(2).any(|pair| {
+        is_keyword(&pair[0].1.token, Keyword::ON) && is_keyword(&pair[1].1.token, Keyword::UPDATE)
+    });
+    if !is_keyword(&first.token, Keyword::CREATE) || !has_on_update {
+        return None;
+    }
+
+    words.last().map(|(i, _)| start + i + 1)
+}
+
+/// The end of the tokens of the `INSERT` statement at `start` if it has the keywords
+/// `OVERRIDING SYSTEM VALUE`, with its tokens up to there without them.
+fn insert_overriding_system_value(
+    tokens: &[TokenWithLocation],
+    start: usize,
+) -> Option<(usize, Vec<TokenWithLocation>)> {
+    let is_keyword =
+        |token: &Token, keyword| matches!(token, Token::Word(word) if word.keyword == keyword);
+    let is_overriding = |token: &Token| match token {
+        Token::Word(word) => word.value.eq_ignore_ascii_case(INSERT_OVERRIDING),
+        _ => false,
+    };
+    let words = tokens
+        .get(start..)?
+        .iter()
+        .enumerate()
+        .take_while(|(_, token)| token.token != Token::SemiColon)
+        .filter(|(_, token)| !matches!(token.token, Token::Whitespace(_)))
+        .collect::<Vec<_>>();
+
+    let (_, first) = words.first()?;
+    if !is_keyword(&first.token, Keyword::INSERT) {
+        return None;
+    }
+    let overriding = words.windows(3).find(|words| {
+        is_overriding(&words[0].1.token)
+            && is_keyword(&words[1].1.token, Keyword::SYSTEM)
+            && is_keyword(&words[2].1.token, Keyword::VALUE)
+    })?;
+    let (overriding_start, _) = overriding[0];
+    let (overriding_end, _) = overriding[2];
+    let (last, _) = words.last()?;
+    let insert_tokens = tokens[start..start + overriding_start]
+        .iter()
+        .chain(&tokens[start + overriding_end + 1..start + last + 1])
+        .cloned()
+        .collect();
+
+    Some((start + last + 1, insert_tokens))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::{parse_sql, CopyErrorOptions, ParsedStatement};
+    use sqlparser::ast::{SqliteOnConflict, Statement};
+
+    #[test]
+    fn test_parse_truncate_tables() {
+        let stmts = parse_sql("truncate t1, t2; truncate table t3").unwrap();
+        let table_names = stmts
+            .iter()
+            .map(|stmt| match &**stmt {
+                Statement::Truncate { table_name, .. } => table_name.to_string(),
+                stmt => panic!("unexpected statement {}", stmt),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(table_names, vec!["t1", "t2", "t3"]);
+        assert!(stmts.iter().all(|stmt| !stmt.restart_identity));
+
+        let stmts =
+            parse_sql("truncate t1, t2 restart identity; truncate t3 continue identity").unwrap();
+        assert_eq!(
+            stmts
+                .iter()
+                .map(|stmt| stmt.restart_identity)
+                .collect::<Vec<_>>(),
+            vec![true, true, false]
+        );
+
+        assert!(parse_sql("truncate t1 t2").is_err());
+        assert!(parse_sql("truncate").is_err());
+        assert!(parse_sql("truncate t1 restart").is_err());
+        assert!(parse_sql("truncate t1 identity").is_err());
+    }
+
+    #[test]
+    fn test_parse_replace_into() {
+        let stmts =
+            parse_sql("replace into t (a, b) values (1, 2); insert into t values (3)").unwrap();
+
+        assert!(matches!(
+            *stmts[0],
+            Statement::Insert {
+                or: Some(SqliteOnConflict::Replace),
+                ..
+            }
+        ));
+        assert!(matches!(*stmts[1], Statement::Insert { or: None, .. }));
+
+        assert!(parse_sql("replace into t").is_err());
+    }
+
+    #[test]
+    fn test_parse_overriding_system_value() {
+        let stmts = parse_sql(
+            "insert into t (a, b) overriding system value values (1, 2); insert into t values (3)",
+        )
+        .unwrap();
+
+        assert_eq!(
+            stmts,
+            vec![
+                ParsedStatement {
+                    overriding_system_value: true,
+                    ..parse_sql("insert into t (a, b) values (1, 2)").unwrap()[0].clone()
+                },
+                parse_sql("insert into t values (3)").unwrap()[0].clone(),
+            ]
+        );
+        assert!(!stmts[1].overriding_system_value);
+
+        assert!(parse_sql("insert into t overriding system value").is_err());
+        assert!(parse_sql("insert into t overriding value values (1)").is_err());
+    }
+
+    #[test]
+    fn test_parse_quantified_subqueries() {
+        let stmts = parse_sql(
+            "select a from t where a > all (select b from u where (b) < 3) \
+             and a = any ((select b from u)) and a <> any (with v as (select b from u) select b from v); \
+             select a from t union all (select b from u)",
+        )
+        .unwrap();
+
+        match &*stmts[0] {
+            Statement::Query(query) => assert_eq!(
+                query.to_string(),
+                "SELECT a FROM t WHERE a > ALL((SELECT b FROM u WHERE (b) < 3)) \
+                 AND a = ANY((SELECT b FROM u)) \
+                 AND a <> ANY((WITH v AS (SELECT b FROM u) SELECT b FROM v))"
+            ),
+            stmt => panic!("unexpected statement {}", stmt),
+        }
+        assert!(matches!(*stmts[1], Statement::Query(_)));
+
+        assert!(parse_sql("select a from t where a > all (select b from u").is_err());
+    }
+
+    #[test]
+    fn test_parse_interval_literals() {
+        let stmts = parse_sql(
+            "select a + interval '1 day' > b, interval '3' hour < interval '1' day to hour \
+             from t where a - interval '2 days' >= b",
+        )
+        .unwrap();
+
+        assert_eq!(
+            stmts[0].to_string(),
+            "SELECT a + (INTERVAL '1 day') > b, (INTERVAL '3' HOUR) < (INTERVAL '1' DAY TO HOUR) \
+             FROM t WHERE a - (INTERVAL '2 days') >= b"
+        );
+        assert!(parse_sql("create table t (a interval)").is_ok());
+    }
+
+    #[test]
+    fn test_parse_on_update() {
+        let stmts = parse_sql(
+            "create table t (id int primary key, a datetime null on update now()); \
+             select a from t where id = 1 # 2;",
+        )
+        .unwrap();
+
+        match &*stmts[0] {
+            Statement::CreateTable { columns, .. } => {
+                assert_eq!(columns[1].to_string(), "a DATETIME NULL ON UPDATE now()")
+            }
+            stmt => panic!("unexpected statement {}", stmt),
+        }
+        // the other statements are still of the PostgreSQL dialect, e.g. `#` is the XOR
+        assert!(matches!(*stmts[1], Statement::Query(_)));
+
+        assert!(parse_sql("create table t (id int primary key on update now() now())").is_err());
+    }
+
+    #[test]
+    fn test_parse_copy_error_options() {
+        let stmts = parse_sql(
+            "copy t from 'a.csv' ( DELIMITER '|' ) on_error = continue error_limit = 10; \
+             copy t from 'a.csv' ( FORCE_NULL (on_error, continue) )",
+        )
+        .unwrap();
+
+        assert_eq!(
+            stmts[0].copy_error_options,
+            CopyErrorOptions {
+                on_error: Some("CONTINUE".to_string()),
+                error_limit: Some(10),
+            }
+        );
+        // an option of the statement is never taken for them
+        assert!(matches!(&*stmts[1], Statement::Copy { options, .. } if options.len() == 1));
+        assert_eq!(stmts[1].copy_error_options, CopyErrorOptions::default());
+
+        assert!(parse_sql("copy t from 'a.csv' on_error continue").is_err());
+        assert!(parse_sql("copy t from 'a.csv' error_limit = -1").is_err());
+        assert!(parse_sql("copy t to 'a.csv' on_error = continue").is_err());
+    }
 }
@@ -2,6 +2,10 @@ pub mod operator;
 
 use crate::catalog::TableName;
 use crate::planner::operator::Operator;
+use crate::types::tuple::SchemaRef;
+use itertools::Itertools;
+use serde_json::{json, Value};
+use std::sync::Arc;
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct LogicalPlan {
@@ -28,4 +32,151 @@ impl LogicalPlan {
         collect_table(self, &mut tables);
         tables
     }
+
+    /// The plan as a tree of one operator per line, the children indented below their parent.
+    pub fn explain(&self, indentation: usize) -> String {
+        let mut result = format!("{:indentation$}{}", "", self.operator);
+
+        for child in &self.childrens {
+            result.push('\n');
+            result.push_str(&child.explain(indentation + 2));
+        }
+        result
+    }
+
+    /// The plan as a JSON tree for programs, each node is the object of
+    /// `Operator::explain_json` along with the names of its output `columns`, if the
+    /// operator produces tuples, and its `children`.
+    pub fn explain_json(&self) -> Value {
+        let mut node = self.operator.explain_json();
+
+        if let Some(schema) = self.output_schema() {
+            node.insert(
+                "columns".to_string(),
+                json!(schema.iter().map(|col| col.name()).collect_vec()),
+            );
+        }
+        node.insert(
+            "children".to_string(),
+            Value::Array(
+                self.childrens
+                    .iter()
+                    .map(LogicalPlan::explain_json)
+                    .collect(),
+            ),
+        );
+
+        Value::Object(node)
+    }
+
+    /// The columns of the tuples the plan produces, computed from its operators without
+    /// executing it, e.g. the columns a subquery or a set operation is bound against.
+    /// Empty for the statements that are not queries.
+    pub fn schema(&self) -> SchemaRef {
+        self.output_schema().unwrap_or_default()
+    }
+
+    /// `LogicalPlan::schema`, `None` for the statements that are not queries.
+    pub fn output_schema(&self) -> Option<SchemaRef> {
+        let child_schema = |index: usize| self.child(index).and_then(LogicalPlan::output_schema);
+
+        match &self.operator {
+            Operator::Dummy => Some(Arc::new(vec![])),
+            Operator::Aggregate(op) => Some(op.output_schema()),
+            Operator::Project(op) => Some(op.output_schema()),
+            Operator::Scan(op) => Some(op.output_schema()),
+            Operator::Values(op) => Some(Arc::new(op.columns.clone())),
+            Operator::Join(op) => Some(op.output_schema(&child_schema(0)?, &child_schema(1)?)),
+            Operator::Window(op) => Some(op.output_schema(&child_schema(0)?)),
+            Operator::Insert(op) if !op.returning.is_empty() => Some(op.output_schema()),
+            // the tuples take the schema of the left side
+            Operator::SetOperation(_)
+            | Operator::Filter(_)
+            | Operator::Sort(_)
+            | Operator::DistinctOn(_)
+            | Operator::Limit(_) => child_schema(0),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::binder::test::select_sql_run;
+    use crate::execution::ExecutorError;
+    use crate::planner::operator::Operator;
+    use crate::planner::LogicalPlan;
+    use crate::types::LogicalType;
+
+    #[tokio::test]
+    async fn test_output_schema() -> Result<(), ExecutorError> {
+        let plan =
+            select_sql_run("select c1, c4, c2 + c3 as s from t1 left join t2 on c1 = c3").await?;
+        let schema = plan.output_schema().unwrap();
+
+        assert_eq!(
+            schema
+                .iter()
+                .map(|col| (col.name(), *col.datatype(), col.nullable))
+                .collect::<Vec<_>>(),
+            vec![
+                ("c1", LogicalType::Integer, false),
+                ("c4", LogicalType::Integer, true),
+                ("s", LogicalType::Integer, true),
+            ]
+        );
+        // the join has the columns of both tables
+        let join_schema = plan.child(0).unwrap().output_schema().unwrap();
+        assert_eq!(
+            join_schema.iter().map(|col| col.name()).collect::<Vec<_>>(),
+            vec!["c1", "c2", "c3", "c4"]
+        );
+        assert!(select_sql_run("insert into t1 values (1, 1)")
+            .await?
+            .output_schema()
+            .is_none());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_schema() -> Result<(), ExecutorError> {
+        let names = |plan: &LogicalPlan| {
+            plan.schema()
+                .iter()
+                .map(|col| (col.name().to_string(), *col.datatype()))
+                .collect::<Vec<_>>()
+        };
+        let plan = select_sql_run("select c2 as a, c1 + 1 from t1").await?;
+        assert_eq!(
+            names(&plan),
+            vec![
+                ("a".to_string(), LogicalType::Integer),
+                ("(c1 + 1)".to_string(), LogicalType::Integer),
+            ]
+        );
+
+        let plan = select_sql_run("select * from t1 join t2 on c1 = c3 order by c2").await?;
+        assert_eq!(names(plan.child(0).unwrap()), names(&plan),);
+        let join = plan.child(0).unwrap().child(0).unwrap();
+        assert!(matches!(join.operator, Operator::Join(_)));
+        assert_eq!(
+            names(join),
+            vec![
+                ("c1".to_string(), LogicalType::Integer),
+                ("c2".to_string(), LogicalType::Integer),
+                ("c3".to_string(), LogicalType::Integer),
+                ("c4".to_string(), LogicalType::Integer),
+            ]
+        );
+        // the columns of a set operation are those of its left side
+        let plan = select_sql_run("select c1 from t1 union select c3 from t2").await?;
+        assert_eq!(names(&plan), vec![("c1".to_string(), LogicalType::Integer)]);
+        assert!(select_sql_run("insert into t1 values (1, 1)")
+            .await?
+            .schema()
+            .is_empty());
+
+        Ok(())
+    }
 }
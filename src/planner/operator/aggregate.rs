@@ -1,10 +1,18 @@
 use crate::planner::LogicalPlan;
+use crate::types::tuple::SchemaRef;
 use crate::{expression::ScalarExpression, planner::operator::Operator};
+use itertools::Itertools;
+use std::fmt;
+use std::fmt::Formatter;
+use std::sync::Arc;
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct AggregateOperator {
     pub groupby_exprs: Vec<ScalarExpression>,
     pub agg_calls: Vec<ScalarExpression>,
+    /// Sets of indexes into `groupby_exprs` for `ROLLUP`/`CUBE`/`GROUPING SETS`,
+    /// empty for a plain group by on all `groupby_exprs`.
+    pub grouping_sets: Vec<Vec<usize>>,
 }
 
 impl AggregateOperator {
@@ -12,13 +20,37 @@ impl AggregateOperator {
         children: LogicalPlan,
         agg_calls: Vec<ScalarExpression>,
         groupby_exprs: Vec<ScalarExpression>,
+        grouping_sets: Vec<Vec<usize>>,
     ) -> LogicalPlan {
         LogicalPlan {
             operator: Operator::Aggregate(Self {
                 groupby_exprs,
                 agg_calls,
+                grouping_sets,
             }),
             childrens: vec![children],
         }
     }
+
+    /// The agg calls followed by the group by exprs.
+    pub fn output_schema(&self) -> SchemaRef {
+        Arc::new(
+            self.agg_calls
+                .iter()
+                .chain(self.groupby_exprs.iter())
+                .map(ScalarExpression::output_columns)
+                .collect(),
+        )
+    }
+}
+
+impl fmt::Display for AggregateOperator {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "Aggregate [{}]", self.agg_calls.iter().join(", "))?;
+        if !self.groupby_exprs.is_empty() {
+            write!(f, " -> Group By [{}]", self.groupby_exprs.iter().join(", "))?;
+        }
+
+        Ok(())
+    }
 }
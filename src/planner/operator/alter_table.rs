@@ -0,0 +1,20 @@
+use crate::catalog::TableName;
+use crate::types::ColumnId;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct AlterTableOperator {
+    pub table_name: TableName,
+    pub operation: AlterTableOperation,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum AlterTableOperation {
+    /// `RENAME TO new_table_name`
+    RenameTable { new_table_name: TableName },
+    /// `RENAME COLUMN old_column_name TO new_column_name`
+    RenameColumn {
+        column_id: ColumnId,
+        old_column_name: String,
+        new_column_name: String,
+    },
+}
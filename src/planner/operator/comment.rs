@@ -0,0 +1,11 @@
+use crate::catalog::TableName;
+use crate::types::ColumnId;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct CommentOperator {
+    pub table_name: TableName,
+    /// The column commented on, `None` for the table itself.
+    pub column_id: Option<ColumnId>,
+    /// `IS NULL` removes the comment.
+    pub comment: Option<String>,
+}
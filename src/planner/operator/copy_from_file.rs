@@ -1,4 +1,4 @@
-use crate::binder::copy::ExtSource;
+use crate::binder::copy::{ExtSource, OnError};
 use crate::catalog::ColumnRef;
 use crate::types::LogicalType;
 
@@ -8,4 +8,5 @@ pub struct CopyFromFileOperator {
     pub source: ExtSource,
     pub types: Vec<LogicalType>,
     pub columns: Vec<ColumnRef>,
+    pub on_error: OnError,
 }
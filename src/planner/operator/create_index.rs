@@ -0,0 +1,10 @@
+use crate::catalog::TableName;
+use crate::types::index::IndexMeta;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct CreateIndexOperator {
+    pub table_name: TableName,
+    /// The index to add, its id is given by the table
+    pub index_meta: IndexMeta,
+    pub if_not_exists: bool,
+}
@@ -6,5 +6,7 @@ pub struct CreateTableOperator {
     pub table_name: TableName,
     /// List of columns of the table
     pub columns: Vec<ColumnCatalog>,
+    /// Unique constraints spanning multiple columns, e.g. `UNIQUE (a, b)`
+    pub unique_keys: Vec<Vec<String>>,
     pub if_not_exists: bool,
 }
@@ -0,0 +1,28 @@
+use crate::expression::ScalarExpression;
+use crate::planner::LogicalPlan;
+use itertools::Itertools;
+
+use super::Operator;
+use std::fmt;
+use std::fmt::Formatter;
+
+/// `SELECT DISTINCT ON (..)`, the first tuple of each key out of the tuples sorted by it.
+#[derive(Debug, PartialEq, Clone)]
+pub struct DistinctOnOperator {
+    pub on_exprs: Vec<ScalarExpression>,
+}
+
+impl DistinctOnOperator {
+    pub fn build(on_exprs: Vec<ScalarExpression>, children: LogicalPlan) -> LogicalPlan {
+        LogicalPlan {
+            operator: Operator::DistinctOn(DistinctOnOperator { on_exprs }),
+            childrens: vec![children],
+        }
+    }
+}
+
+impl fmt::Display for DistinctOnOperator {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "Distinct On [{}]", self.on_exprs.iter().join(", "))
+    }
+}
@@ -4,6 +4,8 @@ use crate::expression::ScalarExpression;
 use crate::planner::LogicalPlan;
 
 use super::Operator;
+use std::fmt;
+use std::fmt::Formatter;
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct FilterOperator {
@@ -19,3 +21,11 @@ impl FilterOperator {
         }
     }
 }
+
+impl fmt::Display for FilterOperator {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let name = if self.having { "Having" } else { "Filter" };
+
+        write!(f, "{} {}", name, self.predicate)
+    }
+}
@@ -1,7 +1,30 @@
 use crate::catalog::TableName;
+use crate::expression::ScalarExpression;
+use crate::types::tuple::SchemaRef;
+use std::sync::Arc;
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct InsertOperator {
     pub table_name: TableName,
     pub is_overwrite: bool,
+    /// `REPLACE INTO`, the tuples having the primary key or the value of a unique index of
+    /// a row are deleted before it is written.
+    pub is_replace: bool,
+    /// `OVERRIDING SYSTEM VALUE`, the values given to the identity column are written even if
+    /// it is `GENERATED ALWAYS`, and the ids up to them are not given out anymore.
+    pub overriding_system_value: bool,
+    /// The items of `RETURNING`, evaluated over each row as written, with the values the
+    /// insertion generated. Nothing is returned without them.
+    pub returning: Vec<ScalarExpression>,
+}
+
+impl InsertOperator {
+    pub fn output_schema(&self) -> SchemaRef {
+        Arc::new(
+            self.returning
+                .iter()
+                .map(ScalarExpression::output_columns)
+                .collect(),
+        )
+    }
 }
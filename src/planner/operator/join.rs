@@ -1,7 +1,15 @@
+use crate::catalog::ColumnCatalog;
+use crate::execution::executor::dql::join::joins_nullable;
 use crate::expression::ScalarExpression;
 use crate::planner::LogicalPlan;
+use crate::types::index::IndexMetaRef;
+use crate::types::tuple::SchemaRef;
+use std::sync::Arc;
 
 use super::Operator;
+use itertools::Itertools;
+use std::fmt;
+use std::fmt::Formatter;
 
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub enum JoinType {
@@ -10,6 +18,15 @@ pub enum JoinType {
     Right,
     Full,
     Cross,
+    /// Each tuple of the right side is joined with the single tuple of the left side,
+    /// or with nulls if there is none, more than one tuple on the left is an error.
+    /// The left side is a scalar subquery, which is evaluated only once.
+    Single,
+    /// `EXISTS`, each tuple of the left side with a matching tuple of the right side,
+    /// which is only checked for, the output is the left side alone.
+    Semi,
+    /// `NOT EXISTS`, each tuple of the left side without a matching tuple of the right side.
+    Anti,
 }
 #[derive(Debug, Clone, PartialEq)]
 pub enum JoinCondition {
@@ -26,6 +43,9 @@ pub enum JoinCondition {
 pub struct JoinOperator {
     pub on: JoinCondition,
     pub join_type: JoinType,
+    // The index of the right key of a semi join over a single scan, it is probed for each
+    // left tuple instead of hashing the right side.
+    pub index_probe: Option<IndexMetaRef>,
 }
 
 impl JoinOperator {
@@ -36,8 +56,63 @@ impl JoinOperator {
         join_type: JoinType,
     ) -> LogicalPlan {
         LogicalPlan {
-            operator: Operator::Join(JoinOperator { on, join_type }),
+            operator: Operator::Join(JoinOperator {
+                on,
+                join_type,
+                index_probe: None,
+            }),
             childrens: vec![left, right],
         }
     }
+
+    /// The columns of `left` followed by those of `right`, whose nullability is
+    /// the one given by the join type. A semi join only has the columns of `left`.
+    pub fn output_schema(&self, left: &SchemaRef, right: &SchemaRef) -> SchemaRef {
+        if matches!(self.join_type, JoinType::Semi | JoinType::Anti) {
+            return left.clone();
+        }
+        let (left_force_nullable, right_force_nullable) = joins_nullable(&self.join_type);
+        let with_nullable = |schema: &SchemaRef, nullable: bool| {
+            schema
+                .iter()
+                .map(move |col| {
+                    let mut col = ColumnCatalog::clone(col);
+                    col.nullable = nullable;
+
+                    Arc::new(col)
+                })
+                .collect::<Vec<_>>()
+        };
+
+        Arc::new(
+            with_nullable(left, left_force_nullable)
+                .into_iter()
+                .chain(with_nullable(right, right_force_nullable))
+                .collect(),
+        )
+    }
+}
+
+impl fmt::Display for JoinOperator {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{:?} Join", self.join_type)?;
+        if let JoinCondition::On { on, filter } = &self.on {
+            if !on.is_empty() {
+                let on = on
+                    .iter()
+                    .map(|(left_expr, right_expr)| format!("{} = {}", left_expr, right_expr))
+                    .join(" AND ");
+
+                write!(f, " On {}", on)?;
+            }
+            if let Some(filter) = filter {
+                write!(f, ", Filter {}", filter)?;
+            }
+        }
+        if let Some(index_meta) = &self.index_probe {
+            write!(f, ", Probe {}", index_meta.name)?;
+        }
+
+        Ok(())
+    }
 }
@@ -1,6 +1,8 @@
 use crate::planner::LogicalPlan;
 
 use super::Operator;
+use std::fmt;
+use std::fmt::Formatter;
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct LimitOperator {
@@ -20,3 +22,17 @@ impl LimitOperator {
         }
     }
 }
+
+impl fmt::Display for LimitOperator {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "Limit")?;
+        if let Some(limit) = self.limit {
+            write!(f, " {}", limit)?;
+        }
+        if let Some(offset) = self.offset {
+            write!(f, ", Offset {}", offset)?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,8 +1,12 @@
 pub mod aggregate;
+pub mod alter_table;
+pub mod comment;
 pub mod copy_from_file;
 pub mod copy_to_file;
+pub mod create_index;
 pub mod create_table;
 pub mod delete;
+pub mod distinct_on;
 pub mod drop_table;
 pub mod filter;
 pub mod insert;
@@ -10,41 +14,63 @@ pub mod join;
 pub mod limit;
 pub mod project;
 pub mod scan;
+pub mod set_operation;
+pub mod set_variable;
 pub mod show;
 pub mod sort;
 pub mod truncate;
 pub mod update;
 pub mod values;
+pub mod window;
 
 use crate::catalog::ColumnRef;
+use crate::expression::simplify::ConstantBinary;
+use crate::expression::ScalarExpression;
+use crate::planner::operator::alter_table::{AlterTableOperation, AlterTableOperator};
+use crate::planner::operator::comment::CommentOperator;
 use crate::planner::operator::copy_from_file::CopyFromFileOperator;
 use crate::planner::operator::copy_to_file::CopyToFileOperator;
+use crate::planner::operator::create_index::CreateIndexOperator;
 use crate::planner::operator::create_table::CreateTableOperator;
 use crate::planner::operator::delete::DeleteOperator;
+use crate::planner::operator::distinct_on::DistinctOnOperator;
 use crate::planner::operator::drop_table::DropTableOperator;
 use crate::planner::operator::insert::InsertOperator;
 use crate::planner::operator::join::JoinCondition;
-use crate::planner::operator::show::ShowTablesOperator;
+use crate::planner::operator::set_operation::SetOperationOperator;
+use crate::planner::operator::set_variable::SetVariableOperator;
+use crate::planner::operator::show::{
+    ShowCreateTableOperator, ShowIndexOperator, ShowTablesOperator,
+};
 use crate::planner::operator::truncate::TruncateOperator;
 use crate::planner::operator::update::UpdateOperator;
 use crate::planner::operator::values::ValuesOperator;
+use crate::planner::operator::window::WindowOperator;
+use crate::types::index::IndexMetaRef;
 use itertools::Itertools;
+use serde_json::{json, Map, Value};
+use std::fmt;
+use std::fmt::Formatter;
+use strum_macros::AsRefStr;
 
 use self::{
     aggregate::AggregateOperator, filter::FilterOperator, join::JoinOperator, limit::LimitOperator,
     project::ProjectOperator, scan::ScanOperator, sort::SortOperator,
 };
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone, AsRefStr)]
 pub enum Operator {
     // DQL
     Dummy,
     Aggregate(AggregateOperator),
     Filter(FilterOperator),
     Join(JoinOperator),
+    SetOperation(SetOperationOperator),
     Project(ProjectOperator),
     Scan(ScanOperator),
     Sort(SortOperator),
+    DistinctOn(DistinctOnOperator),
+    Window(WindowOperator),
     Limit(LimitOperator),
     Values(ValuesOperator),
     // DML
@@ -53,13 +79,31 @@ pub enum Operator {
     Delete(DeleteOperator),
     // DDL
     CreateTable(CreateTableOperator),
+    CreateIndex(CreateIndexOperator),
     DropTable(DropTableOperator),
     Truncate(TruncateOperator),
+    AlterTable(AlterTableOperator),
+    Comment(CommentOperator),
     // Show
     Show(ShowTablesOperator),
+    ShowCreateTable(ShowCreateTableOperator),
+    ShowIndex(ShowIndexOperator),
     // Copy
     CopyFromFile(CopyFromFileOperator),
     CopyToFile(CopyToFileOperator),
+    // applied to the settings of the session by the `Database`, it is not executed
+    SetVariable(SetVariableOperator),
+    // the optimized plan of its child, which is not executed
+    Explain(ExplainFormat),
+}
+
+/// How `EXPLAIN` shows the plan, `EXPLAIN FORMAT JSON` for `Json`.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ExplainFormat {
+    /// A line for each operator, indented under its parent.
+    Text,
+    /// A single JSON tree of the operators, see `LogicalPlan::explain_json`.
+    Json,
 }
 
 impl Operator {
@@ -103,8 +147,165 @@ impl Operator {
                 .map(|field| &field.expr)
                 .flat_map(|expr| expr.referenced_columns(only_column_ref))
                 .collect_vec(),
+            Operator::DistinctOn(op) => op
+                .on_exprs
+                .iter()
+                .flat_map(|expr| expr.referenced_columns(only_column_ref))
+                .collect_vec(),
+            Operator::Window(op) => op
+                .window_calls
+                .iter()
+                .flat_map(|expr| expr.referenced_columns(only_column_ref))
+                .collect_vec(),
             Operator::Values(op) => op.columns.clone(),
             _ => vec![],
         }
     }
+
+    /// The node of the operator in `LogicalPlan::explain_json`: its `operator` type, its
+    /// line of `EXPLAIN` as the `detail`, and the key properties of the operators of queries.
+    pub fn explain_json(&self) -> Map<String, Value> {
+        let exprs = |exprs: &[ScalarExpression]| exprs.iter().map(|e| e.to_string()).collect_vec();
+        let binaries =
+            |binaries: &[ConstantBinary]| binaries.iter().map(|b| b.to_string()).collect_vec();
+
+        let properties = match self {
+            Operator::Aggregate(op) => json!({
+                "agg_calls": exprs(&op.agg_calls),
+                "group_by": exprs(&op.groupby_exprs),
+            }),
+            Operator::Filter(op) => json!({
+                "predicate": op.predicate.to_string(),
+                "having": op.having,
+            }),
+            Operator::Join(op) => {
+                let (on, filter) = match &op.on {
+                    JoinCondition::On { on, filter } => (
+                        on.iter()
+                            .map(|(left, right)| json!([left.to_string(), right.to_string()]))
+                            .collect_vec(),
+                        filter.as_ref().map(|filter| filter.to_string()),
+                    ),
+                    JoinCondition::None => (vec![], None),
+                };
+
+                json!({
+                    "join_type": format!("{:?}", op.join_type),
+                    "on": on,
+                    "filter": filter,
+                    "index_probe": op.index_probe.as_ref().map(|index_meta| &index_meta.name),
+                })
+            }
+            Operator::Project(op) => json!({ "exprs": exprs(&op.exprs) }),
+            Operator::Scan(op) => {
+                let index = |(index_meta, bounds): &(IndexMetaRef, Vec<ConstantBinary>)| {
+                    json!({
+                        "name": index_meta.name,
+                        "bounds": binaries(bounds),
+                    })
+                };
+
+                json!({
+                    "table": op.table_name.as_str(),
+                    "index": op.index_by.as_ref().map(index),
+                    "index_intersection": op.index_intersection.iter().map(index).collect_vec(),
+                    "index_only": op.index_only,
+                    "filter": op.filter.as_ref().map(|filter| filter.to_string()),
+                    "agg_calls": exprs(&op.agg_calls),
+                    "limit": op.limit.1,
+                    "offset": op.limit.0,
+                })
+            }
+            Operator::Sort(op) => json!({
+                "sort_fields": op.sort_fields.iter().map(|field| field.to_string()).collect_vec(),
+                "limit": op.limit,
+            }),
+            Operator::DistinctOn(op) => json!({ "on": exprs(&op.on_exprs) }),
+            Operator::Window(op) => json!({ "window_calls": exprs(&op.window_calls) }),
+            Operator::Limit(op) => json!({
+                "limit": op.limit,
+                "offset": op.offset,
+            }),
+            _ => json!({}),
+        };
+        let mut node = Map::new();
+        node.insert("operator".to_string(), json!(self.as_ref()));
+        node.insert("detail".to_string(), json!(self.to_string()));
+        if let Value::Object(properties) = properties {
+            node.extend(properties);
+        }
+
+        node
+    }
+}
+
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Operator::Dummy => write!(f, "Dummy"),
+            Operator::Aggregate(op) => write!(f, "{}", op),
+            Operator::Filter(op) => write!(f, "{}", op),
+            Operator::Join(op) => write!(f, "{}", op),
+            Operator::SetOperation(op) => write!(f, "{}", op),
+            Operator::Project(op) => write!(f, "{}", op),
+            Operator::Scan(op) => write!(f, "{}", op),
+            Operator::Sort(op) => write!(f, "{}", op),
+            Operator::DistinctOn(op) => write!(f, "{}", op),
+            Operator::Window(op) => write!(f, "{}", op),
+            Operator::Limit(op) => write!(f, "{}", op),
+            Operator::Values(op) => write!(f, "{}", op),
+            Operator::Insert(op) => {
+                write!(f, "Insert {}", op.table_name)?;
+                if op.is_overwrite {
+                    write!(f, ", Overwrite")?;
+                }
+                if op.is_replace {
+                    write!(f, ", Replace")?;
+                }
+                if !op.returning.is_empty() {
+                    write!(f, ", Returning [{}]", op.returning.iter().join(", "))?;
+                }
+
+                Ok(())
+            }
+            Operator::Update(op) => write!(f, "Update {}", op.table_name),
+            Operator::Delete(op) => write!(f, "Delete {}", op.table_name),
+            Operator::CreateTable(op) => write!(f, "Create Table {}", op.table_name),
+            Operator::CreateIndex(op) => write!(
+                f,
+                "Create Index {} On {}",
+                op.index_meta.name, op.table_name
+            ),
+            Operator::DropTable(op) => write!(f, "Drop Table {}", op.table_name),
+            Operator::Truncate(op) => write!(f, "Truncate {}", op.table_name),
+            Operator::AlterTable(op) => match &op.operation {
+                AlterTableOperation::RenameTable { new_table_name } => {
+                    write!(
+                        f,
+                        "Alter Table {} Rename To {}",
+                        op.table_name, new_table_name
+                    )
+                }
+                AlterTableOperation::RenameColumn {
+                    old_column_name,
+                    new_column_name,
+                    ..
+                } => write!(
+                    f,
+                    "Alter Table {} Rename Column {} To {}",
+                    op.table_name, old_column_name, new_column_name
+                ),
+            },
+            Operator::Comment(op) => write!(f, "Comment On {}", op.table_name),
+            Operator::Show(_) => write!(f, "Show Tables"),
+            Operator::ShowCreateTable(op) => write!(f, "Show Create Table {}", op.table_name),
+            Operator::ShowIndex(op) => write!(f, "Show Index {}", op.table_name),
+            Operator::CopyFromFile(op) => {
+                write!(f, "Copy {} -> {}", op.source.path.display(), op.table)
+            }
+            Operator::CopyToFile(op) => write!(f, "Copy To {}", op.source.path.display()),
+            Operator::SetVariable(op) => write!(f, "Set {}", op.variable),
+            Operator::Explain(_) => write!(f, "Explain"),
+        }
+    }
 }
@@ -1,6 +1,28 @@
 use crate::expression::ScalarExpression;
+use crate::types::tuple::SchemaRef;
+use itertools::Itertools;
+use std::fmt;
+use std::fmt::Formatter;
+use std::sync::Arc;
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct ProjectOperator {
     pub exprs: Vec<ScalarExpression>,
 }
+
+impl ProjectOperator {
+    pub fn output_schema(&self) -> SchemaRef {
+        Arc::new(
+            self.exprs
+                .iter()
+                .map(ScalarExpression::output_columns)
+                .collect(),
+        )
+    }
+}
+
+impl fmt::Display for ProjectOperator {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "Projection [{}]", self.exprs.iter().join(", "))
+    }
+}
@@ -1,12 +1,17 @@
 use crate::catalog::{TableCatalog, TableName};
 use crate::expression::simplify::ConstantBinary;
 use crate::expression::ScalarExpression;
+use crate::planner::operator::sort::SortField;
 use crate::planner::LogicalPlan;
 use crate::storage::Bounds;
-use crate::types::index::IndexMetaRef;
+use crate::types::index::{IndexMeta, IndexMetaRef};
+use crate::types::tuple::SchemaRef;
 use itertools::Itertools;
+use std::sync::Arc;
 
 use super::Operator;
+use std::fmt;
+use std::fmt::Formatter;
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct ScanOperator {
@@ -21,6 +26,19 @@ pub struct ScanOperator {
     // Support push down predicate.
     // If pre_where is simple predicate, for example:  a > 1 then can calculate directly when read data.
     pub index_by: Option<(IndexMetaRef, Vec<ConstantBinary>)>,
+    // The other indexes bounding the predicate, only the tuples found in `index_by` and in
+    // all of them are read.
+    pub index_intersection: Vec<(IndexMetaRef, Vec<ConstantBinary>)>,
+    // The index of `index_by` covers all columns, so the tuples are never read.
+    pub index_only: bool,
+    // The residual predicate of a `Filter` the index bounds do not answer, evaluated on the
+    // tuples read so those failing it never leave the storage. The limit counts the others.
+    pub filter: Option<ScalarExpression>,
+    // Support push down global aggregate.
+    // The tuples read are folded into `agg_calls` and the scan yields a single tuple of them.
+    pub agg_calls: Vec<ScalarExpression>,
+    // `SELECT ... FOR UPDATE`, the tuples read are locked until the transaction ends.
+    pub lock: bool,
 }
 impl ScanOperator {
     pub fn build(table_name: TableName, table_catalog: &TableCatalog) -> LogicalPlan {
@@ -39,8 +57,135 @@ impl ScanOperator {
 
                 limit: (None, None),
                 index_by: None,
+                index_intersection: vec![],
+                index_only: false,
+                filter: None,
+                agg_calls: vec![],
+                lock: false,
             }),
             childrens: vec![],
         }
     }
+
+    /// The columns read, which are the agg calls once a global aggregate is pushed down.
+    pub fn output_schema(&self) -> SchemaRef {
+        let exprs = if self.agg_calls.is_empty() {
+            &self.columns
+        } else {
+            &self.agg_calls
+        };
+
+        Arc::new(exprs.iter().map(ScalarExpression::output_columns).collect())
+    }
+
+    /// Whether the tuples are read in the order of `sort_fields`, which holds for the
+    /// leading columns of the index scanned by, or of the primary key for a full scan.
+    /// Neither of them has null values, so the placement of nulls does not matter.
+    pub(crate) fn is_ordered_by(&self, sort_fields: &[SortField]) -> bool {
+        let column_ids = match &self.index_by {
+            // the entries of an expression index are ordered by the value of the expression
+            Some((index_meta, _)) if index_meta.expr.is_some() => return false,
+            Some((index_meta, _)) => index_meta.column_ids.clone(),
+            None => self
+                .index_metas
+                .iter()
+                .find(|index_meta| index_meta.is_primary)
+                .map(|index_meta| index_meta.column_ids.clone())
+                .unwrap_or_default(),
+        };
+
+        !sort_fields.is_empty()
+            && sort_fields.len() <= column_ids.len()
+            && sort_fields.iter().zip(column_ids).all(|(field, col_id)| {
+                match field.expr.unpack_alias() {
+                    ScalarExpression::ColumnRef(col) => field.asc && col.id() == Some(col_id),
+                    _ => false,
+                }
+            })
+    }
+
+    /// Whether the `Prefix`es of the composite index find all the tuples they bound. The tuples
+    /// with a null in any indexed column have no entry, so the columns after the bounded
+    /// ones must be read by the scan as not null.
+    pub(crate) fn is_prefix_complete(
+        &self,
+        index_meta: &IndexMeta,
+        binaries: &[ConstantBinary],
+    ) -> bool {
+        let Some(ConstantBinary::Prefix { values, .. }) = binaries.first() else {
+            return false;
+        };
+
+        index_meta.column_ids[values.len() + 1..]
+            .iter()
+            .all(|col_id| {
+                self.columns.iter().any(|expr| match expr {
+                    ScalarExpression::ColumnRef(col) => col.id() == Some(*col_id) && !col.nullable,
+                    _ => false,
+                })
+            })
+    }
+
+    /// Whether the values kept in the entries of the index, the indexed columns and the
+    /// primary key, are enough for all columns of the scan.
+    pub(crate) fn is_covered_by(&self, index_meta: &IndexMeta) -> bool {
+        index_meta.is_unique
+            && !index_meta.is_primary
+            && index_meta.expr.is_none()
+            && self.columns.iter().all(|expr| match expr {
+                ScalarExpression::ColumnRef(col) => {
+                    col.desc.is_primary
+                        || col
+                            .id()
+                            .map_or(false, |col_id| index_meta.column_ids.contains(&col_id))
+                }
+                _ => false,
+            })
+    }
+}
+
+impl fmt::Display for ScanOperator {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let name = if self.index_by.is_some() {
+            "IndexScan"
+        } else {
+            "SeqScan"
+        };
+
+        write!(
+            f,
+            "{} {} -> [{}]",
+            name,
+            self.table_name,
+            self.columns.iter().join(", ")
+        )?;
+        if let Some((index_meta, _)) = &self.index_by {
+            write!(f, ", Index {}", index_meta.name)?;
+            for (index_meta, _) in &self.index_intersection {
+                write!(f, " & {}", index_meta.name)?;
+            }
+            if self.index_only {
+                write!(f, " Only")?;
+            }
+        }
+        if let Some(filter) = &self.filter {
+            write!(f, ", Filter {}", filter)?;
+        }
+        if !self.agg_calls.is_empty() {
+            write!(f, ", Aggregate [{}]", self.agg_calls.iter().join(", "))?;
+        }
+        // the bounds pushed down, the scan stops reading at them
+        let (offset, limit) = self.limit;
+        if let Some(limit) = limit {
+            write!(f, ", Limit {}", limit)?;
+        }
+        if let Some(offset) = offset {
+            write!(f, ", Offset {}", offset)?;
+        }
+        if self.lock {
+            write!(f, ", For Update")?;
+        }
+
+        Ok(())
+    }
 }
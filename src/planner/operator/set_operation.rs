@@ -0,0 +1,29 @@
+use std::fmt;
+use std::fmt::Formatter;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum SetOperationKind {
+    Union,
+    Except,
+    Intersect,
+}
+
+/// Combines the tuples of its two children, which have the same number of columns of
+/// the same types. The tuples are told apart like by `DISTINCT`, with `all` the duplicates
+/// are kept by their multiplicity instead.
+#[derive(Debug, PartialEq, Clone)]
+pub struct SetOperationOperator {
+    pub kind: SetOperationKind,
+    pub all: bool,
+}
+
+impl fmt::Display for SetOperationOperator {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{:?}", self.kind)?;
+        if self.all {
+            write!(f, " All")?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,48 @@
+use crate::planner::operator::sort::NullsOrder;
+use std::fmt;
+use std::fmt::Formatter;
+
+/// A setting of the session changed by `SET name = value`, with its value.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Variable {
+    StrictTypes(bool),
+    SafeUpdates(bool),
+    PreferFirstTable(bool),
+    AllowCartesianProducts(bool),
+    NullsOrder(NullsOrder),
+    /// `None` for no limit, `SET max_rows = NULL`.
+    MaxRows(Option<usize>),
+}
+
+impl Variable {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Variable::StrictTypes(_) => "strict_types",
+            Variable::SafeUpdates(_) => "safe_updates",
+            Variable::PreferFirstTable(_) => "prefer_first_table",
+            Variable::AllowCartesianProducts(_) => "allow_cartesian_products",
+            Variable::NullsOrder(_) => "nulls_order",
+            Variable::MaxRows(_) => "max_rows",
+        }
+    }
+}
+
+impl fmt::Display for Variable {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Variable::StrictTypes(value)
+            | Variable::SafeUpdates(value)
+            | Variable::PreferFirstTable(value)
+            | Variable::AllowCartesianProducts(value) => write!(f, "{} = {}", self.name(), value),
+            Variable::NullsOrder(NullsOrder::Largest) => write!(f, "{} = largest", self.name()),
+            Variable::NullsOrder(NullsOrder::Smallest) => write!(f, "{} = smallest", self.name()),
+            Variable::MaxRows(Some(rows)) => write!(f, "{} = {}", self.name(), rows),
+            Variable::MaxRows(None) => write!(f, "{} = null", self.name()),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct SetVariableOperator {
+    pub variable: Variable,
+}
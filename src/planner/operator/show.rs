@@ -1,2 +1,17 @@
+use crate::catalog::TableName;
+
 #[derive(Debug, PartialEq, Clone)]
-pub struct ShowTablesOperator {}
+pub struct ShowTablesOperator {
+    /// The `LIKE` pattern the names of the tables shown match.
+    pub pattern: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ShowCreateTableOperator {
+    pub table_name: TableName,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct ShowIndexOperator {
+    pub table_name: TableName,
+}
@@ -1,6 +1,10 @@
 use crate::expression::ScalarExpression;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fmt::Formatter;
 
-#[derive(Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Serialize, Deserialize)]
 pub struct SortField {
     pub expr: ScalarExpression,
     pub asc: bool,
@@ -17,9 +21,51 @@ impl SortField {
     }
 }
 
+/// Where the nulls of a sort key without `NULLS FIRST` or `NULLS LAST` are placed.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum NullsOrder {
+    /// The nulls are larger than any value, last in ascending order and first in
+    /// descending order, as in the SQL standard and PostgreSQL.
+    #[default]
+    Largest,
+    /// The nulls are smaller than any value, first in ascending order and last in
+    /// descending order, as in MySQL and SQLite.
+    Smallest,
+}
+
+impl NullsOrder {
+    /// Whether the nulls of a sort key in the order of `asc` come first.
+    pub fn nulls_first(&self, asc: bool) -> bool {
+        match self {
+            NullsOrder::Largest => !asc,
+            NullsOrder::Smallest => asc,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct SortOperator {
     pub sort_fields: Vec<SortField>,
     /// Support push down limit to sort plan.
     pub limit: Option<usize>,
 }
+
+impl fmt::Display for SortField {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let direction = if self.asc { "ASC" } else { "DESC" };
+        let nulls = if self.nulls_first { "FIRST" } else { "LAST" };
+
+        write!(f, "{} {} NULLS {}", self.expr, direction, nulls)
+    }
+}
+
+impl fmt::Display for SortOperator {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "Sort [{}]", self.sort_fields.iter().join(", "))?;
+        if let Some(limit) = self.limit {
+            write!(f, ", Limit {}", limit)?;
+        }
+
+        Ok(())
+    }
+}
@@ -4,4 +4,7 @@ use crate::catalog::TableName;
 pub struct TruncateOperator {
     /// Table name to insert to
     pub table_name: TableName,
+    /// `RESTART IDENTITY`, the hidden rowids are given out from the first one again,
+    /// otherwise they continue after the truncated rows.
+    pub restart_identity: bool,
 }
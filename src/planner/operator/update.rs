@@ -1,6 +1,9 @@
-use crate::catalog::TableName;
+use crate::catalog::{ColumnRef, TableName};
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct UpdateOperator {
     pub table_name: TableName,
+    /// The columns set by `UPDATE .. FROM`, their values follow the columns of the table
+    /// in each tuple of the input. The values of the other updates are in the second child.
+    pub source_columns: Vec<ColumnRef>,
 }
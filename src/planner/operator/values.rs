@@ -1,8 +1,22 @@
 use crate::catalog::ColumnRef;
 use crate::types::value::ValueRef;
+use itertools::Itertools;
+use std::fmt;
+use std::fmt::Formatter;
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct ValuesOperator {
     pub rows: Vec<Vec<ValueRef>>,
     pub columns: Vec<ColumnRef>,
 }
+
+impl fmt::Display for ValuesOperator {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Values [{}], Rows {}",
+            self.columns.iter().map(|col| col.name()).join(", "),
+            self.rows.len()
+        )
+    }
+}
@@ -0,0 +1,46 @@
+use crate::expression::ScalarExpression;
+use crate::planner::LogicalPlan;
+use crate::types::tuple::SchemaRef;
+use itertools::Itertools;
+
+use super::Operator;
+use std::fmt;
+use std::fmt::Formatter;
+use std::sync::Arc;
+
+/// Computes the `WindowCall`s of the query for each tuple, the tuples keep their columns
+/// and are followed by the value of each window call.
+#[derive(Debug, PartialEq, Clone)]
+pub struct WindowOperator {
+    pub window_calls: Vec<ScalarExpression>,
+}
+
+impl WindowOperator {
+    pub fn build(window_calls: Vec<ScalarExpression>, children: LogicalPlan) -> LogicalPlan {
+        LogicalPlan {
+            operator: Operator::Window(WindowOperator { window_calls }),
+            childrens: vec![children],
+        }
+    }
+
+    /// The columns of the child followed by the window calls.
+    pub fn output_schema(&self, child_schema: &SchemaRef) -> SchemaRef {
+        Arc::new(
+            child_schema
+                .iter()
+                .cloned()
+                .chain(
+                    self.window_calls
+                        .iter()
+                        .map(ScalarExpression::output_columns),
+                )
+                .collect(),
+        )
+    }
+}
+
+impl fmt::Display for WindowOperator {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "Window [{}]", self.window_calls.iter().join(", "))
+    }
+}
@@ -1,54 +1,274 @@
-use crate::catalog::{ColumnCatalog, ColumnRef, TableCatalog, TableName};
+use crate::catalog::{CatalogError, ColumnCatalog, GeneratedExpr, TableCatalog, TableName};
 use crate::expression::simplify::ConstantBinary;
-use crate::storage::table_codec::TableCodec;
+use crate::expression::ScalarExpression;
+use crate::storage::table_codec::{TableCodec, CODEC_VERSION, MIN_CODEC_VERSION};
 use crate::storage::{
-    tuple_projection, Bounds, IndexIter, Iter, Projections, Storage, StorageError, Transaction,
+    is_filtered_in, projection_schema, tuple_projection, unpack_aliases, Bounds, IndexIter,
+    IndexStats, IndexValue, IsolationLevel, Iter, Projections, Storage, StorageError, Transaction,
 };
+use crate::types::errors::TypeError;
 use crate::types::index::{Index, IndexMeta, IndexMetaRef};
-use crate::types::tuple::{Tuple, TupleId};
+use crate::types::tuple::{SchemaRef, Tuple, TupleId};
+use crate::types::value::ValueRef;
+use crate::types::ColumnId;
+use bytes::Bytes;
+use itertools::Itertools;
+use kip_db::kernel::io::IoType;
 use kip_db::kernel::lsm::iterator::Iter as KipDBIter;
 use kip_db::kernel::lsm::mvcc::{CheckType, TransactionIter};
 use kip_db::kernel::lsm::storage::Config;
 use kip_db::kernel::lsm::{mvcc, storage};
 use kip_db::kernel::utils::lru_cache::ShardingLruCache;
+use kip_db::kernel::Storage as KipDBStorage;
+use kip_db::KernelError;
 use std::collections::hash_map::RandomState;
-use std::collections::{Bound, VecDeque};
+use std::collections::{BTreeMap, Bound, HashMap, HashSet, VecDeque};
+use std::ops::Deref;
 use std::path::PathBuf;
 use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Sizing of the per-transaction `TableCatalog` cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CatalogCacheConfig {
+    /// Total number of table catalogs kept in the cache.
+    pub cap: usize,
+    /// Number of shards the cache is split into.
+    pub sharding_size: usize,
+}
+
+impl Default for CatalogCacheConfig {
+    fn default() -> Self {
+        CatalogCacheConfig {
+            cap: 32,
+            sharding_size: 16,
+        }
+    }
+}
+
+/// How the commits reach the write-ahead log of KipDB. Neither mode syncs the log file,
+/// so a commit survives a crash of the process at best, and a crash of the machine only
+/// once the OS wrote it out or `KipStorage::flush` put it into the data files.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WalMode {
+    /// The log is buffered in memory, a crash of the process loses the commits since
+    /// the buffer was last written. Meant for bulk loads followed by `flush`.
+    #[default]
+    Buffered,
+    /// Every commit is written to the log file before the commit returns.
+    Direct,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StorageConfig {
+    pub catalog_cache: CatalogCacheConfig,
+    pub wal_mode: WalMode,
+}
 
 #[derive(Clone)]
 pub struct KipStorage {
     pub inner: Arc<storage::KipStorage>,
+    cache_config: CatalogCacheConfig,
+    // the transactions are committed one at a time, see `KipTransaction::commit`
+    commit_lock: Arc<Mutex<()>>,
 }
 
 impl KipStorage {
     pub async fn new(path: impl Into<PathBuf> + Send) -> Result<Self, StorageError> {
-        let storage =
-            storage::KipStorage::open_with_config(Config::new(path).enable_level_0_memorization())
-                .await?;
+        Self::new_with_config(path, StorageConfig::default()).await
+    }
+
+    pub async fn new_with_config(
+        path: impl Into<PathBuf> + Send,
+        config: StorageConfig,
+    ) -> Result<Self, StorageError> {
+        let wal_io_type = match config.wal_mode {
+            WalMode::Buffered => IoType::Buf,
+            WalMode::Direct => IoType::Direct,
+        };
+        let storage = storage::KipStorage::open_with_config(
+            Config::new(path)
+                .enable_level_0_memorization()
+                .wal_io_type(wal_io_type),
+        )
+        .await?;
+        Self::check_codec_version(&storage).await?;
 
         Ok(KipStorage {
             inner: Arc::new(storage),
+            cache_config: config.catalog_cache,
+            commit_lock: Arc::new(Mutex::new(())),
+        })
+    }
+
+    pub fn cache_config(&self) -> CatalogCacheConfig {
+        self.cache_config
+    }
+
+    async fn new_transaction(
+        &self,
+        read_only: bool,
+        isolation: IsolationLevel,
+    ) -> Result<KipTransaction, StorageError> {
+        let tx = self.inner.new_transaction(CheckType::Optimistic).await;
+        let read_committed = matches!(isolation, IsolationLevel::ReadCommitted);
+
+        Ok(KipTransaction {
+            tx: LoggedTransaction {
+                tx,
+                writes: read_committed.then(BTreeMap::new),
+            },
+            cache: ShardingLruCache::new(
+                self.cache_config.cap,
+                self.cache_config.sharding_size,
+                RandomState::default(),
+            )?,
+            read_only,
+            storage: read_committed.then(|| self.clone()),
+            commit_lock: self.commit_lock.clone(),
         })
     }
+
+    /// Stamps a new data directory with the codec version, and refuses to open one
+    /// stamped with a version this build does not read. A directory with tables but no
+    /// stamp is from before it, of version 1.
+    ///
+    /// The tables of a directory stamped with an earlier version are given that version,
+    /// which picks how they are decoded, and the stamp is moved to the current version,
+    /// as the tables created from now on are of that one.
+    async fn check_codec_version(storage: &storage::KipStorage) -> Result<(), StorageError> {
+        let mut tx = storage.new_transaction(CheckType::Optimistic).await;
+        let found = match tx.get(TableCodec::codec_version_key())? {
+            Some(bytes) => TableCodec::decode_codec_version(&bytes)?,
+            None => {
+                if Self::has_tables(&tx)? {
+                    return Err(StorageError::CodecVersionMismatch {
+                        found: 1,
+                        expected: CODEC_VERSION,
+                    });
+                }
+                let (key, value) = TableCodec::encode_codec_version();
+                tx.set(key, value);
+                tx.commit().await?;
+
+                return Ok(());
+            }
+        };
+        if !(MIN_CODEC_VERSION..=CODEC_VERSION).contains(&found) {
+            return Err(StorageError::CodecVersionMismatch {
+                found,
+                expected: CODEC_VERSION,
+            });
+        }
+        if found < CODEC_VERSION {
+            let (min, max) = TableCodec::root_table_bound();
+            let mut iter = tx.iter(Bound::Included(&min), Bound::Included(&max))?;
+            let mut table_names = vec![];
+
+            while let Some((_, value_option)) = iter.try_next()? {
+                if let Some(value) = value_option {
+                    table_names.push(TableCodec::decode_root_table(&value)?);
+                }
+            }
+            drop(iter);
+
+            for table_name in table_names {
+                if tx
+                    .get(&TableCodec::encode_table_version_key(&table_name))?
+                    .is_none()
+                {
+                    let (key, value) = TableCodec::encode_table_version(&table_name, found);
+                    tx.set(key, value);
+                }
+            }
+            let (key, value) = TableCodec::encode_codec_version();
+            tx.set(key, value);
+            tx.commit().await?;
+        }
+
+        Ok(())
+    }
+
+    fn has_tables(tx: &mvcc::Transaction) -> Result<bool, StorageError> {
+        let (min, max) = TableCodec::root_table_bound();
+        let mut iter = tx.iter(Bound::Included(&min), Bound::Included(&max))?;
+
+        while let Some((_, value_option)) = iter.try_next()? {
+            if value_option.is_some() {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
 }
 
 impl Storage for KipStorage {
     type TransactionType = KipTransaction;
 
     async fn transaction(&self) -> Result<Self::TransactionType, StorageError> {
-        let tx = self.inner.new_transaction(CheckType::Optimistic).await;
+        self.new_transaction(false, IsolationLevel::RepeatableRead)
+            .await
+    }
 
-        Ok(KipTransaction {
-            tx,
-            cache: ShardingLruCache::new(32, 16, RandomState::default())?,
-        })
+    async fn transaction_with_isolation(
+        &self,
+        isolation: IsolationLevel,
+    ) -> Result<Self::TransactionType, StorageError> {
+        self.new_transaction(false, isolation).await
+    }
+
+    async fn read_only_transaction(&self) -> Result<Self::TransactionType, StorageError> {
+        self.new_transaction(true, IsolationLevel::RepeatableRead)
+            .await
+    }
+
+    async fn flush(&self) -> Result<(), StorageError> {
+        Ok(self.inner.flush().await?)
     }
 }
 
 pub struct KipTransaction {
-    tx: mvcc::Transaction,
+    tx: LoggedTransaction,
     cache: ShardingLruCache<String, TableCatalog>,
+    read_only: bool,
+    // the storage each statement takes a new snapshot of, under `IsolationLevel::ReadCommitted`
+    storage: Option<KipStorage>,
+    commit_lock: Arc<Mutex<()>>,
+}
+
+/// The transaction of KipDB under a `KipTransaction`, which logs the writes made through it
+/// when they are to be carried over to a later snapshot. Its reads are those of the
+/// transaction of KipDB, which it derefs to.
+struct LoggedTransaction {
+    tx: mvcc::Transaction,
+    writes: Option<BTreeMap<Bytes, Option<Bytes>>>,
+}
+
+impl LoggedTransaction {
+    fn set(&mut self, key: Bytes, value: Bytes) {
+        if let Some(writes) = &mut self.writes {
+            let _ = writes.insert(key.clone(), Some(value.clone()));
+        }
+        self.tx.set(key, value);
+    }
+
+    fn remove(&mut self, key: &[u8]) -> Result<(), KernelError> {
+        self.tx.remove(key)?;
+        if let Some(writes) = &mut self.writes {
+            let _ = writes.insert(Bytes::copy_from_slice(key), None);
+        }
+
+        Ok(())
+    }
+}
+
+impl Deref for LoggedTransaction {
+    type Target = mvcc::Transaction;
+
+    fn deref(&self) -> &Self::Target {
+        &self.tx
+    }
 }
 
 impl Transaction for KipTransaction {
@@ -60,18 +280,21 @@ impl Transaction for KipTransaction {
         bounds: Bounds,
         projections: Projections,
     ) -> Result<Self::IterType<'_>, StorageError> {
-        let all_columns = self
+        let table_schema_ref = self
             .table(table_name.clone())
             .ok_or(StorageError::TableNotFound)?
-            .all_columns();
+            .schema_ref()
+            .clone();
         let (min, max) = TableCodec::tuple_bound(&table_name);
         let iter = self.tx.iter(Bound::Included(&min), Bound::Included(&max))?;
 
         Ok(KipIter {
             offset: bounds.0.unwrap_or(0),
             limit: bounds.1,
-            projections,
-            all_columns,
+            schema_ref: projection_schema(&projections),
+            projections: unpack_aliases(projections),
+            table_schema_ref,
+            filter: None,
             iter,
         })
     }
@@ -83,25 +306,113 @@ impl Transaction for KipTransaction {
         projections: Projections,
         index_meta: IndexMetaRef,
         binaries: Vec<ConstantBinary>,
+        index_only: bool,
     ) -> Result<IndexIter<'_>, StorageError> {
         let table = self
             .table(table_name.clone())
             .ok_or(StorageError::TableNotFound)?;
         let offset = offset_option.unwrap_or(0);
+        let covered_schema_ref = index_only.then(|| {
+            let primary_key = table
+                .all_columns()
+                .into_iter()
+                .filter(|col| col.desc.is_primary);
+            let columns = index_meta
+                .column_ids
+                .iter()
+                .filter_map(|col_id| table.get_column_by_id(col_id).cloned())
+                .chain(primary_key)
+                .collect_vec();
+
+            Arc::new(columns)
+        });
 
         Ok(IndexIter {
             offset,
             limit: limit_option,
-            projections,
+            schema_ref: projection_schema(&projections),
+            projections: unpack_aliases(projections),
+            filter: None,
             index_meta,
+            covered_schema_ref,
             table,
             index_values: VecDeque::new(),
+            seen_keys: (binaries.len() > 1).then(HashSet::new),
             binaries: VecDeque::from(binaries),
             tx: &self.tx,
             scope_iter: None,
         })
     }
 
+    fn read_by_index_intersection(
+        &self,
+        table_name: TableName,
+        (offset_option, limit_option): Bounds,
+        projections: Projections,
+        index_by: Vec<(IndexMetaRef, Vec<ConstantBinary>)>,
+    ) -> Result<IndexIter<'_>, StorageError> {
+        let table = self
+            .table(table_name.clone())
+            .ok_or(StorageError::TableNotFound)?;
+        let mut index_by = index_by.into_iter();
+        let first = index_by.next().ok_or(TypeError::InvalidType)?;
+        // the tuple ids in the entries of the index, keyed by their encoding
+        let tuple_ids = |(index_meta, binaries): (IndexMetaRef, Vec<ConstantBinary>)| {
+            let mut iter = self.read_by_index(
+                table_name.clone(),
+                (None, None),
+                vec![],
+                index_meta,
+                binaries,
+                true,
+            )?;
+            let mut tuple_ids = Vec::new();
+
+            // the tuples built from the entries carry the ids of the tuples
+            while let Some(Tuple {
+                id: Some(tuple_id), ..
+            }) = iter.next_tuple()?
+            {
+                tuple_ids.push((
+                    TableCodec::encode_tuple_key(&table_name, &tuple_id)?,
+                    tuple_id,
+                ));
+            }
+            Ok::<_, StorageError>(tuple_ids)
+        };
+        let index_meta = first.0.clone();
+        let mut intersection = tuple_ids(first)?;
+
+        for index in index_by {
+            if intersection.is_empty() {
+                break;
+            }
+            let keys: HashSet<Vec<u8>> =
+                tuple_ids(index)?.into_iter().map(|(key, _)| key).collect();
+
+            intersection.retain(|(key, _)| keys.contains(key));
+        }
+
+        Ok(IndexIter {
+            offset: offset_option.unwrap_or(0),
+            limit: limit_option,
+            schema_ref: projection_schema(&projections),
+            projections: unpack_aliases(projections),
+            filter: None,
+            index_meta,
+            covered_schema_ref: None,
+            table,
+            index_values: intersection
+                .into_iter()
+                .map(|(_, tuple_id)| IndexValue::Normal(tuple_id))
+                .collect(),
+            binaries: VecDeque::new(),
+            tx: &self.tx,
+            scope_iter: None,
+            seen_keys: None,
+        })
+    }
+
     fn add_index(
         &mut self,
         table_name: &str,
@@ -109,20 +420,29 @@ impl Transaction for KipTransaction {
         tuple_ids: Vec<TupleId>,
         is_unique: bool,
     ) -> Result<(), StorageError> {
+        self.check_writable()?;
         let (key, value) = TableCodec::encode_index(table_name, &index, &tuple_ids)?;
 
         if let Some(bytes) = self.tx.get(&key)? {
-            if is_unique {
-                let old_tuple_ids = TableCodec::decode_index(&bytes)?;
+            let (mut old_tuple_ids, _) = TableCodec::decode_index(&bytes)?;
 
+            if is_unique {
                 if old_tuple_ids[0] != tuple_ids[0] {
                     return Err(StorageError::DuplicateUniqueValue);
                 } else {
                     return Ok(());
                 }
-            } else {
-                todo!("联合索引")
             }
+            // the tuples of the same values share the entry of a non-unique index
+            for tuple_id in tuple_ids {
+                if !old_tuple_ids.contains(&tuple_id) {
+                    old_tuple_ids.push(tuple_id);
+                }
+            }
+            let (key, value) = TableCodec::encode_index(table_name, &index, &old_tuple_ids)?;
+            self.tx.set(key, value);
+
+            return Ok(());
         }
 
         self.tx.set(key, value);
@@ -130,10 +450,26 @@ impl Transaction for KipTransaction {
         Ok(())
     }
 
-    fn del_index(&mut self, table_name: &str, index: &Index) -> Result<(), StorageError> {
+    fn del_index(
+        &mut self,
+        table_name: &str,
+        index: &Index,
+        tuple_id: &TupleId,
+    ) -> Result<(), StorageError> {
+        self.check_writable()?;
         let key = TableCodec::encode_index_key(table_name, index)?;
+        let Some(bytes) = self.tx.get(&key)? else {
+            return Ok(());
+        };
+        let (mut tuple_ids, _) = TableCodec::decode_index(&bytes)?;
+        tuple_ids.retain(|id| id != tuple_id);
 
-        self.tx.remove(&key)?;
+        if tuple_ids.is_empty() {
+            self.tx.remove(&key)?;
+        } else {
+            let (key, value) = TableCodec::encode_index(table_name, index, &tuple_ids)?;
+            self.tx.set(key, value);
+        }
 
         Ok(())
     }
@@ -144,29 +480,272 @@ impl Transaction for KipTransaction {
         tuple: Tuple,
         is_overwrite: bool,
     ) -> Result<(), StorageError> {
+        self.check_writable()?;
         let (key, value) = TableCodec::encode_tuple(table_name, &tuple)?;
 
-        if !is_overwrite && self.tx.get(&key)?.is_some() {
-            return Err(StorageError::DuplicatePrimaryKey);
+        if let Some(bytes) = self.tx.get(&key)? {
+            if !is_overwrite {
+                return Err(StorageError::DuplicatePrimaryKey);
+            }
+            let mut stale_indexes = Vec::new();
+
+            if let Some(table) = self.table(Arc::new(table_name.to_string())) {
+                let old_tuple = TableCodec::decode_tuple(table.schema_ref().clone(), &bytes);
+
+                for index_meta in table.secondary_indexes() {
+                    let Some(old_values) = index_meta.column_values(&old_tuple)? else {
+                        continue;
+                    };
+                    if index_meta.column_values(&tuple)?.as_ref() != Some(&old_values) {
+                        stale_indexes.push(Index::new(index_meta.id, old_values));
+                    }
+                }
+            }
+            // the entries of the new values are added by the caller, see `update_tuple` for
+            // a tuple whose old values are known
+            if let Some(tuple_id) = &tuple.id {
+                for index in stale_indexes {
+                    self.del_index(table_name, &index, tuple_id)?;
+                }
+            }
+        }
+        self.tx.set(key, value);
+
+        Ok(())
+    }
+
+    fn update_tuple(
+        &mut self,
+        table_name: &str,
+        old: &Tuple,
+        new: Tuple,
+    ) -> Result<(), StorageError> {
+        self.check_writable()?;
+        let index_metas = self
+            .table(Arc::new(table_name.to_string()))
+            .ok_or(StorageError::TableNotFound)?
+            .secondary_indexes()
+            .cloned()
+            .collect_vec();
+        let old_id = old.id.clone().ok_or(TypeError::PrimaryKeyNotFound)?;
+        let new_id = new.id.clone().ok_or(TypeError::PrimaryKeyNotFound)?;
+        let is_key_changed = old_id != new_id;
+
+        for index_meta in index_metas {
+            let old_values = index_meta.column_values(old)?;
+            let new_values = index_meta.column_values(&new)?;
+
+            if old_values == new_values && !is_key_changed {
+                continue;
+            }
+            if let Some(column_values) = old_values {
+                self.del_index(
+                    table_name,
+                    &Index::new(index_meta.id, column_values),
+                    &old_id,
+                )?;
+            }
+            if let Some(column_values) = new_values {
+                self.add_index(
+                    table_name,
+                    Index::new(index_meta.id, column_values),
+                    vec![new_id.clone()],
+                    index_meta.is_unique,
+                )?;
+            }
+        }
+        if is_key_changed {
+            self.delete(table_name, old_id)?;
+
+            return self.append(table_name, new, false);
         }
+        let (key, value) = TableCodec::encode_tuple(table_name, &new)?;
         self.tx.set(key, value);
 
         Ok(())
     }
 
     fn delete(&mut self, table_name: &str, tuple_id: TupleId) -> Result<(), StorageError> {
+        self.check_writable()?;
         let key = TableCodec::encode_tuple_key(table_name, &tuple_id)?;
         self.tx.remove(&key)?;
 
         Ok(())
     }
 
+    fn get_tuple(
+        &self,
+        table_name: &str,
+        tuple_id: &TupleId,
+    ) -> Result<Option<Tuple>, StorageError> {
+        let table = self
+            .table(Arc::new(table_name.to_string()))
+            .ok_or(StorageError::TableNotFound)?;
+        let key = TableCodec::encode_tuple_key(table_name, tuple_id)?;
+
+        Ok(self
+            .tx
+            .get(&key)?
+            .map(|bytes| TableCodec::decode_tuple(table.schema_ref().clone(), &bytes)))
+    }
+
+    fn range_delete(
+        &mut self,
+        table_name: &str,
+        binaries: Vec<ConstantBinary>,
+    ) -> Result<usize, StorageError> {
+        self.check_writable()?;
+        let table = self
+            .table(Arc::new(table_name.to_string()))
+            .ok_or(StorageError::TableNotFound)?;
+        let schema_ref = table.schema_ref().clone();
+        let index_metas = table.secondary_indexes().cloned().collect_vec();
+        let (tuple_min, tuple_max) = TableCodec::tuple_bound(table_name);
+
+        let bound_encode = |bound: Bound<ValueRef>, unbounded: &Vec<u8>| {
+            let encode = |val| TableCodec::encode_tuple_key(table_name, &TupleId::Value(val));
+
+            Ok::<_, StorageError>(match bound {
+                Bound::Included(val) => Bound::Included(encode(val)?),
+                Bound::Excluded(val) => Bound::Excluded(encode(val)?),
+                Bound::Unbounded => Bound::Included(unbounded.clone()),
+            })
+        };
+        let mut tuple_keys = Vec::new();
+        let mut index_entries = Vec::new();
+
+        for binary in binaries {
+            let (min, max) = match binary {
+                ConstantBinary::Scope { min, max } => (min, max),
+                ConstantBinary::Eq(val) => (Bound::Included(val.clone()), Bound::Included(val)),
+                _ => unreachable!("the ranges of a scan are made of `Scope` and `Eq`"),
+            };
+            let encode_min = bound_encode(min, &tuple_min)?;
+            let encode_max = bound_encode(max, &tuple_max)?;
+            let mut iter = self.tx.iter(
+                encode_min.as_ref().map(Vec::as_slice),
+                encode_max.as_ref().map(Vec::as_slice),
+            )?;
+
+            while let Some((key, value_option)) = iter.try_next()? {
+                let Some(bytes) = value_option else {
+                    continue;
+                };
+                if !index_metas.is_empty() {
+                    let tuple = TableCodec::decode_tuple(schema_ref.clone(), &bytes);
+
+                    for index_meta in index_metas.iter() {
+                        if let Some(column_values) = index_meta.column_values(&tuple)? {
+                            let index = Index::new(index_meta.id, column_values);
+                            let tuple_id = tuple.id.clone().ok_or(TypeError::PrimaryKeyNotFound)?;
+
+                            index_entries.push((index, tuple_id));
+                        }
+                    }
+                }
+                tuple_keys.push(key);
+            }
+        }
+        // the ranges may overlap, and removing a key twice fails
+        tuple_keys.sort();
+        tuple_keys.dedup();
+
+        for (index, tuple_id) in index_entries {
+            self.del_index(table_name, &index, &tuple_id)?;
+        }
+        for key in tuple_keys.iter() {
+            self.tx.remove(key)?;
+        }
+
+        Ok(tuple_keys.len())
+    }
+
+    fn set_comment(
+        &mut self,
+        table_name: &str,
+        column_id: Option<ColumnId>,
+        comment: Option<String>,
+    ) -> Result<(), StorageError> {
+        self.check_writable()?;
+        match comment {
+            Some(comment) => {
+                let (key, value) = TableCodec::encode_comment(table_name, column_id, &comment);
+                self.tx.set(key, value);
+            }
+            None => {
+                let key = TableCodec::encode_comment_key(table_name, column_id);
+                if self.tx.get(&key)?.is_some() {
+                    self.tx.remove(&key)?;
+                }
+            }
+        }
+        self.invalidate_table(table_name);
+
+        Ok(())
+    }
+
+    fn lock(&mut self, table_name: &str, tuple_id: TupleId) -> Result<(), StorageError> {
+        self.check_writable()?;
+        let key = TableCodec::encode_tuple_key(table_name, &tuple_id)?;
+
+        // rewriting the tuple unchanged puts it into the keys checked for conflicts at commit
+        if let Some(value) = self.tx.get(&key)? {
+            self.tx.set(Bytes::from(key), value);
+        }
+
+        Ok(())
+    }
+
+    fn next_rowid(&mut self, table_name: &str) -> Result<u64, StorageError> {
+        self.check_writable()?;
+        let key = TableCodec::encode_rowid_key(table_name);
+        let rowid = match self.tx.get(&key)? {
+            Some(bytes) => {
+                u64::from_be_bytes(bytes[..].try_into().map_err(|_| TypeError::InvalidType)?)
+            }
+            None => 0,
+        } + 1;
+        self.tx
+            .set(Bytes::from(key), Bytes::from(rowid.to_be_bytes().to_vec()));
+
+        Ok(rowid)
+    }
+
+    fn reset_rowid(&mut self, table_name: &str) -> Result<(), StorageError> {
+        self.check_writable()?;
+        let key = TableCodec::encode_rowid_key(table_name);
+        if self.tx.get(&key)?.is_some() {
+            self.tx.remove(&key)?;
+        }
+
+        Ok(())
+    }
+
+    fn skip_rowid(&mut self, table_name: &str, rowid: u64) -> Result<(), StorageError> {
+        self.check_writable()?;
+        let key = TableCodec::encode_rowid_key(table_name);
+        let last_rowid = match self.tx.get(&key)? {
+            Some(bytes) => {
+                u64::from_be_bytes(bytes[..].try_into().map_err(|_| TypeError::InvalidType)?)
+            }
+            None => 0,
+        };
+        if last_rowid < rowid {
+            self.tx
+                .set(Bytes::from(key), Bytes::from(rowid.to_be_bytes().to_vec()));
+        }
+
+        Ok(())
+    }
+
     fn create_table(
         &mut self,
         table_name: TableName,
         columns: Vec<ColumnCatalog>,
+        unique_keys: Vec<Vec<String>>,
         if_not_exists: bool,
     ) -> Result<TableName, StorageError> {
+        self.check_writable()?;
         let (table_key, value) = TableCodec::encode_root_table(&table_name)?;
         if self.tx.get(&table_key)?.is_some() {
             if if_not_exists {
@@ -175,21 +754,102 @@ impl Transaction for KipTransaction {
             return Err(StorageError::TableExists);
         }
         self.tx.set(table_key, value);
+        let (version_key, version) = TableCodec::encode_table_version(&table_name, CODEC_VERSION);
+        self.tx.set(version_key, version);
 
         let mut table_catalog = TableCatalog::new(table_name.clone(), columns)?;
 
-        Self::create_index_meta_for_table(&mut self.tx, &mut table_catalog)?;
+        Self::create_index_meta_for_table(&mut self.tx, &mut table_catalog, unique_keys)?;
 
         for column in table_catalog.columns.values() {
             let (key, value) = TableCodec::encode_column(&table_name, column)?;
             self.tx.set(key, value);
+
+            if let Some(comment) = &column.desc.comment {
+                let (key, value) = TableCodec::encode_comment(&table_name, column.id(), comment);
+                self.tx.set(key, value);
+            }
+            if let Some(on_update) = &column.desc.on_update {
+                let (key, value) =
+                    TableCodec::encode_on_update(&table_name, column.id().unwrap(), on_update)?;
+                self.tx.set(key, value);
+            }
+            if let Some(default_expr) = &column.desc.default_expr {
+                let (key, value) = TableCodec::encode_default_expr(
+                    &table_name,
+                    column.id().unwrap(),
+                    default_expr,
+                )?;
+                self.tx.set(key, value);
+            }
         }
+        self.invalidate_table(&table_name);
         self.cache.put(table_name.to_string(), table_catalog);
 
         Ok(table_name)
     }
 
+    fn create_index(
+        &mut self,
+        table_name: &str,
+        index_meta: IndexMeta,
+        if_not_exists: bool,
+    ) -> Result<(), StorageError> {
+        self.check_writable()?;
+        let mut table = self
+            .table(Arc::new(table_name.to_string()))
+            .cloned()
+            .ok_or(StorageError::TableNotFound)?;
+        if table
+            .indexes()
+            .iter()
+            .any(|meta| meta.name == index_meta.name)
+        {
+            if if_not_exists {
+                return Ok(());
+            }
+            return Err(CatalogError::Duplicated("index", index_meta.name).into());
+        }
+        // the index metas of a table of an earlier version are decoded without the expression
+        let version = Self::table_version(table_name, &self.tx)?;
+        if index_meta.expr.is_some() && version < 4 {
+            return Err(TypeError::InternalError(format!(
+                "the table {} of codec version {} can not have an expression index",
+                table_name, version
+            ))
+            .into());
+        }
+        let index_meta = table.add_index_meta(index_meta).clone();
+        let (key, value) = TableCodec::encode_index_meta(table_name, &index_meta)?;
+        self.tx.set(key, value);
+
+        let (min, max) = TableCodec::tuple_bound(table_name);
+        let mut iter = self.tx.iter(Bound::Included(&min), Bound::Included(&max))?;
+        let mut entries = Vec::new();
+
+        while let Some((_, value_option)) = iter.try_next()? {
+            if let Some(bytes) = value_option {
+                let tuple = TableCodec::decode_tuple(table.schema_ref().clone(), &bytes);
+
+                if let Some(column_values) = index_meta.column_values(&tuple)? {
+                    let tuple_id = tuple.id.ok_or(TypeError::PrimaryKeyNotFound)?;
+
+                    entries.push((Index::new(index_meta.id, column_values), tuple_id));
+                }
+            }
+        }
+        drop(iter);
+
+        for (index, tuple_id) in entries {
+            self.add_index(table_name, index, vec![tuple_id], index_meta.is_unique)?;
+        }
+        self.invalidate_table(table_name);
+
+        Ok(())
+    }
+
     fn drop_table(&mut self, table_name: &str) -> Result<(), StorageError> {
+        self.check_writable()?;
         self.drop_data(table_name)?;
 
         let (min, max) = TableCodec::columns_bound(table_name);
@@ -206,35 +866,166 @@ impl Transaction for KipTransaction {
         for col_key in col_keys {
             self.tx.remove(&col_key)?
         }
+        for (min, max) in [
+            TableCodec::index_meta_bound(table_name),
+            TableCodec::comments_bound(table_name),
+            TableCodec::on_updates_bound(table_name),
+            TableCodec::default_exprs_bound(table_name),
+        ] {
+            Self::_drop_data(&mut self.tx, &min, &max)?;
+        }
         self.tx
             .remove(&TableCodec::encode_root_table_key(table_name))?;
+        let version_key = TableCodec::encode_table_version_key(table_name);
+        if self.tx.get(&version_key)?.is_some() {
+            self.tx.remove(&version_key)?;
+        }
+        self.reset_rowid(table_name)?;
 
-        let _ = self.cache.remove(&table_name.to_string());
+        self.invalidate_table(table_name);
 
         Ok(())
     }
 
-    fn drop_data(&mut self, table_name: &str) -> Result<(), StorageError> {
-        let (tuple_min, tuple_max) = TableCodec::tuple_bound(table_name);
-        Self::_drop_data(&mut self.tx, &tuple_min, &tuple_max)?;
-
-        let (index_min, index_max) = TableCodec::all_index_bound(table_name);
-        Self::_drop_data(&mut self.tx, &index_min, &index_max)?;
+    fn rename_table(&mut self, table_name: &str, new_table_name: &str) -> Result<(), StorageError> {
+        self.check_writable()?;
+        let (new_table_key, value) = TableCodec::encode_root_table(new_table_name)?;
+        if self.tx.get(&new_table_key)?.is_some() {
+            return Err(StorageError::TableExists);
+        }
+        let table_key = TableCodec::encode_root_table_key(table_name);
+        if self.tx.get(&table_key)?.is_none() {
+            return Err(StorageError::TableNotFound);
+        }
+        self.tx.remove(&table_key)?;
+        self.tx.set(new_table_key, value);
+
+        for (min, max) in [
+            TableCodec::columns_bound(table_name),
+            TableCodec::index_meta_bound(table_name),
+            TableCodec::all_index_bound(table_name),
+            TableCodec::tuple_bound(table_name),
+            TableCodec::comments_bound(table_name),
+            TableCodec::on_updates_bound(table_name),
+            TableCodec::default_exprs_bound(table_name),
+        ] {
+            Self::_move_data(&mut self.tx, table_name, new_table_name, &min, &max)?;
+        }
+        for (key, new_key) in [
+            (
+                TableCodec::encode_rowid_key(table_name),
+                TableCodec::encode_rowid_key(new_table_name),
+            ),
+            (
+                TableCodec::encode_table_version_key(table_name),
+                TableCodec::encode_table_version_key(new_table_name),
+            ),
+        ] {
+            if let Some(value) = self.tx.get(&key)? {
+                self.tx.remove(&key)?;
+                self.tx.set(Bytes::from(new_key), value);
+            }
+        }
+        self.invalidate_table(table_name);
+        self.invalidate_table(new_table_name);
 
         Ok(())
     }
 
-    fn table(&self, table_name: TableName) -> Option<&TableCatalog> {
-        let mut option = self.cache.get(&table_name);
-
-        if option.is_none() {
-            // TODO: unify the data into a `Meta` prefix and use one iteration to collect all data
-            let columns = Self::column_collect(table_name.clone(), &self.tx).ok()?;
-            let indexes = Self::index_meta_collect(&table_name, &self.tx)?;
+    fn rename_column(
+        &mut self,
+        table_name: &str,
+        column_id: ColumnId,
+        new_column_name: &str,
+    ) -> Result<(), StorageError> {
+        self.check_writable()?;
+        let table = self
+            .table(Arc::new(table_name.to_string()))
+            .ok_or(StorageError::TableNotFound)?;
+        let mut column = table
+            .get_column_by_id(&column_id)
+            .ok_or_else(|| CatalogError::NotFound("column", column_id.to_string()))?
+            .as_ref()
+            .clone();
+        if column.name() == new_column_name {
+            return Ok(());
+        }
+        if table.contains_column(new_column_name) {
+            return Err(CatalogError::Duplicated("column", new_column_name.to_string()).into());
+        }
+        let index_name = |meta: &IndexMeta, renamed: Option<&str>| {
+            let column_names = meta
+                .column_ids
+                .iter()
+                .map(|id| match renamed {
+                    Some(name) if *id == column_id => name,
+                    _ => table.get_column_by_id(id).map_or("", |col| col.name()),
+                })
+                .collect_vec();
+            let prefix = if meta.is_primary { "pk" } else { "uk" };
+
+            format!("{}_{}", prefix, column_names.join("_"))
+        };
+        // the indexes of the constraints are named after their columns, e.g. `uk_a_b`,
+        // those of `CREATE INDEX` keep the name they were given
+        let index_metas = table
+            .indexes()
+            .iter()
+            .filter(|meta| {
+                meta.column_ids.contains(&column_id) && meta.name == index_name(meta, None)
+            })
+            .map(|meta| {
+                let mut meta = meta.as_ref().clone();
+                meta.name = index_name(&meta, Some(new_column_name));
+                meta
+            })
+            .collect_vec();
+
+        column.summary.name = new_column_name.to_string();
+        let (key, value) = TableCodec::encode_column(table_name, &column)?;
+        self.tx.set(key, value);
+
+        for meta in index_metas {
+            let (key, value) = TableCodec::encode_index_meta(table_name, &meta)?;
+            self.tx.set(key, value);
+        }
+        self.invalidate_table(table_name);
+
+        Ok(())
+    }
+
+    fn drop_data(&mut self, table_name: &str) -> Result<(), StorageError> {
+        self.check_writable()?;
+        let (tuple_min, tuple_max) = TableCodec::tuple_bound(table_name);
+        Self::_drop_data(&mut self.tx, &tuple_min, &tuple_max)?;
+
+        let (index_min, index_max) = TableCodec::all_index_bound(table_name);
+        Self::_drop_data(&mut self.tx, &index_min, &index_max)?;
+
+        Ok(())
+    }
+
+    fn table(&self, table_name: TableName) -> Option<&TableCatalog> {
+        let mut option = self.cache.get(&table_name);
 
-            if let Ok(catalog) =
+        if option.is_none() {
+            // TODO: unify the data into a `Meta` prefix and use one iteration to collect all data
+            let version = Self::table_version(&table_name, &self.tx).ok()?;
+            let mut columns = Self::column_collect(table_name.clone(), version, &self.tx).ok()?;
+            let indexes = Self::index_meta_collect(&table_name, version, &self.tx)?;
+            let mut comments = Self::comment_collect(&table_name, &self.tx).ok()?;
+            let mut on_updates = Self::on_update_collect(&table_name, &self.tx).ok()?;
+            let mut default_exprs = Self::default_expr_collect(&table_name, &self.tx).ok()?;
+
+            for column in columns.iter_mut() {
+                column.desc.comment = comments.remove(&column.id());
+                column.desc.on_update = column.id().and_then(|id| on_updates.remove(&id));
+                column.desc.default_expr = column.id().and_then(|id| default_exprs.remove(&id));
+            }
+            if let Ok(mut catalog) =
                 TableCatalog::new_with_indexes(table_name.clone(), columns, indexes)
             {
+                catalog.comment = comments.remove(&None);
                 option = self
                     .cache
                     .get_or_insert(table_name.to_string(), |_| Ok(catalog))
@@ -261,16 +1052,122 @@ impl Transaction for KipTransaction {
         Ok(tables)
     }
 
-    async fn commit(self) -> Result<(), StorageError> {
-        self.tx.commit().await?;
+    fn row_count(&self, table_name: TableName) -> Result<usize, StorageError> {
+        if self.table(table_name.clone()).is_none() {
+            return Err(StorageError::TableNotFound);
+        }
+        let (min, max) = TableCodec::tuple_bound(&table_name);
+        let mut iter = self.tx.iter(Bound::Included(&min), Bound::Included(&max))?;
+        let mut count = 0;
+
+        while let Some((_, value_option)) = iter.try_next()? {
+            if value_option.is_some() {
+                count += 1;
+            }
+        }
+
+        Ok(count)
+    }
+
+    fn index_stats(
+        &self,
+        table_name: TableName,
+    ) -> Result<Vec<(IndexMetaRef, IndexStats)>, StorageError> {
+        let table = self
+            .table(table_name.clone())
+            .ok_or(StorageError::TableNotFound)?;
+        let mut stats = Vec::with_capacity(table.indexes().len());
+
+        for index_meta in table.indexes() {
+            let (min, max) = if index_meta.is_primary {
+                TableCodec::tuple_bound(&table_name)
+            } else {
+                TableCodec::index_bound(&table_name, &index_meta.id)
+            };
+            let mut iter = self.tx.iter(Bound::Included(&min), Bound::Included(&max))?;
+            let mut index_stats = IndexStats::default();
+
+            while let Some((key, value_option)) = iter.try_next()? {
+                if let Some(value) = value_option {
+                    index_stats.entries += 1;
+                    index_stats.bytes += key.len() + value.len();
+                }
+            }
+            stats.push((index_meta.clone(), index_stats));
+        }
+
+        Ok(stats)
+    }
+
+    /// Under `IsolationLevel::ReadCommitted` the writes so far are moved onto a new
+    /// transaction of KipDB, whose snapshot is the data committed by now. A key removed by
+    /// a transaction committed in between stays removed, and the conflicts of the commit are
+    /// only checked against the transactions committed after the last statement began.
+    async fn begin_statement(&mut self) -> Result<(), StorageError> {
+        let Some(storage) = &self.storage else {
+            return Ok(());
+        };
+        let mut transaction = storage
+            .new_transaction(self.read_only, IsolationLevel::ReadCommitted)
+            .await?;
+
+        for (key, value) in self.tx.writes.take().unwrap_or_default() {
+            match value {
+                Some(value) => transaction.tx.set(key, value),
+                None => match transaction.tx.remove(&key) {
+                    Ok(()) | Err(KernelError::KeyNotFound) => (),
+                    Err(err) => return Err(err.into()),
+                },
+            }
+        }
+        *self = transaction;
 
         Ok(())
     }
+
+    /// The commit waits for the ones before it to complete, so the conflicts are checked
+    /// against all the writes applied so far. That is the only point it suspends at: once
+    /// its turn comes, the conflicts are checked and the writes applied in one step, as the
+    /// commit of KipDB never suspends in between. So a commit future dropped before it
+    /// completes, e.g. by a timeout, applied nothing and the transaction is rolled back
+    /// with it.
+    async fn commit(self) -> Result<(), StorageError> {
+        let _turn = self.commit_lock.lock().await;
+
+        self.tx.tx.commit().await.map_err(|err| match err {
+            KernelError::RepeatedWrite => StorageError::TransactionConflict,
+            err => StorageError::from(err),
+        })
+    }
 }
 
 impl KipTransaction {
+    fn check_writable(&self) -> Result<(), StorageError> {
+        if self.read_only {
+            return Err(StorageError::ReadOnly);
+        }
+
+        Ok(())
+    }
+
+    /// Evicts the cached catalog of the table, so the next `table` call
+    /// rebuilds it from storage. Every DDL that changes a table's meta must call this.
+    fn invalidate_table(&mut self, table_name: &str) {
+        let _ = self.cache.remove(&table_name.to_string());
+    }
+
+    /// The codec version the table is written with, the current one for the tables created
+    /// since the directory was stamped with it.
+    fn table_version(table_name: &str, tx: &mvcc::Transaction) -> Result<u32, StorageError> {
+        match tx.get(&TableCodec::encode_table_version_key(table_name))? {
+            Some(bytes) => Ok(TableCodec::decode_codec_version(&bytes)?),
+            None => Ok(CODEC_VERSION),
+        }
+    }
+
     fn column_collect(
         table_name: TableName,
+        version: u32,
         tx: &mvcc::Transaction,
     ) -> Result<Vec<ColumnCatalog>, StorageError> {
         let (column_min, column_max) = TableCodec::columns_bound(&table_name);
@@ -281,14 +1178,90 @@ impl KipTransaction {
 
         while let Some((_, value_option)) = column_iter.try_next().ok().flatten() {
             if let Some(value) = value_option {
-                columns.push(TableCodec::decode_column(&value)?);
+                columns.push(TableCodec::decode_column(&value, version)?);
             }
         }
 
         Ok(columns)
     }
 
-    fn index_meta_collect(name: &str, tx: &mvcc::Transaction) -> Option<Vec<IndexMetaRef>> {
+    /// The comments of the table keyed by their column, `None` for the one of the table.
+    fn comment_collect(
+        table_name: &str,
+        tx: &mvcc::Transaction,
+    ) -> Result<HashMap<Option<ColumnId>, String>, StorageError> {
+        let (min, max) = TableCodec::comments_bound(table_name);
+        let mut iter = tx.iter(Bound::Included(&min), Bound::Included(&max))?;
+        let mut comments = HashMap::new();
+
+        while let Some((key, value_option)) = iter.try_next()? {
+            // the iterator of the flushed tables may go past the bounds, and the version
+            // record is next to the comments
+            if key.as_ref() < min.as_slice() || key.as_ref() > max.as_slice() {
+                continue;
+            }
+            if let Some(value) = value_option {
+                let (column_id, comment) = TableCodec::decode_comment(table_name, &key, &value)?;
+                comments.insert(column_id, comment);
+            }
+        }
+
+        Ok(comments)
+    }
+
+    /// The `ON UPDATE` expressions of the table keyed by their column.
+    fn on_update_collect(
+        table_name: &str,
+        tx: &mvcc::Transaction,
+    ) -> Result<HashMap<ColumnId, GeneratedExpr>, StorageError> {
+        let (min, max) = TableCodec::on_updates_bound(table_name);
+        let mut iter = tx.iter(Bound::Included(&min), Bound::Included(&max))?;
+        let mut on_updates = HashMap::new();
+
+        while let Some((key, value_option)) = iter.try_next()? {
+            // the iterator of the flushed tables may go past the bounds
+            if key.as_ref() < min.as_slice() || key.as_ref() > max.as_slice() {
+                continue;
+            }
+            if let Some(value) = value_option {
+                let (column_id, on_update) =
+                    TableCodec::decode_on_update(table_name, &key, &value)?;
+                on_updates.insert(column_id, on_update);
+            }
+        }
+
+        Ok(on_updates)
+    }
+
+    /// The `DEFAULT` expressions that are not constants of the table keyed by their column.
+    fn default_expr_collect(
+        table_name: &str,
+        tx: &mvcc::Transaction,
+    ) -> Result<HashMap<ColumnId, GeneratedExpr>, StorageError> {
+        let (min, max) = TableCodec::default_exprs_bound(table_name);
+        let mut iter = tx.iter(Bound::Included(&min), Bound::Included(&max))?;
+        let mut default_exprs = HashMap::new();
+
+        while let Some((key, value_option)) = iter.try_next()? {
+            // the iterator of the flushed tables may go past the bounds
+            if key.as_ref() < min.as_slice() || key.as_ref() > max.as_slice() {
+                continue;
+            }
+            if let Some(value) = value_option {
+                let (column_id, default_expr) =
+                    TableCodec::decode_default_expr(table_name, &key, &value)?;
+                default_exprs.insert(column_id, default_expr);
+            }
+        }
+
+        Ok(default_exprs)
+    }
+
+    fn index_meta_collect(
+        name: &str,
+        version: u32,
+        tx: &mvcc::Transaction,
+    ) -> Option<Vec<IndexMetaRef>> {
         let (index_min, index_max) = TableCodec::index_meta_bound(name);
         let mut index_metas = vec![];
         let mut index_iter = tx
@@ -297,7 +1270,7 @@ impl KipTransaction {
 
         while let Some((_, value_option)) = index_iter.try_next().ok().flatten() {
             if let Some(value) = value_option {
-                if let Ok(index_meta) = TableCodec::decode_index_meta(&value) {
+                if let Ok(index_meta) = TableCodec::decode_index_meta(&value, version) {
                     index_metas.push(Arc::new(index_meta));
                 }
             }
@@ -306,7 +1279,7 @@ impl KipTransaction {
         Some(index_metas)
     }
 
-    fn _drop_data(tx: &mut mvcc::Transaction, min: &[u8], max: &[u8]) -> Result<(), StorageError> {
+    fn _drop_data(tx: &mut LoggedTransaction, min: &[u8], max: &[u8]) -> Result<(), StorageError> {
         let mut iter = tx.iter(Bound::Included(min), Bound::Included(max))?;
         let mut data_keys = vec![];
 
@@ -324,35 +1297,121 @@ impl KipTransaction {
         Ok(())
     }
 
+    /// Moves the keys of `table_name` in the range to the same keys of `new_table_name`,
+    /// every key starts with the name of its table.
+    fn _move_data(
+        tx: &mut LoggedTransaction,
+        table_name: &str,
+        new_table_name: &str,
+        min: &[u8],
+        max: &[u8],
+    ) -> Result<(), StorageError> {
+        let mut iter = tx.iter(Bound::Included(min), Bound::Included(max))?;
+        let mut entries = vec![];
+
+        while let Some((key, value_option)) = iter.try_next()? {
+            if let Some(value) = value_option {
+                entries.push((key, value));
+            }
+        }
+        drop(iter);
+
+        for (key, value) in entries {
+            let mut new_key = new_table_name.as_bytes().to_vec();
+            new_key.extend_from_slice(&key[table_name.len()..]);
+
+            tx.remove(&key)?;
+            tx.set(Bytes::from(new_key), value);
+        }
+
+        Ok(())
+    }
+
     fn create_index_meta_for_table(
-        tx: &mut mvcc::Transaction,
+        tx: &mut LoggedTransaction,
         table: &mut TableCatalog,
+        unique_keys: Vec<Vec<String>>,
     ) -> Result<(), StorageError> {
         let table_name = table.name.clone();
+        let primary_key = table
+            .all_columns()
+            .into_iter()
+            .filter(|col| col.desc.is_primary)
+            .collect_vec();
+        let mut metas = Vec::new();
 
         for col in table
             .all_columns()
             .into_iter()
             .filter(|col| col.desc.is_index())
         {
-            let is_primary = col.desc.is_primary;
-            // FIXME: composite indexes may exist on future
-            let prefix = if is_primary { "pk" } else { "uk" };
+            let Some(col_id) = col.id() else {
+                continue;
+            };
+            if col.desc.is_primary && primary_key.len() > 1 {
+                // the composite primary key is a single index, placed at its first column
+                if primary_key[0].id() == Some(col_id) {
+                    metas.push(IndexMeta {
+                        id: 0,
+                        column_ids: primary_key.iter().filter_map(|col| col.id()).collect(),
+                        name: format!("pk_{}", primary_key.iter().map(|col| col.name()).join("_")),
+                        is_unique: false,
+                        is_primary: true,
+                        expr: None,
+                    });
+                }
+                if col.desc.is_unique {
+                    metas.push(IndexMeta {
+                        id: 0,
+                        column_ids: vec![col_id],
+                        name: format!("uk_{}", col.name()),
+                        is_unique: true,
+                        is_primary: false,
+                        expr: None,
+                    });
+                }
+            } else {
+                let is_primary = col.desc.is_primary;
+                let prefix = if is_primary { "pk" } else { "uk" };
 
-            if let Some(col_id) = col.id() {
-                let meta = IndexMeta {
+                metas.push(IndexMeta {
                     id: 0,
                     column_ids: vec![col_id],
                     name: format!("{}_{}", prefix, col.name()),
                     is_unique: col.desc.is_unique,
                     is_primary,
-                };
-                let meta_ref = table.add_index_meta(meta);
-                let (key, value) = TableCodec::encode_index_meta(&table_name, meta_ref)?;
-
-                tx.set(key, value);
+                    expr: None,
+                });
             }
         }
+        for meta in metas {
+            let meta_ref = table.add_index_meta(meta);
+            let (key, value) = TableCodec::encode_index_meta(&table_name, meta_ref)?;
+
+            tx.set(key, value);
+        }
+        for column_names in unique_keys {
+            let column_ids: Vec<ColumnId> = column_names
+                .iter()
+                .map(|name| {
+                    table
+                        .get_column_id_by_name(name)
+                        .ok_or_else(|| CatalogError::NotFound("column", name.clone()))
+                })
+                .try_collect()?;
+            let meta = IndexMeta {
+                id: 0,
+                column_ids,
+                name: format!("uk_{}", column_names.join("_")),
+                is_unique: true,
+                is_primary: false,
+                expr: None,
+            };
+            let meta_ref = table.add_index_meta(meta);
+            let (key, value) = TableCodec::encode_index_meta(&table_name, meta_ref)?;
+
+            tx.set(key, value);
+        }
         Ok(())
     }
 }
@@ -361,56 +1420,264 @@ pub struct KipIter<'a> {
     offset: usize,
     limit: Option<usize>,
     projections: Projections,
-    all_columns: Vec<ColumnRef>,
+    /// schema of the projected tuples
+    schema_ref: SchemaRef,
+    /// schema of the tuples stored in the table
+    table_schema_ref: SchemaRef,
+    /// the residual predicate of the scan, see `Iter::set_filter`
+    filter: Option<ScalarExpression>,
     iter: TransactionIter<'a>,
 }
 
-impl Iter for KipIter<'_> {
-    fn next_tuple(&mut self) -> Result<Option<Tuple>, StorageError> {
+impl KipIter<'_> {
+    /// The next tuple of the table passing the filter, not projected yet.
+    fn next_table_tuple(&mut self) -> Result<Option<Tuple>, StorageError> {
+        while let Some((_, value)) = self.iter.try_next()? {
+            if let Some(value) = value {
+                let tuple = TableCodec::decode_tuple(self.table_schema_ref.clone(), &value);
+
+                if is_filtered_in(&self.filter, &tuple)? {
+                    return Ok(Some(tuple));
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// The tuples of the offset are only decoded to tell whether they pass the filter.
+    fn skip_offset(&mut self) -> Result<(), StorageError> {
         while self.offset > 0 {
-            let _ = self.iter.try_next()?;
+            if self.filter.is_some() {
+                let _ = self.next_table_tuple()?;
+            } else {
+                let _ = self.iter.try_next()?;
+            }
             self.offset -= 1;
         }
 
+        Ok(())
+    }
+}
+
+impl Iter for KipIter<'_> {
+    fn next_tuple(&mut self) -> Result<Option<Tuple>, StorageError> {
+        self.skip_offset()?;
+
         if let Some(num) = self.limit {
             if num == 0 {
                 return Ok(None);
             }
         }
 
-        while let Some(item) = self.iter.try_next()? {
-            if let (_, Some(value)) = item {
-                let tuple = tuple_projection(
+        self.next_table_tuple()?
+            .map(|tuple| {
+                tuple_projection(&mut self.limit, &self.projections, &self.schema_ref, tuple)
+            })
+            .transpose()
+    }
+
+    fn next_batch(&mut self, size: usize) -> Result<Vec<Tuple>, StorageError> {
+        self.skip_offset()?;
+        let size = self.limit.map_or(size, |limit| limit.min(size));
+        let mut tuples = Vec::with_capacity(size);
+
+        while tuples.len() < size {
+            match self.next_table_tuple()? {
+                Some(tuple) => tuples.push(tuple_projection(
                     &mut self.limit,
                     &self.projections,
-                    TableCodec::decode_tuple(self.all_columns.clone(), &value),
-                )?;
-
-                return Ok(Some(tuple));
+                    &self.schema_ref,
+                    tuple,
+                )?),
+                None => break,
             }
         }
 
-        Ok(None)
+        Ok(tuples)
+    }
+
+    fn set_filter(&mut self, predicate: ScalarExpression) {
+        self.filter = Some(predicate);
     }
 }
 
 #[cfg(test)]
 mod test {
     use crate::catalog::{ColumnCatalog, ColumnDesc};
-    use crate::db::{Database, DatabaseError};
+    use crate::db::{Database, DatabaseConfig, DatabaseError};
+    use crate::execution::ExecutorError;
     use crate::expression::simplify::ConstantBinary;
-    use crate::expression::ScalarExpression;
-    use crate::storage::kip::KipStorage;
-    use crate::storage::{IndexIter, Iter, Storage, StorageError, Transaction};
+    use crate::expression::{BinaryOperator, ScalarExpression};
+    use crate::storage::kip::{CatalogCacheConfig, KipStorage};
+    use crate::storage::table_codec::{TableCodec, CODEC_VERSION};
+    use crate::storage::{
+        projection_schema, IndexIter, IsolationLevel, Iter, Storage, StorageError, Transaction,
+    };
     use crate::types::index::IndexMeta;
-    use crate::types::tuple::Tuple;
-    use crate::types::value::DataValue;
+    use crate::types::tuple::{Tuple, TupleId};
+    use crate::types::value::{DataValue, ValueRef};
     use crate::types::LogicalType;
+    use bytes::Bytes;
+    use futures::poll;
     use itertools::Itertools;
-    use std::collections::{Bound, VecDeque};
+    use kip_db::kernel::lsm::iterator::Iter as KipDBIter;
+    use kip_db::kernel::lsm::mvcc::CheckType;
+    use std::collections::{Bound, HashSet, VecDeque};
     use std::sync::Arc;
     use tempfile::TempDir;
 
+    #[tokio::test]
+    async fn test_codec_version() -> Result<(), StorageError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let storage = KipStorage::new(temp_dir.path()).await?;
+        let mut tx = storage.inner.new_transaction(CheckType::Optimistic).await;
+        let version = tx.get(TableCodec::codec_version_key())?.unwrap();
+        assert_eq!(TableCodec::decode_codec_version(&version)?, CODEC_VERSION);
+
+        // a directory written by another version of the codec
+        let (key, _) = TableCodec::encode_codec_version();
+        tx.set(key, Bytes::from((CODEC_VERSION + 1).to_be_bytes().to_vec()));
+        tx.commit().await?;
+
+        assert!(matches!(
+            KipStorage::check_codec_version(&storage.inner).await,
+            Err(StorageError::CodecVersionMismatch { found, expected })
+                if found == CODEC_VERSION + 1 && expected == CODEC_VERSION
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_codec_version_unstamped() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let storage = KipStorage::new(temp_dir.path()).await?;
+        let mut tx = storage.inner.new_transaction(CheckType::Optimistic).await;
+        tx.remove(TableCodec::codec_version_key())
+            .map_err(StorageError::from)?;
+        tx.commit().await.map_err(StorageError::from)?;
+
+        // an empty directory without the stamp is new, and stamped
+        KipStorage::check_codec_version(&storage.inner).await?;
+        let tx = storage.inner.new_transaction(CheckType::Optimistic).await;
+        assert!(tx
+            .get(TableCodec::codec_version_key())
+            .map_err(StorageError::from)?
+            .is_some());
+
+        // a directory with tables but without the stamp is from before it
+        let kipsql = Database::new(storage)?;
+        let _ = kipsql.run("create table t1 (a int primary key)").await?;
+        let mut tx = kipsql
+            .storage
+            .inner
+            .new_transaction(CheckType::Optimistic)
+            .await;
+        tx.remove(TableCodec::codec_version_key())
+            .map_err(StorageError::from)?;
+        tx.commit().await.map_err(StorageError::from)?;
+
+        assert!(matches!(
+            KipStorage::check_codec_version(&kipsql.storage.inner).await,
+            Err(StorageError::CodecVersionMismatch { found: 1, expected })
+                if expected == CODEC_VERSION
+        ));
+
+        Ok(())
+    }
+
+    /// The keys and values of a directory of codec version 2 with
+    /// `create table t1 (a int primary key, b varchar null unique, c int default 7)`
+    /// and `insert into t1 values (1, 'x', 3), (2, null, 4)`, in hex.
+    const CODEC_V2_FIXTURE: [(&str, &str); 10] = [
+            ("436f64656356657273696f6e", "00000002"),
+            ("526f6f74007431", "7431"),
+            ("7431300000000000", "01000000000100000000000000610007000000010000000000"),
+            ("7431300000000001", "0101000000010000000000000062010d00000000000100000000"),
+            ("7431300000000002", "01020000000100000000000000630007000000000001060000000107000000000000"),
+            ("7431310000000000", "000000000100000000000000000000000400000000000000706b5f610001"),
+            ("7431310000000001", "010000000100000000000000010000000400000000000000756b5f620100"),
+            ("7431320000000001007800000000000000f8", "01000000000000000000000006000000010100000001000000000000000c00000001010000000000000078"),
+            ("7431330080000001", "0001000000010000007803000000"),
+            ("7431330080000002", "400200000004000000"),
+    ];
+
+    #[tokio::test]
+    async fn test_decode_codec_v2() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let storage = KipStorage::new(temp_dir.path()).await?;
+        let from_hex = |hex: &str| {
+            (0..hex.len())
+                .step_by(2)
+                .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+                .collect_vec()
+        };
+        let mut tx = storage.inner.new_transaction(CheckType::Optimistic).await;
+        for (key, value) in CODEC_V2_FIXTURE {
+            tx.set(Bytes::from(from_hex(key)), Bytes::from(from_hex(value)));
+        }
+        tx.commit().await.map_err(StorageError::from)?;
+
+        KipStorage::check_codec_version(&storage.inner).await?;
+        let tx = storage.inner.new_transaction(CheckType::Optimistic).await;
+        let get_version = |key: &[u8]| -> Result<u32, StorageError> {
+            let bytes = tx.get(key)?.unwrap();
+            Ok(TableCodec::decode_codec_version(&bytes)?)
+        };
+        // the table keeps the version it was written with, the directory takes the current one
+        assert_eq!(get_version(&TableCodec::encode_table_version_key("t1"))?, 2);
+        assert_eq!(get_version(TableCodec::codec_version_key())?, CODEC_VERSION);
+        drop(tx);
+
+        let kipsql = Database::new(storage)?;
+        let tuples = kipsql.run("select * from t1").await?;
+        assert_eq!(
+            tuples
+                .iter()
+                .map(|tuple| tuple.values.clone())
+                .collect_vec(),
+            vec![
+                vec![
+                    ValueRef::new(DataValue::Int32(Some(1))),
+                    ValueRef::new(DataValue::Utf8(Some("x".to_string()))),
+                    ValueRef::new(DataValue::Int32(Some(3))),
+                ],
+                vec![
+                    ValueRef::new(DataValue::Int32(Some(2))),
+                    ValueRef::new(DataValue::Utf8(None)),
+                    ValueRef::new(DataValue::Int32(Some(4))),
+                ],
+            ]
+        );
+        // the defaults and the unique index of version 2 still work
+        let _ = kipsql.run("insert into t1 (a, b) values (3, 'y')").await?;
+        assert!(kipsql
+            .run("insert into t1 values (4, 'x', 0)")
+            .await
+            .is_err());
+        let tuples = kipsql.run("select c from t1 where b = 'y'").await?;
+        assert_eq!(
+            tuples[0].values,
+            vec![ValueRef::new(DataValue::Int32(Some(7)))]
+        );
+
+        // a table created now is of the current version
+        let _ = kipsql.run("create table t2 (a int primary key)").await?;
+        let tx = kipsql.storage.transaction().await?;
+        let bytes = tx
+            .tx
+            .get(&TableCodec::encode_table_version_key("t2"))
+            .map_err(StorageError::from)?
+            .unwrap();
+        assert_eq!(
+            TableCodec::decode_codec_version(&bytes).map_err(StorageError::from)?,
+            CODEC_VERSION
+        );
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_in_kipdb_storage_works_with_data() -> Result<(), StorageError> {
         let temp_dir = TempDir::new().expect("unable to create temporary working directory");
@@ -433,9 +1700,14 @@ mod test {
 
         let source_columns = columns
             .iter()
-            .map(|col_ref| ColumnCatalog::clone(&col_ref))
+            .map(|col_ref| ColumnCatalog::clone(col_ref))
             .collect_vec();
-        let _ = transaction.create_table(Arc::new("test".to_string()), source_columns, false)?;
+        let _ = transaction.create_table(
+            Arc::new("test".to_string()),
+            source_columns,
+            vec![],
+            false,
+        )?;
 
         let table_catalog = transaction.table(Arc::new("test".to_string()));
         assert!(table_catalog.is_some());
@@ -445,25 +1717,25 @@ mod test {
             .is_some());
 
         transaction.append(
-            &"test".to_string(),
+            "test",
             Tuple {
-                id: Some(Arc::new(DataValue::Int32(Some(1)))),
-                columns: columns.clone(),
+                id: Some(TupleId::Value(ValueRef::new(DataValue::Int32(Some(1))))),
+                schema_ref: Arc::new(columns.clone()),
                 values: vec![
-                    Arc::new(DataValue::Int32(Some(1))),
-                    Arc::new(DataValue::Boolean(Some(true))),
+                    ValueRef::new(DataValue::Int32(Some(1))),
+                    ValueRef::new(DataValue::Boolean(Some(true))),
                 ],
             },
             false,
         )?;
         transaction.append(
-            &"test".to_string(),
+            "test",
             Tuple {
-                id: Some(Arc::new(DataValue::Int32(Some(2)))),
-                columns: columns.clone(),
+                id: Some(TupleId::Value(ValueRef::new(DataValue::Int32(Some(2))))),
+                schema_ref: Arc::new(columns.clone()),
                 values: vec![
-                    Arc::new(DataValue::Int32(Some(2))),
-                    Arc::new(DataValue::Boolean(Some(false))),
+                    ValueRef::new(DataValue::Int32(Some(2))),
+                    ValueRef::new(DataValue::Boolean(Some(false))),
                 ],
             },
             false,
@@ -478,7 +1750,7 @@ mod test {
         let option_1 = iter.next_tuple()?;
         assert_eq!(
             option_1.unwrap().id,
-            Some(Arc::new(DataValue::Int32(Some(2))))
+            Some(TupleId::Value(ValueRef::new(DataValue::Int32(Some(2)))))
         );
 
         let option_2 = iter.next_tuple()?;
@@ -487,6 +1759,48 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_dropped_commit() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+        let _ = kipsql
+            .run("create table t1 (a int primary key, b int)")
+            .await?;
+
+        let mut transaction = kipsql.new_transaction().await?;
+        let _ = transaction.run("insert into t1 values (1, 1)").await?;
+        // the commit waits for another one to be applied, and is dropped as by a timeout
+        let turn = kipsql.storage.commit_lock.lock().await;
+        let mut commit = Box::pin(transaction.commit());
+        assert!(poll!(commit.as_mut()).is_pending());
+        drop(commit);
+        drop(turn);
+
+        let transaction = kipsql.storage.transaction().await?;
+        let table = transaction
+            .table(Arc::new("t1".to_string()))
+            .unwrap()
+            .clone();
+        let mut iter = transaction.read(
+            Arc::new("t1".to_string()),
+            (None, None),
+            table
+                .all_columns()
+                .into_iter()
+                .map(ScalarExpression::ColumnRef)
+                .collect_vec(),
+        )?;
+        assert_eq!(iter.next_tuple()?, None);
+        drop(iter);
+        transaction.commit().await?;
+
+        // the storage takes the commits after it
+        let _ = kipsql.run("insert into t1 values (1, 2)").await?;
+        assert_eq!(kipsql.run("select b from t1").await?.len(), 1);
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_index_iter_pk() -> Result<(), DatabaseError> {
         let temp_dir = TempDir::new().expect("unable to create temporary working directory");
@@ -505,36 +1819,38 @@ mod test {
         let projections = table
             .all_columns()
             .into_iter()
-            .map(|col| ScalarExpression::ColumnRef(col))
+            .map(ScalarExpression::ColumnRef)
             .collect_vec();
-        let tuple_ids = vec![
-            Arc::new(DataValue::Int32(Some(0))),
-            Arc::new(DataValue::Int32(Some(2))),
-            Arc::new(DataValue::Int32(Some(3))),
-            Arc::new(DataValue::Int32(Some(4))),
-        ];
+        let tuple_ids = [0, 2, 3, 4]
+            .map(|id| TupleId::Value(ValueRef::new(DataValue::Int32(Some(id)))))
+            .to_vec();
         let mut iter = IndexIter {
             offset: 0,
             limit: None,
+            schema_ref: projection_schema(&projections),
             projections,
+            filter: None,
             index_meta: Arc::new(IndexMeta {
                 id: 0,
                 column_ids: vec![0],
                 name: "pk_a".to_string(),
                 is_unique: false,
                 is_primary: true,
+                expr: None,
             }),
+            covered_schema_ref: None,
             table: &table,
             binaries: VecDeque::from(vec![
-                ConstantBinary::Eq(Arc::new(DataValue::Int32(Some(0)))),
+                ConstantBinary::Eq(ValueRef::new(DataValue::Int32(Some(0)))),
                 ConstantBinary::Scope {
-                    min: Bound::Included(Arc::new(DataValue::Int32(Some(2)))),
-                    max: Bound::Included(Arc::new(DataValue::Int32(Some(4)))),
+                    min: Bound::Included(ValueRef::new(DataValue::Int32(Some(2)))),
+                    max: Bound::Included(ValueRef::new(DataValue::Int32(Some(4)))),
                 },
             ]),
             index_values: VecDeque::new(),
             tx: &transaction.tx,
             scope_iter: None,
+            seen_keys: Some(HashSet::new()),
         };
         let mut result = Vec::new();
 
@@ -548,26 +1864,167 @@ mod test {
     }
 
     #[tokio::test]
-    async fn test_read_by_index() -> Result<(), DatabaseError> {
+    async fn test_read_with_filter() -> Result<(), DatabaseError> {
         let temp_dir = TempDir::new().expect("unable to create temporary working directory");
         let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+
         let _ = kipsql
-            .run("create table t1 (a int primary key, b int unique)")
+            .run("create table t1 (a int primary key, b int, c int unique)")
             .await?;
+        let values = (0..20)
+            .map(|i| format!("({}, {}, {})", i, i % 10, i))
+            .join(", ");
         let _ = kipsql
-            .run("insert into t1 (a, b) values (0, 0), (1, 1), (2, 2)")
+            .run(&format!("insert into t1 values {}", values))
             .await?;
-        let transaction = kipsql.storage.transaction().await.unwrap();
+        let transaction = kipsql.storage.transaction().await?;
+        let table_name = Arc::new("t1".to_string());
+        let table = transaction.table(table_name.clone()).unwrap().clone();
+        let projections = vec![ScalarExpression::ColumnRef(
+            table.get_column_by_name(&"a".to_string()).unwrap().clone(),
+        )];
+        // b = 3, on a column not projected
+        let filter = ScalarExpression::Binary {
+            op: BinaryOperator::Eq,
+            left_expr: Box::new(ScalarExpression::ColumnRef(
+                table.get_column_by_name(&"b".to_string()).unwrap().clone(),
+            )),
+            right_expr: Box::new(ScalarExpression::Constant(ValueRef::new(DataValue::Int32(
+                Some(3),
+            )))),
+            ty: LogicalType::Boolean,
+        };
+        let collect = |iter: &mut dyn Iter| -> Result<Vec<DataValue>, StorageError> {
+            let mut values = Vec::new();
 
-        let table = transaction
-            .table(Arc::new("t1".to_string()))
+            while let Some(tuple) = iter.next_tuple()? {
+                values.push(tuple.values[0].as_ref().clone());
+            }
+            Ok(values)
+        };
+        let a = |i: i32| DataValue::Int32(Some(i));
+
+        // all the tuples are read, only those passing the filter are emitted
+        let mut iter = transaction.read(table_name.clone(), (None, None), projections.clone())?;
+        let rows_read = collect(&mut iter)?.len();
+        let mut iter = transaction.read(table_name.clone(), (None, None), projections.clone())?;
+        iter.set_filter(filter.clone());
+        let rows_emitted = collect(&mut iter)?;
+        assert_eq!(rows_read, 20);
+        assert_eq!(rows_emitted, vec![a(3), a(13)]);
+
+        // the bounds count the tuples passing the filter
+        let mut iter =
+            transaction.read(table_name.clone(), (Some(1), Some(1)), projections.clone())?;
+        iter.set_filter(filter.clone());
+        assert_eq!(iter.next_batch(10)?.len(), 1);
+        assert_eq!(iter.next_tuple()?, None);
+
+        // the tuples found by an index are filtered too
+        let index_meta = table
+            .indexes
+            .iter()
+            .find(|index_meta| index_meta.name == "uk_c")
             .unwrap()
             .clone();
-        let projections = table
-            .all_columns()
-            .into_iter()
-            .map(|col| ScalarExpression::ColumnRef(col))
-            .collect_vec();
+        let mut iter = transaction.read_by_index(
+            table_name.clone(),
+            (Some(1), None),
+            projections.clone(),
+            index_meta,
+            vec![ConstantBinary::Scope {
+                min: Bound::Included(ValueRef::new(a(2))),
+                max: Bound::Unbounded,
+            }],
+            false,
+        )?;
+        iter.set_filter(filter);
+        assert_eq!(collect(&mut iter)?, vec![a(13)]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_range_delete() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+
+        for table_name in ["t1", "t2"] {
+            let _ = kipsql
+                .run(&format!(
+                    "create table {} (a int primary key, b int unique)",
+                    table_name
+                ))
+                .await?;
+            let _ = kipsql
+                .run(&format!(
+                    "insert into {} values (0, 10), (1, 11), (2, 12), (3, 13), (4, 14), (5, 15), (6, 16), (7, 17), (8, 18)",
+                    table_name
+                ))
+                .await?;
+        }
+        let _ = kipsql
+            .run("delete from t2 where (a >= 2 and a < 5) or (a > 3 and a <= 5) or a = 7")
+            .await?;
+
+        let mut transaction = kipsql.storage.transaction().await?;
+        let value = |i| ValueRef::new(DataValue::Int32(Some(i)));
+        let deleted = transaction.range_delete(
+            "t1",
+            vec![
+                ConstantBinary::Scope {
+                    min: Bound::Included(value(2)),
+                    max: Bound::Excluded(value(5)),
+                },
+                // overlapping the previous range
+                ConstantBinary::Scope {
+                    min: Bound::Excluded(value(3)),
+                    max: Bound::Included(value(5)),
+                },
+                ConstantBinary::Eq(value(7)),
+                // nothing to delete
+                ConstantBinary::Eq(value(20)),
+            ],
+        )?;
+        assert_eq!(deleted, 5);
+        transaction.commit().await?;
+
+        let values =
+            |tuples: Vec<Tuple>| tuples.into_iter().map(|tuple| tuple.values).collect_vec();
+        assert_eq!(
+            values(kipsql.run("select * from t1").await?),
+            values(kipsql.run("select * from t2").await?)
+        );
+        // the unique values of the deleted tuples are free again
+        let _ = kipsql
+            .run("insert into t1 values (12, 12), (13, 13), (14, 14), (15, 15), (17, 17)")
+            .await?;
+        assert_eq!(kipsql.run("select * from t1 where b = 14").await?.len(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_read_by_index() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+        let _ = kipsql
+            .run("create table t1 (a int primary key, b int unique)")
+            .await?;
+        let _ = kipsql
+            .run("insert into t1 (a, b) values (0, 0), (1, 1), (2, 2)")
+            .await?;
+        let transaction = kipsql.storage.transaction().await.unwrap();
+
+        let table = transaction
+            .table(Arc::new("t1".to_string()))
+            .unwrap()
+            .clone();
+        let projections = table
+            .all_columns()
+            .into_iter()
+            .map(ScalarExpression::ColumnRef)
+            .collect_vec();
         let mut iter = transaction
             .read_by_index(
                 Arc::new("t1".to_string()),
@@ -575,23 +2032,651 @@ mod test {
                 projections,
                 table.indexes[0].clone(),
                 vec![ConstantBinary::Scope {
-                    min: Bound::Excluded(Arc::new(DataValue::Int32(Some(0)))),
+                    min: Bound::Excluded(ValueRef::new(DataValue::Int32(Some(0)))),
                     max: Bound::Unbounded,
                 }],
+                false,
             )
             .unwrap();
 
         while let Some(tuple) = iter.next_tuple()? {
-            assert_eq!(tuple.id, Some(Arc::new(DataValue::Int32(Some(1)))));
+            assert_eq!(
+                tuple.id,
+                Some(TupleId::Value(ValueRef::new(DataValue::Int32(Some(1)))))
+            );
             assert_eq!(
                 tuple.values,
                 vec![
-                    Arc::new(DataValue::Int32(Some(1))),
-                    Arc::new(DataValue::Int32(Some(1)))
+                    ValueRef::new(DataValue::Int32(Some(1))),
+                    ValueRef::new(DataValue::Int32(Some(1)))
                 ]
             )
         }
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_unique_index_nulls() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+        let _ = kipsql
+            .run("create table t1 (a int primary key, b int null unique, c int null)")
+            .await?;
+
+        let _ = kipsql
+            .run("insert into t1 values (0, null, null), (1, null, null)")
+            .await?;
+        let _ = kipsql.run("insert into t1 values (2, 2, 2)").await?;
+        let _ = kipsql.run("update t1 set b = null where a = 2").await?;
+        assert_eq!(
+            kipsql.run("select a from t1 where b is null").await?.len(),
+            3
+        );
+        let _ = kipsql.run("insert into t1 values (3, 3, 3)").await?;
+        assert!(matches!(
+            kipsql.run("insert into t1 values (4, 3, 4)").await,
+            Err(DatabaseError::ExecutorError(ExecutorError::StorageError(
+                StorageError::DuplicateUniqueValue
+            )))
+        ));
+        assert!(matches!(
+            kipsql
+                .run("insert into t1 values (4, 4, 4), (5, 4, 5)")
+                .await,
+            Err(DatabaseError::ExecutorError(ExecutorError::StorageError(
+                StorageError::DuplicateUniqueValue
+            )))
+        ));
+
+        // the nulls the tuples already have do not collide either
+        let _ = kipsql.run("create unique index u_c on t1 (c)").await?;
+        let _ = kipsql.run("insert into t1 values (6, null, null)").await?;
+        assert!(matches!(
+            kipsql.run("insert into t1 values (7, null, 3)").await,
+            Err(DatabaseError::ExecutorError(ExecutorError::StorageError(
+                StorageError::DuplicateUniqueValue
+            )))
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_update_tuple() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+        let _ = kipsql
+            .run("create table t1 (a int primary key, b int, c int unique)")
+            .await?;
+        let _ = kipsql
+            .run("insert into t1 values (0, 0, 0), (1, 1, 1)")
+            .await?;
+        let int = |i| ValueRef::new(DataValue::Int32(Some(i)));
+        let tuple_id = TupleId::Value(int(0));
+        let tuple_key = TableCodec::encode_tuple_key("t1", &tuple_id).unwrap();
+
+        // the writes are logged under read committed
+        let mut transaction = kipsql
+            .storage
+            .transaction_with_isolation(IsolationLevel::ReadCommitted)
+            .await?;
+        let old = transaction.get_tuple("t1", &tuple_id)?.unwrap();
+        let mut new = old.clone();
+        new.values[1] = int(10);
+        transaction.update_tuple("t1", &old, new)?;
+        // the column is not indexed, so only the tuple is written
+        assert_eq!(
+            transaction.tx.writes.as_ref().unwrap().keys().collect_vec(),
+            vec![&Bytes::from(tuple_key.clone())]
+        );
+
+        let old = transaction.get_tuple("t1", &tuple_id)?.unwrap();
+        let mut new = old.clone();
+        new.values[2] = int(20);
+        transaction.update_tuple("t1", &old, new)?;
+        // the entry of the old value is removed and the one of the new value added
+        assert_eq!(transaction.tx.writes.as_ref().unwrap().len(), 3);
+
+        transaction.commit().await?;
+
+        let tuples = kipsql.run("select a, b from t1 where c = 20").await?;
+        assert_eq!(tuples[0].values, vec![int(0), int(10)]);
+        assert!(kipsql.run("select a from t1 where c = 0").await?.is_empty());
+
+        let mut transaction = kipsql.storage.transaction().await?;
+        let old = transaction.get_tuple("t1", &tuple_id)?.unwrap();
+        let mut new = old.clone();
+        new.values[2] = int(1);
+        assert!(matches!(
+            transaction.update_tuple("t1", &old, new),
+            Err(StorageError::DuplicateUniqueValue)
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_index_iter_limit() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+        let _ = kipsql.run("create table t1 (a int primary key)").await?;
+        let _ = kipsql
+            .run("insert into t1 (a) values (0), (1), (2), (3), (4)")
+            .await?;
+        let transaction = kipsql.storage.transaction().await?;
+        let table = transaction
+            .table(Arc::new("t1".to_string()))
+            .unwrap()
+            .clone();
+        let projections = table
+            .all_columns()
+            .into_iter()
+            .map(ScalarExpression::ColumnRef)
+            .collect_vec();
+        let mut iter = transaction.read_by_index(
+            Arc::new("t1".to_string()),
+            (Some(1), Some(2)),
+            projections,
+            table.indexes[0].clone(),
+            vec![
+                ConstantBinary::Eq(ValueRef::new(DataValue::Int32(Some(0)))),
+                ConstantBinary::Eq(ValueRef::new(DataValue::Int32(Some(1)))),
+                ConstantBinary::Eq(ValueRef::new(DataValue::Int32(Some(2)))),
+                ConstantBinary::Scope {
+                    min: Bound::Included(ValueRef::new(DataValue::Int32(Some(3)))),
+                    max: Bound::Unbounded,
+                },
+            ],
+            false,
+        )?;
+
+        assert_eq!(
+            iter.next_tuple()?.and_then(|tuple| tuple.id),
+            Some(TupleId::Value(ValueRef::new(DataValue::Int32(Some(1)))))
+        );
+        assert_eq!(
+            iter.next_tuple()?.and_then(|tuple| tuple.id),
+            Some(TupleId::Value(ValueRef::new(DataValue::Int32(Some(2)))))
+        );
+        // the rest of the ranges are never read once the limit is reached
+        assert_eq!(iter.binaries.len(), 1);
+        assert!(iter.scope_iter.is_none());
+        assert_eq!(iter.next_tuple()?, None);
+        assert!(iter.binaries.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_index_iter_overlapping_ranges() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+        let _ = kipsql
+            .run("create table t1 (a int primary key, b int unique)")
+            .await?;
+        let _ = kipsql
+            .run("insert into t1 (a, b) values (0, 10), (1, 11), (2, 12), (3, 13), (4, 14)")
+            .await?;
+        let transaction = kipsql.storage.transaction().await?;
+        let table = transaction
+            .table(Arc::new("t1".to_string()))
+            .unwrap()
+            .clone();
+        let projections = table
+            .all_columns()
+            .into_iter()
+            .map(ScalarExpression::ColumnRef)
+            .collect_vec();
+        let value = |i| ValueRef::new(DataValue::Int32(Some(i)));
+        // e.g. `a >= 1 and a <= 3 or a >= 2 or a = 3`
+        let binaries = |offset: i32| {
+            vec![
+                ConstantBinary::Scope {
+                    min: Bound::Included(value(offset + 1)),
+                    max: Bound::Included(value(offset + 3)),
+                },
+                ConstantBinary::Scope {
+                    min: Bound::Included(value(offset + 2)),
+                    max: Bound::Unbounded,
+                },
+                ConstantBinary::Eq(value(offset + 3)),
+            ]
+        };
+
+        for (index_meta, offset) in [(&table.indexes[0], 0), (&table.indexes[1], 10)] {
+            for index_only in [false, true] {
+                if index_only && index_meta.is_primary {
+                    continue;
+                }
+                let mut iter = transaction.read_by_index(
+                    Arc::new("t1".to_string()),
+                    (Some(1), None),
+                    projections.clone(),
+                    index_meta.clone(),
+                    binaries(offset),
+                    index_only,
+                )?;
+                let mut tuple_ids = Vec::new();
+
+                while let Some(tuple) = iter.next_tuple()? {
+                    tuple_ids.push(tuple.id.unwrap());
+                }
+                // each tuple once, and the offset skips no repeated one
+                assert_eq!(
+                    tuple_ids,
+                    [2, 3, 4].map(|i| TupleId::Value(value(i))).to_vec()
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_read_in_tuple_id_order() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+        let _ = kipsql
+            .run("create table t1 (a int primary key, b int); create table t2 (v int)")
+            .await?;
+        let _ = kipsql
+            .run("insert into t1 values (3, 0), (-1, 1), (256, 2), (0, 3)")
+            .await?;
+        // more tuples than a batch of the scan, inserted by several statements
+        let mut expected = Vec::new();
+        for chunk in (0..1500).rev().collect_vec().chunks(500) {
+            let _ = kipsql
+                .run(&format!(
+                    "insert into t2 values {}",
+                    chunk.iter().map(|v| format!("({})", v)).join(", ")
+                ))
+                .await?;
+            expected.extend_from_slice(chunk);
+        }
+        let _ = kipsql.run("delete from t2 where v = 1000").await?;
+        expected.retain(|v| *v != 1000);
+
+        let values = |tuples: Vec<Tuple>| {
+            tuples
+                .into_iter()
+                .map(|tuple| tuple.values[0].clone())
+                .collect_vec()
+        };
+        assert_eq!(
+            values(kipsql.run("select a from t1").await?),
+            [-1, 0, 3, 256]
+                .into_iter()
+                .map(|v| ValueRef::new(DataValue::Int32(Some(v))))
+                .collect_vec()
+        );
+        let expected = expected
+            .into_iter()
+            .map(|v| ValueRef::new(DataValue::Int32(Some(v))))
+            .collect_vec();
+        for _ in 0..2 {
+            assert_eq!(values(kipsql.run("select * from t2").await?), expected);
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_index_only_scan() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+        let _ = kipsql
+            .run("create table t1 (id int primary key, a int unique, b int)")
+            .await?;
+        let _ = kipsql
+            .run("insert into t1 (id, a, b) values (0, 0, 0), (1, 1, 1), (2, 2, 2)")
+            .await?;
+
+        // remove the tuples but keep the entries of the unique index on `a`
+        let mut transaction = kipsql.storage.transaction().await?;
+        for id in 0..3 {
+            transaction.delete(
+                "t1",
+                TupleId::Value(ValueRef::new(DataValue::Int32(Some(id)))),
+            )?;
+        }
+        transaction.commit().await?;
+
+        let tuples = kipsql.run("select a from t1 where a > 0").await?;
+        assert_eq!(
+            tuples
+                .iter()
+                .map(|tuple| tuple.values.clone())
+                .collect_vec(),
+            vec![
+                vec![ValueRef::new(DataValue::Int32(Some(1)))],
+                vec![ValueRef::new(DataValue::Int32(Some(2)))],
+            ]
+        );
+        // `b` is not in the index, so the tuples have to be read
+        let tuples = kipsql.run("select a, b from t1 where a > 0").await?;
+        assert!(tuples.is_empty());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_index_intersection() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+        let _ = kipsql
+            .run(
+                "create table t1 (id int primary key, a int unique null, b int unique null, c int)",
+            )
+            .await?;
+        let values = (0..200)
+            .map(|i| match i % 17 {
+                0 => format!("({}, null, {}, {})", i, 199 - i, i * 10),
+                _ => format!("({}, {}, {}, {})", i, i, 199 - i, i * 10),
+            })
+            .join(", ");
+        let _ = kipsql
+            .run(&format!("insert into t1 values {}", values))
+            .await?;
+
+        for (predicate, ids) in [
+            (
+                "a >= 50 and b >= 100",
+                (50..100).filter(|i| i % 17 != 0).collect_vec(),
+            ),
+            ("a = 10 and b = 189", vec![10]),
+            ("a = 10 and b = 10", vec![]),
+            (
+                "a < 40 and b > 180 and c > 50",
+                (6..19).filter(|i| i % 17 != 0).collect_vec(),
+            ),
+        ] {
+            let tuples = kipsql
+                .run(&format!("explain select id from t1 where {}", predicate))
+                .await?;
+            assert!(
+                format!("{:?}", tuples).contains("Index uk_a & uk_b"),
+                "{}",
+                predicate
+            );
+            let tuples = kipsql
+                .run(&format!("select id from t1 where {}", predicate))
+                .await?;
+            assert_eq!(
+                tuples
+                    .iter()
+                    .map(|tuple| tuple.values.clone())
+                    .collect_vec(),
+                ids.into_iter()
+                    .map(|id| vec![ValueRef::new(DataValue::Int32(Some(id)))])
+                    .collect_vec(),
+                "{}",
+                predicate
+            );
+        }
+        // the primary key is scanned alone
+        let tuples = kipsql
+            .run("explain select id from t1 where id < 10 and a >= 5 and b > 0")
+            .await?;
+        assert!(!format!("{:?}", tuples).contains(" & "));
+        let tuples = kipsql
+            .run("select id from t1 where id < 10 and a >= 5 and b > 0")
+            .await?;
+        assert_eq!(tuples.len(), 5);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_tuple() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+        let _ = kipsql
+            .run("create table t1 (a int primary key, b varchar null)")
+            .await?;
+        let _ = kipsql
+            .run("insert into t1 values (0, 'x'), (1, null), (2, 'z')")
+            .await?;
+        let transaction = kipsql.storage.transaction().await?;
+
+        let tuple = transaction
+            .get_tuple(
+                "t1",
+                &TupleId::Value(ValueRef::new(DataValue::Int32(Some(1)))),
+            )?
+            .unwrap();
+        assert_eq!(
+            tuple.id,
+            Some(TupleId::Value(ValueRef::new(DataValue::Int32(Some(1)))))
+        );
+        assert_eq!(
+            tuple.values,
+            vec![
+                ValueRef::new(DataValue::Int32(Some(1))),
+                ValueRef::new(DataValue::Utf8(None))
+            ]
+        );
+        assert_eq!(
+            tuple
+                .schema_ref
+                .iter()
+                .map(|col| col.name().to_string())
+                .collect_vec(),
+            vec!["a", "b"]
+        );
+        assert!(transaction
+            .get_tuple(
+                "t1",
+                &TupleId::Value(ValueRef::new(DataValue::Int32(Some(3))))
+            )?
+            .is_none());
+        assert!(matches!(
+            transaction.get_tuple(
+                "t2",
+                &TupleId::Value(ValueRef::new(DataValue::Int32(Some(0))))
+            ),
+            Err(StorageError::TableNotFound)
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_rename_table() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+        let _ = kipsql
+            .run("create table t1 (a int primary key, b int unique, c int)")
+            .await?;
+        let _ = kipsql.run("create table t3 (a int primary key)").await?;
+        let _ = kipsql
+            .run("insert into t1 values (0, 10, 5), (1, 11, 6)")
+            .await?;
+        let mut transaction = kipsql.storage.transaction().await?;
+
+        transaction.rename_table("t1", "t2")?;
+        assert!(transaction.table(Arc::new("t1".to_string())).is_none());
+        assert!(matches!(
+            transaction.rename_table("t1", "t4"),
+            Err(StorageError::TableNotFound)
+        ));
+        assert!(matches!(
+            transaction.rename_table("t2", "t3"),
+            Err(StorageError::TableExists)
+        ));
+
+        // nothing is left under the old name
+        for (min, max) in [
+            TableCodec::columns_bound("t1"),
+            TableCodec::index_meta_bound("t1"),
+            TableCodec::all_index_bound("t1"),
+            TableCodec::tuple_bound("t1"),
+        ] {
+            let mut iter = transaction
+                .tx
+                .iter(Bound::Included(&min), Bound::Included(&max))
+                .map_err(StorageError::from)?;
+            // the removed keys are deleted by tombstones
+            while let Some((_, value_option)) = iter.try_next().map_err(StorageError::from)? {
+                assert!(value_option.is_none());
+            }
+        }
+
+        let column_id = transaction
+            .table(Arc::new("t2".to_string()))
+            .unwrap()
+            .get_column_id_by_name(&"b".to_string())
+            .unwrap();
+        transaction.rename_column("t2", column_id, "b2")?;
+        let table = transaction.table(Arc::new("t2".to_string())).unwrap();
+        assert_eq!(
+            table.get_column_id_by_name(&"b2".to_string()),
+            Some(column_id)
+        );
+        assert!(!table.contains_column("b"));
+        assert_eq!(
+            table
+                .indexes()
+                .iter()
+                .map(|meta| meta.name.as_str())
+                .collect_vec(),
+            vec!["pk_a", "uk_b2"]
+        );
+        transaction.commit().await?;
+
+        let tuples = kipsql.run("select a, c from t2 where b2 = 11").await?;
+        assert_eq!(
+            tuples[0].values,
+            vec![
+                ValueRef::new(DataValue::Int32(Some(1))),
+                ValueRef::new(DataValue::Int32(Some(6)))
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_catalog_cache_invalidation() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let config = CatalogCacheConfig {
+            cap: 4,
+            sharding_size: 2,
+        };
+        let kipsql = Database::with_config(
+            temp_dir.path(),
+            DatabaseConfig::default().with_catalog_cache(config),
+        )
+        .await?;
+        assert_eq!(kipsql.storage.cache_config(), config);
+
+        let _ = kipsql
+            .run("create table t1 (c1 int primary key, c2 int)")
+            .await?;
+        let _ = kipsql.run("insert into t1 values (1, 10)").await?;
+
+        let mut transaction = kipsql.new_transaction().await?;
+        // caches the catalog of `t1` in the transaction
+        assert_eq!(transaction.run("select c2 from t1").await?.len(), 1);
+
+        let _ = transaction
+            .run("alter table t1 rename column c2 to c3")
+            .await?;
+        assert!(transaction.run("select c2 from t1").await.is_err());
+        assert_eq!(transaction.run("select c3 from t1").await?.len(), 1);
+
+        let _ = transaction.run("alter table t1 rename to t2").await?;
+        assert!(transaction.run("select c3 from t1").await.is_err());
+        assert_eq!(transaction.run("select c3 from t2").await?.len(), 1);
+        transaction.commit().await?;
+
+        assert_eq!(kipsql.run("select c3 from t2").await?.len(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_next_batch_same_as_next_tuple() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+        let _ = kipsql
+            .run("create table t1 (a int primary key, b int)")
+            .await?;
+        let values = (0..300).map(|i| format!("({}, {})", i, i % 7)).join(", ");
+        let _ = kipsql
+            .run(&format!("insert into t1 (a, b) values {}", values))
+            .await?;
+        let transaction = kipsql.storage.transaction().await?;
+        let table_name = Arc::new("t1".to_string());
+        let projections = transaction
+            .table(table_name.clone())
+            .unwrap()
+            .all_columns()
+            .into_iter()
+            .map(ScalarExpression::ColumnRef)
+            .collect_vec();
+
+        for bounds in [(None, None), (Some(10), Some(150)), (Some(290), None)] {
+            let mut iter = transaction.read(table_name.clone(), bounds, projections.clone())?;
+            let mut single = Vec::new();
+            while let Some(tuple) = iter.next_tuple()? {
+                single.push(tuple);
+            }
+
+            let mut iter = transaction.read(table_name.clone(), bounds, projections.clone())?;
+            let mut batched = Vec::new();
+            loop {
+                let tuples = iter.next_batch(64)?;
+                if tuples.is_empty() {
+                    break;
+                }
+                assert!(tuples.len() <= 64);
+                batched.extend(tuples);
+            }
+
+            assert!(!single.is_empty());
+            assert_eq!(single, batched);
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_read_tuples_share_schema() -> Result<(), DatabaseError> {
+        let temp_dir = TempDir::new().expect("unable to create temporary working directory");
+        let kipsql = Database::with_kipdb(temp_dir.path()).await?;
+        let _ = kipsql
+            .run("create table t1 (a int primary key, b int)")
+            .await?;
+        let _ = kipsql
+            .run("insert into t1 (a, b) values (0, 10), (1, 11), (2, 12)")
+            .await?;
+        let transaction = kipsql.storage.transaction().await?;
+        let table_name = Arc::new("t1".to_string());
+        let column_b = transaction
+            .table(table_name.clone())
+            .unwrap()
+            .get_column_by_name(&"b".to_string())
+            .unwrap()
+            .clone();
+
+        let mut iter = transaction.read(
+            table_name,
+            (None, None),
+            vec![ScalarExpression::ColumnRef(column_b.clone())],
+        )?;
+        let tuples = iter.next_batch(16)?;
+
+        assert_eq!(tuples.len(), 3);
+        for (i, tuple) in tuples.iter().enumerate() {
+            assert!(Arc::ptr_eq(&tuple.schema_ref, &tuples[0].schema_ref));
+            assert_eq!(tuple.schema_ref.as_ref(), &vec![column_b.clone()]);
+            assert_eq!(
+                tuple.values,
+                vec![ValueRef::new(DataValue::Int32(Some(10 + i as i32)))]
+            );
+        }
+
+        Ok(())
+    }
 }
@@ -1,26 +1,60 @@
 pub mod kip;
-mod table_codec;
+pub(crate) mod table_codec;
 
 use crate::catalog::{CatalogError, ColumnCatalog, TableCatalog, TableName};
 use crate::expression::simplify::ConstantBinary;
 use crate::expression::ScalarExpression;
-use crate::storage::table_codec::TableCodec;
+use crate::storage::table_codec::{TableCodec, MIN_CODEC_VERSION};
 use crate::types::errors::TypeError;
-use crate::types::index::{Index, IndexMetaRef};
-use crate::types::tuple::{Tuple, TupleId};
-use crate::types::value::ValueRef;
+use crate::types::index::{Index, IndexMeta, IndexMetaRef};
+use crate::types::tuple::{SchemaRef, Tuple, TupleId};
+use crate::types::value::{DataValue, ValueRef};
+use crate::types::ColumnId;
 use kip_db::kernel::lsm::iterator::Iter as DBIter;
 use kip_db::kernel::lsm::mvcc;
 use kip_db::KernelError;
-use std::collections::{Bound, VecDeque};
+use std::collections::{Bound, HashSet, VecDeque};
 use std::mem;
 use std::ops::SubAssign;
+use std::sync::Arc;
 
 pub trait Storage: Sync + Send + Clone + 'static {
     type TransactionType: Transaction;
 
     #[allow(async_fn_in_trait)]
     async fn transaction(&self) -> Result<Self::TransactionType, StorageError>;
+
+    /// A transaction whose reads see the commits of the others as `isolation` tells,
+    /// `transaction` is the one of `IsolationLevel::RepeatableRead`.
+    #[allow(async_fn_in_trait)]
+    async fn transaction_with_isolation(
+        &self,
+        isolation: IsolationLevel,
+    ) -> Result<Self::TransactionType, StorageError>;
+
+    /// A transaction that only reads, every write of it fails with `StorageError::ReadOnly`.
+    #[allow(async_fn_in_trait)]
+    async fn read_only_transaction(&self) -> Result<Self::TransactionType, StorageError>;
+
+    /// Writes the committed data still held in memory into the data files, after which it
+    /// survives a crash whatever the durability settings of the storage are.
+    #[allow(async_fn_in_trait)]
+    async fn flush(&self) -> Result<(), StorageError>;
+}
+
+/// What the reads of a transaction see of the transactions committed while it runs.
+/// Either way it sees its own writes, and its commit fails with
+/// `StorageError::TransactionConflict` if a transaction committed after its snapshot was
+/// taken wrote one of the keys it writes.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum IsolationLevel {
+    /// Each statement reads a snapshot of the data committed before it began, so reading
+    /// the same rows in two statements may see the commits of other transactions in between.
+    ReadCommitted,
+    /// All the statements read the snapshot taken when the transaction began, reading the
+    /// same rows twice sees the same data whatever is committed in between.
+    #[default]
+    RepeatableRead,
 }
 
 /// Optional bounds of the reader, of the form (offset, limit).
@@ -32,7 +66,11 @@ pub trait Transaction: Sync + Send + 'static {
 
     /// The bounds is applied to the whole data batches, not per batch.
     ///
-    /// The projections is column indices.
+    /// The projections are evaluated on each tuple read, they are the columns of the table
+    /// or expressions of them pushed down by the optimizer.
+    ///
+    /// The tuples are read in ascending order of their primary key, as the keys of the tuples
+    /// encode it order-preserving. For a table keyed by the hidden rowid it is the order of insertion.
     fn read(
         &self,
         table_name: TableName,
@@ -40,6 +78,8 @@ pub trait Transaction: Sync + Send + 'static {
         projection: Projections,
     ) -> Result<Self::IterType<'_>, StorageError>;
 
+    /// With `index_only`, the tuples are built from the values kept in the index entries,
+    /// which requires the index to cover all the projections.
     fn read_by_index(
         &self,
         table_name: TableName,
@@ -47,8 +87,24 @@ pub trait Transaction: Sync + Send + 'static {
         projection: Projections,
         index_meta: IndexMetaRef,
         binaries: Vec<ConstantBinary>,
+        index_only: bool,
+    ) -> Result<IndexIter<'_>, StorageError>;
+
+    /// Reads the tuples found by every index of `index_by`, each scanned by its `binaries`,
+    /// in the order of the first one. The tuple ids are taken from the entries of the indexes
+    /// and intersected, so only the tuples left are read.
+    fn read_by_index_intersection(
+        &self,
+        table_name: TableName,
+        bounds: Bounds,
+        projection: Projections,
+        index_by: Vec<(IndexMetaRef, Vec<ConstantBinary>)>,
     ) -> Result<IndexIter<'_>, StorageError>;
 
+    /// Adds `tuple_ids` to the entry of the index, a unique index fails with
+    /// `StorageError::DuplicateUniqueValue` if another tuple has the same values. The values
+    /// are not null: the tuples with a null have no entry, as nulls never collide
+    /// (see `IndexMeta::column_values`).
     fn add_index(
         &mut self,
         table_name: &str,
@@ -57,8 +113,17 @@ pub trait Transaction: Sync + Send + 'static {
         is_unique: bool,
     ) -> Result<(), StorageError>;
 
-    fn del_index(&mut self, table_name: &str, index: &Index) -> Result<(), StorageError>;
+    /// Removes `tuple_id` from the entry of the index, the entry is removed once it holds
+    /// no tuple.
+    fn del_index(
+        &mut self,
+        table_name: &str,
+        index: &Index,
+        tuple_id: &TupleId,
+    ) -> Result<(), StorageError>;
 
+    /// With `is_overwrite` the tuple of the same primary key is replaced, the entries of
+    /// its secondary indexes whose values change are removed, those of `tuple` are left to the caller.
     fn append(
         &mut self,
         table_name: &str,
@@ -66,28 +131,129 @@ pub trait Transaction: Sync + Send + 'static {
         is_overwrite: bool,
     ) -> Result<(), StorageError>;
 
+    /// Writes `new` in place of `old`, a tuple of the table with all its columns. Only the
+    /// entries of the secondary indexes whose values change are moved, and with the same
+    /// tuple id the tuple is overwritten in place. Otherwise `old` is deleted and `new` fails
+    /// with `StorageError::DuplicatePrimaryKey` if its primary key is taken.
+    fn update_tuple(
+        &mut self,
+        table_name: &str,
+        old: &Tuple,
+        new: Tuple,
+    ) -> Result<(), StorageError>;
+
     fn delete(&mut self, table_name: &str, tuple_id: TupleId) -> Result<(), StorageError>;
 
+    /// Reads the tuple of `tuple_id` with all the columns of the table, `None` if there is none.
+    fn get_tuple(
+        &self,
+        table_name: &str,
+        tuple_id: &TupleId,
+    ) -> Result<Option<Tuple>, StorageError>;
+
+    /// Deletes the tuples whose primary key falls in any of `binaries`, the `Scope`s and `Eq`s
+    /// of a scan by the primary key, along with the entries of their secondary indexes.
+    /// The keys are removed straight from the range read, a tuple is only decoded when the
+    /// table has secondary indexes to clean up. Returns the number of tuples deleted.
+    fn range_delete(
+        &mut self,
+        table_name: &str,
+        binaries: Vec<ConstantBinary>,
+    ) -> Result<usize, StorageError>;
+
+    /// Sets the comment of the table, or of its column `column_id`, `None` removes it.
+    fn set_comment(
+        &mut self,
+        table_name: &str,
+        column_id: Option<ColumnId>,
+        comment: Option<String>,
+    ) -> Result<(), StorageError>;
+
+    /// Locks the tuple until the transaction ends, a concurrent transaction writing it
+    /// conflicts with this one. KipDB's transactions are optimistic, so the lock does not
+    /// block and the conflict surfaces at commit, failing whichever of them commits last.
+    fn lock(&mut self, table_name: &str, tuple_id: TupleId) -> Result<(), StorageError>;
+
+    /// The next id of the hidden primary key of a table declared without one, or of its
+    /// identity column.
+    fn next_rowid(&mut self, table_name: &str) -> Result<u64, StorageError>;
+
+    /// Gives out the ids of the hidden primary key and of the identity column from the first
+    /// one again.
+    fn reset_rowid(&mut self, table_name: &str) -> Result<(), StorageError>;
+
+    /// The ids up to `rowid` are not given out anymore, e.g. once it is written to the
+    /// identity column by `OVERRIDING SYSTEM VALUE`.
+    fn skip_rowid(&mut self, table_name: &str, rowid: u64) -> Result<(), StorageError>;
+
     fn create_table(
         &mut self,
         table_name: TableName,
         columns: Vec<ColumnCatalog>,
+        unique_keys: Vec<Vec<String>>,
         if_not_exists: bool,
     ) -> Result<TableName, StorageError>;
 
+    /// Adds the index to the table, whose id is given by the table, and fills it with the
+    /// entries of the tuples already in the table.
+    fn create_index(
+        &mut self,
+        table_name: &str,
+        index_meta: IndexMeta,
+        if_not_exists: bool,
+    ) -> Result<(), StorageError>;
+
     fn drop_table(&mut self, table_name: &str) -> Result<(), StorageError>;
+
+    /// Moves the table with its rows, indexes and comments to `new_table_name`.
+    fn rename_table(&mut self, table_name: &str, new_table_name: &str) -> Result<(), StorageError>;
+
+    /// Renames the column `column_id`, along with the indexes named after it.
+    fn rename_column(
+        &mut self,
+        table_name: &str,
+        column_id: ColumnId,
+        new_column_name: &str,
+    ) -> Result<(), StorageError>;
+
     fn drop_data(&mut self, table_name: &str) -> Result<(), StorageError>;
     fn table(&self, table_name: TableName) -> Option<&TableCatalog>;
 
     fn show_tables(&self) -> Result<Vec<String>, StorageError>;
 
+    /// The number of tuples of the table, counted from their keys without decoding them.
+    fn row_count(&self, table_name: TableName) -> Result<usize, StorageError>;
+
+    /// The indexes of the table along with their size in storage.
+    fn index_stats(
+        &self,
+        table_name: TableName,
+    ) -> Result<Vec<(IndexMetaRef, IndexStats)>, StorageError>;
+
+    /// Called before each statement run in the transaction, it moves the transaction onto
+    /// a new snapshot if its `IsolationLevel` reads a snapshot per statement.
+    #[allow(async_fn_in_trait)]
+    async fn begin_statement(&mut self) -> Result<(), StorageError>;
+
+    /// Applies the writes of the transaction all at once. The commit is cancellation-safe:
+    /// if the future is dropped before it completes, none of the writes are applied.
     #[allow(async_fn_in_trait)]
     async fn commit(self) -> Result<(), StorageError>;
 }
 
+/// The size of an index in storage, the entries of the primary key index are the tuples.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IndexStats {
+    pub entries: usize,
+    /// The bytes of the keys and values of the entries.
+    pub bytes: usize,
+}
+
 enum IndexValue {
     PrimaryKey(Tuple),
     Normal(TupleId),
+    // built from the index entry, the tuple itself is never read
+    Covered(Tuple),
 }
 
 // TODO: Table return optimization
@@ -95,8 +261,14 @@ pub struct IndexIter<'a> {
     offset: usize,
     limit: Option<usize>,
     projections: Projections,
+    schema_ref: SchemaRef,
+
+    // the residual predicate of the scan, see `Iter::set_filter`
+    filter: Option<ScalarExpression>,
 
     index_meta: IndexMetaRef,
+    // the indexed columns followed by the primary key, set for the index-only scan
+    covered_schema_ref: Option<SchemaRef>,
     table: &'a TableCatalog,
     tx: &'a mvcc::Transaction,
 
@@ -104,6 +276,9 @@ pub struct IndexIter<'a> {
     index_values: VecDeque<IndexValue>,
     binaries: VecDeque<ConstantBinary>,
     scope_iter: Option<mvcc::TransactionIter<'a>>,
+    // the keys of the tuples already visited, set when the ranges of `binaries` may overlap
+    // so a tuple in several of them is returned once
+    seen_keys: Option<HashSet<Vec<u8>>>,
 }
 
 impl IndexIter<'_> {
@@ -118,26 +293,97 @@ impl IndexIter<'_> {
     }
 
     fn val_to_key(&self, val: ValueRef) -> Result<Vec<u8>, TypeError> {
-        if self.index_meta.is_unique {
+        if !self.index_meta.is_primary {
             let index = Index::new(self.index_meta.id, vec![val]);
 
             TableCodec::encode_index_key(&self.table.name, &index)
         } else {
-            TableCodec::encode_tuple_key(&self.table.name, &val)
+            TableCodec::encode_tuple_key(&self.table.name, &TupleId::Value(val))
         }
     }
 
-    fn get_tuple_by_id(&mut self, tuple_id: &TupleId) -> Result<Option<Tuple>, StorageError> {
-        let key = TableCodec::encode_tuple_key(&self.table.name, &tuple_id)?;
+    /// The range of the keys of a composite index whose leading columns are `values`, and
+    /// whose next column falls in `binary`. The keys of the longer indexes go on after the
+    /// values of the bounds, so a bound on the values of all the keys starting with them is
+    /// the next key in order after all of them.
+    fn prefix_bounds(
+        &self,
+        values: Vec<ValueRef>,
+        binary: ConstantBinary,
+    ) -> Result<(Bound<Vec<u8>>, Bound<Vec<u8>>), TypeError> {
+        let prefix = TableCodec::encode_index_key(
+            &self.table.name,
+            &Index::new(self.index_meta.id, values),
+        )?;
+        let (min, max) = match binary {
+            ConstantBinary::Scope { min, max } => (min, max),
+            ConstantBinary::Eq(val) => (Bound::Included(val.clone()), Bound::Included(val)),
+            _ => unreachable!("the next column of a prefix is bounded by `Scope` or `Eq`"),
+        };
+        let key_of = |val: ValueRef| -> Result<Vec<u8>, TypeError> {
+            let mut key = prefix.clone();
+            val.to_index_key(&mut key)?;
+
+            Ok(key)
+        };
+
+        let encode_min = match min {
+            Bound::Included(val) => Bound::Included(key_of(val)?),
+            Bound::Excluded(val) => Bound::Included(key_successor(key_of(val)?)),
+            Bound::Unbounded => Bound::Included(prefix.clone()),
+        };
+        let encode_max = match max {
+            Bound::Included(val) => Bound::Excluded(key_successor(key_of(val)?)),
+            Bound::Excluded(val) => Bound::Excluded(key_of(val)?),
+            Bound::Unbounded => Bound::Excluded(key_successor(prefix.clone())),
+        };
+
+        Ok((encode_min, encode_max))
+    }
+
+    fn get_tuple_by_id(&self, tuple_id: &TupleId) -> Result<Option<Tuple>, StorageError> {
+        let key = TableCodec::encode_tuple_key(&self.table.name, tuple_id)?;
 
-        self.tx
+        Ok(self
+            .tx
             .get(&key)?
-            .map(|bytes| {
-                let tuple = TableCodec::decode_tuple(self.table.all_columns(), &bytes);
+            .map(|bytes| TableCodec::decode_tuple(self.table.schema_ref().clone(), &bytes)))
+    }
+
+    fn push_index_entry(
+        covered_schema_ref: &Option<SchemaRef>,
+        index_values: &mut VecDeque<IndexValue>,
+        bytes: &[u8],
+    ) -> Result<(), StorageError> {
+        let (tuple_ids, column_values) = TableCodec::decode_index(bytes)?;
+
+        for tuple_id in tuple_ids {
+            let index_value = if let Some(schema_ref) = covered_schema_ref {
+                let mut values = column_values.clone();
+                values.extend(tuple_id.values());
+
+                IndexValue::Covered(Tuple {
+                    id: Some(tuple_id),
+                    schema_ref: schema_ref.clone(),
+                    values,
+                })
+            } else {
+                IndexValue::Normal(tuple_id)
+            };
+            index_values.push_back(index_value);
+        }
 
-                tuple_projection(&mut self.limit, &self.projections, tuple)
-            })
-            .transpose()
+        Ok(())
+    }
+
+    /// Whether the tuple was visited by an earlier range of the scan, it is marked as visited.
+    fn is_seen(&mut self, tuple_id: &TupleId) -> Result<bool, TypeError> {
+        match &mut self.seen_keys {
+            Some(seen_keys) => {
+                Ok(!seen_keys.insert(TableCodec::encode_tuple_key(&self.table.name, tuple_id)?))
+            }
+            None => Ok(false),
+        }
     }
 
     fn is_empty(&self) -> bool {
@@ -155,26 +401,36 @@ impl Iter for IndexIter<'_> {
             return Ok(None);
         }
         // 2. try get tuple on index_values and until it empty
-        loop {
-            if let Some(value) = self.index_values.pop_front() {
-                if Self::offset_move(&mut self.offset) {
+        while let Some(value) = self.index_values.pop_front() {
+            let tuple_id = match &value {
+                IndexValue::PrimaryKey(tuple) | IndexValue::Covered(tuple) => tuple.id.as_ref(),
+                IndexValue::Normal(tuple_id) => Some(tuple_id),
+            };
+            if let Some(tuple_id) = tuple_id {
+                if self.is_seen(tuple_id)? {
                     continue;
                 }
-                match value {
-                    IndexValue::PrimaryKey(tuple) => {
-                        let tuple = tuple_projection(&mut self.limit, &self.projections, tuple)?;
-
-                        return Ok(Some(tuple));
-                    }
-                    IndexValue::Normal(tuple_id) => {
-                        if let Some(tuple) = self.get_tuple_by_id(&tuple_id)? {
-                            return Ok(Some(tuple));
-                        }
-                    }
-                }
-            } else {
-                break;
             }
+            // the offset counts the tuples passing the filter, which are read to know it
+            if self.filter.is_none() && Self::offset_move(&mut self.offset) {
+                continue;
+            }
+            let tuple = match value {
+                IndexValue::PrimaryKey(tuple) | IndexValue::Covered(tuple) => tuple,
+                IndexValue::Normal(tuple_id) => match self.get_tuple_by_id(&tuple_id)? {
+                    Some(tuple) => tuple,
+                    None => continue,
+                },
+            };
+            if self.filter.is_some()
+                && (!is_filtered_in(&self.filter, &tuple)? || Self::offset_move(&mut self.offset))
+            {
+                continue;
+            }
+            let tuple =
+                tuple_projection(&mut self.limit, &self.projections, &self.schema_ref, tuple)?;
+
+            return Ok(Some(tuple));
         }
         assert!(self.index_values.is_empty());
 
@@ -185,13 +441,16 @@ impl Iter for IndexIter<'_> {
             while let Some((_, value_option)) = iter.try_next()? {
                 if let Some(value) = value_option {
                     if self.index_meta.is_primary {
-                        let tuple = TableCodec::decode_tuple(self.table.all_columns(), &value);
+                        let tuple =
+                            TableCodec::decode_tuple(self.table.schema_ref().clone(), &value);
 
                         self.index_values.push_back(IndexValue::PrimaryKey(tuple));
                     } else {
-                        for tuple_id in TableCodec::decode_index(&value)? {
-                            self.index_values.push_back(IndexValue::Normal(tuple_id));
-                        }
+                        Self::push_index_entry(
+                            &self.covered_schema_ref,
+                            &mut self.index_values,
+                            &value,
+                        )?;
                     }
                     has_next = true;
                     break;
@@ -222,7 +481,7 @@ impl Iter for IndexIter<'_> {
                             let _ = mem::replace(value, Bound::Included(bound));
                         }
                     };
-                    let (bound_min, bound_max) = if index_meta.is_unique {
+                    let (bound_min, bound_max) = if !index_meta.is_primary {
                         TableCodec::index_bound(table_name, &index_meta.id)
                     } else {
                         TableCodec::tuple_bound(table_name)
@@ -240,19 +499,29 @@ impl Iter for IndexIter<'_> {
                     )?;
                     self.scope_iter = Some(iter);
                 }
+                ConstantBinary::Prefix { values, binary } => {
+                    let (encode_min, encode_max) = self.prefix_bounds(values, *binary)?;
+
+                    let iter = self.tx.iter(
+                        encode_min.as_ref().map(Vec::as_slice),
+                        encode_max.as_ref().map(Vec::as_slice),
+                    )?;
+                    self.scope_iter = Some(iter);
+                }
                 ConstantBinary::Eq(val) => {
                     let key = self.val_to_key(val)?;
                     if let Some(bytes) = self.tx.get(&key)? {
-                        if self.index_meta.is_unique {
-                            for tuple_id in TableCodec::decode_index(&bytes)? {
-                                self.index_values.push_back(IndexValue::Normal(tuple_id));
-                            }
-                        } else if self.index_meta.is_primary {
-                            let tuple = TableCodec::decode_tuple(self.table.all_columns(), &bytes);
+                        if self.index_meta.is_primary {
+                            let tuple =
+                                TableCodec::decode_tuple(self.table.schema_ref().clone(), &bytes);
 
                             self.index_values.push_back(IndexValue::PrimaryKey(tuple));
                         } else {
-                            todo!()
+                            Self::push_index_entry(
+                                &self.covered_schema_ref,
+                                &mut self.index_values,
+                                &bytes,
+                            )?;
                         }
                     }
                     self.scope_iter = None;
@@ -262,35 +531,97 @@ impl Iter for IndexIter<'_> {
         }
         self.next_tuple()
     }
+
+    fn set_filter(&mut self, predicate: ScalarExpression) {
+        self.filter = Some(predicate);
+    }
+}
+
+/// The least key after all the keys starting with `key`.
+fn key_successor(mut key: Vec<u8>) -> Vec<u8> {
+    while let Some(byte) = key.pop() {
+        if byte < u8::MAX {
+            key.push(byte + 1);
+            break;
+        }
+    }
+
+    key
 }
 
 pub trait Iter: Sync + Send {
     fn next_tuple(&mut self) -> Result<Option<Tuple>, StorageError>;
+
+    /// Skips the tuples `predicate` is not true for, as a `Filter` over the scan would. It is
+    /// evaluated on the tuples of the table before the projections, and the bounds of the
+    /// reader count only the tuples passing it. Set it before fetching the first tuple.
+    fn set_filter(&mut self, predicate: ScalarExpression);
+
+    /// Fetch at most `size` tuples at once, an empty batch means the iterator is exhausted.
+    ///
+    /// It returns the same tuples as calling `next_tuple` repeatedly.
+    fn next_batch(&mut self, size: usize) -> Result<Vec<Tuple>, StorageError> {
+        let mut tuples = Vec::with_capacity(size);
+
+        while tuples.len() < size {
+            match self.next_tuple()? {
+                Some(tuple) => tuples.push(tuple),
+                None => break,
+            }
+        }
+
+        Ok(tuples)
+    }
+}
+
+pub(crate) fn projection_schema(projections: &Projections) -> SchemaRef {
+    Arc::new(
+        projections
+            .iter()
+            .map(ScalarExpression::output_columns)
+            .collect(),
+    )
+}
+
+/// The projections evaluated over the tuples of a table, the name of an alias is not looked
+/// up in them as it may be one of their columns, e.g. `select a + 1 as b, b from t`.
+pub(crate) fn unpack_aliases(projections: Projections) -> Projections {
+    projections
+        .into_iter()
+        .map(|expr| match expr {
+            ScalarExpression::Alias { .. } => expr.unpack_alias().clone(),
+            expr => expr,
+        })
+        .collect()
+}
+
+/// Whether the tuple passes the residual predicate of a scan, only TRUE does.
+pub(crate) fn is_filtered_in(
+    filter: &Option<ScalarExpression>,
+    tuple: &Tuple,
+) -> Result<bool, StorageError> {
+    let Some(predicate) = filter else {
+        return Ok(true);
+    };
+
+    match predicate.eval(tuple, &tuple.schema_ref)?.as_ref() {
+        DataValue::Boolean(Some(true)) => Ok(true),
+        DataValue::Boolean(_) | DataValue::Null => Ok(false),
+        _ => Err(TypeError::InvalidType.into()),
+    }
 }
 
 pub(crate) fn tuple_projection(
     limit: &mut Option<usize>,
     projections: &Projections,
+    schema_ref: &SchemaRef,
     tuple: Tuple,
 ) -> Result<Tuple, StorageError> {
-    let projection_len = projections.len();
-    let mut columns = Vec::with_capacity(projection_len);
-    let mut values = Vec::with_capacity(projection_len);
-
-    for expr in projections.iter() {
-        values.push(expr.eval(&tuple)?);
-        columns.push(expr.output_columns());
-    }
-
     if let Some(num) = limit {
         num.sub_assign(1);
     }
 
-    Ok(Tuple {
-        id: tuple.id,
-        columns,
-        values,
-    })
+    Ok(tuple.project(projections, schema_ref)?)
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -315,6 +646,17 @@ pub enum StorageError {
 
     #[error("The table already exists")]
     TableExists,
+
+    /// Another transaction committed a write to a key this one also wrote since it began,
+    /// the transaction is aborted and can be retried. KipDB does not tell which key it was.
+    #[error("The transaction conflicts with a concurrent write and was aborted, retry it")]
+    TransactionConflict,
+
+    #[error("The data files were written with codec version {found}, but this build reads versions {} to {expected}", MIN_CODEC_VERSION)]
+    CodecVersionMismatch { found: u32, expected: u32 },
+
+    #[error("The transaction is read-only and cannot write")]
+    ReadOnly,
 }
 
 impl From<KernelError> for StorageError {
@@ -1,7 +1,9 @@
-use crate::catalog::{ColumnCatalog, ColumnRef};
+use crate::catalog::{ColumnCatalog, ColumnDesc, ColumnSummary, GeneratedExpr};
 use crate::types::errors::TypeError;
 use crate::types::index::{Index, IndexId, IndexMeta};
-use crate::types::tuple::{Tuple, TupleId};
+use crate::types::tuple::{SchemaRef, Tuple, TupleId};
+use crate::types::value::ValueRef;
+use crate::types::{ColumnId, LogicalType};
 use bytes::Bytes;
 use lazy_static::lazy_static;
 
@@ -9,8 +11,24 @@ const BOUND_MIN_TAG: u8 = 0;
 const BOUND_MAX_TAG: u8 = 1;
 lazy_static! {
     static ref ROOT_BYTES: Vec<u8> = b"Root".to_vec();
+    static ref CODEC_VERSION_BYTES: Vec<u8> = b"CodecVersion".to_vec();
 }
 
+/// The version of the layout of keys and values, bumped on every incompatible change
+/// so a data directory written by a later version is refused instead of misread.
+///
+/// - 1: floats encoded little endian
+/// - 2: tuple ids of composite primary keys and of hidden rowids
+/// - 3: identity columns, the columns are decoded by `TableCodec::decode_column`
+///   from version 2 as well
+/// - 4: expression indexes, the index metas are decoded by `TableCodec::decode_index_meta`
+///   from the earlier versions as well
+pub const CODEC_VERSION: u32 = 4;
+
+/// The oldest version whose tables are still decoded, the keys of the tuples were
+/// different in version 1.
+pub const MIN_CODEC_VERSION: u32 = 2;
+
 #[derive(Clone)]
 pub struct TableCodec {}
 
@@ -20,6 +38,11 @@ enum CodecType {
     IndexMeta,
     Index,
     Tuple,
+    RowId,
+    Comment,
+    Version,
+    OnUpdate,
+    DefaultExpr,
     Root,
 }
 
@@ -43,6 +66,21 @@ impl TableCodec {
             CodecType::Tuple => {
                 table_bytes.push(b'3');
             }
+            CodecType::RowId => {
+                table_bytes.push(b'4');
+            }
+            CodecType::Comment => {
+                table_bytes.push(b'5');
+            }
+            CodecType::Version => {
+                table_bytes.push(b'6');
+            }
+            CodecType::OnUpdate => {
+                table_bytes.push(b'7');
+            }
+            CodecType::DefaultExpr => {
+                table_bytes.push(b'8');
+            }
             CodecType::Root => {
                 let mut bytes = ROOT_BYTES.clone();
                 bytes.push(BOUND_MIN_TAG);
@@ -123,6 +161,39 @@ impl TableCodec {
         (op(BOUND_MIN_TAG), op(BOUND_MAX_TAG))
     }
 
+    pub fn comments_bound(table_name: &str) -> (Vec<u8>, Vec<u8>) {
+        let op = |bound_id| {
+            let mut key_prefix = Self::key_prefix(CodecType::Comment, table_name);
+
+            key_prefix.push(bound_id);
+            key_prefix
+        };
+
+        (op(BOUND_MIN_TAG), op(BOUND_MAX_TAG))
+    }
+
+    pub fn on_updates_bound(table_name: &str) -> (Vec<u8>, Vec<u8>) {
+        let op = |bound_id| {
+            let mut key_prefix = Self::key_prefix(CodecType::OnUpdate, table_name);
+
+            key_prefix.push(bound_id);
+            key_prefix
+        };
+
+        (op(BOUND_MIN_TAG), op(BOUND_MAX_TAG))
+    }
+
+    pub fn default_exprs_bound(table_name: &str) -> (Vec<u8>, Vec<u8>) {
+        let op = |bound_id| {
+            let mut key_prefix = Self::key_prefix(CodecType::DefaultExpr, table_name);
+
+            key_prefix.push(bound_id);
+            key_prefix
+        };
+
+        (op(BOUND_MIN_TAG), op(BOUND_MAX_TAG))
+    }
+
     /// Key: TableName_Tuple_0_RowID(Sorted)
     /// Value: Tuple
     pub fn encode_tuple(table_name: &str, tuple: &Tuple) -> Result<(Bytes, Bytes), TypeError> {
@@ -141,8 +212,8 @@ impl TableCodec {
         Ok(key_prefix)
     }
 
-    pub fn decode_tuple(columns: Vec<ColumnRef>, bytes: &[u8]) -> Tuple {
-        Tuple::deserialize_from(columns, bytes)
+    pub fn decode_tuple(schema_ref: SchemaRef, bytes: &[u8]) -> Tuple {
+        Tuple::deserialize_from(schema_ref, bytes)
     }
 
     /// Key: TableName_IndexMeta_0_IndexID
@@ -161,20 +232,36 @@ impl TableCodec {
         ))
     }
 
-    pub fn decode_index_meta(bytes: &[u8]) -> Result<IndexMeta, TypeError> {
-        Ok(bincode::deserialize(bytes)?)
+    /// Decodes an index meta of a table written with codec `version`, the index metas
+    /// before version 4 are the same without the expression. The indexes created on such
+    /// a table since have no expression, and the trailing tag of it is ignored.
+    pub fn decode_index_meta(bytes: &[u8], version: u32) -> Result<IndexMeta, TypeError> {
+        if version >= 4 {
+            return Ok(bincode::deserialize(bytes)?);
+        }
+        let (id, column_ids, name, is_unique, is_primary) = bincode::deserialize(bytes)?;
+
+        Ok(IndexMeta {
+            id,
+            column_ids,
+            name,
+            is_unique,
+            is_primary,
+            expr: None,
+        })
     }
 
     /// NonUnique Index:
     /// Key: TableName_Index_0_IndexID_0_DataValue1_DataValue2 ..
-    /// Value: TupleIDs
+    /// Value: TupleIDs + DataValues
     ///
     /// Unique Index:
     /// Key: TableName_Index_0_IndexID_0_DataValue
-    /// Value: TupleIDs
+    /// Value: TupleIDs + DataValues
     ///
     /// Tips: The unique index has only one ColumnID and one corresponding DataValue,
     /// so it can be positioned directly.
+    /// The DataValues are kept in the value so that a covering index needs no tuple lookup.
     pub fn encode_index(
         name: &str,
         index: &Index,
@@ -184,7 +271,7 @@ impl TableCodec {
 
         Ok((
             Bytes::from(key),
-            Bytes::from(bincode::serialize(tuple_ids)?),
+            Bytes::from(bincode::serialize(&(tuple_ids, &index.column_values))?),
         ))
     }
 
@@ -201,7 +288,7 @@ impl TableCodec {
         Ok(key_prefix)
     }
 
-    pub fn decode_index(bytes: &[u8]) -> Result<Vec<TupleId>, TypeError> {
+    pub fn decode_index(bytes: &[u8]) -> Result<(Vec<TupleId>, Vec<ValueRef>), TypeError> {
         Ok(bincode::deserialize(bytes)?)
     }
 
@@ -222,8 +309,165 @@ impl TableCodec {
         Ok((Bytes::from(key_prefix), Bytes::from(bytes)))
     }
 
-    pub fn decode_column(bytes: &[u8]) -> Result<ColumnCatalog, TypeError> {
-        Ok(bincode::deserialize::<ColumnCatalog>(bytes)?)
+    /// Decodes a column of a table written with codec `version`, which is between
+    /// `MIN_CODEC_VERSION` and `CODEC_VERSION`.
+    pub fn decode_column(bytes: &[u8], version: u32) -> Result<ColumnCatalog, TypeError> {
+        match version {
+            2 => Self::decode_column_v2(bytes),
+            3 | CODEC_VERSION => Ok(bincode::deserialize::<ColumnCatalog>(bytes)?),
+            _ => Err(TypeError::InternalError(format!(
+                "columns of codec version {} can not be decoded",
+                version
+            ))),
+        }
+    }
+
+    /// A column of version 2 is the same without the identity of `ColumnDesc`, but its
+    /// expressions hold columns of version 2 as well, so the generated columns are refused.
+    fn decode_column_v2(mut bytes: &[u8]) -> Result<ColumnCatalog, TypeError> {
+        let (summary, nullable, column_datatype, is_primary, is_unique, default, is_hidden): (
+            ColumnSummary,
+            bool,
+            LogicalType,
+            bool,
+            bool,
+            Option<ValueRef>,
+            bool,
+        ) = bincode::deserialize_from(&mut bytes)?;
+        // the tags of `generated` and of `ref_expr`, `None` is 0
+        if bytes != [0, 0] {
+            return Err(TypeError::InternalError(format!(
+                "the generated column {} of codec version 2 can not be decoded",
+                summary.name
+            )));
+        }
+
+        Ok(ColumnCatalog {
+            summary,
+            nullable,
+            desc: ColumnDesc {
+                column_datatype,
+                is_primary,
+                is_unique,
+                default,
+                is_hidden,
+                generated: None,
+                identity: None,
+                comment: None,
+                on_update: None,
+                default_expr: None,
+            },
+            ref_expr: None,
+        })
+    }
+
+    /// Key: TableName_Comment_0 for the table, TableName_Comment_0_ColumnId for a column
+    /// Value: Comment
+    pub fn encode_comment(
+        table_name: &str,
+        column_id: Option<ColumnId>,
+        comment: &str,
+    ) -> (Bytes, Bytes) {
+        (
+            Bytes::from(Self::encode_comment_key(table_name, column_id)),
+            Bytes::from(comment.to_string().into_bytes()),
+        )
+    }
+
+    pub fn encode_comment_key(table_name: &str, column_id: Option<ColumnId>) -> Vec<u8> {
+        let mut key_prefix = Self::key_prefix(CodecType::Comment, table_name);
+
+        key_prefix.push(BOUND_MIN_TAG);
+        if let Some(column_id) = column_id {
+            key_prefix.append(&mut column_id.to_be_bytes().to_vec());
+        }
+        key_prefix
+    }
+
+    /// The column of the comment, `None` for the comment of the table, and the comment.
+    pub fn decode_comment(
+        table_name: &str,
+        key: &[u8],
+        value: &[u8],
+    ) -> Result<(Option<ColumnId>, String), TypeError> {
+        let column_id = match &key[table_name.len() + 2..] {
+            [] => None,
+            bytes => Some(ColumnId::from_be_bytes(
+                bytes.try_into().map_err(|_| TypeError::InvalidType)?,
+            )),
+        };
+
+        Ok((column_id, String::from_utf8(value.to_vec())?))
+    }
+
+    /// Key: TableName_OnUpdate_0_ColumnId
+    /// Value: GeneratedExpr
+    ///
+    /// Tips: kept out of `ColumnCatalog` so the columns of the earlier versions decode the same
+    pub fn encode_on_update(
+        table_name: &str,
+        column_id: ColumnId,
+        on_update: &GeneratedExpr,
+    ) -> Result<(Bytes, Bytes), TypeError> {
+        let mut key_prefix = Self::key_prefix(CodecType::OnUpdate, table_name);
+
+        key_prefix.push(BOUND_MIN_TAG);
+        key_prefix.append(&mut column_id.to_be_bytes().to_vec());
+
+        Ok((
+            Bytes::from(key_prefix),
+            Bytes::from(bincode::serialize(on_update)?),
+        ))
+    }
+
+    /// The column of the `ON UPDATE` expression and the expression.
+    pub fn decode_on_update(
+        table_name: &str,
+        key: &[u8],
+        value: &[u8],
+    ) -> Result<(ColumnId, GeneratedExpr), TypeError> {
+        let column_id = ColumnId::from_be_bytes(
+            key[table_name.len() + 2..]
+                .try_into()
+                .map_err(|_| TypeError::InvalidType)?,
+        );
+
+        Ok((column_id, bincode::deserialize(value)?))
+    }
+
+    /// Key: TableName_DefaultExpr_0_ColumnId
+    /// Value: GeneratedExpr
+    ///
+    /// Tips: kept out of `ColumnCatalog` so the columns of the earlier versions decode the same
+    pub fn encode_default_expr(
+        table_name: &str,
+        column_id: ColumnId,
+        default_expr: &GeneratedExpr,
+    ) -> Result<(Bytes, Bytes), TypeError> {
+        let mut key_prefix = Self::key_prefix(CodecType::DefaultExpr, table_name);
+
+        key_prefix.push(BOUND_MIN_TAG);
+        key_prefix.append(&mut column_id.to_be_bytes().to_vec());
+
+        Ok((
+            Bytes::from(key_prefix),
+            Bytes::from(bincode::serialize(default_expr)?),
+        ))
+    }
+
+    /// The column of the `DEFAULT` expression and the expression.
+    pub fn decode_default_expr(
+        table_name: &str,
+        key: &[u8],
+        value: &[u8],
+    ) -> Result<(ColumnId, GeneratedExpr), TypeError> {
+        let column_id = ColumnId::from_be_bytes(
+            key[table_name.len() + 2..]
+                .try_into()
+                .map_err(|_| TypeError::InvalidType)?,
+        );
+
+        Ok((column_id, bincode::deserialize(value)?))
     }
 
     /// Key: RootCatalog_0_TableName
@@ -237,6 +481,28 @@ impl TableCodec {
         ))
     }
 
+    /// Key: TableName_RowId
+    /// Value: the last rowid given out
+    pub fn encode_rowid_key(table_name: &str) -> Vec<u8> {
+        Self::key_prefix(CodecType::RowId, table_name)
+    }
+
+    /// Key: TableName_Version
+    /// Value: the codec version(u32, big endian) the table is written with
+    ///
+    /// Tips: the tables created before the versions of the tables have none,
+    /// `KipStorage` gives them one
+    pub fn encode_table_version(table_name: &str, version: u32) -> (Bytes, Bytes) {
+        (
+            Bytes::from(Self::encode_table_version_key(table_name)),
+            Bytes::from(version.to_be_bytes().to_vec()),
+        )
+    }
+
+    pub fn encode_table_version_key(table_name: &str) -> Vec<u8> {
+        Self::key_prefix(CodecType::Version, table_name)
+    }
+
     pub fn encode_root_table_key(table_name: &str) -> Vec<u8> {
         Self::key_prefix(CodecType::Root, table_name)
     }
@@ -244,16 +510,37 @@ impl TableCodec {
     pub fn decode_root_table(bytes: &[u8]) -> Result<String, TypeError> {
         Ok(String::from_utf8(bytes.to_vec())?)
     }
+
+    /// Key: CodecVersion
+    /// Value: CODEC_VERSION(u32, big endian)
+    ///
+    /// Tips: table names are lower case, so the key never collides with the keys of a table
+    pub fn encode_codec_version() -> (Bytes, Bytes) {
+        (
+            Bytes::from(CODEC_VERSION_BYTES.clone()),
+            Bytes::from(CODEC_VERSION.to_be_bytes().to_vec()),
+        )
+    }
+
+    pub fn codec_version_key() -> &'static [u8] {
+        &CODEC_VERSION_BYTES
+    }
+
+    pub fn decode_codec_version(bytes: &[u8]) -> Result<u32, TypeError> {
+        Ok(u32::from_be_bytes(
+            bytes.try_into().map_err(|_| TypeError::InvalidType)?,
+        ))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::catalog::{ColumnCatalog, ColumnDesc, TableCatalog};
-    use crate::storage::table_codec::TableCodec;
+    use crate::storage::table_codec::{TableCodec, CODEC_VERSION};
     use crate::types::errors::TypeError;
     use crate::types::index::{Index, IndexMeta};
-    use crate::types::tuple::Tuple;
-    use crate::types::value::DataValue;
+    use crate::types::tuple::{Tuple, TupleId};
+    use crate::types::value::{DataValue, ValueRef};
     use crate::types::LogicalType;
     use bytes::Bytes;
     use itertools::Itertools;
@@ -285,23 +572,142 @@ mod tests {
         let table_catalog = build_table_codec();
 
         let tuple = Tuple {
-            id: Some(Arc::new(DataValue::Int32(Some(0)))),
-            columns: table_catalog.all_columns(),
+            id: Some(TupleId::Value(ValueRef::new(DataValue::Int32(Some(0))))),
+            schema_ref: table_catalog.schema_ref().clone(),
             values: vec![
-                Arc::new(DataValue::Int32(Some(0))),
-                Arc::new(DataValue::Decimal(Some(Decimal::new(1, 0)))),
+                ValueRef::new(DataValue::Int32(Some(0))),
+                ValueRef::new(DataValue::Decimal(Some(Decimal::new(1, 0)))),
             ],
         };
         let (_, bytes) = TableCodec::encode_tuple(&table_catalog.name, &tuple)?;
 
         assert_eq!(
-            TableCodec::decode_tuple(table_catalog.all_columns(), &bytes),
+            TableCodec::decode_tuple(table_catalog.schema_ref().clone(), &bytes),
             tuple
         );
 
         Ok(())
     }
 
+    #[test]
+    fn test_table_codec_composite_tuple() -> Result<(), TypeError> {
+        let columns = ["c1", "c2", "c3"]
+            .into_iter()
+            .enumerate()
+            .map(|(i, name)| {
+                ColumnCatalog::new(
+                    name.into(),
+                    false,
+                    ColumnDesc::new(LogicalType::Varchar(None), i < 2, false, None),
+                    None,
+                )
+            })
+            .collect_vec();
+        let table_catalog = TableCatalog::new(Arc::new("t1".to_string()), columns).unwrap();
+        let tuple_id = |c1: &str, c2: &str| {
+            TupleId::Composite(vec![
+                ValueRef::new(DataValue::Utf8(Some(c1.to_string()))),
+                ValueRef::new(DataValue::Utf8(Some(c2.to_string()))),
+            ])
+        };
+
+        let mut values = tuple_id("a", "bc").values();
+        values.push(ValueRef::new(DataValue::Utf8(Some("x".to_string()))));
+        let tuple = Tuple {
+            id: Some(tuple_id("a", "bc")),
+            schema_ref: table_catalog.schema_ref().clone(),
+            values,
+        };
+        let (_, bytes) = TableCodec::encode_tuple(&table_catalog.name, &tuple)?;
+        assert_eq!(
+            TableCodec::decode_tuple(table_catalog.schema_ref().clone(), &bytes),
+            tuple
+        );
+
+        let index = Index::new(0, vec![ValueRef::new(DataValue::Int32(Some(1)))]);
+        let tuple_ids = vec![tuple_id("a", "bc"), TupleId::RowId(7)];
+        let (_, bytes) = TableCodec::encode_index(&table_catalog.name, &index, &tuple_ids)?;
+        assert_eq!(
+            TableCodec::decode_index(&bytes)?,
+            (tuple_ids, index.column_values)
+        );
+
+        // the keys are unique and ordered by the values one after another
+        let ids = [
+            tuple_id("a", "bc"),
+            tuple_id("a", "c"),
+            tuple_id("ab", "c"),
+            tuple_id("abcdefghij", ""),
+            tuple_id("abcdefghij", "a"),
+            tuple_id("b", ""),
+        ];
+        let keys = ids
+            .iter()
+            .map(|id| TableCodec::encode_tuple_key(&table_catalog.name, id))
+            .try_collect::<_, Vec<_>, _>()?;
+        assert!(keys.windows(2).all(|pair| pair[0] < pair[1]));
+
+        let (min, max) = TableCodec::tuple_bound(&table_catalog.name);
+        assert!(keys.iter().all(|key| &min < key && key < &max));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_table_codec_fixture() -> Result<(), TypeError> {
+        let columns = [
+            (LogicalType::Integer, true),
+            (LogicalType::Double, false),
+            (LogicalType::Float, false),
+            (LogicalType::Varchar(None), false),
+            (LogicalType::Bigint, false),
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(i, (ty, is_primary))| {
+            ColumnCatalog::new(
+                format!("c{}", i),
+                !is_primary,
+                ColumnDesc::new(ty, is_primary, false, None),
+                None,
+            )
+        })
+        .collect_vec();
+        let table_catalog = TableCatalog::new(Arc::new("t1".to_string()), columns).unwrap();
+
+        // written on a little endian host, the bytes have to read the same on any host
+        let key = [b't', b'1', b'3', 0, 0x80, 0, 0, 0xff];
+        let value = [
+            0x08, // null bits, c4 is null
+            0xff, 0, 0, 0, // c0: 255_i32
+            0, 0, 0, 0, 0, 0, 0xf8, 0x3f, // c1: 1.5_f64
+            0, 0, 0x80, 0xbe, // c2: -0.25_f32
+            3, 0, 0, 0, b'k', b'i', b'p', // c3: len(u32) + "kip"
+        ];
+        let tuple = TableCodec::decode_tuple(table_catalog.schema_ref().clone(), &value);
+
+        assert_eq!(
+            tuple.values,
+            vec![
+                ValueRef::new(DataValue::Int32(Some(255))),
+                ValueRef::new(DataValue::Float64(Some(1.5))),
+                ValueRef::new(DataValue::Float32(Some(-0.25))),
+                ValueRef::new(DataValue::Utf8(Some("kip".to_string()))),
+                ValueRef::new(DataValue::Int64(None)),
+            ]
+        );
+        let (encoded_key, encoded_value) = TableCodec::encode_tuple("t1", &tuple)?;
+        assert_eq!(&encoded_key[..], &key);
+        assert_eq!(&encoded_value[..], &value);
+
+        let (version_key, version) = TableCodec::encode_codec_version();
+        assert_eq!(&version_key[..], TableCodec::codec_version_key());
+        assert_eq!(TableCodec::decode_codec_version(&version)?, CODEC_VERSION);
+        assert!(TableCodec::decode_codec_version(&[1]).is_err());
+
+        Ok(())
+    }
+
     #[test]
     fn test_root_catalog() {
         let table_catalog = build_table_codec();
@@ -320,10 +726,18 @@ mod tests {
             name: "index_1".to_string(),
             is_unique: false,
             is_primary: false,
+            expr: None,
         };
-        let (_, bytes) = TableCodec::encode_index_meta(&"T1".to_string(), &index_meta)?;
+        let (_, bytes) = TableCodec::encode_index_meta("T1", &index_meta)?;
+
+        assert_eq!(
+            TableCodec::decode_index_meta(&bytes, CODEC_VERSION)?,
+            index_meta
+        );
+        // the index metas of version 3 have no expression
+        let bytes = bincode::serialize(&(0_u32, vec![0_u32], "index_1", false, false))?;
 
-        assert_eq!(TableCodec::decode_index_meta(&bytes)?, index_meta);
+        assert_eq!(TableCodec::decode_index_meta(&bytes, 3)?, index_meta);
 
         Ok(())
     }
@@ -334,12 +748,15 @@ mod tests {
 
         let index = Index {
             id: 0,
-            column_values: vec![Arc::new(DataValue::Int32(Some(0)))],
+            column_values: vec![ValueRef::new(DataValue::Int32(Some(0)))],
         };
-        let tuple_ids = vec![Arc::new(DataValue::Int32(Some(0)))];
+        let tuple_ids = vec![TupleId::Value(ValueRef::new(DataValue::Int32(Some(0))))];
         let (_, bytes) = TableCodec::encode_index(&table_catalog.name, &index, &tuple_ids)?;
 
-        assert_eq!(TableCodec::decode_index(&bytes)?, tuple_ids);
+        assert_eq!(
+            TableCodec::decode_index(&bytes)?,
+            (tuple_ids, index.column_values)
+        );
 
         Ok(())
     }
@@ -350,9 +767,10 @@ mod tests {
         let col = table_catalog.all_columns()[0].clone();
 
         let (_, bytes) = TableCodec::encode_column(&table_catalog.name, &col).unwrap();
-        let decode_col = TableCodec::decode_column(&bytes).unwrap();
+        let decode_col = TableCodec::decode_column(&bytes, CODEC_VERSION).unwrap();
 
         assert_eq!(&decode_col, col.as_ref());
+        assert!(TableCodec::decode_column(&bytes, CODEC_VERSION + 1).is_err());
     }
 
     #[test]
@@ -367,13 +785,19 @@ mod tests {
                     is_primary: false,
                     is_unique: false,
                     default: None,
+                    is_hidden: false,
+                    generated: None,
+                    identity: None,
+                    comment: None,
+                    on_update: None,
+                    default_expr: None,
                 },
                 None,
             );
 
             col.summary.id = Some(col_id as u32);
 
-            let (key, _) = TableCodec::encode_column(&table_name.to_string(), &col).unwrap();
+            let (key, _) = TableCodec::encode_column(table_name, &col).unwrap();
             key
         };
 
@@ -415,10 +839,10 @@ mod tests {
                 name: "".to_string(),
                 is_unique: false,
                 is_primary: false,
+                expr: None,
             };
 
-            let (key, _) =
-                TableCodec::encode_index_meta(&table_name.to_string(), &index_meta).unwrap();
+            let (key, _) = TableCodec::encode_index_meta(table_name, &index_meta).unwrap();
             key
         };
 
@@ -434,7 +858,7 @@ mod tests {
         set.insert(op(1, "T2"));
         set.insert(op(2, "T2"));
 
-        let (min, max) = TableCodec::index_meta_bound(&"T1".to_string());
+        let (min, max) = TableCodec::index_meta_bound("T1");
 
         let vec = set
             .range::<Bytes, (Bound<&Bytes>, Bound<&Bytes>)>((
@@ -464,7 +888,7 @@ mod tests {
         let op = |value: DataValue, index_id: usize, table_name: &String| {
             let index = Index {
                 id: index_id as u32,
-                column_values: vec![Arc::new(value)],
+                column_values: vec![ValueRef::new(value)],
             };
 
             TableCodec::encode_index_key(table_name, &index).unwrap()
@@ -518,10 +942,10 @@ mod tests {
         let op = |value: DataValue, index_id: usize, table_name: &str| {
             let index = Index {
                 id: index_id as u32,
-                column_values: vec![Arc::new(value)],
+                column_values: vec![ValueRef::new(value)],
             };
 
-            TableCodec::encode_index_key(&table_name.to_string(), &index).unwrap()
+            TableCodec::encode_index_key(table_name, &index).unwrap()
         };
 
         set.insert(op(DataValue::Int32(Some(0)), 0, "T0"));
@@ -536,7 +960,7 @@ mod tests {
         set.insert(op(DataValue::Int32(Some(1)), 0, "T2"));
         set.insert(op(DataValue::Int32(Some(2)), 0, "T2"));
 
-        let (min, max) = TableCodec::all_index_bound(&"T1".to_string());
+        let (min, max) = TableCodec::all_index_bound("T1");
 
         let vec = set
             .range::<Vec<u8>, (Bound<&Vec<u8>>, Bound<&Vec<u8>>)>((
@@ -556,7 +980,8 @@ mod tests {
     fn test_table_codec_tuple_bound() {
         let mut set = BTreeSet::new();
         let op = |tuple_id: DataValue, table_name: &str| {
-            TableCodec::encode_tuple_key(&table_name.to_string(), &Arc::new(tuple_id)).unwrap()
+            TableCodec::encode_tuple_key(table_name, &TupleId::Value(ValueRef::new(tuple_id)))
+                .unwrap()
         };
 
         set.insert(op(DataValue::Int32(Some(0)), "T0"));
@@ -571,7 +996,7 @@ mod tests {
         set.insert(op(DataValue::Int32(Some(1)), "T2"));
         set.insert(op(DataValue::Int32(Some(2)), "T2"));
 
-        let (min, max) = TableCodec::tuple_bound(&"T1".to_string());
+        let (min, max) = TableCodec::tuple_bound("T1");
 
         let vec = set
             .range::<Vec<u8>, (Bound<&Vec<u8>>, Bound<&Vec<u8>>)>((
@@ -590,7 +1015,7 @@ mod tests {
     #[test]
     fn test_root_codec_name_bound() {
         let mut set = BTreeSet::new();
-        let op = |table_name: &str| TableCodec::encode_root_table_key(&table_name.to_string());
+        let op = |table_name: &str| TableCodec::encode_root_table_key(table_name);
 
         set.insert(b"A".to_vec());
 
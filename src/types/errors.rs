@@ -1,3 +1,4 @@
+use crate::types::LogicalType;
 use chrono::ParseError;
 use std::num::{ParseFloatError, ParseIntError, TryFromIntError};
 use std::str::ParseBoolError;
@@ -19,6 +20,20 @@ pub enum TypeError {
     TooLong,
     #[error("cannot be Null")]
     NotNull,
+    #[error("invalid input for type {ty}: {value:?}, {reason}")]
+    InvalidInput {
+        ty: LogicalType,
+        value: String,
+        reason: String,
+    },
+    #[error("invalid interval: {0}")]
+    InvalidInterval(String),
+    #[error("division by zero")]
+    DivisionByZero,
+    #[error("{0} out of range")]
+    Overflow(LogicalType),
+    #[error("invalid shift amount: {0}")]
+    InvalidShift(i64),
     #[error("try from int")]
     TryFromInt(
         #[source]
@@ -1,3 +1,6 @@
+use crate::expression::ScalarExpression;
+use crate::types::errors::TypeError;
+use crate::types::tuple::Tuple;
 use crate::types::value::ValueRef;
 use crate::types::ColumnId;
 use serde::{Deserialize, Serialize};
@@ -9,10 +12,49 @@ pub type IndexMetaRef = Arc<IndexMeta>;
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
 pub struct IndexMeta {
     pub id: IndexId,
+    /// The indexed columns, or the columns the expression of the index refers to.
     pub column_ids: Vec<ColumnId>,
     pub name: String,
     pub is_unique: bool,
     pub is_primary: bool,
+    /// The expression of `CREATE INDEX idx ON t (lower(a))`, whose value is the key of
+    /// the entries instead of the values of the columns.
+    pub expr: Option<IndexExpr>,
+}
+
+/// The expression an index is built on, it is computed from the row whenever the row is written.
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq)]
+pub struct IndexExpr {
+    /// The expression as written, to show the definition of the index.
+    pub sql: String,
+    pub expr: ScalarExpression,
+}
+
+impl IndexMeta {
+    /// Picks out the values of the indexed columns from the tuple, or the value of the
+    /// expression of the index.
+    ///
+    /// Returns `None` if any of them is null, since null values never collide in a unique index.
+    pub(crate) fn column_values(&self, tuple: &Tuple) -> Result<Option<Vec<ValueRef>>, TypeError> {
+        if let Some(IndexExpr { expr, .. }) = &self.expr {
+            let value = expr.eval(tuple, &tuple.schema_ref)?;
+
+            return Ok((!value.is_null()).then(|| vec![value]));
+        }
+
+        Ok(self
+            .column_ids
+            .iter()
+            .map(|col_id| {
+                tuple
+                    .schema_ref
+                    .iter()
+                    .position(|col| col.id() == Some(*col_id))
+                    .map(|i| tuple.values[i].clone())
+                    .filter(|value| !value.is_null())
+            })
+            .collect())
+    }
 }
 
 pub struct Index {
@@ -0,0 +1,259 @@
+use crate::types::errors::TypeError;
+use chrono::{Duration, Months, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt;
+use std::fmt::Formatter;
+use std::str::FromStr;
+
+const MICROS_PER_SECOND: i64 = 1_000_000;
+const MICROS_PER_MINUTE: i64 = 60 * MICROS_PER_SECOND;
+const MICROS_PER_HOUR: i64 = 60 * MICROS_PER_MINUTE;
+const MICROS_PER_DAY: i64 = 24 * MICROS_PER_HOUR;
+const DAYS_PER_MONTH: i64 = 30;
+
+/// A span of time kept as months, days and microseconds like PostgreSQL,
+/// because neither a month nor a day has a fixed length of microseconds.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Interval {
+    pub months: i32,
+    pub days: i32,
+    pub micros: i64,
+}
+
+impl Interval {
+    pub fn new(months: i32, days: i32, micros: i64) -> Self {
+        Interval {
+            months,
+            days,
+            micros,
+        }
+    }
+
+    /// The interval between two points in time, expressed in days and microseconds.
+    pub fn between(left: &NaiveDateTime, right: &NaiveDateTime) -> Result<Self, TypeError> {
+        let micros = (*left - *right)
+            .num_microseconds()
+            .ok_or_else(|| TypeError::InvalidInterval("interval out of range".to_string()))?;
+
+        Ok(Interval {
+            months: 0,
+            days: i32::try_from(micros / MICROS_PER_DAY)?,
+            micros: micros % MICROS_PER_DAY,
+        })
+    }
+
+    pub fn checked_add(&self, other: &Interval) -> Option<Interval> {
+        Some(Interval {
+            months: self.months.checked_add(other.months)?,
+            days: self.days.checked_add(other.days)?,
+            micros: self.micros.checked_add(other.micros)?,
+        })
+    }
+
+    pub fn checked_neg(&self) -> Option<Interval> {
+        Some(Interval {
+            months: self.months.checked_neg()?,
+            days: self.days.checked_neg()?,
+            micros: self.micros.checked_neg()?,
+        })
+    }
+
+    /// Shifts `date_time` by the interval, months first and then days and microseconds.
+    pub fn add_to(&self, date_time: &NaiveDateTime) -> Result<NaiveDateTime, TypeError> {
+        let out_of_range = || TypeError::InvalidInterval("datetime out of range".to_string());
+        let months = Months::new(self.months.unsigned_abs());
+        let date_time = if self.months >= 0 {
+            date_time.checked_add_months(months)
+        } else {
+            date_time.checked_sub_months(months)
+        }
+        .ok_or_else(out_of_range)?;
+
+        date_time
+            .checked_add_signed(Duration::days(self.days as i64))
+            .and_then(|date_time| date_time.checked_add_signed(Duration::microseconds(self.micros)))
+            .ok_or_else(out_of_range)
+    }
+
+    /// The length used for ordering, assuming 30 days per month as PostgreSQL does.
+    fn normalized_micros(&self) -> i128 {
+        (self.months as i128 * DAYS_PER_MONTH as i128 + self.days as i128) * MICROS_PER_DAY as i128
+            + self.micros as i128
+    }
+
+    fn parse_time(value: &str) -> Result<i64, TypeError> {
+        let invalid = || TypeError::InvalidInterval(value.to_string());
+        let (negative, unsigned) = match value.strip_prefix('-') {
+            Some(unsigned) => (true, unsigned),
+            None => (false, value),
+        };
+        let parts = unsigned.split(':').collect::<Vec<_>>();
+        if parts.len() < 2 || parts.len() > 3 {
+            return Err(invalid());
+        }
+        let hours = i64::from_str(parts[0]).map_err(|_| invalid())?;
+        let minutes = i64::from_str(parts[1]).map_err(|_| invalid())?;
+        let seconds = parts
+            .get(2)
+            .map(|seconds| f64::from_str(seconds).map_err(|_| invalid()))
+            .transpose()?
+            .unwrap_or(0.0);
+        let micros = hours * MICROS_PER_HOUR
+            + minutes * MICROS_PER_MINUTE
+            + (seconds * MICROS_PER_SECOND as f64).round() as i64;
+
+        Ok(if negative { -micros } else { micros })
+    }
+}
+
+impl PartialOrd for Interval {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Interval {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.normalized_micros()
+            .cmp(&other.normalized_micros())
+            .then_with(|| {
+                (self.months, self.days, self.micros).cmp(&(other.months, other.days, other.micros))
+            })
+    }
+}
+
+/// Parses the PostgreSQL style input, e.g. `1 year 2 mons`, `3 days 04:05:06` or `-1 hour`.
+impl FromStr for Interval {
+    type Err = TypeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || TypeError::InvalidInterval(s.to_string());
+        let mut interval = Interval::default();
+        let mut tokens = s.split_whitespace().peekable();
+
+        if tokens.peek().is_none() {
+            return Err(invalid());
+        }
+        while let Some(token) = tokens.next() {
+            if token.contains(':') {
+                interval.micros += Self::parse_time(token)?;
+                continue;
+            }
+            let number = f64::from_str(token).map_err(|_| invalid())?;
+            let unit = tokens.next().ok_or_else(invalid)?.to_lowercase();
+            let whole = || {
+                if number.fract() != 0.0 {
+                    return Err(invalid());
+                }
+                i32::try_from(number as i64).map_err(|_| invalid())
+            };
+
+            match unit.trim_end_matches('s') {
+                "year" => interval.months += whole()? * 12,
+                "mon" | "month" => interval.months += whole()?,
+                "week" => interval.days += whole()? * 7,
+                "day" => interval.days += whole()?,
+                "hour" => interval.micros += (number * MICROS_PER_HOUR as f64) as i64,
+                "min" | "minute" => interval.micros += (number * MICROS_PER_MINUTE as f64) as i64,
+                "sec" | "second" => interval.micros += (number * MICROS_PER_SECOND as f64) as i64,
+                "millisecond" => interval.micros += (number * 1_000.0) as i64,
+                "microsecond" => interval.micros += number as i64,
+                _ => return Err(invalid()),
+            }
+        }
+
+        Ok(interval)
+    }
+}
+
+/// Formats like PostgreSQL, e.g. `1 year 2 mons 3 days 04:05:06`.
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        fn plural(value: i32, unit: &str) -> String {
+            if value.abs() == 1 {
+                format!("{} {}", value, unit)
+            } else {
+                format!("{} {}s", value, unit)
+            }
+        }
+
+        let mut parts = Vec::new();
+        let (years, months) = (self.months / 12, self.months % 12);
+
+        if years != 0 {
+            parts.push(plural(years, "year"));
+        }
+        if months != 0 {
+            parts.push(plural(months, "mon"));
+        }
+        if self.days != 0 {
+            parts.push(plural(self.days, "day"));
+        }
+        if self.micros != 0 || parts.is_empty() {
+            let sign = if self.micros < 0 { "-" } else { "" };
+            let micros = self.micros.unsigned_abs();
+            let hours = micros / MICROS_PER_HOUR as u64;
+            let minutes = micros % MICROS_PER_HOUR as u64 / MICROS_PER_MINUTE as u64;
+            let seconds = micros % MICROS_PER_MINUTE as u64 / MICROS_PER_SECOND as u64;
+            let fraction = micros % MICROS_PER_SECOND as u64;
+            let mut time = format!("{}{:02}:{:02}:{:02}", sign, hours, minutes, seconds);
+
+            if fraction != 0 {
+                time.push_str(format!(".{:06}", fraction).trim_end_matches('0'));
+            }
+            parts.push(time);
+        }
+
+        write!(f, "{}", parts.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::types::errors::TypeError;
+    use crate::types::interval::Interval;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_interval_parse_and_format() -> Result<(), TypeError> {
+        let cases = [
+            ("1 day", Interval::new(0, 1, 0), "1 day"),
+            (
+                "2 days 4 hours",
+                Interval::new(0, 2, 4 * 3_600_000_000),
+                "2 days 04:00:00",
+            ),
+            ("1 year 2 mons", Interval::new(14, 0, 0), "1 year 2 mons"),
+            ("3 weeks", Interval::new(0, 21, 0), "21 days"),
+            (
+                "1 day 01:02:03.5",
+                Interval::new(0, 1, 3_723_500_000),
+                "1 day 01:02:03.5",
+            ),
+            (
+                "-90 minutes",
+                Interval::new(0, 0, -5_400_000_000),
+                "-01:30:00",
+            ),
+            ("0 seconds", Interval::new(0, 0, 0), "00:00:00"),
+        ];
+
+        for (input, interval, output) in cases {
+            assert_eq!(Interval::from_str(input)?, interval);
+            assert_eq!(interval.to_string(), output);
+        }
+        assert!(Interval::from_str("").is_err());
+        assert!(Interval::from_str("1 fortnight").is_err());
+        assert!(Interval::from_str("1.5 days").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_interval_ordering() {
+        assert!(Interval::new(1, 0, 0) > Interval::new(0, 29, 0));
+        assert!(Interval::new(0, 1, 0) > Interval::new(0, 0, 3_600_000_000));
+        assert!(Interval::new(0, 0, -1) < Interval::default());
+    }
+}
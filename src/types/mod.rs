@@ -1,5 +1,6 @@
 pub mod errors;
 pub mod index;
+pub mod interval;
 pub mod tuple;
 pub mod tuple_builder;
 pub mod value;
@@ -8,11 +9,13 @@ use chrono::{NaiveDate, NaiveDateTime};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use std::any::TypeId;
+use std::cmp;
 
 use sqlparser::ast::ExactNumberInfo;
 use strum_macros::AsRefStr;
 
 use crate::types::errors::TypeError;
+use crate::types::interval::Interval;
 
 pub type ColumnId = u32;
 
@@ -40,6 +43,9 @@ pub enum LogicalType {
     DateTime,
     // decimal (precision, scale)
     Decimal(Option<u8>, Option<u8>),
+    Interval,
+    // the strings of a fixed length, padded with spaces up to it
+    Char(u32),
 }
 
 impl LogicalType {
@@ -70,6 +76,8 @@ impl LogicalType {
             Some(LogicalType::Date)
         } else if type_id == TypeId::of::<NaiveDateTime>() {
             Some(LogicalType::DateTime)
+        } else if type_id == TypeId::of::<Interval>() {
+            Some(LogicalType::Interval)
         } else if type_id == TypeId::of::<Decimal>() {
             Some(LogicalType::Decimal(None, None))
         } else if type_id == TypeId::of::<String>() {
@@ -95,10 +103,11 @@ impl LogicalType {
             LogicalType::Float => Some(4),
             LogicalType::Double => Some(8),
             /// Note: The non-fixed length type's raw_len is None e.g. Varchar
-            LogicalType::Varchar(_) => None,
+            LogicalType::Varchar(_) | LogicalType::Char(_) => None,
             LogicalType::Decimal(_, _) => Some(16),
             LogicalType::Date => Some(4),
             LogicalType::DateTime => Some(8),
+            LogicalType::Interval => Some(16),
         }
     }
 
@@ -153,10 +162,41 @@ impl LogicalType {
         )
     }
 
+    pub fn is_integer(&self) -> bool {
+        self.is_signed_numeric() || self.is_unsigned_numeric()
+    }
+
     pub fn is_floating_point_numeric(&self) -> bool {
         matches!(self, LogicalType::Float | LogicalType::Double)
     }
 
+    /// The result type of `+` and `-` between temporal types and intervals,
+    /// `None` if the operands are not temporal arithmetic.
+    pub fn temporal_arithmetic_type(
+        left: &LogicalType,
+        right: &LogicalType,
+        is_minus: bool,
+    ) -> Option<LogicalType> {
+        match (left, right) {
+            (LogicalType::Date | LogicalType::DateTime, LogicalType::Interval) => {
+                Some(LogicalType::DateTime)
+            }
+            (LogicalType::Interval, LogicalType::Date | LogicalType::DateTime) if !is_minus => {
+                Some(LogicalType::DateTime)
+            }
+            (LogicalType::Date, LogicalType::Date)
+            | (LogicalType::DateTime, LogicalType::DateTime)
+            | (LogicalType::Date, LogicalType::DateTime)
+            | (LogicalType::DateTime, LogicalType::Date)
+                if is_minus =>
+            {
+                Some(LogicalType::Interval)
+            }
+            (LogicalType::Interval, LogicalType::Interval) => Some(LogicalType::Interval),
+            _ => None,
+        }
+    }
+
     pub fn max_logical_type(
         left: &LogicalType,
         right: &LogicalType,
@@ -173,6 +213,17 @@ impl LogicalType {
         if left.is_numeric() && right.is_numeric() {
             return LogicalType::combine_numeric_types(left, right);
         }
+        match (left, right) {
+            // the shorter strings are padded to be compared
+            (LogicalType::Char(len), LogicalType::Varchar(_))
+            | (LogicalType::Varchar(_), LogicalType::Char(len)) => {
+                return Ok(LogicalType::Char(*len))
+            }
+            (LogicalType::Char(left_len), LogicalType::Char(right_len)) => {
+                return Ok(LogicalType::Char(cmp::max(*left_len, *right_len)))
+            }
+            _ => {}
+        }
         if matches!(
             (left, right),
             (LogicalType::Date, LogicalType::Varchar(_))
@@ -193,12 +244,39 @@ impl LogicalType {
         ) {
             return Ok(LogicalType::DateTime);
         }
+        if matches!(
+            (left, right),
+            (LogicalType::Interval, LogicalType::Varchar(_))
+                | (LogicalType::Varchar(_), LogicalType::Interval)
+        ) {
+            return Ok(LogicalType::Interval);
+        }
         Err(TypeError::InternalError(format!(
             "can not compare two types: {:?} and {:?}",
             left, right
         )))
     }
 
+    /// The type of the result of arithmetic between `left` and `right`, wide enough for both
+    /// so no result is truncated: integers widen to the larger one, signed and unsigned to a
+    /// signed one holding both, and a float makes it a float. `None` if they are not numeric.
+    pub fn promote(left: &LogicalType, right: &LogicalType) -> Option<LogicalType> {
+        let is_arithmetic =
+            |ty: &LogicalType| ty.is_numeric() || matches!(ty, LogicalType::Decimal(_, _));
+
+        match (left, right) {
+            (LogicalType::SqlNull, LogicalType::SqlNull) => Some(LogicalType::SqlNull),
+            (LogicalType::SqlNull, ty) | (ty, LogicalType::SqlNull) => {
+                is_arithmetic(ty).then_some(*ty)
+            }
+            _ if left == right => is_arithmetic(left).then_some(*left),
+            _ if left.is_numeric() && right.is_numeric() => {
+                LogicalType::combine_numeric_types(left, right).ok()
+            }
+            _ => None,
+        }
+    }
+
     fn combine_numeric_types(
         left: &LogicalType,
         right: &LogicalType,
@@ -206,9 +284,9 @@ impl LogicalType {
         if left == right {
             return Ok(*left);
         }
-        if left.is_signed_numeric() && right.is_unsigned_numeric() {
+        if left.is_unsigned_numeric() && right.is_signed_numeric() {
             // this method is symmetric
-            // arrange it so the left type is smaller
+            // arrange it so the left type is the signed one
             // to limit the number of options we need to check
             return LogicalType::combine_numeric_types(right, left);
         }
@@ -293,9 +371,12 @@ impl LogicalType {
             LogicalType::UBigint => matches!(to, LogicalType::Float | LogicalType::Double),
             LogicalType::Float => matches!(to, LogicalType::Double),
             LogicalType::Double => false,
-            LogicalType::Varchar(_) => false,
+            LogicalType::Varchar(_) | LogicalType::Char(_) => {
+                matches!(to, LogicalType::Varchar(_) | LogicalType::Char(_))
+            }
             LogicalType::Date => matches!(to, LogicalType::DateTime | LogicalType::Varchar(_)),
             LogicalType::DateTime => matches!(to, LogicalType::Date | LogicalType::Varchar(_)),
+            LogicalType::Interval => matches!(to, LogicalType::Varchar(_)),
             LogicalType::Decimal(_, _) => false,
         }
     }
@@ -307,9 +388,15 @@ impl TryFrom<sqlparser::ast::DataType> for LogicalType {
 
     fn try_from(value: sqlparser::ast::DataType) -> Result<Self, Self::Error> {
         match value {
-            sqlparser::ast::DataType::Char(len) | sqlparser::ast::DataType::Varchar(len) => {
+            // `CHAR` alone is `CHAR(1)`
+            sqlparser::ast::DataType::Char(len) | sqlparser::ast::DataType::Character(len) => {
+                Ok(LogicalType::Char(len.map_or(1, |len| len.length as u32)))
+            }
+            sqlparser::ast::DataType::Varchar(len) => {
                 Ok(LogicalType::Varchar(len.map(|len| len.length as u32)))
             }
+            // kept in its text form, e.g. the ones of `gen_random_uuid()`
+            sqlparser::ast::DataType::Uuid => Ok(LogicalType::Varchar(Some(36))),
             sqlparser::ast::DataType::Float(_) => Ok(LogicalType::Float),
             sqlparser::ast::DataType::Double => Ok(LogicalType::Double),
             sqlparser::ast::DataType::TinyInt(_) => Ok(LogicalType::Tinyint),
@@ -324,7 +411,11 @@ impl TryFrom<sqlparser::ast::DataType> for LogicalType {
             sqlparser::ast::DataType::BigInt(_) => Ok(LogicalType::Bigint),
             sqlparser::ast::DataType::UnsignedBigInt(_) => Ok(LogicalType::UBigint),
             sqlparser::ast::DataType::Boolean => Ok(LogicalType::Boolean),
-            sqlparser::ast::DataType::Datetime(_) => Ok(LogicalType::DateTime),
+            sqlparser::ast::DataType::Date => Ok(LogicalType::Date),
+            sqlparser::ast::DataType::Datetime(_) | sqlparser::ast::DataType::Timestamp(_, _) => {
+                Ok(LogicalType::DateTime)
+            }
+            sqlparser::ast::DataType::Interval => Ok(LogicalType::Interval),
             sqlparser::ast::DataType::Decimal(info) => match info {
                 ExactNumberInfo::None => Ok(Self::Decimal(None, None)),
                 ExactNumberInfo::Precision(p) => Ok(Self::Decimal(Some(p as u8), None)),
@@ -339,8 +430,132 @@ impl TryFrom<sqlparser::ast::DataType> for LogicalType {
     }
 }
 
+impl LogicalType {
+    /// The type as written in `CREATE TABLE`.
+    pub fn to_sql(&self) -> String {
+        match self {
+            LogicalType::UTinyint => "TINYINT UNSIGNED".to_string(),
+            LogicalType::USmallint => "SMALLINT UNSIGNED".to_string(),
+            LogicalType::UInteger => "INTEGER UNSIGNED".to_string(),
+            LogicalType::UBigint => "BIGINT UNSIGNED".to_string(),
+            LogicalType::Varchar(Some(len)) => format!("VARCHAR({})", len),
+            LogicalType::Char(len) => format!("CHAR({})", len),
+            LogicalType::Decimal(Some(precision), Some(scale)) => {
+                format!("DECIMAL({}, {})", precision, scale)
+            }
+            LogicalType::Decimal(Some(precision), None) => format!("DECIMAL({})", precision),
+            _ => self.to_string(),
+        }
+    }
+}
+
 impl std::fmt::Display for LogicalType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.as_ref().to_uppercase())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use crate::types::LogicalType;
+    use itertools::Itertools;
+
+    #[test]
+    fn test_promote() {
+        for (left, right, promoted) in [
+            (
+                LogicalType::Integer,
+                LogicalType::Bigint,
+                LogicalType::Bigint,
+            ),
+            (
+                LogicalType::Tinyint,
+                LogicalType::Smallint,
+                LogicalType::Smallint,
+            ),
+            (
+                LogicalType::UTinyint,
+                LogicalType::Integer,
+                LogicalType::Integer,
+            ),
+            (
+                LogicalType::UTinyint,
+                LogicalType::Tinyint,
+                LogicalType::Smallint,
+            ),
+            (
+                LogicalType::UInteger,
+                LogicalType::Integer,
+                LogicalType::Bigint,
+            ),
+            (
+                LogicalType::UBigint,
+                LogicalType::Bigint,
+                LogicalType::Double,
+            ),
+            (
+                LogicalType::UInteger,
+                LogicalType::UBigint,
+                LogicalType::UBigint,
+            ),
+            (LogicalType::Integer, LogicalType::Float, LogicalType::Float),
+            (
+                LogicalType::Bigint,
+                LogicalType::Double,
+                LogicalType::Double,
+            ),
+            (LogicalType::Float, LogicalType::Double, LogicalType::Double),
+            (
+                LogicalType::SqlNull,
+                LogicalType::Integer,
+                LogicalType::Integer,
+            ),
+            (
+                LogicalType::SqlNull,
+                LogicalType::SqlNull,
+                LogicalType::SqlNull,
+            ),
+            (
+                LogicalType::Decimal(None, None),
+                LogicalType::Decimal(None, None),
+                LogicalType::Decimal(None, None),
+            ),
+        ] {
+            assert_eq!(LogicalType::promote(&left, &right), Some(promoted));
+            assert_eq!(LogicalType::promote(&right, &left), Some(promoted));
+        }
+        for (left, right) in [
+            (LogicalType::Decimal(None, None), LogicalType::Integer),
+            (LogicalType::Varchar(None), LogicalType::Integer),
+            (LogicalType::Date, LogicalType::Date),
+            (LogicalType::Boolean, LogicalType::Boolean),
+            (LogicalType::SqlNull, LogicalType::Varchar(None)),
+        ] {
+            assert_eq!(LogicalType::promote(&left, &right), None);
+            assert_eq!(LogicalType::promote(&right, &left), None);
+        }
+
+        // every pair of numeric types widens to a type both of them fit in
+        for (left, right) in LogicalType::numeric()
+            .into_iter()
+            .cartesian_product(LogicalType::numeric())
+        {
+            let promoted = LogicalType::promote(&left, &right).unwrap();
+
+            assert_eq!(LogicalType::promote(&right, &left), Some(promoted));
+            assert!(LogicalType::can_implicit_cast(&left, &promoted));
+            assert!(LogicalType::can_implicit_cast(&right, &promoted));
+        }
+    }
+
+    #[test]
+    fn test_serialized_tags() {
+        // the catalogs on disk hold the tags, new types only ever take the next ones
+        let tag = |ty: LogicalType| bincode::serialize(&ty).unwrap()[..4].to_vec();
+
+        assert_eq!(tag(LogicalType::DateTime), 15u32.to_le_bytes());
+        assert_eq!(tag(LogicalType::Decimal(None, None)), 16u32.to_le_bytes());
+        assert_eq!(tag(LogicalType::Interval), 17u32.to_le_bytes());
+        assert_eq!(tag(LogicalType::Char(1)), 18u32.to_le_bytes());
+    }
+}
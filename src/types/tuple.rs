@@ -1,42 +1,105 @@
-use crate::catalog::ColumnRef;
+use crate::catalog::{ColumnCatalog, ColumnRef};
+use crate::expression::ScalarExpression;
+use crate::types::errors::TypeError;
 use crate::types::value::{DataValue, ValueRef};
 use comfy_table::{Cell, Table};
 use integer_encoding::FixedInt;
 use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
 const BITS_MAX_INDEX: usize = 8;
 
-pub type TupleId = ValueRef;
+/// The key a tuple is stored under in its table, made of its primary key.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum TupleId {
+    /// The value of a primary key of a single column.
+    Value(ValueRef),
+    /// The values of a composite primary key, in the order of its columns.
+    Composite(Vec<ValueRef>),
+    /// The hidden rowid of a table without a primary key.
+    RowId(u64),
+}
+
+impl TupleId {
+    /// The id of a tuple from its primary key columns with their values,
+    /// `None` if there are none.
+    pub fn from_primary_key<'a>(
+        columns: impl IntoIterator<Item = (&'a ColumnCatalog, ValueRef)>,
+    ) -> Option<Self> {
+        let mut columns = columns.into_iter().collect_vec();
+
+        match columns.len() {
+            0 => None,
+            1 => {
+                let (column, value) = columns.remove(0);
+
+                Some(match value.as_ref() {
+                    DataValue::UInt64(Some(rowid)) if column.desc.is_hidden => {
+                        TupleId::RowId(*rowid)
+                    }
+                    _ => TupleId::Value(value),
+                })
+            }
+            _ => Some(TupleId::Composite(
+                columns.into_iter().map(|(_, value)| value).collect(),
+            )),
+        }
+    }
+
+    /// The values of the primary key columns, in their order.
+    pub fn values(&self) -> Vec<ValueRef> {
+        match self {
+            TupleId::Value(value) => vec![value.clone()],
+            TupleId::Composite(values) => values.clone(),
+            TupleId::RowId(rowid) => vec![ValueRef::new(DataValue::UInt64(Some(*rowid)))],
+        }
+    }
+
+    /// Appends the key of the tuple, which keeps the order of the primary key.
+    ///
+    /// The values of a composite key are encoded one after another, each of them is of
+    /// fixed length or ends with a marker, so different keys never have the same encoding.
+    pub fn to_primary_key(&self, b: &mut Vec<u8>) -> Result<(), TypeError> {
+        match self {
+            TupleId::Value(value) => value.to_primary_key(b),
+            TupleId::Composite(values) => {
+                values.iter().try_for_each(|value| value.to_primary_key(b))
+            }
+            TupleId::RowId(rowid) => DataValue::UInt64(Some(*rowid)).to_primary_key(b),
+        }
+    }
+}
+/// The row description shared by all the tuples produced by an operator.
+pub type Schema = Vec<ColumnRef>;
+pub type SchemaRef = Arc<Schema>;
 
 #[derive(Clone, Debug, PartialEq)]
 pub struct Tuple {
     pub id: Option<TupleId>,
-    pub columns: Vec<ColumnRef>,
+    pub schema_ref: SchemaRef,
     pub values: Vec<ValueRef>,
 }
 
 impl Tuple {
-    pub fn deserialize_from(columns: Vec<ColumnRef>, bytes: &[u8]) -> Self {
+    pub fn deserialize_from(schema_ref: SchemaRef, bytes: &[u8]) -> Self {
         fn is_none(bits: u8, i: usize) -> bool {
             bits & (1 << (7 - i)) > 0
         }
 
-        let values_len = columns.len();
+        let values_len = schema_ref.len();
         let mut values = Vec::with_capacity(values_len);
         let bits_len = (values_len + BITS_MAX_INDEX) / BITS_MAX_INDEX;
-        let mut id_option = None;
-
         let mut pos = bits_len;
 
-        for (i, col) in columns.iter().enumerate() {
+        for (i, col) in schema_ref.iter().enumerate() {
             let logic_type = col.datatype();
 
             if is_none(bytes[i / BITS_MAX_INDEX], i % BITS_MAX_INDEX) {
-                values.push(Arc::new(DataValue::none(logic_type)));
+                values.push(ValueRef::new(DataValue::none(logic_type)));
             } else if let Some(len) = logic_type.raw_len() {
                 /// fixed length (e.g.: int)
-                values.push(Arc::new(DataValue::from_raw(
+                values.push(ValueRef::new(DataValue::from_raw(
                     &bytes[pos..pos + len],
                     logic_type,
                 )));
@@ -45,23 +108,94 @@ impl Tuple {
                 /// variable length (e.g.: varchar)
                 let len = u32::decode_fixed(&bytes[pos..pos + 4]) as usize;
                 pos += 4;
-                values.push(Arc::new(DataValue::from_raw(
+                values.push(ValueRef::new(DataValue::from_raw(
                     &bytes[pos..pos + len],
                     logic_type,
                 )));
                 pos += len;
             }
+        }
+        let mut tuple = Tuple {
+            id: None,
+            schema_ref,
+            values,
+        };
+        tuple.id = tuple.primary_key();
+
+        tuple
+    }
+
+    /// The id made of the values of the primary key columns of the tuple.
+    pub(crate) fn primary_key(&self) -> Option<TupleId> {
+        TupleId::from_primary_key(
+            self.schema_ref
+                .iter()
+                .zip(self.values.iter())
+                .filter(|(col, _)| col.desc.is_primary)
+                .map(|(col, value)| (col.as_ref(), value.clone())),
+        )
+    }
+
+    /// The tuple of the values of `exprs` evaluated over this one, laid out as `schema_ref`,
+    /// the output columns of `exprs`. It is still the same row, so it keeps its id.
+    pub(crate) fn project(
+        &self,
+        exprs: &[ScalarExpression],
+        schema_ref: &SchemaRef,
+    ) -> Result<Tuple, TypeError> {
+        let values = exprs
+            .iter()
+            .map(|expr| expr.eval(self, &self.schema_ref))
+            .try_collect()?;
+
+        Ok(Tuple {
+            id: self.id.clone(),
+            schema_ref: schema_ref.clone(),
+            values,
+        })
+    }
+
+    /// Computes the generated columns of a row of the table from its other columns.
+    pub(crate) fn eval_generated_columns(&mut self) -> Result<(), TypeError> {
+        let schema_ref = self.schema_ref.clone();
+
+        for (i, column) in schema_ref.iter().enumerate() {
+            if let Some(generated) = &column.desc.generated {
+                let value = generated.expr.eval(self, &schema_ref)?;
 
-            if col.desc.is_primary {
-                id_option = Some(values[i].clone());
+                if value.is_null() && !column.nullable {
+                    return Err(TypeError::NotNull);
+                }
+                self.values[i] = ValueRef::new(DataValue::clone(&value).cast(column.datatype())?);
             }
         }
 
-        Tuple {
-            id: id_option,
-            columns,
-            values,
+        Ok(())
+    }
+
+    /// Computes the `ON UPDATE` columns of an updated row, but the ones `is_assigned` by it.
+    pub(crate) fn eval_on_update_columns(
+        &mut self,
+        is_assigned: impl Fn(&ColumnCatalog) -> bool,
+    ) -> Result<(), TypeError> {
+        let schema_ref = self.schema_ref.clone();
+
+        for (i, column) in schema_ref.iter().enumerate() {
+            if let Some(on_update) = &column.desc.on_update {
+                if is_assigned(column) {
+                    continue;
+                }
+                let value = on_update.expr.eval(self, &schema_ref)?;
+
+                if value.is_null() && !column.nullable {
+                    return Err(TypeError::NotNull);
+                }
+                value.check_len(column.datatype())?;
+                self.values[i] = ValueRef::new(DataValue::clone(&value).cast(column.datatype())?);
+            }
         }
+
+        Ok(())
     }
 
     /// e.g.: bits(u8)..|data_0(len for utf8_1)|utf8_0|data_1|
@@ -100,7 +234,7 @@ pub fn create_table(tuples: &[Tuple]) -> Table {
     }
 
     let mut header = Vec::new();
-    for col in &tuples[0].columns {
+    for col in tuples[0].schema_ref.iter() {
         header.push(Cell::new(col.name().to_string()));
     }
     table.set_header(header);
@@ -121,15 +255,17 @@ pub fn create_table(tuples: &[Tuple]) -> Table {
 #[cfg(test)]
 mod tests {
     use crate::catalog::{ColumnCatalog, ColumnDesc};
-    use crate::types::tuple::Tuple;
-    use crate::types::value::DataValue;
+    use crate::expression::{BinaryOperator, ScalarExpression};
+    use crate::types::errors::TypeError;
+    use crate::types::tuple::{Tuple, TupleId};
+    use crate::types::value::{DataValue, ValueRef};
     use crate::types::LogicalType;
     use rust_decimal::Decimal;
     use std::sync::Arc;
 
     #[test]
     fn test_tuple_serialize_to_and_deserialize_from() {
-        let columns = vec![
+        let columns = Arc::new(vec![
             Arc::new(ColumnCatalog::new(
                 "c1".to_string(),
                 false,
@@ -208,45 +344,45 @@ mod tests {
                 ColumnDesc::new(LogicalType::Decimal(None, None), false, false, None),
                 None,
             )),
-        ];
+        ]);
 
         let tuples = vec![
             Tuple {
-                id: Some(Arc::new(DataValue::Int32(Some(0)))),
-                columns: columns.clone(),
+                id: Some(TupleId::Value(ValueRef::new(DataValue::Int32(Some(0))))),
+                schema_ref: columns.clone(),
                 values: vec![
-                    Arc::new(DataValue::Int32(Some(0))),
-                    Arc::new(DataValue::UInt32(Some(1))),
-                    Arc::new(DataValue::Utf8(Some("LOL".to_string()))),
-                    Arc::new(DataValue::Int16(Some(1))),
-                    Arc::new(DataValue::UInt16(Some(1))),
-                    Arc::new(DataValue::Float32(Some(0.1))),
-                    Arc::new(DataValue::Float64(Some(0.1))),
-                    Arc::new(DataValue::Int8(Some(1))),
-                    Arc::new(DataValue::UInt8(Some(1))),
-                    Arc::new(DataValue::Boolean(Some(true))),
-                    Arc::new(DataValue::Date64(Some(0))),
-                    Arc::new(DataValue::Date32(Some(0))),
-                    Arc::new(DataValue::Decimal(Some(Decimal::new(0, 3)))),
+                    ValueRef::new(DataValue::Int32(Some(0))),
+                    ValueRef::new(DataValue::UInt32(Some(1))),
+                    ValueRef::new(DataValue::Utf8(Some("LOL".to_string()))),
+                    ValueRef::new(DataValue::Int16(Some(1))),
+                    ValueRef::new(DataValue::UInt16(Some(1))),
+                    ValueRef::new(DataValue::Float32(Some(0.1))),
+                    ValueRef::new(DataValue::Float64(Some(0.1))),
+                    ValueRef::new(DataValue::Int8(Some(1))),
+                    ValueRef::new(DataValue::UInt8(Some(1))),
+                    ValueRef::new(DataValue::Boolean(Some(true))),
+                    ValueRef::new(DataValue::Date64(Some(0))),
+                    ValueRef::new(DataValue::Date32(Some(0))),
+                    ValueRef::new(DataValue::Decimal(Some(Decimal::new(0, 3)))),
                 ],
             },
             Tuple {
-                id: Some(Arc::new(DataValue::Int32(Some(1)))),
-                columns: columns.clone(),
+                id: Some(TupleId::Value(ValueRef::new(DataValue::Int32(Some(1))))),
+                schema_ref: columns.clone(),
                 values: vec![
-                    Arc::new(DataValue::Int32(Some(1))),
-                    Arc::new(DataValue::UInt32(None)),
-                    Arc::new(DataValue::Utf8(None)),
-                    Arc::new(DataValue::Int16(None)),
-                    Arc::new(DataValue::UInt16(None)),
-                    Arc::new(DataValue::Float32(None)),
-                    Arc::new(DataValue::Float64(None)),
-                    Arc::new(DataValue::Int8(None)),
-                    Arc::new(DataValue::UInt8(None)),
-                    Arc::new(DataValue::Boolean(None)),
-                    Arc::new(DataValue::Date64(None)),
-                    Arc::new(DataValue::Date32(None)),
-                    Arc::new(DataValue::Decimal(None)),
+                    ValueRef::new(DataValue::Int32(Some(1))),
+                    ValueRef::new(DataValue::UInt32(None)),
+                    ValueRef::new(DataValue::Utf8(None)),
+                    ValueRef::new(DataValue::Int16(None)),
+                    ValueRef::new(DataValue::UInt16(None)),
+                    ValueRef::new(DataValue::Float32(None)),
+                    ValueRef::new(DataValue::Float64(None)),
+                    ValueRef::new(DataValue::Int8(None)),
+                    ValueRef::new(DataValue::UInt8(None)),
+                    ValueRef::new(DataValue::Boolean(None)),
+                    ValueRef::new(DataValue::Date64(None)),
+                    ValueRef::new(DataValue::Date32(None)),
+                    ValueRef::new(DataValue::Decimal(None)),
                 ],
             },
         ];
@@ -257,4 +393,76 @@ mod tests {
         assert_eq!(tuples[0], tuple_0);
         assert_eq!(tuples[1], tuple_1);
     }
+
+    #[test]
+    fn test_tuple_project() -> Result<(), TypeError> {
+        let columns = Arc::new(vec![
+            Arc::new(ColumnCatalog::new(
+                "c1".to_string(),
+                false,
+                ColumnDesc::new(LogicalType::Integer, true, false, None),
+                None,
+            )),
+            Arc::new(ColumnCatalog::new(
+                "c2".to_string(),
+                true,
+                ColumnDesc::new(LogicalType::Integer, false, false, None),
+                None,
+            )),
+        ]);
+        let tuple = Tuple {
+            id: Some(TupleId::Value(ValueRef::new(DataValue::Int32(Some(1))))),
+            schema_ref: columns.clone(),
+            values: vec![
+                ValueRef::new(DataValue::Int32(Some(1))),
+                ValueRef::new(DataValue::Int32(Some(5))),
+            ],
+        };
+        let c1 = ScalarExpression::ColumnRef(columns[0].clone());
+        let c2 = ScalarExpression::ColumnRef(columns[1].clone());
+        // c2, c1 + c2 as s, 7
+        let exprs = vec![
+            c2.clone(),
+            ScalarExpression::Alias {
+                expr: Box::new(ScalarExpression::Binary {
+                    op: BinaryOperator::Plus,
+                    left_expr: Box::new(c1),
+                    right_expr: Box::new(c2),
+                    ty: LogicalType::Integer,
+                }),
+                alias: "s".to_string(),
+            },
+            ScalarExpression::Constant(ValueRef::new(DataValue::Int32(Some(7)))),
+        ];
+        let schema_ref = Arc::new(exprs.iter().map(ScalarExpression::output_columns).collect());
+
+        let projected = tuple.project(&exprs, &schema_ref)?;
+        assert_eq!(projected.id, tuple.id);
+        assert_eq!(
+            projected
+                .schema_ref
+                .iter()
+                .map(|col| col.name().to_string())
+                .collect::<Vec<_>>(),
+            vec!["c2", "s", "7"]
+        );
+        assert_eq!(
+            projected.values,
+            vec![
+                ValueRef::new(DataValue::Int32(Some(5))),
+                ValueRef::new(DataValue::Int32(Some(6))),
+                ValueRef::new(DataValue::Int32(Some(7))),
+            ]
+        );
+
+        // the projected tuple is projected again by the names of its columns
+        let exprs = vec![ScalarExpression::ColumnRef(projected.schema_ref[1].clone())];
+        let schema_ref = Arc::new(exprs.iter().map(ScalarExpression::output_columns).collect());
+        assert_eq!(
+            projected.project(&exprs, &schema_ref)?.values,
+            vec![ValueRef::new(DataValue::Int32(Some(6)))]
+        );
+
+        Ok(())
+    }
 }
@@ -3,17 +3,20 @@ use chrono::{Datelike, NaiveDate, NaiveDateTime};
 use integer_encoding::FixedInt;
 use lazy_static::lazy_static;
 use rust_decimal::Decimal;
+use std::borrow::Borrow;
 use std::cmp::Ordering;
 use std::fmt::Formatter;
 use std::hash::Hash;
+use std::ops::Deref;
 use std::str::FromStr;
 use std::sync::Arc;
 use std::{fmt, mem};
 
 use crate::types::errors::TypeError;
+use crate::types::interval::Interval;
 use ordered_float::OrderedFloat;
 use rust_decimal::prelude::FromPrimitive;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 use super::LogicalType;
 
@@ -27,7 +30,108 @@ pub const DATE_TIME_FMT: &str = "%Y-%m-%d %H:%M:%S";
 const ENCODE_GROUP_SIZE: usize = 8;
 const ENCODE_MARKER: u8 = 0xFF;
 
-pub type ValueRef = Arc<DataValue>;
+/// A value of a tuple or an expression, cheap to clone like an `Arc<DataValue>`.
+///
+/// The values of a fixed width are held inline, so that a row of them takes no allocation
+/// for each value, only the strings are shared behind an `Arc`.
+#[derive(Clone)]
+pub struct ValueRef(ValueRepr);
+
+#[derive(Clone)]
+enum ValueRepr {
+    Inline(DataValue),
+    Shared(Arc<DataValue>),
+}
+
+impl ValueRef {
+    pub fn new(value: DataValue) -> Self {
+        match value {
+            DataValue::Utf8(Some(_)) => ValueRef(ValueRepr::Shared(Arc::new(value))),
+            value => ValueRef(ValueRepr::Inline(value)),
+        }
+    }
+
+    /// The value, cloned only if it is shared.
+    pub fn into_inner(self) -> DataValue {
+        match self.0 {
+            ValueRepr::Inline(value) => value,
+            ValueRepr::Shared(value) => Arc::unwrap_or_clone(value),
+        }
+    }
+}
+
+impl Deref for ValueRef {
+    type Target = DataValue;
+
+    fn deref(&self) -> &DataValue {
+        match &self.0 {
+            ValueRepr::Inline(value) => value,
+            ValueRepr::Shared(value) => value,
+        }
+    }
+}
+
+impl AsRef<DataValue> for ValueRef {
+    fn as_ref(&self) -> &DataValue {
+        self
+    }
+}
+
+impl Borrow<DataValue> for ValueRef {
+    fn borrow(&self) -> &DataValue {
+        self
+    }
+}
+
+impl From<DataValue> for ValueRef {
+    fn from(value: DataValue) -> Self {
+        ValueRef::new(value)
+    }
+}
+
+impl PartialEq for ValueRef {
+    fn eq(&self, other: &Self) -> bool {
+        DataValue::eq(self, other)
+    }
+}
+
+impl Eq for ValueRef {}
+
+impl PartialOrd for ValueRef {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        DataValue::partial_cmp(self, other)
+    }
+}
+
+impl Hash for ValueRef {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        DataValue::hash(self, state)
+    }
+}
+
+impl Serialize for ValueRef {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        DataValue::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ValueRef {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        DataValue::deserialize(deserializer).map(ValueRef::new)
+    }
+}
+
+impl fmt::Display for ValueRef {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        DataValue::fmt(self, f)
+    }
+}
+
+impl fmt::Debug for ValueRef {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
 
 #[derive(Clone, Serialize, Deserialize)]
 pub enum DataValue {
@@ -49,6 +153,8 @@ pub enum DataValue {
     /// Date stored as a signed 64bit int timestamp since UNIX epoch 1970-01-01
     Date64(Option<i64>),
     Decimal(Option<Decimal>),
+    /// Interval stored as months, days and microseconds
+    Interval(Option<Interval>),
 }
 
 macro_rules! generate_get_option {
@@ -80,6 +186,7 @@ generate_get_option!(DataValue,
     u32 : UInt32(Option<u32>),
     u64 : UInt64(Option<u64>),
     utf8 : Utf8(Option<String>),
+    interval : Interval(Option<Interval>),
     decimal : Decimal(Option<Decimal>)
 );
 
@@ -125,6 +232,8 @@ impl PartialEq for DataValue {
             (Date32(_), _) => false,
             (Date64(v1), Date64(v2)) => v1.eq(v2),
             (Date64(_), _) => false,
+            (Interval(v1), Interval(v2)) => v1.eq(v2),
+            (Interval(_), _) => false,
             (Decimal(v1), Decimal(v2)) => v1.eq(v2),
             (Decimal(_), _) => false,
         }
@@ -173,6 +282,8 @@ impl PartialOrd for DataValue {
             (Date32(_), _) => None,
             (Date64(v1), Date64(v2)) => v1.partial_cmp(v2),
             (Date64(_), _) => None,
+            (Interval(v1), Interval(v2)) => v1.partial_cmp(v2),
+            (Interval(_), _) => None,
             (Decimal(v1), Decimal(v2)) => v1.partial_cmp(v2),
             (Decimal(_), _) => None,
         }
@@ -212,10 +323,42 @@ impl Hash for DataValue {
             Null => 1.hash(state),
             Date32(v) => v.hash(state),
             Date64(v) => v.hash(state),
+            Interval(v) => v.hash(state),
             Decimal(v) => v.hash(state),
         }
     }
 }
+
+/// A value as GROUP BY and DISTINCT tell the rows apart.
+///
+/// Unlike `=`, all NULLs are the same key whatever their type, e.g. `Null` and `Int32(None)`.
+/// All NaNs are the same key too, and `-0.0` is the key of `0.0`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct GroupKey(pub ValueRef);
+
+impl PartialEq for GroupKey {
+    fn eq(&self, other: &Self) -> bool {
+        match (self.0.is_null(), other.0.is_null()) {
+            (true, true) => true,
+            (false, false) => self.0 == other.0,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for GroupKey {}
+
+impl Hash for GroupKey {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        if self.0.is_null() {
+            state.write_u8(0);
+        } else {
+            state.write_u8(1);
+            self.0.hash(state);
+        }
+    }
+}
 macro_rules! varchar_cast {
     ($value:expr, $len:expr) => {
         $value
@@ -254,6 +397,10 @@ impl DataValue {
             (LogicalType::Varchar(Some(len)), DataValue::Utf8(Some(val))) => {
                 val.len() > *len as usize
             }
+            // the spaces past the length are cut off
+            (LogicalType::Char(len), DataValue::Utf8(Some(val))) => {
+                val.trim_end_matches(' ').chars().count() > *len as usize
+            }
             (LogicalType::Decimal(full_len, scale_len), DataValue::Decimal(Some(val))) => {
                 if let Some(len) = full_len {
                     if val.mantissa().ilog10() + 1 > *len as u32 {
@@ -285,6 +432,28 @@ impl DataValue {
         value.and_then(|v| Self::date_time_format(v).map(|fmt| format!("{}", fmt)))
     }
 
+    /// The value as a SQL literal that is cast back to the same value, e.g. `'it''s'`.
+    ///
+    /// Floats, decimals and the integers beyond the `i64` literals are written as strings,
+    /// so that parsing the literal loses no precision.
+    pub fn to_sql(&self) -> String {
+        if self.is_null() {
+            return "null".to_string();
+        }
+        match self {
+            DataValue::Boolean(_)
+            | DataValue::Int8(_)
+            | DataValue::Int16(_)
+            | DataValue::Int32(_)
+            | DataValue::UInt8(_)
+            | DataValue::UInt16(_)
+            | DataValue::UInt32(_) => self.to_string(),
+            DataValue::Int64(Some(v)) if *v > i64::MIN => self.to_string(),
+            DataValue::UInt64(Some(v)) if *v <= i64::MAX as u64 => self.to_string(),
+            _ => format!("'{}'", self.to_string().replace('\'', "''")),
+        }
+    }
+
     pub fn is_variable(&self) -> bool {
         matches!(self, DataValue::Utf8(_))
     }
@@ -306,6 +475,7 @@ impl DataValue {
             DataValue::Utf8(value) => value.is_none(),
             DataValue::Date32(value) => value.is_none(),
             DataValue::Date64(value) => value.is_none(),
+            DataValue::Interval(value) => value.is_none(),
             DataValue::Decimal(value) => value.is_none(),
         }
     }
@@ -325,9 +495,10 @@ impl DataValue {
             LogicalType::UBigint => DataValue::UInt64(None),
             LogicalType::Float => DataValue::Float32(None),
             LogicalType::Double => DataValue::Float64(None),
-            LogicalType::Varchar(_) => DataValue::Utf8(None),
+            LogicalType::Varchar(_) | LogicalType::Char(_) => DataValue::Utf8(None),
             LogicalType::Date => DataValue::Date32(None),
             LogicalType::DateTime => DataValue::Date64(None),
+            LogicalType::Interval => DataValue::Interval(None),
             LogicalType::Decimal(_, _) => DataValue::Decimal(None),
         }
     }
@@ -348,18 +519,21 @@ impl DataValue {
             LogicalType::Float => DataValue::Float32(Some(0.0)),
             LogicalType::Double => DataValue::Float64(Some(0.0)),
             LogicalType::Varchar(_) => DataValue::Utf8(Some("".to_string())),
+            LogicalType::Char(len) => DataValue::Utf8(Some(" ".repeat(*len as usize))),
             LogicalType::Date => DataValue::Date32(Some(UNIX_DATETIME.num_days_from_ce())),
             LogicalType::DateTime => DataValue::Date64(Some(UNIX_DATETIME.timestamp())),
+            LogicalType::Interval => DataValue::Interval(Some(Interval::default())),
             LogicalType::Decimal(_, _) => DataValue::Decimal(Some(Decimal::new(0, 0))),
         }
     }
 
+    /// Little endian whatever the host is, so data files can be moved across platforms.
     pub fn to_raw(&self) -> Vec<u8> {
         match self {
             DataValue::Null => None,
             DataValue::Boolean(v) => v.map(|v| vec![v as u8]),
-            DataValue::Float32(v) => v.map(|v| v.to_ne_bytes().to_vec()),
-            DataValue::Float64(v) => v.map(|v| v.to_ne_bytes().to_vec()),
+            DataValue::Float32(v) => v.map(|v| v.to_le_bytes().to_vec()),
+            DataValue::Float64(v) => v.map(|v| v.to_le_bytes().to_vec()),
             DataValue::Int8(v) => v.map(|v| v.encode_fixed_vec()),
             DataValue::Int16(v) => v.map(|v| v.encode_fixed_vec()),
             DataValue::Int32(v) => v.map(|v| v.encode_fixed_vec()),
@@ -371,6 +545,13 @@ impl DataValue {
             DataValue::Utf8(v) => v.clone().map(|v| v.into_bytes()),
             DataValue::Date32(v) => v.map(|v| v.encode_fixed_vec()),
             DataValue::Date64(v) => v.map(|v| v.encode_fixed_vec()),
+            DataValue::Interval(v) => v.map(|v| {
+                let mut bytes = Vec::with_capacity(16);
+                bytes.extend(v.months.encode_fixed_vec());
+                bytes.extend(v.days.encode_fixed_vec());
+                bytes.extend(v.micros.encode_fixed_vec());
+                bytes
+            }),
             DataValue::Decimal(v) => v.map(|v| v.serialize().to_vec()),
         }
         .unwrap_or(vec![])
@@ -408,14 +589,14 @@ impl DataValue {
             LogicalType::Float => DataValue::Float32((!bytes.is_empty()).then(|| {
                 let mut buf = [0; 4];
                 buf.copy_from_slice(bytes);
-                f32::from_ne_bytes(buf)
+                f32::from_le_bytes(buf)
             })),
             LogicalType::Double => DataValue::Float64((!bytes.is_empty()).then(|| {
                 let mut buf = [0; 8];
                 buf.copy_from_slice(bytes);
-                f64::from_ne_bytes(buf)
+                f64::from_le_bytes(buf)
             })),
-            LogicalType::Varchar(_) => DataValue::Utf8(
+            LogicalType::Varchar(_) | LogicalType::Char(_) => DataValue::Utf8(
                 (!bytes.is_empty()).then(|| String::from_utf8(bytes.to_owned()).unwrap()),
             ),
             LogicalType::Date => {
@@ -424,6 +605,13 @@ impl DataValue {
             LogicalType::DateTime => {
                 DataValue::Date64((!bytes.is_empty()).then(|| i64::decode_fixed(bytes)))
             }
+            LogicalType::Interval => DataValue::Interval((!bytes.is_empty()).then(|| {
+                Interval::new(
+                    i32::decode_fixed(&bytes[0..4]),
+                    i32::decode_fixed(&bytes[4..8]),
+                    i64::decode_fixed(&bytes[8..16]),
+                )
+            })),
             LogicalType::Decimal(_, _) => DataValue::Decimal(
                 (!bytes.is_empty())
                     .then(|| Decimal::deserialize(<[u8; 16]>::try_from(bytes).unwrap())),
@@ -448,6 +636,7 @@ impl DataValue {
             DataValue::Utf8(_) => LogicalType::Varchar(None),
             DataValue::Date32(_) => LogicalType::Date,
             DataValue::Date64(_) => LogicalType::DateTime,
+            DataValue::Interval(_) => LogicalType::Interval,
             DataValue::Decimal(_) => LogicalType::Decimal(None, None),
         }
     }
@@ -469,8 +658,7 @@ impl DataValue {
     // Refer: https://github.com/facebook/mysql-5.6/wiki/MyRocks-record-format#memcomparable-format
     fn encode_bytes(b: &mut Vec<u8>, data: &[u8]) {
         let d_len = data.len();
-        let realloc_size = (d_len / ENCODE_GROUP_SIZE + 1) * (ENCODE_GROUP_SIZE + 1);
-        Self::realloc_bytes(b, realloc_size);
+        b.reserve((d_len / ENCODE_GROUP_SIZE + 1) * (ENCODE_GROUP_SIZE + 1));
 
         let mut idx = 0;
         while idx <= d_len {
@@ -491,15 +679,6 @@ impl DataValue {
         }
     }
 
-    fn realloc_bytes(b: &mut Vec<u8>, size: usize) {
-        let len = b.len();
-
-        if size > len {
-            b.reserve(size - len);
-            b.resize(size, 0);
-        }
-    }
-
     pub fn to_primary_key(&self, b: &mut Vec<u8>) -> Result<(), TypeError> {
         match self {
             DataValue::Int8(Some(v)) => encode_u!(b, *v as u8 ^ 0x80_u8),
@@ -538,11 +717,14 @@ impl DataValue {
             DataValue::UInt32(Some(v)) => encode_u!(b, v),
             DataValue::UInt64(Some(v)) => encode_u!(b, v),
             DataValue::Utf8(Some(v)) => Self::encode_bytes(b, v.as_bytes()),
+            // `false` before `true`, like the nulls of the other types there is no key of null
             DataValue::Boolean(Some(v)) => b.push(if *v { b'1' } else { b'0' }),
+            // `-0.0` is the key of `0.0` and all NaNs are the key of `NaN`, which is after `inf`
             DataValue::Float32(Some(f)) => {
+                let f = if f.is_nan() { f32::NAN } else { *f };
                 let mut u = f.to_bits();
 
-                if *f >= 0_f32 {
+                if f >= 0_f32 || f.is_nan() {
                     u |= 0x80000000_u32;
                 } else {
                     u = !u;
@@ -551,9 +733,10 @@ impl DataValue {
                 encode_u!(b, u);
             }
             DataValue::Float64(Some(f)) => {
+                let f = if f.is_nan() { f64::NAN } else { *f };
                 let mut u = f.to_bits();
 
-                if *f >= 0_f64 {
+                if f >= 0_f64 || f.is_nan() {
                     u |= 0x8000000000000000_u64;
                 } else {
                     u = !u;
@@ -574,7 +757,95 @@ impl DataValue {
         Ok(())
     }
 
+    /// Parses the text of a value of type `ty`, e.g. a field of the files of `COPY FROM`.
+    /// `NULL` in any case is the null of the type. The whitespace around the text is ignored,
+    /// but for the strings which are taken as they are.
+    pub fn try_from_str(s: &str, ty: &LogicalType) -> Result<DataValue, TypeError> {
+        if s.trim().eq_ignore_ascii_case("null") {
+            return match ty {
+                LogicalType::Invalid => Err(TypeError::InvalidType),
+                ty => Ok(DataValue::none(ty)),
+            };
+        }
+
+        Self::parse_str(s, ty)
+    }
+
+    /// The text of a value of type `ty`, never null, which is also the cast of a string to `ty`.
+    fn parse_str(s: &str, ty: &LogicalType) -> Result<DataValue, TypeError> {
+        let invalid = |reason: &dyn fmt::Display| TypeError::InvalidInput {
+            ty: *ty,
+            value: s.to_string(),
+            reason: reason.to_string(),
+        };
+        let text = s.trim();
+
+        Ok(match ty {
+            LogicalType::Invalid => return Err(TypeError::InvalidType),
+            LogicalType::SqlNull => DataValue::Null,
+            LogicalType::Boolean => {
+                let value = match text.to_ascii_lowercase().as_str() {
+                    "true" | "t" | "yes" | "on" | "1" => true,
+                    "false" | "f" | "no" | "off" | "0" => false,
+                    _ => return Err(invalid(&"expected true or false")),
+                };
+
+                DataValue::Boolean(Some(value))
+            }
+            LogicalType::Tinyint => DataValue::Int8(Some(text.parse().map_err(|e| invalid(&e))?)),
+            LogicalType::UTinyint => DataValue::UInt8(Some(text.parse().map_err(|e| invalid(&e))?)),
+            LogicalType::Smallint => DataValue::Int16(Some(text.parse().map_err(|e| invalid(&e))?)),
+            LogicalType::USmallint => {
+                DataValue::UInt16(Some(text.parse().map_err(|e| invalid(&e))?))
+            }
+            LogicalType::Integer => DataValue::Int32(Some(text.parse().map_err(|e| invalid(&e))?)),
+            LogicalType::UInteger => {
+                DataValue::UInt32(Some(text.parse().map_err(|e| invalid(&e))?))
+            }
+            LogicalType::Bigint => DataValue::Int64(Some(text.parse().map_err(|e| invalid(&e))?)),
+            LogicalType::UBigint => DataValue::UInt64(Some(text.parse().map_err(|e| invalid(&e))?)),
+            LogicalType::Float => DataValue::Float32(Some(text.parse().map_err(|e| invalid(&e))?)),
+            LogicalType::Double => DataValue::Float64(Some(text.parse().map_err(|e| invalid(&e))?)),
+            LogicalType::Varchar(len) => {
+                if matches!(len, Some(len) if s.len() > *len as usize) {
+                    return Err(TypeError::TooLong);
+                }
+                DataValue::Utf8(Some(s.to_string()))
+            }
+            LogicalType::Char(len) => DataValue::Utf8(Some(Self::pad_char(s, *len)?)),
+            LogicalType::Date => {
+                let date = NaiveDate::parse_from_str(text, DATE_FMT).map_err(|e| invalid(&e))?;
+
+                DataValue::Date32(Some(date.num_days_from_ce()))
+            }
+            LogicalType::DateTime => {
+                let date_time = NaiveDateTime::parse_from_str(text, DATE_TIME_FMT)
+                    .or_else(|_| {
+                        NaiveDate::parse_from_str(text, DATE_FMT)
+                            .map(|date| date.and_hms_opt(0, 0, 0).unwrap())
+                    })
+                    .map_err(|e| invalid(&e))?;
+
+                DataValue::Date64(Some(date_time.timestamp()))
+            }
+            LogicalType::Interval => DataValue::Interval(Some(Interval::from_str(text)?)),
+            LogicalType::Decimal(_, _) => {
+                DataValue::Decimal(Some(Decimal::from_str(text).map_err(|e| invalid(&e))?))
+            }
+        })
+    }
+
     pub fn cast(self, to: &LogicalType) -> Result<DataValue, TypeError> {
+        if let LogicalType::Char(len) = to {
+            return match self.cast(&LogicalType::Varchar(None))? {
+                DataValue::Utf8(value) => Ok(DataValue::Utf8(
+                    value
+                        .map(|value| Self::pad_char(&value, *len))
+                        .transpose()?,
+                )),
+                _ => unreachable!("a string is cast to a string"),
+            };
+        }
         match self {
             DataValue::Null => match to {
                 LogicalType::Invalid => Err(TypeError::CastFail),
@@ -590,9 +861,10 @@ impl DataValue {
                 LogicalType::UBigint => Ok(DataValue::UInt64(None)),
                 LogicalType::Float => Ok(DataValue::Float32(None)),
                 LogicalType::Double => Ok(DataValue::Float64(None)),
-                LogicalType::Varchar(_) => Ok(DataValue::Utf8(None)),
+                LogicalType::Varchar(_) | LogicalType::Char(_) => Ok(DataValue::Utf8(None)),
                 LogicalType::Date => Ok(DataValue::Date32(None)),
                 LogicalType::DateTime => Ok(DataValue::Date64(None)),
+                LogicalType::Interval => Ok(DataValue::Interval(None)),
                 LogicalType::Decimal(_, _) => Ok(DataValue::Decimal(None)),
             },
             DataValue::Boolean(value) => match to {
@@ -711,6 +983,7 @@ impl DataValue {
                 }
                 LogicalType::Integer => Ok(DataValue::Int32(value)),
                 LogicalType::Bigint => Ok(DataValue::Int64(value.map(|v| v.into()))),
+                LogicalType::Float => Ok(DataValue::Float32(value.map(|v| v as f32))),
                 LogicalType::Double => Ok(DataValue::Float64(value.map(|v| v.into()))),
                 LogicalType::Varchar(len) => varchar_cast!(value, len),
                 LogicalType::Decimal(_, option) => Ok(DataValue::Decimal(value.map(|v| {
@@ -734,6 +1007,8 @@ impl DataValue {
                     Ok(DataValue::UInt64(value.map(u64::try_from).transpose()?))
                 }
                 LogicalType::Bigint => Ok(DataValue::Int64(value)),
+                LogicalType::Float => Ok(DataValue::Float32(value.map(|v| v as f32))),
+                LogicalType::Double => Ok(DataValue::Float64(value.map(|v| v as f64))),
                 LogicalType::Varchar(len) => varchar_cast!(value, len),
                 LogicalType::Decimal(_, option) => Ok(DataValue::Decimal(value.map(|v| {
                     let mut decimal = Decimal::from(v);
@@ -786,6 +1061,7 @@ impl DataValue {
                 LogicalType::UInteger => Ok(DataValue::UInt32(value)),
                 LogicalType::Bigint => Ok(DataValue::Int64(value.map(|v| v.into()))),
                 LogicalType::UBigint => Ok(DataValue::UInt64(value.map(|v| v.into()))),
+                LogicalType::Float => Ok(DataValue::Float32(value.map(|v| v as f32))),
                 LogicalType::Double => Ok(DataValue::Float64(value.map(|v| v.into()))),
                 LogicalType::Varchar(len) => varchar_cast!(value, len),
                 LogicalType::Decimal(_, option) => Ok(DataValue::Decimal(value.map(|v| {
@@ -799,6 +1075,8 @@ impl DataValue {
             DataValue::UInt64(value) => match to {
                 LogicalType::SqlNull => Ok(DataValue::Null),
                 LogicalType::UBigint => Ok(DataValue::UInt64(value)),
+                LogicalType::Float => Ok(DataValue::Float32(value.map(|v| v as f32))),
+                LogicalType::Double => Ok(DataValue::Float64(value.map(|v| v as f64))),
                 LogicalType::Varchar(len) => varchar_cast!(value, len),
                 LogicalType::Decimal(_, option) => Ok(DataValue::Decimal(value.map(|v| {
                     let mut decimal = Decimal::from(v);
@@ -808,70 +1086,10 @@ impl DataValue {
                 }))),
                 _ => Err(TypeError::CastFail),
             },
-            DataValue::Utf8(value) => match to {
-                LogicalType::Invalid => Err(TypeError::CastFail),
-                LogicalType::SqlNull => Ok(DataValue::Null),
-                LogicalType::Boolean => Ok(DataValue::Boolean(
-                    value.map(|v| bool::from_str(&v)).transpose()?,
-                )),
-                LogicalType::Tinyint => Ok(DataValue::Int8(
-                    value.map(|v| i8::from_str(&v)).transpose()?,
-                )),
-                LogicalType::UTinyint => Ok(DataValue::UInt8(
-                    value.map(|v| u8::from_str(&v)).transpose()?,
-                )),
-                LogicalType::Smallint => Ok(DataValue::Int16(
-                    value.map(|v| i16::from_str(&v)).transpose()?,
-                )),
-                LogicalType::USmallint => Ok(DataValue::UInt16(
-                    value.map(|v| u16::from_str(&v)).transpose()?,
-                )),
-                LogicalType::Integer => Ok(DataValue::Int32(
-                    value.map(|v| i32::from_str(&v)).transpose()?,
-                )),
-                LogicalType::UInteger => Ok(DataValue::UInt32(
-                    value.map(|v| u32::from_str(&v)).transpose()?,
-                )),
-                LogicalType::Bigint => Ok(DataValue::Int64(
-                    value.map(|v| i64::from_str(&v)).transpose()?,
-                )),
-                LogicalType::UBigint => Ok(DataValue::UInt64(
-                    value.map(|v| u64::from_str(&v)).transpose()?,
-                )),
-                LogicalType::Float => Ok(DataValue::Float32(
-                    value.map(|v| f32::from_str(&v)).transpose()?,
-                )),
-                LogicalType::Double => Ok(DataValue::Float64(
-                    value.map(|v| f64::from_str(&v)).transpose()?,
-                )),
-                LogicalType::Varchar(len) => varchar_cast!(value, len),
-                LogicalType::Date => {
-                    let option = value
-                        .map(|v| {
-                            NaiveDate::parse_from_str(&v, DATE_FMT)
-                                .map(|date| date.num_days_from_ce())
-                        })
-                        .transpose()?;
-
-                    Ok(DataValue::Date32(option))
-                }
-                LogicalType::DateTime => {
-                    let option = value
-                        .map(|v| {
-                            NaiveDateTime::parse_from_str(&v, DATE_TIME_FMT)
-                                .or_else(|_| {
-                                    NaiveDate::parse_from_str(&v, DATE_FMT)
-                                        .map(|date| date.and_hms_opt(0, 0, 0).unwrap())
-                                })
-                                .map(|date_time| date_time.timestamp())
-                        })
-                        .transpose()?;
-
-                    Ok(DataValue::Date64(option))
-                }
-                LogicalType::Decimal(_, _) => Ok(DataValue::Decimal(
-                    value.map(|v| Decimal::from_str(&v)).transpose()?,
-                )),
+            DataValue::Utf8(value) => match (value, to) {
+                (_, LogicalType::Invalid) => Err(TypeError::CastFail),
+                (None, to) => Ok(DataValue::none(to)),
+                (Some(value), to) => Self::parse_str(&value, to),
             },
             DataValue::Date32(value) => match to {
                 LogicalType::SqlNull => Ok(DataValue::Null),
@@ -902,6 +1120,12 @@ impl DataValue {
                 LogicalType::DateTime => Ok(DataValue::Date64(value)),
                 _ => Err(TypeError::CastFail),
             },
+            DataValue::Interval(value) => match to {
+                LogicalType::SqlNull => Ok(DataValue::Null),
+                LogicalType::Varchar(len) => varchar_cast!(value, len),
+                LogicalType::Interval => Ok(DataValue::Interval(value)),
+                _ => Err(TypeError::CastFail),
+            },
             DataValue::Decimal(value) => match to {
                 LogicalType::SqlNull => Ok(DataValue::Null),
                 LogicalType::Decimal(_, _) => Ok(DataValue::Decimal(value)),
@@ -911,6 +1135,19 @@ impl DataValue {
         }
     }
 
+    /// The string of `CHAR(len)`, padded with spaces up to `len` characters. It is too long
+    /// if more than spaces are past them, as in SQL.
+    fn pad_char(value: &str, len: u32) -> Result<String, TypeError> {
+        let value = value.trim_end_matches(' ');
+        let count = value.chars().count();
+
+        if count > len as usize {
+            return Err(TypeError::TooLong);
+        }
+
+        Ok(format!("{}{}", value, " ".repeat(len as usize - count)))
+    }
+
     fn decimal_round_i(option: &Option<u8>, decimal: &mut Decimal) {
         if let Some(scale) = option {
             let new_decimal = decimal.trunc_with_scale(*scale as u32);
@@ -1023,6 +1260,7 @@ impl fmt::Display for DataValue {
             DataValue::Null => write!(f, "null")?,
             DataValue::Date32(e) => format_option!(f, e.and_then(DataValue::date_format))?,
             DataValue::Date64(e) => format_option!(f, e.and_then(DataValue::date_time_format))?,
+            DataValue::Interval(e) => format_option!(f, e)?,
             DataValue::Decimal(e) => format_option!(f, e.as_ref().map(DataValue::decimal_format))?,
         };
         Ok(())
@@ -1048,6 +1286,7 @@ impl fmt::Debug for DataValue {
             DataValue::Null => write!(f, "null"),
             DataValue::Date32(_) => write!(f, "Date32({})", self),
             DataValue::Date64(_) => write!(f, "Date64({})", self),
+            DataValue::Interval(_) => write!(f, "Interval({})", self),
             DataValue::Decimal(_) => write!(f, "Decimal({})", self),
         }
     }
@@ -1056,7 +1295,13 @@ impl fmt::Debug for DataValue {
 #[cfg(test)]
 mod test {
     use crate::types::errors::TypeError;
-    use crate::types::value::DataValue;
+    use crate::types::interval::Interval;
+    use crate::types::value::{DataValue, GroupKey, ValueRef, ValueRepr};
+    use crate::types::LogicalType;
+    use ahash::RandomState;
+    use itertools::Itertools;
+    use rust_decimal::Decimal;
+    use std::str::FromStr;
 
     #[test]
     fn test_to_primary_key() -> Result<(), TypeError> {
@@ -1145,4 +1390,285 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn test_to_index_key_special_f() -> Result<(), TypeError> {
+        fn assert_same_order(values: Vec<DataValue>) -> Result<(), TypeError> {
+            let keys: Vec<Vec<u8>> = values
+                .iter()
+                .map(|value| {
+                    let mut key = Vec::new();
+                    value.to_index_key(&mut key).map(|_| key)
+                })
+                .try_collect()?;
+
+            for (v1, k1) in values.iter().zip(keys.iter()) {
+                for (v2, k2) in values.iter().zip(keys.iter()) {
+                    assert_eq!(Some(k1.cmp(k2)), v1.partial_cmp(v2), "{} {}", v1, v2);
+                }
+            }
+
+            Ok(())
+        }
+
+        assert_same_order(
+            [
+                f64::NEG_INFINITY,
+                f64::MIN,
+                -1.0,
+                -0.0,
+                0.0,
+                f64::MIN_POSITIVE,
+                f64::MAX,
+                f64::INFINITY,
+                f64::NAN,
+                -f64::NAN,
+            ]
+            .into_iter()
+            .map(|f| DataValue::Float64(Some(f)))
+            .collect(),
+        )?;
+        assert_same_order(
+            [
+                f32::NEG_INFINITY,
+                -1.0,
+                -0.0,
+                0.0,
+                f32::INFINITY,
+                f32::NAN,
+                -f32::NAN,
+            ]
+            .into_iter()
+            .map(|f| DataValue::Float32(Some(f)))
+            .collect(),
+        )?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_group_key() {
+        let hash_builder = RandomState::new();
+        let same = |v1: DataValue, v2: DataValue| {
+            let (k1, k2) = (GroupKey(ValueRef::new(v1)), GroupKey(ValueRef::new(v2)));
+
+            k1 == k2 && hash_builder.hash_one(&k1) == hash_builder.hash_one(&k2)
+        };
+
+        assert!(same(DataValue::Null, DataValue::Int32(None)));
+        assert!(same(DataValue::Utf8(None), DataValue::Float64(None)));
+        assert!(same(
+            DataValue::Float64(Some(f64::NAN)),
+            DataValue::Float64(Some(-f64::NAN))
+        ));
+        assert!(same(
+            DataValue::Float32(Some(0.0)),
+            DataValue::Float32(Some(-0.0))
+        ));
+        assert!(same(DataValue::Int32(Some(1)), DataValue::Int32(Some(1))));
+
+        assert!(!same(DataValue::Int32(None), DataValue::Int32(Some(0))));
+        assert!(!same(
+            DataValue::Float64(Some(f64::NAN)),
+            DataValue::Float64(None)
+        ));
+        assert!(!same(
+            DataValue::Float64(Some(f64::NAN)),
+            DataValue::Float64(Some(0.0))
+        ));
+    }
+
+    #[test]
+    fn test_try_from_str() -> Result<(), TypeError> {
+        let cases = [
+            ("true", LogicalType::Boolean, DataValue::Boolean(Some(true))),
+            ("F", LogicalType::Boolean, DataValue::Boolean(Some(false))),
+            ("-128", LogicalType::Tinyint, DataValue::Int8(Some(-128))),
+            ("255", LogicalType::UTinyint, DataValue::UInt8(Some(255))),
+            (
+                "-32768",
+                LogicalType::Smallint,
+                DataValue::Int16(Some(-32768)),
+            ),
+            (
+                "65535",
+                LogicalType::USmallint,
+                DataValue::UInt16(Some(65535)),
+            ),
+            (" 42 ", LogicalType::Integer, DataValue::Int32(Some(42))),
+            (
+                "4294967295",
+                LogicalType::UInteger,
+                DataValue::UInt32(Some(u32::MAX)),
+            ),
+            (
+                "-9223372036854775808",
+                LogicalType::Bigint,
+                DataValue::Int64(Some(i64::MIN)),
+            ),
+            (
+                "18446744073709551615",
+                LogicalType::UBigint,
+                DataValue::UInt64(Some(u64::MAX)),
+            ),
+            ("1.5", LogicalType::Float, DataValue::Float32(Some(1.5))),
+            (
+                "-2.5e3",
+                LogicalType::Double,
+                DataValue::Float64(Some(-2500.0)),
+            ),
+            (
+                " a b ",
+                LogicalType::Varchar(Some(5)),
+                DataValue::Utf8(Some(" a b ".to_string())),
+            ),
+            (
+                "2023-03-01",
+                LogicalType::Date,
+                DataValue::Utf8(Some("2023-03-01".to_string())).cast(&LogicalType::Date)?,
+            ),
+            (
+                "1970-01-02 00:00:01",
+                LogicalType::DateTime,
+                DataValue::Date64(Some(86401)),
+            ),
+            (
+                "1970-01-02",
+                LogicalType::DateTime,
+                DataValue::Date64(Some(86400)),
+            ),
+            (
+                "1 day",
+                LogicalType::Interval,
+                DataValue::Interval(Some(Interval::from_str("1 day")?)),
+            ),
+            (
+                "3.14",
+                LogicalType::Decimal(None, None),
+                DataValue::Decimal(Some(Decimal::new(314, 2))),
+            ),
+            ("NULL", LogicalType::Integer, DataValue::Int32(None)),
+            ("null", LogicalType::Varchar(None), DataValue::Utf8(None)),
+        ];
+        for (text, ty, value) in cases {
+            assert_eq!(DataValue::try_from_str(text, &ty)?, value, "{}", text);
+        }
+
+        let invalid_cases = [
+            ("yes please", LogicalType::Boolean),
+            ("128", LogicalType::Tinyint),
+            ("-1", LogicalType::UTinyint),
+            ("1.0", LogicalType::Integer),
+            ("", LogicalType::Bigint),
+            ("one", LogicalType::Double),
+            ("2023-02-30", LogicalType::Date),
+            ("2023-03-01 25:00:00", LogicalType::DateTime),
+            ("1.2.3", LogicalType::Decimal(None, None)),
+        ];
+        for (text, ty) in invalid_cases {
+            assert!(
+                matches!(
+                    DataValue::try_from_str(text, &ty),
+                    Err(TypeError::InvalidInput { ty: err_ty, value, .. }) if err_ty == ty && value == text
+                ),
+                "{}",
+                text
+            );
+        }
+        assert!(matches!(
+            DataValue::try_from_str("1 fortnight", &LogicalType::Interval),
+            Err(TypeError::InvalidInterval(_))
+        ));
+        assert!(matches!(
+            DataValue::try_from_str("abcdef", &LogicalType::Varchar(Some(5))),
+            Err(TypeError::TooLong)
+        ));
+        assert_eq!(
+            DataValue::try_from_str("128", &LogicalType::Tinyint)
+                .unwrap_err()
+                .to_string(),
+            "invalid input for type TINYINT: \"128\", number too large to fit in target type"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_cast_to_char() -> Result<(), TypeError> {
+        let utf8 = |value: &str| DataValue::Utf8(Some(value.to_string()));
+
+        assert_eq!(utf8("ab").cast(&LogicalType::Char(4))?, utf8("ab  "));
+        assert_eq!(utf8("abc   ").cast(&LogicalType::Char(4))?, utf8("abc "));
+        assert_eq!(
+            DataValue::Int32(Some(12)).cast(&LogicalType::Char(3))?,
+            utf8("12 ")
+        );
+        assert_eq!(
+            DataValue::Null.cast(&LogicalType::Char(3))?,
+            DataValue::Utf8(None)
+        );
+        assert!(matches!(
+            utf8("abcde").cast(&LogicalType::Char(4)),
+            Err(TypeError::TooLong)
+        ));
+        assert!(utf8("abcd  ").check_len(&LogicalType::Char(4)).is_ok());
+        assert!(matches!(
+            utf8("abcde").check_len(&LogicalType::Char(4)),
+            Err(TypeError::TooLong)
+        ));
+        assert_eq!(
+            DataValue::try_from_str("x", &LogicalType::Char(2))?,
+            utf8("x ")
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_value_ref() -> Result<(), TypeError> {
+        let values = [
+            DataValue::Null,
+            DataValue::Int32(Some(1)),
+            DataValue::Int32(None),
+            DataValue::Float64(Some(0.5)),
+            DataValue::Decimal(Some(Decimal::from_str("1.5").unwrap())),
+            DataValue::Utf8(Some("a".to_string())),
+            DataValue::Utf8(None),
+        ];
+
+        for value in values {
+            let value_ref = ValueRef::new(value.clone());
+            // only the strings are behind an `Arc`
+            assert_eq!(
+                matches!(value_ref.0, ValueRepr::Shared(_)),
+                matches!(value, DataValue::Utf8(Some(_)))
+            );
+            assert_eq!(value_ref.as_ref(), &value);
+            assert_eq!(value_ref, value_ref.clone());
+            assert_eq!(
+                value_ref.partial_cmp(&ValueRef::new(value.clone())),
+                value.partial_cmp(&value)
+            );
+            assert_eq!(
+                RandomState::with_seeds(0, 0, 0, 0).hash_one(&value_ref),
+                RandomState::with_seeds(0, 0, 0, 0).hash_one(&value)
+            );
+            assert_eq!(format!("{}", value_ref), format!("{}", value));
+            assert_eq!(format!("{:?}", value_ref), format!("{:?}", value));
+            // serialized as the value itself
+            let json = serde_json::to_string(&value_ref).unwrap();
+            assert_eq!(json, serde_json::to_string(&value).unwrap());
+            assert_eq!(serde_json::from_str::<ValueRef>(&json).unwrap(), value_ref);
+            assert_eq!(value_ref.into_inner(), value);
+        }
+        assert!(ValueRef::new(DataValue::Int8(Some(1))) < ValueRef::new(DataValue::Int8(Some(2))));
+        assert_eq!(
+            ValueRef::new(DataValue::Int32(Some(1)))
+                .into_inner()
+                .cast(&LogicalType::Bigint)?,
+            DataValue::Int64(Some(1))
+        );
+
+        Ok(())
+    }
 }